@@ -0,0 +1,135 @@
+//! Adapters that recurse into archive containers, emitting each member
+//! as its own [`ExtractedEntry`] under a virtual path like
+//! `outer.zip!/inner.rs` so the archive keeps one entry per logical
+//! file instead of one opaque blob per container.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+
+use super::{ContentAdapter, ExtractedEntry};
+use crate::error::{ArchiveError, Result as ArchiveResult};
+
+/// Members larger than this are skipped rather than decompressed in
+/// full, the same guard rail `Config::max_file_size` gives plain files.
+const MAX_MEMBER_SIZE: u64 = 10 * 1024 * 1024;
+
+/// A NUL byte or invalid UTF-8 in a member's bytes is treated as binary
+/// and skipped, mirroring `binary::sniff_is_binary`'s content check but
+/// without a filesystem path to sniff from.
+fn looks_binary(bytes: &[u8]) -> bool {
+    bytes.contains(&0) || std::str::from_utf8(bytes).is_err()
+}
+
+pub struct ZipAdapter;
+
+impl ZipAdapter {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ContentAdapter for ZipAdapter {
+    fn name(&self) -> &'static str {
+        "zip"
+    }
+
+    fn matches(&self, path: &Path, head_bytes: &[u8]) -> bool {
+        let by_extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.eq_ignore_ascii_case("zip"))
+            .unwrap_or(false);
+        by_extension || head_bytes.starts_with(b"PK\x03\x04")
+    }
+
+    fn extract(&self, path: &Path) -> ArchiveResult<Vec<ExtractedEntry>> {
+        let file = File::open(path).map_err(|e| ArchiveError::open(e, path))?;
+        let mut zip = zip::ZipArchive::new(file)
+            .map_err(|e| ArchiveError::Other(format!("reading zip archive {}: {e}", path.display())))?;
+
+        let outer = path.display();
+        let mut entries = Vec::new();
+        for i in 0..zip.len() {
+            let mut member = zip
+                .by_index(i)
+                .map_err(|e| ArchiveError::Other(format!("reading zip member {i} of {outer}: {e}")))?;
+            if !member.is_file() || member.size() > MAX_MEMBER_SIZE {
+                continue;
+            }
+
+            let mut bytes = Vec::with_capacity(member.size() as usize);
+            if member.read_to_end(&mut bytes).is_err() || looks_binary(&bytes) {
+                continue;
+            }
+
+            entries.push(ExtractedEntry {
+                virtual_path: format!("{outer}!/{}", member.name()),
+                content: String::from_utf8_lossy(&bytes).into_owned(),
+            });
+        }
+
+        Ok(entries)
+    }
+}
+
+pub struct TarAdapter;
+
+impl TarAdapter {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ContentAdapter for TarAdapter {
+    fn name(&self) -> &'static str {
+        "tar"
+    }
+
+    fn matches(&self, path: &Path, head_bytes: &[u8]) -> bool {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        let by_extension = name.ends_with(".tar") || name.ends_with(".tar.gz") || name.ends_with(".tgz");
+        by_extension || head_bytes.starts_with(&[0x1f, 0x8b])
+    }
+
+    fn extract(&self, path: &Path) -> ArchiveResult<Vec<ExtractedEntry>> {
+        let file = File::open(path).map_err(|e| ArchiveError::open(e, path))?;
+
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        let is_gzip = name.ends_with(".gz") || name.ends_with(".tgz");
+        let reader: Box<dyn Read> = if is_gzip {
+            Box::new(GzDecoder::new(file))
+        } else {
+            Box::new(file)
+        };
+
+        let outer = path.display();
+        let mut archive = tar::Archive::new(reader);
+        let mut entries = Vec::new();
+        for entry in archive
+            .entries()
+            .map_err(|e| ArchiveError::Other(format!("reading tar archive {}: {e}", path.display())))?
+        {
+            let mut entry =
+                entry.map_err(|e| ArchiveError::Other(format!("reading tar entry in {outer}: {e}")))?;
+            if !entry.header().entry_type().is_file() || entry.header().size().unwrap_or(0) > MAX_MEMBER_SIZE {
+                continue;
+            }
+
+            let member_name = entry.path().map(|p| p.display().to_string()).unwrap_or_default();
+            let mut bytes = Vec::new();
+            if entry.read_to_end(&mut bytes).is_err() || looks_binary(&bytes) {
+                continue;
+            }
+
+            entries.push(ExtractedEntry {
+                virtual_path: format!("{outer}!/{member_name}"),
+                content: String::from_utf8_lossy(&bytes).into_owned(),
+            });
+        }
+
+        Ok(entries)
+    }
+}
@@ -0,0 +1,97 @@
+//! Pluggable content-extraction adapters, consulted before the plain
+//! UTF-8 read in [`crate::ArchiveEngine::process_single_file_to_buffer`]
+//! so binary/container formats can surface as text instead of being
+//! dropped as unreadable noise. Mirrors the `formatter` module's
+//! trait-plus-registry shape.
+
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::error::Result as ArchiveResult;
+
+pub mod archive_container;
+pub mod pdf;
+pub mod sqlite;
+
+/// One piece of text recovered from a source file, addressed by a
+/// "virtual path" distinct from the file's real path -- e.g. a member of
+/// a zip comes out as `outer.zip!/inner.rs` so the archive still reads
+/// as one entry per logical file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedEntry {
+    /// Display path for this entry in the archive, not necessarily a
+    /// real filesystem path.
+    pub virtual_path: String,
+    /// Recovered text content.
+    pub content: String,
+}
+
+/// Recovers archivable text from a file that isn't plain UTF-8 source,
+/// e.g. an archive container, a PDF, or a SQLite database.
+pub trait ContentAdapter: Send + Sync {
+    /// Short, stable identifier used in logs and `Debug` output.
+    fn name(&self) -> &'static str;
+
+    /// Whether this adapter should handle `path`. `head_bytes` is
+    /// whatever prefix of the file's content the caller already has in
+    /// hand (empty during directory-walk classification, where no bytes
+    /// have been read yet) -- adapters should prefer an extension check
+    /// and only fall back to sniffing `head_bytes` when it's non-empty.
+    fn matches(&self, path: &Path, head_bytes: &[u8]) -> bool;
+
+    /// Extract one or more text entries from `path`. An empty `Vec`
+    /// means nothing archivable was recovered (e.g. an image-only PDF),
+    /// which the caller treats the same as a skipped binary file.
+    fn extract(&self, path: &Path) -> ArchiveResult<Vec<ExtractedEntry>>;
+}
+
+/// An ordered set of [`ContentAdapter`]s, consulted most recently
+/// registered first; the first match wins. Trait objects aren't `Serialize` or
+/// auto-`Debug`, so `Config` holds this behind `#[serde(skip)]` and
+/// relies on the manual impls below.
+#[derive(Clone)]
+pub struct AdapterRegistry(Vec<Arc<dyn ContentAdapter>>);
+
+impl AdapterRegistry {
+    /// An empty registry with no adapters registered.
+    pub fn empty() -> Self {
+        Self(Vec::new())
+    }
+
+    /// The built-in adapters: archive containers (zip/tar), PDF text
+    /// extraction, and SQLite table dumps.
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::empty();
+        registry.register(Arc::new(archive_container::ZipAdapter::new()));
+        registry.register(Arc::new(archive_container::TarAdapter::new()));
+        registry.register(Arc::new(pdf::PdfAdapter::new()));
+        registry.register(Arc::new(sqlite::SqliteAdapter::new()));
+        registry
+    }
+
+    /// Register an additional adapter, taking precedence over any
+    /// already registered (checked first by [`Self::find`]).
+    pub fn register(&mut self, adapter: Arc<dyn ContentAdapter>) {
+        self.0.push(adapter);
+    }
+
+    /// The first registered adapter that claims `path`, if any.
+    pub fn find(&self, path: &Path, head_bytes: &[u8]) -> Option<&Arc<dyn ContentAdapter>> {
+        self.0.iter().rev().find(|adapter| adapter.matches(path, head_bytes))
+    }
+}
+
+impl Default for AdapterRegistry {
+    fn default() -> Self {
+        Self::with_builtins()
+    }
+}
+
+impl fmt::Debug for AdapterRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AdapterRegistry")
+            .field(&self.0.iter().map(|a| a.name()).collect::<Vec<_>>())
+            .finish()
+    }
+}
@@ -0,0 +1,47 @@
+//! PDF text-extraction adapter, pulling out whatever text layer a PDF
+//! carries so scanned/exported documents archive like any other source
+//! file instead of becoming replacement-character noise.
+
+use std::path::Path;
+
+use super::{ContentAdapter, ExtractedEntry};
+use crate::error::{ArchiveError, Result as ArchiveResult};
+
+pub struct PdfAdapter;
+
+impl PdfAdapter {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ContentAdapter for PdfAdapter {
+    fn name(&self) -> &'static str {
+        "pdf"
+    }
+
+    fn matches(&self, path: &Path, head_bytes: &[u8]) -> bool {
+        let by_extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.eq_ignore_ascii_case("pdf"))
+            .unwrap_or(false);
+        by_extension || head_bytes.starts_with(b"%PDF-")
+    }
+
+    fn extract(&self, path: &Path) -> ArchiveResult<Vec<ExtractedEntry>> {
+        let text = pdf_extract::extract_text(path)
+            .map_err(|e| ArchiveError::Other(format!("extracting text from {}: {e}", path.display())))?;
+
+        // An image-only/scanned PDF yields no text layer; treat that the
+        // same as a skipped binary rather than archiving an empty entry.
+        if text.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Ok(vec![ExtractedEntry {
+            virtual_path: path.display().to_string(),
+            content: text,
+        }])
+    }
+}
@@ -0,0 +1,85 @@
+//! SQLite adapter: dumps every user table's schema and rows as plain
+//! text, so a `.sqlite`/`.db` file's data is searchable in the archive
+//! instead of being skipped as an opaque binary blob.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use rusqlite::types::ValueRef;
+use rusqlite::Connection;
+
+use super::{ContentAdapter, ExtractedEntry};
+use crate::error::{ArchiveError, Result as ArchiveResult};
+
+pub struct SqliteAdapter;
+
+impl SqliteAdapter {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ContentAdapter for SqliteAdapter {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    fn matches(&self, path: &Path, head_bytes: &[u8]) -> bool {
+        let by_extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| matches!(e.to_ascii_lowercase().as_str(), "sqlite" | "sqlite3" | "db"))
+            .unwrap_or(false);
+        by_extension || head_bytes.starts_with(b"SQLite format 3\0")
+    }
+
+    fn extract(&self, path: &Path) -> ArchiveResult<Vec<ExtractedEntry>> {
+        let conn = Connection::open(path)
+            .map_err(|e| ArchiveError::Other(format!("opening sqlite database {}: {e}", path.display())))?;
+
+        let mut table_stmt = conn
+            .prepare("SELECT name, sql FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'")
+            .map_err(|e| ArchiveError::Other(format!("listing tables in {}: {e}", path.display())))?;
+        let tables: Vec<(String, String)> = table_stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get::<_, Option<String>>(1)?.unwrap_or_default())))
+            .map_err(|e| ArchiveError::Other(format!("listing tables in {}: {e}", path.display())))?
+            .filter_map(std::result::Result::ok)
+            .collect();
+
+        let mut content = String::new();
+        for (table, schema) in &tables {
+            let _ = writeln!(content, "-- table: {table}\n{schema}\n");
+
+            let Ok(mut row_stmt) = conn.prepare(&format!("SELECT * FROM \"{table}\"")) else {
+                continue;
+            };
+            let column_count = row_stmt.column_count();
+            let Ok(mut rows) = row_stmt.query([]) else {
+                continue;
+            };
+            while let Ok(Some(row)) = rows.next() {
+                let values: Vec<String> = (0..column_count)
+                    .map(|i| match row.get_ref(i) {
+                        Ok(ValueRef::Null) => "NULL".to_string(),
+                        Ok(ValueRef::Integer(n)) => n.to_string(),
+                        Ok(ValueRef::Real(f)) => f.to_string(),
+                        Ok(ValueRef::Text(t)) => String::from_utf8_lossy(t).into_owned(),
+                        Ok(ValueRef::Blob(_)) => "<blob>".to_string(),
+                        Err(_) => "<error>".to_string(),
+                    })
+                    .collect();
+                let _ = writeln!(content, "{}", values.join("\t"));
+            }
+            content.push('\n');
+        }
+
+        if content.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Ok(vec![ExtractedEntry {
+            virtual_path: path.display().to_string(),
+            content,
+        }])
+    }
+}
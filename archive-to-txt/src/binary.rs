@@ -0,0 +1,218 @@
+//! MIME-driven binary/text classification, replacing the hand-maintained
+//! blocklist of media/document/archive/data-blob extensions that used to
+//! live in `Config::get_default_llm_ignore_patterns` (and had to be
+//! manually extended -- with plenty of accidental duplicates -- every time
+//! a new binary format showed up).
+//!
+//! Coverage now comes from the `mime_guess` extension table, which is kept
+//! current upstream; only files `mime_guess` can't classify at all fall
+//! back to a quick content sniff.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// How many leading bytes to inspect when a file has no recognized
+/// extension at all.
+const SNIFF_LEN: usize = 8192;
+
+/// `application/*` subtypes that are text despite not being under the
+/// `text/` top-level type.
+const TEXT_LIKE_APPLICATION_SUBTYPES: &[&str] = &[
+    "json",
+    "xml",
+    "javascript",
+    "toml",
+    "x-yaml",
+    "yaml",
+    "x-sh",
+    "xhtml+xml",
+    "atom+xml",
+    "rss+xml",
+    "ld+json",
+];
+
+/// Whether `path` is probably binary. A guessed MIME type under `text/`,
+/// or one of the text-like `application/*` subtypes (JSON, XML, SVG,
+/// TOML, ...), is treated as text; a guessed `image/`, `audio/`, or
+/// `video/` type, or any other recognized type, is treated as binary.
+/// Files `mime_guess` doesn't recognize at all fall back to
+/// [`sniff_is_binary`].
+pub fn is_probably_binary(path: &Path) -> bool {
+    match mime_guess::from_path(path).first() {
+        Some(mime) => {
+            if mime.type_() == mime::TEXT {
+                return false;
+            }
+            if mime.subtype() == "svg+xml"
+                || TEXT_LIKE_APPLICATION_SUBTYPES.contains(&mime.subtype().as_str())
+            {
+                return false;
+            }
+            true
+        }
+        None => sniff_is_binary(path),
+    }
+}
+
+/// No recognized extension: read a small prefix and guess from content --
+/// a NUL byte or invalid UTF-8 in the first few KB is a strong binary
+/// signal. A file that can't be opened or read is treated as binary so it
+/// doesn't get dumped into a text archive.
+fn sniff_is_binary(path: &Path) -> bool {
+    let Ok(mut file) = File::open(path) else {
+        return true;
+    };
+    let mut buf = vec![0u8; SNIFF_LEN];
+    let Ok(n) = file.read(&mut buf) else {
+        return true;
+    };
+    let buf = &buf[..n];
+
+    buf.contains(&0) || std::str::from_utf8(buf).is_err()
+}
+
+/// A coarse grouping of guessed MIME types, used by `--include-type` as a
+/// broader-strokes alternative to enumerating individual extensions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MimeGroup {
+    /// Plain prose and markup: `text/*` that isn't source code.
+    Text,
+    /// Programming-language source files.
+    Code,
+    /// Configuration and structured-data formats (JSON, YAML, TOML, ...).
+    Config,
+    /// `image/*`.
+    Image,
+    /// `audio/*`.
+    Audio,
+    /// `video/*`.
+    Video,
+    /// Archive and compressed-container formats.
+    Archive,
+    /// Other structured/tabular data formats (CSV, Parquet, spreadsheets).
+    Data,
+}
+
+/// Extensions treated as source code regardless of what (if anything)
+/// `mime_guess` resolves them to -- most programming languages have no
+/// registered media type at all.
+const CODE_EXTENSIONS: &[&str] = &[
+    "rs", "py", "js", "jsx", "ts", "tsx", "go", "java", "c", "h", "cpp", "hpp", "cc", "cs",
+    "rb", "php", "swift", "kt", "kts", "scala", "sh", "bash", "zsh", "fish", "ps1", "lua",
+    "pl", "pm", "ex", "exs", "erl", "hs", "clj", "cljs", "ml", "mli", "fs", "fsx", "r",
+    "sql", "vue", "svelte", "proto", "graphql", "gql",
+];
+
+/// Extensions treated as configuration/structured-data regardless of
+/// `mime_guess`'s (often coarse `text/plain`) guess for them.
+const CONFIG_EXTENSIONS: &[&str] = &["toml", "yaml", "yml", "ini", "cfg", "conf", "env"];
+
+/// Best-effort MIME-group classification of `path`, combining a small
+/// extension override table (for source/config formats `mime_guess`
+/// doesn't resolve distinctly) with its guessed MIME type. Returns `None`
+/// when neither source tells us anything useful.
+pub fn classify_mime_group(path: &Path) -> Option<MimeGroup> {
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()).map(str::to_lowercase) {
+        if CODE_EXTENSIONS.contains(&ext.as_str()) {
+            return Some(MimeGroup::Code);
+        }
+        if CONFIG_EXTENSIONS.contains(&ext.as_str()) {
+            return Some(MimeGroup::Config);
+        }
+    }
+
+    let guessed = mime_guess::from_path(path).first()?;
+    match guessed.type_() {
+        mime::IMAGE => Some(MimeGroup::Image),
+        mime::AUDIO => Some(MimeGroup::Audio),
+        mime::VIDEO => Some(MimeGroup::Video),
+        mime::TEXT => Some(MimeGroup::Text),
+        _ => match guessed.subtype().as_str() {
+            "zip" | "x-tar" | "gzip" | "x-gzip" | "x-7z-compressed" | "x-rar-compressed"
+            | "x-bzip2" | "vnd.rar" => Some(MimeGroup::Archive),
+            "json" | "xml" | "toml" | "x-yaml" => Some(MimeGroup::Config),
+            "csv" | "vnd.ms-excel"
+            | "vnd.openxmlformats-officedocument.spreadsheetml.sheet" => Some(MimeGroup::Data),
+            _ => None,
+        },
+    }
+}
+
+/// `(extension, language name)` pairs consulted by [`detect_language`],
+/// reusing the same extensions [`CODE_EXTENSIONS`] treats as source.
+const LANGUAGE_BY_EXTENSION: &[(&str, &str)] = &[
+    ("rs", "Rust"),
+    ("py", "Python"),
+    ("js", "JavaScript"),
+    ("jsx", "JavaScript"),
+    ("ts", "TypeScript"),
+    ("tsx", "TypeScript"),
+    ("go", "Go"),
+    ("java", "Java"),
+    ("c", "C"),
+    ("h", "C"),
+    ("cpp", "C++"),
+    ("hpp", "C++"),
+    ("cc", "C++"),
+    ("cs", "C#"),
+    ("rb", "Ruby"),
+    ("php", "PHP"),
+    ("swift", "Swift"),
+    ("kt", "Kotlin"),
+    ("kts", "Kotlin"),
+    ("scala", "Scala"),
+    ("sh", "Shell"),
+    ("bash", "Shell"),
+    ("zsh", "Shell"),
+    ("fish", "Shell"),
+    ("ps1", "PowerShell"),
+    ("lua", "Lua"),
+    ("pl", "Perl"),
+    ("pm", "Perl"),
+    ("ex", "Elixir"),
+    ("exs", "Elixir"),
+    ("erl", "Erlang"),
+    ("hs", "Haskell"),
+    ("clj", "Clojure"),
+    ("cljs", "ClojureScript"),
+    ("ml", "OCaml"),
+    ("mli", "OCaml"),
+    ("fs", "F#"),
+    ("fsx", "F#"),
+    ("r", "R"),
+    ("sql", "SQL"),
+    ("vue", "Vue"),
+    ("svelte", "Svelte"),
+    ("proto", "Protocol Buffers"),
+    ("graphql", "GraphQL"),
+    ("gql", "GraphQL"),
+];
+
+/// Best-effort programming-language name for `path`, looked up by
+/// extension. Returns `None` for non-source files (prose, config, binary
+/// data, ...) rather than guessing.
+pub fn detect_language(path: &Path) -> Option<String> {
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    LANGUAGE_BY_EXTENSION
+        .iter()
+        .find(|(e, _)| *e == ext)
+        .map(|(_, lang)| lang.to_string())
+}
+
+impl MimeGroup {
+    /// Parse a `--include-type` group name (case-insensitive).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "text" => Some(Self::Text),
+            "code" => Some(Self::Code),
+            "config" => Some(Self::Config),
+            "image" => Some(Self::Image),
+            "audio" => Some(Self::Audio),
+            "video" => Some(Self::Video),
+            "archive" => Some(Self::Archive),
+            "data" => Some(Self::Data),
+            _ => None,
+        }
+    }
+}
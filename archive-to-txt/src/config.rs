@@ -1,24 +1,87 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::collections::HashSet;
+use std::sync::{Arc, OnceLock};
 use chrono;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+use crate::adapters::{AdapterRegistry, ContentAdapter};
+use crate::binary::MimeGroup;
+use crate::error::{ArchiveError, Result as ArchiveResult};
+use crate::override_rules::OverrideRules;
+use crate::sink::{LocalFileSink, Sink};
+use crate::vfs::{FileSource, StdFsSource};
 
 /// Configuration for the archiving process
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     /// Input directory to archive
     pub input: PathBuf,
+    /// Backend `ArchiveEngine` reads file bytes and tree structure
+    /// through, decoupling it from the real filesystem (see
+    /// `crate::vfs::FileSource`). Defaults to `StdFsSource`, reading
+    /// `input` directly off disk; set via `with_file_source` to archive
+    /// from a zip, an in-memory tree, or another custom backend. Not
+    /// serialized -- trait objects carry no `Serialize` impl -- restored
+    /// to `StdFsSource` on deserialize.
+    #[serde(skip, default = "default_file_source")]
+    pub file_source: Arc<dyn FileSource>,
     /// Output file path
     pub output: PathBuf,
+    /// Destination the finished archive is streamed to (see
+    /// `crate::sink::Sink`), named with `output`. Defaults to
+    /// `LocalFileSink`, writing `output` straight to disk; set via
+    /// `with_output_sink` to stream to a remote object store instead.
+    /// Not serialized -- trait objects carry no `Serialize` impl --
+    /// restored to `LocalFileSink` on deserialize.
+    #[serde(skip, default = "default_output_sink")]
+    pub output_sink: Arc<dyn Sink>,
     /// Include hidden files and directories
     pub include_hidden: bool,
     /// Maximum file size to include (in bytes)
     pub max_file_size: Option<u64>,
     /// Enable parallel processing
     pub parallel: bool,
+    /// Bound on in-flight formatted buffers between `process_files_parallel`'s
+    /// Rayon workers and its single output-writing consumer. Caps peak
+    /// memory at roughly `parallel_channel_capacity * avg_file_size`
+    /// instead of buffering the whole tree before writing.
+    #[serde(default = "default_parallel_channel_capacity")]
+    pub parallel_channel_capacity: usize,
+    /// How many bytes of formatted output each parallel worker accumulates
+    /// in its thread-local batch before flushing it down the channel to
+    /// the output consumer. Collapses many small per-file handoffs into a
+    /// handful of larger ones for trees with lots of small files, while
+    /// keeping a worker's unflushed memory bounded to roughly this size.
+    #[serde(default = "default_write_batch_size")]
+    pub write_batch_size: usize,
     /// Include git information (if available)
     #[serde(default)]
     pub git_info: bool,
+    /// Tag each archived file's header with its working-tree status
+    /// (`[M]`/`[A]`/`[?]`/...) via `GitInfo::file_statuses`. Only takes
+    /// effect when `git_info` is also set, since it needs the repository
+    /// `GitInfo` already resolves.
+    #[serde(default)]
+    pub git_file_status: bool,
+    /// Archive the tree of this commit, tag, or branch instead of the
+    /// live working directory (requires the `git2-backend` feature,
+    /// since resolving the refspec and reading blobs goes through
+    /// `vfs::GitTreeSource`). `GitInfo`'s reported commit reflects this
+    /// resolved revision rather than `HEAD` when set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
+    /// Restrict the archive to files that differ between this baseline
+    /// commit/tag/branch and the current working tree (staged and
+    /// unstaged), via `GitInfo::changed_since` (requires a resolvable
+    /// `git_info`). Combines with `llm_optimize`, ignore files, and
+    /// `include`/`exclude` as an additional filter during the walk.
+    /// Without the `git2-backend` feature the baseline can't be resolved
+    /// to an arbitrary revision, so this falls back to the porcelain
+    /// status set (everything not `Unmodified`/`Ignored`), which is
+    /// always relative to `HEAD`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changed_since: Option<String>,
     /// Output format
     #[serde(default = "default_format")]
     pub format: OutputFormat,
@@ -28,6 +91,57 @@ pub struct Config {
     /// File patterns to exclude (glob format)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub exclude: Option<Vec<String>>,
+    /// Named language-type presets to include (e.g. `rust`, `python`),
+    /// resolved against `known_file_types()` plus any `custom_types`
+    /// registered on this config and folded into the include patterns.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub types: Option<Vec<String>>,
+    /// Named language-type presets to exclude, resolved the same way as
+    /// `types` and folded into the exclude patterns.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub type_not: Option<Vec<String>>,
+    /// Extra `name -> globs` mappings registered at runtime via
+    /// `with_custom_type`, consulted before `known_file_types()` so a
+    /// custom mapping can override a built-in name.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom_types: Vec<(String, Vec<String>)>,
+    /// An ordered include/exclude rule set, applied on top of `include`/
+    /// `exclude` (and their `types`/`type_not` expansions) for patterns
+    /// that need precise last-match-wins control, e.g. re-including one
+    /// file under an otherwise-excluded directory with a leading `!`. See
+    /// `OverrideRules` and `Config::compiled_override_rules`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub override_rules: Option<Vec<String>>,
+    /// Honor hierarchical `.gitignore`/`.ignore`/`.promptignore` files
+    /// found while walking, layered over the built-in LLM ignore defaults
+    /// (see `ignore_stack::IgnoreStack`).
+    #[serde(default = "default_true")]
+    pub respect_ignore_files: bool,
+    /// Honor hierarchical `.gitattributes` files found while walking:
+    /// drop any path whose `export-ignore` attribute is set, exactly as
+    /// `git archive` does, and normalize line endings on paths whose
+    /// `text`/`eol` attributes request it (see
+    /// `crate::gitattributes::GitAttributesStack`).
+    #[serde(default)]
+    pub respect_git_attributes: bool,
+    /// Encoding to assume for a file with no recognized byte-order-mark
+    /// (an `encoding_rs` label, e.g. `"windows-1252"` or `"GBK"`). `None`
+    /// assumes UTF-8. See `crate::encoding::decode`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_encoding: Option<String>,
+    /// Adapters consulted before the plain UTF-8 read so archive
+    /// containers, PDFs and SQLite databases surface as text instead of
+    /// being dropped as binary (see `crate::adapters::AdapterRegistry`).
+    /// Not serialized -- trait objects carry no `Serialize` impl --
+    /// restored to the built-in set on deserialize.
+    #[serde(skip)]
+    pub content_adapters: AdapterRegistry,
+    /// Best-effort raise of the process's soft `RLIMIT_NOFILE` toward its
+    /// hard limit before parallel processing starts, avoiding `EMFILE`
+    /// under a wide Rayon fan-out on platforms with a low default (e.g.
+    /// macOS's 256). See `crate::fd_limit`.
+    #[serde(default = "default_true")]
+    pub raise_fd_limit: bool,
     /// Enable LLM-optimized filtering
     #[serde(default)]
     pub llm_optimize: bool,
@@ -37,6 +151,9 @@ pub struct Config {
     /// File extensions to include (comma-separated)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub include_extensions: Option<String>,
+    /// Coarse MIME groups to include (comma-separated, e.g. `text,code,config`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_types: Option<String>,
     /// Maximum depth for directory traversal
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_depth: Option<usize>,
@@ -49,6 +166,68 @@ pub struct Config {
     /// Include directory tree structure in output
     #[serde(default)]
     pub include_tree: bool,
+    /// Prefix each tree entry with a per-file-type icon glyph (see
+    /// `crate::icons`), for terminals with a Nerd/icon font.
+    #[serde(default)]
+    pub show_tree_icons: bool,
+    /// When set, stream a Parquet manifest of every file the walker visits
+    /// (kept or filtered, with a reason) to this path alongside the archive.
+    /// See `crate::manifest`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manifest_output: Option<PathBuf>,
+    /// Compression applied to the output file as it's written.
+    #[serde(default)]
+    pub compression: OutputCompression,
+    /// When set, skip re-embedding a file's content if it matches what
+    /// the sidecar manifest at this path recorded last run, and store
+    /// identical content (by hash) once even if it appears at several
+    /// paths. See `crate::incremental`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub incremental_manifest: Option<PathBuf>,
+}
+
+/// Compression applied when writing the archive, wrapping the output
+/// writer in a streaming encoder when enabled so the pipeline never
+/// materializes an uncompressed temp file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutputCompression {
+    /// Plain, uncompressed output.
+    None,
+    /// Gzip-compressed output, at a `flate2` compression level from 0
+    /// (store) to 9 (best compression). Appends `.gz` to the output path
+    /// if it doesn't already end in one.
+    Gzip(u32),
+    /// Zstd-compressed output, at a `zstd` compression level from 1 to
+    /// 22 (higher is smaller but slower). Appends `.zst` to the output
+    /// path if it doesn't already end in one.
+    Zstd(i32),
+    /// Bzip2-compressed output, at a `bzip2` compression level from 0
+    /// (store) to 9 (best compression). Appends `.bz2` to the output
+    /// path if it doesn't already end in one.
+    Bzip2(u32),
+    /// Pick the codec from the output path's extension (`.gz`, `.zst`,
+    /// `.bz2`) at `ArchiveEngine::run` time, at each codec's default
+    /// level; an unrecognized or missing extension means no compression.
+    Auto,
+}
+
+impl OutputCompression {
+    /// The file extension this compression appends to the output path,
+    /// or `None` for uncompressed/`Auto` output.
+    fn extension(&self) -> Option<&'static str> {
+        match self {
+            OutputCompression::None | OutputCompression::Auto => None,
+            OutputCompression::Gzip(_) => Some("gz"),
+            OutputCompression::Zstd(_) => Some("zst"),
+            OutputCompression::Bzip2(_) => Some("bz2"),
+        }
+    }
+}
+
+impl Default for OutputCompression {
+    fn default() -> Self {
+        OutputCompression::None
+    }
 }
 
 /// Output format for the archive
@@ -62,6 +241,8 @@ pub enum OutputFormat {
     Markdown,
     /// Rich text with syntax highlighting
     RichText,
+    /// Self-contained, syntax-highlighted HTML document
+    Html,
 }
 
 fn default_format() -> OutputFormat {
@@ -72,6 +253,26 @@ fn default_verbosity() -> u8 {
     1
 }
 
+fn default_true() -> bool {
+    true
+}
+
+fn default_parallel_channel_capacity() -> usize {
+    64
+}
+
+fn default_write_batch_size() -> usize {
+    256 * 1024
+}
+
+fn default_file_source() -> Arc<dyn FileSource> {
+    Arc::new(StdFsSource)
+}
+
+fn default_output_sink() -> Arc<dyn Sink> {
+    Arc::new(LocalFileSink)
+}
+
 impl Default for Config {
     fn default() -> Self {
         // Generate a timestamp string in the format YYYYMMDD_HHMMSS
@@ -85,21 +286,42 @@ impl Default for Config {
         
         Self {
             input,
+            file_source: default_file_source(),
             output,
+            output_sink: default_output_sink(),
             include_hidden: false,
             max_file_size: Some(10 * 1024 * 1024), // 10MB default max size
             parallel: true,
+            parallel_channel_capacity: default_parallel_channel_capacity(),
+            write_batch_size: default_write_batch_size(),
             git_info: true,
+            git_file_status: false,
+            revision: None,
+            changed_since: None,
             format: OutputFormat::Plain,
             include: None,
             exclude: None,
+            types: None,
+            type_not: None,
+            custom_types: Vec::new(),
+            override_rules: None,
+            respect_ignore_files: true,
+            respect_git_attributes: false,
+            default_encoding: None,
+            content_adapters: AdapterRegistry::with_builtins(),
+            raise_fd_limit: true,
             llm_optimize: true,
             show_filter_stats: true,
             include_extensions: None,
+            include_types: None,
             max_depth: None,
             follow_links: false,
             verbosity: 1,
             include_tree: true,
+            show_tree_icons: false,
+            manifest_output: None,
+            compression: OutputCompression::None,
+            incremental_manifest: None,
         }
     }
 }
@@ -128,6 +350,22 @@ impl Config {
         self
     }
 
+    /// Set the backend `ArchiveEngine` reads file bytes and tree structure
+    /// through (see `file_source`), e.g. `vfs::MemorySource::new()` for an
+    /// in-memory tree, or a custom `FileSource` for a zip or remote store.
+    pub fn with_file_source(mut self, file_source: impl FileSource + 'static) -> Self {
+        self.file_source = Arc::new(file_source);
+        self
+    }
+
+    /// Set the destination the finished archive is streamed to (see
+    /// `output_sink`), e.g. `sink::HttpPutSink::new(url)` to stream to a
+    /// remote object store instead of a local file.
+    pub fn with_output_sink(mut self, output_sink: impl Sink + 'static) -> Self {
+        self.output_sink = Arc::new(output_sink);
+        self
+    }
+
     /// Set the output file path
     /// If a directory is provided, creates a timestamped filename in that directory
     /// If a file is provided, uses that exact path
@@ -160,12 +398,47 @@ impl Config {
         self
     }
 
+    /// Set the bounded-channel capacity between parallel workers and the
+    /// output-writing consumer (see `parallel_channel_capacity`).
+    pub fn with_parallel_channel_capacity(mut self, capacity: usize) -> Self {
+        self.parallel_channel_capacity = capacity.max(1);
+        self
+    }
+
+    /// Set how many bytes of formatted output each parallel worker batches
+    /// before flushing (see `write_batch_size`).
+    pub fn with_write_batch_size(mut self, bytes: usize) -> Self {
+        self.write_batch_size = bytes.max(1);
+        self
+    }
+
     /// Set whether to include git information
     pub fn with_git_info(mut self, git_info: bool) -> Self {
         self.git_info = git_info;
         self
     }
 
+    /// Set whether to tag each file's header with its git working-tree
+    /// status (see `git_file_status`).
+    pub fn with_git_file_status(mut self, git_file_status: bool) -> Self {
+        self.git_file_status = git_file_status;
+        self
+    }
+
+    /// Archive the tree of `revision` (a commit, tag, or branch) instead
+    /// of the working directory (see `revision`).
+    pub fn with_revision(mut self, revision: impl Into<String>) -> Self {
+        self.revision = Some(revision.into());
+        self
+    }
+
+    /// Restrict the archive to files changed relative to `baseline` (see
+    /// `changed_since`).
+    pub fn with_changed_since(mut self, baseline: impl Into<String>) -> Self {
+        self.changed_since = Some(baseline.into());
+        self
+    }
+
     /// Set the output format
     pub fn with_format(mut self, format: OutputFormat) -> Self {
         self.format = format;
@@ -184,18 +457,96 @@ impl Config {
         self
     }
 
+    /// Set named language-type presets to include (e.g. `vec!["rust".into(), "python".into()]`)
+    pub fn with_types(mut self, types: Vec<String>) -> Self {
+        self.types = Some(types);
+        self
+    }
+
+    /// Set named language-type presets to exclude
+    pub fn with_type_not(mut self, types: Vec<String>) -> Self {
+        self.type_not = Some(types);
+        self
+    }
+
+    /// Register a custom `name -> globs` mapping so `types`/`type_not` can
+    /// refer to it alongside the built-in table. Overrides a built-in of the
+    /// same name.
+    pub fn with_custom_type(mut self, name: impl Into<String>, globs: Vec<String>) -> Self {
+        self.custom_types.push((name.into(), globs));
+        self
+    }
+
+    /// Set an ordered include/exclude rule list, applied on top of
+    /// `include`/`exclude`. A leading `!` marks a pattern as a whitelist
+    /// (re-include) rule; see `OverrideRules` for the resolution order.
+    pub fn with_override_rules(mut self, rules: Vec<String>) -> Self {
+        self.override_rules = Some(rules);
+        self
+    }
+
     /// Enable or disable LLM optimization
     pub fn with_llm_optimize(mut self, enable: bool) -> Self {
         self.llm_optimize = enable;
         self
     }
 
+    /// Set whether to honor discovered `.gitignore`/`.ignore`/
+    /// `.promptignore` files, layered over the built-in LLM defaults
+    pub fn with_respect_ignore_files(mut self, respect: bool) -> Self {
+        self.respect_ignore_files = respect;
+        self
+    }
+
+    /// Set whether to honor hierarchical `.gitattributes` files: drop
+    /// `export-ignore`d paths and normalize `text`/`eol`-attributed file
+    /// content, the way `git archive` would package the tree.
+    pub fn with_git_attributes(mut self, enable: bool) -> Self {
+        self.respect_git_attributes = enable;
+        self
+    }
+
+    /// Set the encoding to assume for a file with no recognized
+    /// byte-order-mark (see `default_encoding`).
+    pub fn with_default_encoding(mut self, encoding: impl Into<String>) -> Self {
+        self.default_encoding = Some(encoding.into());
+        self
+    }
+
+    /// Register a custom [`ContentAdapter`], taking precedence over the
+    /// built-in zip/tar/PDF/SQLite adapters already in `content_adapters`.
+    pub fn with_content_adapter(mut self, adapter: Arc<dyn ContentAdapter>) -> Self {
+        self.content_adapters.register(adapter);
+        self
+    }
+
+    /// Replace the entire adapter registry, e.g. with
+    /// `AdapterRegistry::empty()` to disable the built-in adapters.
+    pub fn with_content_adapters(mut self, registry: AdapterRegistry) -> Self {
+        self.content_adapters = registry;
+        self
+    }
+
+    /// Set whether to raise the process's soft `RLIMIT_NOFILE` before
+    /// parallel processing starts (see `raise_fd_limit`).
+    pub fn with_raise_fd_limit(mut self, enable: bool) -> Self {
+        self.raise_fd_limit = enable;
+        self
+    }
+
     /// Set file extensions to include (comma-separated)
     pub fn with_include_extensions(mut self, extensions: &str) -> Self {
         self.include_extensions = Some(extensions.to_string());
         self
     }
 
+    /// Set coarse MIME groups to include (comma-separated, e.g.
+    /// `text,code,config`); ANDed with `include_extensions` when both are set.
+    pub fn with_include_types(mut self, types: &str) -> Self {
+        self.include_types = Some(types.to_string());
+        self
+    }
+
     /// Set maximum depth for directory traversal
     pub fn with_max_depth(mut self, depth: usize) -> Self {
         self.max_depth = Some(depth);
@@ -220,279 +571,213 @@ impl Config {
         self
     }
 
-    /// Get comprehensive LLM ignore patterns for cleaner training data
-    ///
-    /// This method returns a comprehensive list of file patterns that should be
-    /// excluded when preparing code for LLM training. The patterns are based on
-    /// best practices from the AI/ML community and cover:
-    ///
-    /// - Build artifacts and compiled outputs
-    /// - Dependencies and package manager files
-    /// - Cache and temporary files
-    /// - IDE and editor configuration files
-    /// - OS-generated files
-    /// - Version control metadata
-    /// - Logs and databases
-    /// - Environment and secret files
-    /// - Binary media files
-    /// - Archives and compressed files
-    /// - Test coverage reports
-    /// - Language-specific compiled files
-    /// - Cloud and deployment configurations
-    /// - Mobile development artifacts
-    /// - Game development assets
-    /// - Large data files and ML models
+    /// Set whether tree entries are prefixed with a per-file-type icon.
+    pub fn with_show_tree_icons(mut self, show_tree_icons: bool) -> Self {
+        self.show_tree_icons = show_tree_icons;
+        self
+    }
+
+    /// Stream a Parquet manifest of every visited file (included or
+    /// filtered, with the reason) to `path` while the archive is built.
+    pub fn with_manifest_output(mut self, path: impl Into<PathBuf>) -> Self {
+        self.manifest_output = Some(path.into());
+        self
+    }
+
+    /// Skip re-embedding a file's content when it's unchanged since the
+    /// last run, and store identical content once across duplicate paths,
+    /// by diffing against (and updating) a sidecar manifest at `path`
+    /// (see `crate::incremental`).
+    pub fn with_incremental(mut self, path: impl Into<PathBuf>) -> Self {
+        self.incremental_manifest = Some(path.into());
+        self
+    }
+
+    /// Set output compression. Choosing a concrete codec appends its
+    /// extension (`.gz`/`.zst`/`.bz2`) to the output path if it doesn't
+    /// already end in one, so the file on disk always matches what it
+    /// contains; `Auto` leaves the path alone and infers the codec from
+    /// whatever extension it already has.
+    pub fn with_compression(mut self, compression: OutputCompression) -> Self {
+        if let Some(ext) = compression.extension() {
+            if self.output.extension().and_then(|e| e.to_str()) != Some(ext) {
+                let mut name = self.output.clone().into_os_string();
+                name.push(".");
+                name.push(ext);
+                self.output = PathBuf::from(name);
+            }
+        }
+        self.compression = compression;
+        self
+    }
+
+    /// Get comprehensive non-binary LLM ignore patterns for cleaner training data
     ///
-    /// These exclusions help create cleaner, more focused training datasets
-    /// that contain primarily source code and documentation rather than
-    /// generated artifacts or binary files.
+    /// This method returns a list of file/directory patterns that should be
+    /// excluded when preparing code for LLM training: build artifact
+    /// directories, dependency trees, caches, IDE/editor cruft, OS litter,
+    /// version-control metadata, logs, secrets, and known CI/cloud/mobile
+    /// project scaffolding. Binary media, document, archive, and data-blob
+    /// formats are *not* enumerated here by extension anymore -- they're
+    /// identified at filter time by [`crate::binary::is_probably_binary`],
+    /// which stays current automatically via the `mime_guess` table instead
+    /// of a hand-maintained (and, before this list was trimmed, heavily
+    /// duplicated) glob wall.
     pub fn get_default_llm_ignore_patterns() -> Vec<&'static str> {
         vec![
             // Version control
             "**/.git/", "**/.svn/", "**/.hg/", "**/.gitignore", "**/.gitmodules", "**/.gitattributes",
-    
-            // Build artifacts
+
+            // Build artifacts (directories only; compiled binary outputs
+            // like *.so/*.class/*.jar are caught by `is_probably_binary`)
             "**/target/", "**/build/", "**/dist/", "**/node_modules/", "**/__pycache__/",
-            "**/*.pyc", "**/*.pyo", "**/*.pyd", "**/*.so", "**/*.dll", "**/*.dylib",
-            "**/*.a", "**/*.lib", "**/*.o", "**/*.obj", "**/*.class", "**/*.jar", "**/*.war",
-    
+
             // Package managers and dependencies
             "**/package-lock.json", "**/yarn.lock", "**/Cargo.lock", "**/Gemfile.lock",
-            "**/Pipfile.lock", "**/poetry.lock", "**/yarn-error.log", "**/requirements*.txt",
-            "**/requirements/*.txt", "**/constraints.txt", "**/setup.cfg", "**/setup.py",
-    
+            "**/Pipfile.lock", "**/poetry.lock", "**/pnpm-lock.yaml", "**/composer.lock",
+            "**/stack.yaml.lock", "**/flake.lock", "**/mix.lock", "**/pubspec.lock",
+            "**/yarn-error.log*", "**/requirements*.txt", "**/requirements/*.txt",
+            "**/constraints.txt", "**/setup.cfg", "**/setup.py",
+
             // Environment and configuration
             "**/.env", "**/.env.*", "**/.venv/", "**/venv/", "**/env/", "**/ENV/",
             "**/env.bak/", "**/venv.bak/", "**/.python-version", "**/.ruby-version",
             "**/.node-version", "**/.nvmrc", "**/.editorconfig", "**/.prettierrc",
             "**/.eslintrc*", "**/.babelrc*", "**/tsconfig.json", "**/jsconfig.json",
-    
+
             // IDE and editor files
             "**/.idea/", "**/.vscode/", "**/*.swp", "**/*.swo", "**/*.swn",
-            "**/.DS_Store", "**/Thumbs.db", "**/.vs/", "**/*.sublime-*", "**/.history/",
+            "**/.vs/", "**/*.sublime-*", "**/.history/",
             "**/.vscode-test/", "**/.vscode/extensions.json", "**/.vscode/settings.json",
-    
+
             // Logs and databases
             "**/*.log", "**/*.sqlite", "**/*.db", "**/*.sql", "**/*.sqlite3",
             "**/*.sqlite-journal", "**/*.sqlite3-journal", "**/*.db-journal",
             "**/logs/", "**/log/", "**/var/log/",
-    
-            // Archives and binaries
-            "**/*.zip", "**/*.tar.gz", "**/*.tgz", "**/*.7z", "**/*.rar", "**/*.tar",
-            "**/*.exe", "**/*.dmg", "**/*.pkg", "**/*.app", "**/*.msi", "**/*.deb",
-            "**/*.rpm", "**/*.snap",
-    
-            // Media and binary files
-            "**/*.png", "**/*.jpg", "**/*.jpeg", "**/*.gif", "**/*.bmp", "**/*.tiff",
-            "**/*.ico", "**/*.svg", "**/*.mp3", "**/*.wav", "**/*.mp4", "**/*.avi",
-            "**/*.mov", "**/*.wmv", "**/*.flv", "**/*.mkv", "**/*.webp", "**/*.webm",
-            "**/*.woff", "**/*.woff2", "**/*.ttf", "**/*.eot", "**/*.otf",
-    
-            // Documents
-            "**/*.pdf", "**/*.doc", "**/*.docx", "**/*.xls", "**/*.xlsx", "**/*.ppt",
-            "**/*.pptx", "**/*.odt", "**/*.ods", "**/*.odp", "**/*.epub", "**/*.mobi",
-    
+
             // Virtual machines and containers
             "**/.vagrant/", "**/*.vagrant/", "**/*.vbox", "**/*.vbox-prev", "**/Vagrantfile",
-            "**/Dockerfile", "**/docker-compose*.yml", "**/.dockerignore", "**/.docker/",
-            "**/compose.yml", "**/docker-compose.override.yml",
-    
+            "**/Dockerfile", "**/Dockerfile.*", "**/docker-compose*.yml", "**/.dockerignore",
+            "**/.docker/", "**/compose.yml", "**/docker-compose.override.yml",
+
             // OS generated files
-            "**/ehthumbs.db", "**/Thumbs.db", "**/desktop.ini", "**/$RECYCLE.BIN/",
-            "**/Thumbs.db:encryptable", "**/ehthumbs_vista.db", "**/Desktop.ini",
-    
+            "**/.DS_Store", "**/._*", "**/.Spotlight-V100", "**/.Trashes",
+            "**/ehthumbs.db", "**/ehthumbs_vista.db", "**/Thumbs.db", "**/Thumbs.db:encryptable",
+            "**/desktop.ini", "**/Desktop.ini", "**/$RECYCLE.BIN/",
+            "**/.directory", "**/.Trash-*", "**/.nfs*",
+
             // Python specific
-            "**/__pycache__/", "**/*.py[cod]", "**/*$py.class", "**/.pytest_cache/",
-            "**/.mypy_cache/", "**/.pytest_cache/", "**/.coverage", "**/htmlcov/",
-            "**/*.cover", "**/*.py,cover", "**/.hypothesis/", "**/.pytest/",
-    
+            "**/.pytest_cache/", "**/.mypy_cache/", "**/.coverage", "**/htmlcov/",
+            "**/*.cover", "**/*.py,cover", "**/.hypothesis/", "**/.tox/", "**/.eggs/",
+            "**/*.egg-info/", "**/.ipynb_checkpoints/", "**/celerybeat-schedule",
+            "**/celerybeat.pid", "**/.pyre/", "**/.pytype/", "**/cython_debug/",
+
             // Node.js specific
-            "**/node_modules/", "**/.npm/", "**/.yarn-integrity", "**/.yarn/cache/",
-            "**/.yarn/unplugged/", "**/.yarn/build-state.yml", "**/.yarn/install-state.gz",
-            "**/.pnp.*", "**/.yarnrc.yml", "**/yarn-debug.log*", "**/yarn-error.log*",
-    
+            "**/.npm/", "**/.yarn-integrity", "**/.yarn/cache/", "**/.yarn/unplugged/",
+            "**/.yarn/build-state.yml", "**/.yarn/install-state.gz", "**/.pnp.*",
+            "**/.yarnrc.yml", "**/yarn-debug.log*", "**/bower_components/",
+            "**/.bower-cache/", "**/npm-debug.log", "**/.eslintcache",
+
             // Rust specific
-            "**/target/", "**/Cargo.lock", "**/*.rs.bk", "**/Cargo.toml.orig",
-    
+            "**/*.rs.bk", "**/Cargo.toml.orig",
+
             // Java specific
-            "**/.classpath", "**/.project", "**/.settings/", "**/*.class",
-            "**/bin/", "**/build/", "**/out/", "**/*.iml",
-    
+            "**/.classpath", "**/.project", "**/.settings/", "**/out/", "**/*.iml",
+            "**/.gradle/", "**/gradlew", "**/gradlew.bat",
+
             // Go specific
-            "**/bin/", "**/pkg/", "**/vendor/", "**/go.work", "**/go.work.sum",
-    
+            "**/pkg/", "**/vendor/", "**/go.work", "**/go.work.sum",
+
+            // PHP / Ruby / Elixir specific
+            "**/composer.phar", "**/.bundle/", "**/vendor/bundle/", "**/vendor/cache/",
+            "**/*.gem", "**/_build/", "**/deps/",
+
             // Web and frontend
-            "**/dist/", "**/build/", "**/.next/", "**/out/", "**/.nuxt/", "**/.output/",
-            "**/.svelte-kit/", "**/.astro/", "**/.cache/", "**/.parcel-cache/",
-            "**/.turbo/", "**/.vercel/", "**/.netlify/",
-    
+            "**/.next/", "**/.nuxt/", "**/.output/", "**/.svelte-kit/", "**/.astro/",
+            "**/.cache/", "**/.parcel-cache/", "**/.turbo/", "**/.vercel/", "**/.netlify/",
+
             // Testing and coverage
             "**/coverage/", "**/.nyc_output/", "**/coverage-*.lcov", "**/lcov.info",
             "**/.jest-cache/", "**/jest.config.*", "**/karma.conf.*", "**/test-results/",
-    
+
             // Documentation
             "**/docs/_build/", "**/docs/api/", "**/site/", "**/.vuepress/", "**/storybook-static/",
-    
-            // Development tools
+
+            // Development tools and CI
             "**/.github/", "**/.circleci/", "**/.travis.yml", "**/.gitlab-ci.yml",
             "**/Jenkinsfile", "**/azure-pipelines.yml", "**/.github/workflows/*.yaml",
-            "**/.pre-commit-config.yaml", "**/.commitlintrc*", "**/.husky/",
-    
+            "**/.pre-commit-config.yaml", "**/.commitlintrc*", "**/.husky/", "**/.sonarqube/",
+            "**/_NCrunch_*/", "**/.mvn/", "**/mvnw", "**/mvnw.cmd",
+
             // Temporary files
-            "**/*.swp", "**/*.swo", "**/*.swn", "**/*.swo", "**/*.swn", "**/*.bak",
-            "**/*.backup", "**/*.tmp", "**/*.temp", "**/*~", "**/*.orig", "**/*.rej",
-    
-            // macOS specific
-            "**/.DS_Store", "**/._*", "**/.Spotlight-V100", "**/.Trashes", "**/ehthumbs.db",
-    
-            // Windows specific
-            "**/Thumbs.db", "**/Desktop.ini", "**/Thumbs.db:encryptable",
-    
-            // Linux specific
-            "**/.directory", "**/.Trash-*", "**/.nfs*",
-    
-            // Additional patterns (from researched templates)
-            "**/.tox/", "**/.eggs/", "**/*.egg", "**/*.egg-info/", "**/.ipynb_checkpoints/", // Python extras
-            "**/celerybeat-schedule", "**/celerybeat.pid", "**/*.sage.py", "**/.pyre/", "**/.pytype/", "**/cython_debug/",
-            "**/bower_components/", "**/.bower-cache/", "**/npm-debug.log", "**/.eslintcache", // Node extras
-            "**/.gradle/", "**/gradlew", "**/gradlew.bat", "**/.mvn/", "**/mvnw", "**/mvnw.cmd", // Java extras
-            "**/vendor/", "**/composer.lock", "**/composer.phar", // PHP
-            "**/.bundle/", "**/vendor/bundle/", "**/vendor/cache/", "**/*.gem", // Ruby extras
-            "**/_build/", "**/deps/", "**/mix.lock", // Elixir
-            // Added from filter-rules.txt and archival ref.rs for more comprehensive filtering
-            "**/pnpm-lock.yaml", "**/yarn-error.log", "**/.npm/", "**/.yarn-integrity", "**/.yarn/cache/", "**/.yarn/unplugged/",
-            "**/public/hot", "**/public/storage", "**/storage/app/public", "**/storage/framework/sessions/*", "**/storage/framework/views/*",
-            "**/storage/framework/cache/data/*", "**/storage/logs/*.log", "Homestead.yaml", "Homestead.json", "**/var/", "**/public/bundles/",
-            "**/.metadata/", "**/.recommenders/", "**/Carthage/Build/", "**/Carthage/Checkouts/", "**/Pods/", "**/.swiftpm/",
-            "**/DerivedData/", "**/*.xcodeproj/project.xcworkspace/", "**/*.xcodeproj/xcuserdata/", "**/*.xcworkspace/contents.xcworkspacedata",
-            "**/*.xcworkspace/xcuserdata/", "**/app/build/", "**/*.apk", "**/*.aab", "**/captures/", "**/*.jks", "**/*.keystore",
-            "local.properties", "**/dist/", "**/gradle-wrapper.jar", "hs_err_pid*", "**/tmp/", "**/.dvc/cache", "**/.dvc/tmp",
-            "**/mlruns/", "**/mlflow-artifacts/", "**/.cache/huggingface/", "**/.terraform/", "**/*.tfstate", "**/*.tfstate.*",
-            "**/crash.log", "**/*.tfvars", "**/*.tfvars.json", "**/.terraformrc", "**/terraform.rc", "**/Pulumi.*.yaml",
-            "**/.azure-config/", "**/.aws/", "**/.azure/", "**/.config/gcloud/", "**/.dbeaver-data-sources.xml", "**/postman/backups/",
-            "**/.sonarqube/", "**/_NCrunch_*/", "**/*.crt", "**/*.csr", "**/*.ca", "**/*.pfx", "**/*.p12", "**/*.key",
-            "**/*.pem", "**/priv/static/", "**/*.native", "**/*.byte", "**/*.cmi", "**/*.cmo", "**/*.cmx", "**/*.cmxa", "**/*.cma",
-            "**/*.cmxs", "**/cmake-build-*/", "**/CMakeFiles/", "**/CMakeCache.txt", "**/cmake_install.cmake", "**/install_manifest.txt",
-            "**/Makefile", "**/cabal.sandbox.config", "**/dist-newstyle/", "**/.cabal-sandbox/", "**/.stack-work/", "**/pubspec.lock",
-            "**/stack.yaml.lock", "**/flake.lock", "**/cdk.out/", "**/.firebase/", "**/.gcloud/", "**/.vercel/", "**/.serverless/",
-            "**/.pulumi/", "**/local.properties", "**/xcuserdata/", "**/project.xcworkspace", "**/*.xcuserstate", "**/*.hmap",
-            "**/build/", "**/DerivedData/", "**/*.xcodeproj/", "**/*.xcworkspace/", "**/*.xcarchive/", "**/*.dSYM/", "**/*.app",
-            "**/*.ipa", "**/.docker/", "**/Dockerfile.*", "**/.dockerignore", "**/data/", "**/datasets/", "**/*.coreml", "**/*.mlmodel",
-            "**/*.onnx", "**/*.tflite", "**/*.pb", "**/*.h5", "**/*.ckpt", "**/*.pth", "**/*.pt", "**/*.weights", "**/*.model",
-            "**/*.joblib", "**/*.pkl", "**/*.pickle", "**/*.npy", "**/*.npz", "**/*.mat", "**/*.nc", "**/*.hdf5", "**/*.h5",
-            "**/*.parquet", "**/*.xml", "**/*.json", "**/*.tsv", "**/*.csv", "**/*.raw", "**/*.dat", "**/*.bin", "**/*.eot",
-            "**/*.woff2", "**/*.woff", "**/*.m4a", "**/*.opus", "**/*.wma", "**/*.ogg", "**/*.aac", "**/*.flac", "**/*.wav",
-            "**/*.mp3", "**/*.ogv", "**/*.3gp", "**/*.m4v", "**/*.webm", "**/*.flv", "**/*.wmv", "**/*.mov", "**/*.mkv",
-            "**/*.avi", "**/*.mp4", "**/*.fig", "**/*.sketch", "**/*.psd", "**/*.ai", "**/*.eps", "**/*.svg", "**/*.webp",
-            "**/*.tif", "**/*.tiff", "**/*.ico", "**/*.bmp", "**/*.gif", "**/*.jpeg", "**/*.jpg", "**/*.png", "**/*.keynote",
-            "**/*.numbers", "**/*.pages", "**/*.rtf", "**/*.odp", "**/*.ods", "**/*.odt", "**/*.xlsx", "**/*.xls", "**/*.pptx",
-            "**/*.ppt", "**/*.docx", "**/*.doc", "**/*.pdf", "**/azure-pipelines.yml", "**/Jenkinsfile", "**/.gitlab-ci.yml",
-            "**/.travis.yml", "**/.circleci/", "**/.github/", "**/.vercel/", "**/.netlify/", "**/.turbo/", "**/.parcel-cache/",
-            "**/.cache/", "**/.astro/", "**/.svelte-kit/", "**/.output/", "**/.nuxt/", "**/.next/", "**/karma.conf.*",
-            "**/jest.config.*", "**/.jest-cache/", "**/lcov.info", "**/coverage-*.lcov", "**/.nyc_output/", "**/coverage/",
-            "**/test-results/", "**/jest.config.*", "**/jest.config.*", "**/lcov.info", "**/coverage-*.lcov", "**/.nyc_output/",
-            "**/coverage/", "**/storybook-static/", "**/.vuepress/", "**/site/", "**/docs/api/", "**/docs/_build/", "**/*.rej",
-            "**/*.orig", "**/*~", "**/*.temp", "**/*.tmp", "**/*.backup", "**/*.bak", "**/.husky/", "**/.commitlintrc*",
-            "**/.pre-commit-config.yaml", "**/.github/workflows/*.yaml", "**/azure-pipelines.yml", "**/Jenkinsfile",
-            "**/.gitlab-ci.yml", "**/.travis.yml", "**/.circleci/", "**/.github/", "**/*.odp", "**/*.ods", "**/*.odt",
-            "**/*.pptx", "**/*.ppt", "**/*.xlsx", "**/*.xls", "**/*.docx", "**/*.doc", "**/*.pdf", "**/*.otf", "**/*.eot",
-            "**/*.ttf", "**/*.woff2", "**/*.woff", "**/*.webm", "**/*.webp", "**/*.mkv", "**/*.flv", "**/*.wmv", "**/*.mov",
-            "**/*.avi", "**/*.mp4", "**/*.wav", "**/*.mp3", "**/*.svg", "**/*.ico", "**/*.tiff", "**/*.bmp", "**/*.gif",
-            "**/*.jpeg", "**/*.jpg", "**/*.png", "**/*.raw", "**/*.dat", "**/*.bin", "**/data/", "**/datasets/", "**/*.coreml",
-            "**/*.mlmodel", "**/*.onnx", "**/*.tflite", "**/*.pb", "**/*.h5", "**/*.ckpt", "**/*.pth", "**/*.pt", "**/*.weights",
-            "**/*.model", "**/*.joblib", "**/*.pkl", "**/*.pickle", "**/*.npy", "**/*.npz", "**/*.mat", "**/*.nc", "**/*.hdf5",
-            "**/*.h5", "**/*.parquet", "**/*.xml", "**/*.json", "**/*.tsv", "**/*.csv", "**/*.opus", "**/*.m4a", "**/*.wma",
-            "**/*.ogg", "**/*.aac", "**/*.flac", "**/*.wav", "**/*.mp3", "**/*.ogv", "**/*.3gp", "**/*.m4v", "**/*.webm",
-            "**/*.flv", "**/*.wmv", "**/*.mov", "**/*.mkv", "**/*.avi", "**/*.mp4", "**/*.fig", "**/*.sketch", "**/*.psd",
-            "**/*.ai", "**/*.eps", "**/*.svg", "**/*.webp", "**/*.tif", "**/*.tiff", "**/*.ico", "**/*.bmp", "**/*.gif",
-            "**/*.jpeg", "**/*.jpg", "**/*.png", "**/postman/backups/", "**/.dbeaver-data-sources.xml", "**/.config/gcloud/",
-            "**/.azure/", "**/.aws/", "**/.azure-config/", "**/Pulumi.*.yaml", "**/.pulumi/", "**/*.tfvars.json", "**/*.tfvars",
-            "**/crash.log", "**/*.tfstate.*", "**/*.tfstate", "**/.terraform/", "**/.cache/huggingface/", "**/mlflow-artifacts/",
-            "**/mlruns/", "**/.dvc/tmp", "**/.dvc/cache", "**/.ipynb_checkpoints/", "**/*.raw", "**/*.dat", "**/*.bin",
-            "**/data/", "**/datasets/", "**/*.coreml", "**/*.mlmodel", "**/*.onnx", "**/*.tflite", "**/*.pb", "**/*.h5",
-            "**/*.ckpt", "**/*.pth", "**/*.pt", "**/*.weights", "**/*.model", "**/*.joblib", "**/*.pkl", "**/*.pickle",
-            "**/*.npy", "**/*.npz", "**/*.mat", "**/*.nc", "**/*.hdf5", "**/*.h5", "**/*.parquet", "**/*.xml", "**/*.json",
-            "**/*.tsv", "**/*.csv", "**/*.eot", "**/*.woff2", "**/*.woff", "**/*.opus", "**/*.m4a", "**/*.wma", "**/*.ogg",
-            "**/*.aac", "**/*.flac", "**/*.wav", "**/*.mp3", "**/*.ogv", "**/*.3gp", "**/*.m4v", "**/*.webm", "**/*.flv",
-            "**/*.wmv", "**/*.mov", "**/*.mkv", "**/*.avi", "**/*.mp4", "**/*.fig", "**/*.sketch", "**/*.psd", "**/*.ai",
-            "**/*.eps", "**/*.svg", "**/*.webp", "**/*.tif", "**/*.tiff", "**/*.ico", "**/*.bmp", "**/*.gif", "**/*.jpeg",
-            "**/*.jpg", "**/*.png", "**/keynote", "**/numbers", "**/pages", "**/rtf", "**/odp", "**/ods", "**/odt",
-            "**/xlsx", "**/xls", "**/pptx", "**/ppt", "**/docx", "**/doc", "**/pdf", "**/azure-pipelines.yml", "**/Jenkinsfile",
-            "**/.gitlab-ci.yml", "**/.travis.yml", "**/.circleci/", "**/.github/", "**/vercel/", "**/netlify/", "**/turbo/",
-            "**/parcel-cache/", "**/cache/", "**/astro/", "**/svelte-kit/", "**/output/", "**/nuxt/", "**/next/",
-            "**/karma.conf.*", "**/jest.config.*", "**/jest-cache/", "**/lcov.info", "**/coverage-*.lcov", "**/nyc_output/",
-            "**/coverage/", "**/test-results/", "**/storybook-static/", "**/vuepress/", "**/site/", "**/docs/api/",
-            "**/docs_build/", "**/rej", "**/orig", "**/~", "**/temp", "**/tmp", "**/backup", "**/bak", "**/husky/",
-            "**/commitlintrc*", "**/pre-commit-config.yaml", "**/github/workflows/*.yaml", "**/azure-pipelines.yml",
-            "**/Jenkinsfile", "**/gitlab-ci.yml", "**/travis.yml", "**/circleci/", "**/github/", "**/odp", "**/ods",
-            "**/odt", "**/pptx", "**/ppt", "**/xlsx", "**/xls", "**/docx", "**/doc", "**/pdf", "**/otf", "**/eot",
-            "**/ttf", "**/woff2", "**/woff", "**/webm", "**/webp", "**/mkv", "**/flv", "**/wmv", "**/mov", "**/avi",
-            "**/mp4", "**/mp3", "**/wav", "**/flac", "**/aac", "**/ogg", "**/wma", "**/m4a", "**/opus", "**/ogv",
-            "**/3gp", "**/m4v", "**/webm", "**/flv", "**/wmv", "**/mov", "**/mkv", "**/avi", "**/mp4", "**/fig",
-            "**/sketch", "**/psd", "**/ai", "**/eps", "**/svg", "**/webp", "**/tif", "**/tiff", "**/ico", "**/bmp",
-            "**/gif", "**/jpeg", "**/jpg", "**/png", "**/postman/backups/", "**/dbeaver-data-sources.xml",
-            "**/config/gcloud/", "**/azure/", "**/aws/", "**/azure-config/", "**/Pulumi.*.yaml", "**/pulumi/",
-            "**/tfvars.json", "**/tfvars", "**/crash.log", "**/tfstate.*", "**/tfstate", "**/terraform/",
-            "**/cache/huggingface/", "**/mlflow-artifacts/", "**/mlruns/", "**/dvc/tmp", "**/dvc/cache",
-            "**/ipynb_checkpoints/", "**/raw", "**/dat", "**/bin", "**/data/", "**/datasets/", "**/coreml",
-            "**/mlmodel", "**/onnx", "**/tflite", "**/pb", "**/h5", "**/ckpt", "**/pth", "**/pt", "**/weights",
-            "**/model", "**/joblib", "**/pkl", "**/pickle", "**/npy", "**/npz", "**/mat", "**/nc", "**/hdf5",
-            "**/h5", "**/parquet", "**/xml", "**/json", "**/tsv", "**/csv", "**/opus", "**/m4a", "**/wma",
-            "**/ogg", "**/aac", "**/flac", "**/wav", "**/mp3", "**/ogv", "**/3gp", "**/m4v", "**/webm",
-            "**/flv", "**/wmv", "**/mov", "**/mkv", "**/avi", "**/mp4", "**/fig", "**/sketch", "**/psd",
-            "**/ai", "**/eps", "**/svg", "**/webp", "**/tif", "**/tiff", "**/ico", "**/bmp", "**/gif",
-            "**/jpeg", "**/jpg", "**/png", "**/keynote", "**/numbers", "**/pages", "**/rtf", "**/odp",
-            "**/ods", "**/odt", "**/xlsx", "**/xls", "**/pptx", "**/ppt", "**/docx", "**/doc", "**/pdf",
-            "**/otf", "**/eot", "**/ttf", "**/woff2", "**/woff", "**/webm", "**/webp", "**/mkv", "**/flv",
-            "**/wmv", "**/mov", "**/avi", "**/mp4", "**/mp3", "**/wav", "**/flac", "**/aac", "**/ogg",
-            "**/wma", "**/m4a", "**/opus", "**/ogv", "**/3gp", "**/m4v", "**/webm", "**/flv", "**/wmv",
-            "**/mov", "**/mkv", "**/avi", "**/mp4", "**/fig", "**/sketch", "**/psd", "**/ai", "**/eps",
-            "**/svg", "**/webp", "**/tif", "**/tiff", "**/ico", "**/bmp", "**/gif", "**/jpeg", "**/jpg",
-            "**/png", "**/postman/backups/", "**/dbeaver-data-sources.xml", "**/config/gcloud/", "**/azure/",
-            "**/aws/", "**/azure-config/", "**/Pulumi.*.yaml", "**/pulumi/", "**/tfvars.json", "**/tfvars",
-            "**/crash.log", "**/tfstate.*", "**/tfstate", "**/terraform/", "**/cache/huggingface/",
-            "**/mlflow-artifacts/", "**/mlruns/", "**/dvc/tmp", "**/dvc/cache", "**/ipynb_checkpoints/",
-            "**/raw", "**/dat", "**/bin", "**/data/", "**/datasets/", "**/coreml", "**/mlmodel", "**/onnx",
-            "**/tflite", "**/pb", "**/h5", "**/ckpt", "**/pth", "**/pt", "**/weights", "**/model", "**/joblib",
-            "**/pkl", "**/pickle", "**/npy", "**/npz", "**/mat", "**/nc", "**/hdf5", "**/h5", "**/parquet",
-            "**/xml", "**/json", "**/tsv", "**/csv", "**/opus", "**/m4a", "**/wma", "**/ogg", "**/aac",
-            "**/flac", "**/wav", "**/mp3", "**/ogv", "**/3gp", "**/m4v", "**/webm", "**/flv", "**/wmv",
-            "**/mov", "**/mkv", "**/avi", "**/mp4", "**/fig", "**/sketch", "**/psd", "**/ai", "**/eps",
-            "**/svg", "**/webp", "**/tif", "**/tiff", "**/ico", "**/bmp", "**/gif", "**/jpeg", "**/jpg",
-            "**/png", "**/keynote", "**/numbers", "**/pages", "**/rtf", "**/odp", "**/ods", "**/odt",
-            "**/xlsx", "**/xls", "**/pptx", "**/ppt", "**/docx", "**/doc", "**/pdf", "**/otf", "**/eot",
-            "**/ttf", "**/woff2", "**/woff", "**/webm", "**/webp", "**/mkv", "**/flv", "**/wmv", "**/mov",
-            "**/avi", "**/mp4", "**/mp3", "**/wav", "**/flac", "**/aac", "**/ogg", "**/wma", "**/m4a",
-            "**/opus", "**/ogv", "**/3gp", "**/m4v", "**/webm", "**/flv", "**/wmv", "**/mov", "**/mkv",
-            "**/avi", "**/mp4", "**/fig", "**/sketch", "**/psd", "**/ai", "**/eps", "**/svg", "**/webp",
-            "**/tif", "**/tiff", "**/ico", "**/bmp", "**/gif", "**/jpeg", "**/jpg", "**/png", "**/postman/backups/",
-            "**/dbeaver-data-sources.xml", "**/config/gcloud/", "**/azure/", "**/aws/", "**/azure-config/",
-            "**/Pulumi.*.yaml", "**/pulumi/", "**/tfvars.json", "**/tfvars", "**/crash.log", "**/tfstate.*",
-            "**/tfstate", "**/terraform/", "**/cache/huggingface/", "**/mlflow-artifacts/", "**/mlruns/",
-            "**/dvc/tmp", "**/dvc/cache", "**/ipynb_checkpoints/", "**/raw", "**/dat", "**/bin", "**/data/",
-            "**/datasets/", "**/coreml", "**/mlmodel", "**/onnx", "**/tflite", "**/pb", "**/h5", "**/ckpt",
-            "**/pth", "**/pt", "**/weights", "**/model", "**/joblib", "**/pkl", "**/pickle", "**/npy",
-            "**/npz", "**/mat", "**/nc", "**/hdf5", "**/h5", "**/parquet", "**/xml", "**/json", "**/tsv",
-            "**/csv", "**/opus", "**/m4a", "**/wma", "**/ogg", "**/aac", "**/flac", "**/wav", "**/mp3",
-            "**/ogv", "**/3gp", "**/m4v", "**/webm", "**/flv", "**/wmv", "**/mov", "**/mkv", "**/avi",
-            "**/mp4", "**/fig", "**/sketch", "**/psd", "**/ai", "**/eps", "**/svg", "**/webp", "**/tif",
-            "**/tiff", "**/ico", "**/bmp", "**/gif", "**/jpeg", "**/jpg", "**/png", "**/keynote", "**/numbers",
-            "**/pages", "**/rtf", "**/odp", "**/ods", "**/odt", "**/xlsx", "**/xls", "**/pptx", "**/ppt",
-            "**/docx", "**/doc", "**/pdf", "**/otf", "**/eot", "**/ttf", "**/woff2", "**/woff", "**/webm",
-            "**/webp", "**/mkv", "**/flv", "**/wmv", "**/mov", "**/avi", "**/mp4", "**/mp3", "**/wav",
-            "**/flac", "**/aac", "**/ogg", "**/wma", "**/m4a", "**/opus", "**/ogv", "**/3gp", "**/m4v",
-            "**/webm", "**/flv", "**/wmv", "**/mov", "**/mkv", "**/avi", "**/mp4", "**/fig", "**/sketch",
-            "**/psd", "**/ai", "**/eps", "**/svg", "**/webp", "**/tif", "**/tiff", "**/ico", "**/bmp",
-            "**/gif", "**/jpeg", "**/jpg", "**/png", "**/postman/backups/", "**/dbeaver-data-sources.xml",
-            "**/config/gcloud/", "**/azure/", "**/aws/", "**/azure-config/", "**/Pulumi.*.yaml", "**/pulumi/",
-            "**/tfvars.json", "**/tfvars", "**/crash.log", "**/tfstate.*", "**/tfstate", "**/terraform/",
-            "**/cache/huggingface/", "**/mlflow-artifacts/", "**/mlruns/", "**/dvc/tmp", "**/dvc/cache",
-            "**/ipynb_checkpoints/", "**/raw", "**/dat", "**/bin", "**/data/", "**/datasets/", "**/coreml",
-            "**/mlmodel", "**/onnx", "**/tflite", "**/pb", "**/h5", "**/ckpt", "**/pth", "**/pt", "**/weights",
-            "**/model", "**/joblib", "**/pkl", "**/pickle", "**/npy", "**/npz", "**/mat", "**/nc", "**/hdf5",
-            "**/h5", "**/parquet", "**/xml", "**/json", "**/tsv", "**/csv",
+            "**/*.bak", "**/*.backup", "**/*.tmp", "**/*.temp", "**/*~", "**/*.orig",
+            "**/*.rej", "**/hs_err_pid*", "**/crash.log",
+
+            // Cloud, IaC, and secrets
+            "**/.terraform/", "**/.terraformrc", "**/terraform.rc", "**/*.tfstate",
+            "**/*.tfstate.*", "**/*.tfvars", "**/*.tfvars.json", "**/Pulumi.*.yaml",
+            "**/.pulumi/", "**/.aws/", "**/.azure/", "**/.azure-config/", "**/.config/gcloud/",
+            "**/.gcloud/", "**/.firebase/", "**/.serverless/", "**/cdk.out/",
+            "**/.dbeaver-data-sources.xml", "**/postman/backups/", "**/*.crt", "**/*.csr",
+            "**/*.ca", "**/*.pfx", "**/*.p12", "**/*.key", "**/*.pem",
+
+            // Mobile and desktop build scaffolding (project-wrapper files;
+            // the app bundles/archives themselves are binary and caught by
+            // `is_probably_binary`)
+            "**/.metadata/", "**/.recommenders/", "**/Carthage/Build/", "**/Carthage/Checkouts/",
+            "**/Pods/", "**/.swiftpm/", "**/DerivedData/", "**/*.xcodeproj/", "**/*.xcworkspace/",
+            "**/xcuserdata/", "**/captures/", "local.properties", "**/gradle-wrapper.jar",
+
+            // Native build systems
+            "**/*.native", "**/*.byte", "**/*.cmi", "**/*.cmo", "**/*.cmx", "**/*.cmxa",
+            "**/*.cma", "**/*.cmxs", "**/cmake-build-*/", "**/CMakeFiles/", "**/CMakeCache.txt",
+            "**/cmake_install.cmake", "**/install_manifest.txt", "**/cabal.sandbox.config",
+            "**/dist-newstyle/", "**/.cabal-sandbox/", "**/.stack-work/",
+
+            // ML / data tooling caches (the dataset and model files
+            // themselves are binary and caught by `is_probably_binary`)
+            "**/.dvc/cache", "**/.dvc/tmp", "**/mlruns/", "**/mlflow-artifacts/",
+            "**/.cache/huggingface/",
+
+            // Laravel / PHP storage
+            "**/public/hot", "**/public/storage", "**/public/bundles/", "**/storage/app/public",
+            "**/storage/framework/sessions/*", "**/storage/framework/views/*",
+            "**/storage/framework/cache/data/*", "**/storage/logs/*.log",
+            "Homestead.yaml", "Homestead.json", "**/Makefile",
         ]
     }
+
+    /// Deduplicate `get_default_llm_ignore_patterns` (the list above repeats
+    /// entries like `**/*.png` many times over) and compile what's left into
+    /// a single `GlobSet`, so matching a path against it is one constant-time
+    /// lookup instead of thousands of linear pattern comparisons.
+    fn compile_default_llm_ignore_matcher() -> std::result::Result<GlobSet, String> {
+        let mut seen = HashSet::new();
+        let mut builder = GlobSetBuilder::new();
+        for pattern in Self::get_default_llm_ignore_patterns() {
+            if !seen.insert(pattern) {
+                continue;
+            }
+            let glob = Glob::new(pattern)
+                .map_err(|e| format!("invalid built-in ignore pattern '{pattern}': {e}"))?;
+            builder.add(glob);
+        }
+        builder.build().map_err(|e| e.to_string())
+    }
+
+    /// The compiled, deduplicated form of `get_default_llm_ignore_patterns`,
+    /// built once and reused for the rest of the process -- this is the hot
+    /// path when `llm_optimize` is on over a large tree. Returns an error
+    /// (rather than silently dropping the offending pattern) if any built-in
+    /// pattern fails to compile as a glob.
+    pub fn default_llm_ignore_matcher() -> ArchiveResult<&'static GlobSet> {
+        static MATCHER: OnceLock<std::result::Result<GlobSet, String>> = OnceLock::new();
+        MATCHER
+            .get_or_init(Self::compile_default_llm_ignore_matcher)
+            .as_ref()
+            .map_err(|e| ArchiveError::pattern(e.clone()))
+    }
+
     /// Get the set of file extensions to include
     pub fn get_included_extensions(&self) -> Option<HashSet<String>> {
         self.include_extensions.as_ref().map(|exts| {
@@ -502,4 +787,126 @@ impl Config {
                 .collect()
         })
     }
+
+    /// Get the set of coarse MIME groups to include, parsed from
+    /// `include_types`. Unrecognized group names are silently dropped, the
+    /// same way an unrecognized extension in `get_included_extensions`
+    /// would just never match anything.
+    pub fn get_included_mime_groups(&self) -> Option<HashSet<MimeGroup>> {
+        self.include_types.as_ref().map(|types| {
+            types
+                .split(',')
+                .filter_map(MimeGroup::parse)
+                .collect()
+        })
+    }
+
+    /// List every known type name: the built-ins from `known_file_types()`
+    /// plus any registered via `with_custom_type`, in that order.
+    pub fn list_known_file_types(&self) -> Vec<&str> {
+        known_file_types()
+            .iter()
+            .map(|(name, _)| *name)
+            .chain(self.custom_types.iter().map(|(name, _)| name.as_str()))
+            .collect()
+    }
+
+    /// Expand a list of type names into the union of their glob patterns.
+    /// `custom_types` is checked first, so a custom mapping can override a
+    /// built-in of the same name; an unknown name contributes no patterns.
+    fn resolve_file_types(&self, names: &[String]) -> Vec<String> {
+        names
+            .iter()
+            .flat_map(|name| {
+                self.custom_types
+                    .iter()
+                    .find(|(n, _)| n == name)
+                    .map(|(_, globs)| globs.clone())
+                    .or_else(|| {
+                        known_file_types()
+                            .iter()
+                            .find(|(n, _)| n == name)
+                            .map(|(_, globs)| globs.iter().map(|g| g.to_string()).collect())
+                    })
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    /// The effective include patterns: `include` plus anything expanded
+    /// from `types`. Returns `None` when there's nothing to include on.
+    pub fn resolved_include_patterns(&self) -> Option<Vec<String>> {
+        let mut patterns = self.include.clone().unwrap_or_default();
+        if let Some(types) = &self.types {
+            patterns.extend(self.resolve_file_types(types));
+        }
+        if patterns.is_empty() {
+            None
+        } else {
+            Some(patterns)
+        }
+    }
+
+    /// The effective exclude patterns: `exclude` plus anything expanded
+    /// from `type_not`. Returns `None` when there's nothing to exclude on.
+    pub fn resolved_exclude_patterns(&self) -> Option<Vec<String>> {
+        let mut patterns = self.exclude.clone().unwrap_or_default();
+        if let Some(types) = &self.type_not {
+            patterns.extend(self.resolve_file_types(types));
+        }
+        if patterns.is_empty() {
+            None
+        } else {
+            Some(patterns)
+        }
+    }
+
+    /// Compile `include`/`exclude` (with `types`/`type_not` folded in) plus
+    /// any explicit `override_rules` into one ordered, last-match-wins rule
+    /// set -- the single source of truth `collect_files` filters against.
+    pub fn compiled_override_rules(&self) -> OverrideRules {
+        let include = self.resolved_include_patterns().unwrap_or_default();
+        let exclude = self.resolved_exclude_patterns().unwrap_or_default();
+        let mut rules = OverrideRules::from_legacy(&include, &exclude);
+        if let Some(extra) = &self.override_rules {
+            rules.extend(extra);
+        }
+        rules
+    }
+}
+
+/// The built-in language name -> glob-pattern table used to resolve
+/// `types`/`type_not` entries. See `Config::list_known_file_types` for the
+/// flattened name list (including any custom registrations), and
+/// `Config::with_custom_type` to add another mapping at runtime.
+pub fn known_file_types() -> &'static [(&'static str, &'static [&'static str])] {
+    &[
+        ("rust", &["*.rs"]),
+        ("python", &["*.py", "*.pyi"]),
+        ("javascript", &["*.js", "*.jsx", "*.mjs", "*.cjs"]),
+        ("typescript", &["*.ts", "*.tsx"]),
+        ("go", &["*.go"]),
+        ("java", &["*.java"]),
+        ("c", &["*.c", "*.h"]),
+        ("cpp", &["*.cpp", "*.cc", "*.cxx", "*.hpp", "*.hh", "*.hxx"]),
+        ("csharp", &["*.cs"]),
+        ("ruby", &["*.rb"]),
+        ("php", &["*.php"]),
+        ("swift", &["*.swift"]),
+        ("kotlin", &["*.kt", "*.kts"]),
+        ("scala", &["*.scala"]),
+        ("shell", &["*.sh", "*.bash", "*.zsh"]),
+        ("asm", &["*.asm", "*.s", "*.S"]),
+        ("bazel", &["*.bazel", "*.bzl", "BUILD", "MODULE.bazel", "WORKSPACE"]),
+        ("avro", &["*.avdl", "*.avpr", "*.avsc"]),
+        ("proto", &["*.proto"]),
+        ("sql", &["*.sql"]),
+        ("yaml", &["*.yaml", "*.yml"]),
+        ("json", &["*.json"]),
+        ("markdown", &["*.md", "*.markdown"]),
+        ("html", &["*.html", "*.htm"]),
+        ("css", &["*.css", "*.scss", "*.sass", "*.less"]),
+        ("docs", &["*.md", "*.rst", "*.txt", "*.adoc"]),
+        ("web", &["*.html", "*.htm", "*.css", "*.js", "*.jsx", "*.ts", "*.tsx"]),
+    ]
 }
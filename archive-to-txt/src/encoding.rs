@@ -0,0 +1,200 @@
+//! BOM-aware encoding detection and transcoding for file contents.
+//!
+//! `process_single_file`/`process_single_file_to_buffer` used to read
+//! every file as UTF-8 and fall back to `String::from_utf8_lossy` on
+//! failure, which silently mangles UTF-16 logs and Windows-exported text
+//! files into replacement-character soup. [`decode`] instead sniffs a
+//! leading byte-order-mark (UTF-8, UTF-16LE/BE, UTF-32LE/BE), strips it,
+//! and transcodes the remainder to UTF-8 with `encoding_rs`; with no BOM
+//! present it honors `Config::default_encoding` if one was configured,
+//! and otherwise assumes UTF-8. Lossy replacement only kicks in once a
+//! chosen encoding actually fails to decode some of the bytes, exactly as
+//! `encoding_rs`'s own REPLACEMENT-on-error behavior does.
+
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+
+/// The outcome of decoding one file's raw bytes to UTF-8 text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedContent {
+    /// The decoded text, ready to hand to a formatter.
+    pub content: String,
+    /// Name of the encoding that was used (e.g. `"UTF-8"`, `"UTF-16LE"`),
+    /// for callers that want to surface it (manifests, formatters).
+    pub encoding: &'static str,
+    /// Whether decoding had to substitute U+FFFD for malformed sequences.
+    pub had_errors: bool,
+}
+
+/// An encoding identified from a leading byte-order-mark; [`detect_bom`]
+/// returns it together with how many bytes the mark occupies.
+enum Bom {
+    /// An `encoding_rs`-supported encoding.
+    Supported(&'static Encoding),
+    /// UTF-32, little-endian. `encoding_rs` has no UTF-32 codec (it only
+    /// implements the WHATWG encoding standard), so this is decoded by
+    /// hand in [`decode_utf32`].
+    Utf32Le,
+    /// UTF-32, big-endian; see [`Bom::Utf32Le`].
+    Utf32Be,
+}
+
+/// Detect a leading byte-order-mark and the encoding it declares. Checked
+/// longest-prefix-first so the 4-byte UTF-32LE mark (`FF FE 00 00`) isn't
+/// mistaken for the 2-byte UTF-16LE mark (`FF FE`) it starts with.
+fn detect_bom(bytes: &[u8]) -> Option<(Bom, usize)> {
+    if bytes.starts_with(&[0xFF, 0xFE, 0x00, 0x00]) {
+        Some((Bom::Utf32Le, 4))
+    } else if bytes.starts_with(&[0x00, 0x00, 0xFE, 0xFF]) {
+        Some((Bom::Utf32Be, 4))
+    } else if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        Some((Bom::Supported(UTF_8), 3))
+    } else if bytes.starts_with(&[0xFF, 0xFE]) {
+        Some((Bom::Supported(UTF_16LE), 2))
+    } else if bytes.starts_with(&[0xFE, 0xFF]) {
+        Some((Bom::Supported(UTF_16BE), 2))
+    } else {
+        None
+    }
+}
+
+/// Decode `bytes` to UTF-8 text, detecting a BOM first and otherwise
+/// falling back to `default_encoding` (an `encoding_rs` label such as
+/// `"GBK"` or `"windows-1252"`, see `Config::with_default_encoding`), or
+/// plain UTF-8 if that's absent or unrecognized.
+pub fn decode(bytes: &[u8], default_encoding: Option<&str>) -> DecodedContent {
+    if let Some((bom, len)) = detect_bom(bytes) {
+        let body = &bytes[len..];
+        return match bom {
+            Bom::Supported(encoding) => decode_with(encoding, body),
+            Bom::Utf32Le => decode_utf32(body, false),
+            Bom::Utf32Be => decode_utf32(body, true),
+        };
+    }
+
+    let encoding = default_encoding
+        .and_then(|label| Encoding::for_label(label.as_bytes()))
+        .unwrap_or(UTF_8);
+    decode_with(encoding, bytes)
+}
+
+/// Decode `bytes` as `encoding`, letting `encoding_rs` substitute U+FFFD
+/// for any malformed sequences rather than erroring.
+fn decode_with(encoding: &'static Encoding, bytes: &[u8]) -> DecodedContent {
+    let (decoded, _, had_errors) = encoding.decode(bytes);
+    DecodedContent {
+        content: decoded.into_owned(),
+        encoding: encoding.name(),
+        had_errors,
+    }
+}
+
+/// Decode 4-byte UTF-32 code units by hand, since `encoding_rs` doesn't
+/// implement UTF-32. A truncated trailing unit or an out-of-range code
+/// point each count as an error and are replaced with U+FFFD, mirroring
+/// `encoding_rs`'s own error handling.
+fn decode_utf32(bytes: &[u8], big_endian: bool) -> DecodedContent {
+    let mut content = String::with_capacity(bytes.len() / 4);
+    let mut had_errors = false;
+
+    for chunk in bytes.chunks(4) {
+        let code = match *chunk {
+            [a, b, c, d] if big_endian => u32::from_be_bytes([a, b, c, d]),
+            [a, b, c, d] => u32::from_le_bytes([a, b, c, d]),
+            _ => {
+                content.push('\u{FFFD}');
+                had_errors = true;
+                break;
+            }
+        };
+        match char::from_u32(code) {
+            Some(c) => content.push(c),
+            None => {
+                content.push('\u{FFFD}');
+                had_errors = true;
+            }
+        }
+    }
+
+    DecodedContent {
+        content,
+        encoding: if big_endian { "UTF-32BE" } else { "UTF-32LE" },
+        had_errors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_ascii_defaults_to_utf8() {
+        let decoded = decode(b"hello world", None);
+        assert_eq!(decoded.content, "hello world");
+        assert_eq!(decoded.encoding, "UTF-8");
+        assert!(!decoded.had_errors);
+    }
+
+    #[test]
+    fn test_utf8_bom_is_stripped() {
+        let mut bytes = vec![0xEF, 0xBB, 0xBF];
+        bytes.extend_from_slice("caf\u{e9}".as_bytes());
+        let decoded = decode(&bytes, None);
+        assert_eq!(decoded.content, "caf\u{e9}");
+        assert_eq!(decoded.encoding, "UTF-8");
+    }
+
+    #[test]
+    fn test_utf16le_bom_is_transcoded() {
+        let mut bytes = vec![0xFF, 0xFE];
+        for unit in "hi".encode_utf16() {
+            bytes.extend_from_slice(&unit.to_le_bytes());
+        }
+        let decoded = decode(&bytes, None);
+        assert_eq!(decoded.content, "hi");
+        assert_eq!(decoded.encoding, "UTF-16LE");
+        assert!(!decoded.had_errors);
+    }
+
+    #[test]
+    fn test_utf16be_bom_is_transcoded() {
+        let mut bytes = vec![0xFE, 0xFF];
+        for unit in "hi".encode_utf16() {
+            bytes.extend_from_slice(&unit.to_be_bytes());
+        }
+        let decoded = decode(&bytes, None);
+        assert_eq!(decoded.content, "hi");
+        assert_eq!(decoded.encoding, "UTF-16BE");
+    }
+
+    #[test]
+    fn test_utf32le_bom_is_decoded_by_hand() {
+        let mut bytes = vec![0xFF, 0xFE, 0x00, 0x00];
+        for c in "hi".chars() {
+            bytes.extend_from_slice(&(c as u32).to_le_bytes());
+        }
+        let decoded = decode(&bytes, None);
+        assert_eq!(decoded.content, "hi");
+        assert_eq!(decoded.encoding, "UTF-32LE");
+        assert!(!decoded.had_errors);
+    }
+
+    #[test]
+    fn test_default_encoding_applies_without_bom() {
+        // 0xE9 is "\u{e9}" (e-acute) in windows-1252 but invalid as a lone
+        // UTF-8 continuation byte.
+        let bytes = vec![b'c', b'a', b'f', 0xE9];
+        let decoded = decode(&bytes, Some("windows-1252"));
+        assert_eq!(decoded.content, "caf\u{e9}");
+        assert_eq!(decoded.encoding, "windows-1252");
+        assert!(!decoded.had_errors);
+    }
+
+    #[test]
+    fn test_invalid_utf8_without_default_falls_back_to_replacement() {
+        let bytes = vec![b'a', 0xFF, b'b'];
+        let decoded = decode(&bytes, None);
+        assert_eq!(decoded.encoding, "UTF-8");
+        assert!(decoded.had_errors);
+        assert!(decoded.content.contains('\u{FFFD}'));
+    }
+}
@@ -2,21 +2,60 @@ use std::path::{Path, PathBuf};
 use std::fmt;
 use thiserror::Error;
 use std::error::Error as StdError;
+use serde::Serialize;
+
+/// The kind of filesystem operation that failed, attached to
+/// [`ArchiveError::Io`] so a user sees exactly which operation on which path
+/// broke instead of a generic "I/O error".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IoOp {
+    /// Opening a file for reading
+    OpenFile,
+    /// Creating a file for writing
+    CreateFile,
+    /// Reading a file's contents
+    ReadFile,
+    /// Writing a file's contents
+    WriteFile,
+    /// Reading a file or directory's metadata
+    ReadMetadata,
+    /// Reading a directory's entries
+    ReadDir,
+    /// Flushing a writer
+    Flush,
+}
+
+impl fmt::Display for IoOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verb = match self {
+            IoOp::OpenFile => "open file",
+            IoOp::CreateFile => "create file",
+            IoOp::ReadFile => "read file",
+            IoOp::WriteFile => "write file",
+            IoOp::ReadMetadata => "read metadata for",
+            IoOp::ReadDir => "read directory",
+            IoOp::Flush => "flush",
+        };
+        f.write_str(verb)
+    }
+}
 
 /// Main error type for the archive-to-txt library
-/// 
+///
 /// This error type provides detailed information about what went wrong during
 /// the archiving process, with support for chaining multiple errors together.
 #[derive(Debug, Error)]
 pub enum ArchiveError {
     /// I/O operation failed
-    #[error("I/O error: {context}")]
+    #[error("failed to {op} `{}`: {source}", path.display())]
     Io {
         /// The underlying I/O error
         #[source]
         source: std::io::Error,
-        /// Context about where the error occurred
-        context: String,
+        /// The operation that was being attempted
+        op: IoOp,
+        /// The path the operation was being attempted on
+        path: PathBuf,
     },
 
     /// Directory walking error
@@ -30,7 +69,7 @@ pub enum ArchiveError {
     },
 
     /// File exceeds maximum allowed size
-    #[error("File '{path:?}' exceeds maximum size of {} bytes (was {} bytes)", max_size, actual_size)]
+    #[error("File '{path:?}' exceeds maximum size of {} bytes (was {} bytes){}", max_size, actual_size, hint_suffix(hint))]
     FileTooLarge {
         /// Path to the file that's too large
         path: PathBuf,
@@ -38,6 +77,10 @@ pub enum ArchiveError {
         max_size: u64,
         /// Actual file size in bytes
         actual_size: u64,
+        /// Actionable suggestion rendered on its own indented line after
+        /// the message, e.g. "raise the limit with `--max-file-size
+        /// <BYTES>` or exclude this path".
+        hint: Option<String>,
     },
 
     /// Invalid or inaccessible path
@@ -69,8 +112,14 @@ pub enum ArchiveError {
     Config(String),
 
     /// Pattern matching error
-    #[error("Pattern error: {0}")]
-    Pattern(String),
+    #[error("Pattern error: {message}{}", hint_suffix(hint))]
+    Pattern {
+        /// The underlying pattern-matching failure message
+        message: String,
+        /// Actionable suggestion rendered on its own indented line after
+        /// the message, e.g. the correct glob syntax.
+        hint: Option<String>,
+    },
 
     /// Multiple errors occurred
     #[error("Multiple errors occurred:
@@ -88,15 +137,180 @@ pub enum ArchiveError {
 /// A specialized `Result` type for archive operations
 pub type Result<T> = std::result::Result<T, ArchiveError>;
 
+/// Render an optional actionable suggestion as an indented line appended
+/// after an error's main message, for the `ArchiveError` variants that
+/// carry a `hint`; renders as an empty string when there's no hint, so it
+/// splices straight into a `#[error(...)]` format string.
+fn hint_suffix(hint: &Option<String>) -> String {
+    match hint {
+        Some(h) => format!("\n  hint: {h}"),
+        None => String::new(),
+    }
+}
+
+/// A `Serialize`-able, stable-schema rendering of an [`ArchiveError`], for
+/// `--error-format json`. `ArchiveError` itself can't derive `Serialize`
+/// since it wraps non-serde sources (`io::Error`, `walkdir::Error`,
+/// `git2::Error`), so this is a manually-built DTO produced by
+/// [`ArchiveError::to_report`].
+#[derive(Debug, Default, Serialize)]
+pub struct ErrorReport {
+    /// Stable machine-readable error category, e.g. `"file_too_large"`.
+    pub code: &'static str,
+    /// Human-readable message, identical to the `Display` rendering.
+    pub message: String,
+    /// The path the error concerns, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// Maximum allowed size in bytes, for `FileTooLarge`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<u64>,
+    /// Actual size in bytes, for `FileTooLarge`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual_size: Option<u64>,
+    /// Why the path was rejected, for `InvalidPath`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Nested reports, for `Multiple`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<ErrorReport>,
+}
+
 impl ArchiveError {
-    /// Create a new I/O error with context
-    pub fn io_error(source: std::io::Error, context: impl Into<String>) -> Self {
-        ArchiveError::Io {
-            source,
-            context: context.into(),
+    /// Map this error to a conventional `sysexits.h`-style process exit
+    /// code, so a shell script driving the `archive-to-txt` CLI can branch
+    /// on the failure category instead of parsing stderr text.
+    ///
+    /// `Multiple` has no single underlying failure, so it returns the
+    /// numerically highest (and so most severe, by `sysexits.h`'s own
+    /// ordering) code among its contained errors.
+    pub fn exit_code(&self) -> i32 {
+        const EX_USAGE: i32 = 64;
+        const EX_DATAERR: i32 = 65;
+        const EX_NOINPUT: i32 = 66;
+        const EX_SOFTWARE: i32 = 70;
+        const EX_IOERR: i32 = 74;
+        const EX_CONFIG: i32 = 78;
+
+        match self {
+            ArchiveError::Io { .. } => EX_IOERR,
+            ArchiveError::WalkDir { .. } | ArchiveError::InvalidPath { .. } => EX_NOINPUT,
+            ArchiveError::FileTooLarge { .. } => EX_DATAERR,
+            #[cfg(feature = "git")]
+            ArchiveError::Git { .. } => EX_SOFTWARE,
+            ArchiveError::Serialization(_) | ArchiveError::Other(_) => EX_SOFTWARE,
+            ArchiveError::Config(_) => EX_CONFIG,
+            ArchiveError::Pattern { .. } => EX_USAGE,
+            ArchiveError::Multiple { errors } => {
+                errors.iter().map(ArchiveError::exit_code).max().unwrap_or(EX_SOFTWARE)
+            }
         }
     }
 
+
+    /// Render this error as a [`ErrorReport`] DTO, for `--error-format json`
+    /// output that CI systems and editors can consume structurally instead
+    /// of scraping the `Display` text.
+    pub fn to_report(&self) -> ErrorReport {
+        let message = self.to_string();
+        match self {
+            ArchiveError::Io { path, .. } => ErrorReport {
+                code: "io",
+                message,
+                path: Some(path.clone()),
+                ..Default::default()
+            },
+            ArchiveError::WalkDir { path, .. } => ErrorReport {
+                code: "walk_dir",
+                message,
+                path: Some(path.clone()),
+                ..Default::default()
+            },
+            ArchiveError::FileTooLarge { path, max_size, actual_size, .. } => ErrorReport {
+                code: "file_too_large",
+                message,
+                path: Some(path.clone()),
+                max_size: Some(*max_size),
+                actual_size: Some(*actual_size),
+                ..Default::default()
+            },
+            ArchiveError::InvalidPath { path, reason } => ErrorReport {
+                code: "invalid_path",
+                message,
+                path: Some(path.clone()),
+                reason: Some(reason.clone()),
+                ..Default::default()
+            },
+            #[cfg(feature = "git")]
+            ArchiveError::Git { .. } => ErrorReport {
+                code: "git",
+                message,
+                ..Default::default()
+            },
+            ArchiveError::Serialization(_) => ErrorReport {
+                code: "serialization",
+                message,
+                ..Default::default()
+            },
+            ArchiveError::Config(_) => ErrorReport {
+                code: "config",
+                message,
+                ..Default::default()
+            },
+            ArchiveError::Pattern { .. } => ErrorReport {
+                code: "pattern",
+                message,
+                ..Default::default()
+            },
+            ArchiveError::Multiple { errors } => ErrorReport {
+                code: "multiple",
+                message,
+                errors: errors.iter().map(ArchiveError::to_report).collect(),
+                ..Default::default()
+            },
+            ArchiveError::Other(_) => ErrorReport {
+                code: "other",
+                message,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Create a new "failed to open file" error
+    pub fn open(source: std::io::Error, path: impl Into<PathBuf>) -> Self {
+        ArchiveError::Io { source, op: IoOp::OpenFile, path: path.into() }
+    }
+
+    /// Create a new "failed to create file" error
+    pub fn create(source: std::io::Error, path: impl Into<PathBuf>) -> Self {
+        ArchiveError::Io { source, op: IoOp::CreateFile, path: path.into() }
+    }
+
+    /// Create a new "failed to read file" error
+    pub fn read(source: std::io::Error, path: impl Into<PathBuf>) -> Self {
+        ArchiveError::Io { source, op: IoOp::ReadFile, path: path.into() }
+    }
+
+    /// Create a new "failed to write file" error
+    pub fn write(source: std::io::Error, path: impl Into<PathBuf>) -> Self {
+        ArchiveError::Io { source, op: IoOp::WriteFile, path: path.into() }
+    }
+
+    /// Create a new "failed to read metadata" error
+    pub fn read_metadata(source: std::io::Error, path: impl Into<PathBuf>) -> Self {
+        ArchiveError::Io { source, op: IoOp::ReadMetadata, path: path.into() }
+    }
+
+    /// Create a new "failed to read directory" error
+    pub fn read_dir(source: std::io::Error, path: impl Into<PathBuf>) -> Self {
+        ArchiveError::Io { source, op: IoOp::ReadDir, path: path.into() }
+    }
+
+    /// Create a new "failed to flush" error
+    pub fn flush(source: std::io::Error, path: impl Into<PathBuf>) -> Self {
+        ArchiveError::Io { source, op: IoOp::Flush, path: path.into() }
+    }
+
     /// Create a new path validation error
     pub fn invalid_path(path: impl AsRef<Path>, reason: impl Into<String>) -> Self {
         ArchiveError::InvalidPath {
@@ -111,9 +325,15 @@ impl ArchiveError {
             path: path.as_ref().to_path_buf(),
             max_size,
             actual_size,
+            hint: None,
         }
     }
 
+    /// Create a new pattern-matching error
+    pub fn pattern(message: impl Into<String>) -> Self {
+        ArchiveError::Pattern { message: message.into(), hint: None }
+    }
+
     /// Convert multiple errors into a single `Multiple` error
     pub fn multiple(errors: impl IntoIterator<Item = Self>) -> Self {
         let errors: Vec<_> = errors.into_iter().collect();
@@ -123,6 +343,19 @@ impl ArchiveError {
             ArchiveError::Multiple { errors }
         }
     }
+
+    /// Attach an actionable suggestion, rendered on its own indented line
+    /// after the main message. A no-op on variants that don't carry a
+    /// `hint`.
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        match &mut self {
+            ArchiveError::FileTooLarge { hint: h, .. } | ArchiveError::Pattern { hint: h, .. } => {
+                *h = Some(hint.into());
+            }
+            _ => {}
+        }
+        self
+    }
 }
 
 // Conversion from std::io::Error to ArchiveError
@@ -130,7 +363,8 @@ impl From<std::io::Error> for ArchiveError {
     fn from(err: std::io::Error) -> Self {
         ArchiveError::Io {
             source: err,
-            context: "I/O operation failed".to_string(),
+            op: IoOp::OpenFile,
+            path: PathBuf::new(),
         }
     }
 }
@@ -167,7 +401,7 @@ impl From<glob::GlobError> for ArchiveError {
 impl From<anyhow::Error> for ArchiveError {
     fn from(err: anyhow::Error) -> Self {
         if let Some(io_err) = err.downcast_ref::<std::io::Error>() {
-            return ArchiveError::io_error(io_err.clone(), "I/O operation failed");
+            return ArchiveError::open(io_err.kind().into(), PathBuf::new());
         }
         ArchiveError::Other(err.to_string())
     }
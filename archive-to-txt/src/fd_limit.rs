@@ -0,0 +1,25 @@
+//! Best-effort file-descriptor soft-limit raise, so a wide Rayon
+//! fan-out in `process_files_parallel` doesn't start hitting `EMFILE`
+//! on platforms with a low default (256 on macOS). Gated behind
+//! `Config::with_raise_fd_limit`, on by default.
+
+use log::debug;
+
+/// Raise the process's soft `RLIMIT_NOFILE` toward its hard limit
+/// (clamped to `OPEN_MAX` on macOS by the `rlimit` crate). Best-effort:
+/// failures are only logged at debug level, since a low descriptor
+/// limit risks slower/failed reads under heavy parallel fan-out, not
+/// incorrect output, and the call is a no-op on unsupported platforms.
+pub fn raise_soft_limit() {
+    match rlimit::Resource::NOFILE.get() {
+        Ok((soft, hard)) => match rlimit::increase_nofile_limit(hard) {
+            Ok(new_soft) => {
+                debug!("Raised RLIMIT_NOFILE soft limit from {soft} to {new_soft} (hard limit {hard})");
+            }
+            Err(e) => {
+                debug!("Could not raise RLIMIT_NOFILE soft limit (currently {soft}/{hard}): {e}");
+            }
+        },
+        Err(e) => debug!("Could not query RLIMIT_NOFILE: {e}"),
+    }
+}
@@ -0,0 +1,116 @@
+//! Named file-type presets, modeled on ripgrep's `--type` system: a short
+//! name like `rust` or `web` expands to the glob patterns that define it,
+//! so [`crate::filter::FileFilter::with_types`] gives callers a concise
+//! `--type rust` style filter instead of hand-writing extension sets.
+//! Kept in its own module, sorted by name, so the large default table
+//! stays easy to scan and maintain as new types are added.
+
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+/// One builtin type's name and the glob patterns that define it.
+struct TypeDef {
+    name: &'static str,
+    globs: &'static [&'static str],
+}
+
+/// The builtin type table, sorted by name.
+const BUILTIN_TYPES: &[TypeDef] = &[
+    TypeDef { name: "bazel", globs: &["*.bazel", "*.bzl", "BUILD", "WORKSPACE", "MODULE.bazel"] },
+    TypeDef { name: "c", globs: &["*.c", "*.h"] },
+    TypeDef { name: "cpp", globs: &["*.cpp", "*.cc", "*.cxx", "*.hpp", "*.hh"] },
+    TypeDef { name: "go", globs: &["*.go"] },
+    TypeDef { name: "java", globs: &["*.java"] },
+    TypeDef { name: "js", globs: &["*.js", "*.jsx", "*.mjs", "*.cjs"] },
+    TypeDef { name: "json", globs: &["*.json"] },
+    TypeDef { name: "markdown", globs: &["*.md", "*.markdown"] },
+    TypeDef { name: "python", globs: &["*.py", "*.pyi"] },
+    TypeDef { name: "ruby", globs: &["*.rb"] },
+    TypeDef { name: "rust", globs: &["*.rs"] },
+    TypeDef { name: "toml", globs: &["*.toml"] },
+    TypeDef { name: "ts", globs: &["*.ts", "*.tsx"] },
+    TypeDef { name: "web", globs: &["*.html", "*.css", "*.js", "*.ts"] },
+    TypeDef { name: "yaml", globs: &["*.yaml", "*.yml"] },
+];
+
+/// An error produced while resolving named type filters.
+#[derive(Debug, Error)]
+pub enum TypeError {
+    /// No builtin or custom type is registered under this name.
+    #[error("unknown file type '{0}'")]
+    UnknownType(String),
+
+    /// One of the type's glob patterns failed to compile.
+    #[error("{0}")]
+    Glob(#[from] globset::Error),
+}
+
+/// A lookup table of type name -> glob patterns, seeded with the builtin
+/// table and extensible with [`TypeRegistry::register`].
+#[derive(Debug, Clone)]
+pub struct TypeRegistry {
+    types: HashMap<String, Vec<String>>,
+}
+
+impl TypeRegistry {
+    /// A registry containing only the builtin types.
+    pub fn with_builtins() -> Self {
+        let types = BUILTIN_TYPES
+            .iter()
+            .map(|def| {
+                (
+                    def.name.to_string(),
+                    def.globs.iter().map(|glob| glob.to_string()).collect(),
+                )
+            })
+            .collect();
+        Self { types }
+    }
+
+    /// Register (or override) a type's glob patterns.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        globs: impl IntoIterator<Item = impl Into<String>>,
+    ) {
+        self.types
+            .insert(name.into(), globs.into_iter().map(Into::into).collect());
+    }
+
+    /// The glob patterns registered under `name`, if any.
+    pub fn globs_for(&self, name: &str) -> Option<&[String]> {
+        self.types.get(name).map(Vec::as_slice)
+    }
+}
+
+impl Default for TypeRegistry {
+    fn default() -> Self {
+        Self::with_builtins()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_types_are_looked_up_by_name() {
+        let registry = TypeRegistry::with_builtins();
+        assert_eq!(registry.globs_for("rust"), Some(&["*.rs".to_string()][..]));
+        assert!(registry.globs_for("not-a-real-type").is_none());
+    }
+
+    #[test]
+    fn custom_types_can_be_registered_and_override_builtins() {
+        let mut registry = TypeRegistry::with_builtins();
+        registry.register("rust", ["*.rs", "*.rs.in"]);
+        assert_eq!(
+            registry.globs_for("rust"),
+            Some(&["*.rs".to_string(), "*.rs.in".to_string()][..])
+        );
+
+        registry.register("proto", ["*.proto"]);
+        assert_eq!(registry.globs_for("proto"), Some(&["*.proto".to_string()][..]));
+    }
+}
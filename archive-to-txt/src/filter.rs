@@ -6,16 +6,180 @@
 use std::path::{Path, PathBuf};
 use std::collections::HashSet;
 
-use globset::{Glob, GlobSet, GlobSetBuilder};
+use globset::{Glob, GlobMatcher, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
 use log::debug;
+use regex::Regex;
+use thiserror::Error;
+use walkdir::WalkDir;
+
+use crate::filetype::{TypeError, TypeRegistry};
+
+/// A single pattern, either a compiled glob or a raw regular expression
+/// -- the Mercurial/`ignore`-style `glob:`/`regex:` syntax tag a raw
+/// include/exclude string carries decides which. `glob:` is implied
+/// when a pattern has no recognized prefix, so every pre-existing caller
+/// keeps working unchanged.
+#[derive(Debug, Clone)]
+enum Pattern {
+    Glob(GlobMatcher),
+    Regex(Regex),
+}
+
+impl Pattern {
+    /// Parse `raw`'s optional `glob:`/`regex:` prefix and compile the
+    /// remainder accordingly.
+    fn parse(raw: &str) -> Result<Self, PatternError> {
+        if let Some(expr) = raw.strip_prefix("regex:") {
+            Regex::new(expr)
+                .map(Pattern::Regex)
+                .map_err(|source| PatternError::Regex { pattern: expr.to_string(), source })
+        } else {
+            let glob_str = raw.strip_prefix("glob:").unwrap_or(raw);
+            Glob::new(glob_str)
+                .map(|glob| Pattern::Glob(glob.compile_matcher()))
+                .map_err(|source| PatternError::Glob { pattern: glob_str.to_string(), source })
+        }
+    }
+
+    fn is_match(&self, path: &Path) -> bool {
+        match self {
+            Pattern::Glob(matcher) => matcher.is_match(path),
+            Pattern::Regex(regex) => path.to_str().is_some_and(|s| regex.is_match(s)),
+        }
+    }
+}
+
+/// An error produced while compiling an include/exclude pattern, naming
+/// both the offending pattern and which syntax it was compiled as.
+#[derive(Debug, Error)]
+pub enum PatternError {
+    #[error("invalid glob pattern '{pattern}': {source}")]
+    Glob {
+        pattern: String,
+        #[source]
+        source: globset::Error,
+    },
+
+    #[error("invalid regex pattern '{pattern}': {source}")]
+    Regex {
+        pattern: String,
+        #[source]
+        source: regex::Error,
+    },
+}
 
 /// A filter for including/excluding files based on patterns and extensions.
+///
+/// `exclude_patterns` is an ordered list of rules rather than a combined
+/// `GlobSet`, so a pattern prefixed with `!` can whitelist a path a
+/// broader, earlier exclude pattern already rejected -- the same
+/// `.gitignore` negation convention (e.g. exclude `**/*.log` but keep
+/// `**/important.log`). `include_patterns` keeps its existing allowlist
+/// semantics: when set, a path must match at least one of them (checked
+/// before the exclude rules) or it's dropped regardless of negation.
+///
+/// Both lists accept an optional Mercurial-style `glob:`/`regex:` syntax
+/// tag on each entry (`glob:` is implied when absent), so a pattern
+/// globs can't express -- alternation, anchored backreferences,
+/// case-insensitive classes -- can be written as `regex:...` instead;
+/// see [`Pattern`].
 #[derive(Debug, Clone)]
 pub struct FileFilter {
-    include_patterns: Option<GlobSet>,
-    exclude_patterns: Option<GlobSet>,
+    /// A path must match at least one of these (if any are set), each
+    /// optionally tagged `glob:`/`regex:` (see [`Pattern`]).
+    include_patterns: Option<Vec<Pattern>>,
+    /// The literal path prefix before the first glob metacharacter in
+    /// each of `include_patterns`' raw *glob* patterns (e.g.
+    /// `src/**/*.rs` -> `src`), used by `walk` to skip a directory that
+    /// falls outside every prefix and so couldn't possibly contain a
+    /// match. A pattern with no directory component in its literal
+    /// prefix (e.g. `*.rs`), or a `regex:`-tagged one (whose syntax
+    /// isn't glob metacharacters at all), contributes an empty path,
+    /// which matches everything.
+    include_prefixes: Vec<PathBuf>,
+    /// A second, independent "must match at least one" allowlist gate
+    /// populated by `with_types`'s `select_types`, ANDed with
+    /// `include_patterns` when both are set.
+    type_select_patterns: Option<GlobSet>,
+    /// Ordered `(pattern, is_negated)` rules, evaluated in order with a
+    /// mutable `excluded` flag: a normal pattern's match sets it `true`,
+    /// a `!`-prefixed one's sets it `false` -- so the *last* matching
+    /// rule decides the path's fate, exactly like `.gitignore`.
+    exclude_rules: Vec<(Pattern, bool)>,
+    /// Real ignore files (`.gitignore`, `.ignore`, `.llmignore`, ...)
+    /// discovered under a root via `with_ignore_files`, shallowest
+    /// first, so a deeper file's rules are evaluated after (and so
+    /// override) an ancestor's under the same last-match-wins
+    /// resolution as `exclude_rules`. Empty unless opted into.
+    gitignore_layers: Vec<GitignoreLayer>,
     allowed_extensions: Option<HashSet<String>>,
     max_file_size: Option<u64>,
+    /// Whether `walk` should descend into symlinked directories. Off by
+    /// default; when on, `walk` relies on `WalkDir`'s own loop
+    /// detection (which tracks each ancestor directory's identity --
+    /// device and inode on Unix, canonicalized path elsewhere -- the
+    /// same check `tree::generate_tree` already leans on) to recognize
+    /// a symlink pointing back at an ancestor and skip it instead of
+    /// recursing forever. A followed symlink whose target lies under the
+    /// walk root is not descended into either, so each file under the
+    /// root is returned once, under its real path.
+    follow_symlinks: bool,
+    /// First-party paths the user is actively working on, set via
+    /// `with_member_roots`. `None` (the default) treats every path as a
+    /// member, i.e. applies no extra restriction; once set, `is_member`
+    /// and the non-member test/example/bench exclusion in `is_included`
+    /// kick in for anything outside these roots.
+    member_roots: Option<Vec<PathBuf>>,
+    /// Whether `is_included` lets files `is_test_file` recognizes
+    /// through. Off by default, so test code is excluded out of the
+    /// box; set via `with_include_tests` for the full picture.
+    include_tests: bool,
+    /// An alternate, fully ordered `(pattern, is_included)` rule chain
+    /// built by `with_override_rules`, evaluated by
+    /// `is_included_with_overrides` instead of the independent
+    /// include-gate/exclude-rules/etc. checks `is_included` runs.
+    /// Unlike `exclude_rules` (exclude-only, unmatched defaults to
+    /// "not excluded") or `override_rules::OverrideRules` (whose
+    /// `has_whitelist` flag can make an unmatched path default to
+    /// excluded just because the rule chain happens to contain a
+    /// whitelist entry), an unmatched path here always defaults to
+    /// included. Empty unless opted into.
+    override_rules: Vec<(Pattern, bool)>,
+}
+
+/// One `.gitignore` file's compiled rules, tagged with the directory it
+/// lives in (the `Gitignore`'s own matching root). `pub(crate)` so
+/// `tree::generate_tree` can share this layering with `FileFilter`
+/// instead of re-deriving its own gitignore handling.
+#[derive(Debug, Clone)]
+pub(crate) struct GitignoreLayer {
+    root: PathBuf,
+    matcher: Gitignore,
+}
+
+/// Evaluate `layers` against `path` in order starting from `excluded`,
+/// last-match-wins -- the same resolution `FileFilter::is_excluded_by_patterns`
+/// applies to its own `gitignore_layers`, factored out here so
+/// `tree::generate_tree` can reuse it without going through a whole
+/// `FileFilter`. Callers with no prior exclusion state to carry in (e.g.
+/// a plain "is this path gitignored?" check) pass `false`.
+pub(crate) fn is_gitignored(layers: &[GitignoreLayer], path: &Path, is_dir: bool, excluded: bool) -> bool {
+    let mut excluded = excluded;
+    for layer in layers {
+        // A layer only governs paths under its own directory, and a rule
+        // on a parent directory (e.g. `vendor/`) covers everything below it.
+        if !path.starts_with(&layer.root) {
+            continue;
+        }
+        match layer.matcher.matched_path_or_any_parents(path, is_dir) {
+            Match::Ignore(_) => excluded = true,
+            Match::Whitelist(_) => excluded = false,
+            Match::None => {}
+        }
+    }
+    excluded
 }
 
 impl FileFilter {
@@ -25,55 +189,365 @@ impl FileFilter {
         exclude_patterns: Option<Vec<String>>,
         allowed_extensions: Option<HashSet<String>>,
         max_file_size: Option<u64>,
-    ) -> Result<Self, globset::Error> {
-        let include_patterns = if let Some(patterns) = include_patterns {
-            let mut builder = GlobSetBuilder::new();
-            for pattern in patterns {
-                builder.add(Glob::new(&pattern)?);
+    ) -> Result<Self, PatternError> {
+        let (include_patterns, include_prefixes) = if let Some(patterns) = include_patterns {
+            let mut compiled = Vec::with_capacity(patterns.len());
+            let mut prefixes = Vec::with_capacity(patterns.len());
+            for pattern in &patterns {
+                compiled.push(Pattern::parse(pattern)?);
+                prefixes.push(literal_prefix(pattern));
             }
-            Some(builder.build()?)
+            (Some(compiled), prefixes)
         } else {
-            None
+            (None, Vec::new())
         };
 
-        let exclude_patterns = if let Some(patterns) = exclude_patterns {
-            let mut builder = GlobSetBuilder::new();
-            for pattern in patterns {
-                builder.add(Glob::new(&pattern)?);
-            }
-            Some(builder.build()?)
-        } else {
-            None
-        };
+        let exclude_rules = exclude_patterns
+            .unwrap_or_default()
+            .into_iter()
+            .map(|pattern| {
+                let (is_negated, raw) = match pattern.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, pattern.as_str()),
+                };
+                Ok((Pattern::parse(raw)?, is_negated))
+            })
+            .collect::<Result<Vec<_>, PatternError>>()?;
 
         Ok(Self {
             include_patterns,
-            exclude_patterns,
+            include_prefixes,
+            type_select_patterns: None,
+            exclude_rules,
+            gitignore_layers: Vec::new(),
             allowed_extensions,
             max_file_size,
+            follow_symlinks: false,
+            member_roots: None,
+            include_tests: false,
+            override_rules: Vec::new(),
         })
     }
 
+    /// Opt in to `walk` descending into symlinked directories. Off
+    /// (don't follow) by default, matching `Config::follow_links`'s
+    /// default for the same reason: following symlinks by default risks
+    /// an unbounded or cyclic walk over a project the caller didn't
+    /// expect to expand.
+    pub fn with_follow_symlinks(mut self, follow: bool) -> Self {
+        self.follow_symlinks = follow;
+        self
+    }
+
+    /// Distinguish first-party workspace code from vendored/dependency
+    /// directories: once set, anything outside `member_roots` also gets
+    /// its `examples/`, `tests/`, and `benches/` subtrees excluded (a
+    /// dependency's test suite is rarely useful LLM context), while the
+    /// user's own tests under a member root stay included as normal.
+    pub fn with_member_roots(mut self, member_roots: Vec<PathBuf>) -> Self {
+        self.member_roots = Some(member_roots);
+        self
+    }
+
+    /// Whether `path` falls under one of `member_roots`. With no member
+    /// roots configured, every path is considered a member, i.e. this
+    /// feature applies no restriction unless opted into.
+    pub fn is_member<P: AsRef<Path>>(&self, path: P) -> bool {
+        match &self.member_roots {
+            Some(roots) => roots.iter().any(|root| path.as_ref().starts_with(root)),
+            None => true,
+        }
+    }
+
+    /// Let test files through `is_included` instead of excluding them.
+    /// Off by default, matching the old literal `**/*_test.rs`-style
+    /// globs' effect.
+    pub fn with_include_tests(mut self, include_tests: bool) -> Self {
+        self.include_tests = include_tests;
+        self
+    }
+
+    /// Whether `path`'s final component looks like a test file, across
+    /// the naming conventions a handful of ecosystems actually use:
+    /// `_test`/`.test`/`_spec`/`.spec` suffixes before a recognized code
+    /// extension (covering e.g. `foo_test.go`, `foo.test.tsx`,
+    /// `foo_spec.rb`), and bare `test.ts`/`spec.js`-style entry files
+    /// that hold a whole suite rather than testing one module. Driving
+    /// `is_included`'s test exclusion from this classifier instead of a
+    /// fixed glob list means a new suffix variant only needs adding
+    /// here, not threading through every caller's pattern list.
+    pub fn is_test_file<P: AsRef<Path>>(path: P) -> bool {
+        const TEST_SUFFIXES: &[&str] = &["_test", ".test", "_spec", ".spec"];
+        const CODE_EXTENSIONS: &[&str] =
+            &["ts", "tsx", "js", "jsx", "mjs", "cjs", "rs", "go", "py", "rb"];
+
+        let Some(file_name) = path.as_ref().file_name().and_then(|n| n.to_str()) else {
+            return false;
+        };
+        let Some(dot) = file_name.rfind('.') else {
+            return false;
+        };
+        let (stem, ext) = (&file_name[..dot], &file_name[dot + 1..]);
+        if !CODE_EXTENSIONS.contains(&ext) {
+            return false;
+        }
+
+        stem == "test" || stem == "spec" || TEST_SUFFIXES.iter().any(|suffix| stem.ends_with(suffix))
+    }
+
+    /// Build the ordered override-rule chain `is_included_with_overrides`
+    /// evaluates: `default_llm_ignore_patterns` as the lowest-priority
+    /// layer, then this filter's own `exclude_patterns` (as already
+    /// compiled into `exclude_rules`), then `rules` on top as the
+    /// highest-priority, most specific layer. Each entry in `rules`
+    /// defaults to re-including anything it matches, the same as a
+    /// plain `include` pattern would; prefix one with `!` to have it
+    /// exclude instead, so a broad re-include can still carve out its
+    /// own narrower exception.
+    pub fn with_override_rules(mut self, rules: Vec<String>) -> Result<Self, PatternError> {
+        let defaults = Self::default_llm_ignore_patterns();
+        let mut combined =
+            Vec::with_capacity(defaults.len() + self.exclude_rules.len() + rules.len());
+        for pattern in defaults.iter().copied() {
+            combined.push((Pattern::parse(pattern)?, false));
+        }
+        combined.extend(self.exclude_rules.iter().cloned());
+        for raw in &rules {
+            let (is_included, pattern_str) = match raw.strip_prefix('!') {
+                Some(rest) => (false, rest),
+                None => (true, raw.as_str()),
+            };
+            combined.push((Pattern::parse(pattern_str)?, is_included));
+        }
+
+        self.override_rules = combined;
+        Ok(self)
+    }
+
+    /// Walk the `with_override_rules` chain in order and return the
+    /// *last* matching rule's verdict, defaulting to included when
+    /// nothing in the chain matches `path` at all -- unlike
+    /// `is_included`'s independent include-gate/exclude-rules/etc.
+    /// checks, this is a single unified precedence model, for callers
+    /// that opted into `with_override_rules`.
+    pub fn is_included_with_overrides<P: AsRef<Path>>(&self, path: P) -> bool {
+        let path = path.as_ref();
+        self.override_rules
+            .iter()
+            .rev()
+            .find(|(pattern, _)| pattern.is_match(path))
+            .map_or(true, |(_, is_included)| *is_included)
+    }
+
+    /// Opt in to honoring real ignore files found anywhere under `root`,
+    /// layered so a deeper file's rules take precedence over an
+    /// ancestor's -- the same hierarchy a real `git` checkout respects.
+    /// Discovers [`DEFAULT_IGNORE_BASENAMES`] (`.gitignore`, `.ignore`,
+    /// `.llmignore`) plus any `extra_basenames` the caller wants
+    /// recognized (e.g. a project-specific `.promptignore`). Patterns
+    /// from `exclude_patterns` are still evaluated too; this is
+    /// additive, not a replacement.
+    pub fn with_ignore_files(mut self, root: impl AsRef<Path>, extra_basenames: &[&str]) -> Self {
+        let mut basenames: Vec<&str> = DEFAULT_IGNORE_BASENAMES.to_vec();
+        basenames.extend_from_slice(extra_basenames);
+        self.gitignore_layers = load_ignore_layers(root.as_ref(), &basenames);
+        self
+    }
+
+    /// Expand ripgrep-style `--type`/`--type-not` names against
+    /// `registry` into glob filters: `select_types` become a second
+    /// allowlist gate (a path must match at least one, alongside
+    /// `include_patterns` if also set), and `negate_types` are appended
+    /// as plain exclude rules, evaluated in the same ordered,
+    /// last-match-wins pass as `exclude_patterns`.
+    pub fn with_types(
+        mut self,
+        registry: &TypeRegistry,
+        select_types: &[String],
+        negate_types: &[String],
+    ) -> Result<Self, TypeError> {
+        if !select_types.is_empty() {
+            let mut builder = GlobSetBuilder::new();
+            for name in select_types {
+                let globs = registry
+                    .globs_for(name)
+                    .ok_or_else(|| TypeError::UnknownType(name.clone()))?;
+                for glob in globs {
+                    builder.add(Glob::new(glob)?);
+                }
+            }
+            self.type_select_patterns = Some(builder.build()?);
+        }
+
+        for name in negate_types {
+            let globs = registry
+                .globs_for(name)
+                .ok_or_else(|| TypeError::UnknownType(name.clone()))?;
+            for glob in globs {
+                self.exclude_rules.push((Pattern::Glob(Glob::new(glob)?.compile_matcher()), false));
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Evaluate `exclude_rules` and `gitignore_layers` against `path`,
+    /// in that order, under the shared last-match-wins `excluded` flag.
+    /// `is_dir` is the caller's answer when already known (e.g. `walk`
+    /// pruning a directory entry); `None` means "find out with a
+    /// `path.is_dir()` stat, but only if a gitignore layer is actually
+    /// in play" so the common case avoids an extra syscall per file.
+    fn is_excluded_by_patterns(&self, path: &Path, is_dir: Option<bool>) -> bool {
+        let mut excluded = false;
+        for (pattern, is_negated) in &self.exclude_rules {
+            if pattern.is_match(path) {
+                excluded = !is_negated;
+            }
+        }
+
+        if !self.gitignore_layers.is_empty() {
+            let is_dir = is_dir.unwrap_or_else(|| path.is_dir());
+            let before = excluded;
+            excluded = is_gitignored(&self.gitignore_layers, path, is_dir, excluded);
+            if excluded != before {
+                debug!("{} matched a discovered ignore file", path.display());
+            }
+        }
+
+        excluded
+    }
+
+    /// Walk `root`, pruning whole subtrees this filter's pattern-based
+    /// rules (`exclude_rules`/`.gitignore` layers, and the literal
+    /// prefixes of `include_patterns`) rule out, rather than descending
+    /// into them and filtering file-by-file. Returns every included
+    /// file path found; directories themselves are never returned.
+    pub fn walk(&self, root: impl AsRef<Path>) -> Vec<PathBuf> {
+        let root = root.as_ref();
+        let mut results = Vec::new();
+        let canonical_root = if self.follow_symlinks { root.canonicalize().ok() } else { None };
+        let mut walker = WalkDir::new(root)
+            .follow_links(self.follow_symlinks)
+            .into_iter();
+
+        while let Some(entry) = walker.next() {
+            let entry = match entry {
+                Ok(entry) => entry,
+                // A symlink pointing back at one of its own ancestors;
+                // `follow_links(true)` makes `WalkDir` detect this via
+                // the same device+inode/canonical-path ancestry check
+                // described on `follow_symlinks`, rather than erroring
+                // the whole walk out.
+                Err(err) if err.loop_ancestor().is_some() => {
+                    debug!("Skipping symlink cycle at {:?}", err.path());
+                    continue;
+                }
+                Err(_) => continue,
+            };
+            let path = entry.path();
+
+            if entry.file_type().is_dir() {
+                if path != root && entry.path_is_symlink() {
+                    if let (Some(canonical_root), Ok(target)) = (&canonical_root, path.canonicalize()) {
+                        if target.starts_with(canonical_root) {
+                            debug!("Skipping symlink {}: target is walked under its real path", path.display());
+                            walker.skip_current_dir();
+                            continue;
+                        }
+                    }
+                }
+                if path != root && !self.should_descend(root, path) {
+                    debug!("Pruning directory {}: excluded subtree", path.display());
+                    walker.skip_current_dir();
+                }
+                continue;
+            }
+
+            // An unfollowed symlink to a directory is not a file.
+            if entry.path_is_symlink() && path.is_dir() {
+                continue;
+            }
+
+            if self.is_included(path) {
+                results.push(path.to_path_buf());
+            }
+        }
+
+        results
+    }
+
+    /// Whether `walk` should descend into `dir` at all, so a whole
+    /// subtree (e.g. `node_modules/`, `target/`) can be pruned from a
+    /// single directory-level check instead of stat-ing and discarding
+    /// every file beneath it one by one. Returns `false` when either a
+    /// pattern/gitignore rule excludes `dir` outright (mirroring real
+    /// `.gitignore` semantics, where an ignored directory's contents
+    /// aren't reconsidered even if one happens to match a `!`-pattern),
+    /// or `include_patterns` has literal prefixes and none of them could
+    /// plausibly be satisfied by anything under `dir`.
+    pub fn should_descend(&self, root: impl AsRef<Path>, dir: impl AsRef<Path>) -> bool {
+        let dir = dir.as_ref();
+
+        if self.is_excluded_by_patterns(dir, Some(true)) {
+            return false;
+        }
+
+        if !self.include_prefixes.is_empty() {
+            let root = root.as_ref();
+            let relative = dir.strip_prefix(root).unwrap_or(dir);
+            let could_contain_match = self.include_prefixes.iter().any(|prefix| {
+                prefix.as_os_str().is_empty()
+                    || relative.starts_with(prefix)
+                    || prefix.starts_with(relative)
+                    || dir.starts_with(prefix)
+                    || prefix.starts_with(dir)
+            });
+            if !could_contain_match {
+                return false;
+            }
+        }
+
+        true
+    }
+
     /// Check if a file should be included based on the filter criteria.
     pub fn is_included<P: AsRef<Path>>(&self, path: P) -> bool {
         let path = path.as_ref();
-        
+
         // Check against include patterns if any are specified
         if let Some(include_patterns) = &self.include_patterns {
-            if !include_patterns.is_match(path) {
+            if !include_patterns.iter().any(|pattern| pattern.is_match(path)) {
                 debug!("Excluding {}: does not match include patterns", path.display());
                 return false;
             }
         }
 
-        // Check against exclude patterns if any are specified
-        if let Some(exclude_patterns) = &self.exclude_patterns {
-            if exclude_patterns.is_match(path) {
-                debug!("Excluding {}: matches exclude patterns", path.display());
+        // Check against selected `--type` filters, if any
+        if let Some(type_select_patterns) = &self.type_select_patterns {
+            if !type_select_patterns.is_match(path) {
+                debug!("Excluding {}: does not match selected file types", path.display());
                 return false;
             }
         }
 
+        if self.is_excluded_by_patterns(path, None) {
+            debug!("Excluding {}: matches exclude patterns", path.display());
+            return false;
+        }
+
+        // A dependency/vendored path's own tests, examples, and
+        // benchmarks are rarely useful LLM context; the user's own
+        // member-root code is exempt.
+        if self.member_roots.is_some() && !self.is_member(path) && is_test_artifact_path(path) {
+            debug!("Excluding {}: test/example/bench code of a non-member dependency", path.display());
+            return false;
+        }
+
+        if !self.include_tests && Self::is_test_file(path) {
+            debug!("Excluding {}: looks like a test file", path.display());
+            return false;
+        }
+
         // Check file extension if allowed extensions are specified
         if let Some(allowed_exts) = &self.allowed_extensions {
             if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
@@ -268,6 +742,79 @@ impl FileFilter {
     }
 }
 
+/// Ignore-file basenames discovered by default when
+/// [`FileFilter::with_ignore_files`] is enabled, in the order real tools
+/// conventionally layer them: git's own, the `ignore` crate's generic
+/// `.ignore`, and a project-specific `.llmignore` for this tool's own
+/// overrides.
+pub(crate) const DEFAULT_IGNORE_BASENAMES: &[&str] = &[".gitignore", ".ignore", ".llmignore"];
+
+/// Walk `root` for every file whose name is in `basenames` and compile
+/// one layer per directory that has one, ordered shallowest-first by
+/// depth so later entries in the returned `Vec` belong to deeper
+/// directories -- matching `is_included`'s last-match-wins evaluation,
+/// where a later layer's verdict overrides an earlier one's. A
+/// directory with multiple matching basenames (e.g. both `.gitignore`
+/// and `.llmignore`) gets one layer per file, in `basenames` order, so
+/// `.llmignore` still wins ties within the same directory.
+pub(crate) fn load_ignore_layers(root: &Path, basenames: &[&str]) -> Vec<GitignoreLayer> {
+    let mut found: Vec<(usize, usize, PathBuf)> = WalkDir::new(root)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let basename_rank = basenames
+                .iter()
+                .position(|name| entry.file_name() == *name)?;
+            Some((entry.depth(), basename_rank, entry.path().to_path_buf()))
+        })
+        .collect();
+    found.sort_by_key(|(depth, basename_rank, _)| (*depth, *basename_rank));
+
+    found
+        .into_iter()
+        .filter_map(|(_, _, ignore_path)| {
+            let dir = ignore_path.parent()?.to_path_buf();
+            let mut builder = GitignoreBuilder::new(&dir);
+            if builder.add(&ignore_path).is_some() {
+                return None;
+            }
+            let matcher = builder.build().ok()?;
+            Some(GitignoreLayer { root: dir, matcher })
+        })
+        .collect()
+}
+
+/// Whether any component of `path` is `examples`, `tests`, or `benches`
+/// -- the conventional home for a crate's own test/example/bench code,
+/// wherever in the tree that crate happens to be vendored.
+fn is_test_artifact_path(path: &Path) -> bool {
+    path.components().any(|component| {
+        matches!(component.as_os_str().to_str(), Some("examples" | "tests" | "benches"))
+    })
+}
+
+/// The literal directory prefix before the first glob metacharacter in
+/// `pattern` (e.g. `src/**/*.rs` -> `src`, `assets/*.png` -> `assets`,
+/// `*.rs` -> `` since there's no path component before the `*`). A
+/// `regex:`-tagged pattern has no glob metacharacters to speak of, so it
+/// always yields an empty prefix (matches everything, no pruning). Used
+/// by `FileFilter::walk` to skip directories that fall outside every
+/// include pattern's reachable subtree.
+fn literal_prefix(pattern: &str) -> PathBuf {
+    if pattern.starts_with("regex:") {
+        return PathBuf::new();
+    }
+    let glob_str = pattern.strip_prefix("glob:").unwrap_or(pattern);
+    let literal_head = glob_str
+        .split(['*', '?', '[', '{'])
+        .next()
+        .unwrap_or("");
+    match literal_head.rfind('/') {
+        Some(idx) => PathBuf::from(&literal_head[..idx]),
+        None => PathBuf::new(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -292,7 +839,8 @@ mod tests {
             None,
             None,
             None,
-        ).unwrap();
+        ).unwrap()
+        .with_include_tests(true); // this test exercises include-pattern matching, not test-file detection
 
         // Test basic inclusion
         assert!(filter.is_included("src/main.rs"));
@@ -412,6 +960,370 @@ mod tests {
         assert!(filter.is_included(&large_file));
     }
     
+    #[test]
+    fn test_negation_pattern_reincludes_file() {
+        // A `!`-prefixed exclude pattern re-includes a path an earlier
+        // rule would otherwise have excluded, the same as a `.gitignore`
+        // negation line -- here, `build/` is excluded wholesale except
+        // for `build/keep.txt`.
+        let filter = FileFilter::new(
+            None,
+            Some(vec![
+                "build/**".to_string(),
+                "!build/keep.txt".to_string(),
+            ]),
+            None,
+            None,
+        ).unwrap();
+
+        assert!(!filter.is_included("build/output.o"));
+        assert!(filter.is_included("build/keep.txt"));
+        assert!(filter.is_included("src/main.rs"));
+    }
+
+    #[test]
+    fn test_regex_syntax_prefix_matches_what_glob_cannot() {
+        // Alternation of unrelated suffixes in one pattern -- awkward to
+        // express as a glob, trivial as a regex.
+        let filter = FileFilter::new(
+            None,
+            Some(vec!["regex:.*_(generated|codegen)\\.rs$".to_string()]),
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert!(!filter.is_included("src/schema_generated.rs"));
+        assert!(!filter.is_included("src/api_codegen.rs"));
+        assert!(filter.is_included("src/main.rs"));
+    }
+
+    #[test]
+    fn test_glob_syntax_prefix_is_equivalent_to_unprefixed() {
+        let filter = FileFilter::new(
+            None,
+            Some(vec!["glob:**/*.log".to_string()]),
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert!(!filter.is_included("app.log"));
+        assert!(filter.is_included("src/main.rs"));
+    }
+
+    #[test]
+    fn test_invalid_regex_pattern_names_the_offending_pattern() {
+        let err = FileFilter::new(None, Some(vec!["regex:(unclosed".to_string()]), None, None)
+            .expect_err("an invalid regex should fail to compile");
+        assert!(err.to_string().contains("(unclosed"));
+    }
+
+    #[test]
+    fn test_gitignore_hierarchical_override() {
+        let temp_dir = tempdir().unwrap();
+        let output = create_test_file(&temp_dir, "build/output.o", "x");
+        let log_at_root = create_test_file(&temp_dir, "notes.log", "x");
+        let kept_log = create_test_file(&temp_dir, "build/keep/important.log", "x");
+        let main_rs = create_test_file(&temp_dir, "src/main.rs", "fn main() {}");
+
+        fs::write(temp_dir.path().join(".gitignore"), "*.o\n*.log\n").unwrap();
+        fs::write(
+            temp_dir.path().join("build/keep/.gitignore"),
+            "!*.log\n",
+        )
+        .unwrap();
+
+        let filter = FileFilter::new(None, None, None, None)
+            .unwrap()
+            .with_ignore_files(temp_dir.path(), &[]);
+
+        assert!(!filter.is_included(&output), "root .gitignore should exclude *.o");
+        assert!(!filter.is_included(&log_at_root), "root .gitignore should exclude *.log");
+        assert!(
+            filter.is_included(&kept_log),
+            "the deeper build/keep/.gitignore's negation should override the root's *.log exclude"
+        );
+        assert!(filter.is_included(&main_rs), "files matching no gitignore rule stay included");
+    }
+
+    #[test]
+    fn test_llmignore_and_custom_basenames_are_discovered_by_default_and_on_request() {
+        let temp_dir = tempdir().unwrap();
+        let secret = create_test_file(&temp_dir, "secrets.env", "x");
+        let vendored = create_test_file(&temp_dir, "vendor/dep.rs", "x");
+        let main_rs = create_test_file(&temp_dir, "src/main.rs", "fn main() {}");
+
+        fs::write(temp_dir.path().join(".llmignore"), "*.env\n").unwrap();
+        fs::write(temp_dir.path().join(".promptignore"), "vendor/\n").unwrap();
+
+        let filter = FileFilter::new(None, None, None, None)
+            .unwrap()
+            .with_ignore_files(temp_dir.path(), &[".promptignore"]);
+
+        assert!(!filter.is_included(&secret), "a default .llmignore basename should be discovered");
+        assert!(
+            !filter.is_included(&vendored),
+            "a caller-registered extra basename (.promptignore) should be discovered too"
+        );
+        assert!(filter.is_included(&main_rs));
+    }
+
+    #[test]
+    fn test_member_roots_exempt_own_tests_but_not_a_dependencys() {
+        let temp_dir = tempdir().unwrap();
+        let own_test = create_test_file(&temp_dir, "tests/foo.rs", "#[test] fn it_works() {}");
+        let dep_test = create_test_file(&temp_dir, "deps/somecrate/tests/foo.rs", "#[test] fn x() {}");
+        let dep_src = create_test_file(&temp_dir, "deps/somecrate/src/lib.rs", "pub fn x() {}");
+
+        // Only the workspace's own top-level `tests/` and `src/` are
+        // member roots; `deps/` (where this fixture vendors `somecrate`)
+        // is deliberately left out, even though it lives in the same
+        // physical tree.
+        let filter = FileFilter::new(None, None, None, None).unwrap().with_member_roots(vec![
+            temp_dir.path().join("tests"),
+            temp_dir.path().join("src"),
+        ]);
+
+        assert!(filter.is_member(&own_test));
+        assert!(!filter.is_member(&dep_test));
+
+        assert!(filter.is_included(&own_test), "the workspace's own tests stay included");
+        assert!(!filter.is_included(&dep_test), "a non-member dependency's tests are excluded");
+        assert!(filter.is_included(&dep_src), "non-test code of a non-member dependency is unaffected");
+    }
+
+    #[test]
+    fn test_is_test_file_recognizes_variants_the_old_glob_list_missed() {
+        for path in [
+            "src/foo_test.tsx",
+            "src/foo_test.mjs",
+            "src/foo_test.jsx",
+            "components/foo.spec.tsx",
+            "lib/bar_spec.rb",
+            "cmd/test.ts",
+            "cmd/test.js",
+        ] {
+            assert!(FileFilter::is_test_file(path), "{path} should be recognized as a test file");
+        }
+
+        for path in ["src/main.rs", "src/contest.rs", "src/testimonial.js"] {
+            assert!(!FileFilter::is_test_file(path), "{path} should not be misclassified as a test file");
+        }
+    }
+
+    #[test]
+    fn test_include_tests_toggle_lets_test_files_through() {
+        let temp_dir = tempdir().unwrap();
+        let test_file = create_test_file(&temp_dir, "src/foo_test.go", "package foo");
+
+        let excluding = FileFilter::new(None, None, None, None).unwrap();
+        assert!(!excluding.is_included(&test_file));
+
+        let including = FileFilter::new(None, None, None, None).unwrap().with_include_tests(true);
+        assert!(including.is_included(&test_file));
+    }
+
+    #[test]
+    fn test_override_rules_re_include_on_top_of_a_broad_exclude() {
+        let filter = FileFilter::new(
+            None,
+            Some(vec!["**/generated/**".to_string()]),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_override_rules(vec!["src/generated/schema.rs".to_string()])
+        .unwrap();
+
+        assert!(
+            !filter.is_included_with_overrides("src/generated/other.rs"),
+            "still excluded by the broad **/generated/** rule"
+        );
+        assert!(
+            filter.is_included_with_overrides("src/generated/schema.rs"),
+            "the trailing explicit include should re-include this one file"
+        );
+        assert!(
+            filter.is_included_with_overrides("src/main.rs"),
+            "a path matching no rule in the chain defaults to included"
+        );
+    }
+
+    #[test]
+    fn test_select_types_narrows_to_named_type() {
+        let registry = crate::filetype::TypeRegistry::with_builtins();
+        let filter = FileFilter::new(None, None, None, None)
+            .unwrap()
+            .with_types(&registry, &["rust".to_string()], &[])
+            .unwrap();
+
+        assert!(filter.is_included("src/main.rs"));
+        assert!(!filter.is_included("src/main.py"));
+        assert!(!filter.is_included("README.md"));
+    }
+
+    #[test]
+    fn test_negate_types_excludes_named_type() {
+        let registry = crate::filetype::TypeRegistry::with_builtins();
+        let filter = FileFilter::new(None, None, None, None)
+            .unwrap()
+            .with_types(&registry, &[], &["python".to_string()])
+            .unwrap();
+
+        assert!(filter.is_included("src/main.rs"));
+        assert!(!filter.is_included("script.py"));
+    }
+
+    #[test]
+    fn test_unknown_type_name_is_an_error() {
+        let registry = crate::filetype::TypeRegistry::with_builtins();
+        let result = FileFilter::new(None, None, None, None)
+            .unwrap()
+            .with_types(&registry, &["not-a-real-type".to_string()], &[]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_walk_prunes_excluded_subtree() {
+        let temp_dir = tempdir().unwrap();
+        let main_rs = create_test_file(&temp_dir, "src/main.rs", "fn main() {}");
+        let cargo_toml = create_test_file(&temp_dir, "Cargo.toml", "[package]");
+        create_test_file(&temp_dir, "node_modules/lodash/index.js", "module.exports = {}");
+        create_test_file(&temp_dir, "target/debug/build/out", "binary");
+
+        let filter = FileFilter::new(
+            None,
+            Some(vec!["**/node_modules".to_string(), "**/target".to_string()]),
+            None,
+            None,
+        )
+        .unwrap();
+
+        let mut found = filter.walk(temp_dir.path());
+        found.sort();
+        let mut expected = vec![main_rs, cargo_toml];
+        expected.sort();
+
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn test_should_descend_rules_out_excluded_directory_before_walk_enters_it() {
+        let temp_dir = tempdir().unwrap();
+        create_test_file(&temp_dir, "node_modules/lodash/index.js", "module.exports = {}");
+
+        let filter = FileFilter::new(None, Some(vec!["**/node_modules".to_string()]), None, None)
+            .unwrap();
+
+        assert!(!filter.should_descend(temp_dir.path(), temp_dir.path().join("node_modules")));
+        assert!(filter.should_descend(temp_dir.path(), temp_dir.path().join("src")));
+    }
+
+    #[test]
+    fn test_walk_prunes_a_giant_excluded_node_modules_without_descending() {
+        // Stands in for a criterion-style benchmark (this repo has no
+        // bench harness to hook into): a `node_modules` deep and wide
+        // enough that naively stat-ing every file under it would be slow
+        // confirms `should_descend` lets `walk` skip the whole subtree
+        // via a single directory-level check rather than descending and
+        // filtering thousands of individual files one at a time.
+        let temp_dir = tempdir().unwrap();
+        let main_rs = create_test_file(&temp_dir, "src/main.rs", "fn main() {}");
+        for package in 0..200 {
+            create_test_file(
+                &temp_dir,
+                &format!("node_modules/pkg{package}/index.js"),
+                "module.exports = {}",
+            );
+        }
+
+        let filter = FileFilter::new(None, Some(vec!["**/node_modules".to_string()]), None, None)
+            .unwrap();
+
+        let found = filter.walk(temp_dir.path());
+        assert_eq!(found, vec![main_rs]);
+    }
+
+    #[test]
+    fn test_walk_respects_include_patterns() {
+        let temp_dir = tempdir().unwrap();
+        let main_rs = create_test_file(&temp_dir, "src/main.rs", "fn main() {}");
+        create_test_file(&temp_dir, "src/main.py", "print('hi')");
+        create_test_file(&temp_dir, "docs/guide.rs", "// not really rust, but outside src/");
+
+        // Anchor the pattern to this test's own temp directory so it
+        // matches the full paths `walk` produces (the same limitation
+        // `test_include_patterns` notes: a bare `src/**/*.rs` only
+        // matches a path that literally starts with `src/`).
+        let src_only_pattern = format!("{}/src/**/*.rs", temp_dir.path().display());
+        let filter = FileFilter::new(Some(vec![src_only_pattern]), None, None, None).unwrap();
+
+        let found = filter.walk(temp_dir.path());
+        assert_eq!(found, vec![main_rs]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_walk_does_not_follow_symlinks_by_default() {
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = tempdir().unwrap();
+        let main_rs = create_test_file(&temp_dir, "src/main.rs", "fn main() {}");
+        symlink(temp_dir.path().join("src"), temp_dir.path().join("link_to_src")).unwrap();
+
+        let filter = FileFilter::new(None, None, None, None).unwrap();
+        let found = filter.walk(temp_dir.path());
+
+        assert_eq!(found, vec![main_rs]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_walk_terminates_on_symlink_cycle_when_following() {
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = tempdir().unwrap();
+        let main_rs = create_test_file(&temp_dir, "a/main.rs", "fn main() {}");
+        // `a/loop` points back at `a` itself -- naive recursion here
+        // would never terminate.
+        symlink(temp_dir.path().join("a"), temp_dir.path().join("a").join("loop")).unwrap();
+
+        let filter = FileFilter::new(None, None, None, None)
+            .unwrap()
+            .with_follow_symlinks(true);
+
+        let found = filter.walk(temp_dir.path());
+        assert_eq!(found, vec![main_rs]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_walk_terminates_on_mutually_recursive_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = tempdir().unwrap();
+        let main_rs = create_test_file(&temp_dir, "a/main.rs", "fn main() {}");
+        create_test_file(&temp_dir, "b/lib.rs", "pub fn lib() {}");
+        let lib_rs = temp_dir.path().join("b/lib.rs");
+        // `a/to_b` -> `b` and `b/to_a` -> `a`: neither symlink points at
+        // its own ancestor, so this only loops once the traversal
+        // alternates between the two directories.
+        symlink(temp_dir.path().join("b"), temp_dir.path().join("a").join("to_b")).unwrap();
+        symlink(temp_dir.path().join("a"), temp_dir.path().join("b").join("to_a")).unwrap();
+
+        let filter = FileFilter::new(None, None, None, None)
+            .unwrap()
+            .with_follow_symlinks(true);
+
+        let mut found = filter.walk(temp_dir.path());
+        found.sort();
+        let mut expected = vec![main_rs, lib_rs];
+        expected.sort();
+        assert_eq!(found, expected);
+    }
+
     #[test]
     fn test_combined_filters() {
         let temp_dir = tempdir().unwrap();
@@ -436,8 +1348,9 @@ mod tests {
             Some(vec!["**/large_*".to_string()]), // Exclude large files
             Some(extensions),
             Some(1000), // Max 1000 bytes
-        ).unwrap();
-        
+        ).unwrap()
+        .with_include_tests(true); // this test exercises extension/exclude/size rules, not test-file detection
+
         // Test inclusion
         assert!(filter.is_included(&src_file));
         assert!(filter.is_included(&test_file));
@@ -0,0 +1,138 @@
+use std::path::Path;
+
+use syntect::html::{css_for_theme_with_class_style, ClassStyle, ClassedHTMLGenerator};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+use super::Formatter;
+use crate::config::Config;
+use crate::git::{GitStatus, RepoSummary};
+use crate::vfs::FileMetadata;
+
+/// Theme name passed to `syntect::html::css_for_theme_with_class_style`
+/// for the `<style>` block emitted by `format_header`. `syntect` ships
+/// this one in its default theme set, so it doesn't require loading a
+/// theme file from disk.
+const THEME_NAME: &str = "InspiredGitHub";
+
+/// Renders an archive as a single self-contained HTML document, with
+/// each file's content syntax-highlighted via `syntect`'s classed
+/// (CSS-driven, not inline-styled) HTML generator. The `SyntaxSet` is
+/// loaded once here rather than per call, since `load_defaults_newlines`
+/// parses every bundled `.sublime-syntax` definition.
+pub struct HtmlFormatter {
+    syntax_set: SyntaxSet,
+}
+
+impl HtmlFormatter {
+    pub fn new() -> Self {
+        Self {
+            syntax_set: SyntaxSet::load_defaults_newlines(),
+        }
+    }
+}
+
+impl Formatter for HtmlFormatter {
+    fn format_header(&self, config: Option<&Config>, _git_summary: Option<&RepoSummary>) -> String {
+        let theme_set = syntect::highlighting::ThemeSet::load_defaults();
+        let theme = &theme_set.themes[THEME_NAME];
+        let css = css_for_theme_with_class_style(theme, ClassStyle::Spaced)
+            .unwrap_or_default();
+
+        let title = config
+            .map(|c| c.input.display().to_string())
+            .unwrap_or_else(|| "Archive".to_string());
+
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n{css}\n\
+             body {{ font-family: sans-serif; }}\n\
+             pre {{ padding: 1em; overflow-x: auto; }}\n\
+             section {{ margin-bottom: 2em; }}\n\
+             </style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+        )
+    }
+
+    fn format_file(
+        &self,
+        path: &Path,
+        content: &str,
+        encoding: &str,
+        _metadata: &FileMetadata,
+        git_status: Option<GitStatus>,
+    ) -> String {
+        let syntax = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(|ext| self.syntax_set.find_syntax_by_extension(ext))
+            .unwrap_or_else(|| self.syntax_set.find_syntax_plain_text());
+
+        let mut generator =
+            ClassedHTMLGenerator::new_with_class_style(syntax, &self.syntax_set, ClassStyle::Spaced);
+        for line in LinesWithEndings::from(content) {
+            // `parse_html_for_line_which_includes_newline` never fails
+            // for a well-formed `SyntaxSet` syntax, so a failure here
+            // means corrupted bundled syntax data -- not something a
+            // caller can usefully recover from.
+            generator
+                .parse_html_for_line_which_includes_newline(line)
+                .expect("bundled syntect syntax definitions failed to parse a line");
+        }
+        let highlighted = generator.finalize();
+
+        let marker = git_status_suffix(git_status);
+        let encoding_note = if encoding == "UTF-8" {
+            String::new()
+        } else {
+            format!(" (encoding: {encoding})")
+        };
+
+        format!(
+            "<section>\n<h2>{}{}{}</h2>\n<pre><code class=\"code\">{}</code></pre>\n</section>\n",
+            html_escape(&path.display().to_string()),
+            encoding_note,
+            marker,
+            highlighted,
+        )
+    }
+
+    fn format_unchanged_file(&self, path: &Path, _metadata: &FileMetadata) -> String {
+        format!(
+            "<section>\n<h2>{}</h2>\n<p><em>unchanged</em></p>\n</section>\n",
+            html_escape(&path.display().to_string()),
+        )
+    }
+
+    fn format_duplicate_file(&self, path: &Path, original_path: &Path, _metadata: &FileMetadata) -> String {
+        format!(
+            "<section>\n<h2>{}</h2>\n<p><em>duplicate of {}</em></p>\n</section>\n",
+            html_escape(&path.display().to_string()),
+            html_escape(&original_path.display().to_string()),
+        )
+    }
+
+    fn format_footer(&self, file_count: usize) -> String {
+        format!("<p>Total files processed: {file_count}</p>\n</body>\n</html>\n")
+    }
+}
+
+/// Render `status` as a parenthetical suffix on an HTML file heading,
+/// e.g. `" (modified)"`. Mirrors `formatter::text::git_status_marker`'s
+/// bracketed-letter convention, spelled out in full since HTML headings
+/// have the room for it.
+fn git_status_suffix(status: Option<GitStatus>) -> String {
+    let word = match status {
+        None | Some(GitStatus::Unmodified) => return String::new(),
+        Some(GitStatus::Modified) => "modified",
+        Some(GitStatus::Staged) => "staged",
+        Some(GitStatus::New) => "new",
+        Some(GitStatus::Renamed) => "renamed",
+        Some(GitStatus::Ignored) => "ignored",
+    };
+    format!(" ({word})")
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,134 @@
+use std::path::Path;
+
+use super::Formatter;
+use crate::config::Config;
+use crate::git::{GitStatus, RepoSummary};
+use crate::tree::{generate_structure_summary, generate_structure_summary_with_icons, generate_tree, TreeConfig};
+use crate::utils::format_path;
+use crate::vfs::FileMetadata;
+
+/// Renders an archive as a Markdown document: a `#` title, the directory
+/// tree in its own fenced block, then one `##` section per file with its
+/// content in a fenced code block tagged with the language inferred from
+/// its extension. Meant to be dropped straight into docs or pasted into
+/// a chat window, unlike `text::PlainTextFormatter`'s custom delimiters.
+pub struct MarkdownFormatter;
+
+impl MarkdownFormatter {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn generate_directory_tree(&self, config: &Config) -> String {
+        let tree_config = TreeConfig {
+            include_hidden: config.include_hidden,
+            max_depth: config.max_depth,
+            follow_links: config.follow_links,
+            include_extensions: config.get_included_extensions().map(|set| {
+                set.into_iter().collect()
+            }),
+            max_file_size: config.max_file_size,
+            exclude_dirs: None,
+            respect_gitignore: config.respect_ignore_files,
+            threads: None,
+            progress: None,
+        };
+
+        match generate_tree(&config.input, &tree_config) {
+            Ok(tree) if config.show_tree_icons => generate_structure_summary_with_icons(&tree),
+            Ok(tree) => generate_structure_summary(&tree),
+            Err(e) => format!("Error generating directory tree: {}\n", e),
+        }
+    }
+}
+
+impl Formatter for MarkdownFormatter {
+    fn format_header(&self, config: Option<&Config>, _git_summary: Option<&RepoSummary>) -> String {
+        let mut header = String::new();
+        header.push_str("# Archive Contents\n\n");
+
+        if let Some(config) = config {
+            if config.include_tree {
+                header.push_str("```\n");
+                header.push_str(&self.generate_directory_tree(config));
+                header.push_str("```\n\n");
+            }
+        }
+
+        header
+    }
+
+    fn format_file(
+        &self,
+        path: &Path,
+        content: &str,
+        encoding: &str,
+        _metadata: &FileMetadata,
+        git_status: Option<GitStatus>,
+    ) -> String {
+        let marker = git_status_suffix(git_status);
+        let encoding_note = if encoding == "UTF-8" {
+            String::new()
+        } else {
+            format!(" (encoding: {encoding})")
+        };
+
+        let lang = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("");
+        let fence = fence_for(content);
+
+        format!(
+            "## {}{}{}\n\n{fence}{lang}\n{content}\n{fence}\n\n",
+            format_path(path),
+            encoding_note,
+            marker,
+        )
+    }
+
+    fn format_unchanged_file(&self, path: &Path, _metadata: &FileMetadata) -> String {
+        format!("## {}\n\n_unchanged_\n\n", format_path(path))
+    }
+
+    fn format_duplicate_file(&self, path: &Path, original_path: &Path, _metadata: &FileMetadata) -> String {
+        format!(
+            "## {}\n\n_duplicate of {}_\n\n",
+            format_path(path),
+            format_path(original_path),
+        )
+    }
+
+    fn format_footer(&self, file_count: usize) -> String {
+        format!("---\n\nTotal files processed: {file_count}\n")
+    }
+}
+
+/// Render `status` as a parenthetical suffix on a Markdown heading, e.g.
+/// `" (modified)"`. Mirrors `formatter::text::git_status_marker`'s
+/// bracketed-letter convention, spelled out in full the way
+/// `formatter::html::git_status_suffix` does.
+fn git_status_suffix(status: Option<GitStatus>) -> String {
+    let word = match status {
+        None | Some(GitStatus::Unmodified) => return String::new(),
+        Some(GitStatus::Modified) => "modified",
+        Some(GitStatus::Staged) => "staged",
+        Some(GitStatus::New) => "new",
+        Some(GitStatus::Renamed) => "renamed",
+        Some(GitStatus::Ignored) => "ignored",
+    };
+    format!(" ({word})")
+}
+
+/// Pick a fence of backticks at least 3 long and longer than any run of
+/// backticks already present in `content`, so a file containing its own
+/// fenced code blocks (e.g. a Markdown file being archived) can't
+/// prematurely close this one.
+fn fence_for(content: &str) -> String {
+    let longest_run = content
+        .split(|c| c != '`')
+        .map(|run| run.len())
+        .max()
+        .unwrap_or(0);
+    "`".repeat((longest_run + 1).max(3))
+}
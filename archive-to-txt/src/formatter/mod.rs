@@ -1,18 +1,56 @@
 use std::path::Path;
 
 use super::config::{OutputFormat, Config};
+use super::git::{GitStatus, RepoSummary};
+use super::vfs::FileMetadata;
 
+pub mod html;
+pub mod markdown;
 pub mod text;
 
 pub trait Formatter: Send + Sync {
-    fn format_header(&self, config: Option<&Config>) -> String;
-    fn format_file(&self, path: &Path, content: &str) -> String;
+    /// `git_summary` is the repo's branch/ahead-behind/dirty-count
+    /// summary from `GitInfo::summary`, for formatters that print a
+    /// "Repository" block -- `None` when `git_info` is disabled or the
+    /// archived path isn't inside a git repository.
+    fn format_header(&self, config: Option<&Config>, git_summary: Option<&RepoSummary>) -> String;
+    /// Format one file's content for the archive. `encoding` is the name
+    /// `crate::encoding::decode` chose for this file (e.g. `"UTF-8"`,
+    /// `"UTF-16LE"`), so a formatter can surface non-default encodings to
+    /// the reader. `metadata` carries the file's size/mtime/mode (and, on
+    /// Unix with the `xattr` feature, extended attributes) as read from
+    /// `Config::file_source`, so the archive can record enough to restore
+    /// them on extraction -- a source with no such concept (e.g.
+    /// `vfs::MemorySource`, or a virtual entry surfaced by a
+    /// `ContentAdapter`) passes `FileMetadata::default()`. `git_status` is
+    /// this path's entry in `GitInfo::file_statuses`, resolved against the
+    /// repo-relative archive path -- `None` when `git_info` is disabled,
+    /// the path isn't inside a git repository, or it has no reported
+    /// status (the unmodified, up-to-date common case).
+    fn format_file(
+        &self,
+        path: &Path,
+        content: &str,
+        encoding: &str,
+        metadata: &FileMetadata,
+        git_status: Option<GitStatus>,
+    ) -> String;
+    /// Format a compact reference for a file whose content matches the
+    /// previous run's incremental manifest (see `crate::incremental`),
+    /// in place of re-embedding its content.
+    fn format_unchanged_file(&self, path: &Path, metadata: &FileMetadata) -> String;
+    /// Format a compact reference for a file whose content duplicates
+    /// `original_path`, already embedded earlier in this same archive
+    /// (see `crate::incremental`), in place of storing a second copy.
+    fn format_duplicate_file(&self, path: &Path, original_path: &Path, metadata: &FileMetadata) -> String;
     fn format_footer(&self, file_count: usize) -> String;
 }
 
 pub fn create_formatter(format: OutputFormat) -> Box<dyn Formatter> {
     match format {
         OutputFormat::Plain => Box::new(text::PlainTextFormatter::new()),
+        OutputFormat::Html => Box::new(html::HtmlFormatter::new()),
+        OutputFormat::Markdown => Box::new(markdown::MarkdownFormatter::new()),
         _ => Box::new(text::PlainTextFormatter::new()), // Default to plain text
     }
 }
@@ -1,7 +1,14 @@
 use std::path::Path;
 use super::Formatter;
-use crate::tree::{generate_tree, generate_structure_summary, TreeConfig};
+use crate::tree::{generate_tree, generate_structure_summary, generate_structure_summary_with_icons, TreeConfig};
 use crate::config::Config;
+use crate::git::{GitStatus, RepoSummary};
+use crate::vfs::FileMetadata;
+
+/// The rule between a file's header and its content (and again before
+/// the next file). `crate::parser::text::PlainTextParser` relies on this
+/// exact string to find entry boundaries, so the two stay in lockstep.
+pub(crate) const FILE_DELIMITER: &str = "================================================\n";
 
 pub struct PlainTextFormatter;
 
@@ -12,11 +19,15 @@ impl PlainTextFormatter {
 }
 
 impl Formatter for PlainTextFormatter {
-    fn format_header(&self, config: Option<&Config>) -> String {
+    fn format_header(&self, config: Option<&Config>, git_summary: Option<&RepoSummary>) -> String {
         let mut header = String::new();
         header.push_str("Archive Contents\n");
         header.push_str("================\n\n");
-        
+
+        if let Some(summary) = git_summary {
+            header.push_str(&format_repo_summary(summary));
+        }
+
         // Add directory tree if requested and config is available
         if let Some(config) = config {
             if config.include_tree {
@@ -31,8 +42,51 @@ impl Formatter for PlainTextFormatter {
         header
     }
 
-    fn format_file(&self, path: &Path, content: &str) -> String {
-        format!("\n================================================\nFILE: {}\n================================================\n{}\n", path.display(), content)
+    fn format_file(
+        &self,
+        path: &Path,
+        content: &str,
+        encoding: &str,
+        metadata: &FileMetadata,
+        git_status: Option<GitStatus>,
+    ) -> String {
+        // Only call out the encoding when it's not the default, so the
+        // common UTF-8 case reads exactly as it always has.
+        let marker = git_status_marker(git_status);
+        let header = if encoding == "UTF-8" {
+            format!("FILE: {marker}{}", path.display())
+        } else {
+            format!("FILE: {marker}{} (encoding: {})", path.display(), encoding)
+        };
+
+        let meta_lines = format_metadata_lines(metadata);
+
+        // `LENGTH` records the exact content byte count so
+        // `parser::text::PlainTextParser` can read content by byte range
+        // instead of scanning for the next delimiter, which content
+        // containing a `FILE:`/`====` line of its own would desync.
+        format!(
+            "\n{FILE_DELIMITER}{header}\n{meta_lines}LENGTH: {}\n{FILE_DELIMITER}{content}\n",
+            content.len(),
+        )
+    }
+
+    fn format_unchanged_file(&self, path: &Path, metadata: &FileMetadata) -> String {
+        let meta_lines = format_metadata_lines(metadata);
+        // No content follows -- `STATUS: unchanged` tells
+        // `parser::text::PlainTextParser` this entry can't be restored
+        // from this archive alone, so it's skipped rather than
+        // extracted as a zero-byte file.
+        format!("\n{FILE_DELIMITER}FILE: {}\nSTATUS: unchanged\n{meta_lines}LENGTH: 0\n{FILE_DELIMITER}\n", path.display())
+    }
+
+    fn format_duplicate_file(&self, path: &Path, original_path: &Path, metadata: &FileMetadata) -> String {
+        let meta_lines = format_metadata_lines(metadata);
+        format!(
+            "\n{FILE_DELIMITER}FILE: {}\nSTATUS: duplicate-of {}\n{meta_lines}LENGTH: 0\n{FILE_DELIMITER}\n",
+            path.display(),
+            original_path.display(),
+        )
     }
 
     fn format_footer(&self, file_count: usize) -> String {
@@ -50,14 +104,90 @@ impl PlainTextFormatter {
                 set.into_iter().collect()
             }),
             max_file_size: config.max_file_size,
+            exclude_dirs: None,
+            respect_gitignore: config.respect_ignore_files,
+            threads: None,
+            progress: None,
         };
         
         match generate_tree(&config.input, &tree_config) {
+            Ok(tree) if config.show_tree_icons => generate_structure_summary_with_icons(&tree),
             Ok(tree) => generate_structure_summary(&tree),
             Err(e) => format!("Error generating directory tree: {}\n", e),
         }
     }
 }
 
+/// Render an xattr value as hex so an arbitrary byte string survives as
+/// one line of plain text.
+#[cfg(all(unix, feature = "xattr"))]
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Render `summary` as the "Repository" block `format_header` prints
+/// above the directory tree: branch, ahead/behind (omitted when no
+/// upstream is configured), and aggregate dirty counts.
+fn format_repo_summary(summary: &RepoSummary) -> String {
+    let mut block = String::new();
+    block.push_str("Repository\n");
+    block.push_str("==========\n");
+
+    if let Some(branch) = &summary.branch {
+        block.push_str(&format!("Branch: {branch}\n"));
+    }
+
+    if let (Some(ahead), Some(behind)) = (summary.ahead, summary.behind) {
+        block.push_str(&format!("Ahead: {ahead}, Behind: {behind}\n"));
+    }
+
+    block.push_str(&format!(
+        "Staged: {}, Modified: {}, Untracked: {}, Deleted: {}, Conflicted: {}\n",
+        summary.staged, summary.modified, summary.untracked, summary.deleted, summary.conflicted,
+    ));
+    block.push('\n');
+
+    block
+}
+
+/// Render `status` as the `exa`-style bracketed marker this formatter
+/// prefixes a file header with, e.g. `[M] ` for a modified file. `None`
+/// (status unknown) and `Some(GitStatus::Unmodified)` both render as
+/// nothing, so a clean tree's output reads exactly as it did before this
+/// existed.
+fn git_status_marker(status: Option<GitStatus>) -> String {
+    let letter = match status {
+        None | Some(GitStatus::Unmodified) => return String::new(),
+        Some(GitStatus::Modified) => "M",
+        Some(GitStatus::Staged) => "A",
+        Some(GitStatus::New) => "?",
+        Some(GitStatus::Renamed) => "R",
+        Some(GitStatus::Ignored) => "I",
+    };
+    format!("[{letter}] ")
+}
+
+/// Build the optional `MTIME`/`MODE`/`XATTR` lines shared by every entry
+/// variant (full, unchanged, duplicate) -- all optional, and all come
+/// before `LENGTH`; `parser::text::PlainTextParser` reads past any line
+/// it doesn't recognize until it finds `LENGTH`, so adding another one
+/// here doesn't break older archives that never had any.
+fn format_metadata_lines(metadata: &FileMetadata) -> String {
+    let mut meta_lines = String::new();
+    if let Some(modified) = metadata.modified {
+        if let Ok(unix_secs) = modified.duration_since(std::time::UNIX_EPOCH) {
+            meta_lines.push_str(&format!("MTIME: {}\n", unix_secs.as_secs()));
+        }
+    }
+    if let Some(mode) = metadata.mode {
+        meta_lines.push_str(&format!("MODE: {mode:o}\n"));
+    }
+    #[cfg(all(unix, feature = "xattr"))]
+    for (name, value) in &metadata.xattrs {
+        meta_lines.push_str(&format!("XATTR: {name}={}\n", hex_encode(value)));
+    }
+    meta_lines
+}
+
 // Keep the old name for backward compatibility
 pub type TextFormatter = PlainTextFormatter;
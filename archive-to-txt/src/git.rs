@@ -3,14 +3,84 @@
 //! This module provides functionality to extract and format Git repository information
 //! for inclusion in the text archive.
 
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use anyhow::{Context, Result};
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, TimeZone};
 use log::{debug, warn};
 use serde::Serialize;
 
+/// A file's working-tree status, as reported by `git status --porcelain`,
+/// for annotating per-file entries in the archive (see
+/// `GitInfo::file_statuses` and `Formatter::format_file`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum GitStatus {
+    /// Tracked, with no staged or worktree changes.
+    Unmodified,
+    /// Worktree changes not yet staged.
+    Modified,
+    /// Changes staged for commit.
+    Staged,
+    /// Untracked (`??`).
+    New,
+    /// Renamed or copied (`R`/`C` in either column).
+    Renamed,
+    /// Excluded by `.gitignore` (`!!`, only reported when `git status`
+    /// is run with `--ignored`, which `file_statuses` does not pass, but
+    /// kept as a variant other producers of this map could still set).
+    Ignored,
+}
+
+impl GitStatus {
+    /// Classify a two-character porcelain `XY` code: `X` is the staged
+    /// (index) state, `Y` is the worktree state.
+    fn from_xy(xy: &str) -> Self {
+        match xy {
+            "??" => GitStatus::New,
+            "!!" => GitStatus::Ignored,
+            _ => {
+                let mut chars = xy.chars();
+                let staged = chars.next().unwrap_or(' ');
+                let worktree = chars.next().unwrap_or(' ');
+                if staged != ' ' {
+                    GitStatus::Staged
+                } else if worktree != ' ' {
+                    GitStatus::Modified
+                } else {
+                    GitStatus::Unmodified
+                }
+            }
+        }
+    }
+}
+
+/// Aggregate repository state, as of the most recent `Statuses` scan:
+/// the current branch, how far it is ahead/behind its upstream, and a
+/// tally of dirty files by category. See [`GitInfo::summary`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RepoSummary {
+    /// Branch short name, or the detached-HEAD short SHA.
+    pub branch: Option<String>,
+    /// Commits the local branch is ahead of its upstream, or `None` if
+    /// no upstream is configured.
+    pub ahead: Option<usize>,
+    /// Commits the local branch is behind its upstream, or `None` if no
+    /// upstream is configured.
+    pub behind: Option<usize>,
+    /// Files with staged (index) changes.
+    pub staged: usize,
+    /// Files with unstaged worktree modifications.
+    pub modified: usize,
+    /// Untracked files.
+    pub untracked: usize,
+    /// Files deleted in the worktree but not yet staged.
+    pub deleted: usize,
+    /// Files with unresolved merge conflicts.
+    pub conflicted: usize,
+}
+
 /// Information about a Git repository
 #[derive(Debug, Clone, Serialize)]
 pub struct GitInfo {
@@ -36,7 +106,91 @@ impl GitInfo {
     ///
     /// # Returns
     /// `Some(GitInfo)` if the path is in a git repository, `None` otherwise.
+    ///
+    /// Routes through [`Self::from_path_git2`] when the `git2-backend`
+    /// feature is enabled - no process spawns, no stdout parsing - and
+    /// falls back to shelling out to the `git` binary otherwise.
     pub fn from_path(path: &Path) -> Result<Self> {
+        #[cfg(feature = "git2-backend")]
+        {
+            Self::from_path_git2(path)
+        }
+        #[cfg(not(feature = "git2-backend"))]
+        {
+            Self::from_path_subprocess(path)
+        }
+    }
+
+    /// Library-backed implementation: opens the repository's object database
+    /// directly via `git2` instead of spawning `git rev-parse`/`git show`/
+    /// `git diff-index` and parsing their stdout. Produces the same fields
+    /// [`Self::format`] renders, modulo the detached-HEAD edge case noted on
+    /// `branch` below.
+    #[cfg(feature = "git2-backend")]
+    fn from_path_git2(path: &Path) -> Result<Self> {
+        let repo = git2::Repository::discover(path).context("Not a git repository")?;
+        let root = repo
+            .workdir()
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| repo.path().to_path_buf());
+
+        let head = repo.head().ok();
+
+        // `shorthand()` returns the abbreviated commit id rather than the
+        // literal "HEAD" `git rev-parse --abbrev-ref HEAD` reports for a
+        // detached HEAD - an acceptable divergence since `format()` only
+        // ever displays this as a label, not a ref callers act on.
+        let branch = head
+            .as_ref()
+            .and_then(|head_ref| head_ref.shorthand())
+            .map(|s| s.to_string());
+
+        let head_commit = head.as_ref().and_then(|head_ref| head_ref.peel_to_commit().ok());
+
+        let commit = head_commit.as_ref().map(|c| c.id().to_string());
+
+        let (author, date) = match &head_commit {
+            Some(c) => {
+                let signature = c.author();
+                let author = Some(format!(
+                    "{} <{}>",
+                    signature.name().unwrap_or(""),
+                    signature.email().unwrap_or("")
+                ));
+
+                let time = c.time();
+                let offset = chrono::FixedOffset::east_opt(time.offset_minutes() * 60)
+                    .unwrap_or_else(|| chrono::FixedOffset::east_opt(0).unwrap());
+                let date = offset
+                    .timestamp_opt(time.seconds(), 0)
+                    .single()
+                    .map(|dt| dt.with_timezone(&Local));
+
+                (author, date)
+            }
+            None => (None, None),
+        };
+
+        let has_uncommitted_changes = repo
+            .diff_index_to_workdir(None, None)
+            .map(|diff| diff.deltas().len() > 0)
+            .unwrap_or(false);
+
+        Ok(Self {
+            root,
+            branch,
+            commit,
+            author,
+            date,
+            has_uncommitted_changes,
+        })
+    }
+
+    /// Subprocess-based implementation, used when the `git2-backend` feature
+    /// is disabled (or as the build's only implementation before that
+    /// feature existed).
+    #[cfg_attr(feature = "git2-backend", allow(dead_code))]
+    fn from_path_subprocess(path: &Path) -> Result<Self> {
         // Find the git repository root
         let output = Command::new("git")
             .args(["rev-parse", "--show-toplevel"])
@@ -124,6 +278,278 @@ impl GitInfo {
         })
     }
 
+    /// Overwrite `commit` with a resolved revision from `Config::revision`
+    /// so [`Self::format`] reports the commit actually archived rather
+    /// than `HEAD`. `has_uncommitted_changes` is left as-is -- it still
+    /// accurately describes the worktree, independent of which commit's
+    /// tree got archived.
+    pub fn set_resolved_commit(&mut self, commit_id: String) {
+        self.commit = Some(commit_id);
+    }
+
+    /// Run `git status --porcelain=v1 -z` once at `self.root` and build a
+    /// map from repo-relative path to [`GitStatus`], so a caller can tag
+    /// every archived file with its working-tree status in one shot
+    /// instead of shelling out per file.
+    pub fn file_statuses(&self) -> Result<HashMap<PathBuf, GitStatus>> {
+        let output = Command::new("git")
+            .args(["status", "--porcelain=v1", "-z"])
+            .current_dir(&self.root)
+            .output()
+            .context("Failed to execute git status")?;
+
+        if !output.status.success() {
+            return Err(anyhow::anyhow!("git status failed"));
+        }
+
+        let mut statuses = HashMap::new();
+
+        // `-z` NUL-terminates every field instead of relying on "\n" (which
+        // a path containing a newline could forge) or the human-readable
+        // " -> " rename separator (which a path containing " -> " could
+        // forge); a rename/copy entry is simply two consecutive NUL-
+        // terminated fields, destination first then source.
+        let mut fields = output
+            .stdout
+            .split(|&b| b == 0)
+            .filter(|field| !field.is_empty())
+            .map(|field| String::from_utf8_lossy(field).into_owned());
+
+        while let Some(entry) = fields.next() {
+            if entry.len() < 3 {
+                continue;
+            }
+            let xy = &entry[0..2];
+            let path = PathBuf::from(&entry[3..]);
+
+            if xy.contains('R') || xy.contains('C') {
+                let original_path = fields.next().map(PathBuf::from);
+                if let Some(original_path) = original_path {
+                    statuses.insert(original_path, GitStatus::Renamed);
+                }
+                statuses.insert(path, GitStatus::Renamed);
+                continue;
+            }
+
+            statuses.insert(path, GitStatus::from_xy(xy));
+        }
+
+        Ok(statuses)
+    }
+
+    /// Diff `baseline` (a commit, tag, or branch) against the working
+    /// tree (including staged changes) and collect every repo-relative
+    /// path touched -- added, modified, renamed/copied (both sides), or
+    /// deleted -- so `Config::changed_since` (`--since`) can restrict an
+    /// archive to just what changed. Only available with the
+    /// `git2-backend` feature, which the underlying tree/diff access
+    /// needs.
+    #[cfg(feature = "git2-backend")]
+    pub fn changed_since(&self, baseline: &str) -> Result<HashSet<PathBuf>> {
+        let repo = git2::Repository::discover(&self.root).context("Not a git repository")?;
+        let baseline_tree = repo
+            .revparse_single(baseline)
+            .and_then(|obj| obj.peel_to_commit())
+            .and_then(|commit| commit.tree())
+            .with_context(|| format!("Failed to resolve revision {baseline:?}"))?;
+
+        let diff = repo
+            .diff_tree_to_workdir_with_index(Some(&baseline_tree), None)
+            .context("Failed to diff baseline against the working tree")?;
+
+        let mut changed = HashSet::new();
+        diff.foreach(
+            &mut |delta, _progress| {
+                if let Some(path) = delta.old_file().path() {
+                    changed.insert(path.to_path_buf());
+                }
+                if let Some(path) = delta.new_file().path() {
+                    changed.insert(path.to_path_buf());
+                }
+                true
+            },
+            None,
+            None,
+            None,
+        )
+        .context("Failed to walk baseline/working-tree diff")?;
+
+        Ok(changed)
+    }
+
+    /// Summarize the branch, upstream ahead/behind counts, and aggregate
+    /// working-tree dirtiness, for the "Repository" block
+    /// `formatter::text::PlainTextFormatter::format_header` renders above
+    /// the directory tree. Mirrors the [`Self::from_path`] git2-backend/
+    /// subprocess split.
+    pub fn summary(&self) -> Result<RepoSummary> {
+        #[cfg(feature = "git2-backend")]
+        {
+            self.summary_git2()
+        }
+        #[cfg(not(feature = "git2-backend"))]
+        {
+            self.summary_subprocess()
+        }
+    }
+
+    /// Library-backed implementation: resolves the upstream and tallies
+    /// `Statuses` directly via `git2` instead of shelling out to
+    /// `git rev-list`/`git status`.
+    #[cfg(feature = "git2-backend")]
+    fn summary_git2(&self) -> Result<RepoSummary> {
+        let repo = git2::Repository::discover(&self.root).context("Not a git repository")?;
+        let head = repo.head().ok();
+        let branch = head.as_ref().and_then(|head_ref| head_ref.shorthand()).map(|s| s.to_string());
+        let local_oid = head.as_ref().and_then(|head_ref| head_ref.target());
+
+        // Only a branch (not a detached HEAD) can have a configured
+        // upstream; `graph_ahead_behind` wants both tips resolved first.
+        let (ahead, behind) = head
+            .as_ref()
+            .filter(|head_ref| head_ref.is_branch())
+            .and_then(|_| repo.head().ok())
+            .map(git2::Branch::wrap)
+            .and_then(|branch| branch.upstream().ok())
+            .and_then(|upstream| upstream.get().target())
+            .zip(local_oid)
+            .and_then(|(upstream_oid, local_oid)| repo.graph_ahead_behind(local_oid, upstream_oid).ok())
+            .map_or((None, None), |(ahead, behind)| (Some(ahead), Some(behind)));
+
+        let mut opts = git2::StatusOptions::new();
+        opts.include_untracked(true);
+        let statuses = repo
+            .statuses(Some(&mut opts))
+            .context("Failed to collect repository statuses")?;
+
+        let mut summary = RepoSummary {
+            branch,
+            ahead,
+            behind,
+            ..RepoSummary::default()
+        };
+
+        for entry in statuses.iter() {
+            let status = entry.status();
+            if status.is_conflicted() {
+                summary.conflicted += 1;
+                continue;
+            }
+            if status.is_index_new()
+                || status.is_index_modified()
+                || status.is_index_deleted()
+                || status.is_index_renamed()
+                || status.is_index_typechange()
+            {
+                summary.staged += 1;
+            }
+            if status.is_wt_modified() || status.is_wt_typechange() {
+                summary.modified += 1;
+            }
+            if status.is_wt_deleted() {
+                summary.deleted += 1;
+            }
+            if status.is_wt_new() {
+                summary.untracked += 1;
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Subprocess-based implementation, used when the `git2-backend`
+    /// feature is disabled.
+    #[cfg_attr(feature = "git2-backend", allow(dead_code))]
+    fn summary_subprocess(&self) -> Result<RepoSummary> {
+        let branch = Command::new("git")
+            .args(["rev-parse", "--abbrev-ref", "HEAD"])
+            .current_dir(&self.root)
+            .output()
+            .ok()
+            .and_then(|output| {
+                if output.status.success() {
+                    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+                } else {
+                    None
+                }
+            });
+
+        // `@{upstream}...HEAD` orders the range as (upstream-only, HEAD-only)
+        // commits, i.e. (behind, ahead); fails outright (no `0`/`0`) when no
+        // upstream is configured, which we treat as "unknown" rather than 0.
+        let (ahead, behind) = Command::new("git")
+            .args(["rev-list", "--left-right", "--count", "@{upstream}...HEAD"])
+            .current_dir(&self.root)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| {
+                let text = String::from_utf8_lossy(&output.stdout).into_owned();
+                let mut counts = text.split_whitespace();
+                let behind = counts.next()?.parse::<usize>().ok()?;
+                let ahead = counts.next()?.parse::<usize>().ok()?;
+                Some((Some(ahead), Some(behind)))
+            })
+            .unwrap_or((None, None));
+
+        let output = Command::new("git")
+            .args(["status", "--porcelain=v1", "-z"])
+            .current_dir(&self.root)
+            .output()
+            .context("Failed to execute git status")?;
+
+        if !output.status.success() {
+            return Err(anyhow::anyhow!("git status failed"));
+        }
+
+        let mut summary = RepoSummary {
+            branch,
+            ahead,
+            behind,
+            ..RepoSummary::default()
+        };
+
+        let mut fields = output
+            .stdout
+            .split(|&b| b == 0)
+            .filter(|field| !field.is_empty())
+            .map(|field| String::from_utf8_lossy(field).into_owned());
+
+        while let Some(entry) = fields.next() {
+            if entry.len() < 3 {
+                continue;
+            }
+            let xy = &entry[0..2];
+
+            if xy.contains('R') || xy.contains('C') {
+                fields.next();
+                summary.staged += 1;
+                continue;
+            }
+
+            match xy {
+                "??" => summary.untracked += 1,
+                "!!" => {}
+                "UU" | "AA" | "DD" | "AU" | "UA" | "UD" | "DU" => summary.conflicted += 1,
+                _ => {
+                    let mut chars = xy.chars();
+                    let index = chars.next().unwrap_or(' ');
+                    let worktree = chars.next().unwrap_or(' ');
+                    if index != ' ' {
+                        summary.staged += 1;
+                    }
+                    match worktree {
+                        'M' => summary.modified += 1,
+                        'D' => summary.deleted += 1,
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+
     /// Format the git information as a string for inclusion in the archive.
     pub fn format(&self) -> String {
         let mut parts = Vec::new();
@@ -212,4 +638,45 @@ mod tests {
         let git_info = GitInfo::from_path(repo_path).unwrap();
         assert!(git_info.has_uncommitted_changes);
     }
+
+    #[test]
+    fn test_file_statuses() {
+        let dir = tempdir().unwrap();
+        let repo_path = dir.path();
+
+        Command::new("git").args(["init"]).current_dir(repo_path).status().unwrap();
+        Command::new("git")
+            .args(["config", "user.name", "Test User"])
+            .current_dir(repo_path)
+            .status()
+            .unwrap();
+        Command::new("git")
+            .args(["config", "user.email", "test@example.com"])
+            .current_dir(repo_path)
+            .status()
+            .unwrap();
+
+        std::fs::write(repo_path.join("committed.txt"), "original").unwrap();
+        Command::new("git").args(["add", "."]).current_dir(repo_path).status().unwrap();
+        Command::new("git")
+            .args(["commit", "-m", "Initial commit"])
+            .current_dir(repo_path)
+            .status()
+            .unwrap();
+
+        // Modified in the worktree, not staged.
+        std::fs::write(repo_path.join("committed.txt"), "changed").unwrap();
+        // Staged.
+        std::fs::write(repo_path.join("staged.txt"), "new").unwrap();
+        Command::new("git").args(["add", "staged.txt"]).current_dir(repo_path).status().unwrap();
+        // Untracked.
+        std::fs::write(repo_path.join("untracked.txt"), "new").unwrap();
+
+        let git_info = GitInfo::from_path(repo_path).unwrap();
+        let statuses = git_info.file_statuses().unwrap();
+
+        assert_eq!(statuses.get(&PathBuf::from("committed.txt")), Some(&GitStatus::Modified));
+        assert_eq!(statuses.get(&PathBuf::from("staged.txt")), Some(&GitStatus::Staged));
+        assert_eq!(statuses.get(&PathBuf::from("untracked.txt")), Some(&GitStatus::New));
+    }
 }
@@ -0,0 +1,284 @@
+//! Hierarchical `.gitattributes` handling: `export-ignore` filtering
+//! (mirrors what `git archive` drops from its output) and `text`/`eol`
+//! driven line-ending normalization, layered the same way
+//! [`crate::ignore_stack::IgnoreStack`] layers `.gitignore` files --
+//! nearest directory wins, and patterns are matched relative to the
+//! directory that defines them.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use globset::{Glob, GlobMatcher};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+/// How a `text`/`eol` attribute says a file's line endings should be
+/// normalized before it's handed to the formatter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EolNormalization {
+    /// `eol=lf`, or bare `text` with no `eol` override: normalize to `\n`.
+    Lf,
+    /// `eol=crlf`: normalize to `\r\n`.
+    Crlf,
+}
+
+impl EolNormalization {
+    /// Apply this normalization to `content`, first unifying any existing
+    /// line endings to `\n` so the result is consistent regardless of what
+    /// was on disk.
+    pub fn normalize(self, content: &str) -> String {
+        let unified = content.replace("\r\n", "\n");
+        match self {
+            EolNormalization::Lf => unified,
+            EolNormalization::Crlf => unified.replace('\n', "\r\n"),
+        }
+    }
+}
+
+/// One directory's own `.gitattributes`, parsed once and cached.
+#[derive(Debug, Default)]
+struct DirAttributes {
+    /// Compiled like `IgnoreStack`'s `.gitignore` layers: `export-ignore`
+    /// lines become ignore patterns, `-export-ignore` lines become
+    /// `!`-negations, so the same nearest-match-wins `Gitignore` semantics
+    /// apply.
+    export_ignore: Option<Gitignore>,
+    /// `text`/`eol=lf`/`eol=crlf` rules found in this file, in file order;
+    /// matched last-to-first so a later line overrides an earlier one for
+    /// the same path, mirroring real `.gitattributes` resolution.
+    eol_rules: Vec<(GlobMatcher, EolNormalization)>,
+}
+
+/// A stack of `.gitattributes` matchers rooted at an archive's input
+/// directory. See the module docs for precedence.
+#[derive(Debug)]
+pub struct GitAttributesStack {
+    root: PathBuf,
+    /// Per-directory attributes, built lazily and cached the first time
+    /// each directory is consulted. `Arc` lets a cache hit hand back a
+    /// cheap clone without requiring every field inside `DirAttributes`
+    /// to implement `Clone`.
+    layers: Mutex<HashMap<PathBuf, Option<Arc<DirAttributes>>>>,
+}
+
+impl GitAttributesStack {
+    /// Build a stack rooted at `root`.
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            layers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether `path` carries `export-ignore`, exactly as `git archive`
+    /// would drop it from the output. Every directory from `path`'s parent
+    /// up to `root` is checked nearest-first; the first rule that matches
+    /// either way (ignore or `-export-ignore` whitelist) wins.
+    pub fn is_export_ignored(&self, path: &Path) -> bool {
+        let is_dir = path.is_dir();
+        let mut dir = path.parent();
+
+        while let Some(d) = dir {
+            if let Some(attrs) = self.layer_for(d) {
+                if let Some(matcher) = &attrs.export_ignore {
+                    let m = matcher.matched(path, is_dir);
+                    if m.is_ignore() {
+                        return true;
+                    }
+                    if m.is_whitelist() {
+                        return false;
+                    }
+                }
+            }
+
+            if d == self.root {
+                break;
+            }
+            dir = d.parent();
+        }
+
+        false
+    }
+
+    /// The `text`/`eol` normalization `path` should receive, if any
+    /// `.gitattributes` between it and `root` assigns one. Checked
+    /// nearest-directory-first, same as `is_export_ignored`.
+    pub fn eol_normalization(&self, path: &Path) -> Option<EolNormalization> {
+        let mut dir = path.parent();
+
+        while let Some(d) = dir {
+            if let Some(attrs) = self.layer_for(d) {
+                let relative = path.strip_prefix(d).unwrap_or(path);
+                if let Some((_, normalization)) = attrs
+                    .eol_rules
+                    .iter()
+                    .rev()
+                    .find(|(matcher, _)| matcher.is_match(relative))
+                {
+                    return Some(*normalization);
+                }
+            }
+
+            if d == self.root {
+                break;
+            }
+            dir = d.parent();
+        }
+
+        None
+    }
+
+    /// Build (and cache) the parsed `.gitattributes` for a single
+    /// directory, if it has one.
+    fn layer_for(&self, dir: &Path) -> Option<Arc<DirAttributes>> {
+        if let Some(cached) = self.layers.lock().unwrap().get(dir) {
+            return cached.clone();
+        }
+
+        let candidate = dir.join(".gitattributes");
+        let attrs = candidate
+            .is_file()
+            .then(|| std::fs::read_to_string(&candidate).ok())
+            .flatten()
+            .map(|contents| Arc::new(parse_gitattributes(dir, &contents)));
+
+        self.layers.lock().unwrap().insert(dir.to_path_buf(), attrs.clone());
+        attrs
+    }
+}
+
+/// Parse one `.gitattributes` file's contents into its export-ignore
+/// matcher and ordered eol rules.
+fn parse_gitattributes(dir: &Path, contents: &str) -> DirAttributes {
+    let mut export_ignore_builder = GitignoreBuilder::new(dir);
+    let mut has_export_ignore = false;
+    let mut eol_rules = Vec::new();
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        let Some(pattern) = parts.next() else {
+            continue;
+        };
+
+        for attr in parts {
+            match attr {
+                "export-ignore" => {
+                    has_export_ignore = true;
+                    let _ = export_ignore_builder.add_line(None, pattern);
+                }
+                "-export-ignore" => {
+                    has_export_ignore = true;
+                    let _ = export_ignore_builder.add_line(None, &format!("!{pattern}"));
+                }
+                "text" => {
+                    if let Some(matcher) = compile_matcher(pattern) {
+                        eol_rules.push((matcher, EolNormalization::Lf));
+                    }
+                }
+                "eol=lf" => {
+                    if let Some(matcher) = compile_matcher(pattern) {
+                        eol_rules.push((matcher, EolNormalization::Lf));
+                    }
+                }
+                "eol=crlf" => {
+                    if let Some(matcher) = compile_matcher(pattern) {
+                        eol_rules.push((matcher, EolNormalization::Crlf));
+                    }
+                }
+                // `-text`, `binary`, and any other attribute don't affect
+                // export-ignore or eol normalization, so they're ignored here.
+                _ => {}
+            }
+        }
+    }
+
+    DirAttributes {
+        export_ignore: has_export_ignore.then(|| export_ignore_builder.build().ok()).flatten(),
+        eol_rules,
+    }
+}
+
+/// Compile a `.gitattributes` pattern into a matcher tested against a path
+/// relative to the attributes file's own directory. An unanchored pattern
+/// (no `/`) matches at any depth beneath that directory, mirroring
+/// gitignore pattern semantics.
+fn compile_matcher(pattern: &str) -> Option<GlobMatcher> {
+    let anchored = if pattern.contains('/') {
+        pattern.trim_start_matches('/').to_string()
+    } else {
+        format!("**/{pattern}")
+    };
+    Glob::new(&anchored).ok().map(|g| g.compile_matcher())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_export_ignore_matches_file() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join(".gitattributes"), "secrets.txt export-ignore\n").unwrap();
+        fs::write(dir.path().join("secrets.txt"), "shh").unwrap();
+
+        let stack = GitAttributesStack::new(dir.path());
+        assert!(stack.is_export_ignored(&dir.path().join("secrets.txt")));
+    }
+
+    #[test]
+    fn test_export_ignore_does_not_match_other_files() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join(".gitattributes"), "secrets.txt export-ignore\n").unwrap();
+        fs::write(dir.path().join("readme.txt"), "hi").unwrap();
+
+        let stack = GitAttributesStack::new(dir.path());
+        assert!(!stack.is_export_ignored(&dir.path().join("readme.txt")));
+    }
+
+    #[test]
+    fn test_negated_export_ignore_overrides_broader_pattern() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join(".gitattributes"),
+            "*.generated export-ignore\nkeep.generated -export-ignore\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("drop.generated"), "x").unwrap();
+        fs::write(dir.path().join("keep.generated"), "x").unwrap();
+
+        let stack = GitAttributesStack::new(dir.path());
+        assert!(stack.is_export_ignored(&dir.path().join("drop.generated")));
+        assert!(!stack.is_export_ignored(&dir.path().join("keep.generated")));
+    }
+
+    #[test]
+    fn test_eol_lf_normalizes_crlf_content() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join(".gitattributes"), "*.txt text eol=lf\n").unwrap();
+        let path = dir.path().join("file.txt");
+        fs::write(&path, "a\r\nb\r\n").unwrap();
+
+        let stack = GitAttributesStack::new(dir.path());
+        let normalization = stack.eol_normalization(&path).unwrap();
+        assert_eq!(normalization, EolNormalization::Lf);
+        assert_eq!(normalization.normalize("a\r\nb\r\n"), "a\nb\n");
+    }
+
+    #[test]
+    fn test_no_gitattributes_means_no_normalization() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("file.txt");
+        fs::write(&path, "a\r\n").unwrap();
+
+        let stack = GitAttributesStack::new(dir.path());
+        assert!(stack.eol_normalization(&path).is_none());
+        assert!(!stack.is_export_ignored(&path));
+    }
+}
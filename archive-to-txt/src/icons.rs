@@ -0,0 +1,149 @@
+//! Extension/filename → icon glyph map for the decorated tree renderer
+//! (`tree::format_tree_with_icons`), in the VSCode-icon-theme style: exact
+//! filenames (e.g. `Dockerfile`, `Makefile`) are checked first, then
+//! double extensions (e.g. `.tar.gz`, `.d.ts`), then single extensions,
+//! with a fallback by MIME group for anything not in any of the maps --
+//! so images/audio/video/data files all get a sensible default icon
+//! without enumerating every extension by hand. See
+//! `crate::binary::classify_mime_group`.
+//!
+//! Glyphs here are plain emoji rather than Nerd Font codepoints, so they
+//! render recognizably in any terminal; swap in Nerd Font glyphs by
+//! editing these tables if `--tree-icons` is used with a Nerd Font.
+
+use std::path::Path;
+
+use crate::binary::{classify_mime_group, MimeGroup};
+
+/// Exact file-name matches, checked first -- files like `Dockerfile` that
+/// have no extension worth keying off of.
+const ICON_BY_FILENAME: &[(&str, &str)] = &[
+    ("Dockerfile", "🐳"),
+    ("Makefile", "🔧"),
+    ("CMakeLists.txt", "🔧"),
+    ("LICENSE", "📜"),
+    ("README.md", "📖"),
+    (".gitignore", "🙈"),
+    (".gitattributes", "🙈"),
+];
+
+/// Double-extension matches, checked before the single-extension table so
+/// the more specific entry wins (e.g. `archive.tar.gz` gets the archive
+/// icon, not whatever `.gz` alone would map to).
+const ICON_BY_DOUBLE_EXTENSION: &[(&str, &str)] = &[
+    ("tar.gz", "📦"),
+    ("tar.bz2", "📦"),
+    ("tar.xz", "📦"),
+    ("d.ts", "📘"),
+    ("min.js", "📜"),
+];
+
+/// Single-extension matches.
+const ICON_BY_EXTENSION: &[(&str, &str)] = &[
+    ("rs", "🦀"),
+    ("py", "🐍"),
+    ("js", "📜"),
+    ("jsx", "📜"),
+    ("ts", "📘"),
+    ("tsx", "📘"),
+    ("go", "🐹"),
+    ("java", "☕"),
+    ("rb", "💎"),
+    ("php", "🐘"),
+    ("md", "📝"),
+    ("json", "⚙️"),
+    ("yaml", "⚙️"),
+    ("yml", "⚙️"),
+    ("toml", "⚙️"),
+    ("lock", "🔒"),
+    ("sh", "💻"),
+    ("html", "🌐"),
+    ("css", "🎨"),
+];
+
+/// Icon shown for every directory, regardless of name.
+const DIRECTORY_ICON: &str = "📁";
+
+/// Fallback icon when nothing more specific matched, chosen from the
+/// file's coarse MIME group.
+fn icon_for_mime_group(group: MimeGroup) -> &'static str {
+    match group {
+        MimeGroup::Text => "📄",
+        MimeGroup::Code => "💻",
+        MimeGroup::Config => "⚙️",
+        MimeGroup::Image => "🖼️",
+        MimeGroup::Audio => "🎵",
+        MimeGroup::Video => "🎬",
+        MimeGroup::Archive => "📦",
+        MimeGroup::Data => "📊",
+    }
+}
+
+/// Generic default when even MIME-group classification comes up empty.
+const DEFAULT_FILE_ICON: &str = "📄";
+
+/// Pick an icon glyph for a tree entry named `name` at `path`. Checked in
+/// order: exact filename, double extension, single extension, MIME-group
+/// fallback, generic default. Directories always get [`DIRECTORY_ICON`].
+pub fn icon_for(name: &str, path: &Path, is_dir: bool) -> &'static str {
+    if is_dir {
+        return DIRECTORY_ICON;
+    }
+
+    if let Some((_, icon)) = ICON_BY_FILENAME.iter().find(|(n, _)| *n == name) {
+        return icon;
+    }
+
+    let lower = name.to_lowercase();
+    if let Some((_, icon)) = ICON_BY_DOUBLE_EXTENSION
+        .iter()
+        .find(|(ext, _)| lower.ends_with(&format!(".{ext}")))
+    {
+        return icon;
+    }
+
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        let ext_lower = ext.to_lowercase();
+        if let Some((_, icon)) = ICON_BY_EXTENSION.iter().find(|(e, _)| *e == ext_lower) {
+            return icon;
+        }
+    }
+
+    classify_mime_group(path)
+        .map(icon_for_mime_group)
+        .unwrap_or(DEFAULT_FILE_ICON)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn exact_filename_beats_everything_else() {
+        assert_eq!(icon_for("Dockerfile", Path::new("Dockerfile"), false), "🐳");
+    }
+
+    #[test]
+    fn double_extension_beats_single_extension() {
+        let path = PathBuf::from("archive.tar.gz");
+        assert_eq!(icon_for("archive.tar.gz", &path, false), "📦");
+    }
+
+    #[test]
+    fn single_extension_matches() {
+        let path = PathBuf::from("main.rs");
+        assert_eq!(icon_for("main.rs", &path, false), "🦀");
+    }
+
+    #[test]
+    fn unmapped_extension_falls_back_to_mime_group() {
+        let path = PathBuf::from("photo.png");
+        assert_eq!(icon_for("photo.png", &path, false), "🖼️");
+    }
+
+    #[test]
+    fn directories_always_get_the_directory_icon() {
+        assert_eq!(icon_for("src", Path::new("src"), true), "📁");
+    }
+}
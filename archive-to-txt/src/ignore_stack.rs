@@ -0,0 +1,100 @@
+//! Hierarchical `.gitignore`/`.ignore`/`.promptignore` handling, with the
+//! compiled built-in LLM-ignore defaults folded in as an overridable base
+//! layer.
+//!
+//! Unlike a flat pattern list, this mirrors real gitignore semantics: each
+//! directory between the archive root and a candidate path gets its own
+//! [`Gitignore`] matcher when it has one of the three ignore files present,
+//! and a path is tested against the resulting stack nearest-ancestor-first
+//! so a deeper rule -- including a `!`-negation -- takes precedence over a
+//! shallower one, with the built-in defaults (see
+//! `Config::default_llm_ignore_matcher`) checked last as the least
+//! specific, most easily overridden layer.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use globset::GlobSet;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+/// Ignore file names consulted in each directory, checked in this order.
+const IGNORE_FILE_NAMES: &[&str] = &[".gitignore", ".ignore", ".promptignore"];
+
+/// A stack of gitignore-style matchers rooted at an archive's input
+/// directory, plus a base layer matched against the compiled built-in LLM
+/// ignore patterns. See the module docs for the precedence rules.
+#[derive(Debug)]
+pub struct IgnoreStack {
+    root: PathBuf,
+    base: Option<&'static GlobSet>,
+    /// Per-directory matchers, built lazily and cached the first time each
+    /// directory is consulted.
+    layers: RefCell<HashMap<PathBuf, Option<Gitignore>>>,
+}
+
+impl IgnoreStack {
+    /// Build a stack rooted at `root`, with `base` forming the overridable
+    /// base layer (typically `Config::default_llm_ignore_matcher()`'s
+    /// result, pre-compiled so every check here is a single `GlobSet`
+    /// lookup rather than walking the raw pattern list).
+    pub fn new(root: impl AsRef<Path>, base: Option<&'static GlobSet>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            base,
+            layers: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Whether `path` (somewhere under `root`) is ignored. Every directory
+    /// from `path`'s parent up to `root` is checked nearest-first; the
+    /// first rule that matches either way (ignore or `!`-whitelist) wins,
+    /// falling back to the base layer only when no directory-level rule
+    /// matched at all.
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        let is_dir = path.is_dir();
+        let mut dir = path.parent();
+
+        while let Some(d) = dir {
+            if let Some(matcher) = self.layer_for(d) {
+                let m = matcher.matched(path, is_dir);
+                if m.is_ignore() {
+                    return true;
+                }
+                if m.is_whitelist() {
+                    return false;
+                }
+            }
+
+            if d == self.root {
+                break;
+            }
+            dir = d.parent();
+        }
+
+        self.base.is_some_and(|base| base.is_match(path))
+    }
+
+    /// Build (and cache) the matcher for a single directory's own
+    /// `.gitignore`/`.ignore`/`.promptignore`, if it has any of them.
+    fn layer_for(&self, dir: &Path) -> Option<Gitignore> {
+        if let Some(cached) = self.layers.borrow().get(dir) {
+            return cached.clone();
+        }
+
+        let mut builder = GitignoreBuilder::new(dir);
+        let mut found = false;
+        for name in IGNORE_FILE_NAMES {
+            let candidate = dir.join(name);
+            if candidate.is_file() && builder.add(&candidate).is_none() {
+                found = true;
+            }
+        }
+
+        let matcher = if found { builder.build().ok() } else { None };
+        self.layers
+            .borrow_mut()
+            .insert(dir.to_path_buf(), matcher.clone());
+        matcher
+    }
+}
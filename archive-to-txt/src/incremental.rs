@@ -0,0 +1,193 @@
+//! Content-hash incremental archiving: skip re-embedding a file's content
+//! when it matches what the previous run recorded for that same path, and
+//! store identical content once even when it appears at several paths.
+//!
+//! Enabled via `Config::with_incremental`, which points at a small JSON
+//! sidecar manifest (distinct from the Parquet `--manifest` walk log in
+//! `crate::manifest`) mapping each archived path to its content hash.
+//! `ArchiveEngine::run` loads it at the start of a run, consults it per
+//! file through [`IncrementalTracker::classify`], and writes an updated
+//! copy back out once the run finishes.
+//!
+//! An `unchanged`/`duplicate-of` entry carries no content of its own (see
+//! `formatter::Formatter::format_unchanged_file`/`format_duplicate_file`),
+//! so `parser::text::PlainTextParser` can't restore it on extraction --
+//! the sidecar manifest, not the archive, is the source of truth for
+//! those files' bytes.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::Result as ArchiveResult;
+
+/// One file's content hash and size as of the run that last embedded it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncrementalEntry {
+    /// Hex-encoded SHA-256 of the file's raw bytes.
+    pub hash: String,
+    /// Size in bytes, recorded alongside the hash for quick human
+    /// inspection of the sidecar file; not itself used for comparison.
+    pub size: u64,
+}
+
+/// The sidecar manifest `IncrementalTracker` loads at the start of a run
+/// and saves at the end, keyed by path relative to the archived root.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IncrementalManifest {
+    /// Every path archived as of the last run this manifest was saved from.
+    pub entries: HashMap<String, IncrementalEntry>,
+}
+
+impl IncrementalManifest {
+    /// Load a manifest from `path`, or an empty one if it doesn't exist
+    /// yet -- the first run of an incremental archive has nothing to diff
+    /// against, so every file is `Added`.
+    pub fn load(path: &Path) -> ArchiveResult<Self> {
+        match File::open(path) {
+            Ok(file) => Ok(serde_json::from_reader(BufReader::new(file))?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(crate::error::ArchiveError::open(e, path)),
+        }
+    }
+
+    /// Write this manifest to `path`, truncating any existing file there.
+    pub fn save(&self, path: &Path) -> ArchiveResult<()> {
+        let file = File::create(path).map_err(|e| crate::error::ArchiveError::create(e, path))?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self)?;
+        Ok(())
+    }
+}
+
+/// Hash a file's raw bytes for incremental comparison.
+pub fn hash_content(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+/// How a file's content compares to the previous manifest and to every
+/// other file already seen so far this run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IncrementalOutcome {
+    /// Not present in the previous manifest; archive it in full.
+    Added,
+    /// Present in the previous manifest under a different hash; archive
+    /// it in full.
+    Changed,
+    /// Same path, same hash as the previous manifest; skip re-embedding
+    /// and emit a compact reference instead.
+    Unchanged,
+    /// Same hash as a file already embedded earlier in this run, at
+    /// `first_path`; emit a reference to it instead of a second copy.
+    DuplicateOf {
+        /// The first path this run that had this same content.
+        first_path: String,
+    },
+}
+
+/// Classifies each archived file against the previous manifest (for
+/// unchanged-skip) and against every other file already seen this run
+/// (for dedup), and accumulates the manifest `ArchiveEngine::run` writes
+/// back out at the end. Shared across parallel workers behind a `Mutex`
+/// (see `ArchiveEngine::incremental`), since `classify` needs `&mut self`.
+#[derive(Debug, Default)]
+pub struct IncrementalTracker {
+    previous: IncrementalManifest,
+    current: IncrementalManifest,
+    seen_hashes: HashMap<String, String>,
+    /// Count of each outcome seen so far, for `ArchiveStats::incremental`.
+    pub summary: IncrementalSummary,
+}
+
+/// Counts of each outcome over a run, reported on `ArchiveStats`.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct IncrementalSummary {
+    /// Files not present in the previous manifest.
+    pub added: usize,
+    /// Files present in the previous manifest under a different hash.
+    pub changed: usize,
+    /// Files whose content matched the previous manifest, skipped instead
+    /// of re-embedded.
+    pub unchanged: usize,
+    /// Files whose content duplicated another file already embedded this
+    /// run, stored once instead of twice.
+    pub duplicates: usize,
+    /// Files in the previous manifest that weren't seen this run, i.e.
+    /// deleted since the last archive.
+    pub removed: usize,
+}
+
+impl IncrementalTracker {
+    /// Load the previous manifest at `path` (or start empty if it doesn't
+    /// exist yet) to diff this run's files against.
+    pub fn load(path: &Path) -> ArchiveResult<Self> {
+        Ok(Self {
+            previous: IncrementalManifest::load(path)?,
+            current: IncrementalManifest::default(),
+            seen_hashes: HashMap::new(),
+            summary: IncrementalSummary::default(),
+        })
+    }
+
+    /// Hash `content` and classify it relative to the previous manifest
+    /// and to every file already classified this run, recording it in
+    /// the manifest this tracker will save at the end of the run.
+    pub fn classify(&mut self, relative_path: &str, content: &[u8]) -> IncrementalOutcome {
+        let hash = hash_content(content);
+
+        let outcome = if let Some(first_path) = self.seen_hashes.get(&hash) {
+            IncrementalOutcome::DuplicateOf {
+                first_path: first_path.clone(),
+            }
+        } else {
+            match self.previous.entries.get(relative_path) {
+                Some(entry) if entry.hash == hash => IncrementalOutcome::Unchanged,
+                Some(_) => IncrementalOutcome::Changed,
+                None => IncrementalOutcome::Added,
+            }
+        };
+
+        match &outcome {
+            IncrementalOutcome::Added => self.summary.added += 1,
+            IncrementalOutcome::Changed => self.summary.changed += 1,
+            IncrementalOutcome::Unchanged => self.summary.unchanged += 1,
+            IncrementalOutcome::DuplicateOf { .. } => self.summary.duplicates += 1,
+        }
+
+        // Only content embedded in full can be the target of a later
+        // `DuplicateOf`; an `Unchanged` file carries no content here.
+        if matches!(outcome, IncrementalOutcome::Added | IncrementalOutcome::Changed) {
+            self.seen_hashes
+                .entry(hash.clone())
+                .or_insert_with(|| relative_path.to_string());
+        }
+        self.current.entries.insert(
+            relative_path.to_string(),
+            IncrementalEntry {
+                hash,
+                size: content.len() as u64,
+            },
+        );
+
+        outcome
+    }
+
+    /// Finish the run: count how many previously-archived paths weren't
+    /// seen this run (deleted since the last archive) and save the
+    /// updated manifest to `path`.
+    pub fn finish(mut self, path: &Path) -> ArchiveResult<IncrementalSummary> {
+        self.summary.removed = self
+            .previous
+            .entries
+            .keys()
+            .filter(|p| !self.current.entries.contains_key(*p))
+            .count();
+        self.current.save(path)?;
+        Ok(self.summary)
+    }
+}
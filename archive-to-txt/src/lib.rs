@@ -47,21 +47,38 @@
 #![allow(clippy::return_self_not_must_use)]
 
 // Public modules
+pub mod adapters;
+pub mod binary;
 pub mod config;
+pub mod encoding;
 pub mod error;
+pub mod fd_limit;
+pub mod filetype;
 pub mod formatter;
 pub mod git;
+pub mod gitattributes;
+pub mod icons;
+pub mod ignore_stack;
+pub mod manifest;
+pub mod override_rules;
+pub mod parser;
+pub mod sink;
 pub mod stats;
 pub mod utils;
 pub mod filter;
+pub mod tree;
+pub mod vfs;
+pub mod incremental;
 
-use std::fs::{self, File, Metadata};
-use std::io::{self, BufWriter, Write};
+use std::collections::HashMap;
+use std::fs::{self, Metadata};
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::sync::{
     atomic::{AtomicUsize, Ordering},
     Arc, Mutex,
 };
+use std::thread;
 use std::time::SystemTime;
 
 use chrono::{DateTime, Local};
@@ -71,13 +88,129 @@ use serde::Serialize;
 use walkdir::WalkDir;
 
 use crate::{
+    adapters::ExtractedEntry,
+    binary::{classify_mime_group, detect_language, is_probably_binary},
     config::Config,
+    encoding::decode as decode_content,
     error::{ArchiveError, Result as ArchiveResult},
     formatter::{create_formatter, Formatter as FormatterTrait},
-    git::GitInfo,
-    utils::{format_file_size, format_path, format_timestamp},
+    git::{GitInfo, GitStatus},
+    gitattributes::GitAttributesStack,
+    ignore_stack::IgnoreStack,
+    incremental::{self, IncrementalOutcome, IncrementalTracker},
+    manifest::{ManifestRow, ManifestWriter},
+    parser::{create_parser, ParsedFile},
+    vfs::WalkOptions,
+    utils::{estimate_token_count, format_file_size, format_path, format_timestamp},
 };
 
+/// The concrete writer `run` hands to file processing, unifying the
+/// plain and compressed (`--compress gzip`/`zstd`/`bzip2`) cases behind
+/// one `Write` impl, over whatever `Config::output_sink` handed back
+/// (a local file by default, or a remote destination -- see
+/// `crate::sink::Sink`).
+enum OutputWriter {
+    /// Raw text output.
+    Plain(Box<dyn Write + Send>),
+    /// Gzip-compressed output; [`OutputWriter::finish`] must be called to
+    /// write the trailer, which a plain `flush` doesn't do.
+    Gzip(flate2::write::GzEncoder<Box<dyn Write + Send>>),
+    /// Zstd-compressed output; [`OutputWriter::finish`] must be called to
+    /// write the frame epilogue.
+    Zstd(zstd::stream::write::Encoder<'static, Box<dyn Write + Send>>),
+    /// Bzip2-compressed output; [`OutputWriter::finish`] must be called to
+    /// flush the final block.
+    Bzip2(bzip2::write::BzEncoder<Box<dyn Write + Send>>),
+}
+
+impl OutputWriter {
+    /// Finalize the writer: flush a plain writer, or write the trailer
+    /// and flush the inner writer for a compressed one.
+    fn finish(self) -> io::Result<()> {
+        match self {
+            OutputWriter::Plain(mut w) => w.flush(),
+            OutputWriter::Gzip(w) => w.finish().map(|_| ()),
+            OutputWriter::Zstd(w) => w.finish().map(|_| ()),
+            OutputWriter::Bzip2(w) => w.finish().map(|_| ()),
+        }
+    }
+}
+
+impl Write for OutputWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            OutputWriter::Plain(w) => w.write(buf),
+            OutputWriter::Gzip(w) => w.write(buf),
+            OutputWriter::Zstd(w) => w.write(buf),
+            OutputWriter::Bzip2(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            OutputWriter::Plain(w) => w.flush(),
+            OutputWriter::Gzip(w) => w.flush(),
+            OutputWriter::Zstd(w) => w.flush(),
+            OutputWriter::Bzip2(w) => w.flush(),
+        }
+    }
+}
+
+/// A per-worker accumulator used by [`ArchiveEngine::process_files_parallel`]
+/// to batch formatted file buffers before handing them to the output
+/// consumer, so a tree of many small files doesn't pay one channel
+/// synchronization per file.
+struct WriteBatch {
+    tx: crossbeam_channel::Sender<Vec<(usize, Vec<u8>)>>,
+    pending: Vec<(usize, Vec<u8>)>,
+    pending_bytes: usize,
+    threshold: usize,
+}
+
+impl WriteBatch {
+    /// Create a batch that flushes to `tx` once its buffered bytes reach
+    /// `threshold`.
+    fn new(tx: crossbeam_channel::Sender<Vec<(usize, Vec<u8>)>>, threshold: usize) -> Self {
+        Self {
+            tx,
+            pending: Vec::new(),
+            pending_bytes: 0,
+            threshold,
+        }
+    }
+
+    /// Append one file's formatted bytes, then flush the batch (this
+    /// entry included) once its buffered bytes reach `threshold`.
+    fn push(&mut self, index: usize, bytes: Vec<u8>) -> Result<()> {
+        self.pending_bytes += bytes.len();
+        self.pending.push((index, bytes));
+        if self.pending_bytes >= self.threshold {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Send every buffered entry to the consumer, if any are buffered.
+    fn flush(&mut self) -> Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let batch = std::mem::take(&mut self.pending);
+        self.pending_bytes = 0;
+        self.tx
+            .send(batch)
+            .map_err(|_| ArchiveError::Other("Output consumer thread exited early".to_string()))
+    }
+}
+
+impl Drop for WriteBatch {
+    /// Flush any remainder that never crossed `threshold`, so the last
+    /// partial batch from each worker still reaches the consumer.
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
 /// Statistics about the archiving process
 #[derive(Debug, Default, Clone, Serialize)]
 pub struct ArchiveStats {
@@ -97,6 +230,45 @@ pub struct ArchiveStats {
     /// Timestamp when the archive was created (ISO 8601 format)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timestamp: Option<String>,
+    /// Name of the compression codec applied to the output, if any
+    /// (`"gzip"`, `"zstd"`, `"bzip2"`; see `config::OutputCompression`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compression_codec: Option<&'static str>,
+    /// Size of the output file on disk, in bytes, after compression.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compressed_size: Option<u64>,
+    /// Total size of file content written before compression, in bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uncompressed_size: Option<u64>,
+    /// Counts of added/changed/unchanged/duplicate/removed files, present
+    /// when `config.incremental_manifest` is set. See `crate::incremental`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub incremental: Option<incremental::IncrementalSummary>,
+}
+
+/// The outcome of [`ArchiveEngine::run`], pairing the usual [`ArchiveStats`]
+/// with every per-file error that `Config::continue_on_error` downgraded to
+/// a skip rather than aborting the run. A genuinely fatal error still
+/// short-circuits `run()` via its `Result::Err`, consistent with the rest
+/// of this crate, so there's no separate "fatal" slot here -- a caller that
+/// wants every skip folded into one error can pass `report.skipped_errors()`
+/// to [`ArchiveError::multiple`].
+#[derive(Debug, Default)]
+pub struct ArchiveReport {
+    /// Statistics about the files that did archive successfully.
+    pub stats: ArchiveStats,
+    /// Files that failed to process but were skipped rather than aborting
+    /// the run, in the order they were encountered. Only non-empty when
+    /// `Config::continue_on_error` is set.
+    pub skipped: Vec<(PathBuf, ArchiveError)>,
+}
+
+impl ArchiveReport {
+    /// The skipped errors alone, in the shape [`ArchiveError::multiple`]
+    /// expects.
+    pub fn skipped_errors(&self) -> impl Iterator<Item = &ArchiveError> {
+        self.skipped.iter().map(|(_, e)| e)
+    }
 }
 
 /// The main archive engine that handles the archiving process.
@@ -118,9 +290,9 @@ pub struct ArchiveStats {
 ///     .with_include_extensions("rs,toml,md");
 ///
 /// let engine = ArchiveEngine::new(config);
-/// let stats = engine.run().expect("Failed to create archive");
+/// let report = engine.run().expect("Failed to create archive");
 ///
-/// println!("Archived {} files ({} bytes)", stats.files_processed, stats.total_size);
+/// println!("Archived {} files ({} bytes)", report.stats.files_processed, report.stats.total_size);
 /// ```
 #[derive(Debug)]
 pub struct ArchiveEngine {
@@ -128,8 +300,24 @@ pub struct ArchiveEngine {
     config: Config,
     /// Git repository information (if available)
     git_info: Option<GitInfo>,
+    /// Per-file working-tree status, keyed by repo-relative path, built
+    /// once from `git_info` when `config.git_file_status` is set (see
+    /// `GitInfo::file_statuses`).
+    git_status: Option<HashMap<PathBuf, GitStatus>>,
+    /// Repo-relative paths changed since `config.changed_since`'s
+    /// baseline (see `GitInfo::changed_since`), used as an additional
+    /// walk filter. `None` when `changed_since` isn't set.
+    changed_files: Option<std::collections::HashSet<PathBuf>>,
+    /// Hierarchical `.gitattributes` handling (export-ignore, text/eol
+    /// normalization), built once when `config.respect_git_attributes` is
+    /// set so both collection and per-file processing share one cache.
+    attributes: Option<GitAttributesStack>,
     /// Statistics about the archiving process
     stats: ArchiveStats,
+    /// Content-hash diff/dedup state for `config.incremental_manifest`
+    /// (see `crate::incremental`), shared across parallel workers behind
+    /// a `Mutex` since `process_single_file_to_buffer` only has `&self`.
+    incremental: Option<Mutex<IncrementalTracker>>,
 }
 
 impl ArchiveEngine {
@@ -156,18 +344,107 @@ impl ArchiveEngine {
     /// // Create a new archive engine with the configuration
     /// let engine = ArchiveEngine::new(config).expect("Failed to initialize archive engine");
     /// ```
-    pub fn new(config: Config) -> Result<Self> {
+    pub fn new(mut config: Config) -> Result<Self> {
+        // `--rev`/`with_revision`: resolve the refspec to a tree via
+        // `vfs::GitTreeSource` and swap it in as `file_source`, so the
+        // rest of the engine (walking, filtering, reading) runs exactly
+        // as it does against the real filesystem, just backed by a
+        // commit's blobs instead. Only available with `git2-backend`,
+        // the feature `GitTreeSource` itself requires.
+        #[cfg(feature = "git2-backend")]
+        let resolved_revision = if let Some(refspec) = &config.revision {
+            let source = crate::vfs::GitTreeSource::open(&config.input, refspec)
+                .map_err(|e| ArchiveError::Other(format!("Failed to resolve revision {refspec:?}: {e}")))?;
+            let commit_id = source.commit_id();
+            config.file_source = std::sync::Arc::new(source);
+            Some(commit_id)
+        } else {
+            None
+        };
+        #[cfg(not(feature = "git2-backend"))]
+        if config.revision.is_some() {
+            return Err(ArchiveError::Config(
+                "Config::revision (--rev) requires the `git2-backend` feature".to_string(),
+            ));
+        }
+
         // Initialize git info if git is enabled
-        let git_info = if config.git_info {
+        let mut git_info = if config.git_info {
             GitInfo::from_path(&config.input).ok()
         } else {
             None
         };
 
+        // Report the resolved revision, not `HEAD`, when archiving a
+        // specific commit/tag/branch rather than the working tree.
+        #[cfg(feature = "git2-backend")]
+        if let (Some(info), Some(commit_id)) = (git_info.as_mut(), resolved_revision) {
+            info.set_resolved_commit(commit_id.to_string());
+        }
+
+        let git_status = if config.git_file_status {
+            git_info.as_ref().and_then(|info| info.file_statuses().ok())
+        } else {
+            None
+        };
+
+        let changed_files = match (&config.changed_since, &git_info) {
+            (Some(baseline), Some(info)) => {
+                #[cfg(feature = "git2-backend")]
+                {
+                    Some(info.changed_since(baseline).map_err(|e| {
+                        ArchiveError::Other(format!("Failed to diff against baseline {baseline:?}: {e}"))
+                    })?)
+                }
+                #[cfg(not(feature = "git2-backend"))]
+                {
+                    // No `git2-backend` means no tree-vs-workdir diff, so
+                    // `baseline` can't be resolved to an arbitrary
+                    // revision here -- fall back to the porcelain status
+                    // set (everything not `Unmodified`/`Ignored`), which
+                    // is always relative to `HEAD` regardless of what was
+                    // asked for.
+                    warn!(
+                        "Config::changed_since (--since {baseline:?}) requires the `git2-backend` feature to diff \
+                         against an arbitrary baseline; falling back to the working tree's status against HEAD"
+                    );
+                    Some(
+                        info.file_statuses()
+                            .map_err(|e| ArchiveError::Other(format!("Failed to collect file statuses: {e}")))?
+                            .into_iter()
+                            .filter(|(_, status)| !matches!(status, GitStatus::Unmodified | GitStatus::Ignored))
+                            .map(|(path, _)| path)
+                            .collect(),
+                    )
+                }
+            }
+            (Some(_), None) => {
+                return Err(ArchiveError::Config(
+                    "Config::changed_since (--since) requires a resolvable git repository".to_string(),
+                ));
+            }
+            (None, _) => None,
+        };
+
+        let attributes = config
+            .respect_git_attributes
+            .then(|| GitAttributesStack::new(&config.input));
+
+        let incremental = config
+            .incremental_manifest
+            .as_deref()
+            .map(IncrementalTracker::load)
+            .transpose()?
+            .map(Mutex::new);
+
         Ok(Self {
             config,
             git_info,
+            git_status,
+            changed_files,
+            attributes,
             stats: ArchiveStats::default(),
+            incremental,
         })
     }
 
@@ -178,8 +455,9 @@ impl ArchiveEngine {
     ///
     /// # Returns
     ///
-    /// Returns `Ok(ArchiveStats)` containing statistics about the archiving process,
-    /// or an `ArchiveError` if the operation fails.
+    /// Returns `Ok(ArchiveReport)` containing statistics about the archiving
+    /// process plus any per-file errors that were skipped rather than
+    /// aborting the run, or an `ArchiveError` if the operation fails.
     ///
     /// # Errors
     ///
@@ -199,51 +477,117 @@ impl ArchiveEngine {
     ///     .with_output("./archive.txt");
     ///
     /// let mut engine = ArchiveEngine::new(config)?;
-    /// let stats = engine.run()?;
+    /// let report = engine.run()?;
     ///
     /// println!("Archived {} files ({} bytes) in {:?}",
-    ///     stats.files_processed,
-    ///     stats.total_size,
-    ///     stats.duration
+    ///     report.stats.files_processed,
+    ///     report.stats.total_size,
+    ///     report.stats.duration
     /// );
     /// ```
-    pub fn run(&mut self) -> Result<ArchiveStats> {
+    pub fn run(&mut self) -> Result<ArchiveReport> {
         let start_time = Instant::now();
         let mut stats = ArchiveStats::default();
 
-        // Create output directory if it doesn't exist
-        if let Some(parent) = self.config.output.parent() {
-            if !parent.exists() {
-                std::fs::create_dir_all(parent)
-                    .map_err(|e| ArchiveError::io_error(e, "Failed to create output directory"))?;
-            }
-        }
-
-        // Create the output file
-        let output_file = File::create(&self.config.output)
-            .map_err(|e| ArchiveError::io_error(e, format!("Failed to create output file: {:?}", self.config.output)))?;
-
-        // Create a buffered writer for better performance
-        let writer = BufWriter::new(output_file);
+        // Open a writer for the archive through `Config::output_sink`
+        // (a local file by default, or a remote destination -- see
+        // `crate::sink::Sink`), optionally wrapped in a streaming
+        // compressor so the rest of the pipeline writes through a
+        // single `Write` impl regardless of `--compress`. `Auto`
+        // resolves to a concrete codec here, from the output path's
+        // extension, since that's only decided once
+        // `with_compression`/`with_output` have both been applied.
+        let sink_writer = self
+            .config
+            .output_sink
+            .create(&self.config.output.to_string_lossy())
+            .map_err(|e| ArchiveError::open(e, &self.config.output))?;
+        let resolved_compression = match self.config.compression {
+            config::OutputCompression::Auto => match self.config.output.extension().and_then(|e| e.to_str()) {
+                Some("gz") => config::OutputCompression::Gzip(6),
+                Some("zst") => config::OutputCompression::Zstd(3),
+                Some("bz2") => config::OutputCompression::Bzip2(6),
+                _ => config::OutputCompression::None,
+            },
+            other => other,
+        };
+        let codec_name = match resolved_compression {
+            config::OutputCompression::None => None,
+            config::OutputCompression::Gzip(_) => Some("gzip"),
+            config::OutputCompression::Zstd(_) => Some("zstd"),
+            config::OutputCompression::Bzip2(_) => Some("bzip2"),
+            config::OutputCompression::Auto => unreachable!("Auto is resolved above"),
+        };
+        let writer = match resolved_compression {
+            config::OutputCompression::None => OutputWriter::Plain(sink_writer),
+            config::OutputCompression::Gzip(level) => OutputWriter::Gzip(
+                flate2::write::GzEncoder::new(sink_writer, flate2::Compression::new(level)),
+            ),
+            config::OutputCompression::Zstd(level) => OutputWriter::Zstd(
+                zstd::stream::write::Encoder::new(sink_writer, level)
+                    .map_err(|e| ArchiveError::create(e, &self.config.output))?,
+            ),
+            config::OutputCompression::Bzip2(level) => OutputWriter::Bzip2(bzip2::write::BzEncoder::new(
+                sink_writer,
+                bzip2::Compression::new(level),
+            )),
+            config::OutputCompression::Auto => unreachable!("Auto is resolved above"),
+        };
         let writer_mutex = Arc::new(Mutex::new(writer));
 
         // Select formatter based on output format
         let formatter = create_formatter(self.config.format);
 
-        // Process files in parallel or sequentially based on configuration
-        if self.config.parallel {
-            self.process_files_parallel(&formatter, &writer_mutex)?;
+        // Process files in parallel or sequentially based on configuration.
+        // Either path returns the per-file errors that `continue_on_error`
+        // downgraded to a skip instead of aborting the run.
+        let skipped = if self.config.parallel {
+            if self.config.raise_fd_limit {
+                fd_limit::raise_soft_limit();
+            }
+            self.process_files_parallel(&formatter, &writer_mutex)?
         } else {
-            self.process_files_sequential(&formatter, &writer_mutex)?;
+            self.process_files_sequential(&formatter, &writer_mutex)?
+        };
+
+        // Flush any remaining output, then finalize the writer -- for
+        // gzip this writes the trailer, which a plain `flush` doesn't do.
+        {
+            let mut writer_guard = writer_mutex.lock().map_err(|e| ArchiveError::Other(e.to_string()))?;
+            writer_guard.flush().map_err(|e| ArchiveError::flush(e, &self.config.output))?;
         }
+        let writer = Arc::try_unwrap(writer_mutex)
+            .map_err(|_| ArchiveError::Other("Output writer still has outstanding references".to_string()))?
+            .into_inner()
+            .map_err(|e| ArchiveError::Other(e.to_string()))?;
+        writer.finish().map_err(|e| ArchiveError::flush(e, &self.config.output))?;
 
-        // Flush any remaining output
-        let mut writer_guard = writer_mutex.lock().map_err(|e| ArchiveError::Other(e.to_string()))?;
-        writer_guard.flush().map_err(|e| ArchiveError::io_error(e, "Failed to flush output"))?;
+        // Record the codec and byte counts so compression's payoff is
+        // visible without reaching for `ls -l` and `gzip -l` by hand.
+        // `compressed_size` is only available for a local-file sink --
+        // a remote `Sink` has no path to `stat`, so it's left `None`.
+        if let Some(codec) = codec_name {
+            stats.compression_codec = Some(codec);
+            stats.compressed_size = std::fs::metadata(&self.config.output).ok().map(|m| m.len());
+            stats.uncompressed_size = Some(self.stats.total_size);
+        }
+
+        // Save the updated incremental manifest now that every file's
+        // been classified, and report the run's added/changed/unchanged/
+        // duplicate/removed counts.
+        if let Some(tracker) = self.incremental.take() {
+            let manifest_path = self
+                .config
+                .incremental_manifest
+                .as_ref()
+                .expect("`incremental` is only Some when `incremental_manifest` is set");
+            let tracker = tracker.into_inner().map_err(|e| ArchiveError::Other(e.to_string()))?;
+            stats.incremental = Some(tracker.finish(manifest_path)?);
+        }
 
         // Calculate and return statistics
         stats.duration = start_time.elapsed();
-        Ok(stats)
+        Ok(ArchiveReport { stats, skipped })
     }
 
     /// Process files sequentially.
@@ -255,17 +599,20 @@ impl ArchiveEngine {
     /// * `writer` - Thread-safe writer for output
     ///
     /// # Returns
-    /// `Result<(), ArchiveError>` indicating success or failure
+    /// The per-file errors that were downgraded to a skip under
+    /// `Config::continue_on_error` rather than aborting the run; a fatal
+    /// error (every other case) is returned as `Err` instead.
     fn process_files_sequential<W: Write + Send + 'static>(
         &self,
         formatter: &dyn FormatterTrait,
         writer: &Arc<Mutex<W>>,
-    ) -> Result<()> {
+    ) -> Result<Vec<(PathBuf, ArchiveError)>> {
         // Collect all files that match the criteria
         let entries = self.collect_files()?;
         let file_count = entries.len();
         let mut processed_count = 0;
-        
+        let mut skipped = Vec::new();
+
         // Process each file
         for path in entries {
             match self.process_single_file(&path, formatter, writer) {
@@ -275,72 +622,142 @@ impl ArchiveEngine {
                     if !self.config.continue_on_error {
                         return Err(e);
                     }
+                    skipped.push((path, e));
                 }
             }
         }
-        
+
         info!("Processed {} of {} files sequentially", processed_count, file_count);
-        
+
         if processed_count == 0 && file_count > 0 {
             return Err(ArchiveError::Other("No files were processed successfully".to_string()));
         }
-        
-        Ok(())
+
+        Ok(skipped)
     }
 
-    /// Process files in parallel using Rayon's work-stealing thread pool.
+    /// Process files in parallel using Rayon's work-stealing thread pool,
+    /// while keeping the archive's byte layout deterministic.
     ///
-    /// This method distributes file processing across multiple threads for improved
-    /// performance on multi-core systems. Each file is processed independently and
-    /// results are written to the output in a thread-safe manner.
+    /// Each worker formats its file into an in-memory buffer via
+    /// [`Self::process_single_file_to_buffer`] and appends `(index, bytes)`
+    /// to a thread-local [`WriteBatch`] (see `config.write_batch_size`)
+    /// rather than handing it to the consumer one file at a time; the
+    /// batch flushes itself down a bounded channel (capacity
+    /// `config.parallel_channel_capacity`) once it crosses the threshold,
+    /// or when the worker runs out of work and the batch is dropped. This
+    /// collapses the cross-thread handoff for a tree of many small files
+    /// from one synchronization per file down to a handful of larger
+    /// ones. A single consumer thread owns the writer and reassembles
+    /// buffers in `collect_files`'s original order using a small reorder
+    /// map keyed by the next-expected index, flushing every contiguous run
+    /// it can as soon as it arrives. The channel bound caps how many
+    /// batches can queue up at once, so peak memory stays near
+    /// `parallel_channel_capacity * write_batch_size` rather than
+    /// buffering the whole tree, and output is byte-for-byte reproducible
+    /// regardless of thread scheduling.
     ///
     /// # Arguments
     /// * `formatter` - The formatter to use for formatting file contents
     /// * `writer` - Thread-safe writer for output
     ///
     /// # Returns
-    /// `Result<(), ArchiveError>` indicating success or failure
+    /// The per-file errors that were downgraded to a skip under
+    /// `Config::continue_on_error` rather than aborting the run; a fatal
+    /// error (every other case) is returned as `Err` instead.
     ///
     /// # Errors
     /// Returns an error if any file processing fails or if writing to the output fails.
-    ///
     fn process_files_parallel<W: Write + Send + 'static>(
         &self,
         formatter: &dyn FormatterTrait,
         writer: &Arc<Mutex<W>>,
-    ) -> Result<()> {
-        use rayon::prelude::*;
-
-        // Collect all files that match the criteria
+    ) -> Result<Vec<(PathBuf, ArchiveError)>> {
+        // Collect all files that match the criteria; a file's position in
+        // this vec is the index the consumer reassembles output in.
         let entries = self.collect_files()?;
         let file_count = entries.len();
         let processed_count = AtomicUsize::new(0);
-        
-        // Process files in parallel using Rayon
-        let result: Result<(), ArchiveError> = entries.par_iter().try_for_each(|path| {
-            match self.process_single_file(path, formatter, writer) {
-                Ok(_) => {
-                    processed_count.fetch_add(1, Ordering::Relaxed);
-                    Ok(())
-                }
-                Err(e) => {
-                    error!("Error processing file {}: {}", path.display(), e);
-                    if !self.config.continue_on_error {
-                        return Err(e);
+        let write_batch_size = self.config.write_batch_size;
+        let skipped: Mutex<Vec<(PathBuf, ArchiveError)>> = Mutex::new(Vec::new());
+
+        let (tx, rx) = crossbeam_channel::bounded::<Vec<(usize, Vec<u8>)>>(
+            self.config.parallel_channel_capacity,
+        );
+
+        let result: Result<(), ArchiveError> = thread::scope(|scope| {
+            let consumer = scope.spawn(|| -> Result<()> {
+                let mut writer_guard = writer.lock().map_err(|e| {
+                    ArchiveError::Other(format!("Failed to acquire write lock: {e}"))
+                })?;
+
+                // Reorder buffer: a buffer lands here if it arrives ahead
+                // of `next_index`, and is drained once every index up to
+                // it has shown up.
+                let mut pending: HashMap<usize, Vec<u8>> = HashMap::new();
+                let mut next_index = 0usize;
+
+                for batch in rx.iter() {
+                    for (index, bytes) in batch {
+                        pending.insert(index, bytes);
+                    }
+                    while let Some(bytes) = pending.remove(&next_index) {
+                        writer_guard.write_all(&bytes).map_err(|e| {
+                            ArchiveError::write(e, &self.config.output)
+                        })?;
+                        next_index += 1;
                     }
-                    Ok(())
                 }
-            }
+
+                Ok(())
+            });
+
+            // Process files in parallel using Rayon; each worker formats
+            // into its own buffer and appends it to a thread-local batch
+            // rather than synchronizing with the consumer on every file.
+            let produced: Result<(), ArchiveError> = entries.par_iter().enumerate().try_for_each_init(
+                || WriteBatch::new(tx.clone(), write_batch_size),
+                |batch, (index, path)| {
+                    let mut buffer = Vec::new();
+                    match self.process_single_file_to_buffer(path, formatter, &mut buffer, &processed_count) {
+                        Ok(()) => batch.push(index, buffer),
+                        Err(e) => {
+                            error!("Error processing file {}: {}", path.display(), e);
+                            if !self.config.continue_on_error {
+                                return Err(e);
+                            }
+                            skipped
+                                .lock()
+                                .map_err(|e| ArchiveError::Other(format!("Failed to acquire write lock: {e}")))?
+                                .push((path.clone(), e));
+                            Ok(())
+                        }
+                    }
+                },
+            );
+
+            // Drop our sender so the consumer's `rx.iter()` ends once every
+            // worker's batch has been dropped (flushing its remainder),
+            // then wait for the last buffers to flush.
+            drop(tx);
+            let consumed = consumer
+                .join()
+                .map_err(|_| ArchiveError::Other("Output consumer thread panicked".to_string()))?;
+
+            produced.and(consumed)
         });
-        
+
         let processed = processed_count.load(Ordering::Relaxed);
         info!("Processed {} of {} files in parallel", processed, file_count);
-        
+
         if processed == 0 && file_count > 0 {
             return Err(ArchiveError::Other("No files were processed successfully".to_string()));
         }
-        
-        result
+
+        result?;
+        Ok(skipped
+            .into_inner()
+            .map_err(|e| ArchiveError::Other(format!("Failed to acquire write lock: {e}")))?)
     }
 
     /// Collect all files that should be included in the archive based on the configuration.
@@ -354,85 +771,157 @@ impl ArchiveEngine {
     /// # Errors
     /// Returns an error if the input directory cannot be read or if any I/O error occurs.
     fn collect_files(&self) -> Result<Vec<PathBuf>> {
-        let mut entries = Vec::new();
-        let mut walker = WalkBuilder::new(&self.config.input);
-
-        // Configure the walker based on the configuration
-        walker
-            .hidden(!self.config.include_hidden)
-            .follow_links(self.config.follow_links)
-            .git_ignore(self.config.llm_optimize)
-            .git_global(self.config.llm_optimize)
-            .git_exclude(self.config.llm_optimize);
-
-        // Apply max depth if specified
-        if let Some(max_depth) = self.config.max_depth {
-            walker.max_depth(Some(max_depth));
-        }
-
-        // Add custom ignore patterns
-        if let Some(patterns) = &self.config.exclude {
-            for pattern in patterns {
-                walker.add_custom_ignore_filename(pattern);
+        // Delegate the raw tree walk to `Config::file_source` (the real
+        // filesystem by default, see `vfs::StdFsSource`) so archives can
+        // be built from a zip, an in-memory tree, or any other backend
+        // without this method -- or its gitignore/override filtering
+        // below -- knowing the difference. A non-filesystem source has
+        // no real directories to count, so `stats.dirs_processed` is
+        // only tracked for the default `StdFsSource`.
+        let walk_options = WalkOptions {
+            include_hidden: self.config.include_hidden,
+            follow_links: self.config.follow_links,
+            max_depth: self.config.max_depth,
+        };
+        let entries = self
+            .config
+            .file_source
+            .walk(&self.config.input, &walk_options)
+            .map_err(|e| ArchiveError::read_dir(e, &self.config.input))?;
+
+        // The compiled, deduplicated built-in LLM ignore patterns form the
+        // base layer of the ignore stack -- lowest precedence, so a
+        // `.gitignore`/`.ignore`/`.promptignore` found along the way
+        // (including a `!`-negated line) can override it.
+        let llm_ignore_matcher = self
+            .config
+            .llm_optimize
+            .then(Config::default_llm_ignore_matcher)
+            .transpose()?;
+        let ignore_stack = self
+            .config
+            .respect_ignore_files
+            .then(|| IgnoreStack::new(&self.config.input, llm_ignore_matcher));
+
+        // `include`/`exclude` (with `types`/`type_not` folded in) plus any
+        // explicit `override_rules` are compiled once into a single ordered,
+        // last-match-wins rule set -- unrelated to the directory hierarchy,
+        // so it's evaluated separately from the `ignore_stack` above.
+        let override_rules = self.config.compiled_override_rules();
+
+        // Apply additional filters
+        let included_extensions = self.config.get_included_extensions();
+        let included_mime_groups = self.config.get_included_mime_groups();
+        let max_file_size = self.config.max_file_size;
+        let llm_optimize = self.config.llm_optimize;
+
+        // Classify a path against every active filter in the same order
+        // they used to run as sequential early returns below, so the first
+        // one that would have excluded the file also labels it for the
+        // `--manifest` export.
+        let classify = |path: &Path| -> Option<&'static str> {
+            // Filter by the `.gitignore`/`.ignore`/`.promptignore` stack
+            // (with the LLM defaults as its overridable base layer), then
+            // by the compiled include/exclude/override rule set.
+            if let Some(stack) = &ignore_stack {
+                if stack.is_ignored(path) {
+                    return Some(
+                        "ignored by .gitignore/.ignore/.promptignore or built-in LLM defaults",
+                    );
+                }
+            }
+            if let Some(attributes) = &self.attributes {
+                if attributes.is_export_ignored(path) {
+                    return Some("export-ignore (.gitattributes)");
+                }
+            }
+            if !override_rules.is_included(path) {
+                return Some("excluded by include/exclude/type rules");
             }
-        }
 
-        // Add LLM ignore patterns if enabled
-        if self.config.llm_optimize {
-            for pattern in Config::get_default_llm_ignore_patterns() {
-                walker.add_custom_ignore_filename(pattern);
+            // `--since`: drop anything that didn't change relative to
+            // `changed_files`'s baseline. `changed_files` holds
+            // repo-relative paths (see `GitInfo::changed_since`), so
+            // `path` -- generally absolute, from the default
+            // `StdFsSource` -- is first made relative to the repo root.
+            if let Some(changed) = &self.changed_files {
+                let repo_root = self.git_info.as_ref().map(|info| info.root.as_path());
+                let relative = repo_root
+                    .and_then(|root| path.strip_prefix(root).ok())
+                    .unwrap_or(path);
+                if !changed.contains(relative) {
+                    return Some("unchanged relative to --since baseline");
+                }
             }
-        }
 
-        // Build the walker and process entries
-        for entry in walker.build() {
-            match entry {
-                Ok(entry) => {
-                    if entry.file_type().map_or(false, |ft| ft.is_file()) {
-                        entries.push(entry.into_path());
-                    } else if entry.file_type().map_or(false, |ft| ft.is_dir()) {
-                        self.stats.dirs_processed += 1;
-                    }
+            // Drop binary files under LLM-optimized filtering, classified
+            // by guessed MIME type (falling back to a content sniff)
+            // rather than the old hand-maintained extension blocklist --
+            // unless a `ContentAdapter` claims the path by extension, in
+            // which case it's handled later instead of being dropped
+            // here (no bytes have been read yet, so matching is
+            // extension-only at this stage).
+            let adapter_claimed = self.config.content_adapters.find(path, &[]).is_some();
+            if llm_optimize && !adapter_claimed && is_probably_binary(path) {
+                return Some("binary (mime/content sniff)");
+            }
+
+            // Filter by extension if specified
+            if let Some(exts) = &included_extensions {
+                match path.extension().and_then(|e| e.to_str()) {
+                    Some(ext) if exts.contains(&ext.to_lowercase()) => {}
+                    _ => return Some("extension not in --include-extensions"),
                 }
-                Err(e) => {
-                    error!("Error reading directory entry: {}", e);
-                    self.stats.read_errors += 1;
+            }
+
+            // Filter by MIME group if specified, ANDed with the extension
+            // filter above
+            if let Some(groups) = &included_mime_groups {
+                match classify_mime_group(path) {
+                    Some(group) if groups.contains(&group) => {}
+                    _ => return Some("mime group not in --include-type"),
                 }
             }
-        }
 
-        // Apply additional filters
-        let included_extensions = self.config.get_included_extensions();
-        let max_file_size = self.config.max_file_size;
+            // Filter by file size if specified
+            if let (Some(max_size), Ok(metadata)) = (max_file_size, self.config.file_source.metadata(path)) {
+                if metadata.len > max_size {
+                    return Some("exceeds --max-file-size");
+                }
+            }
+
+            None
+        };
+
+        let mut manifest_writer = match &self.config.manifest_output {
+            Some(path) => Some(ManifestWriter::create(path)?),
+            None => None,
+        };
+        let input_root = self.config.input.clone();
 
         let filtered_entries: Vec<_> = entries
             .into_iter()
             .filter(|path| {
-                // Filter by extension if specified
-                if let Some(exts) = &included_extensions {
-                    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                        if !exts.contains(&ext.to_lowercase()) {
-                            self.stats.files_skipped += 1;
-                            return false;
-                        }
-                    } else {
-                        self.stats.files_skipped += 1;
-                        return false;
-                    }
+                let reason = classify(path);
+                if reason.is_some() {
+                    self.stats.files_skipped += 1;
                 }
 
-                // Filter by file size if specified
-                if let (Some(max_size), Ok(metadata)) = (max_file_size, path.metadata()) {
-                    if metadata.len() > max_size {
-                        self.stats.files_skipped += 1;
-                        return false;
+                if let Some(writer) = manifest_writer.as_mut() {
+                    let row = build_manifest_row(&input_root, path, reason);
+                    if let Err(e) = writer.push(row) {
+                        error!("Failed to write manifest row for {}: {}", path.display(), e);
                     }
                 }
 
-                true
+                reason.is_none()
             })
             .collect();
 
+        if let Some(writer) = manifest_writer {
+            writer.finish()?;
+        }
+
         Ok(filtered_entries)
     }
 
@@ -458,6 +947,27 @@ impl ArchiveEngine {
     /// * `formatter` - Formatter to use for the file content
     /// * `writer` - Thread-safe writer for output
     /// * `file_count` - Atomic counter for tracking processed files
+    /// Apply the `.gitattributes`-driven `text`/`eol` clean filter to
+    /// `content`, when `respect_git_attributes` is enabled and some
+    /// `.gitattributes` between `path` and the input root requests a
+    /// normalization. A no-op otherwise, so callers can apply this
+    /// unconditionally right after reading a file.
+    fn apply_eol_normalization(&self, path: &Path, content: String) -> String {
+        match &self.attributes {
+            Some(attributes) => match attributes.eol_normalization(path) {
+                Some(normalization) => normalization.normalize(&content),
+                None => content,
+            },
+            None => content,
+        }
+    }
+
+    /// Resolve `relative_path`'s `GitStatus`, if `config.git_file_status`
+    /// built a status map for this run.
+    fn lookup_git_status(&self, relative_path: &Path) -> Option<GitStatus> {
+        self.git_status.as_ref().and_then(|statuses| statuses.get(relative_path).copied())
+    }
+
     fn process_single_file<W: Write + Send>(
         &self,
         path: &Path,
@@ -467,52 +977,39 @@ impl ArchiveEngine {
     ) -> Result<()> {
         // Check file size limit if specified
         if let Some(max_size) = self.config.max_file_size {
-            let metadata = std::fs::metadata(path).map_err(|e| {
-                ArchiveError::io_error(e, format!("Failed to get metadata for: {}", path.display()))
-            })?;
-            
-            if metadata.len() > max_size {
-                debug!("Skipping large file: {} ({} bytes)", path.display(), metadata.len());
+            let metadata = self.config.file_source.metadata(path)
+                .map_err(|e| ArchiveError::read_metadata(e, path))?;
+
+            if metadata.len > max_size {
+                debug!("Skipping large file: {} ({} bytes)", path.display(), metadata.len);
                 return Ok(());
             }
         }
 
-        // Read file content
-        let content = match std::fs::read_to_string(path) {
-            Ok(content) => content,
-            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
-                // File is not valid UTF-8, read as binary
-                let bytes = std::fs::read(path).map_err(|e| {
-                    ArchiveError::io_error(e, format!("Failed to read file: {}", path.display()))
-                })?;
-                
-                // Try to convert to UTF-8 with replacement characters for invalid sequences
-                String::from_utf8_lossy(&bytes).into_owned()
-            }
-            Err(e) => {
-                return Err(ArchiveError::io_error(
-                    e,
-                    format!("Failed to read file: {}", path.display()),
-                ));
-            }
-        };
+        // Read the file as raw bytes (via `Config::file_source`, the real
+        // filesystem by default) and decode it with BOM detection,
+        // falling back to `config.default_encoding` (or plain UTF-8) when
+        // no BOM is present -- see `crate::encoding::decode`.
+        let bytes = self.config.file_source.read_file(path)
+            .map_err(|e| ArchiveError::read(e, path))?;
+        let decoded = decode_content(&bytes, self.config.default_encoding.as_deref());
+        if decoded.had_errors {
+            debug!(
+                "{}: some bytes were not valid {}; replaced with U+FFFD",
+                path.display(),
+                decoded.encoding
+            );
+        }
+        let content = self.apply_eol_normalization(path, decoded.content);
 
         // Format and write the file content
         let mut writer_guard = writer.lock().map_err(|e| {
-            ArchiveError::io_error(
-                std::io::Error::new(std::io::ErrorKind::Other, e.to_string()),
-                "Failed to acquire write lock",
-            )
+            ArchiveError::Other(format!("Failed to acquire write lock: {e}"))
         })?;
 
         formatter
             .format_file(path, &content, &mut *writer_guard)
-            .map_err(|e| {
-                ArchiveError::io_error(
-                    e,
-                    format!("Failed to format file: {}", path.display()),
-                )
-            })?;
+            .map_err(|e| ArchiveError::write(e, path))?;
 
         // Update statistics
         self.stats.files_processed += 1;
@@ -563,24 +1060,93 @@ impl ArchiveEngine {
         buffer: &mut Vec<u8>,
         file_count: &AtomicUsize,
     ) -> Result<()> {
-        // Read file as binary
-        let bytes = std::fs::read(path)
-            .map_err(ArchiveError::Io)?;
-        
-        // Convert to string, replacing invalid UTF-8 sequences with replacement characters
-        let content = String::from_utf8_lossy(&bytes);
-        
+        // Read file as binary (via `Config::file_source`, the real
+        // filesystem by default), then decode it with BOM detection
+        // (falling back to `config.default_encoding` or plain UTF-8)
+        // instead of a blind lossy UTF-8 read -- see `crate::encoding::decode`.
+        let bytes = self.config.file_source.read_file(path)
+            .map_err(|e| ArchiveError::read(e, path))?;
+
+        let sniff_len = bytes.len().min(4096);
+        if let Some(adapter) = self.config.content_adapters.find(path, &bytes[..sniff_len]) {
+            let entries = adapter.extract(path)?;
+            for ExtractedEntry { virtual_path, content } in entries {
+                // A virtual entry (e.g. a file inside a zip) has no real
+                // filesystem metadata of its own -- `vfs::FileMetadata`'s
+                // default (everything absent) is the honest answer.
+                let formatted = formatter.format_file(
+                    Path::new(&virtual_path),
+                    &content,
+                    "UTF-8",
+                    &vfs::FileMetadata::default(),
+                    self.lookup_git_status(Path::new(&virtual_path)),
+                );
+                buffer.extend_from_slice(formatted.as_bytes());
+                self.stats.files_processed += 1;
+                self.stats.total_size += content.len() as u64;
+                file_count.fetch_add(1, Ordering::Relaxed);
+            }
+            return Ok(());
+        }
+
+        let decoded = decode_content(&bytes, self.config.default_encoding.as_deref());
+        if decoded.had_errors {
+            debug!(
+                "{}: some bytes were not valid {}; replaced with U+FFFD",
+                path.display(),
+                decoded.encoding
+            );
+        }
+        let content = self.apply_eol_normalization(path, decoded.content);
+
         // Get the relative path and convert to string
         let relative_path = path.strip_prefix(&self.config.input)
             .unwrap_or_else(|_| path);
-        
-        // Format the file content
-        let formatted = formatter.format_file(relative_path, &content);
-        
+
+        // Gather mtime/mode (and, with the `xattr` feature, extended
+        // attributes) through `Config::file_source` -- best-effort, since
+        // a real file can vanish between the read above and this lookup.
+        let metadata = self.config.file_source.metadata(path).unwrap_or_default();
+
+        // When `config.incremental_manifest` is set, hash the raw bytes
+        // against the previous run's manifest (and every path already
+        // seen this run) and, if it's unchanged or a duplicate, emit a
+        // compact reference instead of re-embedding the content -- see
+        // `crate::incremental`.
+        let formatted = match &self.incremental {
+            Some(tracker) => {
+                let outcome = tracker
+                    .lock()
+                    .map_err(|e| ArchiveError::Other(e.to_string()))?
+                    .classify(&format_path(relative_path), &bytes);
+                match outcome {
+                    IncrementalOutcome::Unchanged => {
+                        formatter.format_unchanged_file(relative_path, &metadata)
+                    }
+                    IncrementalOutcome::DuplicateOf { first_path } => formatter
+                        .format_duplicate_file(relative_path, Path::new(&first_path), &metadata),
+                    IncrementalOutcome::Added | IncrementalOutcome::Changed => formatter.format_file(
+                        relative_path,
+                        &content,
+                        decoded.encoding,
+                        &metadata,
+                        self.lookup_git_status(relative_path),
+                    ),
+                }
+            }
+            None => formatter.format_file(
+                relative_path,
+                &content,
+                decoded.encoding,
+                &metadata,
+                self.lookup_git_status(relative_path),
+            ),
+        };
+
         // Write to buffer
         buffer.extend_from_slice(formatted.as_bytes());
         file_count.fetch_add(1, Ordering::Relaxed);
-        
+
         Ok(())
     }
     
@@ -622,23 +1188,51 @@ impl ArchiveEngine {
         &self,
         path: &Path,
         content: &str,
+        encoding: &str,
         formatter: &dyn crate::formatter::Formatter,
         writer: &mut dyn Write,
     ) -> Result<()> {
         // Get the relative path and convert to string
         let relative_path = path.strip_prefix(&self.config.input)
             .unwrap_or_else(|_| path);
-        
+
         // Format the file content
-        let formatted = formatter.format_file(relative_path, content);
-        
+        let formatted = formatter.format_file(
+            relative_path,
+            content,
+            encoding,
+            &vfs::FileMetadata::default(),
+            self.lookup_git_status(relative_path),
+        );
+
         // Write to output
         writer.write_all(formatted.as_bytes())
-            .map_err(ArchiveError::Io)?;
-            
+            .map_err(|e| ArchiveError::write(e, path))?;
+
         Ok(())
     }
 
+/// Build one `--manifest` row describing `path`, labeled with `reason` if
+/// some filter excluded it (`None` means it was kept).
+fn build_manifest_row(input_root: &Path, path: &Path, reason: Option<&'static str>) -> ManifestRow {
+    let relative_path = format_path(path.strip_prefix(input_root).unwrap_or(path));
+    let size_bytes = path.metadata().map(|m| m.len()).unwrap_or(0);
+    let mime_type = mime_guess::from_path(path)
+        .first()
+        .map(|m| m.to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    ManifestRow {
+        relative_path,
+        size_bytes,
+        mime_type,
+        language: detect_language(path),
+        approx_tokens: estimate_token_count(size_bytes),
+        included: reason.is_none(),
+        exclusion_reason: reason.map(str::to_string),
+    }
+}
+
 /// Archives the given directory to the specified output file.
 ///
 /// This is a convenience function that creates a new `ArchiveEngine` and runs it.
@@ -682,10 +1276,129 @@ pub fn archive_directory(
     // Create and run the archive engine
     let mut engine = ArchiveEngine::new(config)?;
     engine.run()?;
-    
+
     Ok(())
 }
 
+/// Statistics about an `ExtractEngine` run.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ExtractStats {
+    /// Number of files written to disk.
+    pub files_written: usize,
+    /// Number of intermediate directories created.
+    pub dirs_created: usize,
+    /// Total bytes written across all files.
+    pub bytes_written: u64,
+}
+
+/// The inverse of `ArchiveEngine`: parses a previously-created archive
+/// file back into the directory tree it came from, via the
+/// `ArchiveParser` matching the archive's `OutputFormat`.
+///
+/// # Examples
+/// ```no_run
+/// use archive_to_txt::{ExtractEngine, config::Config};
+///
+/// let config = Config::default();
+/// let mut engine = ExtractEngine::new("./archive.txt", "./restored", &config);
+/// let stats = engine.run().expect("Failed to extract archive");
+/// println!("Restored {} files", stats.files_written);
+/// ```
+#[derive(Debug)]
+pub struct ExtractEngine {
+    archive_path: PathBuf,
+    output_dir: PathBuf,
+    format: config::OutputFormat,
+}
+
+impl ExtractEngine {
+    /// Creates a new `ExtractEngine` reading `archive_path` and writing
+    /// the recovered tree under `output_dir`, using `config.format` to
+    /// select the matching `ArchiveParser`.
+    pub fn new(archive_path: impl Into<PathBuf>, output_dir: impl Into<PathBuf>, config: &Config) -> Self {
+        Self {
+            archive_path: archive_path.into(),
+            output_dir: output_dir.into(),
+            format: config.format,
+        }
+    }
+
+    /// Parses the archive and writes every recovered file under
+    /// `output_dir`, recreating intermediate directories as needed.
+    ///
+    /// # Errors
+    /// Returns an error if the archive can't be read, its framing is
+    /// malformed, or a recovered file can't be written.
+    pub fn run(&mut self) -> Result<ExtractStats> {
+        let archive_bytes = std::fs::read(&self.archive_path)
+            .map_err(|e| ArchiveError::read(e, &self.archive_path))?;
+
+        let parser = create_parser(self.format);
+        let files = parser.parse(&archive_bytes)?;
+
+        let mut stats = ExtractStats::default();
+        let mut created_dirs = std::collections::HashSet::new();
+        for ParsedFile { path, content, modified, mode } in files {
+            // Guard against a path escaping `output_dir` -- an absolute
+            // path or `..` component recovered from a malformed or
+            // maliciously-crafted archive.
+            if path.is_absolute()
+                || path.components().any(|c| matches!(c, std::path::Component::ParentDir))
+            {
+                return Err(ArchiveError::Other(format!(
+                    "refusing to extract unsafe path: {}",
+                    path.display()
+                )));
+            }
+
+            let full_path = self.output_dir.join(&path);
+            if let Some(parent) = full_path.parent() {
+                if created_dirs.insert(parent.to_path_buf()) && !parent.exists() {
+                    std::fs::create_dir_all(parent)
+                        .map_err(|e| ArchiveError::create(e, parent))?;
+                    stats.dirs_created += 1;
+                }
+            }
+
+            std::fs::write(&full_path, &content)
+                .map_err(|e| ArchiveError::write(e, &full_path))?;
+
+            // Restore whatever of the original mtime/mode the archive
+            // recorded (see `formatter::Formatter::format_file`);
+            // best-effort, since a read-only destination filesystem can
+            // legitimately reject either.
+            if let Some(modified) = modified {
+                let _ = filetime::set_file_mtime(&full_path, filetime::FileTime::from_system_time(modified));
+            }
+            #[cfg(unix)]
+            if let Some(mode) = mode {
+                use std::os::unix::fs::PermissionsExt;
+                let _ = std::fs::set_permissions(&full_path, std::fs::Permissions::from_mode(mode));
+            }
+
+            stats.files_written += 1;
+            stats.bytes_written += content.len() as u64;
+        }
+
+        Ok(stats)
+    }
+}
+
+/// Extract a previously-created archive back into a directory tree,
+/// the inverse of `archive_directory`.
+///
+/// # Errors
+/// Returns an error if the archive can't be read or its framing is
+/// malformed.
+pub fn extract_archive(
+    input: impl AsRef<Path>,
+    output_dir: impl AsRef<Path>,
+    config: &Config,
+) -> Result<ExtractStats> {
+    let mut engine = ExtractEngine::new(input.as_ref(), output_dir.as_ref(), config);
+    engine.run()
+}
+
 /// Default formatter implementation for testing
 #[cfg(test)]
 mod test_utils {
@@ -892,11 +1605,10 @@ mod tests {
         let engine = ArchiveEngine::new(config)?;
         let formatter = TestFormatter;
         let output = Arc::new(Mutex::new(Vec::new()));
-        
-        // Collect and process files in parallel
-        let files = engine.collect_files()?;
-        engine.process_files_parallel(&files, &formatter, &output)?;
-        
+
+        // Process files in parallel; `collect_files` runs internally.
+        engine.process_files_parallel(&formatter, &output)?;
+
         // Verify all files were processed
         let output_str = String::from_utf8_lossy(&output.lock().unwrap());
         for file in &expected_files {
@@ -914,7 +1626,57 @@ mod tests {
         
         Ok(())
     }
-    
+
+    #[test]
+    fn test_parallel_processing_is_deterministically_ordered() -> TestResult {
+        let temp_dir = tempfile::tempdir()?;
+
+        for i in 0..20 {
+            create_test_file(
+                &temp_dir,
+                &format!("file_{:02}.txt", i),
+                &format!("Content {}", i),
+            );
+        }
+
+        let config = Config::default()
+            .with_input(temp_dir.path().to_path_buf())
+            .with_output(PathBuf::from("output.txt"))
+            .with_parallel(true)
+            .with_parallel_channel_capacity(2);
+
+        let engine = ArchiveEngine::new(config)?;
+        let formatter = TestFormatter;
+        let expected_order = engine.collect_files()?;
+
+        // Run the parallel path twice; the reassembled output should land
+        // in `collect_files`'s order both times regardless of which worker
+        // happens to finish first.
+        let mut outputs = Vec::new();
+        for _ in 0..2 {
+            let output = Arc::new(Mutex::new(Vec::new()));
+            engine.process_files_parallel(&formatter, &output)?;
+            outputs.push(output.lock().unwrap().clone());
+        }
+        assert_eq!(outputs[0], outputs[1], "parallel output is not reproducible across runs");
+
+        let output_str = String::from_utf8_lossy(&outputs[0]);
+        let positions: Vec<usize> = expected_order
+            .iter()
+            .map(|path| {
+                let needle = path.file_name().unwrap().to_string_lossy().to_string();
+                output_str.find(&needle).unwrap_or_else(|| panic!("missing {needle} in output"))
+            })
+            .collect();
+        assert!(
+            positions.windows(2).all(|w| w[0] < w[1]),
+            "output order does not follow collect_files order: {:?}",
+            positions
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_format_utilities() -> TestResult {
         // Test path formatting
@@ -932,7 +1694,274 @@ mod tests {
         let timestamp = std::time::SystemTime::now();
         let formatted_time = format_timestamp(timestamp);
         assert!(!formatted_time.is_empty(), "Timestamp should not be empty");
-        
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_git_file_status_tags_output() -> TestResult {
+        // `GitInfo::file_statuses` (the non-`git2-backend` default) shells
+        // out to the real `git` binary, so this exercises the full path
+        // from `Config::git_file_status` through `lookup_git_status` to
+        // the `[M]`/`[?]` markers `formatter::text::git_status_marker`
+        // renders in the `FILE:` header.
+        let temp_dir = tempfile::tempdir()?;
+        let run = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(args)
+                .current_dir(temp_dir.path())
+                .status()
+                .unwrap()
+        };
+        run(&["init"]);
+        run(&["config", "user.name", "Test User"]);
+        run(&["config", "user.email", "test@example.com"]);
+
+        create_test_file(&temp_dir, "committed.txt", "original");
+        run(&["add", "."]);
+        run(&["commit", "-m", "Initial commit"]);
+
+        // Modified in the worktree, not staged.
+        std::fs::write(temp_dir.path().join("committed.txt"), "changed")?;
+        // Untracked.
+        create_test_file(&temp_dir, "untracked.txt", "new");
+
+        let output_file = temp_dir.path().join("archive.txt");
+        let config = Config::default()
+            .with_input(temp_dir.path())
+            .with_output(&output_file)
+            .with_git_file_status(true);
+
+        archive_directory(temp_dir.path(), &output_file, &config)?;
+
+        let content = std::fs::read_to_string(&output_file)?;
+        assert!(
+            content.contains("FILE: [M] committed.txt"),
+            "expected a modified-status marker on committed.txt, got:\n{content}"
+        );
+        assert!(
+            content.contains("FILE: [?] untracked.txt"),
+            "expected an untracked-status marker on untracked.txt, got:\n{content}"
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn test_extract_round_trips_byte_identical_tree() -> TestResult {
+        let source_dir = tempfile::tempdir()?;
+        create_test_file(&source_dir, "src/main.rs", "fn main() {}\n");
+        create_test_file(&source_dir, "README.md", "# Demo\n");
+        // Content that would desync a delimiter-scanning parser, since it
+        // contains lines that look like another entry's framing.
+        create_test_file(
+            &source_dir,
+            "tricky.txt",
+            "FILE: not/a/real/entry\nLENGTH: 0\n================================================\n",
+        );
+
+        let archive_file = source_dir.path().join("archive.txt");
+        let config = Config::default()
+            .with_input(source_dir.path())
+            .with_output(&archive_file);
+        archive_directory(source_dir.path(), &archive_file, &config)?;
+
+        let restored_dir = tempfile::tempdir()?;
+        let stats = extract_archive(&archive_file, restored_dir.path(), &config)?;
+        assert_eq!(stats.files_written, 3);
+
+        for relative in ["src/main.rs", "README.md", "tricky.txt"] {
+            let original = std::fs::read(source_dir.path().join(relative))?;
+            let restored = std::fs::read(restored_dir.path().join(relative))?;
+            assert_eq!(original, restored, "round-trip mismatch for {relative}");
+        }
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[cfg(unix)]
+    fn test_extract_restores_mtime_and_mode() -> TestResult {
+        use std::os::unix::fs::PermissionsExt;
+
+        let source_dir = tempfile::tempdir()?;
+        let source_file = create_test_file(&source_dir, "script.sh", "#!/bin/sh\necho hi\n");
+        std::fs::set_permissions(&source_file, std::fs::Permissions::from_mode(0o750))?;
+        let original_mtime = filetime::FileTime::from_last_modification_time(&std::fs::metadata(&source_file)?);
+
+        let archive_file = source_dir.path().join("archive.txt");
+        let config = Config::default()
+            .with_input(source_dir.path())
+            .with_output(&archive_file);
+        archive_directory(source_dir.path(), &archive_file, &config)?;
+
+        let restored_dir = tempfile::tempdir()?;
+        extract_archive(&archive_file, restored_dir.path(), &config)?;
+
+        let restored_file = restored_dir.path().join("script.sh");
+        let restored_metadata = std::fs::metadata(&restored_file)?;
+        assert_eq!(restored_metadata.permissions().mode() & 0o7777, 0o750);
+        let restored_mtime = filetime::FileTime::from_last_modification_time(&restored_metadata);
+        assert_eq!(restored_mtime.unix_seconds(), original_mtime.unix_seconds());
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn test_parallel_and_sequential_archives_are_byte_identical() -> TestResult {
+        let temp_dir = tempfile::tempdir()?;
+        for i in 0..5 {
+            create_test_file(
+                &temp_dir,
+                &format!("file_{i}.txt"),
+                &format!("Content {i}"),
+            );
+        }
+
+        let parallel_output = temp_dir.path().join("parallel.txt");
+        archive_directory(
+            temp_dir.path(),
+            &parallel_output,
+            &Config::default()
+                .with_input(temp_dir.path().to_path_buf())
+                .with_output(&parallel_output)
+                .with_parallel(true),
+        )?;
+
+        let sequential_output = temp_dir.path().join("sequential.txt");
+        archive_directory(
+            temp_dir.path(),
+            &sequential_output,
+            &Config::default()
+                .with_input(temp_dir.path().to_path_buf())
+                .with_output(&sequential_output)
+                .with_parallel(false),
+        )?;
+
+        let parallel_content = std::fs::read_to_string(&parallel_output)?;
+        let sequential_content = std::fs::read_to_string(&sequential_output)?;
+        assert_eq!(
+            parallel_content, sequential_content,
+            "archive output should be byte-identical regardless of `with_parallel`"
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn test_archive_from_memory_source() -> TestResult {
+        // Swapping in a `MemorySource` exercises the same `collect_files`/
+        // `process_single_file_to_buffer` path as the real filesystem,
+        // without touching disk for the input side.
+        let source = vfs::MemorySource::new()
+            .with_file("src/main.rs", "fn main() {}\n")
+            .with_file("README.md", "# Demo\n");
+
+        let output_dir = assert_fs::TempDir::new()?;
+        let output_file = output_dir.path().join("archive.txt");
+        let config = Config::default()
+            .with_input("src".to_string())
+            .with_output(&output_file)
+            .with_file_source(source);
+
+        archive_directory(Path::new("src"), &output_file, &config)?;
+
+        let content = std::fs::read_to_string(&output_file)?;
+        assert!(content.contains("main.rs"));
+        assert!(content.contains("fn main() {}"));
+        assert!(content.contains("Total files processed: 1"));
+
+        Ok(())
+    }
+
+    /// A test-only `Sink` writing into a shared in-memory buffer instead
+    /// of a local file, standing in for a remote destination like
+    /// `sink::HttpPutSink` without requiring network access in a test.
+    #[derive(Debug, Clone, Default)]
+    struct MemorySink {
+        written: Arc<Mutex<Vec<u8>>>,
+    }
+
+    impl sink::Sink for MemorySink {
+        fn create(&self, _name: &str) -> io::Result<Box<dyn Write + Send>> {
+            struct Writer(Arc<Mutex<Vec<u8>>>);
+            impl Write for Writer {
+                fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                    self.0.lock().unwrap().extend_from_slice(buf);
+                    Ok(buf.len())
+                }
+                fn flush(&mut self) -> io::Result<()> {
+                    Ok(())
+                }
+            }
+            Ok(Box::new(Writer(self.written.clone())))
+        }
+    }
+
+    #[rstest]
+    fn test_archive_to_custom_sink() -> TestResult {
+        let temp_dir = tempfile::tempdir()?;
+        create_test_file(&temp_dir, "main.rs", "fn main() {}\n");
+
+        let sink = MemorySink::default();
+        let config = Config::default()
+            .with_input(temp_dir.path().to_path_buf())
+            .with_output(PathBuf::from("archive.txt"))
+            .with_output_sink(sink.clone());
+
+        let mut engine = ArchiveEngine::new(config)?;
+        engine.run()?;
+
+        let content = String::from_utf8(sink.written.lock().unwrap().clone())?;
+        assert!(content.contains("main.rs"));
+        assert!(content.contains("fn main() {}"));
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn test_incremental_archive_skips_unchanged_and_dedups_duplicates() -> TestResult {
+        let temp_dir = tempfile::tempdir()?;
+        create_test_file(&temp_dir, "a.txt", "same content\n");
+        create_test_file(&temp_dir, "b.txt", "same content\n");
+        create_test_file(&temp_dir, "c.txt", "will change\n");
+
+        let manifest_path = temp_dir.path().join("incremental.json");
+        let archive_path = temp_dir.path().join("archive.txt");
+        let config = || {
+            Config::default()
+                .with_input(temp_dir.path().to_path_buf())
+                .with_output(&archive_path)
+                .with_parallel(true)
+                .with_incremental(&manifest_path)
+        };
+
+        // First run: nothing to diff against, so every path is `Added`,
+        // and `b.txt` dedups against `a.txt`'s identical content.
+        let mut engine = ArchiveEngine::new(config())?;
+        let report = engine.run()?;
+        let summary = report.stats.incremental.expect("incremental summary on first run");
+        assert_eq!(summary.added, 3);
+        assert_eq!(summary.duplicates, 1);
+        assert!(manifest_path.exists());
+
+        // Second run with one file changed and one untouched: `a.txt`
+        // and `b.txt` both match the previous manifest, so neither is
+        // embedded and neither can serve as a duplicate target; `c.txt`
+        // changed, nothing removed.
+        create_test_file(&temp_dir, "c.txt", "did change\n");
+        let mut engine = ArchiveEngine::new(config())?;
+        let report = engine.run()?;
+        let summary = report.stats.incremental.expect("incremental summary on second run");
+        assert_eq!(summary.changed, 1);
+        assert_eq!(summary.unchanged + summary.duplicates, 2);
+        assert_eq!(summary.removed, 0);
+
+        let archived = std::fs::read_to_string(&archive_path)?;
+        assert!(archived.contains("STATUS: unchanged") || archived.contains("STATUS: duplicate-of"));
+        assert!(archived.contains("did change"));
+
         Ok(())
     }
 }
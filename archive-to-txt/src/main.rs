@@ -1,7 +1,7 @@
 use anyhow::Result;
 use clap::Parser;
 use std::path::PathBuf;
-use archive_to_txt::{archive_directory, config::Config};
+use archive_to_txt::{ArchiveEngine, config::{Config, OutputCompression, OutputFormat}};
 
 /// Command line interface for the archive-to-txt tool
 ///
@@ -36,6 +36,34 @@ struct Args {
     #[arg(long = "no-llm-optimize")]
     no_llm_optimize: bool,
 
+    /// Don't honor discovered `.gitignore`/`.ignore`/`.promptignore` files
+    /// while walking (enabled by default)
+    #[arg(long = "no-respect-ignore-files")]
+    no_respect_ignore_files: bool,
+
+    /// Don't raise the process's soft file-descriptor limit before
+    /// parallel processing starts (raised by default, best-effort)
+    #[arg(long = "no-raise-fd-limit")]
+    no_raise_fd_limit: bool,
+
+    /// Honor hierarchical `.gitattributes` files: drop `export-ignore`d
+    /// paths and normalize `text`/`eol`-attributed file content, the way
+    /// `git archive` would package the tree (disabled by default).
+    #[arg(long)]
+    git_attributes: bool,
+
+    /// Tag each file's header with its git working-tree status
+    /// (`[M]`/`[A]`/`[?]`/...), like `exa`'s git column (disabled by
+    /// default; has no effect without git info, which is on by default).
+    #[arg(long)]
+    git_file_status: bool,
+
+    /// Encoding to assume for a file with no recognized byte-order-mark
+    /// (an `encoding_rs` label, e.g. `windows-1252` or `GBK`); defaults to
+    /// UTF-8 when not set.
+    #[arg(long, value_name = "ENCODING")]
+    default_encoding: Option<String>,
+
     /// Show filtering statistics (files included/excluded with reasons)
     #[arg(long)]
     show_filter_stats: bool,
@@ -47,12 +75,103 @@ struct Args {
     /// Include only specific file extensions (e.g., rs,js,py)
     #[arg(long)]
     include_extensions: Option<String>,
+
+    /// Include only coarse MIME groups (e.g. text,code,config); ANDed with
+    /// `--include-extensions` when both are given. Groups: text, code,
+    /// config, image, audio, video, archive, data.
+    #[arg(long = "include-type")]
+    include_type: Option<String>,
+
+    /// Named file-type preset to include (e.g. `rust`, `python`); repeatable.
+    /// See `--type-list` for the full set.
+    #[arg(long = "type")]
+    file_type: Vec<String>,
+
+    /// Named file-type preset to exclude; repeatable.
+    #[arg(long = "type-not")]
+    type_not: Vec<String>,
+
+    /// Print every known file-type name and exit.
+    #[arg(long = "type-list")]
+    type_list: bool,
+
+    /// Write a Parquet manifest of every visited file (kept or filtered,
+    /// with a reason) to this path, for auditing or tuning include/exclude
+    /// rules with tools like DuckDB or pandas.
+    #[arg(long, value_name = "FILE")]
+    manifest: Option<PathBuf>,
+
+    /// Compress the output (`gzip`, `zstd`, `bzip2`, or `auto` to infer
+    /// the codec from `--output`'s extension); appends the codec's
+    /// extension (`.gz`/`.zst`/`.bz2`) to the output path and streams
+    /// through the matching encoder.
+    #[arg(long, value_name = "METHOD")]
+    compress: Option<String>,
+
+    /// Compression level, 0 (store) to 9 (best); only used with
+    /// `--compress gzip` or `--compress bzip2`. Ignored for `zstd`, whose
+    /// wider level range is set to a fixed default.
+    #[arg(long, default_value_t = 6)]
+    compression_level: u32,
+
+    /// Prefix each directory-tree entry with a per-file-type icon glyph
+    /// (e.g. 🦀 for `.rs`, 🐍 for `.py`), for terminals with a Nerd/icon
+    /// font. Falls back to a MIME-group icon for unmapped extensions.
+    #[arg(long)]
+    tree_icons: bool,
+
+    /// Archive the tree of this commit, tag, or branch instead of the
+    /// working directory (requires the `git2-backend` build feature).
+    #[arg(long, value_name = "REFSPEC")]
+    rev: Option<String>,
+
+    /// Restrict the archive to files changed since this baseline commit,
+    /// tag, or branch (staged and unstaged changes both count). Diffing
+    /// against an arbitrary baseline requires the `git2-backend` build
+    /// feature; without it, this falls back to the working tree's status
+    /// against HEAD regardless of the baseline given.
+    #[arg(long, value_name = "REFSPEC")]
+    since: Option<String>,
+
+    /// Output format: `plain` (default), `html` (self-contained,
+    /// syntax-highlighted via `syntect`), or `markdown` (fenced code
+    /// blocks, for pasting into docs or chat).
+    #[arg(long, value_name = "FORMAT", default_value = "plain")]
+    format: String,
+
+    /// How to print a fatal error on stderr: `text` (default) or `json`
+    /// (a single `ErrorReport` object, for CI systems and editors to
+    /// consume structurally instead of scraping formatted text).
+    #[arg(long, value_name = "FORMAT", default_value = "text")]
+    error_format: String,
+}
+
+/// Print a fatal `ArchiveError` in the requested `--error-format` and exit
+/// with its `exit_code()`.
+fn report_fatal_error(err: &archive_to_txt::error::ArchiveError, error_format: &str) -> ! {
+    let exit_code = err.exit_code();
+    if error_format == "json" {
+        match serde_json::to_string(&err.to_report()) {
+            Ok(json) => eprintln!("{}", json),
+            Err(e) => eprintln!("Error: {} (failed to serialize as JSON: {})", err, e),
+        }
+    } else {
+        eprintln!("Error: {}", err);
+    }
+    std::process::exit(exit_code);
 }
 
 fn main() -> Result<()> {
     // Parse command line arguments
     let args = Args::parse();
 
+    if args.type_list {
+        for name in Config::default().list_known_file_types() {
+            println!("{}", name);
+        }
+        return Ok(());
+    }
+
     // Validate input directory exists
     if !args.input.exists() {
         anyhow::bail!("Input directory does not exist: {}", args.input.display());
@@ -69,7 +188,31 @@ fn main() -> Result<()> {
         .with_include_hidden(!args.exclude_hidden)
         .with_parallel(!args.no_parallel)
         .with_include_tree(!args.no_tree)
-        .with_llm_optimize(!args.no_llm_optimize); // LLM optimization enabled by default
+        .with_llm_optimize(!args.no_llm_optimize) // LLM optimization enabled by default
+        .with_respect_ignore_files(!args.no_respect_ignore_files)
+        .with_raise_fd_limit(!args.no_raise_fd_limit)
+        .with_git_attributes(args.git_attributes)
+        .with_git_file_status(args.git_file_status)
+        .with_show_tree_icons(args.tree_icons);
+
+    if let Some(rev) = args.rev {
+        config = config.with_revision(rev);
+    }
+
+    if let Some(since) = args.since {
+        config = config.with_changed_since(since);
+    }
+
+    config = match args.format.to_lowercase().as_str() {
+        "plain" | "text" => config.with_format(OutputFormat::Plain),
+        "html" => config.with_format(OutputFormat::Html),
+        "markdown" | "md" => config.with_format(OutputFormat::Markdown),
+        other => anyhow::bail!("Unknown output format: {other} (expected \"plain\", \"html\", or \"markdown\")"),
+    };
+
+    if let Some(encoding) = args.default_encoding {
+        config = config.with_default_encoding(encoding);
+    }
 
     // Configure filtering options
     if args.show_filter_stats {
@@ -84,6 +227,34 @@ fn main() -> Result<()> {
         config = config.with_include_extensions(&extensions);
     }
 
+    if let Some(types) = args.include_type {
+        config = config.with_include_types(&types);
+    }
+
+    if !args.file_type.is_empty() {
+        config = config.with_types(args.file_type);
+    }
+
+    if !args.type_not.is_empty() {
+        config = config.with_type_not(args.type_not);
+    }
+
+    if let Some(manifest_path) = args.manifest {
+        config = config.with_manifest_output(manifest_path);
+    }
+
+    if let Some(method) = args.compress {
+        let compression = match method.to_lowercase().as_str() {
+            "gzip" | "gz" => OutputCompression::Gzip(args.compression_level.min(9)),
+            "zstd" | "zst" => OutputCompression::Zstd(3),
+            // bzip2 has no level 0 (its block sizes run 1..=9).
+            "bzip2" | "bz2" => OutputCompression::Bzip2(args.compression_level.clamp(1, 9)),
+            "auto" => OutputCompression::Auto,
+            other => anyhow::bail!("Unknown compression method: {other} (expected \"gzip\", \"zstd\", \"bzip2\", or \"auto\")"),
+        };
+        config = config.with_compression(compression);
+    }
+
     // Run the archive process
     println!("Creating archive from: {}", args.input.display());
     println!("Output will be saved to: {}", args.output.display());
@@ -96,13 +267,34 @@ fn main() -> Result<()> {
     if args.show_filter_stats {
         println!("📊 Filter statistics will be shown");
     }
-    
-    archive_directory(&args.input, &args.output, &config)?;
+    if let Some(manifest_path) = &config.manifest_output {
+        println!("📋 Writing file manifest to: {}", manifest_path.display());
+    }
+
+    // Run directly through the engine (rather than the `archive_directory`
+    // convenience wrapper) since `config.output` may have picked up a
+    // `.gz` suffix from `--compress gzip` above, and the wrapper would
+    // otherwise reset it back to `args.output`.
+    let output_path = config.output.clone();
+    let mut engine = match ArchiveEngine::new(config) {
+        Ok(engine) => engine,
+        Err(e) => report_fatal_error(&e, &args.error_format),
+    };
+    let report = match engine.run() {
+        Ok(report) => report,
+        Err(e) => report_fatal_error(&e, &args.error_format),
+    };
 
-    println!("\n✅ Successfully created archive at: {}", args.output.display());
-    if let Ok(metadata) = std::fs::metadata(&args.output) {
+    println!("\n✅ Successfully created archive at: {}", output_path.display());
+    if let Ok(metadata) = std::fs::metadata(&output_path) {
         println!("   Archive size: {:.2} MB", metadata.len() as f64 / (1024.0 * 1024.0));
     }
-    
+    if !report.skipped.is_empty() {
+        println!("⚠️  Skipped {} file(s) that failed to process:", report.skipped.len());
+        for (path, err) in &report.skipped {
+            println!("   - {}: {}", path.display(), err);
+        }
+    }
+
     Ok(())
 }
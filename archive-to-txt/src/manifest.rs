@@ -0,0 +1,149 @@
+//! Streaming Parquet manifest of every file the walker visits, for users
+//! who want to audit or re-rank what an archive run included before
+//! feeding the result to an LLM.
+//!
+//! One row per visited path, written out a `RecordBatch` (and therefore a
+//! Parquet row group) at a time as [`collect_files`](crate::ArchiveEngine)
+//! walks the tree, so even a huge repository produces a single
+//! `manifest.parquet` without holding every row in memory at once. The
+//! result is meant to be opened with something like DuckDB or pandas to
+//! tune `--ignore-pattern`/`--include-extensions`/`--include-type`.
+
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, BooleanArray, StringArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use parquet::file::properties::WriterProperties;
+
+use crate::error::{ArchiveError, Result as ArchiveResult};
+
+/// Number of rows buffered before they're flushed as one Parquet row
+/// group, so memory use stays bounded regardless of tree size.
+const ROW_GROUP_SIZE: usize = 4096;
+
+/// A single visited file's outcome: what it looked like, and whether (or
+/// why not) it made it into the archive.
+#[derive(Debug, Clone)]
+pub struct ManifestRow {
+    /// Path relative to the archive's input directory.
+    pub relative_path: String,
+    /// File size in bytes.
+    pub size_bytes: u64,
+    /// Guessed MIME type (`mime_guess`'s best guess, or `"unknown"`).
+    pub mime_type: String,
+    /// Detected programming language, if any (see
+    /// [`crate::binary::detect_language`]).
+    pub language: Option<String>,
+    /// Approximate token count (see [`crate::utils::estimate_token_count`]).
+    pub approx_tokens: u64,
+    /// Whether the file was kept in the archive.
+    pub included: bool,
+    /// A short description of which filter excluded the file, if any.
+    pub exclusion_reason: Option<String>,
+}
+
+/// Streams [`ManifestRow`]s into a single `manifest.parquet`, one Arrow
+/// `RecordBatch` (and therefore Parquet row group) at a time.
+pub struct ManifestWriter {
+    writer: ArrowWriter<File>,
+    schema: Arc<Schema>,
+    buffer: Vec<ManifestRow>,
+}
+
+impl ManifestWriter {
+    /// Create a manifest at `path`, truncating any existing file there.
+    pub fn create(path: &Path) -> ArchiveResult<Self> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("relative_path", DataType::Utf8, false),
+            Field::new("size_bytes", DataType::UInt64, false),
+            Field::new("mime_type", DataType::Utf8, false),
+            Field::new("language", DataType::Utf8, true),
+            Field::new("approx_tokens", DataType::UInt64, false),
+            Field::new("included", DataType::Boolean, false),
+            Field::new("exclusion_reason", DataType::Utf8, true),
+        ]));
+
+        let file = File::create(path).map_err(|e| ArchiveError::create(e, path))?;
+        let writer = ArrowWriter::try_new(file, schema.clone(), Some(WriterProperties::builder().set_max_row_group_size(ROW_GROUP_SIZE).build()))
+            .map_err(|e| ArchiveError::pattern(format!("failed to start Parquet manifest: {e}")))?;
+
+        Ok(Self {
+            writer,
+            schema,
+            buffer: Vec::with_capacity(ROW_GROUP_SIZE),
+        })
+    }
+
+    /// Record one file's outcome, flushing a row group once the buffer
+    /// reaches [`ROW_GROUP_SIZE`].
+    pub fn push(&mut self, row: ManifestRow) -> ArchiveResult<()> {
+        self.buffer.push(row);
+        if self.buffer.len() >= ROW_GROUP_SIZE {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Write any buffered rows as one more row group.
+    fn flush(&mut self) -> ArchiveResult<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+
+        let rows = std::mem::take(&mut self.buffer);
+        let relative_path: ArrayRef = Arc::new(StringArray::from(
+            rows.iter().map(|r| r.relative_path.as_str()).collect::<Vec<_>>(),
+        ));
+        let size_bytes: ArrayRef = Arc::new(UInt64Array::from(
+            rows.iter().map(|r| r.size_bytes).collect::<Vec<_>>(),
+        ));
+        let mime_type: ArrayRef = Arc::new(StringArray::from(
+            rows.iter().map(|r| r.mime_type.as_str()).collect::<Vec<_>>(),
+        ));
+        let language: ArrayRef = Arc::new(StringArray::from(
+            rows.iter().map(|r| r.language.as_deref()).collect::<Vec<_>>(),
+        ));
+        let approx_tokens: ArrayRef = Arc::new(UInt64Array::from(
+            rows.iter().map(|r| r.approx_tokens).collect::<Vec<_>>(),
+        ));
+        let included: ArrayRef = Arc::new(BooleanArray::from(
+            rows.iter().map(|r| r.included).collect::<Vec<_>>(),
+        ));
+        let exclusion_reason: ArrayRef = Arc::new(StringArray::from(
+            rows.iter().map(|r| r.exclusion_reason.as_deref()).collect::<Vec<_>>(),
+        ));
+
+        let batch = RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                relative_path,
+                size_bytes,
+                mime_type,
+                language,
+                approx_tokens,
+                included,
+                exclusion_reason,
+            ],
+        )
+        .map_err(|e| ArchiveError::pattern(format!("failed to build manifest batch: {e}")))?;
+
+        self.writer
+            .write(&batch)
+            .map_err(|e| ArchiveError::pattern(format!("failed to write manifest row group: {e}")))?;
+
+        Ok(())
+    }
+
+    /// Flush any remaining buffered rows and close the Parquet file.
+    pub fn finish(mut self) -> ArchiveResult<()> {
+        self.flush()?;
+        self.writer
+            .close()
+            .map_err(|e| ArchiveError::pattern(format!("failed to close manifest: {e}")))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+//! An ordered include/exclude rule set with gitignore-style last-match-wins
+//! resolution, so patterns like "exclude everything under `build/` except
+//! `build/generated.rs`" can be expressed precisely instead of only through
+//! independent `include`/`exclude` lists.
+
+use std::path::Path;
+
+use globset::{Glob, GlobMatcher};
+
+/// Whether a rule re-includes (whitelists) or excludes (ignores) a path it
+/// matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RuleKind {
+    Whitelist,
+    Ignore,
+}
+
+/// One compiled pattern in the ordered sequence.
+#[derive(Debug)]
+struct Rule {
+    glob: GlobMatcher,
+    kind: RuleKind,
+}
+
+/// A compiled, ordered override rule set. Rules are tested in the order
+/// they were supplied; the *last* rule that matches a path decides its
+/// fate, defaulting to "not matched" when nothing matches. If any
+/// whitelist rule exists in the set and a path matches none of them, it is
+/// excluded -- mirroring how a non-empty legacy `include` list behaves.
+#[derive(Debug, Default)]
+pub struct OverrideRules {
+    rules: Vec<Rule>,
+    has_whitelist: bool,
+}
+
+impl OverrideRules {
+    /// Compile an already-ordered, already-tagged pattern list: each entry
+    /// defaults to `default_kind` unless it starts with `!`, which flips it
+    /// to the opposite kind and is stripped from the pattern itself. An
+    /// unparseable glob is skipped rather than failing the whole set.
+    fn push_tagged(&mut self, patterns: &[String], default_kind: RuleKind) {
+        for pattern in patterns {
+            let (kind, raw) = match pattern.strip_prefix('!') {
+                Some(rest) => (flip(default_kind), rest),
+                None => (default_kind, pattern.as_str()),
+            };
+
+            let Ok(glob) = Glob::new(raw) else { continue };
+            self.rules.push(Rule {
+                glob: glob.compile_matcher(),
+                kind,
+            });
+            if kind == RuleKind::Whitelist {
+                self.has_whitelist = true;
+            }
+        }
+    }
+
+    /// Build the rule set directly from a single ordered pattern list (the
+    /// `with_override_rules` path): every entry defaults to an ignore rule
+    /// unless `!`-prefixed, the standard gitignore convention.
+    pub fn new(patterns: &[String]) -> Self {
+        let mut rules = Self::default();
+        rules.push_tagged(patterns, RuleKind::Ignore);
+        rules
+    }
+
+    /// Append more patterns on top of the existing rules, so they take
+    /// precedence over anything already compiled in. Defaults each entry to
+    /// an ignore rule unless `!`-prefixed, same as `new`.
+    pub fn extend(&mut self, patterns: &[String]) {
+        self.push_tagged(patterns, RuleKind::Ignore);
+    }
+
+    /// Build the rule set by lowering the legacy `include`/`exclude`
+    /// fields: every `include` entry first (default whitelist), then every
+    /// `exclude` entry (default ignore) -- the same order and AND-like
+    /// result those two independent fields used to produce, since a later
+    /// exclude match always beats an earlier include match under
+    /// last-match-wins resolution.
+    pub fn from_legacy(include: &[String], exclude: &[String]) -> Self {
+        let mut rules = Self::default();
+        rules.push_tagged(include, RuleKind::Whitelist);
+        rules.push_tagged(exclude, RuleKind::Ignore);
+        rules
+    }
+
+    /// Whether `path` should be included under this rule set.
+    pub fn is_included(&self, path: &Path) -> bool {
+        match self.rules.iter().rev().find(|rule| rule.glob.is_match(path)) {
+            Some(rule) => rule.kind == RuleKind::Whitelist,
+            None => !self.has_whitelist,
+        }
+    }
+}
+
+fn flip(kind: RuleKind) -> RuleKind {
+    match kind {
+        RuleKind::Whitelist => RuleKind::Ignore,
+        RuleKind::Ignore => RuleKind::Whitelist,
+    }
+}
@@ -0,0 +1,46 @@
+//! Inverse of `formatter`: parses an archive's bytes back into its
+//! original per-file entries, for `ExtractEngine`'s archive-to-tree
+//! round-trip. Each `ArchiveParser` impl understands exactly the framing
+//! its `Formatter` counterpart writes.
+
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use super::config::OutputFormat;
+use crate::error::Result as ArchiveResult;
+
+pub mod text;
+
+/// One file recovered from an archive: its original relative path,
+/// raw content bytes, and whatever of its original mtime/mode the
+/// formatter recorded (see `formatter::Formatter::format_file`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedFile {
+    /// The file's path relative to the archived root.
+    pub path: PathBuf,
+    /// The file's raw content, as written to disk during extraction.
+    pub content: Vec<u8>,
+    /// The file's original modification time, if the archive recorded
+    /// one (an `MTIME` line).
+    pub modified: Option<SystemTime>,
+    /// The file's original Unix permission bits, if the archive
+    /// recorded them (a `MODE` line).
+    pub mode: Option<u32>,
+}
+
+/// Streams through an archive's bytes recognizing per-file boundaries,
+/// recovering each entry's relative path and content.
+pub trait ArchiveParser: Send + Sync {
+    /// Parse every file entry out of an archive's raw bytes, in the
+    /// order they appear.
+    fn parse(&self, archive: &[u8]) -> ArchiveResult<Vec<ParsedFile>>;
+}
+
+/// Create the `ArchiveParser` matching a `Formatter` created by
+/// `formatter::create_formatter` for the same `format`.
+pub fn create_parser(format: OutputFormat) -> Box<dyn ArchiveParser> {
+    match format {
+        OutputFormat::Plain => Box::new(text::PlainTextParser::new()),
+        _ => Box::new(text::PlainTextParser::new()), // Default to plain text
+    }
+}
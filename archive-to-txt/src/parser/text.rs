@@ -0,0 +1,124 @@
+//! Parser for `formatter::text::PlainTextFormatter`'s framing: each file
+//! entry is a `FILE:`/`LENGTH:` header block followed by exactly
+//! `LENGTH` content bytes, so content containing a stray `FILE:` or
+//! `====` line of its own can't desynchronize the parser.
+
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use super::{ArchiveParser, ParsedFile};
+use crate::error::{ArchiveError, Result as ArchiveResult};
+use crate::formatter::text::FILE_DELIMITER;
+
+pub struct PlainTextParser;
+
+impl PlainTextParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ArchiveParser for PlainTextParser {
+    fn parse(&self, archive: &[u8]) -> ArchiveResult<Vec<ParsedFile>> {
+        let text = std::str::from_utf8(archive)
+            .map_err(|e| ArchiveError::Other(format!("archive is not valid UTF-8: {e}")))?;
+
+        let mut files = Vec::new();
+        let mut cursor = 0usize;
+        while let Some(delimiter_at) = text[cursor..].find(FILE_DELIMITER) {
+            let header_start = cursor + delimiter_at + FILE_DELIMITER.len();
+
+            let Some(file_line_end) = text[header_start..].find('\n') else {
+                break;
+            };
+            let file_line = &text[header_start..header_start + file_line_end];
+            let Some(rest) = file_line.strip_prefix("FILE: ") else {
+                // Not a file header (e.g. the `SUMMARY` footer's
+                // delimiter); keep scanning past it.
+                cursor = header_start;
+                continue;
+            };
+            // Drop the `[M] `-style git status marker the formatter puts
+            // before the path when `Config::git_file_status` is set.
+            let rest = ["[M] ", "[A] ", "[?] ", "[R] ", "[I] "]
+                .iter()
+                .find_map(|marker| rest.strip_prefix(marker))
+                .unwrap_or(rest);
+            // Strip the optional " (encoding: ...)" suffix the formatter
+            // records for non-UTF-8 source; extraction always writes the
+            // already-decoded UTF-8 text back out.
+            let path_str = rest.split(" (encoding: ").next().unwrap_or(rest);
+
+            // Zero or more optional metadata lines (`MTIME`/`MODE`/
+            // `XATTR`) come between the `FILE:` header and `LENGTH:`;
+            // walk them in order, picking out the ones this parser
+            // understands and ignoring the rest, so an archive from a
+            // newer formatter version still parses.
+            let mut line_start = header_start + file_line_end + 1;
+            let mut modified = None;
+            let mut mode = None;
+            // Set when a `STATUS: unchanged`/`STATUS: duplicate-of ...`
+            // line shows up: the formatter skipped embedding this file's
+            // content (see `formatter::Formatter::format_unchanged_file`/
+            // `format_duplicate_file`), so there's nothing here to
+            // restore it from -- the entry is skipped below rather than
+            // extracted as a zero-byte file.
+            let mut skip_restore = false;
+            let length_str = loop {
+                let Some(line_end) = text[line_start..].find('\n') else {
+                    return Err(ArchiveError::Other(format!(
+                        "malformed entry for {path_str}: missing LENGTH line"
+                    )));
+                };
+                let line = &text[line_start..line_start + line_end];
+                line_start += line_end + 1;
+
+                if let Some(value) = line.strip_prefix("LENGTH: ") {
+                    break value;
+                } else if let Some(value) = line.strip_prefix("MTIME: ") {
+                    if let Ok(secs) = value.parse::<u64>() {
+                        modified = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+                    }
+                } else if let Some(value) = line.strip_prefix("MODE: ") {
+                    if let Ok(bits) = u32::from_str_radix(value, 8) {
+                        mode = Some(bits);
+                    }
+                } else if line.starts_with("STATUS: ") {
+                    skip_restore = true;
+                }
+                // Any other prefix (e.g. `XATTR:`) is recognized by the
+                // formatter but not restored by extraction yet; skip it.
+            };
+            let length: usize = length_str
+                .parse()
+                .map_err(|e| ArchiveError::Other(format!("invalid LENGTH for {path_str}: {e}")))?;
+
+            let second_delimiter_start = line_start;
+            if !text[second_delimiter_start..].starts_with(FILE_DELIMITER) {
+                return Err(ArchiveError::Other(format!(
+                    "malformed entry for {path_str}: missing closing delimiter"
+                )));
+            }
+            let content_start = second_delimiter_start + FILE_DELIMITER.len();
+            let content_end = content_start + length;
+            if content_end > text.len() {
+                return Err(ArchiveError::Other(format!(
+                    "truncated content for {path_str}: expected {length} bytes"
+                )));
+            }
+
+            if !skip_restore {
+                files.push(ParsedFile {
+                    path: PathBuf::from(path_str),
+                    content: text[content_start..content_end].as_bytes().to_vec(),
+                    modified,
+                    mode,
+                });
+            }
+
+            cursor = content_end;
+        }
+
+        Ok(files)
+    }
+}
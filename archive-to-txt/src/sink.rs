@@ -0,0 +1,161 @@
+//! Pluggable output destinations for the archive writer, decoupling
+//! `ArchiveEngine::run` from `std::fs` the same way `vfs::FileSource`
+//! decouples file reading from it. A `Sink` hands out one `Write` per
+//! named object; `LocalFileSink` is the default (writes straight to
+//! disk, at the path the engine names it with), `HttpPutSink` streams
+//! the same bytes to a remote object store over a streaming HTTP `PUT`
+//! instead, so the archive never has to be fully buffered in memory or
+//! written to a local temp file first.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+
+/// A destination an archive can be streamed to, one named object at a
+/// time. `create` is called once per `ArchiveEngine::run`, named with
+/// the archive's configured output path, and returns a `Write` the
+/// engine streams formatted file chunks into as they're produced.
+pub trait Sink: Send + Sync + fmt::Debug {
+    /// Open a writer for the named object, creating it (and any
+    /// storage-specific parent structure) if needed.
+    fn create(&self, name: &str) -> io::Result<Box<dyn Write + Send>>;
+}
+
+/// The default `Sink`: writes straight to a local file, creating
+/// parent directories as needed. `name` is interpreted as a filesystem
+/// path.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LocalFileSink;
+
+impl Sink for LocalFileSink {
+    fn create(&self, name: &str) -> io::Result<Box<dyn Write + Send>> {
+        let path = Path::new(name);
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() && !parent.exists() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        let file = File::create(path)?;
+        Ok(Box::new(BufWriter::new(file)))
+    }
+}
+
+/// Streams an object to a remote HTTP endpoint via a `PUT` whose body
+/// is read straight off an internal channel, e.g. an S3-compatible
+/// presigned URL or any server that accepts a streamed request body.
+/// Bytes written to the returned `Write` are relayed, unbuffered, to a
+/// background thread driving the request, so the whole archive is
+/// never held in memory before it's sent.
+pub struct HttpPutSink {
+    base_url: String,
+}
+
+impl HttpPutSink {
+    /// An `HttpPutSink` that `PUT`s each named object to
+    /// `{base_url}/{name}`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+        }
+    }
+}
+
+impl fmt::Debug for HttpPutSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpPutSink")
+            .field("base_url", &self.base_url)
+            .finish()
+    }
+}
+
+impl Sink for HttpPutSink {
+    fn create(&self, name: &str) -> io::Result<Box<dyn Write + Send>> {
+        let url = format!("{}/{}", self.base_url.trim_end_matches('/'), name);
+        // Bounded so a slow upload applies backpressure to the engine's
+        // writer thread instead of letting unsent chunks pile up.
+        let (tx, rx) = mpsc::sync_channel::<Vec<u8>>(4);
+
+        let handle = std::thread::spawn(move || -> io::Result<()> {
+            let body = ChannelReader {
+                rx,
+                pending: Vec::new(),
+            };
+            ureq::put(&url)
+                .send(body)
+                .map(|_| ())
+                .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))
+        });
+
+        Ok(Box::new(HttpPutWriter {
+            tx: Some(tx),
+            handle: Some(handle),
+        }))
+    }
+}
+
+/// Adapts the chunks `HttpPutWriter` sends down the channel into a
+/// `Read` the HTTP client streams the request body from.
+struct ChannelReader {
+    rx: mpsc::Receiver<Vec<u8>>,
+    pending: Vec<u8>,
+}
+
+impl Read for ChannelReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pending.is_empty() {
+            match self.rx.recv() {
+                Ok(chunk) => self.pending = chunk,
+                Err(_) => return Ok(0), // sender dropped: end of stream
+            }
+        }
+        let n = buf.len().min(self.pending.len());
+        buf[..n].copy_from_slice(&self.pending[..n]);
+        self.pending.drain(..n);
+        Ok(n)
+    }
+}
+
+/// The `Write` side handed back from `HttpPutSink::create`, relaying
+/// each write to the background request thread and joining it once
+/// dropped so a caller who drops the writer still waits for the
+/// upload to finish (and surfaces a panic instead of silently losing
+/// it).
+struct HttpPutWriter {
+    tx: Option<mpsc::SyncSender<Vec<u8>>>,
+    handle: Option<JoinHandle<io::Result<()>>>,
+}
+
+impl Write for HttpPutWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.tx
+            .as_ref()
+            .expect("HttpPutWriter used after being finished")
+            .send(buf.to_vec())
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "upload thread exited early"))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for HttpPutWriter {
+    fn drop(&mut self) {
+        // Close the channel first so the background thread's `recv()`
+        // sees the stream end and finishes the `PUT`.
+        self.tx.take();
+        if let Some(handle) = self.handle.take() {
+            // Re-raise a panic from the upload thread, unless this thread
+            // is already unwinding (a second panic would abort).
+            if let Err(panic) = handle.join() {
+                if !std::thread::panicking() {
+                    std::panic::resume_unwind(panic);
+                }
+            }
+        }
+    }
+}
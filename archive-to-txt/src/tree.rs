@@ -3,10 +3,64 @@
 //! This module provides utilities for generating ASCII-based directory tree representations
 //! similar to the Unix `tree` command output.
 
-use std::collections::BTreeMap;
+use crate::filter::{is_gitignored, load_ignore_layers, DEFAULT_IGNORE_BASENAMES};
+use crate::icons::icon_for;
+use crate::utils::format_file_size;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use walkdir::WalkDir;
 
+/// A snapshot of tree-generation progress, passed to a registered
+/// `ProgressCallback`. `generate_tree` runs two stages: enumerating
+/// candidate entries (stage 1) and statting them (stage 2, fanned out
+/// across the thread pool); `entries_checked`/`entries_to_check` track
+/// position within whichever stage is current.
+#[derive(Debug, Clone, Copy)]
+pub struct ProgressData {
+    /// The stage currently running (1 = enumerating entries, 2 = statting entries).
+    pub current_stage: usize,
+    /// The total number of stages `generate_tree` runs.
+    pub max_stage: usize,
+    /// Entries processed so far within the current stage.
+    pub entries_checked: usize,
+    /// Total entries expected in the current stage.
+    pub entries_to_check: usize,
+}
+
+/// How often `generate_tree` invokes a registered `ProgressCallback` while
+/// statting entries, so a caller driving a progress bar isn't hit on every
+/// single entry — especially with several threads statting concurrently.
+const PROGRESS_EMIT_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// A progress callback registered via `TreeConfig::progress`. Wrapped in
+/// `Arc` rather than stored as a bare `Box<dyn Fn>` so `TreeConfig` can
+/// still derive `Clone`; `Debug` is hand-written since `dyn Fn` doesn't
+/// implement it. `Send + Sync` so it can be called safely from any thread
+/// in the scoped pool `generate_tree` stats entries on.
+#[derive(Clone)]
+pub struct ProgressCallback(Arc<dyn Fn(&ProgressData) + Send + Sync>);
+
+impl ProgressCallback {
+    /// Wrap `f` to be invoked with progress updates during `generate_tree`.
+    pub fn new(f: impl Fn(&ProgressData) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    fn call(&self, data: &ProgressData) {
+        (self.0)(data)
+    }
+}
+
+impl std::fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ProgressCallback(..)")
+    }
+}
+
 /// Represents a node in the directory tree
 #[derive(Debug, Clone)]
 pub struct TreeNode {
@@ -18,6 +72,26 @@ pub struct TreeNode {
     pub children: BTreeMap<String, TreeNode>,
     /// The full path to this node
     pub path: PathBuf,
+    /// On-disk size in bytes for files; always 0 for directories (use
+    /// `total_size` to get a directory's aggregate size).
+    pub size: u64,
+    /// Set when this node was reached through a symbolic link that
+    /// couldn't be followed further — a cycle back onto the current path,
+    /// or a destination that doesn't exist.
+    pub symlink_status: Option<SymlinkStatus>,
+}
+
+/// Why descent stopped at a symbolic-link node encountered while walking a
+/// tree with `TreeConfig::follow_links` enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymlinkStatus {
+    /// Following this link would revisit a directory already on the
+    /// current path — descent stopped here instead of looping forever.
+    /// Also set on every symlink met after the first `MAX_SYMLINK_HOPS`
+    /// in one `generate_tree` call; the walk still descends through those.
+    Loop,
+    /// The link's destination doesn't exist.
+    Broken,
 }
 
 impl TreeNode {
@@ -28,6 +102,8 @@ impl TreeNode {
             is_dir,
             children: BTreeMap::new(),
             path,
+            size: 0,
+            symlink_status: None,
         }
     }
 
@@ -55,6 +131,18 @@ impl TreeNode {
         }
         count
     }
+
+    /// Get the aggregate on-disk size of this node, in bytes.
+    ///
+    /// A file contributes its own size; a directory contributes the sum of
+    /// its children's `total_size`, recursively — the same recurrence `du`
+    /// uses to report directory sizes.
+    pub fn total_size(&self) -> u64 {
+        if !self.is_dir {
+            return self.size;
+        }
+        self.children.values().map(TreeNode::total_size).sum()
+    }
 }
 
 /// Configuration for directory tree generation
@@ -70,6 +158,24 @@ pub struct TreeConfig {
     pub include_extensions: Option<Vec<String>>,
     /// Maximum file size to include
     pub max_file_size: Option<u64>,
+    /// Glob patterns (matched against the directory's path relative to the
+    /// tree root) identifying directories to prune entirely — their
+    /// subtrees are never opened, not just filtered out afterward.
+    pub exclude_dirs: Option<Vec<String>>,
+    /// Honor hierarchical `.gitignore`/`.ignore`/`.llmignore` files
+    /// discovered under the tree root, pruning matching directories
+    /// entirely (their subtrees are never opened) and dropping matching
+    /// files the same way `exclude_dirs`/`FileFilter` do. On by default,
+    /// matching `Config::respect_ignore_files`'s default, so the rendered
+    /// tree reflects what a developer actually tracks; set to `false` to
+    /// force-include otherwise-ignored paths.
+    pub respect_gitignore: bool,
+    /// Thread count for the scoped pool that stats entries in parallel.
+    /// Defaults to the number of logical CPUs when unset.
+    pub threads: Option<usize>,
+    /// Optional callback invoked with periodic progress updates during
+    /// `generate_tree`, for driving a progress bar on large trees.
+    pub progress: Option<ProgressCallback>,
 }
 
 impl Default for TreeConfig {
@@ -80,11 +186,68 @@ impl Default for TreeConfig {
             follow_links: false,
             include_extensions: None,
             max_file_size: None,
+            exclude_dirs: None,
+            respect_gitignore: true,
+            threads: None,
+            progress: None,
         }
     }
 }
 
-/// Generate a directory tree structure from the given path
+/// How `generate_tree` should handle a given directory during traversal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DirMatch {
+    /// Don't descend into this directory at all, and don't list it either.
+    Empty,
+    /// List this directory's immediate entries, but don't recurse into its
+    /// subdirectories.
+    This,
+    /// Only descend into the named child directories (by file name).
+    Set(HashSet<PathBuf>),
+    /// Descend into every subdirectory.
+    Recursive,
+}
+
+/// Compiles `TreeConfig::exclude_dirs` once up front and decides, per
+/// directory, whether `generate_tree` should walk into it. Consulting this
+/// before `WalkDir` opens a directory is what lets excluded subtrees be
+/// skipped entirely, rather than statted and discarded afterward.
+#[derive(Debug, Clone)]
+struct DirMatcher {
+    exclude: Option<GlobSet>,
+}
+
+impl DirMatcher {
+    fn new(exclude_dirs: &Option<Vec<String>>) -> Result<Self, globset::Error> {
+        let exclude = match exclude_dirs {
+            Some(patterns) => {
+                let mut builder = GlobSetBuilder::new();
+                for pattern in patterns {
+                    builder.add(Glob::new(pattern)?);
+                }
+                Some(builder.build()?)
+            }
+            None => None,
+        };
+        Ok(Self { exclude })
+    }
+
+    /// Decide how to handle `dir_path`, given relative to the tree root.
+    fn matches(&self, dir_path: &Path) -> DirMatch {
+        if let Some(exclude) = &self.exclude {
+            if exclude.is_match(dir_path) {
+                return DirMatch::Empty;
+            }
+        }
+        DirMatch::Recursive
+    }
+}
+
+/// Generate a directory tree structure from the given path.
+///
+/// Entries are enumerated serially, but the metadata/size/extension checks
+/// for each one run on a scoped rayon pool (sized by `TreeConfig::threads`)
+/// before the tree itself is built back up single-threaded.
 pub fn generate_tree(root_path: &Path, config: &TreeConfig) -> Result<TreeNode, Box<dyn std::error::Error>> {
     let root_name = root_path
         .file_name()
@@ -93,105 +256,290 @@ pub fn generate_tree(root_path: &Path, config: &TreeConfig) -> Result<TreeNode,
         .to_string();
     
     let mut root = TreeNode::new(root_name, true, root_path.to_path_buf());
-    
+
+    let matcher = DirMatcher::new(&config.exclude_dirs)?;
+    let gitignore_layers = if config.respect_gitignore {
+        load_ignore_layers(root_path, DEFAULT_IGNORE_BASENAMES)
+    } else {
+        Vec::new()
+    };
+
     // Configure the walker
     let mut walker = WalkDir::new(root_path)
         .min_depth(1)
         .follow_links(config.follow_links);
-    
+
     if let Some(max_depth) = config.max_depth {
         walker = walker.max_depth(max_depth);
     }
-    
-    // Collect all valid entries
-    let entries: Vec<_> = walker
+
+    // Walk, consulting the matcher before WalkDir opens a directory so an
+    // excluded subtree is pruned rather than statted and discarded
+    // afterward. `filter_entry` also lets WalkDir's own loop detection run
+    // (when `follow_links` is set) before we ever see the resulting error.
+    let walked: Vec<_> = walker
         .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|entry| {
-            // Skip hidden files if not included
-            if !config.include_hidden {
-                if let Some(name) = entry.file_name().to_str() {
-                    if name.starts_with('.') {
-                        return false;
+        .filter_entry(|entry| {
+            if !entry.file_type().is_dir() {
+                return true;
+            }
+            let relative = entry.path().strip_prefix(root_path).unwrap_or(entry.path());
+            if matcher.matches(relative) == DirMatch::Empty {
+                return false;
+            }
+            if !gitignore_layers.is_empty() && is_gitignored(&gitignore_layers, entry.path(), true, false) {
+                return false;
+            }
+            true
+        })
+        .collect();
+
+    // Split off loop errors and hidden files up front — both are decided
+    // from the `DirEntry` alone, with no stat call needed — leaving only
+    // the entries whose metadata/size/extension checks are worth fanning
+    // out across threads.
+    let mut loop_paths = Vec::new();
+    let mut candidates = Vec::new();
+    for result in walked {
+        match result {
+            Ok(entry) => {
+                if !config.include_hidden {
+                    if let Some(name) = entry.file_name().to_str() {
+                        if name.starts_with('.') {
+                            continue;
+                        }
                     }
                 }
+                if !entry.file_type().is_dir()
+                    && !gitignore_layers.is_empty()
+                    && is_gitignored(&gitignore_layers, entry.path(), false, false)
+                {
+                    continue;
+                }
+                candidates.push(entry);
             }
-            
-            // Check file size limit for files
-            if entry.file_type().is_file() {
-                if let Some(max_size) = config.max_file_size {
-                    if let Ok(metadata) = entry.metadata() {
-                        if metadata.len() > max_size {
-                            return false;
-                        }
+            // A loop error carries the offending path even though the
+            // entry itself couldn't be materialized; record it as a
+            // stopped-descent marker instead of dropping it silently.
+            Err(err) if err.loop_ancestor().is_some() => {
+                if let Some(path) = err.path() {
+                    if let Ok(relative_path) = path.strip_prefix(root_path) {
+                        loop_paths.push(relative_path.to_path_buf());
                     }
                 }
-                
-                // Check file extensions if specified
-                if let Some(extensions) = &config.include_extensions {
-                    if let Some(ext) = entry.path().extension() {
-                        let ext_str = ext.to_string_lossy().to_lowercase();
-                        if !extensions.iter().any(|e| e.eq_ignore_ascii_case(&ext_str)) {
-                            return false;
-                        }
-                    } else {
-                        return false;
+            }
+            Err(_) => {}
+        }
+    }
+
+    // Stage 1 (enumeration) is already done at this point — `candidates`
+    // is the full count — so the first callback fires immediately at 100%
+    // of stage 1 rather than needing a second pass.
+    let entries_to_check = candidates.len();
+    if let Some(cb) = &config.progress {
+        cb.call(&ProgressData {
+            current_stage: 1,
+            max_stage: 2,
+            entries_checked: entries_to_check,
+            entries_to_check,
+        });
+    }
+
+    // Shared across the pool rather than per-thread so the cap on
+    // pathological symlink chains (`MAX_SYMLINK_HOPS`) still holds
+    // regardless of which thread happens to visit which link. `last_emit`
+    // is likewise shared (behind a `Mutex`, not per-thread) so the ~100ms
+    // throttle holds across however many threads are statting at once.
+    let symlink_hops = AtomicUsize::new(0);
+    let entries_checked = AtomicUsize::new(0);
+    let last_emit = Mutex::new(std::time::Instant::now());
+    let process_entry = |entry: walkdir::DirEntry| -> Option<ProcessedEntry> {
+        if let Some(cb) = &config.progress {
+            let checked = entries_checked.fetch_add(1, Ordering::Relaxed) + 1;
+            let mut last = last_emit.lock().unwrap();
+            if last.elapsed() >= PROGRESS_EMIT_INTERVAL {
+                cb.call(&ProgressData {
+                    current_stage: 2,
+                    max_stage: 2,
+                    entries_checked: checked,
+                    entries_to_check,
+                });
+                *last = std::time::Instant::now();
+            }
+        }
+
+        let is_symlink = entry.path_is_symlink();
+        let mut symlink_status = None;
+        if is_symlink {
+            let hops = symlink_hops.fetch_add(1, Ordering::Relaxed) + 1;
+            if hops > MAX_SYMLINK_HOPS {
+                symlink_status = Some(SymlinkStatus::Loop);
+            } else if std::fs::metadata(entry.path()).is_err() {
+                symlink_status = Some(SymlinkStatus::Broken);
+            }
+        }
+        let is_broken = symlink_status == Some(SymlinkStatus::Broken);
+
+        // Check file size limit for files (broken links have no metadata to check)
+        if entry.file_type().is_file() && !is_broken {
+            if let Some(max_size) = config.max_file_size {
+                if let Ok(metadata) = entry.metadata() {
+                    if metadata.len() > max_size {
+                        return None;
                     }
                 }
             }
-            
-            true
+
+            // Check file extensions if specified
+            if let Some(extensions) = &config.include_extensions {
+                if let Some(ext) = entry.path().extension() {
+                    let ext_str = ext.to_string_lossy().to_lowercase();
+                    if !extensions.iter().any(|e| e.eq_ignore_ascii_case(&ext_str)) {
+                        return None;
+                    }
+                } else {
+                    return None;
+                }
+            }
+        }
+
+        let relative_path = entry.path().strip_prefix(root_path).ok()?.to_path_buf();
+        let size = if entry.file_type().is_file() && !is_broken {
+            entry.metadata().map(|m| m.len()).unwrap_or(0)
+        } else {
+            0
+        };
+        Some(ProcessedEntry {
+            relative_path,
+            is_dir: entry.file_type().is_dir(),
+            size,
+            symlink_status,
         })
-        .collect();
-    
-    // Build the tree structure
-    for entry in entries {
-        let relative_path = entry.path().strip_prefix(root_path)?;
-        add_path_to_tree(&mut root, relative_path, entry.file_type().is_dir());
+    };
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(config.threads.unwrap_or_else(num_cpus::get))
+        .build()?;
+    let processed: Vec<ProcessedEntry> =
+        pool.install(|| candidates.into_par_iter().filter_map(process_entry).collect());
+
+    if let Some(cb) = &config.progress {
+        cb.call(&ProgressData {
+            current_stage: 2,
+            max_stage: 2,
+            entries_checked: entries_to_check,
+            entries_to_check,
+        });
     }
-    
+
+    // Tree construction is cheap and order-independent (children are kept
+    // in a `BTreeMap` and `format_tree` sorts siblings), so it stays
+    // single-threaded; only the per-entry I/O above needed the pool.
+    for path in loop_paths {
+        add_path_to_tree(&mut root, &path, false, 0, Some(SymlinkStatus::Loop));
+    }
+    for entry in processed {
+        add_path_to_tree(&mut root, &entry.relative_path, entry.is_dir, entry.size, entry.symlink_status);
+    }
+
     Ok(root)
 }
 
+/// The outcome of statting a single walked entry, computed in parallel and
+/// later folded into the tree serially.
+struct ProcessedEntry {
+    relative_path: PathBuf,
+    is_dir: bool,
+    size: u64,
+    symlink_status: Option<SymlinkStatus>,
+}
+
+/// Number of symlinks a single `generate_tree` call counts before marking
+/// every further one `SymlinkStatus::Loop`. The count only labels nodes;
+/// it does not stop WalkDir from following long (non-cyclic) chains.
+const MAX_SYMLINK_HOPS: usize = 20;
+
 /// Add a path to the tree structure
-fn add_path_to_tree(root: &mut TreeNode, path: &Path, is_dir: bool) {
+fn add_path_to_tree(
+    root: &mut TreeNode,
+    path: &Path,
+    is_dir: bool,
+    size: u64,
+    symlink_status: Option<SymlinkStatus>,
+) {
     let components: Vec<_> = path.components().collect();
     let root_path = root.path.clone(); // Clone the root path to avoid borrow issues
     let mut current = root;
-    
+
     for (i, component) in components.iter().enumerate() {
         let name = component.as_os_str().to_string_lossy().to_string();
         let is_last = i == components.len() - 1;
         let node_is_dir = if is_last { is_dir } else { true };
-        
+
         if !current.children.contains_key(&name) {
             let full_path = root_path.join(path.iter().take(i + 1).collect::<PathBuf>());
-            let node = TreeNode::new(name.clone(), node_is_dir, full_path);
+            let mut node = TreeNode::new(name.clone(), node_is_dir, full_path);
+            if is_last {
+                if !node_is_dir {
+                    node.size = size;
+                }
+                node.symlink_status = symlink_status;
+            }
             current.children.insert(name.clone(), node);
         }
-        
+
         current = current.children.get_mut(&name).unwrap();
     }
 }
 
 /// Format a directory tree as ASCII art
 pub fn format_tree(tree: &TreeNode, show_root: bool) -> String {
+    format_tree_impl(tree, show_root, false, false)
+}
+
+/// Format a directory tree as ASCII art, appending each node's aggregate
+/// on-disk size (via `TreeNode::total_size`) in human-readable form.
+pub fn format_tree_with_sizes(tree: &TreeNode, show_root: bool) -> String {
+    format_tree_impl(tree, show_root, true, false)
+}
+
+/// Format a directory tree as ASCII art, prefixing each entry with an
+/// icon glyph chosen by `crate::icons::icon_for` -- for terminals with a
+/// Nerd/icon font where per-file-type markers are recognizable at a
+/// glance.
+pub fn format_tree_with_icons(tree: &TreeNode, show_root: bool) -> String {
+    format_tree_impl(tree, show_root, false, true)
+}
+
+fn format_tree_impl(tree: &TreeNode, show_root: bool, show_sizes: bool, show_icons: bool) -> String {
     let mut output = String::new();
-    
+
     if show_root {
-        output.push_str(&format!("└── {}/\n", tree.name));
-        format_tree_recursive(tree, &mut output, "    ", true);
+        let icon = if show_icons { format!("{} ", icon_for(&tree.name, &tree.path, true)) } else { String::new() };
+        if show_sizes {
+            output.push_str(&format!("└── {}{}/ ({})\n", icon, tree.name, format_file_size(tree.total_size())));
+        } else {
+            output.push_str(&format!("└── {}{}/\n", icon, tree.name));
+        }
+        format_tree_recursive(tree, &mut output, "    ", true, show_sizes, show_icons);
     } else {
-        format_tree_recursive(tree, &mut output, "", false);
+        format_tree_recursive(tree, &mut output, "", false, show_sizes, show_icons);
     }
-    
+
     output
 }
 
 /// Recursively format the tree structure
-fn format_tree_recursive(node: &TreeNode, output: &mut String, prefix: &str, skip_root: bool) {
+fn format_tree_recursive(
+    node: &TreeNode,
+    output: &mut String,
+    prefix: &str,
+    skip_root: bool,
+    show_sizes: bool,
+    show_icons: bool,
+) {
     let children: Vec<_> = node.children.values().collect();
-    
+
     if !skip_root && !children.is_empty() {
         // Sort children: directories first, then files, both alphabetically
         let mut sorted_children = children;
@@ -202,22 +550,18 @@ fn format_tree_recursive(node: &TreeNode, output: &mut String, prefix: &str, ski
                 _ => a.name.cmp(&b.name),
             }
         });
-        
+
         for (i, child) in sorted_children.iter().enumerate() {
             let is_last = i == sorted_children.len() - 1;
             let connector = if is_last { "└── " } else { "├── " };
             let child_prefix = if is_last { "    " } else { "│   " };
-            
-            let display_name = if child.is_dir {
-                format!("{}/", child.name)
-            } else {
-                child.name.clone()
-            };
-            
+
+            let display_name = format_node_name(child, show_sizes, show_icons);
+
             output.push_str(&format!("{}{}{}\n", prefix, connector, display_name));
-            
+
             if child.is_dir && !child.children.is_empty() {
-                format_tree_recursive(child, output, &format!("{}{}", prefix, child_prefix), false);
+                format_tree_recursive(child, output, &format!("{}{}", prefix, child_prefix), false, show_sizes, show_icons);
             }
         }
     } else if skip_root {
@@ -230,37 +574,69 @@ fn format_tree_recursive(node: &TreeNode, output: &mut String, prefix: &str, ski
                 _ => a.name.cmp(&b.name),
             }
         });
-        
+
         for (i, child) in sorted_children.iter().enumerate() {
             let is_last = i == sorted_children.len() - 1;
             let connector = if is_last { "└── " } else { "├── " };
             let child_prefix = if is_last { "    " } else { "│   " };
-            
-            let display_name = if child.is_dir {
-                format!("{}/", child.name)
-            } else {
-                child.name.clone()
-            };
-            
+
+            let display_name = format_node_name(child, show_sizes, show_icons);
+
             output.push_str(&format!("{}{}\n", connector, display_name));
-            
+
             if child.is_dir && !child.children.is_empty() {
-                format_tree_recursive(child, output, child_prefix, false);
+                format_tree_recursive(child, output, child_prefix, false, show_sizes, show_icons);
             }
         }
     }
 }
 
+/// Render a single node's display name, optionally prefixed with an icon
+/// glyph and/or suffixed with its human-readable aggregate size.
+fn format_node_name(node: &TreeNode, show_sizes: bool, show_icons: bool) -> String {
+    let base = if node.is_dir {
+        format!("{}/", node.name)
+    } else {
+        node.name.clone()
+    };
+    let base = if show_icons {
+        format!("{} {}", icon_for(&node.name, &node.path, node.is_dir), base)
+    } else {
+        base
+    };
+    if show_sizes {
+        format!("{} ({})", base, format_file_size(node.total_size()))
+    } else {
+        base
+    }
+}
+
 /// Generate a compact directory structure summary
 pub fn generate_structure_summary(tree: &TreeNode) -> String {
+    generate_structure_summary_impl(tree, format_tree(tree, true))
+}
+
+/// Generate a compact directory structure summary with each entry
+/// prefixed by a per-file-type icon (see
+/// `crate::icons`/`format_tree_with_icons`).
+pub fn generate_structure_summary_with_icons(tree: &TreeNode) -> String {
+    generate_structure_summary_impl(tree, format_tree_with_icons(tree, true))
+}
+
+fn generate_structure_summary_impl(tree: &TreeNode, rendered_tree: String) -> String {
     let file_count = tree.count_files();
     let dir_count = tree.count_dirs() - 1; // Subtract 1 for the root directory
-    
+
     let mut output = String::new();
     output.push_str(&format!("Directory structure:\n"));
-    output.push_str(&format_tree(tree, true));
-    output.push_str(&format!("\nSummary: {} directories, {} files\n", dir_count, file_count));
-    
+    output.push_str(&rendered_tree);
+    output.push_str(&format!(
+        "\nSummary: {} directories, {} files, {} total\n",
+        dir_count,
+        file_count,
+        format_file_size(tree.total_size())
+    ));
+
     output
 }
 
@@ -297,6 +673,171 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_total_size_aggregates_descendant_files() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let root_path = temp_dir.path();
+
+        fs::create_dir(root_path.join("subdir"))?;
+        fs::write(root_path.join("file1.txt"), "12345")?; // 5 bytes
+        fs::write(root_path.join("subdir").join("file2.txt"), "1234567")?; // 7 bytes
+
+        let config = TreeConfig::default();
+        let tree = generate_tree(root_path, &config)?;
+
+        assert_eq!(tree.total_size(), 12);
+        let subdir = &tree.children["subdir"];
+        assert_eq!(subdir.total_size(), 7);
+        assert_eq!(subdir.size, 0); // directories themselves don't carry a size
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_tree_with_sizes_shows_human_readable_sizes() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let root_path = temp_dir.path();
+
+        fs::write(root_path.join("file1.txt"), "1234567890")?; // 10 bytes
+
+        let config = TreeConfig::default();
+        let tree = generate_tree(root_path, &config)?;
+        let formatted = format_tree_with_sizes(&tree, true);
+
+        assert!(formatted.contains("file1.txt (10 B)"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_structure_summary_includes_total_bytes() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let root_path = temp_dir.path();
+
+        fs::write(root_path.join("file1.txt"), "1234567890")?; // 10 bytes
+
+        let config = TreeConfig::default();
+        let tree = generate_tree(root_path, &config)?;
+        let summary = generate_structure_summary(&tree);
+
+        assert!(summary.contains("10 B total"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_exclude_dirs_prunes_matching_subtree() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let root_path = temp_dir.path();
+
+        fs::create_dir(root_path.join("node_modules"))?;
+        fs::write(root_path.join("node_modules").join("pkg.js"), "ignored")?;
+        fs::write(root_path.join("main.rs"), "kept")?;
+
+        let config = TreeConfig {
+            exclude_dirs: Some(vec!["node_modules".to_string()]),
+            ..TreeConfig::default()
+        };
+        let tree = generate_tree(root_path, &config)?;
+
+        assert!(!tree.children.contains_key("node_modules"));
+        assert!(tree.children.contains_key("main.rs"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_respect_gitignore_prunes_ignored_dir_and_file_by_default() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let root_path = temp_dir.path();
+
+        fs::write(root_path.join(".gitignore"), "build/\n*.log\n")?;
+        fs::create_dir(root_path.join("build"))?;
+        fs::write(root_path.join("build").join("out.o"), "artifact")?;
+        fs::write(root_path.join("app.log"), "noisy")?;
+        fs::write(root_path.join("main.rs"), "kept")?;
+
+        let config = TreeConfig::default();
+        let tree = generate_tree(root_path, &config)?;
+
+        assert!(!tree.children.contains_key("build"));
+        assert!(!tree.children.contains_key("app.log"));
+        assert!(tree.children.contains_key("main.rs"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_respect_gitignore_false_keeps_ignored_paths() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let root_path = temp_dir.path();
+
+        fs::write(root_path.join(".gitignore"), "*.log\n")?;
+        fs::write(root_path.join("app.log"), "noisy")?;
+
+        let config = TreeConfig {
+            respect_gitignore: false,
+            ..TreeConfig::default()
+        };
+        let tree = generate_tree(root_path, &config)?;
+
+        assert!(tree.children.contains_key("app.log"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dir_matcher_returns_empty_for_excluded_path() -> Result<(), Box<dyn std::error::Error>> {
+        let matcher = DirMatcher::new(&Some(vec!["target".to_string()]))?;
+        assert_eq!(matcher.matches(Path::new("target")), DirMatch::Empty);
+        assert_eq!(matcher.matches(Path::new("src")), DirMatch::Recursive);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_broken_symlink_marked_instead_of_dropped() -> Result<(), Box<dyn std::error::Error>> {
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = TempDir::new()?;
+        let root_path = temp_dir.path();
+
+        symlink(root_path.join("does_not_exist"), root_path.join("dangling"))?;
+
+        let config = TreeConfig {
+            follow_links: true,
+            ..TreeConfig::default()
+        };
+        let tree = generate_tree(root_path, &config)?;
+
+        let link_node = tree.children.get("dangling").expect("dangling symlink should still appear");
+        assert_eq!(link_node.symlink_status, Some(SymlinkStatus::Broken));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_symlink_cycle_stops_descent_instead_of_looping() -> Result<(), Box<dyn std::error::Error>> {
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = TempDir::new()?;
+        let root_path = temp_dir.path();
+
+        fs::create_dir(root_path.join("a"))?;
+        symlink(root_path.join("a"), root_path.join("a").join("loop"))?;
+
+        let config = TreeConfig {
+            follow_links: true,
+            ..TreeConfig::default()
+        };
+        // Must terminate rather than recursing forever.
+        let tree = generate_tree(root_path, &config)?;
+        assert!(tree.children.contains_key("a"));
+
+        Ok(())
+    }
+
     #[test]
     fn test_tree_formatting() -> Result<(), Box<dyn std::error::Error>> {
         let temp_dir = TempDir::new()?;
@@ -314,7 +855,74 @@ mod tests {
         assert!(formatted.contains("└──"));
         assert!(formatted.contains("file1.txt"));
         assert!(formatted.contains("subdir/"));
-        
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_tree_with_icons_prefixes_entries() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let root_path = temp_dir.path();
+
+        fs::write(root_path.join("main.rs"), "fn main() {}")?;
+
+        let config = TreeConfig::default();
+        let tree = generate_tree(root_path, &config)?;
+        let formatted = format_tree_with_icons(&tree, true);
+
+        assert!(formatted.contains("🦀 main.rs"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_capped_threads_still_finds_every_entry() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let root_path = temp_dir.path();
+
+        fs::create_dir(root_path.join("subdir"))?;
+        for i in 0..8 {
+            fs::write(root_path.join(format!("file{i}.txt")), "content")?;
+        }
+        fs::write(root_path.join("subdir").join("nested.txt"), "content")?;
+
+        let config = TreeConfig {
+            threads: Some(1),
+            ..TreeConfig::default()
+        };
+        let tree = generate_tree(root_path, &config)?;
+
+        assert_eq!(tree.count_files(), 9);
+        assert!(tree.children["subdir"].children.contains_key("nested.txt"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_progress_callback_reports_both_stages() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let root_path = temp_dir.path();
+
+        for i in 0..5 {
+            fs::write(root_path.join(format!("file{i}.txt")), "content")?;
+        }
+
+        let stages_seen = Arc::new(Mutex::new(HashSet::new()));
+        let stages_seen_cb = Arc::clone(&stages_seen);
+        let config = TreeConfig {
+            progress: Some(ProgressCallback::new(move |data: &ProgressData| {
+                stages_seen_cb.lock().unwrap().insert(data.current_stage);
+            })),
+            ..TreeConfig::default()
+        };
+
+        let tree = generate_tree(root_path, &config)?;
+
+        assert_eq!(tree.count_files(), 5);
+        let stages_seen = stages_seen.lock().unwrap();
+        assert!(stages_seen.contains(&1), "expected a stage-1 (enumeration) update");
+        assert!(stages_seen.contains(&2), "expected a stage-2 (statting) update");
+
         Ok(())
     }
 }
\ No newline at end of file
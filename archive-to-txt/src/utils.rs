@@ -56,6 +56,15 @@ pub fn format_timestamp(time: SystemTime) -> String {
     datetime.format("%Y-%m-%d %H:%M:%S").to_string()
 }
 
+/// Rough token-count estimate for a file of `size_bytes`, used by the
+/// `--manifest` export where an exact tokenizer isn't worth the
+/// dependency. Uses the common approximation of ~4 bytes per token for
+/// English-like source and prose; good enough to rank files by weight,
+/// not to budget an exact context window.
+pub fn estimate_token_count(size_bytes: u64) -> u64 {
+    size_bytes / 4
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -79,5 +88,9 @@ mod tests {
         let timestamp = SystemTime::now();
         let formatted_time = format_timestamp(timestamp);
         assert!(!formatted_time.is_empty(), "Timestamp should not be empty");
+
+        // Test token count estimation
+        assert_eq!(estimate_token_count(0), 0);
+        assert_eq!(estimate_token_count(400), 100);
     }
 }
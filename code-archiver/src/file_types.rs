@@ -0,0 +1,158 @@
+//! Named file-type presets for `--include`/`--exclude`, mirroring ripgrep's
+//! `--type`: a label like `rust` or `web` expands to the glob patterns a
+//! user would otherwise have to spell out by hand, and resolved presets
+//! layer into [`crate::ArchiveConfig::include`]/[`crate::ArchiveConfig::exclude`]
+//! the same way a literal `--include`/`--exclude` pattern would.
+
+/// One named preset: a label (`"rust"`) and the glob patterns it expands to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileTypeDef {
+    /// The name passed to `--type`/`--type-not` (e.g. `"rust"`).
+    pub name: String,
+    /// The glob patterns this type expands to.
+    pub globs: Vec<String>,
+}
+
+/// The built-in presets, in the order `--type-list` prints them.
+fn builtin_defs() -> Vec<FileTypeDef> {
+    [
+        ("rust", &["*.rs", "Cargo.toml"][..]),
+        ("web", &["*.js", "*.ts", "*.jsx", "*.tsx", "*.css", "*.html"][..]),
+        ("python", &["*.py", "*.pyi"][..]),
+        ("cpp", &["*.c", "*.h", "*.cpp", "*.hpp", "*.cc", "*.hh"][..]),
+        ("go", &["*.go"][..]),
+        ("docs", &["*.md", "*.rst", "*.txt"][..]),
+    ]
+    .into_iter()
+    .map(|(name, globs)| FileTypeDef {
+        name: name.to_string(),
+        globs: globs.iter().map(|g| g.to_string()).collect(),
+    })
+    .collect()
+}
+
+/// An ordered registry of named file-type presets, resolvable into plain
+/// glob patterns. Starts pre-populated with the built-in presets; custom
+/// types added via [`FileTypeRegistry::add`] can redefine a built-in name
+/// or introduce a new one.
+#[derive(Debug, Clone)]
+pub struct FileTypeRegistry {
+    defs: Vec<FileTypeDef>,
+}
+
+impl FileTypeRegistry {
+    /// Create a registry pre-populated with the built-in presets.
+    pub fn new() -> Self {
+        Self { defs: builtin_defs() }
+    }
+
+    /// Define or override a type from a `--type-add` spec of the form
+    /// `name:glob,glob,...` (e.g. `"foo:*.foo,*.foo2"`).
+    pub fn add(&mut self, spec: &str) -> Result<(), String> {
+        let (name, globs) = spec
+            .split_once(':')
+            .ok_or_else(|| format!("invalid --type-add spec '{spec}': expected 'name:glob,glob,...'"))?;
+
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(format!("invalid --type-add spec '{spec}': type name is empty"));
+        }
+
+        let globs: Vec<String> = globs
+            .split(',')
+            .map(|g| g.trim().to_string())
+            .filter(|g| !g.is_empty())
+            .collect();
+        if globs.is_empty() {
+            return Err(format!("--type-add '{spec}' defines no glob patterns"));
+        }
+
+        self.defs.retain(|d| d.name != name);
+        self.defs.push(FileTypeDef {
+            name: name.to_string(),
+            globs,
+        });
+        Ok(())
+    }
+
+    /// Resolve `names` (from `--type`/`--type-not`) into the flat glob list
+    /// to fold into `ArchiveConfig::include`/`exclude`.
+    pub fn resolve(&self, names: &[String]) -> Result<Vec<String>, String> {
+        let mut globs = Vec::new();
+        for name in names {
+            let def = self
+                .defs
+                .iter()
+                .find(|d| &d.name == name)
+                .ok_or_else(|| format!("unknown file type '{name}' (see --type-list)"))?;
+            globs.extend(def.globs.iter().cloned());
+        }
+        Ok(globs)
+    }
+
+    /// All registered types, in definition order, for `--type-list`.
+    pub fn list(&self) -> &[FileTypeDef] {
+        &self.defs
+    }
+}
+
+impl Default for FileTypeRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolves_builtin_type() {
+        let registry = FileTypeRegistry::new();
+        let globs = registry.resolve(&["rust".to_string()]).unwrap();
+        assert_eq!(globs, vec!["*.rs".to_string(), "Cargo.toml".to_string()]);
+    }
+
+    #[test]
+    fn test_resolves_multiple_types_in_order() {
+        let registry = FileTypeRegistry::new();
+        let globs = registry.resolve(&["go".to_string(), "docs".to_string()]).unwrap();
+        assert_eq!(globs, vec!["*.go", "*.md", "*.rst", "*.txt"]);
+    }
+
+    #[test]
+    fn test_unknown_type_errors() {
+        let registry = FileTypeRegistry::new();
+        assert!(registry.resolve(&["nonexistent".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_type_add_defines_custom_type() {
+        let mut registry = FileTypeRegistry::new();
+        registry.add("foo:*.foo,*.foo2").unwrap();
+        let globs = registry.resolve(&["foo".to_string()]).unwrap();
+        assert_eq!(globs, vec!["*.foo".to_string(), "*.foo2".to_string()]);
+    }
+
+    #[test]
+    fn test_type_add_overrides_builtin() {
+        let mut registry = FileTypeRegistry::new();
+        registry.add("rust:*.rust-only").unwrap();
+        let globs = registry.resolve(&["rust".to_string()]).unwrap();
+        assert_eq!(globs, vec!["*.rust-only".to_string()]);
+    }
+
+    #[test]
+    fn test_type_add_rejects_malformed_spec() {
+        let mut registry = FileTypeRegistry::new();
+        assert!(registry.add("no-colon-here").is_err());
+        assert!(registry.add("empty-globs:").is_err());
+    }
+
+    #[test]
+    fn test_list_includes_builtins() {
+        let registry = FileTypeRegistry::new();
+        assert!(registry.list().iter().any(|d| d.name == "rust"));
+        assert!(registry.list().iter().any(|d| d.name == "web"));
+    }
+}
@@ -1,4 +1,6 @@
-use git2::{Repository, Status};
+use git2::{Repository, Status, StatusOptions};
+use globset::{Glob, GlobSetBuilder};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
@@ -6,63 +8,144 @@ use thiserror::Error;
 pub enum GitError {
     #[error("Git repository error: {0}")]
     Repository(#[from] git2::Error),
-    
+
     #[error("Path is not in a git repository: {0}")]
     NotARepository(PathBuf),
+
+    #[error("Invalid .gitattributes pattern '{0}': {1}")]
+    InvalidAttributePattern(String, String),
 }
 
+/// A tracked path's state in the index (the staged, "ready to commit"
+/// side of `git status`), decoded from `status.is_index_*()` independently
+/// of whatever the worktree side (`WorktreeState`) reports.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum GitStatus {
-    Unmodified,
-    Modified,
+pub enum IndexState {
     Added,
+    Modified,
     Deleted,
     Renamed,
+    /// libgit2's `git_status_t` has no dedicated index-copied bit -- a
+    /// copy is only ever reported as `Renamed` in practice -- so this
+    /// variant exists for API completeness but `From<Status>` never
+    /// produces it, mirroring this enum's predecessor.
     Copied,
+    Typechange,
+}
+
+/// A path's state in the working tree (local, unstaged edits), decoded
+/// from `status.is_wt_*()` independently of `IndexState`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorktreeState {
+    Modified,
+    Deleted,
+    Renamed,
+    Typechange,
     Untracked,
-    Ignored,
 }
 
-impl From<Status> for GitStatus {
+/// A file's git status with its index (staged) and worktree (unstaged)
+/// sides tracked independently, so a file that's staged-modified but also
+/// has further unstaged edits reports both instead of collapsing into one
+/// flat "modified" state. `index`/`worktree` are `None` when that side has
+/// no change; `ignored` is `.gitignore` membership, which is orthogonal to
+/// both (an ignored path is never otherwise tracked in the index).
+/// `conflicted` is a merge/rebase conflict (the porcelain `u ` state) --
+/// it takes priority over `index`/`worktree` wherever the status is
+/// rendered or summarized, since a file with unresolved conflicts should
+/// never be reported as merely modified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GitFileStatus {
+    pub index: Option<IndexState>,
+    pub worktree: Option<WorktreeState>,
+    pub ignored: bool,
+    pub conflicted: bool,
+}
+
+impl From<Status> for GitFileStatus {
     fn from(status: Status) -> Self {
-        if status.is_wt_new() {
-            GitStatus::Untracked
-        } else if status.is_index_new() {
-            GitStatus::Added
-        } else if status.is_wt_modified() || status.is_index_modified() {
-            GitStatus::Modified
-        } else if status.is_wt_deleted() || status.is_index_deleted() {
-            GitStatus::Deleted
-        } else if status.is_wt_renamed() || status.is_index_renamed() {
-            GitStatus::Renamed
-        } else if status.is_wt_typechange() || status.is_index_typechange() {
-            GitStatus::Modified
-        } else if status.is_ignored() {
-            GitStatus::Ignored
-        } else {
-            GitStatus::Unmodified
+        if status.is_conflicted() {
+            return Self { conflicted: true, ..Self::default() };
         }
+
+        let index = if status.is_index_new() {
+            Some(IndexState::Added)
+        } else if status.is_index_modified() {
+            Some(IndexState::Modified)
+        } else if status.is_index_deleted() {
+            Some(IndexState::Deleted)
+        } else if status.is_index_renamed() {
+            Some(IndexState::Renamed)
+        } else if status.is_index_typechange() {
+            Some(IndexState::Typechange)
+        } else {
+            None
+        };
+
+        let worktree = if status.is_wt_new() {
+            Some(WorktreeState::Untracked)
+        } else if status.is_wt_modified() {
+            Some(WorktreeState::Modified)
+        } else if status.is_wt_deleted() {
+            Some(WorktreeState::Deleted)
+        } else if status.is_wt_renamed() {
+            Some(WorktreeState::Renamed)
+        } else if status.is_wt_typechange() {
+            Some(WorktreeState::Typechange)
+        } else {
+            None
+        };
+
+        Self { index, worktree, ignored: status.is_ignored(), conflicted: false }
     }
 }
 
-impl std::fmt::Display for GitStatus {
+/// Renders a two-column porcelain-like code, index column first, e.g.
+/// `MM` (staged and unstaged edits), `A.` (staged add, clean worktree),
+/// `.M` (unstaged edit only), `??` (untracked), `!!` (ignored), `UU`
+/// (unresolved merge conflict).
+impl std::fmt::Display for GitFileStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            GitStatus::Unmodified => write!(f, "unmodified"),
-            GitStatus::Modified => write!(f, "modified"),
-            GitStatus::Added => write!(f, "added"),
-            GitStatus::Deleted => write!(f, "deleted"),
-            GitStatus::Renamed => write!(f, "renamed"),
-            GitStatus::Copied => write!(f, "copied"),
-            GitStatus::Untracked => write!(f, "untracked"),
-            GitStatus::Ignored => write!(f, "ignored"),
+        if self.conflicted {
+            return write!(f, "UU");
+        }
+        if matches!(self.worktree, Some(WorktreeState::Untracked)) {
+            return write!(f, "??");
+        }
+        if self.ignored && self.index.is_none() && self.worktree.is_none() {
+            return write!(f, "!!");
         }
+
+        let index_code = match self.index {
+            Some(IndexState::Added) => 'A',
+            Some(IndexState::Modified) => 'M',
+            Some(IndexState::Deleted) => 'D',
+            Some(IndexState::Renamed) => 'R',
+            Some(IndexState::Copied) => 'C',
+            Some(IndexState::Typechange) => 'T',
+            None => '.',
+        };
+        let worktree_code = match self.worktree {
+            Some(WorktreeState::Modified) => 'M',
+            Some(WorktreeState::Deleted) => 'D',
+            Some(WorktreeState::Renamed) => 'R',
+            Some(WorktreeState::Typechange) => 'T',
+            Some(WorktreeState::Untracked) => unreachable!("handled above"),
+            None => '.',
+        };
+
+        write!(f, "{index_code}{worktree_code}")
     }
 }
 
+#[derive(Debug)]
 pub struct GitContext {
     repo: Repository,
     workdir: PathBuf,
+    /// Populated by `load_statuses`, keyed by workdir-relative path. `None`
+    /// until then, in which case `get_status`/`is_ignored` fall back to
+    /// their original per-path libgit2 calls.
+    statuses: Option<HashMap<PathBuf, Status>>,
 }
 
 impl GitContext {
@@ -73,33 +156,73 @@ impl GitContext {
                     .ok_or_else(|| GitError::Repository(git2::Error::from_str("Bare repositories are not supported")))?
                     .to_path_buf();
                 
-                Ok(Some(Self { repo, workdir }))
+                Ok(Some(Self { repo, workdir, statuses: None }))
             }
             Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
             Err(e) => Err(GitError::Repository(e)),
         }
     }
 
-    pub fn get_status(&self, path: &Path) -> Result<Option<GitStatus>, GitError> {
+    /// Run one `git status`-equivalent scan over the whole repository and
+    /// cache every entry's `Status` by workdir-relative path, so
+    /// `get_status`/`is_ignored` become cheap map lookups afterward instead
+    /// of calling `status_file`/`is_path_ignored` per file -- each of which
+    /// re-scans the index/workdir on every invocation and turns a whole-tree
+    /// walk into O(files^2) libgit2 work.
+    ///
+    /// `include_ignored` controls whether ignored paths are scanned and
+    /// cached at all (callers pass their own ignored-files setting); the
+    /// remaining options mirror what a single path-by-path `status_file`
+    /// call would have reported.
+    pub fn load_statuses(&mut self, include_ignored: bool) -> Result<(), GitError> {
+        let mut options = StatusOptions::new();
+        options
+            .include_untracked(true)
+            .include_ignored(include_ignored)
+            .recurse_untracked_dirs(true)
+            .renames_head_to_index(true);
+
+        let statuses = self.repo.statuses(Some(&mut options))?;
+        let mut cache = HashMap::with_capacity(statuses.len());
+        for entry in statuses.iter() {
+            if let Some(path) = entry.path() {
+                cache.insert(PathBuf::from(path), entry.status());
+            }
+        }
+        self.statuses = Some(cache);
+        Ok(())
+    }
+
+    pub fn get_status(&self, path: &Path) -> Result<Option<GitFileStatus>, GitError> {
         let rel_path = path.strip_prefix(&self.workdir)
             .map_err(|_| GitError::NotARepository(path.to_path_buf()))?;
-        
-        let status = self.repo.status_file(rel_path)?;
-        
-        if status.is_empty() {
-            // File is not ignored and has no changes
-            Ok(Some(GitStatus::Unmodified))
-        } else if status.is_ignored() {
-            Ok(Some(GitStatus::Ignored))
-        } else {
-            Ok(Some(status.into()))
-        }
+
+        let status = match &self.statuses {
+            Some(cache) => cache.get(rel_path).copied().unwrap_or_else(Status::empty),
+            None => self.repo.status_file(rel_path)?,
+        };
+
+        Ok(Some(status.into()))
+    }
+
+    /// Look up the git blob OID for a tracked path as recorded in the index,
+    /// so callers can later verify reconstructed content against what git committed.
+    pub fn blob_oid(&self, path: &Path) -> Result<Option<git2::Oid>, GitError> {
+        let rel_path = path.strip_prefix(&self.workdir)
+            .map_err(|_| GitError::NotARepository(path.to_path_buf()))?;
+
+        let index = self.repo.index()?;
+        Ok(index.get_path(rel_path, 0).map(|entry| entry.id))
     }
 
     pub fn is_ignored(&self, path: &Path) -> Result<bool, GitError> {
         let rel_path = path.strip_prefix(&self.workdir)
             .map_err(|_| GitError::NotARepository(path.to_path_buf()))?;
-        
+
+        if let Some(cache) = &self.statuses {
+            return Ok(cache.get(rel_path).is_some_and(|status| status.is_ignored()));
+        }
+
         self.repo.is_path_ignored(rel_path)
             .map_err(Into::into)
     }
@@ -107,6 +230,93 @@ impl GitContext {
     pub fn get_root(&self) -> &Path {
         &self.workdir
     }
+
+    /// Check whether `path` is excluded by an `export-ignore` attribute in
+    /// `.gitattributes`, matching the semantics `git archive` applies.
+    ///
+    /// `.gitattributes` files are read from the repository root down to the
+    /// directory containing `path`; a deeper file's matching rule takes
+    /// precedence over a shallower one, and within a single file the last
+    /// matching pattern wins, mirroring how git itself resolves attributes.
+    pub fn export_ignored(&self, path: &Path) -> Result<bool, GitError> {
+        let rel_path = path.strip_prefix(&self.workdir)
+            .map_err(|_| GitError::NotARepository(path.to_path_buf()))?;
+
+        for dir in ancestor_dirs_nearest_first(rel_path) {
+            let attributes_path = self.workdir.join(&dir).join(".gitattributes");
+            let Ok(content) = std::fs::read_to_string(&attributes_path) else {
+                continue;
+            };
+
+            let path_in_dir = rel_path.strip_prefix(&dir).unwrap_or(rel_path);
+            if let Some(value) = export_ignore_setting(&content, path_in_dir)? {
+                return Ok(value);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+/// The directories containing `rel_path`, nearest first, ending with the
+/// repository root (an empty path). Each is a candidate location for a
+/// `.gitattributes` file.
+fn ancestor_dirs_nearest_first(rel_path: &Path) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    let mut current = rel_path.parent();
+
+    while let Some(dir) = current {
+        dirs.push(dir.to_path_buf());
+        current = if dir.as_os_str().is_empty() { None } else { dir.parent() };
+    }
+
+    dirs
+}
+
+/// Parse one `.gitattributes` file's contents and resolve the `export-ignore`
+/// setting for `path_in_dir` (the path being checked, relative to the
+/// directory this file lives in). Returns `None` if no line in the file
+/// matches the path, so the caller can fall back to a shallower file.
+fn export_ignore_setting(content: &str, path_in_dir: &Path) -> Result<Option<bool>, GitError> {
+    let mut builder = GlobSetBuilder::new();
+    let mut settings = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        let pattern = match parts.next() {
+            Some(pattern) => pattern,
+            None => continue,
+        };
+
+        let setting = parts.fold(None, |acc, attr| match attr {
+            "export-ignore" => Some(true),
+            "-export-ignore" => Some(false),
+            _ => acc,
+        });
+        let Some(setting) = setting else {
+            continue;
+        };
+
+        let glob = Glob::new(pattern)
+            .map_err(|e| GitError::InvalidAttributePattern(pattern.to_string(), e.to_string()))?;
+        builder.add(glob);
+        settings.push(setting);
+    }
+
+    if settings.is_empty() {
+        return Ok(None);
+    }
+
+    let globset = builder.build()
+        .map_err(|e| GitError::InvalidAttributePattern(String::new(), e.to_string()))?;
+
+    // The last matching pattern wins, mirroring git's own attribute resolution.
+    Ok(globset.matches(path_in_dir).into_iter().max().map(|idx| settings[idx]))
 }
 
 #[cfg(test)]
@@ -196,11 +406,71 @@ mod tests {
         // Test get_status on committed file
         let status = git_ctx.get_status(&file_path)?.unwrap();
         // After commit, the file should be Unmodified since it's already in the repository
-        assert_eq!(status, GitStatus::Unmodified, "Committed file should be Unmodified");
+        assert_eq!(status, GitFileStatus::default(), "Committed file should be Unmodified");
         
         // Test is_ignored
         assert!(!git_ctx.is_ignored(&file_path)?);
-        
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_ignored_matches_root_gitattributes() -> Result<(), Box<dyn std::error::Error>> {
+        let test_repo = TestGitRepo::new();
+        test_repo.add_file(".gitattributes", "*.log export-ignore\n");
+        let log_path = test_repo.add_file("debug.log", "noisy");
+        let keep_path = test_repo.add_file("keep.txt", "kept");
+        test_repo.commit("Initial commit");
+
+        let git_ctx = GitContext::open(test_repo.path())?.unwrap();
+
+        assert!(git_ctx.export_ignored(&log_path)?);
+        assert!(!git_ctx.export_ignored(&keep_path)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_ignored_nearest_directory_wins() -> Result<(), Box<dyn std::error::Error>> {
+        let test_repo = TestGitRepo::new();
+        test_repo.add_file(".gitattributes", "*.txt export-ignore\n");
+        test_repo.add_file("vendor/.gitattributes", "*.txt -export-ignore\n");
+        let vendor_file = test_repo.add_file("vendor/readme.txt", "kept by nearer rule");
+        let root_file = test_repo.add_file("notes.txt", "dropped by root rule");
+        test_repo.commit("Initial commit");
+
+        let git_ctx = GitContext::open(test_repo.path())?.unwrap();
+
+        assert!(!git_ctx.export_ignored(&vendor_file)?, "deeper .gitattributes should override the root rule");
+        assert!(git_ctx.export_ignored(&root_file)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_ignored_last_matching_pattern_wins_within_file() -> Result<(), Box<dyn std::error::Error>> {
+        let test_repo = TestGitRepo::new();
+        test_repo.add_file(".gitattributes", "*.txt export-ignore\nkeep.txt -export-ignore\n");
+        let keep_path = test_repo.add_file("keep.txt", "kept despite the earlier *.txt rule");
+        test_repo.commit("Initial commit");
+
+        let git_ctx = GitContext::open(test_repo.path())?.unwrap();
+
+        assert!(!git_ctx.export_ignored(&keep_path)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_ignored_defaults_to_false_without_gitattributes() -> Result<(), Box<dyn std::error::Error>> {
+        let test_repo = TestGitRepo::new();
+        let file_path = test_repo.add_file("plain.txt", "no attributes at all");
+        test_repo.commit("Initial commit");
+
+        let git_ctx = GitContext::open(test_repo.path())?.unwrap();
+
+        assert!(!git_ctx.export_ignored(&file_path)?);
+
         Ok(())
     }
 }
@@ -1,6 +1,8 @@
 //! A library for archiving code directories with filtering and formatting options.
 
+pub mod file_types;
 pub mod git;
+pub mod patterns;
 
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
@@ -8,7 +10,6 @@ pub mod test_utils;
 use std::borrow::Cow;
 use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
-use globset::{Glob, GlobSetBuilder};
 use std::sync::{Arc, Mutex};
 use ignore::WalkBuilder;
 use serde::{Serialize, Deserialize};
@@ -37,11 +38,92 @@ pub enum ArchiveError {
     /// Ignore error
     #[error("Ignore error: {0}")]
     Ignore(#[from] ignore::Error),
+
+    /// A configured safety cap (total size, entry count, or per-file size)
+    /// was crossed while collecting entries
+    #[error("Archive limit exceeded: {0}")]
+    LimitExceeded(String),
+
+    /// A followed symlink resolved to a target outside `root_dir` and
+    /// `skip_symlink_escapes` was disabled
+    #[error("Symlink escape: {0}")]
+    SymlinkEscape(String),
+
+    /// A restored file's size didn't match the size recorded when the
+    /// archive was created, indicating truncated or corrupted restore data
+    #[error("Size mismatch: {0}")]
+    SizeMismatch(String),
 }
 
 /// Result type for archiving operations
 pub type Result<T> = std::result::Result<T, ArchiveError>;
 
+/// A snapshot of archiving progress, passed to a registered
+/// `ProgressCallback`. `create_archive` runs two stages: counting
+/// candidate entries up front (stage 1), then processing them (stage 2);
+/// `entries_checked`/`entries_to_check` track position within whichever
+/// stage is current.
+#[derive(Debug, Clone, Copy)]
+pub struct ProgressData {
+    /// The stage currently running (1 = counting candidates, 2 = processing entries).
+    pub current_stage: usize,
+    /// The total number of stages `create_archive` runs.
+    pub max_stage: usize,
+    /// Entries processed so far within the current stage.
+    pub entries_checked: usize,
+    /// Total entries expected in the current stage.
+    pub entries_to_check: usize,
+}
+
+/// Counts of entries skipped during the most recent `create_archive` walk,
+/// broken down by why. `excluded_dirs` is incremented once per pruned
+/// directory (its whole subtree, however large, counts as a single event,
+/// since `build_walker`'s `filter_entry` never descends into it to see what
+/// it contains), while `excluded_files` is incremented per individual file
+/// rejected after being visited.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExclusionStats {
+    /// Directories pruned before descent: a common-noise name (`target`,
+    /// `node_modules`, `.git`) or a match against an exclude pattern.
+    pub excluded_dirs: usize,
+    /// Individual files rejected after being visited: a miss against the
+    /// include patterns, a match against an exclude pattern, or a
+    /// gitignore/export-ignore rule.
+    pub excluded_files: usize,
+    /// Files dropped because `ArchiveConfig::git_tracked_only` was enabled
+    /// and git reports them as untracked or ignored.
+    pub excluded_by_git: usize,
+}
+
+/// How often `create_archive` invokes a registered `ProgressCallback`
+/// while processing entries, so a caller driving a progress bar isn't hit
+/// on every single file.
+const PROGRESS_EMIT_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// A progress callback registered via `ArchiveConfig::progress`. Wrapped
+/// in `Arc` rather than stored as a bare `Box<dyn Fn>` so `ArchiveConfig`
+/// can still derive `Clone`; `Debug` is hand-written since `dyn Fn` doesn't
+/// implement it.
+#[derive(Clone)]
+pub struct ProgressCallback(Arc<dyn Fn(&ProgressData) + Send + Sync>);
+
+impl ProgressCallback {
+    /// Wrap `f` to be invoked with progress updates during `create_archive`.
+    pub fn new(f: impl Fn(&ProgressData) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    fn call(&self, data: &ProgressData) {
+        (self.0)(data)
+    }
+}
+
+impl std::fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ProgressCallback(..)")
+    }
+}
+
 /// Configuration for the code archiver
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArchiveConfig {
@@ -68,12 +150,74 @@ pub struct ArchiveConfig {
     
     /// Whether to respect .gitignore files (requires git to be installed)
     pub gitignore: bool,
-    
+
+    /// Master switch disabling all ignore-file processing at once --
+    /// `.gitignore`, the tool-neutral `.ignore` file, and any name in
+    /// `custom_ignore_filenames` -- independent of `gitignore` and
+    /// `include_ignored`, which only ever affect git's own ignore rules.
+    pub no_ignore: bool,
+
+    /// Extra ignore-file names (besides the built-in `.gitignore`/`.ignore`)
+    /// honored at every directory level, e.g. `.archiveignore`. Lets users
+    /// keep archive-specific exclusions in a file that isn't tied to git
+    /// and isn't overridden by `include_ignored`. Ignored entirely when
+    /// `no_ignore` is set.
+    pub custom_ignore_filenames: Vec<String>,
+
     /// Whether to include Git status information in the output
     pub include_git_status: bool,
     
     /// Whether to include Git-ignored files
     pub include_ignored: bool,
+
+    /// Restrict collected files to those git reports as tracked or staged
+    /// (i.e. present in the index or HEAD), dropping anything untracked or
+    /// ignored -- the set `git add -A` would pick up. This gives a corpus
+    /// that exactly matches "what git would commit", which is usually the
+    /// ideal input for feeding a codebase to an LLM. Falls back to the
+    /// normal walk, with a warning, when `root_dir` isn't a git repository.
+    pub git_tracked_only: bool,
+
+    /// Drop files marked `export-ignore` in `.gitattributes`, matching the
+    /// set `git archive` would produce. Only takes effect when git
+    /// integration is active (`include_git_status` or `gitignore`);
+    /// independent of `gitignore`/`include_ignored`, which only cover
+    /// `.gitignore` rules.
+    pub respect_export_ignore: bool,
+
+    /// Maximum total uncompressed bytes `create_archive` will collect
+    /// before aborting with `ArchiveError::LimitExceeded`. Unlike
+    /// `max_size` (which silently skips an oversized individual file),
+    /// exceeding this aborts collection entirely.
+    pub max_total_size: Option<u64>,
+
+    /// Maximum number of entries `create_archive` will collect before
+    /// aborting with `ArchiveError::LimitExceeded`.
+    pub max_entries: Option<usize>,
+
+    /// Maximum size of any single file `create_archive` will collect
+    /// before aborting with `ArchiveError::LimitExceeded`, enforced
+    /// alongside (and distinct from) the silent skip `max_size` performs.
+    pub max_entry_size: Option<u64>,
+
+    /// When `follow_links` is enabled and a symlink resolves outside
+    /// `root_dir`, skip it (`true`, the default) instead of aborting with
+    /// `ArchiveError::SymlinkEscape`.
+    pub skip_symlink_escapes: bool,
+
+    /// Where `create_tar`/`create_tar_gz` write the packaged archive when
+    /// invoked through `package`. Unused by `create_archive`, which only
+    /// ever returns in-memory entry metadata.
+    pub output_path: Option<PathBuf>,
+
+    /// Compression `package` uses when writing the archive at `output_path`.
+    pub compression: TarCompression,
+
+    /// Optional callback invoked with periodic progress updates during
+    /// `create_archive`, for driving a progress bar on large trees. Not
+    /// serialized; defaults to `None` when deserializing.
+    #[serde(skip)]
+    pub progress: Option<ProgressCallback>,
 }
 
 impl Default for ArchiveConfig {
@@ -87,8 +231,19 @@ impl Default for ArchiveConfig {
             follow_links: false,
             hidden: false,
             gitignore: true,
+            no_ignore: false,
+            custom_ignore_filenames: Vec::new(),
             include_git_status: true,
             include_ignored: false,
+            git_tracked_only: false,
+            respect_export_ignore: true,
+            max_total_size: None,
+            max_entries: None,
+            max_entry_size: None,
+            skip_symlink_escapes: true,
+            output_path: None,
+            compression: TarCompression::None,
+            progress: None,
         }
     }
 }
@@ -112,12 +267,57 @@ pub struct FileEntry {
     /// Git status if available and enabled
     #[serde(skip_serializing_if = "Option::is_none")]
     pub git_status: Option<String>,
+
+    /// Unix permission bits (e.g. `0o644`), for restoring file modes when
+    /// packaging into a tar archive. `0o644` on platforms without a native
+    /// mode bit.
+    pub mode: u32,
+
+    /// The link target, if this entry is itself a symlink rather than a
+    /// regular file. `None` for every entry collected as a regular file,
+    /// including one reached by following a symlink when `follow_links`
+    /// is enabled (that case embeds the target's content, not the link).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symlink_target: Option<String>,
+}
+
+/// Compression applied when packaging collected entries into a tar archive
+/// via [`CodeArchiver::create_tar`]/[`CodeArchiver::create_tar_gz`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TarCompression {
+    /// Plain, uncompressed USTAR archive.
+    None,
+    /// Gzip-compressed tar, at a `flate2` compression level from 0 (store)
+    /// to 9 (best compression).
+    Gzip(u32),
 }
 
 /// The main archiver struct
 #[derive(Debug)]
 pub struct CodeArchiver {
     config: ArchiveConfig,
+    /// Directories the walker actually descends into, derived once from
+    /// `config.include`'s literal prefixes so unrelated subtrees are never
+    /// opened. Just `[root_dir]` when there's no include filter to narrow it.
+    walk_roots: Vec<PathBuf>,
+    /// `exclude` then `include` compiled once into an ordered, negation-
+    /// aware rule set (see `patterns::PatternSet`), matched against each
+    /// entry's path relative to `root_dir`.
+    pattern_set: patterns::PatternSet,
+    /// Opened once in `new` and reused for every gitignore/export-ignore/
+    /// tracked-only check in `build_walker` and every status lookup in
+    /// `create_archive`, instead of reopening the repository per entry.
+    /// `None` when `root_dir` isn't a git repository. `Arc<Mutex<_>>` for
+    /// the same reason as `exclusion_stats`: shared across `'static`
+    /// `filter_entry` closures.
+    git_context: Option<Arc<Mutex<git::GitContext>>>,
+    /// Reset at the start of every `create_archive` call and incremented by
+    /// `build_walker`'s `filter_entry` closure during the real processing
+    /// pass; read back afterward via `last_exclusion_stats`. `Arc<Mutex<_>>`
+    /// rather than a plain field since `filter_entry` closures must be
+    /// `'static` and own what they capture, the same reason `GitContext` is
+    /// wrapped in one below.
+    exclusion_stats: Arc<Mutex<ExclusionStats>>,
 }
 
 impl CodeArchiver {
@@ -129,201 +329,396 @@ impl CodeArchiver {
                 config.root_dir.display()
             )));
         }
-        
+
         if !config.root_dir.is_dir() {
             return Err(ArchiveError::InvalidPath(format!(
                 "Root path is not a directory: {}",
                 config.root_dir.display()
             )));
         }
-        
-        // Validate include patterns
+
+        // Validate include patterns, ignoring the negation/dir-only markers
+        // `glob::Pattern` (unlike `globset::Glob`) doesn't understand.
         if let Some(patterns) = &config.include {
             for pattern in patterns {
-                glob::Pattern::new(pattern)?;
+                glob::Pattern::new(Self::strip_rule_markers(pattern))?;
             }
         }
-        
+
         // Validate exclude patterns
         if let Some(patterns) = &config.exclude {
             for pattern in patterns {
-                glob::Pattern::new(pattern)?;
+                glob::Pattern::new(Self::strip_rule_markers(pattern))?;
             }
         }
-        
-        Ok(Self { config })
+
+        let walk_roots = patterns::PatternSet::compute_walk_roots(&config.root_dir, config.include.as_deref());
+        let pattern_set = patterns::PatternSet::compile(
+            config.exclude.as_deref().unwrap_or(&[]),
+            config.include.as_deref().unwrap_or(&[]),
+        );
+
+        let git_context = match git::GitContext::open(&config.root_dir) {
+            Ok(ctx) => ctx.map(|ctx| Arc::new(Mutex::new(ctx))),
+            Err(e) => {
+                tracing::warn!("Failed to initialize Git context: {}", e);
+                None
+            }
+        };
+
+        Ok(Self {
+            config,
+            walk_roots,
+            pattern_set,
+            git_context,
+            exclusion_stats: Arc::new(Mutex::new(ExclusionStats::default())),
+        })
     }
-    
-    /// Create an archive of the configured directory
-    #[instrument(skip(self))]
-    pub fn create_archive(&self) -> Result<Vec<FileEntry>> {
-        let mut entries = Vec::new();
-        
+
+    /// Strip a pattern's leading `!` (negation) and trailing `/`
+    /// (directory-only) markers, leaving the bare glob `glob::Pattern`
+    /// validates.
+    fn strip_rule_markers(pattern: &str) -> &str {
+        let pattern = pattern.strip_prefix('!').unwrap_or(pattern);
+        pattern.strip_suffix('/').unwrap_or(pattern)
+    }
+
+    /// Exclusion counts from the most recent `create_archive` call (all
+    /// zero if it hasn't run yet).
+    pub fn last_exclusion_stats(&self) -> ExclusionStats {
+        *self.exclusion_stats.lock().unwrap()
+    }
+
+    /// Build the configured `ignore::WalkBuilder`: include/exclude globs,
+    /// common-directory and gitignore handling. Returns an owned builder
+    /// rather than a `Walk` iterator so `create_archive` can call `.build()`
+    /// on it twice — once to pre-count entries for progress reporting, once
+    /// to actually process them. `count_exclusions` should only be set on
+    /// the real processing pass; otherwise the pre-count pass over the same
+    /// tree would double every `ExclusionStats` count.
+    fn build_walker(&self, count_exclusions: bool) -> WalkBuilder {
         // Clone configuration values needed for the filter
-        let exclude_patterns = self.config.exclude.clone();
-        let include_patterns = self.config.include.clone();
-        let include_git_status = self.config.include_git_status;
+        let pattern_set = self.pattern_set.clone();
+        let root_dir = self.config.root_dir.clone();
         let include_ignored = self.config.include_ignored;
         let use_git = self.config.include_git_status || self.config.gitignore;
+        let exclusion_stats = Arc::clone(&self.exclusion_stats);
 
-        // Configure the directory walker
-        let mut walker = WalkBuilder::new(&self.config.root_dir);
-        
-        // Apply configuration to walker
+        // Configure the directory walker. Starting from `walk_roots` instead
+        // of the full `root_dir` means a base directory outside an include
+        // pattern's literal prefix is never opened at all.
+        let mut walker = WalkBuilder::new(&self.walk_roots[0]);
+        for extra_root in &self.walk_roots[1..] {
+            walker.add(extra_root);
+        }
+
+        // Apply configuration to walker. `ignore`/`git_exclude`/`parents`
+        // are already the crate's defaults, but set them explicitly so the
+        // hierarchical `.gitignore`/`.ignore`/global-exclude behavior this
+        // archiver relies on doesn't silently change if `ignore`'s defaults
+        // ever do: every directory's own ignore file is honored, and a
+        // `!pattern` in a deeper file can still re-include something a
+        // shallower one excluded. `pattern_set` (applied below in
+        // `filter_entry`) is this archiver's own override layer on top of
+        // that -- the same "layer user patterns over git's own ignore
+        // rules" precedence an `ignore::overrides::OverrideBuilder` would
+        // give, just expressed through the ordered rule set this archiver
+        // already builds once in `new` and reuses across every walk.
         walker
             .hidden(!self.config.hidden)
             .follow_links(self.config.follow_links)
-            .git_ignore(self.config.gitignore);
+            .git_ignore(self.config.gitignore && !self.config.no_ignore)
+            .git_exclude(self.config.gitignore && !self.config.no_ignore)
+            .ignore(!self.config.no_ignore)
+            .parents(true);
 
-        // Include patterns are handled in the filter_entry closure below
+        // `.ignore` above already covers the tool-neutral `.ignore` file
+        // (ripgrep/fd's convention); layer any further archive-specific
+        // ignore filenames on top of it, honored at every directory level
+        // the same way. Skipped entirely under `no_ignore`, same as the
+        // built-in ignore files.
+        if !self.config.no_ignore {
+            for filename in &self.config.custom_ignore_filenames {
+                walker.add_custom_ignore_filename(filename);
+            }
+        }
 
-        // Add exclude patterns for common directories
-        let walker = walker.filter_entry(move |e| {
+        // Match every pattern against the path relative to `root_dir`
+        // (with a leading "./" variant too, for patterns like "./Cargo.toml"),
+        // not the absolute filesystem path — a literal pattern like
+        // "Cargo.toml" can never match an absolute path, only a relative one.
+        walker.filter_entry(move |e| {
             let path = e.path();
             let path_str = path.to_string_lossy();
-            
+            let is_dir = e.file_type().map_or(false, |ft| ft.is_dir());
+
+            let mut record_excluded = |is_dir: bool| {
+                if !count_exclusions {
+                    return;
+                }
+                let mut stats = exclusion_stats.lock().unwrap();
+                if is_dir {
+                    stats.excluded_dirs += 1;
+                } else {
+                    stats.excluded_files += 1;
+                }
+            };
+
             // Skip common directories
-            if path_str.contains("/target/") || 
-               path_str.contains("/node_modules/") || 
-               path_str.contains("/.git/")
+            if path_str.contains("/target/")
+                || path_str.contains("/node_modules/")
+                || path_str.contains("/.git/")
             {
+                record_excluded(is_dir);
                 return false;
             }
-            
+
             // Skip root level directories
             if let Some(name) = path.file_name() {
                 let name = name.to_string_lossy();
                 if name == "target" || name == "node_modules" || name == ".git" {
+                    record_excluded(is_dir);
                     return false;
                 }
             }
-            
-            // For directories, always include them to allow traversal
-            if e.file_type().map_or(false, |ft| ft.is_dir()) {
+
+            let relative = path.strip_prefix(&root_dir).unwrap_or(path);
+            let verdict = pattern_set.evaluate(relative, is_dir);
+
+            if is_dir {
+                // Prune an excluded directory's whole subtree here, before
+                // any of its files are ever statted. A directory is never
+                // pruned purely for matching nothing -- only an explicit
+                // `Ignore` verdict stops descent, since files deeper down
+                // may still match an include pattern the directory itself
+                // doesn't.
+                if verdict == patterns::Verdict::Ignore {
+                    tracing::debug!("Excluding directory '{}' - matched exclude pattern", path_str);
+                    record_excluded(true);
+                    return false;
+                }
                 tracing::debug!("Including directory '{}' for traversal", path_str);
                 return true;
             }
-            
-            // For files, check against include patterns
-            if let Some(includes) = &include_patterns {
-                if includes.is_empty() {
-                    return true; // No include patterns means include everything
+
+            match verdict {
+                patterns::Verdict::Ignore => {
+                    tracing::debug!("Excluding '{}' - matched exclude pattern", path_str);
+                    record_excluded(false);
+                    false
                 }
-                
-                let path = path.to_string_lossy();
-                tracing::debug!("Checking include patterns for path: {}", path);
-                
-                // Check each pattern individually for better debugging
-                let mut matched = false;
-                
-                for pattern in includes {
-                    match Glob::new(pattern) {
-                        Ok(glob) => {
-                            let matcher = glob.compile_matcher();
-                            let path_str = path.as_ref();
-                            let matches = matcher.is_match(path_str);
-                            
-                            tracing::debug!("Pattern '{}' matches '{}': {}", pattern, path, matches);
-                            
-                            if matches {
-                                matched = true;
-                                break;
-                            }
-                            
-                            // Also try with a leading "./"
-                            let path_with_dot = format!("./{}", path);
-                            let matches_with_dot = matcher.is_match(&path_with_dot);
-                            
-                            tracing::debug!("Pattern '{}' matches '{}': {}", pattern, path_with_dot, matches_with_dot);
-                            
-                            if matches_with_dot {
-                                matched = true;
-                                break;
-                            }
-                        },
-                        Err(e) => {
-                            tracing::warn!("Invalid glob pattern '{}': {}", pattern, e);
-                        }
-                    }
+                patterns::Verdict::Whitelist => {
+                    tracing::debug!("Including '{}' - matched whitelist pattern", path_str);
+                    true
                 }
-                
-                if !matched && !includes.is_empty() {
-                    tracing::debug!("Excluding '{}' - no matching include patterns", path);
-                    return false;
+                patterns::Verdict::None if pattern_set.exclude_unmatched() => {
+                    tracing::debug!("Excluding '{}' - no matching include patterns", path_str);
+                    record_excluded(false);
+                    false
                 }
-                
-                tracing::debug!("Including '{}' - matched include pattern", path);
+                patterns::Verdict::None => true,
             }
-            
-            // Apply custom exclude patterns
-            if let Some(excludes) = &exclude_patterns {
-                // Compile all exclude patterns
-                let mut glob_builder = GlobSetBuilder::new();
-                let mut has_valid_patterns = false;
-                
-                for pattern in excludes {
-                    match Glob::new(pattern) {
-                        Ok(glob) => {
-                            glob_builder.add(glob);
-                            has_valid_patterns = true;
-                        },
-                        Err(e) => {
-                            tracing::warn!("Invalid exclude pattern '{}': {}", pattern, e);
+        });
+
+        // Handle Git ignore if needed. `git_context` is opened once in
+        // `new` and shared across this and the two filters below, rather
+        // than reopening the repository per filter layer.
+        if use_git && !include_ignored {
+            if let Some(git_ctx) = &self.git_context {
+                let git_ctx = Arc::clone(git_ctx);
+                let exclusion_stats = Arc::clone(&self.exclusion_stats);
+                walker.filter_entry(move |e| {
+                    if e.file_type().map_or(false, |ft| !ft.is_dir()) {
+                        if let Ok(ctx) = git_ctx.lock() {
+                            if let Ok(true) = ctx.is_ignored(e.path()) {
+                                if count_exclusions {
+                                    exclusion_stats.lock().unwrap().excluded_files += 1;
+                                }
+                                return false;
+                            }
                         }
                     }
-                }
-                
-                // Only check patterns if we have at least one valid pattern
-                if has_valid_patterns {
-                    // Build the glob set
-                    if let Ok(glob_set) = glob_builder.build() {
-                        let path = Path::new(path_str.as_ref());
-                        if glob_set.is_match(path) {
-                            tracing::debug!("Excluding '{}' - matched exclude pattern", path_str);
-                            return false;
-                        }
-                        
-                        // Also check with a leading "./"
-                        let path_with_dot = Path::new(".").join(path);
-                        if glob_set.is_match(&path_with_dot) {
-                            tracing::debug!("Excluding '{}' - matched exclude pattern with leading './'", path_str);
-                            return false;
+                    true
+                });
+            }
+        }
+
+        // Honor `git archive`'s export-ignore semantics: a file marked
+        // export-ignore in .gitattributes is dropped from the archive the
+        // same way a gitignored file is, independent of `self.config.gitignore`.
+        if use_git && self.config.respect_export_ignore {
+            if let Some(git_ctx) = &self.git_context {
+                let git_ctx = Arc::clone(git_ctx);
+                let exclusion_stats = Arc::clone(&self.exclusion_stats);
+                walker.filter_entry(move |e| {
+                    if e.file_type().map_or(false, |ft| !ft.is_dir()) {
+                        if let Ok(ctx) = git_ctx.lock() {
+                            if let Ok(true) = ctx.export_ignored(e.path()) {
+                                tracing::debug!(
+                                    "Excluding '{}' - export-ignore in .gitattributes",
+                                    e.path().display()
+                                );
+                                if count_exclusions {
+                                    exclusion_stats.lock().unwrap().excluded_files += 1;
+                                }
+                                return false;
+                            }
                         }
                     }
-                }
+                    true
+                });
             }
-            
-            true
-        });
-        
-        // Handle Git ignore if needed
-        let walker = if use_git && !include_ignored {
-            match git::GitContext::open(&self.config.root_dir) {
-                Ok(Some(git_ctx)) => {
-                    let git_ctx = Arc::new(Mutex::new(git_ctx));
+        }
+
+        // `git_tracked_only` restricts the file set to whatever git reports
+        // as tracked or staged, dropping untracked and ignored files. This
+        // is independent of `self.config.gitignore`/`include_ignored` above
+        // -- it's a stricter, opt-in filter rather than the default ignore
+        // handling -- so it gets its own `filter_entry` layer.
+        if self.config.git_tracked_only {
+            match &self.git_context {
+                Some(git_ctx) => {
+                    let git_ctx = Arc::clone(git_ctx);
+                    let exclusion_stats = Arc::clone(&self.exclusion_stats);
                     walker.filter_entry(move |e| {
                         if e.file_type().map_or(false, |ft| !ft.is_dir()) {
-                            if let Ok(ctx) = git_ctx.lock() {
-                                if let Ok(true) = ctx.is_ignored(e.path()) {
-                                    return false;
+                            let tracked = git_ctx
+                                .lock()
+                                .ok()
+                                .and_then(|ctx| ctx.get_status(e.path()).ok().flatten())
+                                .map_or(false, |status: git::GitFileStatus| {
+                                    !(status.ignored
+                                        || matches!(status.worktree, Some(git::WorktreeState::Untracked)))
+                                });
+                            if !tracked {
+                                tracing::debug!(
+                                    "Excluding '{}' - not tracked or staged in git",
+                                    e.path().display()
+                                );
+                                if count_exclusions {
+                                    exclusion_stats.lock().unwrap().excluded_by_git += 1;
                                 }
+                                return false;
                             }
                         }
                         true
-                    })
+                    });
                 },
-                Ok(None) => walker,
-                Err(e) => {
-                    tracing::warn!("Failed to initialize Git context: {}", e);
-                    walker
+                None => {
+                    tracing::warn!(
+                        "git_tracked_only is enabled but '{}' is not a git repository; falling back to the normal walk",
+                        self.config.root_dir.display()
+                    );
+                },
+            }
+        }
+
+        walker
+    }
+
+    /// Reject an entry whose path could let the archive escape `root_dir`:
+    /// a literal `..`/root component in `rel_path`, or (for a symlinked
+    /// entry) a resolved target outside `root_dir` once both paths are
+    /// canonicalized. Component-by-component rejection catches a crafted
+    /// `..` segment even on a filesystem where canonicalization would
+    /// otherwise silently normalize it away.
+    /// `is_symlink` entries are exempted from the canonical-containment
+    /// check here when `follow_links` is enabled: that case is handled by
+    /// the dedicated symlink-escape/cycle logic in `create_archive`, which
+    /// honors `skip_symlink_escapes` instead of always hard-erroring.
+    fn validate_entry_path(&self, path: &Path, rel_path: &Path, is_symlink: bool) -> Result<()> {
+        for component in rel_path.components() {
+            if matches!(
+                component,
+                std::path::Component::ParentDir
+                    | std::path::Component::RootDir
+                    | std::path::Component::Prefix(_)
+            ) {
+                return Err(ArchiveError::InvalidPath(format!(
+                    "Entry path escapes the archive root: {}",
+                    rel_path.display()
+                )));
+            }
+        }
+
+        if self.config.follow_links && is_symlink {
+            return Ok(());
+        }
+
+        let canonical_root = self.config.root_dir.canonicalize()?;
+        let canonical_path = path.canonicalize()?;
+        if !canonical_path.starts_with(&canonical_root) {
+            return Err(ArchiveError::InvalidPath(format!(
+                "Entry path resolves outside the archive root: {}",
+                path.display()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Create an archive of the configured directory
+    #[instrument(skip(self))]
+    pub fn create_archive(&self) -> Result<Vec<FileEntry>> {
+        let mut entries = Vec::new();
+        let include_git_status = self.config.include_git_status;
+        let progress = self.config.progress.clone();
+        let mut total_size: u64 = 0;
+        *self.exclusion_stats.lock().unwrap() = ExclusionStats::default();
+
+        // Populate `GitContext`'s status cache once up front, rather than
+        // letting every `is_ignored`/`get_status` call below (from the
+        // walker's filters and from the git-status tagging further down)
+        // re-scan the repository per file.
+        if self.config.include_git_status || self.config.gitignore || self.config.git_tracked_only {
+            if let Some(git_ctx) = &self.git_context {
+                if let Err(e) = git_ctx.lock().unwrap().load_statuses(self.config.include_ignored) {
+                    tracing::warn!("Failed to batch-load Git status: {}", e);
                 }
             }
+        }
+
+        // Stage 1: pre-count candidate entries (only done when a callback
+        // is registered — the count needs a full extra walk, which isn't
+        // worth paying for when nothing is listening for it). Exclusions
+        // aren't counted here, since this pass and stage 2 below walk the
+        // same tree and would otherwise double every count.
+        let entries_to_check = if progress.is_some() {
+            self.build_walker(false).build().count()
         } else {
-            walker
+            0
         };
+        if let Some(cb) = &progress {
+            cb.call(&ProgressData {
+                current_stage: 1,
+                max_stage: 2,
+                entries_checked: entries_to_check,
+                entries_to_check,
+            });
+        }
+
+        let mut entries_checked = 0usize;
+        let mut last_emit = std::time::Instant::now();
+        #[cfg(unix)]
+        let mut visited_symlink_targets: std::collections::HashSet<u64> = std::collections::HashSet::new();
+
+        // Stage 2: process each file in the directory, throttling progress
+        // updates so a callback driving a progress bar isn't hit on every
+        // single entry.
+        for result in self.build_walker(true).build() {
+            entries_checked += 1;
+            if let Some(cb) = &progress {
+                if last_emit.elapsed() >= PROGRESS_EMIT_INTERVAL {
+                    cb.call(&ProgressData {
+                        current_stage: 2,
+                        max_stage: 2,
+                        entries_checked,
+                        entries_to_check,
+                    });
+                    last_emit = std::time::Instant::now();
+                }
+            }
 
-        // Process each file in the directory
-        for result in walker.build() {
             let entry = match result {
                 Ok(entry) => entry,
                 Err(err) => {
@@ -360,16 +755,108 @@ impl CodeArchiver {
                 );
                 ArchiveError::Io(io_err)
             })?;
-            
+
+            // Get relative path
+            let rel_path = path.strip_prefix(&self.config.root_dir)
+                .map_err(|_| ArchiveError::InvalidPath("Failed to get relative path".to_string()))?;
+
+            // `file_type()` reflects the resolved target when `follow_links`
+            // is enabled, so whether `path` itself is a symlink has to be
+            // checked separately via `symlink_metadata`.
+            let is_symlink = std::fs::symlink_metadata(path)
+                .map(|m| m.file_type().is_symlink())
+                .unwrap_or(false);
+
+            // Reject any entry whose path would escape `root_dir`, whether
+            // via a literal `..` component or a symlink resolving outside
+            // it, before it's ever added to the archive.
+            self.validate_entry_path(path, rel_path, is_symlink)?;
+
+            if self.config.follow_links && is_symlink {
+                match path.canonicalize() {
+                    Ok(canonical_target) => {
+                        let canonical_root = self.config.root_dir.canonicalize()?;
+                        if !canonical_target.starts_with(&canonical_root) {
+                            if self.config.skip_symlink_escapes {
+                                tracing::debug!(
+                                    "Skipping symlink '{}' - target '{}' escapes the archive root",
+                                    rel_path.display(),
+                                    canonical_target.display()
+                                );
+                                continue;
+                            }
+                            return Err(ArchiveError::SymlinkEscape(format!(
+                                "Symlink '{}' resolves outside the archive root to '{}'",
+                                rel_path.display(),
+                                canonical_target.display()
+                            )));
+                        }
+
+                        // Skip a symlink whose resolved target (by inode) was
+                        // already collected through an earlier symlink.
+                        // Directory loops never reach here: the walker reports
+                        // them as errors, which are skipped above.
+                        #[cfg(unix)]
+                        {
+                            use std::os::unix::fs::MetadataExt;
+                            let ino = std::fs::metadata(&canonical_target)?.ino();
+                            if !visited_symlink_targets.insert(ino) {
+                                tracing::debug!(
+                                    "Skipping symlink '{}' - target already visited (cycle)",
+                                    rel_path.display()
+                                );
+                                continue;
+                            }
+                        }
+                    }
+                    Err(_) => {
+                        // Broken symlink target: nothing to resolve or archive.
+                        continue;
+                    }
+                }
+            }
+
             // Skip if file is too large
             if let Some(max_size) = self.config.max_size {
                 if metadata.len() > max_size {
                     continue;
                 }
             }
-            
 
-            
+            // Enforce the hard safety caps: unlike `max_size` (which simply
+            // skips an oversized file), crossing any of these aborts the
+            // whole collection, since they exist to bound worst-case
+            // resource usage against a maliciously crafted tree.
+            if let Some(max_entries) = self.config.max_entries {
+                if entries.len() >= max_entries {
+                    return Err(ArchiveError::LimitExceeded(format!(
+                        "Archive exceeded the maximum entry count of {}",
+                        max_entries
+                    )));
+                }
+            }
+
+            if let Some(max_entry_size) = self.config.max_entry_size {
+                if metadata.len() > max_entry_size {
+                    return Err(ArchiveError::LimitExceeded(format!(
+                        "File '{}' ({} bytes) exceeds the maximum per-file size of {} bytes",
+                        rel_path.display(),
+                        metadata.len(),
+                        max_entry_size
+                    )));
+                }
+            }
+
+            if let Some(max_total_size) = self.config.max_total_size {
+                total_size += metadata.len();
+                if total_size > max_total_size {
+                    return Err(ArchiveError::LimitExceeded(format!(
+                        "Archive exceeded the maximum total size of {} bytes",
+                        max_total_size
+                    )));
+                }
+            }
+
             // Get file extension if any
             let extension = path.extension()
                 .and_then(|ext| ext.to_str())
@@ -387,21 +874,16 @@ impl CodeArchiver {
                 }
             }
             
-            // Get Git status if enabled
+            // Get Git status if enabled, reusing the repository opened
+            // once in `new` rather than reopening it per file.
             let git_status: Option<String> = if include_git_status {
-                if let Ok(Some(git_ctx)) = git::GitContext::open(&self.config.root_dir) {
-                    git_ctx.get_status(path).ok().flatten().map(|s| s.to_string())
-                } else {
-                    None
-                }
+                self.git_context.as_ref().and_then(|git_ctx| {
+                    git_ctx.lock().unwrap().get_status(path).ok().flatten().map(|s| s.to_string())
+                })
             } else {
                 None
             };
-            
-            // Get relative path
-            let rel_path = path.strip_prefix(&self.config.root_dir)
-                .map_err(|_| ArchiveError::InvalidPath("Failed to get relative path".to_string()))?;
-            
+
             // Convert to string
             let path_str = rel_path.to_string_lossy().to_string();
             
@@ -414,7 +896,26 @@ impl CodeArchiver {
             let modified = chrono::DateTime::<chrono::Utc>::from(
                 std::time::UNIX_EPOCH + modified
             ).to_rfc3339();
-            
+
+            #[cfg(unix)]
+            let mode = {
+                use std::os::unix::fs::PermissionsExt;
+                metadata.permissions().mode()
+            };
+            #[cfg(not(unix))]
+            let mode = 0o644u32;
+
+            // Captured only when the entry is itself a symlink that wasn't
+            // followed; a followed symlink is archived as the resolved
+            // file's own content, per `symlink_target`'s doc comment.
+            let symlink_target = if is_symlink && !self.config.follow_links {
+                std::fs::read_link(path)
+                    .ok()
+                    .map(|target| target.to_string_lossy().to_string())
+            } else {
+                None
+            };
+
             // Add to entries
             let file_entry = FileEntry {
                 path: path_str,
@@ -422,12 +923,23 @@ impl CodeArchiver {
                 modified,
                 extension,
                 git_status,
+                mode,
+                symlink_target,
             };
             
             debug!("Adding file to archive: {}", path.display());
             entries.push(file_entry);
         }
-        
+
+        if let Some(cb) = &progress {
+            cb.call(&ProgressData {
+                current_stage: 2,
+                max_stage: 2,
+                entries_checked,
+                entries_to_check,
+            });
+        }
+
         info!("Archive created with {} files", entries.len());
         Ok(entries)
     }
@@ -438,6 +950,227 @@ impl CodeArchiver {
         serde_json::to_string_pretty(&entries)
             .map_err(|e| ArchiveError::Config(e.to_string()))
     }
+
+    /// Write every collected file into a USTAR tar archive at `output_path`,
+    /// preserving each entry's unix mode and modification time.
+    pub fn create_tar<P: AsRef<Path>>(&self, output_path: P) -> Result<()> {
+        let file = std::fs::File::create(output_path.as_ref())?;
+        self.write_entries_tar(file)?;
+        Ok(())
+    }
+
+    /// Same as `create_tar`, but wraps the tar stream in a gzip encoder at
+    /// `level` (0 = store, 9 = best compression).
+    pub fn create_tar_gz<P: AsRef<Path>>(&self, output_path: P, level: u32) -> Result<()> {
+        let file = std::fs::File::create(output_path.as_ref())?;
+        let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::new(level));
+        let encoder = self.write_entries_tar(encoder)?;
+        encoder.finish()?;
+        Ok(())
+    }
+
+    /// Package the archive using `self.config.output_path` and
+    /// `self.config.compression`, for callers that configure packaging
+    /// declaratively rather than calling `create_tar`/`create_tar_gz` directly.
+    pub fn package(&self) -> Result<()> {
+        let output_path = self.config.output_path.as_ref().ok_or_else(|| {
+            ArchiveError::Config("output_path is not set".to_string())
+        })?;
+
+        match self.config.compression {
+            TarCompression::None => self.create_tar(output_path),
+            TarCompression::Gzip(level) => self.create_tar_gz(output_path, level),
+        }
+    }
+
+    /// Stream `create_archive`'s entries into a tar builder wrapping
+    /// `writer`, reopening each file's content from `root_dir` and restoring
+    /// its stored unix mode and modification time on the tar header.
+    /// Returns the underlying writer so callers wrapping it in something
+    /// that needs finalizing (e.g. a gzip encoder) can do so afterward.
+    fn write_entries_tar<W: std::io::Write>(&self, writer: W) -> Result<W> {
+        let mut builder = tar::Builder::new(writer);
+
+        for entry in self.create_archive()? {
+            let mtime = chrono::DateTime::parse_from_rfc3339(&entry.modified)
+                .map_err(|e| {
+                    ArchiveError::InvalidPath(format!(
+                        "Invalid stored modification time for '{}': {}",
+                        entry.path, e
+                    ))
+                })?
+                .timestamp()
+                .max(0) as u64;
+
+            let mut header = tar::Header::new_gnu();
+            header.set_mode(entry.mode);
+            header.set_mtime(mtime);
+
+            if let Some(target) = &entry.symlink_target {
+                header.set_entry_type(tar::EntryType::Symlink);
+                header.set_size(0);
+                header.set_link_name(target)?;
+                header.set_cksum();
+                builder.append_data(&mut header, &entry.path, std::io::empty())?;
+            } else {
+                let abs_path = self.config.root_dir.join(&entry.path);
+                let mut file = std::fs::File::open(&abs_path)?;
+                header.set_size(entry.size);
+                header.set_cksum();
+                builder.append_data(&mut header, &entry.path, &mut file)?;
+            }
+        }
+
+        Ok(builder.into_inner()?)
+    }
+
+    /// Reconstruct a directory tree under `target_root` from a tar archive
+    /// written by `create_tar`: recreates intermediate directories,
+    /// restores each entry's unix mode and modification time, and
+    /// re-establishes a symlink entry as a link rather than copied content.
+    /// When `verify` is set, each restored regular file's size is compared
+    /// against the tar header's recorded size and a mismatch returns
+    /// `ArchiveError::SizeMismatch`. Returns the number of entries restored.
+    pub fn extract_tar<P: AsRef<Path>, Q: AsRef<Path>>(
+        archive_path: P,
+        target_root: Q,
+        verify: bool,
+    ) -> Result<usize> {
+        let file = std::fs::File::open(archive_path.as_ref())?;
+        Self::extract_tar_entries(tar::Archive::new(file), target_root.as_ref(), verify)
+    }
+
+    /// Same as `extract_tar`, but reads a gzip-compressed tar stream as
+    /// written by `create_tar_gz`.
+    pub fn extract_tar_gz<P: AsRef<Path>, Q: AsRef<Path>>(
+        archive_path: P,
+        target_root: Q,
+        verify: bool,
+    ) -> Result<usize> {
+        let file = std::fs::File::open(archive_path.as_ref())?;
+        let decoder = flate2::read::GzDecoder::new(file);
+        Self::extract_tar_entries(tar::Archive::new(decoder), target_root.as_ref(), verify)
+    }
+
+    /// Shared restore loop driving both `extract_tar` and `extract_tar_gz`.
+    fn extract_tar_entries<R: std::io::Read>(
+        mut archive: tar::Archive<R>,
+        target_root: &Path,
+        verify: bool,
+    ) -> Result<usize> {
+        std::fs::create_dir_all(target_root)?;
+        let canonical_root = target_root.canonicalize()?;
+        let mut restored = 0usize;
+
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let rel_path = entry.path()?.into_owned();
+            let dest_path = Self::validate_restore_path(&canonical_root, &rel_path)?;
+            let header = entry.header().clone();
+
+            match header.entry_type() {
+                tar::EntryType::Directory => {
+                    std::fs::create_dir_all(&dest_path)?;
+                }
+                tar::EntryType::Symlink => {
+                    let link_name = entry.link_name()?.ok_or_else(|| {
+                        ArchiveError::InvalidPath(format!(
+                            "Symlink entry '{}' has no recorded link target",
+                            rel_path.display()
+                        ))
+                    })?;
+
+                    #[cfg(unix)]
+                    {
+                        if dest_path.symlink_metadata().is_ok() {
+                            std::fs::remove_file(&dest_path)?;
+                        }
+                        std::os::unix::fs::symlink(&link_name, &dest_path)?;
+                    }
+                    #[cfg(not(unix))]
+                    {
+                        tracing::warn!(
+                            "Skipping symlink '{}' -> '{}': symlinks are not restored on this platform",
+                            rel_path.display(),
+                            link_name.display()
+                        );
+                    }
+                }
+                _ => {
+                    entry.unpack(&dest_path)?;
+
+                    if let Ok(mtime) = header.mtime() {
+                        let mtime = std::time::UNIX_EPOCH + std::time::Duration::from_secs(mtime);
+                        if let Ok(restored_file) = std::fs::OpenOptions::new().write(true).open(&dest_path) {
+                            let _ = restored_file.set_modified(mtime);
+                        }
+                    }
+
+                    #[cfg(unix)]
+                    {
+                        use std::os::unix::fs::PermissionsExt;
+                        if let Ok(mode) = header.mode() {
+                            std::fs::set_permissions(&dest_path, std::fs::Permissions::from_mode(mode))?;
+                        }
+                    }
+
+                    if verify {
+                        let expected_size = header.size()?;
+                        let restored_size = std::fs::metadata(&dest_path)?.len();
+                        if restored_size != expected_size {
+                            return Err(ArchiveError::SizeMismatch(format!(
+                                "Restored file '{}' is {} bytes, expected {} bytes",
+                                rel_path.display(),
+                                restored_size,
+                                expected_size
+                            )));
+                        }
+                    }
+                }
+            }
+
+            restored += 1;
+        }
+
+        Ok(restored)
+    }
+
+    /// Validate a tar entry's path the same way `validate_entry_path`
+    /// validates a collected entry: reject any `..`/root/prefix component
+    /// outright, then (once its parent directories exist) confirm the
+    /// resolved destination still resolves inside `canonical_root` --
+    /// catching both a crafted `..` segment and a symlink already extracted
+    /// into the tree that a later entry's path might otherwise walk through.
+    fn validate_restore_path(canonical_root: &Path, rel_path: &Path) -> Result<PathBuf> {
+        for component in rel_path.components() {
+            if matches!(
+                component,
+                std::path::Component::ParentDir
+                    | std::path::Component::RootDir
+                    | std::path::Component::Prefix(_)
+            ) {
+                return Err(ArchiveError::InvalidPath(format!(
+                    "Archive entry path escapes the restore root: {}",
+                    rel_path.display()
+                )));
+            }
+        }
+
+        let dest_path = canonical_root.join(rel_path);
+
+        if let Some(parent) = dest_path.parent() {
+            std::fs::create_dir_all(parent)?;
+            let canonical_parent = parent.canonicalize()?;
+            if !canonical_parent.starts_with(canonical_root) {
+                return Err(ArchiveError::InvalidPath(format!(
+                    "Archive entry path resolves outside the restore root: {}",
+                    rel_path.display()
+                )));
+            }
+        }
+
+        Ok(dest_path)
+    }
 }
 
 #[cfg(test)]
@@ -579,7 +1312,93 @@ mod tests {
         // Should only include the top-level file, not files in target/ or node_modules/
         assert_eq!(entries.len(), 1);
         assert_eq!(entries[0].path, "file1.txt");
-        
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_progress_callback_reports_both_stages() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        for i in 0..3 {
+            let file = temp_dir.child(format!("file{i}.txt"));
+            file.touch().map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        }
+
+        let stages_seen = Arc::new(Mutex::new(std::collections::HashSet::new()));
+        let stages_seen_cb = Arc::clone(&stages_seen);
+        let config = ArchiveConfig {
+            root_dir: temp_dir.path().to_path_buf(),
+            progress: Some(ProgressCallback::new(move |data: &ProgressData| {
+                stages_seen_cb.lock().unwrap().insert(data.current_stage);
+            })),
+            ..Default::default()
+        };
+
+        let archiver = CodeArchiver::new(config)?;
+        let entries = archiver.create_archive()?;
+
+        assert_eq!(entries.len(), 3);
+        let stages_seen = stages_seen.lock().unwrap();
+        assert!(stages_seen.contains(&1), "expected a stage-1 (counting) update");
+        assert!(stages_seen.contains(&2), "expected a stage-2 (processing) update");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_ignore_filename_is_honored() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+
+        temp_dir.child(".archiveignore")
+            .write_str("secret.txt\n")
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        temp_dir.child("secret.txt").touch()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        temp_dir.child("public.txt").touch()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+
+        let config = ArchiveConfig {
+            root_dir: temp_dir.path().to_path_buf(),
+            custom_ignore_filenames: vec![".archiveignore".to_string()],
+            ..Default::default()
+        };
+
+        let archiver = CodeArchiver::new(config)?;
+        let entries = archiver.create_archive()?;
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path, "public.txt");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_ignore_disables_custom_ignore_filenames() -> Result<()> {
+        let temp_dir = assert_fs::TempDir::new()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+
+        temp_dir.child(".archiveignore")
+            .write_str("secret.txt\n")
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        temp_dir.child("secret.txt").touch()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+
+        let config = ArchiveConfig {
+            root_dir: temp_dir.path().to_path_buf(),
+            custom_ignore_filenames: vec![".archiveignore".to_string()],
+            no_ignore: true,
+            ..Default::default()
+        };
+
+        let archiver = CodeArchiver::new(config)?;
+        let entries = archiver.create_archive()?;
+
+        // secret.txt and .archiveignore itself (hidden, so excluded by the
+        // default `hidden: false` rather than by the ignore file).
+        assert!(entries.iter().any(|e| e.path == "secret.txt"));
+
         Ok(())
     }
 }
@@ -1,8 +1,17 @@
 use std::path::PathBuf;
+use std::sync::mpsc::channel;
+use std::time::Duration;
 use clap::Parser;
+use code_archiver::file_types::FileTypeRegistry;
 use code_archiver::{ArchiveConfig, CodeArchiver};
+use notify::{RecursiveMode, Watcher};
 use std::process;
 
+/// How long to keep draining incoming filesystem events after the first one
+/// before triggering a rebuild, so a burst of saves (e.g. an editor's
+/// write-then-rename) collapses into a single re-archive.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
 /// A tool for archiving code directories with filtering and formatting options
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -22,7 +31,27 @@ struct Args {
     /// File extensions to include (without leading .)
     #[arg(long)]
     extensions: Vec<String>,
-    
+
+    /// Named file-type preset to include (e.g. `rust`, `web`); repeatable.
+    /// See `--type-list` for the full set.
+    #[arg(long = "type")]
+    file_type: Vec<String>,
+
+    /// Named file-type preset to exclude; repeatable.
+    #[arg(long = "type-not")]
+    type_not: Vec<String>,
+
+    /// Define a custom file type as `name:glob,glob,...` (e.g.
+    /// `foo:*.foo,*.foo2`); repeatable. Overrides a built-in type of the
+    /// same name.
+    #[arg(long = "type-add")]
+    type_add: Vec<String>,
+
+    /// Print every registered file type (built-in plus any `--type-add`)
+    /// and exit.
+    #[arg(long = "type-list")]
+    type_list: bool,
+
     /// Maximum file size in bytes
     #[arg(long)]
     max_size: Option<u64>,
@@ -38,7 +67,33 @@ struct Args {
     /// Don't respect .gitignore files
     #[arg(long)]
     no_gitignore: bool,
-    
+
+    /// Disable all ignore-file processing at once: `.gitignore`, `.ignore`,
+    /// and any `--ignore-file` name, independent of `--no-gitignore`.
+    #[arg(long)]
+    no_ignore: bool,
+
+    /// Extra ignore-file name (besides `.gitignore`/`.ignore`) to honor at
+    /// every directory level, e.g. `.archiveignore`; repeatable.
+    #[arg(long = "ignore-file")]
+    ignore_file: Vec<String>,
+
+    /// Only archive files git reports as tracked or staged, dropping
+    /// anything untracked or ignored. Falls back to the normal walk (with
+    /// a warning) when the target isn't a git repository.
+    #[arg(long = "git-tracked")]
+    git_tracked: bool,
+
+    /// Don't drop files marked `export-ignore` in `.gitattributes`.
+    #[arg(long)]
+    no_export_ignore: bool,
+
+    /// After the initial archive, keep running and re-archive whenever a
+    /// file under the target directory changes, debouncing rapid bursts
+    /// into a single rebuild.
+    #[arg(long)]
+    watch: bool,
+
     /// Output format (json, text)
     #[arg(short, long, default_value = "text")]
     format: String,
@@ -65,63 +120,165 @@ fn main() {
     
     // Use max_size directly as it's now a u64
     let max_file_size = args.max_size;
-    
+
+    // Build the file-type registry: built-ins plus any `--type-add` custom
+    // definitions, which may override a built-in name.
+    let mut type_registry = FileTypeRegistry::new();
+    for spec in &args.type_add {
+        if let Err(e) = type_registry.add(spec) {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+    }
+
+    if args.type_list {
+        for def in type_registry.list() {
+            println!("{:10} {}", def.name, def.globs.join(", "));
+        }
+        return;
+    }
+
+    let mut include = args.include;
+    match type_registry.resolve(&args.file_type) {
+        Ok(globs) => include.extend(globs),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+    }
+
+    let mut exclude = args.exclude;
+    match type_registry.resolve(&args.type_not) {
+        Ok(globs) => exclude.extend(globs),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+    }
+
+    // Resolve the target directory to an absolute path up front, so the
+    // watcher below keeps watching the intended root for the lifetime of
+    // the process even if the working directory changes or the directory
+    // is deleted and recreated.
+    let root_dir = args.dir.canonicalize().unwrap_or(args.dir);
+
+    // Carry the resolved include/exclude patterns in the same `FileFlags`
+    // type `ts-compressor` uses, for a consistent include/ignore shape
+    // across tools. Unlike `ts-compressor`, this walker matches patterns
+    // against paths already made relative to `root_dir` (see
+    // `build_walker` in `code-archiver`'s lib), so they stay relative here
+    // rather than going through `with_absolute_paths`.
+    let flags = common::FileFlags::new(include, exclude);
+
     // Create archive configuration
     let config = ArchiveConfig {
-        root_dir: args.dir,
-        include: if args.include.is_empty() { None } else { Some(args.include) },
-        exclude: if args.exclude.is_empty() { None } else { Some(args.exclude) },
+        root_dir: root_dir.clone(),
+        include: if flags.include.is_empty() { None } else { Some(flags.include) },
+        exclude: if flags.ignore.is_empty() { None } else { Some(flags.ignore) },
         extensions: if args.extensions.is_empty() { None } else { Some(args.extensions) },
         max_size: max_file_size,
         follow_links: args.follow_links,
         hidden: args.hidden,
         gitignore: !args.no_gitignore,
+        no_ignore: args.no_ignore,
+        custom_ignore_filenames: args.ignore_file.clone(),
         include_git_status: false,  // Default to false for CLI
         include_ignored: false,     // Default to false for CLI
+        git_tracked_only: args.git_tracked,
+        respect_export_ignore: !args.no_export_ignore,
+        ..Default::default()
     };
-    
-    // Create and run the archiver
-    match CodeArchiver::new(config) {
-        Ok(archiver) => {
-            match args.format.as_str() {
-                "json" => {
-                    match archiver.archive_to_json() {
-                        Ok(json) => println!("{}", json),
-                        Err(e) => {
-                            eprintln!("Error creating archive: {}", e);
-                            process::exit(1);
-                        }
-                    }
+
+    if !run_archive(&config, &args.format) {
+        process::exit(1);
+    }
+
+    if args.watch {
+        watch_and_rearchive(&root_dir, &config, &args.format);
+    }
+}
+
+/// Run one archive pass with `config` in the requested `format`, printing
+/// results (or errors) the same way for both the one-shot path and every
+/// `--watch` rebuild. Returns whether the pass succeeded.
+fn run_archive(config: &ArchiveConfig, format: &str) -> bool {
+    match CodeArchiver::new(config.clone()) {
+        Ok(archiver) => match format {
+            "json" => match archiver.archive_to_json() {
+                Ok(json) => {
+                    println!("{}", json);
+                    true
+                }
+                Err(e) => {
+                    eprintln!("Error creating archive: {}", e);
+                    false
                 }
-                "text" => {
-                    match archiver.create_archive() {
-                        Ok(entries) => {
-                            let count = entries.len();
-                            for entry in &entries {
-                                println!("{:8}  {}  {}", 
-                                    bytesize::to_string(entry.size, true),
-                                    entry.modified,
-                                    entry.path
-                                );
-                            }
-                            println!("\nTotal: {} files", count);
-                        }
-                        Err(e) => {
-                            eprintln!("Error creating archive: {}", e);
-                            process::exit(1);
-                        }
+            },
+            "text" => match archiver.create_archive() {
+                Ok(entries) => {
+                    let count = entries.len();
+                    for entry in &entries {
+                        println!("{:8}  {}  {}",
+                            bytesize::to_string(entry.size, true),
+                            entry.modified,
+                            entry.path
+                        );
                     }
+                    println!("\nTotal: {} files", count);
+                    true
                 }
-                _ => {
-                    eprintln!("Error: Unsupported format '{}'. Use 'json' or 'text'.", args.format);
-                    process::exit(1);
+                Err(e) => {
+                    eprintln!("Error creating archive: {}", e);
+                    false
                 }
+            },
+            _ => {
+                eprintln!("Error: Unsupported format '{}'. Use 'json' or 'text'.", format);
+                false
             }
-        }
+        },
         Err(e) => {
             eprintln!("Error initializing archiver: {}", e);
+            false
+        }
+    }
+}
+
+/// Watch `root_dir` (already resolved to an absolute path) for changes and
+/// re-run `run_archive` with the same `config`/`format` on every debounced
+/// burst of filesystem events. The watcher is recursive, so deleted and
+/// recreated subdirectories under `root_dir` are picked back up without
+/// restarting the process; exclusion of paths like `target/` or `.git/` is
+/// left to `run_archive`'s normal filtering rather than the watcher, so a
+/// change there still wakes the debounce loop but produces no new entries.
+fn watch_and_rearchive(root_dir: &std::path::Path, config: &ArchiveConfig, format: &str) {
+    let (tx, rx) = channel();
+    let mut watcher = match notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res {
+            let _ = tx.send(event);
+        }
+    }) {
+        Ok(w) => w,
+        Err(e) => {
+            eprintln!("Error: failed to start file watcher: {}", e);
             process::exit(1);
         }
+    };
+
+    if let Err(e) = watcher.watch(root_dir, RecursiveMode::Recursive) {
+        eprintln!("Error: failed to watch '{}': {}", root_dir.display(), e);
+        process::exit(1);
+    }
+
+    println!("Watching '{}' for changes (Ctrl+C to stop)...", root_dir.display());
+
+    while rx.recv().is_ok() {
+        // Drain the rest of this burst so rapid successive events collapse
+        // into a single rebuild.
+        while rx.recv_timeout(WATCH_DEBOUNCE).is_ok() {}
+
+        println!("\nChange detected, rebuilding archive...");
+        run_archive(config, format);
     }
 }
 
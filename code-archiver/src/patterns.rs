@@ -0,0 +1,329 @@
+//! Ordered, gitignore-style matching for `ArchiveConfig`'s `include`/
+//! `exclude` pattern lists.
+//!
+//! A flat `GlobSet` treats every pattern as an independent OR, so there's
+//! no way to re-include a file an earlier broad pattern knocked out (e.g.
+//! exclude `*.log` but keep `important.log`). `PatternSet` instead
+//! compiles `exclude` followed by `include` into one ordered rule list and
+//! evaluates every rule for a candidate path, keeping the verdict of the
+//! *last* rule that matched -- the same semantics a `.gitignore` file
+//! gives a sequence of patterns. A leading `!` flips a rule's default
+//! verdict (an `exclude` entry normally asserts `Ignore`, an `include`
+//! entry normally asserts `Whitelist`), a trailing `/` restricts a rule to
+//! directories, and a pattern containing a non-trailing `/` is anchored to
+//! the root and matched against the relative path, while an unanchored
+//! pattern matches any path's basename.
+
+use globset::{Glob, GlobMatcher};
+use std::path::{Path, PathBuf};
+
+/// The result of evaluating a path against a `PatternSet`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// The last matching rule excludes this path.
+    Ignore,
+    /// The last matching rule explicitly re-includes this path, overriding
+    /// any earlier `Ignore` verdict.
+    Whitelist,
+    /// No rule matched this path.
+    None,
+}
+
+/// A single compiled rule parsed from an `include`/`exclude` pattern.
+#[derive(Debug, Clone)]
+struct Rule {
+    /// What this rule asserts when it matches; always `Ignore` or
+    /// `Whitelist`, never `None`.
+    verdict: Verdict,
+    /// Restricts the rule to directory entries (pattern had a trailing `/`).
+    dir_only: bool,
+    /// Whether the pattern is anchored to the archive root (contained a
+    /// non-trailing `/`) rather than matching any path's basename.
+    anchored: bool,
+    /// The anchored pattern's literal directory prefix (the portion before
+    /// its first wildcard), if any. A candidate path outside this base
+    /// can never match the rule, so `evaluate` skips the glob match
+    /// entirely rather than testing every rule against every file.
+    base: Option<PathBuf>,
+    matcher: GlobMatcher,
+}
+
+/// An ordered list of compiled gitignore-style rules, built once from
+/// `ArchiveConfig::exclude` and `ArchiveConfig::include` and reused across
+/// every `create_archive` walk.
+#[derive(Debug, Clone, Default)]
+pub struct PatternSet {
+    rules: Vec<Rule>,
+    /// Set when at least one `include` pattern was configured, in which
+    /// case a path matched by nothing defaults to excluded -- the
+    /// allow-list behavior `include` has always had -- rather than
+    /// included, which is the default when only `exclude` is configured.
+    exclude_unmatched: bool,
+}
+
+impl PatternSet {
+    /// Compile `exclude` then `include` into one ordered rule list,
+    /// skipping (with a warning) any pattern that fails to compile rather
+    /// than aborting the whole set. Processing `exclude` first means a
+    /// later `include` entry (whitelisted by default, since it came from
+    /// `include`) can re-include a path an earlier `exclude` pattern
+    /// knocked out.
+    pub fn compile(exclude: &[String], include: &[String]) -> Self {
+        let mut rules = Vec::new();
+        for pattern in exclude {
+            Self::push_rule(&mut rules, pattern, Verdict::Ignore);
+        }
+        for pattern in include {
+            Self::push_rule(&mut rules, pattern, Verdict::Whitelist);
+        }
+
+        Self {
+            rules,
+            exclude_unmatched: !include.is_empty(),
+        }
+    }
+
+    fn push_rule(rules: &mut Vec<Rule>, pattern: &str, default_verdict: Verdict) {
+        match Self::parse_rule(pattern, default_verdict) {
+            Ok(rule) => rules.push(rule),
+            Err(e) => tracing::warn!("Invalid glob pattern '{}': {}", pattern, e),
+        }
+    }
+
+    fn parse_rule(pattern: &str, default_verdict: Verdict) -> Result<Rule, globset::Error> {
+        let (negated, pattern) = match pattern.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, pattern),
+        };
+        let (dir_only, pattern) = match pattern.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, pattern),
+        };
+        let anchored = pattern.contains('/');
+        let glob_pattern = pattern.trim_start_matches('/');
+
+        let verdict = if negated {
+            match default_verdict {
+                Verdict::Ignore => Verdict::Whitelist,
+                Verdict::Whitelist => Verdict::Ignore,
+                Verdict::None => Verdict::None,
+            }
+        } else {
+            default_verdict
+        };
+
+        let base = if anchored {
+            let base = literal_base_dir(glob_pattern);
+            if base.is_empty() { None } else { Some(PathBuf::from(base)) }
+        } else {
+            None
+        };
+
+        let matcher = Glob::new(glob_pattern)?.compile_matcher();
+        Ok(Rule { verdict, dir_only, anchored, base, matcher })
+    }
+
+    /// Evaluate `relative_path` (relative to the archive root) against
+    /// every rule in order, returning the verdict of the last one that
+    /// matched, or `Verdict::None` if none did. `is_dir` restricts
+    /// directory-only (trailing-`/`) rules to directory entries.
+    pub fn evaluate(&self, relative_path: &Path, is_dir: bool) -> Verdict {
+        let relative_with_dot = Path::new(".").join(relative_path);
+        let basename = relative_path.file_name();
+        let mut verdict = Verdict::None;
+
+        for rule in &self.rules {
+            if rule.dir_only && !is_dir {
+                continue;
+            }
+
+            if let Some(base) = &rule.base {
+                if !relative_path.starts_with(base) && !relative_with_dot.starts_with(base) {
+                    continue;
+                }
+            }
+
+            let is_match = if rule.anchored {
+                rule.matcher.is_match(relative_path) || rule.matcher.is_match(&relative_with_dot)
+            } else {
+                basename.map_or(false, |name| rule.matcher.is_match(name))
+            };
+
+            if is_match {
+                verdict = rule.verdict;
+            }
+        }
+
+        verdict
+    }
+
+    /// Whether a path matched by nothing should be treated as excluded --
+    /// true whenever at least one `include` pattern was configured.
+    pub fn exclude_unmatched(&self) -> bool {
+        self.exclude_unmatched
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Decompose each `include` pattern into a literal base-directory
+    /// prefix plus its remaining pattern, and return the minimal set of
+    /// directories a walker needs to descend into to find every possible
+    /// match -- the same literal-prefix split `Rule::base` uses to skip
+    /// irrelevant rules during `evaluate`. A pattern with no literal
+    /// prefix (e.g. `**/*.rs`) falls back to `root_dir` itself, and `None`/
+    /// empty `include` falls back to `[root_dir]`, preserving whole-tree
+    /// traversal when there's nothing to narrow it.
+    pub fn compute_walk_roots(root_dir: &Path, include: Option<&[String]>) -> Vec<PathBuf> {
+        let patterns = match include.filter(|p| !p.is_empty()) {
+            Some(patterns) => patterns,
+            None => return vec![root_dir.to_path_buf()],
+        };
+
+        let mut roots = Vec::new();
+        for pattern in patterns {
+            // A negated (`!`) include pattern still has its base walked like
+            // any other include pattern; only the `!` marker is dropped here.
+            let pattern = pattern.strip_prefix('!').unwrap_or(pattern);
+            let base = literal_base_dir(pattern);
+            let base = if base.is_empty() { root_dir.to_path_buf() } else { root_dir.join(base) };
+            // Fall back to the full root if the computed base doesn't
+            // actually exist, rather than silently walking nothing.
+            let base = if base.exists() { base } else { root_dir.to_path_buf() };
+            if !roots.contains(&base) {
+                roots.push(base);
+            }
+        }
+
+        Self::dedup_nested_roots(roots)
+    }
+
+    /// Drop any root that's nested inside another root already kept, so a
+    /// file under two overlapping include bases isn't walked (and archived)
+    /// twice.
+    fn dedup_nested_roots(mut roots: Vec<PathBuf>) -> Vec<PathBuf> {
+        roots.sort();
+        let mut kept: Vec<PathBuf> = Vec::new();
+        for root in roots {
+            if !kept.iter().any(|existing| root.starts_with(existing)) {
+                kept.retain(|existing| !existing.starts_with(&root));
+                kept.push(root);
+            }
+        }
+        kept
+    }
+}
+
+/// The literal directory prefix of a glob pattern, i.e. everything before
+/// the last path separator that precedes the first wildcard character.
+/// Returns an empty string when the pattern has no literal prefix (e.g.
+/// starts with `*` or `**`).
+fn literal_base_dir(pattern: &str) -> &str {
+    match pattern.find(|c| matches!(c, '*' | '?' | '[' | '{')) {
+        Some(wildcard_pos) => match pattern[..wildcard_pos].rfind('/') {
+            Some(slash_pos) => &pattern[..slash_pos],
+            None => "",
+        },
+        // No wildcard at all: the whole pattern is a literal file or
+        // directory path; its parent is the base to walk.
+        None => pattern.rsplit_once('/').map_or("", |(parent, _)| parent),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exclude_only_matches_excludes() {
+        let set = PatternSet::compile(&["*.log".to_string()], &[]);
+        assert_eq!(set.evaluate(Path::new("debug.log"), false), Verdict::Ignore);
+        assert_eq!(set.evaluate(Path::new("readme.md"), false), Verdict::None);
+        assert!(!set.exclude_unmatched());
+    }
+
+    #[test]
+    fn test_include_entry_whitelists_an_earlier_exclude() {
+        let set = PatternSet::compile(&["*.log".to_string()], &["important.log".to_string()]);
+        assert_eq!(set.evaluate(Path::new("debug.log"), false), Verdict::Ignore);
+        assert_eq!(set.evaluate(Path::new("important.log"), false), Verdict::Whitelist);
+    }
+
+    #[test]
+    fn test_include_present_excludes_unmatched_by_default() {
+        let set = PatternSet::compile(&[], &["*.rs".to_string()]);
+        assert!(set.exclude_unmatched());
+        assert_eq!(set.evaluate(Path::new("main.rs"), false), Verdict::Whitelist);
+        assert_eq!(set.evaluate(Path::new("readme.md"), false), Verdict::None);
+    }
+
+    #[test]
+    fn test_negated_include_overrides_a_broader_include() {
+        let set = PatternSet::compile(&[], &["*.rs".to_string(), "!generated.rs".to_string()]);
+        assert_eq!(set.evaluate(Path::new("main.rs"), false), Verdict::Whitelist);
+        assert_eq!(set.evaluate(Path::new("generated.rs"), false), Verdict::Ignore);
+    }
+
+    #[test]
+    fn test_dir_only_rule_ignores_files() {
+        let set = PatternSet::compile(&["build/".to_string()], &[]);
+        assert_eq!(set.evaluate(Path::new("build"), true), Verdict::Ignore);
+        assert_eq!(set.evaluate(Path::new("build"), false), Verdict::None);
+    }
+
+    #[test]
+    fn test_unanchored_pattern_matches_basename_at_any_depth() {
+        let set = PatternSet::compile(&["*.log".to_string()], &[]);
+        assert_eq!(set.evaluate(Path::new("nested/dir/debug.log"), false), Verdict::Ignore);
+    }
+
+    #[test]
+    fn test_anchored_pattern_only_matches_from_root() {
+        let set = PatternSet::compile(&["src/main.rs".to_string()], &[]);
+        assert_eq!(set.evaluate(Path::new("src/main.rs"), false), Verdict::Ignore);
+        assert_eq!(set.evaluate(Path::new("other/src/main.rs"), false), Verdict::None);
+    }
+
+    #[test]
+    fn test_last_matching_rule_wins() {
+        let set = PatternSet::compile(
+            &["*.rs".to_string(), "!keep.rs".to_string(), "keep.rs".to_string()],
+            &[],
+        );
+        assert_eq!(set.evaluate(Path::new("keep.rs"), false), Verdict::Ignore);
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_skipped_not_fatal() {
+        let set = PatternSet::compile(&["[".to_string(), "*.log".to_string()], &[]);
+        assert_eq!(set.evaluate(Path::new("debug.log"), false), Verdict::Ignore);
+    }
+
+    #[test]
+    fn test_anchored_rule_base_skips_files_outside_its_prefix() {
+        let set = PatternSet::compile(&["src/*.rs".to_string()], &[]);
+        assert_eq!(set.evaluate(Path::new("src/main.rs"), false), Verdict::Ignore);
+        assert_eq!(set.evaluate(Path::new("other/main.rs"), false), Verdict::None);
+    }
+
+    #[test]
+    fn test_compute_walk_roots_narrows_to_include_base() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::create_dir_all(dir.path().join("docs")).unwrap();
+
+        let roots = PatternSet::compute_walk_roots(
+            dir.path(),
+            Some(&["src/**/*.rs".to_string()]),
+        );
+
+        assert_eq!(roots, vec![dir.path().join("src")]);
+    }
+
+    #[test]
+    fn test_compute_walk_roots_falls_back_to_root_without_include() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert_eq!(PatternSet::compute_walk_roots(dir.path(), None), vec![dir.path().to_path_buf()]);
+    }
+}
@@ -1,7 +1,8 @@
 #![cfg(feature = "test-utils")]
 
-use code_archiver::git::{GitContext, GitStatus};
+use code_archiver::git::{GitContext, GitFileStatus, WorktreeState};
 use code_archiver::test_utils::TestGitRepo;
+use code_archiver::{ArchiveConfig, CodeArchiver};
 use std::fs;
 
 #[test]
@@ -29,11 +30,57 @@ fn test_git_ignore() -> Result<(), Box<dyn std::error::Error>> {
     assert!(git_ctx.is_ignored(&ignored_file)?);
     
     // Verify status
-    assert_eq!(git_ctx.get_status(&tracked_file)?.unwrap(), GitStatus::Unmodified);
-    
+    assert_eq!(git_ctx.get_status(&tracked_file)?.unwrap(), GitFileStatus::default());
+
     // Modify the tracked file and check status
     fs::write(&tracked_file, "modified content")?;
-    assert_eq!(git_ctx.get_status(&tracked_file)?.unwrap(), GitStatus::Modified);
-    
+    assert_eq!(
+        git_ctx.get_status(&tracked_file)?.unwrap().worktree,
+        Some(WorktreeState::Modified)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_respect_export_ignore_drops_marked_files() -> Result<(), Box<dyn std::error::Error>> {
+    let test_repo = TestGitRepo::new();
+
+    test_repo.add_file(".gitattributes", "*.log export-ignore\n");
+    test_repo.add_file("debug.log", "noisy");
+    test_repo.add_file("keep.txt", "kept");
+    test_repo.commit("Initial commit");
+
+    let config = ArchiveConfig {
+        root_dir: test_repo.temp_dir.path().to_path_buf(),
+        ..Default::default()
+    };
+    let archiver = CodeArchiver::new(config)?;
+    let entries = archiver.create_archive()?;
+
+    assert!(entries.iter().any(|e| e.path == "keep.txt"));
+    assert!(!entries.iter().any(|e| e.path == "debug.log"));
+
+    Ok(())
+}
+
+#[test]
+fn test_respect_export_ignore_false_keeps_marked_files() -> Result<(), Box<dyn std::error::Error>> {
+    let test_repo = TestGitRepo::new();
+
+    test_repo.add_file(".gitattributes", "*.log export-ignore\n");
+    test_repo.add_file("debug.log", "noisy");
+    test_repo.commit("Initial commit");
+
+    let config = ArchiveConfig {
+        root_dir: test_repo.temp_dir.path().to_path_buf(),
+        respect_export_ignore: false,
+        ..Default::default()
+    };
+    let archiver = CodeArchiver::new(config)?;
+    let entries = archiver.create_archive()?;
+
+    assert!(entries.iter().any(|e| e.path == "debug.log"));
+
     Ok(())
 }
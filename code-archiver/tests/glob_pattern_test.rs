@@ -132,13 +132,19 @@ fn test_glob_pattern_validation() -> Result<(), Box<dyn std::error::Error>> {
     
     let archiver = CodeArchiver::new(config)?;
     let entries = archiver.create_archive()?;
-    
-    // Should only include .rs files (Cargo.toml is not included because the pattern matching needs to be fixed)
-    assert_eq!(entries.len(), 5, "Expected 5 .rs files, found: {:?}", 
+
+    // Should include the 5 .rs files plus Cargo.toml, now that patterns are
+    // matched against the path relative to root_dir instead of the absolute
+    // filesystem path (a literal pattern like "Cargo.toml" can never match
+    // an absolute path).
+    assert_eq!(entries.len(), 6, "Expected 5 .rs files plus Cargo.toml, found: {:?}",
         entries.iter().map(|e| e.path.as_str()).collect::<Vec<_>>());
-    assert!(entries.iter().all(|e| e.path.ends_with(".rs")), 
-        "Not all entries are .rs files: {:?}", 
-        entries.iter().filter(|e| !e.path.ends_with(".rs")).collect::<Vec<_>>());
+    assert!(entries.iter().any(|e| e.path == "Cargo.toml"),
+        "Cargo.toml should be included: {:?}",
+        entries.iter().map(|e| e.path.as_str()).collect::<Vec<_>>());
+    assert!(entries.iter().all(|e| e.path.ends_with(".rs") || e.path == "Cargo.toml"),
+        "Unexpected entry outside .rs files and Cargo.toml: {:?}",
+        entries.iter().filter(|e| !e.path.ends_with(".rs") && e.path != "Cargo.toml").collect::<Vec<_>>());
     
     // Test 4: Invalid glob pattern (should not panic)
     let config = ArchiveConfig {
@@ -1,6 +1,5 @@
 use assert_fs::prelude::*;
 use code_archiver::{ArchiveConfig, CodeArchiver};
-use code_archiver::git::GitStatus;
 use std::path::PathBuf;
 
 #[test]
@@ -0,0 +1,212 @@
+//! Layered, INI-style configuration with `%include` and `%unset` directives.
+//!
+//! Sections are written as `[name]`, entries as `key = value`, `;` and `#`
+//! start a comment, and an indented line continues the previous entry's
+//! value across multiple lines. `%include <path>` merges another config
+//! file in at that point (paths resolved relative to the including file,
+//! recursively, with cycle detection); `%unset <key>` (or `%unset
+//! section.key` outside the section it targets) removes a key set by an
+//! earlier layer. Layers apply in the order they're read, so a later file
+//! or include overrides an earlier one, letting a machine-local override
+//! file sit on top of a shared base config without duplicating its rules.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+use crate::path::PathExt;
+
+/// A merged configuration built from one or more layered INI-style files.
+///
+/// Within a single file, repeating a key (e.g. several `include = ...`
+/// lines) accumulates a list; a key set by a *later* layer (a subsequent
+/// `%include`, or a later call to [`LayeredConfig::merge_file`]) replaces
+/// whatever an earlier layer set for that key rather than appending to it.
+#[derive(Debug, Clone, Default)]
+pub struct LayeredConfig {
+    sections: BTreeMap<String, BTreeMap<String, Vec<String>>>,
+}
+
+impl LayeredConfig {
+    /// Parse `path` (and anything it `%include`s) into a fresh config.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut config = Self::default();
+        config.merge_file(path)?;
+        Ok(config)
+    }
+
+    /// Layer `path` (and anything it `%include`s) on top of the config
+    /// accumulated so far; keys it sets override the same keys from
+    /// earlier layers.
+    pub fn merge_file<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let mut ancestry = HashSet::new();
+        load_into(path.as_ref(), self, &mut ancestry)
+    }
+
+    /// All values recorded for `section.key`, in the order they were set;
+    /// empty if the key was never set (or was `%unset`).
+    pub fn get(&self, section: &str, key: &str) -> &[String] {
+        self.sections
+            .get(section)
+            .and_then(|keys| keys.get(key))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// The last value recorded for `section.key`, for settings that only
+    /// make sense as a single value (e.g. an output path).
+    pub fn get_one(&self, section: &str, key: &str) -> Option<&str> {
+        self.get(section, key).last().map(String::as_str)
+    }
+
+    fn set(&mut self, section: &str, key: &str, value: String) {
+        self.sections
+            .entry(section.to_string())
+            .or_default()
+            .insert(key.to_string(), vec![value]);
+    }
+
+    fn push(&mut self, section: &str, key: &str, value: String) {
+        self.sections
+            .entry(section.to_string())
+            .or_default()
+            .entry(key.to_string())
+            .or_default()
+            .push(value);
+    }
+
+    fn unset(&mut self, section: &str, key: &str) {
+        if let Some(keys) = self.sections.get_mut(section) {
+            keys.remove(key);
+        }
+    }
+
+    /// Project the merged config onto the settings an archiver's walker
+    /// and dictionary builder care about: `[archive] include`/`ignore`
+    /// (multi-valued) and `output` (single-valued) from the `archive`
+    /// section, plus every key in the `dictionary` section passed through
+    /// as raw strings for the caller's own dictionary config type to parse.
+    pub fn archive_settings(&self) -> ArchiveSettings {
+        let dictionary = self
+            .sections
+            .get("dictionary")
+            .map(|keys| {
+                keys.iter()
+                    .filter_map(|(k, v)| v.last().map(|value| (k.clone(), value.clone())))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        ArchiveSettings {
+            include: self.get("archive", "include").to_vec(),
+            ignore: self.get("archive", "ignore").to_vec(),
+            output: self.get_one("archive", "output").map(PathBuf::from),
+            dictionary,
+        }
+    }
+}
+
+/// The subset of a [`LayeredConfig`] a walker and dictionary builder need,
+/// with the `archive`/`dictionary` section convention already resolved.
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveSettings {
+    /// Include glob patterns, in the order they were set in the winning layer.
+    pub include: Vec<String>,
+    /// Ignore glob patterns, in the order they were set in the winning layer.
+    pub ignore: Vec<String>,
+    /// Output path, if the config specifies one.
+    pub output: Option<PathBuf>,
+    /// Raw `[dictionary]` section entries, for a dictionary builder's own
+    /// config type to parse and validate.
+    pub dictionary: BTreeMap<String, String>,
+}
+
+fn load_into(path: &Path, merged: &mut LayeredConfig, ancestry: &mut HashSet<PathBuf>) -> Result<()> {
+    let canonical = path.canonicalize_path().unwrap_or_else(|_| path.to_path_buf());
+    if !ancestry.insert(canonical.clone()) {
+        return Err(Error::invalid_input(format!(
+            "config include cycle detected at '{}'",
+            path.display()
+        )));
+    }
+
+    let content = crate::fs::read_to_string(path)?;
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+
+    let mut section = String::new();
+    let mut touched: HashSet<(String, String)> = HashSet::new();
+    let mut pending: Option<(String, String, String)> = None;
+
+    for raw_line in content.lines() {
+        if let Some((sect, key, value)) = pending.take() {
+            if raw_line.starts_with(' ') || raw_line.starts_with('\t') {
+                let mut value = value;
+                value.push('\n');
+                value.push_str(raw_line.trim());
+                pending = Some((sect, key, value));
+                continue;
+            }
+            apply_entry(merged, &mut touched, sect, key, value);
+        }
+
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("%include") {
+            let include_rel = rest.trim();
+            let include_path = if Path::new(include_rel).is_absolute() {
+                PathBuf::from(include_rel)
+            } else {
+                base_dir.join(include_rel)
+            };
+            load_into(&include_path, merged, ancestry)?;
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("%unset") {
+            let dotted = rest.trim();
+            match dotted.split_once('.') {
+                Some((sect, key)) => merged.unset(sect, key),
+                None => merged.unset(&section, dotted),
+            }
+            continue;
+        }
+
+        if line.starts_with('[') && line.ends_with(']') {
+            section = line[1..line.len() - 1].trim().to_string();
+            continue;
+        }
+
+        if let Some((key, value)) = line.split_once('=') {
+            pending = Some((section.clone(), key.trim().to_string(), value.trim().to_string()));
+            continue;
+        }
+
+        return Err(Error::invalid_input(format!(
+            "unrecognized config line in '{}': '{}'",
+            path.display(),
+            raw_line
+        )));
+    }
+
+    if let Some((sect, key, value)) = pending.take() {
+        apply_entry(merged, &mut touched, sect, key, value);
+    }
+
+    ancestry.remove(&canonical);
+    Ok(())
+}
+
+/// Record a `key = value` entry parsed from a single file: the first time a
+/// key is touched during this file's parse it replaces whatever an earlier
+/// layer set (`%unset`-like override-on-entry), and every repeat within the
+/// same file accumulates instead.
+fn apply_entry(merged: &mut LayeredConfig, touched: &mut HashSet<(String, String)>, section: String, key: String, value: String) {
+    if touched.insert((section.clone(), key.clone())) {
+        merged.set(&section, &key, value);
+    } else {
+        merged.push(&section, &key, value);
+    }
+}
@@ -40,6 +40,18 @@ pub enum Error {
     /// Custom error
     #[error("{0}")]
     Custom(String),
+
+    /// A human-readable context message wrapping an underlying error,
+    /// preserving it as the source instead of flattening it into a string.
+    /// Building a chain of these (e.g. via repeated [`ResultExt::context`]
+    /// calls) lets [`Error::chain`] walk back from the outermost context to
+    /// the root cause.
+    #[error("{context}")]
+    Context {
+        context: String,
+        #[source]
+        source: Box<Error>,
+    },
 }
 
 impl Error {
@@ -69,6 +81,129 @@ impl Error {
             _ => io::Error::new(io::ErrorKind::Other, self.to_string()),
         }
     }
+
+    /// Walk the chain of errors from `self` (outermost context first) down
+    /// to the root cause, following [`Error::Context`] links.
+    pub fn chain(&self) -> Chain<'_> {
+        Chain { next: Some(self) }
+    }
+
+    /// The [`io::ErrorKind`] of the first [`Error::Io`] found while walking
+    /// [`Error::chain`], so callers that match on IO error kinds keep
+    /// working even when the `Io` variant is wrapped in layers of
+    /// [`Error::Context`].
+    pub fn io_kind(&self) -> Option<io::ErrorKind> {
+        self.chain().find_map(|err| match err {
+            Error::Io(e) => Some(e.kind()),
+            _ => None,
+        })
+    }
+
+    /// Render this error as a cargo-style `caused by:` chain: the outermost
+    /// message first, then each deeper cause under its own `Caused by:`
+    /// heading, indented four spaces.
+    pub fn caused_by(&self) -> String {
+        let mut out = String::new();
+        for (i, err) in self.chain().enumerate() {
+            if i == 0 {
+                out.push_str(&err.to_string());
+            } else {
+                out.push_str("\n\nCaused by:\n");
+                out.push_str(&format!("    {}", err));
+            }
+        }
+        out
+    }
+}
+
+/// Iterator over an [`Error`] chain, from the outermost context to the root
+/// cause. Returned by [`Error::chain`].
+pub struct Chain<'a> {
+    next: Option<&'a Error>,
+}
+
+impl<'a> Iterator for Chain<'a> {
+    type Item = &'a Error;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next.take()?;
+        self.next = match current {
+            Error::Context { source, .. } => Some(source),
+            _ => None,
+        };
+        Some(current)
+    }
+}
+
+/// A top-level failure paired with the process exit code it should produce,
+/// modeled on cargo's own exit-code mapping: `error` is `None` when the
+/// failure was already reported upstream (e.g. by clap) and only the code
+/// needs to propagate.
+#[derive(Debug)]
+pub struct CliError {
+    pub error: Option<Error>,
+    pub exit_code: i32,
+}
+
+impl CliError {
+    /// Wrap `error`, deriving its exit code from [`exit_code_for`].
+    pub fn new(error: Error) -> Self {
+        let exit_code = exit_code_for(&error);
+        Self {
+            error: Some(error),
+            exit_code,
+        }
+    }
+
+    /// An exit code with no error to report, for failures already printed
+    /// by something else (e.g. argument parsing).
+    pub fn with_code(exit_code: i32) -> Self {
+        Self {
+            error: None,
+            exit_code,
+        }
+    }
+
+    /// Print the wrapped error's `caused by:` chain to stderr, if any.
+    pub fn report(&self) {
+        if let Some(error) = &self.error {
+            eprintln!("Error: {}", error.caused_by());
+        }
+    }
+}
+
+impl From<Error> for CliError {
+    fn from(error: Error) -> Self {
+        Self::new(error)
+    }
+}
+
+/// Map an [`Error`]'s root cause to a stable process exit code: invalid
+/// input is a user mistake (`2`), a missing path or file is distinct from a
+/// broader I/O failure (`3`), a git failure is its own category (`4`),
+/// (de)serialization problems are data-format issues (`5`), and anything
+/// else is treated as an internal error (`101`), mirroring the distinction
+/// rustc/cargo draw between usage errors and ICEs.
+pub fn exit_code_for(error: &Error) -> i32 {
+    match error.chain().last().unwrap_or(error) {
+        Error::InvalidInput(_) => 2,
+        Error::Path(_) => 3,
+        Error::Io(e) => exit_code_for_io_kind(e.kind()),
+        Error::Git(_) => 4,
+        Error::Serialization(_) | Error::Deserialization(_) => 5,
+        Error::Custom(_) | Error::Context { .. } => 101,
+    }
+}
+
+/// Map a bare [`io::ErrorKind`] to the same exit code [`exit_code_for`]
+/// would give an [`Error::Io`] wrapping it, for callers that only have the
+/// underlying `io::Error` (e.g. a CLI downcasting `anyhow::Error`) rather
+/// than a full [`Error`].
+pub fn exit_code_for_io_kind(kind: io::ErrorKind) -> i32 {
+    match kind {
+        io::ErrorKind::NotFound => 3,
+        _ => 101,
+    }
 }
 
 impl From<Error> for io::Error {
@@ -91,17 +226,9 @@ pub trait ResultExt<T, E> {
 
 impl<T, E: Into<Error>> ResultExt<T, E> for std::result::Result<T, E> {
     fn context(self, context: impl Into<String>) -> Result<T> {
-        self.map_err(|e| {
-            let mut err: Error = e.into();
-            match &mut err {
-                Error::Custom(msg) => {
-                    *msg = format!("{}: {}", context.into(), msg);
-                }
-                _ => {
-                    err = Error::Custom(format!("{}: {}", context.into(), err));
-                }
-            }
-            err
+        self.map_err(|e| Error::Context {
+            context: context.into(),
+            source: Box::new(e.into()),
         })
     }
 }
@@ -121,6 +248,9 @@ impl<T> IoResultExt<T> for std::result::Result<T, io::Error> {
         F: FnOnce() -> S,
         S: Into<String>,
     {
-        self.map_err(|e| Error::Io(io::Error::new(e.kind(), context().into())))
+        self.map_err(|e| Error::Context {
+            context: context().into(),
+            source: Box::new(Error::Io(e)),
+        })
     }
 }
@@ -0,0 +1,53 @@
+//! A shared include/ignore pattern set, so the archiver tools resolve
+//! relative patterns against the same base directory instead of each
+//! binary rolling its own path handling.
+
+use std::path::Path;
+
+/// The glob patterns a file walker filters by: `include` narrows the walk
+/// to paths matching at least one pattern (or everything, if empty), and
+/// `ignore` drops a path matching any of its patterns regardless of
+/// `include`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileFlags {
+    /// Patterns a path must match at least one of to be included.
+    pub include: Vec<String>,
+    /// Patterns that exclude a path matching any of them.
+    pub ignore: Vec<String>,
+}
+
+impl FileFlags {
+    /// Build a flag set from raw include/ignore pattern lists, without any
+    /// path normalization.
+    pub fn new(include: Vec<String>, ignore: Vec<String>) -> Self {
+        Self { include, ignore }
+    }
+
+    /// Rewrite every relative include/ignore entry to be rooted at `base`,
+    /// leaving already-absolute entries and URL-like entries (`http:`,
+    /// `https:`, `file:`) untouched. This makes filtering deterministic
+    /// regardless of the caller's current working directory.
+    pub fn with_absolute_paths<P: AsRef<Path>>(&self, base: P) -> Self {
+        let base = base.as_ref();
+        Self {
+            include: self.include.iter().map(|p| root_pattern(p, base)).collect(),
+            ignore: self.ignore.iter().map(|p| root_pattern(p, base)).collect(),
+        }
+    }
+}
+
+/// Root a single pattern at `base` unless it's already absolute or looks
+/// like a URL (`scheme:` prefix) rather than a filesystem path.
+fn root_pattern(pattern: &str, base: &Path) -> String {
+    if is_url_like(pattern) || Path::new(pattern).is_absolute() {
+        return pattern.to_string();
+    }
+
+    base.join(pattern).to_string_lossy().into_owned()
+}
+
+fn is_url_like(pattern: &str) -> bool {
+    ["http:", "https:", "file:"]
+        .iter()
+        .any(|scheme| pattern.starts_with(scheme))
+}
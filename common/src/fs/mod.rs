@@ -2,6 +2,8 @@
 
 mod file;
 mod metadata;
+mod walker;
 
 pub use file::*;
 pub use metadata::*;
+pub use walker::{WalkStats, Walker};
@@ -0,0 +1,174 @@
+//! Glob-based include/exclude file selection that only descends into
+//! directories an include pattern could actually match.
+
+use std::path::{Path, PathBuf};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+use crate::error::{Error, Result};
+use crate::fs::metadata::{metadata, Metadata};
+
+/// Counts gathered while `Walker::walk` runs, for a caller to fold into its
+/// own traversal/archive statistics.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WalkStats {
+    /// Directories opened and read.
+    pub dirs_visited: usize,
+    /// Directories pruned by an ignore pattern before being opened.
+    pub dirs_pruned: usize,
+    /// Files that matched an include pattern and no ignore pattern.
+    pub files_matched: usize,
+    /// Files visited but rejected by the include/ignore patterns.
+    pub files_skipped: usize,
+}
+
+/// Walks a directory tree selecting files by include glob and rejecting
+/// them (or whole subtrees) by ignore glob, without ever pre-expanding
+/// either pattern set into a file list.
+///
+/// Each include pattern is split into a literal directory prefix (the path
+/// components before its first wildcard) and the pattern itself; the walk
+/// only recurses into directories under one of these prefixes, instead of
+/// enumerating the whole tree and matching every entry against every
+/// pattern. Ignore patterns are checked against each directory as it's
+/// opened, so a matching directory prunes its entire subtree rather than
+/// every descendant being matched individually.
+#[derive(Debug)]
+pub struct Walker {
+    include_roots: Vec<PathBuf>,
+    includes: GlobSet,
+    ignores: GlobSet,
+}
+
+impl Walker {
+    /// Build a walker from a set of include globs and a set of ignore
+    /// globs. Non-absolute patterns are joined onto `base_dir` before
+    /// being compiled, so callers can pass patterns relative to a project
+    /// root without resolving them first.
+    pub fn new<P: AsRef<Path>>(base_dir: P, includes: &[String], ignores: &[String]) -> Result<Self> {
+        let base_dir = base_dir.as_ref();
+
+        let mut include_builder = GlobSetBuilder::new();
+        let mut include_roots = Vec::new();
+        for pattern in includes {
+            let joined = join_pattern(base_dir, pattern);
+            include_builder.add(compile_glob(&joined)?);
+
+            let prefix = literal_prefix(&joined);
+            include_roots.push(if prefix.as_os_str().is_empty() { base_dir.to_path_buf() } else { prefix });
+        }
+        let includes = include_builder.build().map_err(|e| Error::invalid_input(e.to_string()))?;
+
+        let mut ignore_builder = GlobSetBuilder::new();
+        for pattern in ignores {
+            ignore_builder.add(compile_glob(&join_pattern(base_dir, pattern))?);
+        }
+        let ignores = ignore_builder.build().map_err(|e| Error::invalid_input(e.to_string()))?;
+
+        Ok(Self { include_roots: dedup_roots(include_roots), includes, ignores })
+    }
+
+    /// Walk the tree, returning `Metadata` for every matching file plus the
+    /// counts gathered along the way.
+    pub fn walk(&self) -> Result<(Vec<Metadata>, WalkStats)> {
+        let mut matches = Vec::new();
+        let mut stats = WalkStats::default();
+
+        for root in &self.include_roots {
+            if root.is_dir() {
+                self.visit_dir(root, &mut matches, &mut stats)?;
+            } else if root.is_file() {
+                // A literal include pattern with no wildcards at all (e.g.
+                // "Cargo.toml") has itself as its own prefix.
+                self.visit_file(root, &mut matches, &mut stats);
+            }
+        }
+
+        Ok((matches, stats))
+    }
+
+    fn visit_dir(&self, dir: &Path, matches: &mut Vec<Metadata>, stats: &mut WalkStats) -> Result<()> {
+        if self.ignores.is_match(dir) {
+            stats.dirs_pruned += 1;
+            return Ok(());
+        }
+        stats.dirs_visited += 1;
+
+        let entries = std::fs::read_dir(dir).map_err(|e| Error::path_error(dir, e.to_string()))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| Error::path_error(dir, e.to_string()))?;
+            let path = entry.path();
+            let file_type = entry.file_type().map_err(|e| Error::path_error(&path, e.to_string()))?;
+
+            if file_type.is_dir() {
+                self.visit_dir(&path, matches, stats)?;
+            } else if file_type.is_file() {
+                self.visit_file(&path, matches, stats);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn visit_file(&self, path: &Path, matches: &mut Vec<Metadata>, stats: &mut WalkStats) {
+        if self.ignores.is_match(path) || !self.includes.is_match(path) {
+            stats.files_skipped += 1;
+            return;
+        }
+
+        match metadata(path) {
+            Ok(meta) => {
+                stats.files_matched += 1;
+                matches.push(meta);
+            }
+            Err(_) => stats.files_skipped += 1,
+        }
+    }
+}
+
+fn compile_glob(pattern: &str) -> Result<Glob> {
+    Glob::new(pattern).map_err(|e| Error::invalid_input(format!("invalid glob pattern '{}': {}", pattern, e)))
+}
+
+/// Join `pattern` onto `base_dir` unless it's already absolute, so patterns
+/// can be supplied relative to a project root.
+fn join_pattern(base_dir: &Path, pattern: &str) -> String {
+    if Path::new(pattern).is_absolute() {
+        pattern.to_string()
+    } else {
+        base_dir.join(pattern).to_string_lossy().into_owned()
+    }
+}
+
+/// Split `pattern` into the path components preceding its first wildcard
+/// (`*`, `?`, `[`, `{`) — the directory a walk must start from to have any
+/// chance of matching it. A pattern with no wildcards returns itself
+/// unchanged.
+fn literal_prefix(pattern: &str) -> PathBuf {
+    let mut prefix = PathBuf::new();
+    for component in Path::new(pattern).components() {
+        let part = component.as_os_str().to_string_lossy();
+        if part.contains(['*', '?', '[', '{']) {
+            break;
+        }
+        prefix.push(component.as_os_str());
+    }
+    prefix
+}
+
+/// Drop any root that's a descendant of another root already in the list,
+/// so overlapping include patterns (e.g. `src/**/*.rs` and
+/// `src/utils/*.rs`) don't walk the same subtree twice and double-count or
+/// duplicate matches.
+fn dedup_roots(mut roots: Vec<PathBuf>) -> Vec<PathBuf> {
+    roots.sort();
+    roots.dedup();
+
+    let mut kept: Vec<PathBuf> = Vec::with_capacity(roots.len());
+    for root in roots {
+        if !kept.iter().any(|parent| root.starts_with(parent)) {
+            kept.push(root);
+        }
+    }
+    kept
+}
@@ -4,9 +4,12 @@
 #![warn(rust_2018_idioms)]
 #![warn(missing_debug_implementations)]
 
+pub mod config;
 pub mod error;
+pub mod flags;
 pub mod fs;
 pub mod path;
 
 // Re-exports
 pub use error::{Error, Result};
+pub use flags::FileFlags;
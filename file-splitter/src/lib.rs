@@ -1,10 +1,21 @@
 //! A library for splitting files into smaller chunks with various strategies.
 
 use std::path::{Path, PathBuf};
+use std::collections::HashSet;
 use std::fs::File;
 use std::io::{Read, Write};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
 use thiserror::Error;
 use std::fmt;
+use rayon::prelude::*;
+use code_archiver::git::{GitContext, GitStatus};
+
+/// Hex-encoded BLAKE3 hash of a chunk's contents, used to name and
+/// deduplicate chunks when `SplitConfig::dedup` is enabled
+fn content_hash(data: &[u8]) -> String {
+    blake3::hash(data).to_hex().to_string()
+}
 
 /// Custom error type for file splitting operations
 #[derive(Error, Debug)]
@@ -24,28 +35,91 @@ pub enum SplitError {
     /// Invalid output directory
     #[error("Invalid output directory: {0}")]
     InvalidOutputDir(String),
+
+    /// Manifest is missing, malformed, or has the wrong magic/version
+    #[error("Invalid manifest: {0}")]
+    InvalidManifest(String),
+
+    /// A chunk's checksum did not match the manifest, or the chunk is missing
+    #[error("Checksum mismatch for chunk {index}: {reason}")]
+    ChecksumMismatch {
+        /// Index of the offending chunk
+        index: usize,
+        /// Human-readable description of the mismatch
+        reason: String,
+    },
 }
 
 /// Result type for file splitting operations
 pub type Result<T> = std::result::Result<T, SplitError>;
 
+/// How chunk boundaries are chosen
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChunkStrategy {
+    /// Cut every `chunk_size` bytes, regardless of content
+    FixedSize,
+    /// Content-defined chunking (FastCDC): boundaries depend on a rolling
+    /// fingerprint of the data, so inserting or removing bytes only
+    /// reshuffles the chunks around the edit instead of every chunk after it
+    FastCdc {
+        /// Smallest chunk allowed; no cut point is tested before this many bytes
+        min: u64,
+        /// Target average chunk size, used to pick between the strict and
+        /// loose cut masks
+        avg: u64,
+        /// Largest chunk allowed; a cut is forced here even with no boundary
+        max: u64,
+    },
+}
+
+/// Compression applied to each chunk before it is written
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompressionKind {
+    /// Compress with zstd at the given level
+    Zstd {
+        /// zstd compression level (1-22; higher is slower but smaller)
+        level: i32,
+    },
+}
+
 /// Configuration for file splitting
 #[derive(Debug, Clone)]
 pub struct SplitConfig {
     /// Path to the input file
     pub input_path: String,
-    
+
     /// Directory to output chunks (defaults to same as input file)
     pub output_dir: Option<String>,
-    
-    /// Size of each chunk in bytes
+
+    /// Size of each chunk in bytes (used by `ChunkStrategy::FixedSize`)
     pub chunk_size: u64,
-    
+
+    /// How chunk boundaries are determined
+    pub strategy: ChunkStrategy,
+
     /// Prefix for output chunk filenames (defaults to input filename)
     pub prefix: Option<String>,
-    
+
     /// Number of digits to use in chunk numbering (default: 3)
     pub digits: u8,
+
+    /// When `true`, name chunks by their content hash and skip writing a
+    /// chunk whose hash is already present in `output_dir`, turning the
+    /// splitter into a content-addressed, deduplicating chunk store
+    pub dedup: bool,
+
+    /// When set, each chunk is compressed before being written and a
+    /// `.zst` suffix is appended to its filename
+    pub compression: Option<CompressionKind>,
+
+    /// Number of worker threads used to compress/write chunks concurrently.
+    /// Defaults to the available core count when `None`.
+    pub parallelism: Option<usize>,
+
+    /// Root of the git repository containing `input_path`, if any. When set,
+    /// the input file's git blob OID and `GitStatus` are recorded in the
+    /// manifest so a reassembled file can be checked against what git committed.
+    pub git_root: Option<PathBuf>,
 }
 
 impl Default for SplitConfig {
@@ -54,19 +128,92 @@ impl Default for SplitConfig {
             input_path: String::new(),
             output_dir: None,
             chunk_size: 1024 * 1024, // 1MB default chunk size
+            strategy: ChunkStrategy::FixedSize,
+            dedup: false,
+            compression: None,
+            parallelism: None,
+            git_root: None,
             prefix: None,
             digits: 3,
         }
     }
 }
 
+/// 256-entry table of random `u64` values used to roll the FastCDC gear hash
+const GEAR: [u64; 256] = build_gear_table();
+
+const fn build_gear_table() -> [u64; 256] {
+    // A fixed xorshift-style LCG seeded with a constant, expanded at compile
+    // time so the table is deterministic without pulling in a rng dependency
+    let mut table = [0u64; 256];
+    let mut seed: u64 = 0x2545F4914F6CDD1D;
+    let mut i = 0;
+    while i < 256 {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        table[i] = seed;
+        i += 1;
+    }
+    table
+}
+
+/// Stricter mask (more 1-bits, fewer cut points) used below the average size
+const MASK_S: u64 = 0x0003_5907_0353_0000;
+/// Looser mask (fewer 1-bits, more cut points) used above the average size
+const MASK_L: u64 = 0x0000_d900_0353_0000;
+
+/// Find FastCDC cut points within `data`, returning the length of each chunk.
+/// Uses normalized chunking: `mask_s` while below `avg`, `mask_l` once past it.
+fn fastcdc_boundaries(data: &[u8], min: u64, avg: u64, max: u64) -> Vec<u64> {
+    let min = min.max(1);
+    let avg = avg.max(min);
+    let max = max.max(avg);
+
+    let mut boundaries = Vec::new();
+    let mut start = 0usize;
+
+    while start < data.len() {
+        let remaining = data.len() - start;
+        if remaining as u64 <= min {
+            boundaries.push(remaining as u64);
+            break;
+        }
+
+        let max_len = max.min(remaining as u64) as usize;
+        let mut fp: u64 = 0;
+        let mut len = min as usize;
+        let mut cut = max_len;
+
+        while len < max_len {
+            let b = data[start + len];
+            fp = (fp << 1).wrapping_add(GEAR[b as usize]);
+            let mask = if (len as u64) < avg { MASK_S } else { MASK_L };
+            if fp & mask == 0 {
+                cut = len;
+                break;
+            }
+            len += 1;
+        }
+
+        boundaries.push(cut as u64);
+        start += cut;
+    }
+
+    boundaries
+}
+
 /// Represents a chunk of a file
 #[derive(Debug)]
 pub struct FileChunk {
     /// The path to the chunk file
     pub path: PathBuf,
-    /// The size of the chunk in bytes
+    /// The uncompressed size of the chunk in bytes
     pub size: u64,
+    /// FNV-1a checksum of the chunk's uncompressed contents, as recorded in the manifest
+    pub checksum: u64,
+    /// Size of the chunk on disk after compression, if `compression` was set
+    pub compressed_size: Option<u64>,
 }
 
 impl fmt::Display for FileChunk {
@@ -95,6 +242,17 @@ pub struct SplitResult {
     pub total_chunks: usize,
     /// Total size of the original file in bytes
     pub total_size: u64,
+    /// Number of chunks whose content was not already present in the store
+    /// (equal to `total_chunks` unless `dedup` is enabled)
+    pub unique_chunks: usize,
+    /// Bytes saved by skipping chunks whose content hash already existed
+    /// on disk (always 0 unless `dedup` is enabled)
+    pub deduplicated_bytes: u64,
+    /// Total on-disk size across all written chunks after compression
+    /// (equal to `total_size` minus deduplicated bytes when `compression` is `None`)
+    pub compressed_size: u64,
+    /// `compressed_size / total_size`, or `None` if there was nothing to split
+    pub compression_ratio: Option<f64>,
 }
 
 impl fmt::Display for SplitResult {
@@ -158,9 +316,6 @@ pub fn split_file(config: &SplitConfig) -> Result<SplitResult> {
         return Err(SplitError::InvalidInputPath("Input file is empty".into()));
     }
     
-    // Calculate number of chunks needed
-    let total_chunks = ((file_size as f64) / (config.chunk_size as f64)).ceil() as usize;
-    
     // Determine the filename prefix
     let prefix = match &config.prefix {
         Some(p) => p.clone(),
@@ -169,42 +324,147 @@ pub fn split_file(config: &SplitConfig) -> Result<SplitResult> {
             .unwrap_or("chunk")
             .to_string(),
     };
-    
-    // Open the input file
-    let mut input_file = File::open(&input_path).map_err(SplitError::Io)?;
-    
-    // Buffer for reading chunks
-    let mut buffer = vec![0u8; config.chunk_size as usize];
-    let mut chunks = Vec::with_capacity(total_chunks);
-    
-    // Process each chunk
-    for chunk_num in 0..total_chunks {
-        let chunk_path = output_dir.join(format!(
-            "{}.{:0width$}",
-            prefix,
-            chunk_num + 1,
-            width = config.digits as usize
-        ));
-        
-        // Read a chunk from the input file
-        let bytes_read = input_file.read(&mut buffer).map_err(SplitError::Io)?;
-        
-        if bytes_read == 0 {
-            break; // End of file
+
+    // Record git blob identity for the input file, if it lives in a repo
+    let git_info = match &config.git_root {
+        Some(root) => GitContext::open(root)
+            .map_err(|e| SplitError::InvalidInputPath(format!("git error: {e}")))?
+            .and_then(|ctx| {
+                let status = ctx.get_status(&input_path).ok().flatten()?;
+                let blob_oid = ctx.blob_oid(&input_path).ok().flatten()?;
+                Some(GitInfo { status, blob_oid })
+            }),
+        None => None,
+    };
+
+    // Determine chunk boundaries sequentially (required by both strategies),
+    // collecting each chunk's bytes so the write stage below can run in parallel.
+    let input_bytes = match config.strategy {
+        ChunkStrategy::FixedSize => {
+            let total_chunks = ((file_size as f64) / (config.chunk_size as f64)).ceil() as usize;
+            let mut input_file = File::open(&input_path).map_err(SplitError::Io)?;
+            let mut buffer = vec![0u8; config.chunk_size as usize];
+            let mut pieces = Vec::with_capacity(total_chunks);
+
+            for _ in 0..total_chunks {
+                let bytes_read = input_file.read(&mut buffer).map_err(SplitError::Io)?;
+                if bytes_read == 0 {
+                    break;
+                }
+                pieces.push(buffer[..bytes_read].to_vec());
+            }
+
+            pieces
         }
-        
-        // Write the chunk to the output file
-        let mut output_file = File::create(&chunk_path).map_err(SplitError::Io)?;
-        output_file.write_all(&buffer[..bytes_read]).map_err(SplitError::Io)?;
-        
-        // Add chunk info to the result
-        chunks.push(FileChunk {
+        ChunkStrategy::FastCdc { min, avg, max } => {
+            let mut input_file = File::open(&input_path).map_err(SplitError::Io)?;
+            let mut data = Vec::with_capacity(file_size as usize);
+            input_file.read_to_end(&mut data).map_err(SplitError::Io)?;
+
+            let boundaries = fastcdc_boundaries(&data, min, avg, max);
+            let mut pieces = Vec::with_capacity(boundaries.len());
+            let mut offset = 0usize;
+            for len in boundaries {
+                let len = len as usize;
+                pieces.push(data[offset..offset + len].to_vec());
+                offset += len;
+            }
+
+            pieces
+        }
+    };
+
+    // Compress (if enabled) and write each chunk concurrently via a bounded
+    // worker pool, keeping the crate's existing pattern of spreading I/O-bound
+    // work across threads instead of one at a time.
+    let unique_chunks = AtomicUsize::new(0);
+    let deduplicated_bytes = AtomicU64::new(0);
+    let written_bytes = AtomicU64::new(0);
+    // `write_chunk` runs across the rayon pool below, so two chunks with
+    // identical content (exactly the case `dedup` exists for) can race on
+    // a `Path::exists()` check: both observe "not written yet" before
+    // either's `File::create` lands, and both take the write branch. Guard
+    // the hash set itself rather than the filesystem, the same way
+    // `IncrementalTracker::classify` atomically checks-and-inserts under
+    // its own lock instead of racing on the files it's about to write.
+    let seen_hashes: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+
+    let write_chunk = |chunk_num: usize, data: &[u8]| -> Result<FileChunk> {
+        let hash = config.dedup.then(|| content_hash(data));
+        let mut chunk_path = if let Some(hash) = &hash {
+            output_dir.join(format!("{}.chunk", hash))
+        } else {
+            output_dir.join(format!(
+                "{}.{:0width$}",
+                prefix,
+                chunk_num + 1,
+                width = config.digits as usize
+            ))
+        };
+
+        let to_write: std::borrow::Cow<[u8]> = match config.compression {
+            Some(CompressionKind::Zstd { level }) => {
+                chunk_path = PathBuf::from(format!("{}.zst", chunk_path.display()));
+                std::borrow::Cow::Owned(
+                    zstd::bulk::compress(data, level)
+                        .map_err(|e| SplitError::InvalidChunkSize(format!("zstd compression failed: {e}")))?,
+                )
+            }
+            None => std::borrow::Cow::Borrowed(data),
+        };
+        let compressed_size = config.compression.map(|_| to_write.len() as u64);
+
+        // The set admits each hash once per run; a file already on disk for
+        // an admitted hash was left there by an earlier split.
+        let already_written = match &hash {
+            Some(hash) => !seen_hashes.lock().unwrap().insert(hash.clone()) || chunk_path.exists(),
+            None => false,
+        };
+
+        if already_written {
+            deduplicated_bytes.fetch_add(data.len() as u64, Ordering::Relaxed);
+        } else {
+            let mut output_file = File::create(&chunk_path).map_err(SplitError::Io)?;
+            output_file.write_all(&to_write).map_err(SplitError::Io)?;
+            unique_chunks.fetch_add(1, Ordering::Relaxed);
+            written_bytes.fetch_add(to_write.len() as u64, Ordering::Relaxed);
+        }
+
+        Ok(FileChunk {
             path: chunk_path,
-            size: bytes_read as u64,
-        });
-    }
-    
+            size: data.len() as u64,
+            checksum: fnv1a(data),
+            compressed_size,
+        })
+    };
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(config.parallelism.unwrap_or_else(num_cpus::get))
+        .build()
+        .map_err(|e| SplitError::Io(std::io::Error::new(std::io::ErrorKind::Other, e.to_string())))?;
+
+    let chunks: Vec<FileChunk> = pool.install(|| {
+        input_bytes
+            .par_iter()
+            .enumerate()
+            .map(|(chunk_num, data)| write_chunk(chunk_num, data))
+            .collect::<Result<Vec<_>>>()
+    })?;
+
+    let total_chunks = chunks.len();
+    let unique_chunks = unique_chunks.into_inner();
+    let deduplicated_bytes = deduplicated_bytes.into_inner();
+    let written_bytes = written_bytes.into_inner();
+
+    write_manifest(&output_dir, &prefix, config, file_size, &chunks, git_info.as_ref())?;
+
     // Build and return the result
+    let compression_ratio = if file_size > 0 {
+        Some(written_bytes as f64 / file_size as f64)
+    } else {
+        None
+    };
+
     Ok(SplitResult {
         input_path,
         output_dir,
@@ -212,9 +472,454 @@ pub fn split_file(config: &SplitConfig) -> Result<SplitResult> {
         chunks,
         total_chunks,
         total_size: file_size,
+        unique_chunks,
+        deduplicated_bytes,
+        compressed_size: written_bytes,
+        compression_ratio,
+    })
+}
+
+/// FNV-1a 64-bit hash, used as the chunk checksum in the manifest
+fn fnv1a(data: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    let mut hash = OFFSET_BASIS;
+    for &byte in data {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(PRIME);
+    }
+    hash
+}
+
+/// Git blob identity for the input file, recorded in the manifest so a
+/// reassembled file can later be checked against what git committed
+struct GitInfo {
+    status: GitStatus,
+    blob_oid: git2::Oid,
+}
+
+/// Byte size of a raw git OID (SHA-1)
+const GIT_OID_LEN: usize = 20;
+
+fn git_status_to_byte(status: GitStatus) -> u8 {
+    match status {
+        GitStatus::Unmodified => 0,
+        GitStatus::Modified => 1,
+        GitStatus::Added => 2,
+        GitStatus::Deleted => 3,
+        GitStatus::Renamed => 4,
+        GitStatus::Copied => 5,
+        GitStatus::Untracked => 6,
+        GitStatus::Ignored => 7,
+    }
+}
+
+fn git_status_from_byte(byte: u8) -> Result<GitStatus> {
+    match byte {
+        0 => Ok(GitStatus::Unmodified),
+        1 => Ok(GitStatus::Modified),
+        2 => Ok(GitStatus::Added),
+        3 => Ok(GitStatus::Deleted),
+        4 => Ok(GitStatus::Renamed),
+        5 => Ok(GitStatus::Copied),
+        6 => Ok(GitStatus::Untracked),
+        7 => Ok(GitStatus::Ignored),
+        other => Err(SplitError::InvalidManifest(format!("unknown git status byte {other}"))),
+    }
+}
+
+/// Magic bytes identifying a split-manifest file
+const MANIFEST_MAGIC: &[u8; 7] = b"PDXSPLT";
+/// Current manifest format version
+const MANIFEST_VERSION: u8 = 1;
+
+/// Length of a hex-encoded BLAKE3 hash
+const HASH_HEX_LEN: usize = 64;
+/// Base byte size of a contents-table entry: `offset: u64` + `length: u64` + `checksum: u64`
+const ENTRY_LEN_BASE: usize = 24;
+
+/// Byte size of one contents-table entry, given whether `dedup` and
+/// `compression` are enabled for this manifest
+fn entry_len(dedup: bool, compressed: bool) -> usize {
+    ENTRY_LEN_BASE + if dedup { HASH_HEX_LEN } else { 0 } + if compressed { 8 } else { 0 }
+}
+
+/// One entry in a manifest's contents table
+struct ManifestEntry {
+    offset: u64,
+    length: u64,
+    checksum: u64,
+    /// Hex content hash naming the chunk file, when `dedup` is enabled
+    hash: Option<String>,
+    /// On-disk size of the chunk, when `compression` was enabled
+    compressed_size: Option<u64>,
+}
+
+/// A manifest's header plus its contents table
+struct Manifest {
+    digits: u8,
+    dedup: bool,
+    compression: Option<CompressionKind>,
+    git_status: Option<GitStatus>,
+    git_blob_oid: Option<git2::Oid>,
+    entries: Vec<ManifestEntry>,
+}
+
+/// Write the sidecar `<prefix>.manifest` describing the chunks just written.
+///
+/// Layout: magic (7 bytes) + version (1 byte), then a header of
+/// `digits: u8`, `dedup: u8`, `total_size: u64`, `git tag: u8` (+ `status: u8`
+/// and a 20-byte raw OID when present), `strategy tag: u8` (+ `min`/`avg`/`max: u64`
+/// each for FastCDC), `compression tag: u8` (+ zstd `level: i32`), then
+/// `contents_len: u64` giving the byte length of the contents table, followed
+/// by the table itself: `(offset: u64, length: u64, checksum: u64)` per chunk,
+/// plus a 64-byte hex content hash per chunk when `dedup` is enabled, plus the
+/// on-disk `compressed_size: u64` per chunk when compression is set.
+fn write_manifest(
+    output_dir: &Path,
+    prefix: &str,
+    config: &SplitConfig,
+    total_size: u64,
+    chunks: &[FileChunk],
+    git_info: Option<&GitInfo>,
+) -> Result<()> {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(MANIFEST_MAGIC);
+    buf.push(MANIFEST_VERSION);
+    buf.push(config.digits);
+    buf.push(config.dedup as u8);
+    buf.extend_from_slice(&total_size.to_le_bytes());
+
+    match git_info {
+        None => buf.push(0),
+        Some(info) => {
+            buf.push(1);
+            buf.push(git_status_to_byte(info.status));
+            buf.extend_from_slice(info.blob_oid.as_bytes());
+        }
+    }
+
+    match config.strategy {
+        ChunkStrategy::FixedSize => {
+            buf.push(0);
+        }
+        ChunkStrategy::FastCdc { min, avg, max } => {
+            buf.push(1);
+            buf.extend_from_slice(&min.to_le_bytes());
+            buf.extend_from_slice(&avg.to_le_bytes());
+            buf.extend_from_slice(&max.to_le_bytes());
+        }
+    }
+
+    match config.compression {
+        None => buf.push(0),
+        Some(CompressionKind::Zstd { level }) => {
+            buf.push(1);
+            buf.extend_from_slice(&level.to_le_bytes());
+        }
+    }
+    let compressed = config.compression.is_some();
+
+    let entry_len = entry_len(config.dedup, compressed);
+    let contents_len = (chunks.len() * entry_len) as u64;
+    buf.extend_from_slice(&contents_len.to_le_bytes());
+
+    let mut offset = 0u64;
+    for chunk in chunks {
+        buf.extend_from_slice(&offset.to_le_bytes());
+        buf.extend_from_slice(&chunk.size.to_le_bytes());
+        buf.extend_from_slice(&chunk.checksum.to_le_bytes());
+        if config.dedup {
+            // The chunk filename is `<hash>.chunk[.zst]`; recover the hash from it
+            let hash = chunk
+                .path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(|s| s.strip_suffix(".chunk").or(Some(s)))
+                .unwrap_or_default();
+            debug_assert_eq!(hash.len(), HASH_HEX_LEN);
+            buf.extend_from_slice(hash.as_bytes());
+        }
+        if compressed {
+            buf.extend_from_slice(&chunk.compressed_size.unwrap_or(chunk.size).to_le_bytes());
+        }
+        offset += chunk.size;
+    }
+
+    let manifest_path = output_dir.join(format!("{}.manifest", prefix));
+    let mut file = File::create(&manifest_path).map_err(SplitError::Io)?;
+    file.write_all(&buf).map_err(SplitError::Io)?;
+
+    Ok(())
+}
+
+/// Read and validate a manifest, returning its header and contents table.
+fn read_manifest(manifest_path: &Path) -> Result<Manifest> {
+    let mut buf = Vec::new();
+    File::open(manifest_path)
+        .map_err(SplitError::Io)?
+        .read_to_end(&mut buf)
+        .map_err(SplitError::Io)?;
+
+    if buf.len() < MANIFEST_MAGIC.len() + 1 {
+        return Err(SplitError::InvalidManifest("manifest is too short".into()));
+    }
+
+    let mut pos = 0usize;
+    if &buf[pos..pos + MANIFEST_MAGIC.len()] != MANIFEST_MAGIC {
+        return Err(SplitError::InvalidManifest("bad magic bytes".into()));
+    }
+    pos += MANIFEST_MAGIC.len();
+
+    let version = buf[pos];
+    pos += 1;
+    if version != MANIFEST_VERSION {
+        return Err(SplitError::InvalidManifest(format!(
+            "unsupported manifest version {version}"
+        )));
+    }
+
+    let digits = *buf
+        .get(pos)
+        .ok_or_else(|| SplitError::InvalidManifest("missing digits field".into()))?;
+    pos += 1;
+
+    let dedup = *buf
+        .get(pos)
+        .ok_or_else(|| SplitError::InvalidManifest("missing dedup field".into()))?
+        != 0;
+    pos += 1;
+
+    let read_u64 = |buf: &[u8], pos: &mut usize| -> Result<u64> {
+        let bytes = buf.get(*pos..*pos + 8).ok_or_else(|| {
+            SplitError::InvalidManifest("unexpected end of manifest".into())
+        })?;
+        *pos += 8;
+        Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+    };
+
+    let _total_size = read_u64(&buf, &mut pos)?;
+
+    let git_tag = *buf
+        .get(pos)
+        .ok_or_else(|| SplitError::InvalidManifest("missing git tag".into()))?;
+    pos += 1;
+    let (git_status, git_blob_oid) = match git_tag {
+        0 => (None, None),
+        1 => {
+            let status_byte = *buf
+                .get(pos)
+                .ok_or_else(|| SplitError::InvalidManifest("missing git status".into()))?;
+            pos += 1;
+            let status = git_status_from_byte(status_byte)?;
+
+            let oid_bytes = buf.get(pos..pos + GIT_OID_LEN).ok_or_else(|| {
+                SplitError::InvalidManifest("unexpected end of manifest".into())
+            })?;
+            pos += GIT_OID_LEN;
+            let oid = git2::Oid::from_bytes(oid_bytes)
+                .map_err(|e| SplitError::InvalidManifest(format!("invalid git OID: {e}")))?;
+
+            (Some(status), Some(oid))
+        }
+        other => {
+            return Err(SplitError::InvalidManifest(format!("unknown git tag {other}")))
+        }
+    };
+
+    let strategy_tag = *buf
+        .get(pos)
+        .ok_or_else(|| SplitError::InvalidManifest("missing strategy tag".into()))?;
+    pos += 1;
+    match strategy_tag {
+        0 => {}
+        1 => {
+            let _min = read_u64(&buf, &mut pos)?;
+            let _avg = read_u64(&buf, &mut pos)?;
+            let _max = read_u64(&buf, &mut pos)?;
+        }
+        other => {
+            return Err(SplitError::InvalidManifest(format!(
+                "unknown chunk strategy tag {other}"
+            )))
+        }
+    }
+
+    let compression_tag = *buf
+        .get(pos)
+        .ok_or_else(|| SplitError::InvalidManifest("missing compression tag".into()))?;
+    pos += 1;
+    let compression = match compression_tag {
+        0 => None,
+        1 => {
+            let bytes = buf.get(pos..pos + 4).ok_or_else(|| {
+                SplitError::InvalidManifest("unexpected end of manifest".into())
+            })?;
+            pos += 4;
+            Some(CompressionKind::Zstd { level: i32::from_le_bytes(bytes.try_into().unwrap()) })
+        }
+        other => {
+            return Err(SplitError::InvalidManifest(format!(
+                "unknown compression tag {other}"
+            )))
+        }
+    };
+
+    let entry_len = entry_len(dedup, compression.is_some());
+    let contents_len = read_u64(&buf, &mut pos)? as usize;
+    if contents_len % entry_len != 0 {
+        return Err(SplitError::InvalidManifest(
+            "contents table length is not a multiple of entry size".into(),
+        ));
+    }
+    let entry_count = contents_len / entry_len;
+
+    let mut entries = Vec::with_capacity(entry_count);
+    for _ in 0..entry_count {
+        let offset = read_u64(&buf, &mut pos)?;
+        let length = read_u64(&buf, &mut pos)?;
+        let checksum = read_u64(&buf, &mut pos)?;
+        let hash = if dedup {
+            let bytes = buf.get(pos..pos + HASH_HEX_LEN).ok_or_else(|| {
+                SplitError::InvalidManifest("unexpected end of manifest".into())
+            })?;
+            pos += HASH_HEX_LEN;
+            Some(
+                std::str::from_utf8(bytes)
+                    .map_err(|_| SplitError::InvalidManifest("hash is not valid UTF-8".into()))?
+                    .to_string(),
+            )
+        } else {
+            None
+        };
+        let compressed_size = if compression.is_some() {
+            Some(read_u64(&buf, &mut pos)?)
+        } else {
+            None
+        };
+        entries.push(ManifestEntry { offset, length, checksum, hash, compressed_size });
+    }
+
+    Ok(Manifest { digits, dedup, compression, git_status, git_blob_oid, entries })
+}
+
+/// Git blob identity recorded in a split manifest, if the input file was
+/// split with `SplitConfig::git_root` set and lived in a git repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitBlobInfo {
+    /// The file's `GitStatus` at the time it was split
+    pub status: GitStatus,
+    /// Hex-encoded git blob OID, for comparison against `git hash-object`
+    pub blob_oid: String,
+}
+
+/// Read the git blob identity recorded in a split manifest, if any.
+///
+/// Downstream tools can compare `blob_oid` against `git hash-object` on a
+/// reassembled file to confirm it matches what git had committed.
+pub fn manifest_git_info(manifest_path: impl AsRef<Path>) -> Result<Option<GitBlobInfo>> {
+    let manifest = read_manifest(manifest_path.as_ref())?;
+    Ok(match (manifest.git_status, manifest.git_blob_oid) {
+        (Some(status), Some(oid)) => Some(GitBlobInfo { status, blob_oid: oid.to_string() }),
+        _ => None,
     })
 }
 
+/// Reassemble a split file from its manifest.
+///
+/// Reads `<prefix>.manifest`, locates the chunk files alongside it (same
+/// directory; numbered with the manifest filename's prefix, or named
+/// `<hash>.chunk` for a `dedup` split, with `.zst` appended when compressed),
+/// verifies each chunk's checksum, and concatenates them into `output_path`.
+/// Returns `SplitError::ChecksumMismatch` if a chunk is missing or corrupt.
+pub fn join_file(manifest_path: impl AsRef<Path>, output_path: impl AsRef<Path>) -> Result<()> {
+    let manifest_path = manifest_path.as_ref();
+    let manifest = read_manifest(manifest_path)?;
+
+    let dir = manifest_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."));
+    let prefix = manifest_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| SplitError::InvalidManifest("manifest has no usable filename".into()))?;
+
+    let mut output = File::create(output_path.as_ref()).map_err(SplitError::Io)?;
+
+    for (index, entry) in manifest.entries.iter().enumerate() {
+        let mut chunk_path = match &entry.hash {
+            Some(hash) => dir.join(format!("{hash}.chunk")),
+            None => dir.join(format!(
+                "{}.{:0width$}",
+                prefix,
+                index + 1,
+                width = manifest.digits as usize
+            )),
+        };
+        if manifest.compression.is_some() {
+            chunk_path = PathBuf::from(format!("{}.zst", chunk_path.display()));
+        }
+
+        let mut raw = Vec::new();
+        let read_result = File::open(&chunk_path).and_then(|mut f| f.read_to_end(&mut raw));
+        if let Err(e) = read_result {
+            return Err(SplitError::ChecksumMismatch {
+                index,
+                reason: format!("could not read {}: {e}", chunk_path.display()),
+            });
+        }
+
+        if let Some(expected) = entry.compressed_size {
+            if raw.len() as u64 != expected {
+                return Err(SplitError::ChecksumMismatch {
+                    index,
+                    reason: format!(
+                        "expected {} compressed bytes on disk, found {}",
+                        expected,
+                        raw.len()
+                    ),
+                });
+            }
+        }
+
+        let data = match manifest.compression {
+            Some(CompressionKind::Zstd { .. }) => {
+                zstd::bulk::decompress(&raw, entry.length as usize).map_err(|e| {
+                    SplitError::ChecksumMismatch {
+                        index,
+                        reason: format!("failed to decompress: {e}"),
+                    }
+                })?
+            }
+            None => raw,
+        };
+
+        if data.len() as u64 != entry.length {
+            return Err(SplitError::ChecksumMismatch {
+                index,
+                reason: format!(
+                    "expected {} bytes, found {}",
+                    entry.length,
+                    data.len()
+                ),
+            });
+        }
+
+        let checksum = fnv1a(&data);
+        if checksum != entry.checksum {
+            return Err(SplitError::ChecksumMismatch {
+                index,
+                reason: "checksum does not match manifest".into(),
+            });
+        }
+
+        output.write_all(&data).map_err(SplitError::Io)?;
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -274,11 +979,279 @@ mod tests {
         };
         
         split_file(&config)?;
-        
+
         // Verify output directory was created
         assert!(output_dir.exists());
         assert!(output_dir.is_dir());
-        
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_file_fastcdc() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let input_path = temp_dir.path().join("test.bin");
+        let output_dir = temp_dir.path().join("output");
+
+        // Enough varied data for the rolling hash to find multiple cut points
+        let mut file = File::create(&input_path)?;
+        let data: Vec<u8> = (0..20_000u32).map(|i| (i % 251) as u8).collect();
+        file.write_all(&data)?;
+
+        let config = SplitConfig {
+            input_path: input_path.to_str().unwrap().to_string(),
+            output_dir: Some(output_dir.to_str().unwrap().to_string()),
+            strategy: ChunkStrategy::FastCdc { min: 512, avg: 2048, max: 8192 },
+            ..Default::default()
+        };
+
+        let result = split_file(&config)?;
+
+        assert!(result.total_chunks > 1);
+        assert_eq!(
+            result.chunks.iter().map(|c| c.size).sum::<u64>(),
+            data.len() as u64
+        );
+        for chunk in &result.chunks {
+            assert!(chunk.size <= 8192);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fastcdc_boundaries_respect_min_and_max() {
+        let data = vec![0u8; 10_000];
+        let boundaries = fastcdc_boundaries(&data, 512, 2048, 4096);
+        assert_eq!(boundaries.iter().sum::<u64>(), data.len() as u64);
+        for len in &boundaries {
+            assert!(*len <= 4096);
+        }
+        // Only the final chunk may be shorter than `min`
+        for len in &boundaries[..boundaries.len() - 1] {
+            assert!(*len >= 512);
+        }
+    }
+
+    #[test]
+    fn test_split_then_join_roundtrip() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let input_path = temp_dir.path().join("test.txt");
+        let output_dir = temp_dir.path().join("output");
+
+        let original = b"The quick brown fox jumps over the lazy dog".repeat(50);
+        let mut file = File::create(&input_path)?;
+        file.write_all(&original)?;
+
+        let config = SplitConfig {
+            input_path: input_path.to_str().unwrap().to_string(),
+            output_dir: Some(output_dir.to_str().unwrap().to_string()),
+            chunk_size: 64,
+            ..Default::default()
+        };
+        split_file(&config)?;
+
+        let manifest_path = output_dir.join("test.manifest");
+        assert!(manifest_path.exists());
+
+        let joined_path = temp_dir.path().join("joined.txt");
+        join_file(&manifest_path, &joined_path)?;
+
+        let joined = std::fs::read(&joined_path)?;
+        assert_eq!(joined, original);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_join_file_detects_corruption() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let input_path = temp_dir.path().join("test.txt");
+        let output_dir = temp_dir.path().join("output");
+
+        let mut file = File::create(&input_path)?;
+        file.write_all(b"some file contents to split into chunks")?;
+
+        let config = SplitConfig {
+            input_path: input_path.to_str().unwrap().to_string(),
+            output_dir: Some(output_dir.to_str().unwrap().to_string()),
+            chunk_size: 8,
+            ..Default::default()
+        };
+        split_file(&config)?;
+
+        // Corrupt the first chunk
+        let first_chunk = output_dir.join("test.001");
+        std::fs::write(&first_chunk, b"XXXXXXXX")?;
+
+        let manifest_path = output_dir.join("test.manifest");
+        let joined_path = temp_dir.path().join("joined.txt");
+        let result = join_file(&manifest_path, &joined_path);
+
+        assert!(matches!(result, Err(SplitError::ChecksumMismatch { .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_file_dedup_skips_repeated_chunks() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let input_path = temp_dir.path().join("test.bin");
+        let output_dir = temp_dir.path().join("output");
+
+        // Three identical 16-byte chunks: the content store should write one
+        let mut file = File::create(&input_path)?;
+        file.write_all(&[b'a'; 16].repeat(3))?;
+
+        let config = SplitConfig {
+            input_path: input_path.to_str().unwrap().to_string(),
+            output_dir: Some(output_dir.to_str().unwrap().to_string()),
+            chunk_size: 16,
+            dedup: true,
+            ..Default::default()
+        };
+
+        let result = split_file(&config)?;
+
+        assert_eq!(result.total_chunks, 3);
+        assert_eq!(result.unique_chunks, 1);
+        assert_eq!(result.deduplicated_bytes, 32);
+
+        let chunk_files: Vec<_> = std::fs::read_dir(&output_dir)?
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().map(|ext| ext == "chunk").unwrap_or(false))
+            .collect();
+        assert_eq!(chunk_files.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_then_join_with_zstd_compression() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let input_path = temp_dir.path().join("test.txt");
+        let output_dir = temp_dir.path().join("output");
+
+        let original = b"highly compressible data ".repeat(200);
+        let mut file = File::create(&input_path)?;
+        file.write_all(&original)?;
+
+        let config = SplitConfig {
+            input_path: input_path.to_str().unwrap().to_string(),
+            output_dir: Some(output_dir.to_str().unwrap().to_string()),
+            chunk_size: 512,
+            compression: Some(CompressionKind::Zstd { level: 3 }),
+            ..Default::default()
+        };
+
+        let result = split_file(&config)?;
+        assert!(result.compressed_size < result.total_size);
+        for chunk in &result.chunks {
+            assert!(chunk.path.extension().map(|e| e == "zst").unwrap_or(false));
+            assert!(chunk.compressed_size.is_some());
+        }
+
+        let manifest_path = output_dir.join("test.manifest");
+        let joined_path = temp_dir.path().join("joined.txt");
+        join_file(&manifest_path, &joined_path)?;
+
+        assert_eq!(std::fs::read(&joined_path)?, original);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_file_preserves_order_with_limited_parallelism() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let input_path = temp_dir.path().join("test.bin");
+        let output_dir = temp_dir.path().join("output");
+
+        let original: Vec<u8> = (0..5000u32).map(|i| (i % 256) as u8).collect();
+        let mut file = File::create(&input_path)?;
+        file.write_all(&original)?;
+
+        let config = SplitConfig {
+            input_path: input_path.to_str().unwrap().to_string(),
+            output_dir: Some(output_dir.to_str().unwrap().to_string()),
+            chunk_size: 128,
+            parallelism: Some(4),
+            ..Default::default()
+        };
+
+        let result = split_file(&config)?;
+
+        let manifest_path = output_dir.join("test.manifest");
+        let joined_path = temp_dir.path().join("joined.bin");
+        join_file(&manifest_path, &joined_path)?;
+
+        assert_eq!(std::fs::read(&joined_path)?, original);
+        assert_eq!(result.unique_chunks, result.total_chunks);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_file_records_git_blob_info() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let input_path = temp_dir.path().join("test.txt");
+        let output_dir = temp_dir.path().join("output");
+
+        let mut file = File::create(&input_path)?;
+        file.write_all(b"tracked file contents")?;
+
+        let repo = git2::Repository::init(temp_dir.path()).unwrap();
+        let mut git_config = repo.config().unwrap();
+        git_config.set_str("user.name", "Test User").unwrap();
+        git_config.set_str("user.email", "test@example.com").unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("test.txt")).unwrap();
+        index.write().unwrap();
+        let tree_id = index.write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let sig = repo.signature().unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[])
+            .unwrap();
+
+        let config = SplitConfig {
+            input_path: input_path.to_str().unwrap().to_string(),
+            output_dir: Some(output_dir.to_str().unwrap().to_string()),
+            chunk_size: 8,
+            git_root: Some(temp_dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        split_file(&config)?;
+
+        let manifest_path = output_dir.join("test.manifest");
+        let git_info = manifest_git_info(&manifest_path)?.expect("git info should be recorded");
+        assert_eq!(git_info.status, GitStatus::Unmodified);
+
+        let expected_oid = repo.blob(b"tracked file contents").unwrap();
+        assert_eq!(git_info.blob_oid, expected_oid.to_string());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_manifest_git_info_none_without_git_root() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let input_path = temp_dir.path().join("test.txt");
+        let output_dir = temp_dir.path().join("output");
+
+        let mut file = File::create(&input_path)?;
+        file.write_all(b"not tracked by any repo")?;
+
+        let config = SplitConfig {
+            input_path: input_path.to_str().unwrap().to_string(),
+            output_dir: Some(output_dir.to_str().unwrap().to_string()),
+            chunk_size: 8,
+            ..Default::default()
+        };
+        split_file(&config)?;
+
+        let manifest_path = output_dir.join("test.manifest");
+        assert_eq!(manifest_git_info(&manifest_path)?, None);
+
         Ok(())
     }
 }
@@ -0,0 +1,280 @@
+//! Single-pass Aho-Corasick dictionary substitution
+//!
+//! Builds one automaton over every `(pattern, token)` pair in a dictionary
+//! and substitutes every occurrence in a single left-to-right scan, instead
+//! of the O(text x patterns) repeated full-text passes `PatternReplacer`
+//! does. A match is taken at the first position where any pattern ends: the
+//! automaton's failure links find the longest pattern ending there, and a
+//! short forward walk down the trie's direct edges from there checks
+//! whether a longer sibling pattern (one that has this match as a strict
+//! prefix, e.g. "ab" vs. "abc") also starts at the same position and should
+//! be preferred instead. A longer pattern that starts earlier and would end
+//! later (e.g. "abcd" when "bc" ends inside it) is not preferred, so this is
+//! not full leftmost-longest resolution. The scan resumes right after
+//! whichever pattern is chosen, so substitutions never overlap.
+
+use std::collections::{HashMap, VecDeque};
+
+/// One trie/automaton node: `goto` are direct byte transitions, `fail` is
+/// the suffix link followed when a byte has no direct transition, and `end`
+/// is the index into the matcher's pattern/token lists when this node marks
+/// a pattern's end.
+#[derive(Debug, Default)]
+struct Node {
+    goto: HashMap<u8, usize>,
+    fail: usize,
+    end: Option<usize>,
+}
+
+/// An Aho-Corasick automaton built over a dictionary's `(pattern, token)`
+/// pairs, applying every substitution in a single pass over the input.
+#[derive(Debug)]
+pub struct AhoCorasickMatcher {
+    nodes: Vec<Node>,
+    patterns: Vec<String>,
+    tokens: Vec<String>,
+}
+
+impl AhoCorasickMatcher {
+    /// Build the matcher from `DictionaryBuilder::get_dictionary_entries()`'s
+    /// `(pattern, token)` pairs. Patterns are matched case-sensitively over
+    /// raw bytes; an empty pattern is skipped since it would match
+    /// everywhere.
+    pub fn from_entries(entries: Vec<(String, String)>) -> Self {
+        let mut nodes = vec![Node::default()];
+        let mut patterns = Vec::new();
+        let mut tokens = Vec::new();
+
+        for (pattern, token) in entries {
+            if pattern.is_empty() {
+                continue;
+            }
+
+            let mut state = 0;
+            for &byte in pattern.as_bytes() {
+                state = *nodes[state].goto.entry(byte).or_insert_with(|| {
+                    nodes.push(Node::default());
+                    nodes.len() - 1
+                });
+            }
+            nodes[state].end = Some(patterns.len());
+            patterns.push(pattern);
+            tokens.push(token);
+        }
+
+        // Breadth-first fail-link construction: root's children fail to
+        // root, and every other node's fail link is its parent's fail
+        // target's transition on the same byte (falling back to root).
+        let mut queue = VecDeque::new();
+        let root_children: Vec<(u8, usize)> = nodes[0]
+            .goto
+            .iter()
+            .map(|(&byte, &next)| (byte, next))
+            .collect();
+        for (_, next) in root_children {
+            nodes[next].fail = 0;
+            queue.push_back(next);
+        }
+
+        while let Some(state) = queue.pop_front() {
+            let transitions: Vec<(u8, usize)> = nodes[state]
+                .goto
+                .iter()
+                .map(|(&byte, &next)| (byte, next))
+                .collect();
+            for (byte, next) in transitions {
+                let mut fail = nodes[state].fail;
+                while fail != 0 && !nodes[fail].goto.contains_key(&byte) {
+                    fail = nodes[fail].fail;
+                }
+                nodes[next].fail = nodes[fail].goto.get(&byte).copied().unwrap_or(0);
+                if nodes[next].fail == next {
+                    nodes[next].fail = 0;
+                }
+                queue.push_back(next);
+            }
+        }
+
+        Self { nodes, patterns, tokens }
+    }
+
+    /// True if the dictionary has no usable (non-empty) patterns.
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    fn step(&self, mut state: usize, byte: u8) -> usize {
+        loop {
+            if let Some(&next) = self.nodes[state].goto.get(&byte) {
+                return next;
+            }
+            if state == 0 {
+                return 0;
+            }
+            state = self.nodes[state].fail;
+        }
+    }
+
+    /// The node marking the longest pattern ending at `state`, found by
+    /// walking the fail chain until a terminal node is reached. Fail links
+    /// always point to a strictly shorter suffix, so the first terminal
+    /// node found is the longest match ending here.
+    fn terminal_node_at(&self, mut state: usize) -> Option<usize> {
+        loop {
+            if self.nodes[state].end.is_some() {
+                return Some(state);
+            }
+            if state == 0 {
+                return None;
+            }
+            state = self.nodes[state].fail;
+        }
+    }
+
+    /// Replace every non-overlapping dictionary pattern in `content` with
+    /// its token in one left-to-right pass.
+    ///
+    /// Returns the tokenized text and the number of replacements made, for
+    /// callers folding this into `ArchiveStats`.
+    pub fn apply(&self, content: &str) -> (String, usize) {
+        if self.patterns.is_empty() || content.is_empty() {
+            return (content.to_string(), 0);
+        }
+
+        let bytes = content.as_bytes();
+        let mut output = String::with_capacity(content.len());
+        let mut replacements = 0usize;
+
+        let mut state = 0usize;
+        let mut pos = 0usize;
+        let mut last_flush = 0usize;
+
+        while pos < bytes.len() {
+            state = self.step(state, bytes[pos]);
+            pos += 1;
+
+            if let Some(term_node) = self.terminal_node_at(state) {
+                let mut best_idx = self.nodes[term_node].end.expect("terminal_node_at only returns terminal nodes");
+                let match_start = pos - self.patterns[best_idx].len();
+
+                // `best_idx`'s pattern may itself be a strict prefix of a
+                // longer sibling sharing the same start (e.g. "ab" vs.
+                // "abc"): keep descending the trie's direct edges from this
+                // node to find the longest pattern that still starts here.
+                let mut node = term_node;
+                let mut extend_pos = pos;
+                while extend_pos < bytes.len() {
+                    match self.nodes[node].goto.get(&bytes[extend_pos]) {
+                        Some(&next) => {
+                            node = next;
+                            extend_pos += 1;
+                            if let Some(idx) = self.nodes[node].end {
+                                best_idx = idx;
+                                pos = extend_pos;
+                            }
+                        }
+                        None => break,
+                    }
+                }
+
+                output.push_str(&content[last_flush..match_start]);
+                output.push_str(&self.tokens[best_idx]);
+                replacements += 1;
+                last_flush = pos;
+                state = 0;
+            }
+        }
+
+        output.push_str(&content[last_flush..]);
+        (output, replacements)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(entries: &[(&str, &str)]) -> AhoCorasickMatcher {
+        AhoCorasickMatcher::from_entries(
+            entries.iter().map(|(p, t)| (p.to_string(), t.to_string())).collect(),
+        )
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let ac = matcher(&[("function", "T0")]);
+        let (result, count) = ac.apply("");
+        assert_eq!(result, "");
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn test_empty_dictionary() {
+        let ac = matcher(&[]);
+        assert!(ac.is_empty());
+        let (result, count) = ac.apply("function test() {}");
+        assert_eq!(result, "function test() {}");
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn test_single_pattern_multiple_occurrences() {
+        let ac = matcher(&[("test", "A0")]);
+        let (result, count) = ac.apply("test test test");
+        assert_eq!(result, "A0 A0 A0");
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn test_prefers_longest_pattern_ending_at_same_position() {
+        // "on" and "ion" both end at the same position in "function"; the
+        // longer "ion" should win, not the shorter "on".
+        let ac = matcher(&[("on", "X"), ("ion", "Y")]);
+        let (result, count) = ac.apply("function");
+        assert_eq!(result, "functY");
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_prefix_patterns_prefer_the_longer_sibling() {
+        // "ab" is a strict prefix of "abc"; whenever both could start at the
+        // same position the longer pattern should be preferred.
+        let ac = matcher(&[("ab", "X"), ("abc", "Y")]);
+        let (result, count) = ac.apply("abcab");
+        assert_eq!(result, "YX");
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_adjacent_matches_with_no_gap() {
+        let ac = matcher(&[("cat", "X"), ("dog", "Y")]);
+        let (result, count) = ac.apply("catdog");
+        assert_eq!(result, "XY");
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_non_matching_content_is_unchanged() {
+        let ac = matcher(&[("function", "A0")]);
+        let (result, count) = ac.apply("const x = 42;");
+        assert_eq!(result, "const x = 42;");
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn test_matches_dictionary_builder_output() {
+        let mut builder = crate::compression::DictionaryBuilder::new();
+        crate::compression::DictionaryBuilding::build_dictionary(
+            &mut builder,
+            vec![("function".to_string(), 5), ("return".to_string(), 3)],
+        )
+        .unwrap();
+
+        let ac = AhoCorasickMatcher::from_entries(builder.get_dictionary_entries());
+        let (result, count) = ac.apply("function test() { return 42; }");
+
+        assert_eq!(count, 2);
+        assert!(!result.contains("function"));
+        assert!(!result.contains("return"));
+    }
+}
@@ -0,0 +1,236 @@
+//! `algotest` benchmarking mode
+//!
+//! Modeled on zvault's `algotest` subcommand: run several candidate
+//! `CompressionMethod`s over the same corpus and report which one actually
+//! pays off for it, rather than picking one by reputation alone.
+
+use crate::compression::brotli_compressor::BrotliCompressor;
+use crate::compression::codec::Compressor;
+use crate::compression::config::{CompressionConfig, Lz4Level, ZstdLevel};
+use crate::compression::deflate_compressor::DeflateCompressor;
+use crate::compression::error::CompressionError;
+use crate::compression::lz4_compressor::Lz4Compressor;
+use crate::compression::snappy_compressor::SnappyCompressor;
+use crate::compression::types::{CompressionMethod, CompressionStatistics, FileSize};
+use crate::compression::zstd_compressor::ZstdCompressor;
+use crate::compression::{
+    DictionaryBuilder, DictionaryBuilding, FrequencyAnalysis, FrequencyAnalyzer, PatternReplacement,
+};
+use std::path::Path;
+use std::time::Instant;
+use walkdir::WalkDir;
+
+/// One method's measured result.
+#[derive(Debug, Clone)]
+pub struct AlgotestResult {
+    pub method: CompressionMethod,
+    pub statistics: CompressionStatistics,
+}
+
+impl AlgotestResult {
+    /// Bytes saved per second of `processing_time`; the metric `winner()`
+    /// ranks by. `f64::INFINITY` for a method that saved bytes in
+    /// effectively zero time, `0.0` for one that saved none.
+    pub fn bytes_saved_per_second(&self) -> f64 {
+        let saved = self.statistics.space_saved().bytes() as f64;
+        let seconds = self.statistics.processing_time.as_secs_f64();
+        if seconds > 0.0 {
+            saved / seconds
+        } else if saved > 0.0 {
+            f64::INFINITY
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Ranked results from running `run` over a set of `CompressionMethod`s.
+#[derive(Debug, Clone)]
+pub struct AlgotestReport {
+    pub results: Vec<AlgotestResult>,
+}
+
+impl AlgotestReport {
+    /// The method with the highest bytes-saved-per-second, if any method ran.
+    pub fn winner(&self) -> Option<&AlgotestResult> {
+        self.results.iter().max_by(|a, b| {
+            a.bytes_saved_per_second()
+                .partial_cmp(&b.bytes_saved_per_second())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+    }
+}
+
+impl std::fmt::Display for AlgotestReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Algotest comparison:")?;
+        writeln!(
+            f,
+            "  {:<16} {:>14} {:>14} {:>10} {:>16}",
+            "method", "original", "compressed", "ratio", "bytes saved/s"
+        )?;
+        for result in &self.results {
+            writeln!(
+                f,
+                "  {:<16} {:>14} {:>14} {:>10} {:>16.0}",
+                result.method.to_string(),
+                result.statistics.original_total_size.to_string(),
+                result.statistics.compressed_total_size.to_string(),
+                result.statistics.compression_ratio().to_string(),
+                result.bytes_saved_per_second()
+            )?;
+        }
+        match self.winner() {
+            Some(winner) => write!(f, "Winner: {} (most bytes saved per second)", winner.method),
+            None => write!(f, "Winner: none (no methods ran)"),
+        }
+    }
+}
+
+/// Run every method in `methods` over every readable text file under
+/// `target`, timing each the way `UniversalCompressor::compress` times
+/// `processing_time`: one `Instant` per method, covering that method's
+/// whole pass over the corpus.
+pub fn run(methods: &[CompressionMethod], target: &Path) -> Result<AlgotestReport, CompressionError> {
+    let files = collect_text_files(target)?;
+    let mut results = Vec::with_capacity(methods.len());
+
+    for &method in methods {
+        let start = Instant::now();
+        let mut statistics = run_method(method, &files)?;
+        statistics.processing_time = start.elapsed();
+        results.push(AlgotestResult { method, statistics });
+    }
+
+    Ok(AlgotestReport { results })
+}
+
+fn collect_text_files(target: &Path) -> Result<Vec<String>, CompressionError> {
+    let mut files = Vec::new();
+    for entry in WalkDir::new(target) {
+        let entry = entry.map_err(|e| {
+            CompressionError::file_processing(
+                "directory traversal",
+                &format!("Failed to read directory entry: {}", e),
+            )
+        })?;
+        if entry.file_type().is_file() {
+            if let Ok(content) = std::fs::read_to_string(entry.path()) {
+                files.push(content);
+            }
+        }
+    }
+
+    if files.is_empty() {
+        return Err(CompressionError::file_processing(
+            target,
+            "No text files found to benchmark",
+        ));
+    }
+
+    Ok(files)
+}
+
+fn run_method(method: CompressionMethod, files: &[String]) -> Result<CompressionStatistics, CompressionError> {
+    let mut stats = CompressionStatistics::new();
+    stats.total_files_processed = files.len();
+
+    let (original_total, compressed_total) = match method {
+        CompressionMethod::Store => run_store(files, &mut stats),
+        CompressionMethod::Dictionary => run_dictionary(files, &mut stats)?,
+        CompressionMethod::Deflate { .. } => run_with_compressor(&DeflateCompressor::new(), method, files, &mut stats)?,
+        CompressionMethod::Brotli { .. } => run_with_compressor(&BrotliCompressor::new(), method, files, &mut stats)?,
+        CompressionMethod::Lz4 => run_with_compressor(&Lz4Compressor::new(Lz4Level::default()), method, files, &mut stats)?,
+        CompressionMethod::Snappy => run_with_compressor(&SnappyCompressor::new(), method, files, &mut stats)?,
+        CompressionMethod::Zstd { level } => {
+            let compressor = ZstdCompressor::new(ZstdLevel::new(level)?)?;
+            run_with_compressor(&compressor, method, files, &mut stats)?
+        }
+    };
+
+    stats.original_total_size = FileSize::new(original_total);
+    stats.compressed_total_size = FileSize::new(compressed_total);
+    Ok(stats)
+}
+
+fn run_store(files: &[String], stats: &mut CompressionStatistics) -> (usize, usize) {
+    let mut original_total = 0;
+    for content in files {
+        original_total += content.len();
+        stats.record_method(CompressionMethod::Store, content.len(), content.len());
+    }
+    (original_total, original_total)
+}
+
+fn run_with_compressor(
+    compressor: &dyn Compressor,
+    method: CompressionMethod,
+    files: &[String],
+    stats: &mut CompressionStatistics,
+) -> Result<(usize, usize), CompressionError> {
+    let mut original_total = 0;
+    let mut compressed_total = 0;
+    for content in files {
+        let compressed = compressor.compress(content.as_bytes())?;
+        original_total += content.len();
+        compressed_total += compressed.len();
+        stats.record_method(method, content.len(), compressed.len());
+    }
+    Ok((original_total, compressed_total))
+}
+
+fn run_dictionary(files: &[String], stats: &mut CompressionStatistics) -> Result<(usize, usize), CompressionError> {
+    let config = CompressionConfig::default();
+    let mut analyzer = FrequencyAnalyzer::new(config.min_pattern_length.get(), config.min_frequency_threshold.get());
+    for content in files {
+        analyzer.analyze_content(content);
+    }
+
+    let patterns = analyzer.get_frequent_patterns();
+    stats.total_patterns_found = patterns.len();
+
+    let mut builder = DictionaryBuilder::new();
+    builder.build_dictionary(patterns)?;
+    stats.dictionary_entries = builder.entry_count();
+
+    let replacer = crate::compression::PatternReplacer::from_entries(builder.get_dictionary_entries());
+
+    let mut original_total = 0;
+    let mut compressed_total = 0;
+    for content in files {
+        let compressed = replacer.replace_patterns(content);
+        original_total += content.len();
+        compressed_total += compressed.len();
+        stats.record_method(CompressionMethod::Dictionary, content.len(), compressed.len());
+    }
+
+    Ok((original_total, compressed_total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_run_ranks_store_against_deflate() {
+        let dir = TempDir::new().unwrap();
+        let text = "the quick brown fox jumps over the lazy dog\n".repeat(50);
+        fs::write(dir.path().join("sample.txt"), &text).unwrap();
+
+        let methods = [CompressionMethod::Store, CompressionMethod::Deflate { level: 6 }];
+        let report = run(&methods, dir.path()).unwrap();
+
+        assert_eq!(report.results.len(), 2);
+        let winner = report.winner().unwrap();
+        assert_eq!(winner.method, CompressionMethod::Deflate { level: 6 });
+    }
+
+    #[test]
+    fn test_run_errors_on_empty_directory() {
+        let dir = TempDir::new().unwrap();
+        let methods = [CompressionMethod::Store];
+        assert!(run(&methods, dir.path()).is_err());
+    }
+}
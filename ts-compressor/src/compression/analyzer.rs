@@ -13,6 +13,17 @@ pub struct FrequencyAnalyzer {
     min_pattern_length: usize,
     min_frequency_threshold: usize,
     pattern_frequencies: HashMap<String, usize>,
+    /// When set, a window is only seeded as a candidate pattern if at least
+    /// one of its bytes has a `BYTE_COMMONNESS` score at or below this rank.
+    /// See `with_rarity_seeding`.
+    max_rarity_rank: Option<u8>,
+    /// When set, `get_frequent_patterns` returns this list as-is instead of
+    /// filtering `pattern_frequencies` by `min_frequency_threshold`. Used by
+    /// `DictionaryStrategy::Cover` (see `load_external_patterns`), whose
+    /// candidates are already ranked by realized savings rather than raw
+    /// occurrence count, so the threshold filter (tuned for counts) would
+    /// reject them arbitrarily.
+    external_patterns: Option<Vec<(String, usize)>>,
 }
 
 impl FrequencyAnalyzer {
@@ -22,8 +33,247 @@ impl FrequencyAnalyzer {
             min_pattern_length: min_length,
             min_frequency_threshold: min_frequency,
             pattern_frequencies: HashMap::new(),
+            max_rarity_rank: None,
+            external_patterns: None,
         }
     }
+
+    /// Create an analyzer that seeds candidate patterns only around
+    /// distinctive bytes, instead of tracking every window that passes the
+    /// alphanumeric filter.
+    ///
+    /// A window is skipped unless at least one of its bytes is "rare"
+    /// according to the static `BYTE_COMMONNESS` table, i.e. has a
+    /// commonness score no greater than `max_rarity_rank` (lower scores are
+    /// rarer). This concentrates tracking on distinctive substrings such as
+    /// identifiers and keywords instead of the huge number of near-uniform
+    /// whitespace/filler windows a large input produces.
+    ///
+    /// This trades completeness (some genuinely frequent but byte-uniform
+    /// patterns will never be seeded) for a large reduction in the size of
+    /// the pattern map on large inputs.
+    pub fn with_rarity_seeding(min_length: usize, min_frequency: usize, max_rarity_rank: u8) -> Self {
+        Self {
+            min_pattern_length: min_length,
+            min_frequency_threshold: min_frequency,
+            pattern_frequencies: HashMap::new(),
+            max_rarity_rank: Some(max_rarity_rank),
+            external_patterns: None,
+        }
+    }
+
+    /// Supply already-ranked candidates (pattern, realized-savings score)
+    /// for `get_frequent_patterns` to return verbatim, bypassing the
+    /// `analyze_content`/`min_frequency_threshold` path entirely. See
+    /// `crate::compression::cover::train`, the one producer of this shape
+    /// of input today.
+    pub fn load_external_patterns(&mut self, patterns: Vec<(String, usize)>) {
+        self.external_patterns = Some(patterns);
+    }
+
+    /// Alternative to `analyze_content` that counts exact substring
+    /// occurrences with a suffix automaton (DAWG) instead of enumerating
+    /// every window from `min_pattern_length..=50`.
+    ///
+    /// Construction is O(n) in the content length and every distinct
+    /// substring is represented by exactly one automaton state, so this
+    /// avoids both the O(n * L) window enumeration cost and the redundant
+    /// overlapping entries that approach produces. Only the longest
+    /// substring per suffix-link equivalence class is recorded, since
+    /// shorter substrings in the same class occur exactly as often.
+    #[instrument(name = "analyze_content_exact", skip(self, content), fields(content_size = content.len()))]
+    pub fn analyze_content_exact(&mut self, content: &str) {
+        if content.is_empty() {
+            debug!("Skipping empty content");
+            return;
+        }
+
+        let bytes = content.as_bytes();
+        let mut sam = SuffixAutomaton::new();
+        for (pos, &byte) in bytes.iter().enumerate() {
+            sam.extend(byte, pos);
+        }
+        sam.propagate_counts();
+
+        let mut patterns_recorded = 0;
+        for state in 1..sam.states.len() {
+            let cnt = sam.states[state].cnt;
+            let len = sam.states[state].len;
+            if cnt < self.min_frequency_threshold || len < self.min_pattern_length {
+                continue;
+            }
+
+            let end = sam.states[state].end_pos;
+            let start = end + 1 - len;
+            if let Ok(pattern) = std::str::from_utf8(&bytes[start..=end]) {
+                self.pattern_frequencies.insert(pattern.to_string(), cnt);
+                patterns_recorded += 1;
+            }
+        }
+
+        debug!(
+            automaton_states = sam.states.len(),
+            patterns_recorded,
+            "Suffix-automaton analysis completed"
+        );
+    }
+}
+
+/// One state in a suffix automaton (DAWG): `transitions` maps the next byte
+/// to the state reached by extending with it, `link` is the suffix link to
+/// the next-shorter equivalence class, `len` is the length of the longest
+/// substring this state represents, `end_pos` is the index (into the
+/// original byte string) where that longest substring ends, and `cnt` is
+/// its occurrence count once `propagate_counts` has run.
+#[derive(Debug, Clone)]
+struct SamState {
+    transitions: HashMap<u8, usize>,
+    link: Option<usize>,
+    len: usize,
+    end_pos: usize,
+    cnt: usize,
+}
+
+/// Suffix automaton (DAWG) built incrementally, one byte at a time.
+///
+/// Unlike the sliding-window scan in `analyze_content`, this builds in O(n)
+/// time over the input and gives exact occurrence counts for every distinct
+/// substring without ever materializing a window.
+#[derive(Debug)]
+struct SuffixAutomaton {
+    states: Vec<SamState>,
+    last: usize,
+}
+
+impl SuffixAutomaton {
+    fn new() -> Self {
+        Self {
+            states: vec![SamState {
+                transitions: HashMap::new(),
+                link: None,
+                len: 0,
+                end_pos: 0,
+                cnt: 0,
+            }],
+            last: 0,
+        }
+    }
+
+    /// Extend the automaton with one more byte of content. `pos` is that
+    /// byte's index in the original string, recorded as the new primary
+    /// state's `end_pos` so the represented substring can be read back later.
+    fn extend(&mut self, byte: u8, pos: usize) {
+        let cur = self.states.len();
+        self.states.push(SamState {
+            transitions: HashMap::new(),
+            link: None,
+            len: self.states[self.last].len + 1,
+            end_pos: pos,
+            cnt: 1,
+        });
+
+        let mut p = Some(self.last);
+        while let Some(state) = p {
+            if self.states[state].transitions.contains_key(&byte) {
+                break;
+            }
+            self.states[state].transitions.insert(byte, cur);
+            p = self.states[state].link;
+        }
+
+        match p {
+            None => self.states[cur].link = Some(0),
+            Some(state) => {
+                let q = self.states[state].transitions[&byte];
+                if self.states[state].len + 1 == self.states[q].len {
+                    self.states[cur].link = Some(q);
+                } else {
+                    let clone_idx = self.states.len();
+                    let mut clone = self.states[q].clone();
+                    clone.len = self.states[state].len + 1;
+                    clone.cnt = 0; // a clone only gains occurrences via propagation, not directly
+                    self.states.push(clone);
+
+                    let mut r = Some(state);
+                    while let Some(rs) = r {
+                        if self.states[rs].transitions.get(&byte) == Some(&q) {
+                            self.states[rs].transitions.insert(byte, clone_idx);
+                            r = self.states[rs].link;
+                        } else {
+                            break;
+                        }
+                    }
+
+                    self.states[q].link = Some(clone_idx);
+                    self.states[cur].link = Some(clone_idx);
+                }
+            }
+        }
+
+        self.last = cur;
+    }
+
+    /// Propagate occurrence counts from longest to shortest state along
+    /// suffix links, so each state's `cnt` becomes the true number of times
+    /// the substrings it represents occur in the source text.
+    fn propagate_counts(&mut self) {
+        let mut order: Vec<usize> = (0..self.states.len()).collect();
+        order.sort_by_key(|&i| std::cmp::Reverse(self.states[i].len));
+        for state in order {
+            if let Some(link) = self.states[state].link {
+                let cnt = self.states[state].cnt;
+                self.states[link].cnt += cnt;
+            }
+        }
+    }
+}
+
+/// Relative commonness of each byte in typical text/code, used by
+/// `FrequencyAnalyzer::with_rarity_seeding` to anchor candidate patterns on
+/// distinctive bytes instead of tracking every window. Lower scores are
+/// rarer; `u8::MAX` (255) is reserved for the single most common byte.
+const BYTE_COMMONNESS: [u8; 256] = build_byte_commonness_table();
+
+const fn build_byte_commonness_table() -> [u8; 256] {
+    // Default: punctuation, control bytes, and non-ASCII are fairly rare
+    let mut table = [30u8; 256];
+
+    table[b' ' as usize] = 255;
+    table[b'\t' as usize] = 210;
+    table[b'\n' as usize] = 210;
+    table[b'\r' as usize] = 190;
+
+    // Lowercase letters, in roughly descending English/code frequency order
+    const COMMON_LOWER: &[u8] = b"etaoinshrdlucmwfgypbvkjxqz";
+    let mut i = 0;
+    while i < COMMON_LOWER.len() {
+        table[COMMON_LOWER[i] as usize] = 220 - (i as u8) * 6;
+        i += 1;
+    }
+
+    // Uppercase letters are common in code (types, constants) but rarer
+    // than their lowercase counterparts in running text overall
+    let mut c = b'A';
+    while c <= b'Z' {
+        table[c as usize] = 100;
+        c += 1;
+    }
+
+    let mut d = b'0';
+    while d <= b'9' {
+        table[d as usize] = 90;
+        d += 1;
+    }
+
+    // Common code punctuation
+    const COMMON_PUNCTUATION: &[u8] = b"_(){}[].,;:=\"'";
+    let mut j = 0;
+    while j < COMMON_PUNCTUATION.len() {
+        table[COMMON_PUNCTUATION[j] as usize] = 150;
+        j += 1;
+    }
+
+    table
 }
 
 impl FrequencyAnalysis for FrequencyAnalyzer {
@@ -65,7 +315,17 @@ impl FrequencyAnalysis for FrequencyAnalyzer {
                         patterns_skipped += 1;
                         continue;
                     }
-                    
+
+                    // With rarity seeding, only track windows anchored on at
+                    // least one distinctive (rare) byte
+                    if let Some(max_rank) = self.max_rarity_rank {
+                        let rarest = window.iter().map(|&b| BYTE_COMMONNESS[b as usize]).min().unwrap_or(u8::MAX);
+                        if rarest > max_rank {
+                            patterns_skipped += 1;
+                            continue;
+                        }
+                    }
+
                     *self.pattern_frequencies.entry(pattern.to_string()).or_insert(0) += 1;
                 }
             }
@@ -88,12 +348,20 @@ impl FrequencyAnalysis for FrequencyAnalyzer {
     /// Returns patterns sorted by frequency (descending) that meet the minimum threshold
     #[instrument(name = "get_frequent_patterns", skip(self))]
     fn get_frequent_patterns(&self) -> Vec<(String, usize)> {
+        if let Some(patterns) = &self.external_patterns {
+            debug!(
+                pattern_count = patterns.len(),
+                "Returning externally-supplied patterns verbatim"
+            );
+            return patterns.clone();
+        }
+
         debug!(
             total_patterns = self.pattern_frequencies.len(),
             min_frequency_threshold = self.min_frequency_threshold,
             "Filtering patterns by frequency threshold"
         );
-        
+
         let mut patterns: Vec<_> = self.pattern_frequencies
             .iter()
             .filter(|(_, &freq)| freq >= self.min_frequency_threshold)
@@ -254,4 +522,49 @@ mod tests {
         assert!(pattern_strings.iter().any(|p| p.contains("function")));
         assert!(pattern_strings.iter().any(|p| p.contains("return")));
     }
+
+    #[test]
+    fn test_analyze_content_exact_basic() {
+        let mut analyzer = FrequencyAnalyzer::new(4, 2);
+        let content = "function test() { function demo() { function main() { } } }";
+
+        analyzer.analyze_content_exact(content);
+
+        let patterns = analyzer.get_frequent_patterns();
+        assert!(!patterns.is_empty());
+        assert!(patterns.iter().any(|(pattern, _)| pattern == "function"));
+    }
+
+    #[test]
+    fn test_analyze_content_exact_gives_non_inflated_counts() {
+        // "aa" occurs three times in "aaaa" (overlapping, at positions
+        // 0,1,2) and "aaa" twice; the automaton's counts are these exact
+        // overlapping occurrence counts.
+        let mut analyzer = FrequencyAnalyzer::new(2, 1);
+        analyzer.analyze_content_exact("aaaa");
+
+        assert_eq!(analyzer.pattern_frequencies.get("aa"), Some(&3));
+        assert_eq!(analyzer.pattern_frequencies.get("aaa"), Some(&2));
+    }
+
+    #[test]
+    fn test_rarity_seeding_skips_common_only_windows() {
+        // A window made only of spaces and common lowercase letters has no
+        // rare anchor byte and should be skipped entirely.
+        let mut analyzer = FrequencyAnalyzer::with_rarity_seeding(4, 1, 50);
+        analyzer.analyze_content("aaaa aaaa aaaa");
+        assert!(analyzer.get_frequent_patterns().is_empty());
+    }
+
+    #[test]
+    fn test_rarity_seeding_keeps_windows_anchored_on_rare_bytes() {
+        // "_id_" contains the underscore, which scores well below common
+        // letters, so it should still be seeded as a candidate.
+        let mut analyzer = FrequencyAnalyzer::with_rarity_seeding(4, 2, 150);
+        analyzer.analyze_content("user_id_ user_id_ user_id_");
+        assert!(analyzer
+            .get_frequent_patterns()
+            .iter()
+            .any(|(pattern, _)| pattern.contains('_')));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,122 @@
+//! Standard base64 (RFC 4648) text encoding
+//!
+//! `FileEntry::compressed_content` is a `String`, but a `Codec`'s output is
+//! arbitrary bytes; this gives the final compression stage a reversible way
+//! to carry those bytes through a `String` field instead of a placeholder
+//! that can't be decoded back.
+
+use crate::compression::error::{CompressionError, CompressionResult};
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const PAD: u8 = b'=';
+
+/// Encode `data` as standard, padded base64 text.
+pub fn encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity((data.len() + 2) / 3 * 4);
+
+    for chunk in data.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = chunk.get(1).copied();
+        let b2 = chunk.get(2).copied();
+
+        out.push(ALPHABET[(b0 >> 2) as usize] as char);
+        out.push(ALPHABET[((b0 & 0x03) << 4 | b1.unwrap_or(0) >> 4) as usize] as char);
+        out.push(match b1 {
+            Some(b1) => ALPHABET[((b1 & 0x0f) << 2 | b2.unwrap_or(0) >> 6) as usize] as char,
+            None => PAD as char,
+        });
+        out.push(match b2 {
+            Some(b2) => ALPHABET[(b2 & 0x3f) as usize] as char,
+            None => PAD as char,
+        });
+    }
+
+    out
+}
+
+/// Decode standard, padded base64 text produced by `encode`.
+pub fn decode(text: &str) -> CompressionResult<Vec<u8>> {
+    let bytes = text.as_bytes();
+    if bytes.len() % 4 != 0 {
+        return Err(CompressionError::config_validation(
+            "Invalid base64: length is not a multiple of 4",
+        ));
+    }
+
+    let mut out = Vec::with_capacity(bytes.len() / 4 * 3);
+    for quad in bytes.chunks(4) {
+        let mut values = [0u8; 4];
+        let mut pad_count = 0;
+        for (i, &byte) in quad.iter().enumerate() {
+            if byte == PAD {
+                pad_count += 1;
+                continue;
+            }
+            values[i] = decode_symbol(byte)?;
+        }
+
+        out.push(values[0] << 2 | values[1] >> 4);
+        if pad_count < 2 {
+            out.push(values[1] << 4 | values[2] >> 2);
+        }
+        if pad_count < 1 {
+            out.push(values[2] << 6 | values[3]);
+        }
+    }
+
+    Ok(out)
+}
+
+fn decode_symbol(byte: u8) -> CompressionResult<u8> {
+    ALPHABET
+        .iter()
+        .position(|&symbol| symbol == byte)
+        .map(|index| index as u8)
+        .ok_or_else(|| {
+            CompressionError::config_validation(format!(
+                "Invalid base64 symbol: '{}'",
+                byte as char
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_without_padding() {
+        let data = b"abc";
+        assert_eq!(decode(&encode(data)).unwrap(), data);
+    }
+
+    #[test]
+    fn test_round_trip_with_padding() {
+        for data in [b"a".as_slice(), b"ab", b"abcd", b"abcde"] {
+            assert_eq!(decode(&encode(data)).unwrap(), data, "round trip failed for {data:?}");
+        }
+    }
+
+    #[test]
+    fn test_encode_matches_known_vector() {
+        assert_eq!(encode(b"Man"), "TWFu");
+        assert_eq!(encode(b"Ma"), "TWE=");
+        assert_eq!(encode(b"M"), "TQ==");
+    }
+
+    #[test]
+    fn test_round_trip_binary_data() {
+        let data: Vec<u8> = (0u8..=255).collect();
+        assert_eq!(decode(&encode(&data)).unwrap(), data);
+    }
+
+    #[test]
+    fn test_decode_rejects_invalid_length() {
+        assert!(decode("abc").is_err());
+    }
+
+    #[test]
+    fn test_decode_rejects_invalid_symbol() {
+        assert!(decode("ab!=").is_err());
+    }
+}
@@ -0,0 +1,609 @@
+//! Framed block archive with per-block checksums
+//!
+//! `framed.rs` wraps a single payload as one `[codec id][length][payload]`
+//! frame; it has no way to verify a truncated or bit-flipped payload before
+//! decoding it, and no place to carry more than one logical blob. This
+//! module splits an archive's concatenated file content into fixed-size
+//! blocks, compresses and checksums each one independently, and appends a
+//! binary index recording every file's byte range plus the dictionary
+//! needed to expand it. `read_archive` checks every block's header and
+//! payload length, then decodes it and verifies its decoded length and
+//! checksum before using it, so truncation or corruption is reported as an
+//! error instead of surfacing as garbage output.
+//!
+//! Layout: `[block]* [index] [index offset: u64 LE]`. Each block is
+//! `[magic: u8][uncompressed len: u32 LE][compressed len: u32 LE][checksum:
+//! 0/8/16 bytes, per `ChecksumConfig`][payload]`. The trailing 8 bytes are
+//! always the absolute offset of the index, Parquet-footer style, so a
+//! reader can seek straight to it without scanning every block first.
+//!
+//! Like `codec`, the `ChecksumConfig` passed to `write_archive` isn't
+//! recorded in the archive itself - a reader must pass back the same one to
+//! `read_archive`/`extract_archive` to get a correctly-sized checksum field.
+//!
+//! `read_archive` only reverses the block layer, handing back each file's
+//! raw Step-3 content. [`extract_archive`] goes the rest of the way: it also
+//! reverses the `ContentHeader`/`CompressionMethod` pair recorded per entry
+//! in the index (dictionary expansion, final-stage codec) and writes the
+//! recovered files under an output directory, the binary-format sibling of
+//! `restore::restore_archive`.
+
+use crate::compression::codec::Codec;
+use crate::compression::config::ChecksumConfig;
+use crate::compression::error::{CompressionError, CompressionResult};
+use crate::compression::restore::restore_path;
+use crate::compression::types::{CompressionMethod, ContentHeader, Dictionary, FileEntry};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use xxhash_rust::xxh3::{xxh3_128, xxh3_64};
+
+/// Tag byte at the start of every block header.
+pub const BLOCK_MAGIC: u8 = 0xB1;
+
+/// Target number of uncompressed bytes per block.
+pub const BLOCK_SIZE: usize = 64 * 1024;
+
+/// Fixed portion of a block header, before its (variable-length) checksum
+/// and payload: `[magic: u8][uncompressed len: u32 LE][compressed len: u32 LE]`.
+const HEADER_PREFIX_LEN: usize = 1 + 4 + 4;
+
+/// Render `bytes` as lowercase hex, for `checksum_mismatch` error messages.
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// One file's byte range within the reassembled (decompressed, concatenated)
+/// blob, as recorded in the archive's trailing index, plus the
+/// `ContentHeader`/`CompressionMethod` pair `FileEntry::decompress` needs to
+/// reverse that range back to the file's original content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexEntry {
+    pub relative_path: PathBuf,
+    pub offset: u64,
+    pub len: u64,
+    pub header: ContentHeader,
+    pub method: Option<CompressionMethod>,
+}
+
+/// Render a `ContentHeader` the same way `main.rs`'s `write_compressed_content`
+/// renders its `Header:` line, so both formats agree on what a dictionary or
+/// fsst entry's header looks like as text.
+fn encode_header(header: &ContentHeader) -> String {
+    match header {
+        ContentHeader::Plain => "Plain".to_string(),
+        ContentHeader::Compressed { dictionary_id } => format!("Compressed dictionary_id={}", dictionary_id),
+        ContentHeader::FsstCompressed { table_id } => format!("FsstCompressed table_id={}", table_id),
+    }
+}
+
+/// Reverse `encode_header`.
+fn decode_header(text: &str) -> CompressionResult<ContentHeader> {
+    if text == "Plain" {
+        return Ok(ContentHeader::Plain);
+    }
+    if let Some(dictionary_id) = text.strip_prefix("Compressed dictionary_id=") {
+        return Ok(ContentHeader::Compressed {
+            dictionary_id: dictionary_id.to_string(),
+        });
+    }
+    if let Some(table_id) = text.strip_prefix("FsstCompressed table_id=") {
+        return Ok(ContentHeader::FsstCompressed {
+            table_id: table_id.to_string(),
+        });
+    }
+
+    Err(CompressionError::integrity_check(format!(
+        "Block archive index has an unrecognized content header: '{}'",
+        text
+    )))
+}
+
+/// Checksum of `data` under `checksum`, empty for `ChecksumConfig::None`.
+fn block_checksum(data: &[u8], checksum: ChecksumConfig) -> Vec<u8> {
+    match checksum {
+        ChecksumConfig::None => Vec::new(),
+        ChecksumConfig::Xxh3_64 => xxh3_64(data).to_le_bytes().to_vec(),
+        ChecksumConfig::Xxh3_128 => xxh3_128(data).to_le_bytes().to_vec(),
+    }
+}
+
+/// Split `data` into `BLOCK_SIZE` chunks, compress each with `codec`, and
+/// write it as `[magic][uncompressed len][compressed len][checksum][payload]`,
+/// where the checksum is sized and computed per `checksum`.
+fn write_blocks(data: &[u8], codec: Codec, checksum: ChecksumConfig) -> CompressionResult<Vec<u8>> {
+    let compressor = codec.compressor()?;
+    let mut out = Vec::with_capacity(data.len());
+
+    for chunk in data.chunks(BLOCK_SIZE) {
+        let payload = compressor.compress(chunk)?;
+        let block_checksum = block_checksum(chunk, checksum);
+
+        out.push(BLOCK_MAGIC);
+        out.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        out.extend_from_slice(&block_checksum);
+        out.extend_from_slice(&payload);
+    }
+
+    Ok(out)
+}
+
+/// Reverse `write_blocks`, verifying each block's magic byte, decompressed
+/// length, and checksum (per `checksum`) before appending it to the output.
+/// Returns an `integrity_check` error on the first truncated header or
+/// truncated payload, and a `checksum_mismatch` error on the first corrupt
+/// block, instead of returning partial output.
+fn read_blocks(data: &[u8], codec: Codec, checksum: ChecksumConfig) -> CompressionResult<Vec<u8>> {
+    let compressor = codec.compressor()?;
+    let checksum_len = checksum.checksum_len();
+    let header_len = HEADER_PREFIX_LEN + checksum_len;
+    let mut out = Vec::with_capacity(data.len());
+    let mut pos = 0;
+
+    while pos < data.len() {
+        if pos + header_len > data.len() {
+            return Err(CompressionError::integrity_check(
+                "Block archive is truncated: incomplete block header",
+            ));
+        }
+
+        let magic = data[pos];
+        if magic != BLOCK_MAGIC {
+            return Err(CompressionError::integrity_check(format!(
+                "Block archive is corrupt: expected magic byte {:#x}, found {:#x}",
+                BLOCK_MAGIC, magic
+            )));
+        }
+
+        let uncompressed_len = u32::from_le_bytes(data[pos + 1..pos + 5].try_into().unwrap()) as usize;
+        let compressed_len = u32::from_le_bytes(data[pos + 5..pos + 9].try_into().unwrap()) as usize;
+        let stored_checksum = &data[pos + HEADER_PREFIX_LEN..pos + header_len];
+
+        let payload_start = pos + header_len;
+        let payload_end = payload_start + compressed_len;
+        if payload_end > data.len() {
+            return Err(CompressionError::integrity_check(
+                "Block archive is truncated: incomplete block payload",
+            ));
+        }
+
+        let payload = &data[payload_start..payload_end];
+        let decompressed = compressor.decompress(payload)?;
+
+        if decompressed.len() != uncompressed_len {
+            return Err(CompressionError::integrity_check(format!(
+                "Block archive is corrupt: header claims {} uncompressed bytes, decoded {}",
+                uncompressed_len,
+                decompressed.len()
+            )));
+        }
+        if checksum_len > 0 {
+            let actual_checksum = block_checksum(&decompressed, checksum);
+            if actual_checksum != stored_checksum {
+                return Err(CompressionError::checksum_mismatch(
+                    hex(stored_checksum),
+                    hex(&actual_checksum),
+                ));
+            }
+        }
+
+        out.extend_from_slice(&decompressed);
+        pos = payload_end;
+    }
+
+    Ok(out)
+}
+
+fn write_len_prefixed(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    out.extend_from_slice(bytes);
+}
+
+fn read_u32(data: &[u8], pos: &mut usize) -> CompressionResult<u32> {
+    if *pos + 4 > data.len() {
+        return Err(CompressionError::integrity_check("Block archive index is truncated"));
+    }
+    let value = u32::from_le_bytes(data[*pos..*pos + 4].try_into().unwrap());
+    *pos += 4;
+    Ok(value)
+}
+
+fn read_u64(data: &[u8], pos: &mut usize) -> CompressionResult<u64> {
+    if *pos + 8 > data.len() {
+        return Err(CompressionError::integrity_check("Block archive index is truncated"));
+    }
+    let value = u64::from_le_bytes(data[*pos..*pos + 8].try_into().unwrap());
+    *pos += 8;
+    Ok(value)
+}
+
+fn read_len_prefixed_string(data: &[u8], pos: &mut usize) -> CompressionResult<String> {
+    let len = read_u32(data, pos)? as usize;
+    if *pos + len > data.len() {
+        return Err(CompressionError::integrity_check("Block archive index is truncated"));
+    }
+    let bytes = &data[*pos..*pos + len];
+    *pos += len;
+    String::from_utf8(bytes.to_vec())
+        .map_err(|e| CompressionError::integrity_check(format!("Block archive index is not valid UTF-8: {}", e)))
+}
+
+/// Serialize the file index and dictionary entries into the index section
+/// written between the blocks and the trailing offset pointer.
+fn encode_index(index: &[IndexEntry], dictionary_entries: &[(String, String)]) -> Vec<u8> {
+    let mut out = Vec::new();
+
+    out.extend_from_slice(&(index.len() as u32).to_le_bytes());
+    for entry in index {
+        write_len_prefixed(&mut out, entry.relative_path.to_string_lossy().as_bytes());
+        out.extend_from_slice(&entry.offset.to_le_bytes());
+        out.extend_from_slice(&entry.len.to_le_bytes());
+        write_len_prefixed(&mut out, encode_header(&entry.header).as_bytes());
+        // An empty string means "no final-stage codec ran" (`method: None`);
+        // `CompressionMethod`'s `Display` never produces an empty string, so
+        // there's no ambiguity with a real method name.
+        let method_text = entry.method.map(|m| m.to_string()).unwrap_or_default();
+        write_len_prefixed(&mut out, method_text.as_bytes());
+    }
+
+    out.extend_from_slice(&(dictionary_entries.len() as u32).to_le_bytes());
+    for (pattern, token) in dictionary_entries {
+        write_len_prefixed(&mut out, pattern.as_bytes());
+        write_len_prefixed(&mut out, token.as_bytes());
+    }
+
+    out
+}
+
+fn decode_index(data: &[u8]) -> CompressionResult<(Vec<IndexEntry>, Vec<(String, String)>)> {
+    let mut pos = 0;
+
+    let file_count = read_u32(data, &mut pos)? as usize;
+    let mut index = Vec::with_capacity(file_count);
+    for _ in 0..file_count {
+        let relative_path = PathBuf::from(read_len_prefixed_string(data, &mut pos)?);
+        let offset = read_u64(data, &mut pos)?;
+        let len = read_u64(data, &mut pos)?;
+        let header = decode_header(&read_len_prefixed_string(data, &mut pos)?)?;
+        let method_text = read_len_prefixed_string(data, &mut pos)?;
+        let method = if method_text.is_empty() {
+            None
+        } else {
+            Some(CompressionMethod::from_str(&method_text)?)
+        };
+        index.push(IndexEntry {
+            relative_path,
+            offset,
+            len,
+            header,
+            method,
+        });
+    }
+
+    let dict_count = read_u32(data, &mut pos)? as usize;
+    let mut dictionary_entries = Vec::with_capacity(dict_count);
+    for _ in 0..dict_count {
+        let pattern = read_len_prefixed_string(data, &mut pos)?;
+        let token = read_len_prefixed_string(data, &mut pos)?;
+        dictionary_entries.push((pattern, token));
+    }
+
+    Ok((index, dictionary_entries))
+}
+
+/// Build a self-describing block archive from `entries`' Step-3 content
+/// (`compressed_content`, falling back to `original_content` for entries
+/// Step 3 left untouched, e.g. `Store`d files) and `dictionary_entries`.
+/// Every file's content is concatenated into one blob, block-compressed
+/// with `codec` via `write_blocks`, and followed by a binary index
+/// recording each file's byte range in that blob plus the dictionary, so
+/// `read_archive` can reverse the whole thing from the buffer plus the same
+/// `codec` and `checksum` it was written with.
+pub fn write_archive(
+    entries: &[FileEntry],
+    dictionary_entries: &[(String, String)],
+    codec: Codec,
+    checksum: ChecksumConfig,
+) -> CompressionResult<Vec<u8>> {
+    let mut blob = Vec::new();
+    let mut index = Vec::with_capacity(entries.len());
+
+    for entry in entries {
+        let content = entry.compressed_content.as_deref().unwrap_or(&entry.original_content);
+        let offset = blob.len() as u64;
+        blob.extend_from_slice(content.as_bytes());
+        index.push(IndexEntry {
+            relative_path: entry.relative_path.clone(),
+            offset,
+            len: content.len() as u64,
+            header: entry.header.clone(),
+            method: entry.method,
+        });
+    }
+
+    let blocks = write_blocks(&blob, codec, checksum)?;
+    let index_bytes = encode_index(&index, dictionary_entries);
+    let index_offset = blocks.len() as u64;
+
+    let mut archive = Vec::with_capacity(blocks.len() + index_bytes.len() + 8);
+    archive.extend_from_slice(&blocks);
+    archive.extend_from_slice(&index_bytes);
+    archive.extend_from_slice(&index_offset.to_le_bytes());
+    Ok(archive)
+}
+
+/// Reverse `write_archive`: follow the trailing offset pointer to the
+/// index, verify and decode every block (see `read_blocks`), then slice the
+/// reassembled blob back into each file's Step-3 content plus the
+/// dictionary entries needed to expand it. `checksum` must match what
+/// `write_archive` was called with.
+pub fn read_archive(
+    archive: &[u8],
+    codec: Codec,
+    checksum: ChecksumConfig,
+) -> CompressionResult<(Vec<(PathBuf, String)>, Vec<(String, String)>)> {
+    if archive.len() < 8 {
+        return Err(CompressionError::integrity_check(
+            "Block archive is too short to contain an index offset",
+        ));
+    }
+
+    let trailer_start = archive.len() - 8;
+    let index_offset = u64::from_le_bytes(archive[trailer_start..].try_into().unwrap()) as usize;
+    if index_offset > trailer_start {
+        return Err(CompressionError::integrity_check(
+            "Block archive index offset points past the end of the archive",
+        ));
+    }
+
+    let blob = read_blocks(&archive[..index_offset], codec, checksum)?;
+    let (index, dictionary_entries) = decode_index(&archive[index_offset..trailer_start])?;
+
+    let mut files = Vec::with_capacity(index.len());
+    for entry in index {
+        let start = entry.offset as usize;
+        let end = start
+            .checked_add(entry.len as usize)
+            .filter(|&end| end <= blob.len())
+            .ok_or_else(|| {
+                CompressionError::integrity_check(format!(
+                    "Block archive index entry for '{}' extends past the reassembled content",
+                    entry.relative_path.display()
+                ))
+            })?;
+        let content = String::from_utf8(blob[start..end].to_vec()).map_err(|e| {
+            CompressionError::integrity_check(format!(
+                "Block archive entry for '{}' is not valid UTF-8: {}",
+                entry.relative_path.display(),
+                e
+            ))
+        })?;
+        files.push((entry.relative_path, content));
+    }
+
+    Ok((files, dictionary_entries))
+}
+
+/// Reverse the full chain `write_archive` produced: verify and decode every
+/// block (see `read_blocks`), then for each index entry run
+/// `FileEntry::decompress` - which undoes the final-stage codec recorded in
+/// `method` and, per `header`, expands dictionary tokens - and write the
+/// recovered content to `relative_path` under `output_dir`.
+///
+/// Like `restore::restore_archive`, an entry whose `header` is
+/// `FsstCompressed` can't be restored from this function alone: this
+/// archive format doesn't carry a trained `SymbolTable`, so `decompress`
+/// surfaces that as an error rather than returning garbage.
+///
+/// Returns the relative paths written, in index order. `checksum` must
+/// match what `write_archive` was called with.
+pub fn extract_archive(
+    archive: &[u8],
+    codec: Codec,
+    checksum: ChecksumConfig,
+    output_dir: &Path,
+) -> CompressionResult<Vec<PathBuf>> {
+    if archive.len() < 8 {
+        return Err(CompressionError::integrity_check(
+            "Block archive is too short to contain an index offset",
+        ));
+    }
+
+    let trailer_start = archive.len() - 8;
+    let index_offset = u64::from_le_bytes(archive[trailer_start..].try_into().unwrap()) as usize;
+    if index_offset > trailer_start {
+        return Err(CompressionError::integrity_check(
+            "Block archive index offset points past the end of the archive",
+        ));
+    }
+
+    let blob = read_blocks(&archive[..index_offset], codec, checksum)?;
+    let (index, dictionary_entries) = decode_index(&archive[index_offset..trailer_start])?;
+    let dictionary = Dictionary::from_entries(dictionary_entries);
+
+    std::fs::create_dir_all(output_dir)?;
+    let canonical_root = std::fs::canonicalize(output_dir)?;
+
+    let mut restored_paths = Vec::with_capacity(index.len());
+    for entry in index {
+        let start = entry.offset as usize;
+        let end = start
+            .checked_add(entry.len as usize)
+            .filter(|&end| end <= blob.len())
+            .ok_or_else(|| {
+                CompressionError::integrity_check(format!(
+                    "Block archive index entry for '{}' extends past the reassembled content",
+                    entry.relative_path.display()
+                ))
+            })?;
+        let content = String::from_utf8(blob[start..end].to_vec()).map_err(|e| {
+            CompressionError::integrity_check(format!(
+                "Block archive entry for '{}' is not valid UTF-8: {}",
+                entry.relative_path.display(),
+                e
+            ))
+        })?;
+
+        let file_entry = FileEntry {
+            relative_path: entry.relative_path.clone(),
+            original_content: String::new(),
+            compressed_content: Some(content),
+            is_binary: false,
+            original_size: crate::compression::types::FileSize::new(0),
+            compressed_size: None,
+            header: entry.header,
+            method: entry.method,
+        };
+        // Same limitation as the text archive format: no `SymbolTable` or
+        // `ZstdDictionary` is embedded in this container, so Fsst- or
+        // zstd-dictionary-compressed entries can't be restored from it yet.
+        let restored = file_entry.decompress(&dictionary, None, None)?;
+
+        let dest = restore_path(&canonical_root, &entry.relative_path)?;
+        std::fs::write(&dest, &restored)?;
+        restored_paths.push(entry.relative_path);
+    }
+
+    Ok(restored_paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, content: &str) -> FileEntry {
+        let mut entry = FileEntry::new(PathBuf::from(path), content.to_string(), false);
+        entry.compressed_content = Some(content.to_string());
+        entry
+    }
+
+    #[test]
+    fn test_archive_round_trips_multiple_files() {
+        let entries = vec![
+            entry("a.rs", &"fn a() {}\n".repeat(5000)),
+            entry("b.rs", "fn b() {}"),
+        ];
+        let dictionary_entries = vec![("fn a() {}\n".to_string(), "T0000".to_string())];
+        let codec = Codec::from_str("zstd/5").unwrap();
+
+        let archive = write_archive(&entries, &dictionary_entries, codec, ChecksumConfig::default()).unwrap();
+        let (files, restored_dictionary) = read_archive(&archive, codec, ChecksumConfig::default()).unwrap();
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0], (PathBuf::from("a.rs"), "fn a() {}\n".repeat(5000)));
+        assert_eq!(files[1], (PathBuf::from("b.rs"), "fn b() {}".to_string()));
+        assert_eq!(restored_dictionary, dictionary_entries);
+    }
+
+    #[test]
+    fn test_archive_spans_multiple_blocks() {
+        let big_content = "x".repeat(BLOCK_SIZE * 3 + 17);
+        let entries = vec![entry("big.txt", &big_content)];
+        let codec = Codec::from_str("lz4").unwrap();
+
+        let archive = write_archive(&entries, &[], codec, ChecksumConfig::default()).unwrap();
+        let (files, _) = read_archive(&archive, codec, ChecksumConfig::default()).unwrap();
+
+        assert_eq!(files[0].1, big_content);
+    }
+
+    #[test]
+    fn test_read_archive_rejects_corrupted_block() {
+        let entries = vec![entry("a.txt", "hello world")];
+        let codec = Codec::from_str("stored").unwrap();
+        let checksum = ChecksumConfig::default();
+        let mut archive = write_archive(&entries, &[], codec, checksum).unwrap();
+
+        // Flip a byte inside the first block's payload.
+        archive[HEADER_PREFIX_LEN + checksum.checksum_len()] ^= 0xff;
+
+        let err = read_archive(&archive, codec, checksum).unwrap_err();
+        assert!(matches!(err, CompressionError::ChecksumMismatch { .. }));
+    }
+
+    #[test]
+    fn test_read_archive_rejects_truncated_archive() {
+        let entries = vec![entry("a.txt", "hello world")];
+        let codec = Codec::from_str("stored").unwrap();
+        let archive = write_archive(&entries, &[], codec, ChecksumConfig::default()).unwrap();
+
+        let truncated = &archive[..archive.len() - 4];
+        assert!(read_archive(truncated, codec, ChecksumConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_read_archive_rejects_bad_index_offset() {
+        let mut archive = vec![0u8; 4];
+        archive.extend_from_slice(&u64::MAX.to_le_bytes());
+
+        assert!(read_archive(&archive, Codec::from_str("stored").unwrap(), ChecksumConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_checksum_config_none_skips_verification() {
+        let entries = vec![entry("a.txt", "hello world")];
+        let codec = Codec::from_str("stored").unwrap();
+        let mut archive = write_archive(&entries, &[], codec, ChecksumConfig::None).unwrap();
+
+        // With no checksum stored, flipping a payload byte is only caught if
+        // it also breaks the codec or the length check - stored content
+        // flipped in place still decodes, so this must NOT error.
+        archive[HEADER_PREFIX_LEN] ^= 0x01;
+        let (files, _) = read_archive(&archive, codec, ChecksumConfig::None).unwrap();
+        assert_ne!(files[0].1, "hello world");
+    }
+
+    #[test]
+    fn test_checksum_config_xxh3_64_round_trips() {
+        let entries = vec![entry("a.txt", "hello world")];
+        let codec = Codec::from_str("zstd/3").unwrap();
+        let archive = write_archive(&entries, &[], codec, ChecksumConfig::Xxh3_64).unwrap();
+        let (files, _) = read_archive(&archive, codec, ChecksumConfig::Xxh3_64).unwrap();
+        assert_eq!(files[0].1, "hello world");
+    }
+
+    #[test]
+    fn test_extract_archive_reverses_dictionary_and_final_codec() {
+        let dictionary_entries = vec![("hello world".to_string(), "T0000".to_string())];
+        let dictionary_id = Dictionary::from_entries(dictionary_entries.clone()).id().to_string();
+
+        // Mimic `compress()`'s forward pipeline by hand: Step 3 substitutes
+        // the dictionary token, then Step 4 runs a (here: no-op) final-stage
+        // codec over that text and base64-encodes the result.
+        let mut file = FileEntry::new(PathBuf::from("greeting.txt"), "hello world".to_string(), false);
+        file.apply_dictionary_compression("T0000".to_string(), dictionary_id, None);
+        let encoded = crate::compression::base64::encode(b"T0000");
+        file.apply_compression(encoded, CompressionMethod::Store, None);
+
+        let codec = Codec::from_str("stored").unwrap();
+        let archive = write_archive(&[file], &dictionary_entries, codec, ChecksumConfig::default()).unwrap();
+
+        let output_dir = tempfile::TempDir::new().unwrap();
+        let restored_paths =
+            extract_archive(&archive, codec, ChecksumConfig::default(), output_dir.path()).unwrap();
+
+        assert_eq!(restored_paths, vec![PathBuf::from("greeting.txt")]);
+        let restored = std::fs::read_to_string(output_dir.path().join("greeting.txt")).unwrap();
+        assert_eq!(restored, "hello world");
+    }
+
+    #[test]
+    fn test_extract_archive_rejects_path_traversal() {
+        let entries = vec![entry("../escape.txt", "hello")];
+        let codec = Codec::from_str("stored").unwrap();
+        let archive = write_archive(&entries, &[], codec, ChecksumConfig::default()).unwrap();
+
+        let output_dir = tempfile::TempDir::new().unwrap();
+        assert!(extract_archive(&archive, codec, ChecksumConfig::default(), output_dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_empty_archive_round_trips() {
+        let codec = Codec::from_str("zstd/3").unwrap();
+        let archive = write_archive(&[], &[], codec, ChecksumConfig::default()).unwrap();
+        let (files, dictionary_entries) = read_archive(&archive, codec, ChecksumConfig::default()).unwrap();
+
+        assert!(files.is_empty());
+        assert!(dictionary_entries.is_empty());
+    }
+}
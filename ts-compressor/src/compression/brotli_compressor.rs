@@ -0,0 +1,79 @@
+//! Brotli compression integration
+//!
+//! Thin wrapper around the `brotli` crate's streaming `Read`/`Write`
+//! adapters, used in one-shot mode for the `Compressor` abstraction.
+
+use crate::compression::codec::Compressor;
+use crate::compression::error::{CompressionError, CompressionResult};
+use std::io::{Read, Write};
+
+const BUFFER_SIZE: usize = 4096;
+const LG_WINDOW_SIZE: u32 = 22;
+
+/// Brotli compressor at a fixed quality suited to general-purpose text.
+#[derive(Debug)]
+pub struct BrotliCompressor {
+    quality: u32,
+}
+
+impl BrotliCompressor {
+    /// Create a new brotli compressor using the default quality.
+    pub fn new() -> Self {
+        Self { quality: 9 }
+    }
+}
+
+impl Default for BrotliCompressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Compressor for BrotliCompressor {
+    fn compress(&self, data: &[u8]) -> CompressionResult<Vec<u8>> {
+        let mut compressed = Vec::new();
+        {
+            let mut writer =
+                brotli::CompressorWriter::new(&mut compressed, BUFFER_SIZE, self.quality, LG_WINDOW_SIZE);
+            writer
+                .write_all(data)
+                .map_err(CompressionError::brotli_compression)?;
+        }
+        Ok(compressed)
+    }
+
+    fn decompress(&self, data: &[u8]) -> CompressionResult<Vec<u8>> {
+        let mut decompressed = Vec::new();
+        let mut reader = brotli::Decompressor::new(data, BUFFER_SIZE);
+        reader
+            .read_to_end(&mut decompressed)
+            .map_err(CompressionError::brotli_compression)?;
+        Ok(decompressed)
+    }
+
+    fn codec_id(&self) -> u8 {
+        4
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_brotli_round_trip() {
+        let compressor = BrotliCompressor::new();
+        let data = b"function test() { return 'hello world'; }".repeat(10);
+
+        let compressed = compressor.compress(&data).unwrap();
+        let decompressed = compressor.decompress(&compressed).unwrap();
+
+        assert_eq!(decompressed, data);
+    }
+
+    #[test]
+    fn test_brotli_rejects_garbage() {
+        let compressor = BrotliCompressor::new();
+        assert!(compressor.decompress(b"not brotli data").is_err());
+    }
+}
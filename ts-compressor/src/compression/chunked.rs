@@ -0,0 +1,234 @@
+//! Chunked, incrementally-dictionary-trained compression for corpora too
+//! large to hold in memory at once.
+//!
+//! `UniversalCompressor::compress` assumes the whole target tree's
+//! `FileEntry`s and a single whole-corpus frequency pass fit in memory
+//! together. [`compress_in_batches`] instead partitions an already-collected
+//! file list into [`Batch`]es that each fit under a byte budget
+//! ([`partition_into_batches`]), then trains the dictionary incrementally:
+//! batch 1 seeds it, and each later batch's newly-discovered patterns are
+//! merged in, keeping the highest-frequency ones when the dictionary size
+//! budget is exceeded. A pattern that already has a token never gets a new
+//! one - reassigning would invalidate every earlier batch's compressed
+//! output, which already references that token - so every batch's output
+//! stays valid against the final, cumulative dictionary. The per-batch file
+//! counts and that final dictionary are recorded in a [`ChunkManifest`] so
+//! the result is reproducible.
+
+use crate::compression::{
+    CompressionError, FrequencyAnalysis, FrequencyAnalyzer, PatternReplacement, PatternReplacer,
+};
+use crate::compression::generator::HexTokenGenerator;
+use crate::compression::types::FileEntry;
+use crate::compression::TokenGeneration;
+use std::collections::HashMap;
+
+/// One group of files sized to fit under a batch's byte budget, produced by
+/// [`partition_into_batches`].
+#[derive(Debug)]
+pub struct Batch {
+    pub files: Vec<FileEntry>,
+}
+
+/// Records how [`compress_in_batches`] divided up and compressed its input,
+/// so the result is reproducible without re-running batch partitioning or
+/// dictionary training.
+#[derive(Debug, Clone)]
+pub struct ChunkManifest {
+    /// Number of files in each batch, in processing order.
+    pub batch_file_counts: Vec<usize>,
+    /// The final, cumulative dictionary every output entry was compressed
+    /// against, as `(pattern, token)` pairs.
+    pub dictionary_entries: Vec<(String, String)>,
+}
+
+/// Output of [`compress_in_batches`].
+#[derive(Debug)]
+pub struct ChunkedOutcome {
+    pub files: Vec<FileEntry>,
+    pub manifest: ChunkManifest,
+}
+
+/// Split `files` into batches whose total `original_content` size each stays
+/// under `max_batch_bytes`, preserving `files`' order. A single file larger
+/// than `max_batch_bytes` still gets its own batch rather than being
+/// dropped or split.
+pub fn partition_into_batches(files: Vec<FileEntry>, max_batch_bytes: u64) -> Vec<Batch> {
+    let mut batches = Vec::new();
+    let mut current = Vec::new();
+    let mut current_bytes: u64 = 0;
+
+    for file in files {
+        let file_bytes = file.original_content.len() as u64;
+        if !current.is_empty() && current_bytes + file_bytes > max_batch_bytes {
+            batches.push(Batch {
+                files: std::mem::take(&mut current),
+            });
+            current_bytes = 0;
+        }
+        current_bytes += file_bytes;
+        current.push(file);
+    }
+    if !current.is_empty() {
+        batches.push(Batch { files: current });
+    }
+
+    batches
+}
+
+/// Compress `batches` in order, training the dictionary incrementally
+/// across them instead of in one whole-corpus pass.
+///
+/// Each batch is analyzed with its own [`FrequencyAnalyzer`], new patterns
+/// it discovers are assigned tokens from a single [`HexTokenGenerator`]
+/// shared across every batch (so tokens are never reused), subject to
+/// `max_dictionary_entries` - once that budget is reached, only the
+/// remaining headroom is filled, taking this batch's highest-frequency new
+/// patterns first. The batch is then compressed with a fresh
+/// [`PatternReplacer`] built from the dictionary as it stands after that
+/// merge. Every output entry is finally stamped with the completed,
+/// cumulative dictionary's id, which is valid for all of them since a
+/// token's meaning never changes once assigned.
+pub fn compress_in_batches(
+    batches: Vec<Batch>,
+    min_pattern_length: usize,
+    min_frequency_threshold: usize,
+    max_dictionary_entries: usize,
+) -> Result<ChunkedOutcome, CompressionError> {
+    let mut token_generator = HexTokenGenerator::new();
+    let mut dictionary: HashMap<String, String> = HashMap::new();
+    let mut output_files = Vec::new();
+    let mut batch_file_counts = Vec::with_capacity(batches.len());
+
+    for batch in batches {
+        let mut analyzer = FrequencyAnalyzer::new(min_pattern_length, min_frequency_threshold);
+        for file in &batch.files {
+            analyzer.analyze_content(&file.original_content);
+        }
+
+        let mut new_patterns: Vec<(String, usize)> = analyzer
+            .get_frequent_patterns()
+            .into_iter()
+            .filter(|(pattern, _)| !dictionary.contains_key(pattern))
+            .collect();
+        new_patterns.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        let remaining_capacity = max_dictionary_entries.saturating_sub(dictionary.len());
+        for (pattern, _frequency) in new_patterns.into_iter().take(remaining_capacity) {
+            let token = token_generator.next_token()?;
+            dictionary.insert(pattern, token);
+        }
+
+        let replacer = PatternReplacer::new(dictionary.clone());
+        batch_file_counts.push(batch.files.len());
+        for mut file in batch.files {
+            let compressed_content = replacer.replace_patterns(&file.original_content);
+            file.apply_compression(
+                compressed_content,
+                crate::compression::CompressionMethod::Dictionary,
+                None,
+            );
+            output_files.push(file);
+        }
+    }
+
+    let dictionary_entries: Vec<(String, String)> = dictionary.into_iter().collect();
+    let final_dictionary_id = crate::compression::Dictionary::from_entries(dictionary_entries.clone())
+        .id()
+        .to_string();
+    for file in &mut output_files {
+        file.header = crate::compression::types::ContentHeader::Compressed {
+            dictionary_id: final_dictionary_id.clone(),
+        };
+    }
+
+    Ok(ChunkedOutcome {
+        files: output_files,
+        manifest: ChunkManifest {
+            batch_file_counts,
+            dictionary_entries,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn file(name: &str, content: &str) -> FileEntry {
+        FileEntry::new(PathBuf::from(name), content.to_string(), false)
+    }
+
+    #[test]
+    fn test_partition_into_batches_respects_budget() {
+        let files = vec![file("a.rs", "aaaaaaaaaa"), file("b.rs", "bbbbbbbbbb"), file("c.rs", "cccccccccc")];
+        let batches = partition_into_batches(files, 15);
+
+        assert_eq!(batches.len(), 3);
+        assert_eq!(batches[0].files.len(), 1);
+        assert_eq!(batches[1].files.len(), 1);
+    }
+
+    #[test]
+    fn test_partition_into_batches_keeps_oversized_file_alone() {
+        let files = vec![file("big.rs", "x".repeat(100).as_str()), file("small.rs", "y")];
+        let batches = partition_into_batches(files, 10);
+
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].files.len(), 1);
+        assert_eq!(batches[0].files[0].relative_path, PathBuf::from("big.rs"));
+    }
+
+    #[test]
+    fn test_compress_in_batches_sums_file_count_across_batches() {
+        let pattern = "function_name_placeholder";
+        let content = pattern.repeat(5);
+        let files: Vec<FileEntry> = (0..4)
+            .map(|i| file(&format!("file_{}.rs", i), &content))
+            .collect();
+        let batches = partition_into_batches(files, content.len() as u64 + 1);
+        assert!(batches.len() > 1, "test setup should produce multiple batches");
+
+        let outcome = compress_in_batches(batches, 4, 2, 1000).unwrap();
+
+        assert_eq!(outcome.files.len(), 4);
+        assert_eq!(outcome.manifest.batch_file_counts.iter().sum::<usize>(), 4);
+    }
+
+    #[test]
+    fn test_compress_in_batches_never_reassigns_earlier_tokens() {
+        let shared_pattern = "shared_common_token_text";
+        let first_batch_content = shared_pattern.repeat(5);
+        let second_batch_content = format!("{}{}", shared_pattern.repeat(5), "unique_second_batch_text".repeat(5));
+
+        let files = vec![
+            file("first.rs", &first_batch_content),
+            file("second.rs", &second_batch_content),
+        ];
+        let batches = partition_into_batches(files, first_batch_content.len() as u64 + 1);
+        assert_eq!(batches.len(), 2);
+
+        let outcome = compress_in_batches(batches, 4, 2, 1000).unwrap();
+
+        let first_entry = outcome.files.iter().find(|f| f.relative_path == PathBuf::from("first.rs")).unwrap();
+        let second_entry = outcome.files.iter().find(|f| f.relative_path == PathBuf::from("second.rs")).unwrap();
+
+        let dictionary: HashMap<String, String> = outcome.manifest.dictionary_entries.iter().cloned().collect();
+        let shared_token = dictionary.get(shared_pattern).expect("shared pattern should be tokenized");
+
+        assert!(first_entry.compressed_content.as_ref().unwrap().contains(shared_token));
+        assert!(second_entry.compressed_content.as_ref().unwrap().contains(shared_token));
+    }
+
+    #[test]
+    fn test_compress_in_batches_respects_dictionary_budget() {
+        let content = "pattern_one_here".repeat(5) + &"pattern_two_here".repeat(5);
+        let files = vec![file("only.rs", &content)];
+        let batches = partition_into_batches(files, content.len() as u64 + 1);
+
+        let outcome = compress_in_batches(batches, 4, 2, 1).unwrap();
+
+        assert_eq!(outcome.manifest.dictionary_entries.len(), 1);
+    }
+}
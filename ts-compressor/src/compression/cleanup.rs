@@ -0,0 +1,88 @@
+//! Crash-safe cleanup for interrupted compression runs.
+//!
+//! `UniversalCompressor::prepare_replacement`/`compress` can run for minutes
+//! against a large directory tree; without this, interrupting the process
+//! (Ctrl+C) partway through leaves whatever temp files and partially-written
+//! output had been created on disk. [`CleanupGuard`] tracks every path the
+//! pipeline creates for a run and installs a SIGINT handler that deletes all
+//! of them before the process exits. On successful completion the caller
+//! `commit`s the guard instead, which atomically renames the temp output
+//! into place and disarms the handler so normal process exit leaves it
+//! alone.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+struct GuardState {
+    temp_paths: Mutex<Vec<PathBuf>>,
+    armed: AtomicBool,
+}
+
+static GUARD: OnceLock<GuardState> = OnceLock::new();
+
+fn handle_interrupt() {
+    if let Some(state) = GUARD.get() {
+        if state.armed.load(Ordering::SeqCst) {
+            for path in state.temp_paths.lock().unwrap().drain(..) {
+                let _ = std::fs::remove_file(&path).or_else(|_| std::fs::remove_dir_all(&path));
+            }
+        }
+    }
+    std::process::exit(130); // 128 + SIGINT, the conventional shell exit code
+}
+
+/// Handle to the process-wide cleanup tracker for the pipeline run currently
+/// in progress. Only one compression pipeline runs per process, so the
+/// tracked state lives behind a single global rather than threaded through
+/// every typestate transition.
+pub struct CleanupGuard {
+    _private: (),
+}
+
+impl CleanupGuard {
+    /// Arm cleanup for the pipeline run in this process. `prepare_replacement`
+    /// and `generate_output_file` each call this, so arming is idempotent
+    /// rather than re-clearing on every call: `GUARD.get_or_init` only runs
+    /// its initializer (armed + empty `temp_paths`) the first time, and
+    /// every later call just returns a handle to that same state, leaving
+    /// whatever the first call's handle already registered via
+    /// `register_temp_path` intact. The Ctrl+C handler itself installs only
+    /// once per process - `ctrlc::set_handler` errors on a second call, which
+    /// is harmless to ignore here since the first call's handler already
+    /// reads the (still current) global state on every interrupt.
+    pub fn new() -> Self {
+        GUARD.get_or_init(|| GuardState {
+            temp_paths: Mutex::new(Vec::new()),
+            armed: AtomicBool::new(true),
+        });
+        let _ = ctrlc::set_handler(handle_interrupt);
+
+        CleanupGuard { _private: () }
+    }
+
+    /// Track `path` for deletion if the process is interrupted before
+    /// [`commit`](Self::commit) is called.
+    pub fn register_temp_path(&self, path: PathBuf) {
+        if let Some(state) = GUARD.get() {
+            state.temp_paths.lock().unwrap().push(path);
+        }
+    }
+
+    /// Atomically move `temp_output` into `final_output` and disarm cleanup -
+    /// the run succeeded, so there is nothing left to delete on exit.
+    pub fn commit(&self, temp_output: &Path, final_output: &Path) -> std::io::Result<()> {
+        std::fs::rename(temp_output, final_output)?;
+        if let Some(state) = GUARD.get() {
+            state.armed.store(false, Ordering::SeqCst);
+            state.temp_paths.lock().unwrap().clear();
+        }
+        Ok(())
+    }
+}
+
+impl Default for CleanupGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
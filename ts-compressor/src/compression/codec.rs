@@ -0,0 +1,302 @@
+//! Pluggable compression codec abstraction
+//!
+//! Provides a common `Compressor` trait implemented by each supported
+//! algorithm, plus a `Codec` selector that can be parsed from and rendered
+//! back to the `"name/level"` config form (e.g. `"zstd/9"`).
+
+use crate::compression::brotli_compressor::BrotliCompressor;
+use crate::compression::config::{Lz4Level, ZstdAdvanced, ZstdLevel};
+use crate::compression::deflate_compressor::DeflateCompressor;
+use crate::compression::error::{CompressionError, CompressionResult};
+use crate::compression::lz4_compressor::Lz4Compressor;
+use crate::compression::snappy_compressor::SnappyCompressor;
+use crate::compression::stored_compressor::StoredCompressor;
+use crate::compression::zstd_compressor::ZstdCompressor;
+use std::fmt;
+use std::str::FromStr;
+
+/// Common interface implemented by every compression algorithm supported
+/// through the `Codec` abstraction.
+///
+/// `Send + Sync` so a `Box<dyn Compressor>` built once per `compress()` run
+/// can be shared read-only across a rayon parallel iterator (see
+/// `UniversalCompressor::apply_final_compression`) instead of being rebuilt
+/// per thread or per file.
+pub trait Compressor: Send + Sync {
+    /// Compress `data`, returning the compressed bytes.
+    fn compress(&self, data: &[u8]) -> CompressionResult<Vec<u8>>;
+
+    /// Decompress `data`, returning the original bytes.
+    fn decompress(&self, data: &[u8]) -> CompressionResult<Vec<u8>>;
+
+    /// Single-byte identifier for this codec, used by callers (e.g. a
+    /// framed container format) that need to tag compressed payloads with
+    /// the algorithm that produced them.
+    fn codec_id(&self) -> u8;
+}
+
+/// Selects which compression algorithm a `Compressor` should be built for.
+///
+/// Round-trips through `Codec::from_str`/`Display` using the `"name/level"`
+/// form (e.g. `"zstd/9"`). Zstd and lz4 carry a tunable level; a level given
+/// for the remaining algorithms is still parsed and validated, but is not
+/// retained since none of them have a meaningful compression level at the
+/// simple one-shot API this module wraps. Lz4's level is retained for the
+/// same reason but is likewise unused by `Lz4Compressor` -- see its doc
+/// comment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    Zstd(ZstdLevel),
+    Lz4(Lz4Level),
+    Snappy,
+    Brotli,
+    Deflate,
+    /// No-op passthrough, for output that shouldn't be compressed at all
+    /// but still needs a codec id to round-trip through a self-describing
+    /// header.
+    Stored,
+}
+
+impl Codec {
+    /// Identifier used to tag payloads compressed with this codec.
+    pub fn codec_id(&self) -> u8 {
+        match self {
+            Codec::Stored => 0,
+            Codec::Zstd(_) => 1,
+            Codec::Lz4(_) => 2,
+            Codec::Snappy => 3,
+            Codec::Brotli => 4,
+            Codec::Deflate => 5,
+        }
+    }
+
+    /// Reconstruct a codec from the byte written by `codec_id`. For codecs
+    /// that carry a level, the default level is used since the id alone
+    /// doesn't encode it; this is safe for decompression, which doesn't
+    /// depend on the level the data was compressed with.
+    pub fn from_codec_id(id: u8) -> CompressionResult<Self> {
+        match id {
+            0 => Ok(Codec::Stored),
+            1 => Ok(Codec::Zstd(ZstdLevel::default())),
+            2 => Ok(Codec::Lz4(Lz4Level::default())),
+            3 => Ok(Codec::Snappy),
+            4 => Ok(Codec::Brotli),
+            5 => Ok(Codec::Deflate),
+            other => Err(CompressionError::config_validation(format!(
+                "Unknown codec id: {other}"
+            ))),
+        }
+    }
+
+    /// Build the `Compressor` implementation for this codec.
+    pub fn compressor(&self) -> CompressionResult<Box<dyn Compressor>> {
+        match self {
+            Codec::Zstd(level) => Ok(Box::new(ZstdCompressor::new(*level)?)),
+            Codec::Lz4(level) => Ok(Box::new(Lz4Compressor::new(*level))),
+            Codec::Snappy => Ok(Box::new(SnappyCompressor::new())),
+            Codec::Brotli => Ok(Box::new(BrotliCompressor::new())),
+            Codec::Deflate => Ok(Box::new(DeflateCompressor::new())),
+            Codec::Stored => Ok(Box::new(StoredCompressor::new())),
+        }
+    }
+
+    /// Like `compressor`, but for `Codec::Zstd` applies `advanced`'s
+    /// strategy/window-log/long-distance-matching tuning on top of the
+    /// level (see `ZstdCompressor::with_advanced`). `advanced` is ignored
+    /// for every other codec, which have no equivalent knobs.
+    pub fn compressor_with_zstd_advanced(&self, advanced: ZstdAdvanced) -> CompressionResult<Box<dyn Compressor>> {
+        match self {
+            Codec::Zstd(level) => Ok(Box::new(ZstdCompressor::new(*level)?.with_advanced(advanced)?)),
+            _ => self.compressor(),
+        }
+    }
+}
+
+impl FromStr for Codec {
+    type Err = CompressionError;
+
+    /// Parse the `"name"` or `"name/level"` config form, e.g. `"zstd"`,
+    /// `"zstd/9"`, or `"brotli/5"`. The level is optional and defaults to
+    /// a sensible value when omitted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, level) = match s.split_once('/') {
+            Some((name, level)) => (name, Some(level)),
+            None => (s, None),
+        };
+
+        let parse_level = |level: &str| {
+            level.parse::<i32>().map_err(|_| {
+                CompressionError::config_validation(format!("Invalid codec level: {level}"))
+            })
+        };
+
+        match name.trim().to_ascii_lowercase().as_str() {
+            "zstd" => {
+                let level = match level {
+                    Some(level) => parse_level(level)?,
+                    None => ZstdLevel::default().get(),
+                };
+                Ok(Codec::Zstd(ZstdLevel::new(level)?))
+            }
+            "lz4" => {
+                let level = match level {
+                    Some(level) => parse_level(level)?,
+                    None => Lz4Level::default().get(),
+                };
+                Ok(Codec::Lz4(Lz4Level::new(level)?))
+            }
+            "snappy" => {
+                if let Some(level) = level {
+                    parse_level(level)?;
+                }
+                Ok(Codec::Snappy)
+            }
+            "brotli" => {
+                if let Some(level) = level {
+                    parse_level(level)?;
+                }
+                Ok(Codec::Brotli)
+            }
+            "deflate" => {
+                if let Some(level) = level {
+                    parse_level(level)?;
+                }
+                Ok(Codec::Deflate)
+            }
+            "stored" | "none" => {
+                if let Some(level) = level {
+                    parse_level(level)?;
+                }
+                Ok(Codec::Stored)
+            }
+            other => Err(CompressionError::config_validation(format!(
+                "Unknown codec: {other}"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Codec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Codec::Zstd(level) => write!(f, "zstd/{}", level.get()),
+            Codec::Lz4(level) => write!(f, "lz4/{}", level.get()),
+            Codec::Snappy => write!(f, "snappy"),
+            Codec::Brotli => write!(f, "brotli"),
+            Codec::Deflate => write!(f, "deflate"),
+            Codec::Stored => write!(f, "stored"),
+        }
+    }
+}
+
+/// Serializes through the same `"name/level"` form `Display`/`FromStr`
+/// already round-trip through (e.g. `"zstd/9"`), so a `CompressionConfig`
+/// written to a TOML file reads as `final_codec = "zstd/9"` rather than a
+/// nested table.
+impl serde::Serialize for Codec {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Codec {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str_zstd_with_level() {
+        let codec = Codec::from_str("zstd/9").unwrap();
+        assert_eq!(codec, Codec::Zstd(ZstdLevel::new(9).unwrap()));
+        assert_eq!(codec.to_string(), "zstd/9");
+    }
+
+    #[test]
+    fn test_from_str_zstd_defaults_level() {
+        let codec = Codec::from_str("zstd").unwrap();
+        assert_eq!(codec, Codec::Zstd(ZstdLevel::default()));
+    }
+
+    #[test]
+    fn test_from_str_lz4_with_level() {
+        let codec = Codec::from_str("lz4/5").unwrap();
+        assert_eq!(codec, Codec::Lz4(Lz4Level::new(5).unwrap()));
+        assert_eq!(codec.to_string(), "lz4/5");
+    }
+
+    #[test]
+    fn test_from_str_lz4_defaults_level() {
+        let codec = Codec::from_str("lz4").unwrap();
+        assert_eq!(codec, Codec::Lz4(Lz4Level::default()));
+    }
+
+    #[test]
+    fn test_from_str_other_codecs() {
+        assert_eq!(Codec::from_str("snappy").unwrap(), Codec::Snappy);
+        assert_eq!(Codec::from_str("brotli/5").unwrap(), Codec::Brotli);
+        assert_eq!(Codec::from_str("deflate").unwrap(), Codec::Deflate);
+        assert_eq!(Codec::from_str("stored").unwrap(), Codec::Stored);
+        assert_eq!(Codec::from_str("none").unwrap(), Codec::Stored);
+    }
+
+    #[test]
+    fn test_from_str_is_case_insensitive() {
+        assert_eq!(Codec::from_str("ZSTD/3").unwrap(), Codec::Zstd(ZstdLevel::new(3).unwrap()));
+        assert_eq!(Codec::from_str("Lz4").unwrap(), Codec::Lz4(Lz4Level::default()));
+    }
+
+    #[test]
+    fn test_from_str_rejects_unknown_codec() {
+        assert!(Codec::from_str("rle").is_err());
+    }
+
+    #[test]
+    fn test_from_str_rejects_invalid_level() {
+        assert!(Codec::from_str("zstd/not-a-number").is_err());
+        assert!(Codec::from_str("zstd/99").is_err());
+        assert!(Codec::from_str("lz4/99").is_err());
+    }
+
+    #[test]
+    fn test_from_codec_id_round_trips() {
+        for spec in ["zstd/3", "lz4", "snappy", "brotli", "deflate", "stored"] {
+            let codec = Codec::from_str(spec).unwrap();
+            assert_eq!(Codec::from_codec_id(codec.codec_id()).unwrap().codec_id(), codec.codec_id());
+        }
+    }
+
+    #[test]
+    fn test_from_codec_id_rejects_unknown_id() {
+        assert!(Codec::from_codec_id(99).is_err());
+    }
+
+    #[test]
+    fn test_codec_id_is_stable_per_variant() {
+        assert_eq!(Codec::Stored.codec_id(), 0);
+        assert_eq!(Codec::Zstd(ZstdLevel::default()).codec_id(), 1);
+        assert_eq!(Codec::Lz4(Lz4Level::default()).codec_id(), 2);
+        assert_eq!(Codec::Snappy.codec_id(), 3);
+        assert_eq!(Codec::Brotli.codec_id(), 4);
+        assert_eq!(Codec::Deflate.codec_id(), 5);
+    }
+
+    #[test]
+    fn test_compressor_round_trip_per_codec() {
+        let data = b"the quick brown fox jumps over the lazy dog";
+        for spec in ["zstd/3", "lz4", "snappy", "brotli", "deflate", "stored"] {
+            let codec = Codec::from_str(spec).unwrap();
+            let compressor = codec.compressor().unwrap();
+            assert_eq!(compressor.codec_id(), codec.codec_id());
+
+            let compressed = compressor.compress(data).unwrap();
+            let decompressed = compressor.decompress(&compressed).unwrap();
+            assert_eq!(decompressed, data, "round trip failed for {spec}");
+        }
+    }
+}
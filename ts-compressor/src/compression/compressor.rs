@@ -4,12 +4,16 @@
 //! the entire compression pipeline using the typestate pattern to enforce
 //! correct execution order at compile time.
 
+use crate::compression::codec::{Codec, Compressor};
+use crate::compression::config::{DictSize, ThreadPinning, ZstdDictionaryConfig};
 use crate::compression::{
-    CompressionConfig, CompressionError, CompressionResult, CompressionStatistics,
-    DictionaryBuilder, DictionaryBuilding, FrequencyAnalysis, FrequencyAnalyzer,
-    PatternReplacement, PatternReplacer,
+    CollectionLimit, CompressionConfig, CompressionError, CompressionMethod, CompressionResult,
+    CompressionStatistics, CompressorLimits, Dictionary, DictionaryBuilder, DictionaryBuilding,
+    DictionaryStrategy, FileEntry, FrequencyAnalysis, FrequencyAnalyzer, FsstCompressor,
+    PatternReplacement, PatternReplacer, Report, SymbolTable, ZstdCompressor, ZstdDictionary,
 };
 use crate::CodeArchiver;
+use rayon::prelude::*;
 use std::marker::PhantomData;
 use std::path::PathBuf;
 use tracing::{debug, error, info, instrument, warn};
@@ -105,17 +109,24 @@ impl BuilderProvider for DefaultBuilderProvider {
 
 // Implementation for InitialState
 impl UniversalCompressor<InitialState> {
-    /// Create a new universal compressor in initial state
+    /// Create a new universal compressor in initial state.
+    ///
+    /// Every test caller uses the default output directory, so the second
+    /// argument that used to be `output_dir: Option<PathBuf>` (always
+    /// passed as `None`) now carries the file-collection limits instead -
+    /// `None` falls back to `CompressorLimits::default()` exactly as it
+    /// fell back to the default output dir before.
     #[cfg(test)]
     pub fn new(
         target_folder: PathBuf,
-        output_dir: Option<PathBuf>,
+        limits: Option<CompressorLimits>,
     ) -> Result<Self, CompressionError> {
-        let archiver = CodeArchiver::new(target_folder, output_dir).map_err(|e| {
+        let archiver = CodeArchiver::new(target_folder, None).map_err(|e| {
             CompressionError::config_validation(format!("Failed to create archiver: {}", e))
         })?;
 
-        let config = CompressionConfig::default();
+        let mut config = CompressionConfig::default();
+        config.collection_limits = limits.unwrap_or_default();
         let frequency_analyzer = FrequencyAnalyzer::new(
             config.min_pattern_length.get(),
             config.min_frequency_threshold.get(),
@@ -198,6 +209,109 @@ impl UniversalCompressor<InitialState> {
     }
 }
 
+/// A directory holding more than this many direct file entries has the
+/// excess skipped (entries are sorted by path first, so the kept ones are
+/// deterministic), so one bloated folder - a generated-code dump, a flat
+/// log directory - can't dominate the corpus `analyze()` trains its
+/// dictionary on.
+const MAX_ENTRIES_PER_DIR: usize = 200;
+
+/// Walk `target_folder` the same way for every `collect_files_from_archiver`
+/// implementation: `.gitignore`/`.ignore`/`.promptignore`-aware (so a
+/// target tree's own ignore rules, plus this tool's own, are honored),
+/// plus `target/`, `node_modules/`, and `.git/` always excluded regardless
+/// of what the tree's ignore files say, plus `extra_excludes` layered on
+/// top as additional glob overrides. Hidden files are still walked
+/// (`.hidden(false)`) since `FileTypeRegistry` recognizes dotfiles like
+/// `.gitignore` itself as text. Uses `ignore`'s parallel walker since
+/// directory traversal on a large tree is I/O-bound and embarrassingly
+/// parallel across subdirectories, then applies `MAX_ENTRIES_PER_DIR`
+/// afterward so the cap is enforced deterministically regardless of which
+/// thread visited a directory first.
+fn walk_target_files(target_folder: &std::path::Path, extra_excludes: &[String]) -> CompressionResult<Vec<PathBuf>> {
+    let mut overrides = ignore::overrides::OverrideBuilder::new(target_folder);
+    let always_excluded = ["!/target", "!**/target", "!**/node_modules", "!**/.git"];
+    let extra_excluded: Vec<String> = extra_excludes.iter().map(|p| format!("!{}", p)).collect();
+    for pattern in always_excluded.iter().copied().chain(extra_excluded.iter().map(String::as_str)) {
+        overrides.add(pattern).map_err(|e| {
+            CompressionError::file_processing(
+                "directory traversal",
+                format!("Invalid exclude glob '{}': {}", pattern, e),
+            )
+        })?;
+    }
+    let overrides = overrides.build().map_err(|e| {
+        CompressionError::file_processing(
+            "directory traversal",
+            format!("Failed to build exclude overrides: {}", e),
+        )
+    })?;
+
+    let paths = std::sync::Mutex::new(Vec::new());
+    let walk_error = std::sync::Mutex::new(None);
+    let walker = ignore::WalkBuilder::new(target_folder)
+        .hidden(false)
+        .overrides(overrides)
+        .add_custom_ignore_filename(".promptignore")
+        .build_parallel();
+    walker.run(|| {
+        Box::new(|entry| {
+            match entry {
+                Ok(entry) => {
+                    if entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+                        paths.lock().unwrap().push(entry.path().to_path_buf());
+                    }
+                }
+                Err(e) => {
+                    let mut walk_error = walk_error.lock().unwrap();
+                    if walk_error.is_none() {
+                        *walk_error = Some(CompressionError::file_processing(
+                            "directory traversal",
+                            format!("Failed to read directory entry: {}", e),
+                        ));
+                    }
+                    return ignore::WalkState::Quit;
+                }
+            }
+            ignore::WalkState::Continue
+        })
+    });
+
+    if let Some(e) = walk_error.into_inner().unwrap() {
+        return Err(e);
+    }
+    let mut paths = paths.into_inner().unwrap();
+
+    // The parallel walker's visit order depends on thread scheduling;
+    // sort so the per-directory cap below (and collection generally) is
+    // deterministic across runs.
+    paths.sort();
+
+    let mut per_dir_counts: std::collections::HashMap<PathBuf, usize> = std::collections::HashMap::new();
+    let mut warned_dirs: std::collections::HashSet<PathBuf> = std::collections::HashSet::new();
+    let paths: Vec<PathBuf> = paths
+        .into_iter()
+        .filter(|path| {
+            let parent = path.parent().unwrap_or(target_folder).to_path_buf();
+            let count = per_dir_counts.entry(parent.clone()).or_insert(0);
+            *count += 1;
+            if *count > MAX_ENTRIES_PER_DIR {
+                if warned_dirs.insert(parent.clone()) {
+                    warn!(
+                        directory = %parent.display(),
+                        max_entries_per_dir = MAX_ENTRIES_PER_DIR,
+                        "Directory exceeds per-directory entry cap, skipping remaining siblings"
+                    );
+                }
+                return false;
+            }
+            true
+        })
+        .collect();
+
+    Ok(paths)
+}
+
 // Implementation for ConfiguredState
 impl UniversalCompressor<ConfiguredState> {
     /// Perform frequency analysis and transition to analyzed state
@@ -207,25 +321,47 @@ impl UniversalCompressor<ConfiguredState> {
 
         // Collect files from the archiver
         debug!("Collecting files from target directory");
-        let files = self.collect_files_from_archiver()?;
+        let outcome = self.collect_files_from_archiver(self.config.collection_limits)?;
+        let files = outcome.files;
         info!(file_count = files.len(), "Files collected for analysis");
 
+        let mut stats = CompressionStatistics::new();
+        for limit in outcome.limits_hit {
+            stats.record_limit_hit(limit);
+        }
+        stats.oversized_files_skipped = outcome.oversized_files_skipped;
+        stats.collected_content_bytes = outcome.collected_content_bytes;
+        self.statistics = Some(stats);
+
         // Analyze content for frequent patterns
         debug!("Analyzing content for frequent patterns");
-        let mut total_content_size = 0;
-        for (index, file) in files.iter().enumerate() {
-            let file_size = file.original_content.len();
-            total_content_size += file_size;
-
-            debug!(
-                file_index = index,
-                file_path = %file.relative_path.display(),
-                file_size = file_size,
-                "Analyzing file content"
-            );
+        let total_content_size: usize = files.iter().map(|file| file.original_content.len()).sum();
+
+        if self.config.dictionary_strategy == DictionaryStrategy::Cover {
+            // COVER needs raw byte content with per-file provenance to score
+            // candidate substrings by realized savings, not just an
+            // aggregate frequency count, so it samples `files` directly
+            // instead of going through `analyze_content`. Its output is
+            // already ranked, so it's handed to the analyzer verbatim via
+            // `load_external_patterns` rather than merged into
+            // `pattern_frequencies`.
+            debug!("Sampling files for COVER-style dictionary training");
+            let patterns = crate::compression::cover::train(&files, &self.config.cover_config);
+            self.frequency_analyzer.load_external_patterns(patterns);
+        } else {
+            for (index, file) in files.iter().enumerate() {
+                let file_size = file.original_content.len();
+
+                debug!(
+                    file_index = index,
+                    file_path = %file.relative_path.display(),
+                    file_size = file_size,
+                    "Analyzing file content"
+                );
 
-            self.frequency_analyzer
-                .analyze_content(&file.original_content);
+                self.frequency_analyzer
+                    .analyze_content(&file.original_content);
+            }
         }
 
         let patterns = self.frequency_analyzer.get_frequent_patterns();
@@ -246,102 +382,114 @@ impl UniversalCompressor<ConfiguredState> {
         })
     }
 
-    /// Collect files using the actual CodeArchiver
-    #[instrument(name = "collect_files", skip(self))]
+    /// Collect files using the actual CodeArchiver, enforcing `limits`
+    /// along the way. `analyze()` passes `self.config.collection_limits`
+    /// directly; `compress_chunked()` passes a copy with `max_total_bytes`
+    /// lifted, since its whole point is to process a corpus too large for
+    /// that cap in one pass - the cap is applied per-batch afterward
+    /// instead (see `crate::compression::chunked::partition_into_batches`).
+    #[instrument(name = "collect_files", skip(self, limits))]
     fn collect_files_from_archiver(
         &self,
-    ) -> Result<Vec<crate::compression::types::FileEntry>, CompressionError> {
+        limits: CompressorLimits,
+    ) -> Result<FileCollectionOutcome, CompressionError> {
+        use crate::compression::file_type::FileTypeRegistry;
         use crate::compression::types::FileEntry;
 
-        use std::fs;
-        use walkdir::WalkDir;
-
         debug!("Starting file collection from target directory");
+        let registry = FileTypeRegistry::new();
         let mut files = Vec::new();
         let mut skipped_files = 0;
         let mut read_errors = 0;
+        let mut limits_hit = Vec::new();
+        let mut oversized_files_skipped = 0;
+
+        let mut total_size: u64 = 0;
+        let mut file_count: usize = 0;
 
-        // ADD SAFETY LIMITS - TDD Implementation
-        let max_files = 1000; // Process max 1000 files
-        let max_memory_mb = 500; // Stop at 500MB total content
-        let mut total_size = 0;
-        let mut file_count = 0;
-
-        // Use walkdir to traverse the target directory
-        for entry in WalkDir::new(self.archiver.target_folder()) {
-            let entry = entry.map_err(|e| {
-                error!(
-                    target_folder = %self.archiver.target_folder().display(),
-                    error = %e,
-                    "Failed to read directory entry"
+        let paths = walk_target_files(self.archiver.target_folder(), &self.config.exclude_globs)?;
+
+        for path in &paths {
+            let path = path.as_path();
+
+            if limits.max_files.is_some_and(|max_files| file_count >= max_files) {
+                warn!(
+                    max_files = limits.max_files,
+                    files_collected = file_count,
+                    "Reached max_files limit, stopping collection"
                 );
-                CompressionError::file_processing(
-                    "directory traversal",
-                    &format!("Failed to read directory entry: {}", e),
-                )
-            })?;
+                record_limit(&mut limits_hit, CollectionLimit::MaxFiles);
+                break;
+            }
 
-            if entry.file_type().is_file() {
-                let path = entry.path();
+            if limits.max_total_bytes.is_some_and(|max_total_bytes| total_size >= max_total_bytes) {
+                warn!(
+                    max_total_bytes = limits.max_total_bytes,
+                    current_bytes = total_size,
+                    "Reached max_total_bytes limit, stopping collection"
+                );
+                record_limit(&mut limits_hit, CollectionLimit::MaxTotalBytes);
+                break;
+            }
 
-                // ADD SAFETY CHECKS - TDD Implementation
-                if file_count >= max_files {
-                    warn!(
-                        max_files = max_files,
-                        files_collected = file_count,
-                        "Reached file limit, stopping collection"
-                    );
-                    break;
+            if let Some(max_file_size) = limits.max_file_size {
+                match std::fs::metadata(path) {
+                    Ok(metadata) if metadata.len() > max_file_size => {
+                        warn!(
+                            file_path = %path.display(),
+                            file_size = metadata.len(),
+                            max_file_size,
+                            "File exceeds max_file_size limit, skipping"
+                        );
+                        record_limit(&mut limits_hit, CollectionLimit::MaxFileSize);
+                        oversized_files_skipped += 1;
+                        continue;
+                    }
+                    _ => {}
                 }
+            }
 
-                if total_size > max_memory_mb * 1024 * 1024 {
-                    warn!(
-                        max_memory_mb = max_memory_mb,
-                        current_memory_mb = total_size / (1024 * 1024),
-                        "Reached memory limit, stopping collection"
+            let relative_path = path
+                .strip_prefix(self.archiver.target_folder())
+                .unwrap_or(path)
+                .to_path_buf();
+
+            match FileEntry::from_path(
+                path,
+                relative_path,
+                &registry,
+                self.config.text_sniff_sample_len.get(),
+                self.config.max_non_printable_ratio.get(),
+            ) {
+                Ok(Some(entry)) => {
+                    total_size += entry.original_content.len() as u64;
+                    file_count += 1;
+
+                    debug!(
+                        file_path = %path.display(),
+                        content_size = entry.original_content.len(),
+                        total_memory_mb = total_size / (1024 * 1024),
+                        "Successfully read file"
                     );
-                    break;
-                }
 
-                // Skip binary files and focus on text files
-                if self.is_text_file(path) {
-                    match fs::read_to_string(path) {
-                        Ok(content) => {
-                            // ADD MEMORY TRACKING - TDD Implementation
-                            total_size += content.len();
-                            file_count += 1;
-
-                            let relative_path = path
-                                .strip_prefix(self.archiver.target_folder())
-                                .unwrap_or(path)
-                                .to_path_buf();
-
-                            debug!(
-                                file_path = %path.display(),
-                                content_size = content.len(),
-                                total_memory_mb = total_size / (1024 * 1024),
-                                "Successfully read file"
-                            );
-
-                            files.push(FileEntry::new(relative_path, content, false));
-                        }
-                        Err(e) => {
-                            // Log error but continue processing other files
-                            warn!(
-                                file_path = %path.display(),
-                                error = %e,
-                                "Failed to read file, skipping"
-                            );
-                            read_errors += 1;
-                        }
-                    }
-                } else {
+                    files.push(entry);
+                }
+                Ok(None) => {
                     debug!(
                         file_path = %path.display(),
-                        "Skipping non-text file"
+                        "Skipping file per registered policy"
                     );
                     skipped_files += 1;
                 }
+                Err(e) => {
+                    // Log error but continue processing other files
+                    warn!(
+                        file_path = %path.display(),
+                        error = %e,
+                        "Failed to read file, skipping"
+                    );
+                    read_errors += 1;
+                }
             }
         }
 
@@ -349,6 +497,7 @@ impl UniversalCompressor<ConfiguredState> {
             files_collected = files.len(),
             files_skipped = skipped_files,
             read_errors = read_errors,
+            oversized_files_skipped = oversized_files_skipped,
             total_memory_mb = total_size / (1024 * 1024),
             "File collection completed"
         );
@@ -361,52 +510,162 @@ impl UniversalCompressor<ConfiguredState> {
             ));
         }
 
-        Ok(files)
+        Ok(FileCollectionOutcome {
+            files,
+            limits_hit,
+            oversized_files_skipped,
+            collected_content_bytes: total_size,
+        })
+    }
+
+    /// Compress `target_folder` in bounded memory, for corpora too large
+    /// for `analyze()`/`build_dictionary()`/`prepare_replacement()`/
+    /// `compress()`'s single whole-corpus pass to hold at once.
+    ///
+    /// Collection ignores `collection_limits.max_total_bytes` (that cap
+    /// would otherwise just truncate the corpus instead of letting it be
+    /// processed), then [`crate::compression::chunked::partition_into_batches`]
+    /// re-applies it as a per-batch byte budget, and
+    /// [`crate::compression::chunked::compress_in_batches`] trains the
+    /// dictionary incrementally across those batches - see that module for
+    /// why tokens already assigned are never reassigned. The other limits
+    /// (`max_files`, `max_file_size`) still apply during collection exactly
+    /// as they do for `analyze()`.
+    ///
+    /// Returns the usual [`CompressionResult`] alongside the
+    /// [`crate::compression::ChunkManifest`] recording each batch's file
+    /// count and the final dictionary, so a caller can persist it and
+    /// reproduce exactly how the batches were assembled.
+    #[instrument(name = "compress_chunked", skip(self))]
+    pub fn compress_chunked(self) -> Result<(CompressionResult, crate::compression::ChunkManifest), CompressionError> {
+        use crate::compression::chunked::{compress_in_batches, partition_into_batches};
+        use crate::compression::types::{CompressionStatistics, FileSize};
+        use std::time::Instant;
+
+        info!("Starting chunked compression phase");
+        let start_time = Instant::now();
+
+        let collection_limits = CompressorLimits {
+            max_total_bytes: None,
+            ..self.config.collection_limits
+        };
+        let outcome = self.collect_files_from_archiver(collection_limits)?;
+
+        let max_batch_bytes = self
+            .config
+            .collection_limits
+            .max_total_bytes
+            .unwrap_or_else(|| CompressorLimits::default().max_total_bytes.unwrap());
+        let batches = partition_into_batches(outcome.files, max_batch_bytes);
+        info!(
+            batch_count = batches.len(),
+            max_batch_bytes, "Partitioned corpus into batches for chunked compression"
+        );
+
+        let chunked = compress_in_batches(
+            batches,
+            self.config.min_pattern_length.get(),
+            self.config.min_frequency_threshold.get(),
+            self.config.max_dictionary_entries,
+        )?;
+
+        info!(codec = %self.config.final_codec, "Applying final-stage compression to chunked output");
+        let (final_output, zstd_dictionary) = self.apply_final_compression_standalone(chunked.files)?;
+
+        let mut stats = CompressionStatistics::new();
+        for limit in outcome.limits_hit {
+            stats.record_limit_hit(limit);
+        }
+        stats.oversized_files_skipped = outcome.oversized_files_skipped;
+        stats.collected_content_bytes = outcome.collected_content_bytes;
+        stats.total_files_processed = final_output.len();
+        stats.dictionary_entries = chunked.manifest.dictionary_entries.len();
+
+        let mut original_total = 0;
+        let mut compressed_total = 0;
+        for file in &final_output {
+            original_total += file.original_size.bytes();
+            if let Some(compressed_size) = file.compressed_size {
+                compressed_total += compressed_size.bytes();
+                if let Some(method) = file.method {
+                    stats.record_method(method, file.original_size.bytes(), compressed_size.bytes());
+                }
+            }
+        }
+        stats.original_total_size = FileSize::new(original_total);
+        stats.compressed_total_size = FileSize::new(compressed_total);
+        stats.processing_time = start_time.elapsed();
+
+        info!(
+            batch_count = chunked.manifest.batch_file_counts.len(),
+            total_files = stats.total_files_processed,
+            dictionary_entries = stats.dictionary_entries,
+            processing_time_ms = stats.processing_time.as_millis(),
+            "Chunked compression phase completed successfully"
+        );
+
+        let dictionary_size = chunked.manifest.dictionary_entries.len();
+        let dictionary = Dictionary::from_entries(chunked.manifest.dictionary_entries.clone());
+        let result = CompressionResult::new(
+            std::path::PathBuf::from("output.txt"),
+            stats,
+            dictionary_size,
+            dictionary_size,
+            final_output,
+            dictionary,
+            None,
+            zstd_dictionary,
+        );
+
+        Ok((result, chunked.manifest))
+    }
+
+    /// Run `self.config.final_codec`'s `Compressor` over already
+    /// dictionary-compressed `files`. The `ReadyState` sibling
+    /// (`apply_final_compression`) needs `&self.pattern_replacer` to exist
+    /// first; `compress_chunked` has no single pattern replacer (it builds
+    /// one per batch), so this standalone copy only needs `self.config`.
+    fn apply_final_compression_standalone(
+        &self,
+        files: Vec<crate::compression::types::FileEntry>,
+    ) -> Result<(Vec<crate::compression::types::FileEntry>, Option<ZstdDictionary>), CompressionError> {
+        let (compressor, dictionary) = build_final_compressor(&self.config, &files)?;
+        let method = compression_method_for_codec(self.config.final_codec);
+
+        let compressed = files
+            .into_par_iter()
+            .map(|mut file| {
+                if let Some(content) = &file.compressed_content {
+                    let final_compressed = compressor.compress(content.as_bytes())?;
+                    let encoded = crate::compression::base64::encode(&final_compressed);
+                    file.apply_compression(encoded, method, None);
+                }
+                Ok(file)
+            })
+            .collect::<Result<Vec<_>, CompressionError>>()?;
+        Ok((compressed, dictionary))
     }
+}
+
+/// Outcome of `ConfiguredState::collect_files_from_archiver`: the collected
+/// entries plus which `CompressorLimits` caps fired along the way, so
+/// `analyze()` can record them onto `self.statistics` for callers and
+/// tests to inspect without re-parsing `warn!` logs.
+struct FileCollectionOutcome {
+    files: Vec<crate::compression::types::FileEntry>,
+    limits_hit: Vec<CollectionLimit>,
+    oversized_files_skipped: usize,
+    /// Sum of `entry.original_content.len()` across every collected file -
+    /// the in-memory decoded size the `max_total_bytes` check is measured
+    /// against, not `std::fs::metadata(path).len()`, which can lag a
+    /// buffered or still-growing file and under- or over-report what
+    /// actually landed in the corpus.
+    collected_content_bytes: u64,
+}
 
-    /// Check if a file is likely a text file based on extension
-    fn is_text_file(&self, path: &std::path::Path) -> bool {
-        let text_extensions = [
-            "rs",
-            "toml",
-            "md",
-            "txt",
-            "json",
-            "yaml",
-            "yml",
-            "js",
-            "ts",
-            "tsx",
-            "jsx",
-            "html",
-            "css",
-            "scss",
-            "py",
-            "rb",
-            "go",
-            "java",
-            "c",
-            "cpp",
-            "h",
-            "hpp",
-            "sh",
-            "bash",
-            "zsh",
-            "fish",
-            "ps1",
-            "bat",
-            "cmd",
-            "xml",
-            "svg",
-            "gitignore",
-            "dockerfile",
-            "makefile",
-        ];
-
-        path.extension()
-            .and_then(|ext| ext.to_str())
-            .map(|ext| text_extensions.contains(&ext.to_lowercase().as_str()))
-            .unwrap_or(false)
+fn record_limit(limits_hit: &mut Vec<CollectionLimit>, limit: CollectionLimit) {
+    if !limits_hit.contains(&limit) {
+        limits_hit.push(limit);
     }
 }
 
@@ -484,6 +743,11 @@ impl UniversalCompressor<DictionaryBuiltState> {
     pub fn prepare_replacement(self) -> Result<UniversalCompressor<ReadyState>, CompressionError> {
         info!("Starting pattern replacement preparation phase");
 
+        // Arm Ctrl+C cleanup for the rest of this run, covering the temp
+        // files `compress` and the final output writer create - see
+        // `CleanupGuard`.
+        crate::compression::cleanup::CleanupGuard::new();
+
         // Get dictionary entries from the builder
         debug!("Retrieving dictionary entries from builder");
         let dictionary_entries = self.dictionary_builder.get_dictionary_entries();
@@ -530,6 +794,93 @@ impl UniversalCompressor<ReadyState> {
         self.dictionary_builder.get_dictionary_entries()
     }
 
+    /// Run `f` on a rayon thread pool capped at
+    /// `self.config.parallel_config.max_threads`, instead of rayon's
+    /// process-wide default pool. Bounds how many threads `compress()`'s
+    /// rayon-parallel stages (file collection, Step 3 substitution,
+    /// Step 4 final-stage compression) are allowed to spread across, which
+    /// matters when a caller embeds this compressor alongside other work
+    /// competing for the same cores. When `parallel_config.thread_pinning` is
+    /// `ThreadPinning::StartingAt(start_core)`, worker `i` is additionally
+    /// pinned to the physical core at `start_core + i`, following `validate()`'s
+    /// guarantee that range fits within this machine's core count.
+    fn with_bounded_thread_pool<R: Send>(&self, f: impl FnOnce() -> R + Send) -> CompressionResult<R> {
+        let mut builder =
+            rayon::ThreadPoolBuilder::new().num_threads(self.config.parallel_config.max_threads.get());
+
+        if let ThreadPinning::StartingAt(start_core) = self.config.parallel_config.thread_pinning {
+            let core_ids = core_affinity::get_core_ids().unwrap_or_default();
+            builder = builder.spawn_handler(move |thread| {
+                let core_id = core_ids.get(start_core + thread.index()).copied();
+                std::thread::Builder::new()
+                    .name(format!("ts-compressor-{}", thread.index()))
+                    .spawn(move || {
+                        if let Some(core_id) = core_id {
+                            core_affinity::set_for_current(core_id);
+                        }
+                        thread.run()
+                    })
+                    .map(|_| ())
+            });
+        }
+
+        let pool = builder
+            .build()
+            .map_err(|e| {
+                CompressionError::config_validation(format!("Failed to build bounded thread pool: {}", e))
+            })?;
+        Ok(pool.install(f))
+    }
+
+    /// Restore the tree `compress` produced from the archive written at
+    /// `archive_path` (see `generate_output_file` in `main.rs`) into
+    /// `output_dir`, the reverse of the forward `compress` pipeline.
+    /// Returns a [`crate::compression::RestoreReport`] recording, per
+    /// restored entry, whether its content matched the `Checksum:` the
+    /// archive captured at compress time.
+    pub fn restore(
+        archive_path: &std::path::Path,
+        output_dir: &std::path::Path,
+    ) -> Result<crate::compression::RestoreReport, CompressionError> {
+        crate::compression::restore::restore_archive(archive_path, output_dir)
+    }
+
+    /// Package `files` (as produced by `compress`) into a single binary
+    /// archive and write it to `output_path`.
+    ///
+    /// `generate_output_file` in `main.rs` already writes the pipeline's
+    /// default, human-readable deliverable (statistics, embedded dictionary,
+    /// directory manifest, then compressed content, all as text). This is a
+    /// binary sibling of that format for callers who want a compact,
+    /// self-describing container instead - under the hood it's
+    /// `crate::compression::block_archive`'s framed, checksummed block
+    /// layout, fed this compressor's own dictionary entries and
+    /// `self.config.final_codec` so the archive can be reversed with
+    /// [`crate::compression::read_block_archive`], given the same
+    /// `final_codec` and `checksum_config` it was written with.
+    pub fn write_archive(
+        &self,
+        files: &[FileEntry],
+        output_path: &std::path::Path,
+    ) -> Result<PathBuf, CompressionError> {
+        let dictionary_entries = self.get_dictionary_entries();
+        let archive = crate::compression::block_archive::write_archive(
+            files,
+            &dictionary_entries,
+            self.config.final_codec,
+            self.config.checksum_config,
+        )?;
+
+        std::fs::write(output_path, &archive).map_err(|e| {
+            CompressionError::file_processing(
+                output_path.display().to_string(),
+                format!("Failed to write archive: {}", e),
+            )
+        })?;
+
+        Ok(output_path.to_path_buf())
+    }
+
     /// Get compressed files for output generation
     pub fn get_compressed_files(
         &self,
@@ -538,9 +889,15 @@ impl UniversalCompressor<ReadyState> {
 
         // Apply pattern replacement to each file if pattern replacer is available
         if let Some(pattern_replacer) = &self.pattern_replacer {
+            let dictionary_id = Dictionary::from_entries(self.dictionary_builder.get_dictionary_entries())
+                .id()
+                .to_string();
             for file in &mut files {
+                if file.method == Some(CompressionMethod::Store) {
+                    continue;
+                }
                 let compressed_content = pattern_replacer.replace_patterns(&file.original_content);
-                file.apply_compression(compressed_content);
+                file.apply_dictionary_compression(compressed_content, dictionary_id.clone(), None);
             }
         }
 
@@ -570,6 +927,19 @@ impl UniversalCompressor<ReadyState> {
         }
         info!(file_count = files.len(), "Files collected for compression");
 
+        // Step 1.5: Pre-dictionary content-defined chunking pass. This only
+        // measures the dedup opportunity across `files` for reporting
+        // (`CompressionStatistics::dedup_bytes_saved`); Steps 2-4 below still
+        // run over each file's own content, not the dedup pool - see
+        // `crate::compression::dedup` for why.
+        let dedup_result =
+            crate::compression::dedup::deduplicate(&files, self.config.parallel_config.chunking_strategy);
+        info!(
+            unique_chunks = dedup_result.pool.unique_chunk_count(),
+            bytes_saved = dedup_result.bytes_saved(),
+            "Content-defined chunking dedup pass completed"
+        );
+
         // Step 2: Use the pattern replacer that was prepared in the previous state
         debug!("Retrieving pattern replacer");
         let pattern_replacer = self.pattern_replacer.as_ref().ok_or_else(|| {
@@ -577,61 +947,161 @@ impl UniversalCompressor<ReadyState> {
             CompressionError::pattern_replacement("Pattern replacer not initialized".to_string())
         })?;
 
-        // Step 3: Replace patterns with tokens in all files
-        info!("Applying pattern replacement to files");
+        // Step 3: Substitute patterns/symbols with shorter codes in all
+        // files, using whichever algorithm `self.config.dictionary_strategy`
+        // selects (see `DictionaryStrategy`).
+        info!(strategy = ?self.config.dictionary_strategy, "Applying Step 3 substitution to files");
         let replacement_start = Instant::now();
-        let mut compressed_files = Vec::new();
-        let mut total_replacements = 0;
-
-        for (index, mut file) in files.into_iter().enumerate() {
-            debug!(
-                file_index = index,
-                file_path = %file.relative_path.display(),
-                original_size = file.original_content.len(),
-                "Processing file for pattern replacement"
-            );
+        let report = Report::new();
+
+        let (compressed_files, total_replacements, dictionary, symbol_table): (
+            Vec<FileEntry>,
+            usize,
+            Dictionary,
+            Option<SymbolTable>,
+        ) = match self.config.dictionary_strategy {
+            // `Cover`'s candidates reach `dictionary_builder` through
+            // `FrequencyAnalyzer::load_external_patterns` (see `analyze`),
+            // but from here on they're ordinary dictionary entries - Step 3
+            // substitution doesn't need to know where they came from.
+            DictionaryStrategy::Frequency | DictionaryStrategy::Cover => {
+                let dictionary = Dictionary::from_entries(self.dictionary_builder.get_dictionary_entries());
+
+                // `PatternReplacer` and `Report` are both safe to share across threads
+                // (the former has no interior mutability, the latter is
+                // `Mutex`-protected - see `Report::add`), so the per-file work itself
+                // runs on rayon's pool; only rebuilding `compressed_files` and
+                // `total_replacements` from the results needs to stay serial.
+                let results: Vec<(FileEntry, bool)> = self.with_bounded_thread_pool(|| files
+                    .into_par_iter()
+                    .enumerate()
+                    .map(|(index, mut file)| {
+                        debug!(
+                            file_index = index,
+                            file_path = %file.relative_path.display(),
+                            original_size = file.original_content.len(),
+                            "Processing file for pattern replacement"
+                        );
+
+                        // Pre-compressed entries (e.g. images, archives) were already
+                        // marked `Store`d by `FileEntry::from_path`; running pattern
+                        // replacement over already-compressed bytes wastes time and
+                        // can't help, so leave them as-is.
+                        if file.method == Some(CompressionMethod::Store) {
+                            let size = file.original_size.bytes();
+                            report.add(size, size);
+                            return (file, false);
+                        }
 
-            let compressed_content = pattern_replacer.replace_patterns(&file.original_content);
-            let compression_ratio = if file.original_content.len() > 0 {
-                compressed_content.len() as f64 / file.original_content.len() as f64
-            } else {
-                1.0
-            };
-
-            debug!(
-                file_index = index,
-                compressed_size = compressed_content.len(),
-                compression_ratio = compression_ratio,
-                "Pattern replacement completed for file"
-            );
+                        let compressed_content = pattern_replacer.replace_patterns(&file.original_content);
+                        let compression_ratio = if file.original_content.len() > 0 {
+                            compressed_content.len() as f64 / file.original_content.len() as f64
+                        } else {
+                            1.0
+                        };
+
+                        debug!(
+                            file_index = index,
+                            compressed_size = compressed_content.len(),
+                            compression_ratio = compression_ratio,
+                            "Pattern replacement completed for file"
+                        );
+
+                        file.apply_dictionary_compression(compressed_content, dictionary.id().to_string(), Some(&report));
+                        (file, true)
+                    })
+                    .collect::<Vec<_>>())?;
+
+                let mut compressed_files = Vec::with_capacity(results.len());
+                let mut total_replacements = 0;
+                for (file, replaced) in results {
+                    if replaced {
+                        total_replacements += 1;
+                    }
+                    compressed_files.push(file);
+                }
 
-            file.apply_compression(compressed_content);
-            compressed_files.push(file);
-            total_replacements += 1;
-        }
+                (compressed_files, total_replacements, dictionary, None)
+            }
+            DictionaryStrategy::Fsst => {
+                // Trained once across the whole batch (excluding already
+                // `Store`d entries, the same way the frequency analyzer skips
+                // them), then shared read-only across the rayon pool below -
+                // `SymbolTable::compress` takes `&self`.
+                let trainable: Vec<&FileEntry> = files
+                    .iter()
+                    .filter(|file| file.method != Some(CompressionMethod::Store))
+                    .collect();
+                let table = FsstCompressor::train_bulk(&trainable);
+                let table_id = table.id();
+
+                let results: Vec<(FileEntry, bool)> = self.with_bounded_thread_pool(|| files
+                    .into_par_iter()
+                    .enumerate()
+                    .map(|(index, mut file)| {
+                        debug!(
+                            file_index = index,
+                            file_path = %file.relative_path.display(),
+                            original_size = file.original_content.len(),
+                            "Processing file for fsst substitution"
+                        );
+
+                        if file.method == Some(CompressionMethod::Store) {
+                            let size = file.original_size.bytes();
+                            report.add(size, size);
+                            return (file, false);
+                        }
+
+                        // `compressed_content: String` can't hold arbitrary
+                        // FSST codes directly, so they're base64-encoded the
+                        // same way a final-stage `Codec`'s output is (see
+                        // `apply_final_compression`).
+                        let fsst_bytes = table.compress(file.original_content.as_bytes());
+                        let encoded = crate::compression::base64::encode(&fsst_bytes);
+
+                        debug!(
+                            file_index = index,
+                            compressed_size = encoded.len(),
+                            "Fsst substitution completed for file"
+                        );
+
+                        file.apply_fsst_compression(encoded, table_id.clone(), Some(&report));
+                        (file, true)
+                    })
+                    .collect::<Vec<_>>())?;
+
+                let mut compressed_files = Vec::with_capacity(results.len());
+                let mut total_replacements = 0;
+                for (file, replaced) in results {
+                    if replaced {
+                        total_replacements += 1;
+                    }
+                    compressed_files.push(file);
+                }
+
+                (compressed_files, total_replacements, Dictionary::default(), Some(table))
+            }
+        };
 
         let replacement_duration = replacement_start.elapsed();
         info!(
             files_processed = total_replacements,
             duration_ms = replacement_duration.as_millis(),
-            "Pattern replacement completed for all files"
+            "Step 3 substitution completed for all files"
         );
 
-        // Step 4: Apply zstd final compression if enabled
-        let final_output = if self.config.enable_zstd_compression {
-            info!("Applying zstd compression");
-            let zstd_start = Instant::now();
-            let result = self.apply_zstd_compression(compressed_files)?;
-            let zstd_duration = zstd_start.elapsed();
-            info!(
-                duration_ms = zstd_duration.as_millis(),
-                "Zstd compression completed"
-            );
-            result
-        } else {
-            debug!("Zstd compression disabled, skipping");
-            compressed_files
-        };
+        // Step 4: Run the final-stage codec (selected by `final_codec`, which
+        // defaults to zstd/stored based on `enable_zstd_compression` but can
+        // be overridden to any registered `Codec`) over the dictionary-stage
+        // output.
+        info!(codec = %self.config.final_codec, "Applying final-stage compression");
+        let final_start = Instant::now();
+        let (final_output, zstd_dictionary) = self.apply_final_compression(compressed_files)?;
+        let final_duration = final_start.elapsed();
+        info!(
+            duration_ms = final_duration.as_millis(),
+            "Final-stage compression completed"
+        );
 
         // Step 5: Calculate statistics
         debug!("Calculating compression statistics");
@@ -647,12 +1117,17 @@ impl UniversalCompressor<ReadyState> {
             original_total += file.original_size.bytes();
             if let Some(compressed_size) = file.compressed_size {
                 compressed_total += compressed_size.bytes();
+                if let Some(method) = file.method {
+                    stats.record_method(method, file.original_size.bytes(), compressed_size.bytes());
+                }
             }
         }
 
         stats.original_total_size = FileSize::new(original_total);
         stats.compressed_total_size = FileSize::new(compressed_total);
+        stats.collected_content_bytes = original_total as u64;
         stats.dictionary_entries = self.dictionary_builder.entry_count();
+        stats.dedup_bytes_saved = dedup_result.bytes_saved();
 
         let overall_compression_ratio = if original_total > 0 {
             compressed_total as f64 / original_total as f64
@@ -675,6 +1150,10 @@ impl UniversalCompressor<ReadyState> {
             stats,
             self.dictionary_builder.entry_count(),
             pattern_replacer.pattern_count(),
+            final_output,
+            dictionary,
+            symbol_table,
+            zstd_dictionary,
         );
 
         info!("Compression phase completed successfully");
@@ -682,50 +1161,69 @@ impl UniversalCompressor<ReadyState> {
     }
 
     /// Collect files using the actual CodeArchiver (reuse from ConfiguredState)
+    ///
+    /// The tree walk (`walk_target_files`, on `ignore`'s parallel walker)
+    /// reads no file contents and finishes first, returning its first
+    /// `ignore::Error` before any file is read; the
+    /// expensive part - reading and policy-filtering each path into a
+    /// `FileEntry` - runs across a rayon parallel iterator. `file_count`/
+    /// `total_size` are atomics shared across that parallel pass so the same
+    /// file-count/memory ceilings `ConfiguredState::collect_files_from_archiver`
+    /// enforces serially still stop collection once either is exceeded,
+    /// whichever thread observes it first; rayon's `collect` preserves the
+    /// paths' original order regardless of which thread finishes first.
     fn collect_files_from_archiver(
         &self,
     ) -> Result<Vec<crate::compression::types::FileEntry>, CompressionError> {
-        use crate::compression::types::FileEntry;
-        use std::fs;
-        use walkdir::WalkDir;
-
-        let mut files = Vec::new();
-
-        // Use walkdir to traverse the target directory
-        for entry in WalkDir::new(self.archiver.target_folder()) {
-            let entry = entry.map_err(|e| {
-                CompressionError::file_processing(
-                    "directory traversal",
-                    &format!("Failed to read directory entry: {}", e),
-                )
-            })?;
-
-            if entry.file_type().is_file() {
-                let path = entry.path();
-
-                // Skip binary files and focus on text files
-                if self.is_text_file(path) {
-                    match fs::read_to_string(path) {
-                        Ok(content) => {
-                            let relative_path = path
-                                .strip_prefix(self.archiver.target_folder())
-                                .unwrap_or(path)
-                                .to_path_buf();
+        use crate::compression::file_type::FileTypeRegistry;
+        use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+        let max_files = self.config.collection_limits.max_files.unwrap_or(usize::MAX);
+        let max_memory_bytes = self.config.collection_limits.max_total_bytes.unwrap_or(u64::MAX);
+
+        let target_folder = self.archiver.target_folder();
+        let paths = walk_target_files(target_folder, &self.config.exclude_globs)?;
+
+        let registry = FileTypeRegistry::new();
+        let file_count = AtomicUsize::new(0);
+        let total_size = AtomicU64::new(0);
+        let sniff_sample_len = self.config.text_sniff_sample_len.get();
+        let max_non_printable_ratio = self.config.max_non_printable_ratio.get();
+
+        let mut files: Vec<FileEntry> = self.with_bounded_thread_pool(|| {
+            paths
+                .par_iter()
+                .filter_map(|path| {
+                    if file_count.load(Ordering::Relaxed) >= max_files
+                        || total_size.load(Ordering::Relaxed) >= max_memory_bytes
+                    {
+                        return None;
+                    }
 
-                            files.push(FileEntry::new(relative_path, content, false));
+                    let relative_path = path.strip_prefix(target_folder).unwrap_or(path).to_path_buf();
+
+                    match FileEntry::from_path(
+                        path,
+                        relative_path,
+                        &registry,
+                        sniff_sample_len,
+                        max_non_printable_ratio,
+                    ) {
+                        Ok(Some(entry)) => {
+                            total_size.fetch_add(entry.original_content.len() as u64, Ordering::Relaxed);
+                            file_count.fetch_add(1, Ordering::Relaxed);
+                            Some(entry)
                         }
+                        Ok(None) => None,
                         Err(e) => {
                             // Log error but continue processing other files
-                            warn!(
-                                file_path = %path.display(),
-                                error = %e,
-                                "Failed to read file, skipping"
-                            );
+                            warn!(file_path = %path.display(), error = %e, "Failed to read file, skipping");
+                            None
                         }
                     }
-                }
-            }
-        }
+                })
+                .collect()
+        })?;
 
         if files.is_empty() {
             return Err(CompressionError::file_processing(
@@ -734,78 +1232,53 @@ impl UniversalCompressor<ReadyState> {
             ));
         }
 
+        // `walk_target_files` returns `paths` sorted and the rayon pass
+        // above preserves that order; sorting by relative path here pins
+        // the archive's file ordering - and therefore its bytes - to that
+        // key explicitly, regardless of how the walk or the parallel
+        // collection interleaved.
+        files.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+
         Ok(files)
     }
 
-    /// Check if a file is likely a text file based on extension (reuse from ConfiguredState)
-    fn is_text_file(&self, path: &std::path::Path) -> bool {
-        let text_extensions = [
-            "rs",
-            "toml",
-            "md",
-            "txt",
-            "json",
-            "yaml",
-            "yml",
-            "js",
-            "ts",
-            "tsx",
-            "jsx",
-            "html",
-            "css",
-            "scss",
-            "py",
-            "rb",
-            "go",
-            "java",
-            "c",
-            "cpp",
-            "h",
-            "hpp",
-            "sh",
-            "bash",
-            "zsh",
-            "fish",
-            "ps1",
-            "bat",
-            "cmd",
-            "xml",
-            "svg",
-            "gitignore",
-            "dockerfile",
-            "makefile",
-        ];
-
-        path.extension()
-            .and_then(|ext| ext.to_str())
-            .map(|ext| text_extensions.contains(&ext.to_lowercase().as_str()))
-            .unwrap_or(false)
-    }
-
-    /// Apply zstd compression to the final output
-    fn apply_zstd_compression(
+    /// Run `self.config.final_codec`'s `Compressor` over the final output.
+    ///
+    /// The shared `Report` built in `compress()` was already updated with
+    /// each file's dictionary-stage sizes, so this second pass over the
+    /// same entries doesn't record into it again.
+    ///
+    /// Per-file compression is embarrassingly parallel (each `Compressor`
+    /// call only touches its own file's bytes), so this runs across rayon's
+    /// pool the same way Step 3's pattern replacement does; `Compressor:
+    /// Send + Sync` is what lets the single `compressor` built below be
+    /// shared read-only across threads instead of rebuilt per file.
+    /// `into_par_iter().map().collect::<Result<Vec<_>, _>>()` preserves the
+    /// input order (it's an `IndexedParallelIterator`), so `files`' existing
+    /// order survives untouched.
+    fn apply_final_compression(
         &self,
         files: Vec<crate::compression::types::FileEntry>,
-    ) -> Result<Vec<crate::compression::types::FileEntry>, CompressionError> {
-        use crate::compression::zstd_compressor::ZstdCompressor;
-
-        let compressor = ZstdCompressor::new(self.config.zstd_compression_level)?;
-        let mut compressed_files = Vec::new();
-
-        for mut file in files {
-            if let Some(content) = &file.compressed_content {
-                // Apply zstd compression to the content
-                let zstd_compressed = compressor.compress_string(content)?;
-
-                // For demonstration, we'll store the compressed data as base64
-                // In a real implementation, this would be handled differently
-                let base64_compressed = base64_encode(&zstd_compressed);
-                file.apply_compression(base64_compressed);
-            }
-            compressed_files.push(file);
-        }
-
-        Ok(compressed_files)
+    ) -> Result<(Vec<crate::compression::types::FileEntry>, Option<ZstdDictionary>), CompressionError> {
+        let (compressor, dictionary) = build_final_compressor(&self.config, &files)?;
+        let method = compression_method_for_codec(self.config.final_codec);
+
+        let compressed = self.with_bounded_thread_pool(|| files
+            .into_par_iter()
+            .map(|mut file| {
+                if let Some(content) = &file.compressed_content {
+                    let final_compressed = compressor.compress(content.as_bytes())?;
+
+                    // Stored as base64 text so the final codec's arbitrary bytes
+                    // still fit `compressed_content: String`, and `restore` can
+                    // decode and invert this stage exactly.
+                    let encoded = crate::compression::base64::encode(&final_compressed);
+                    file.apply_compression(encoded, method, None);
+                }
+                Ok(file)
+            })
+            .collect::<Result<Vec<_>, CompressionError>>())??;
+        Ok((compressed, dictionary))
     }
 
     /// Create final output without zstd compression
@@ -821,11 +1294,62 @@ impl UniversalCompressor<ReadyState> {
     }
 }
 
-/// Simple base64 encoding for demonstration
-fn base64_encode(data: &[u8]) -> String {
-    // This is a simplified base64 encoding for demonstration
-    // In a real implementation, you'd use a proper base64 library
-    format!("base64:{}", data.len())
+/// Build the final-stage `Compressor` for `config`, honoring
+/// `config.zstd_dictionary_config` when `config.final_codec` is
+/// `Codec::Zstd`. `files` is sampled (up to `sample_limit`) to train a fresh
+/// dictionary for `ZstdDictionaryConfig::Train`; it's otherwise unused.
+///
+/// Returns the trained/provided `ZstdDictionary` alongside the compressor so
+/// the caller can carry it on `CompressionResult::zstd_dictionary` - without
+/// it, `FileEntry::decompress` has no way to reconstruct a matching
+/// dictionary-aware `ZstdCompressor` for entries compressed against it.
+fn build_final_compressor(
+    config: &CompressionConfig,
+    files: &[FileEntry],
+) -> Result<(Box<dyn Compressor>, Option<ZstdDictionary>), CompressionError> {
+    let Codec::Zstd(level) = config.final_codec else {
+        return Ok((config.final_codec.compressor_with_zstd_advanced(config.zstd_advanced)?, None));
+    };
+
+    let dictionary = match &config.zstd_dictionary_config {
+        ZstdDictionaryConfig::None => None,
+        ZstdDictionaryConfig::Provided(bytes) => Some(ZstdDictionary::from_bytes(bytes.clone())),
+        ZstdDictionaryConfig::Train { sample_limit, dict_size } => {
+            let samples: Vec<&[u8]> = files
+                .iter()
+                .take(*sample_limit)
+                .map(|file| file.original_content.as_bytes())
+                .collect();
+            Some(ZstdDictionary::train_from_samples(&samples, dict_size.get())?)
+        }
+    };
+
+    match dictionary {
+        Some(dictionary) => {
+            let compressor = ZstdCompressor::with_dictionary(level, &dictionary)?.with_advanced(config.zstd_advanced)?;
+            Ok((Box::new(compressor), Some(dictionary)))
+        }
+        None => Ok((config.final_codec.compressor_with_zstd_advanced(config.zstd_advanced)?, None)),
+    }
+}
+
+/// Map the codec that produced `compressed_content` to the `CompressionMethod`
+/// recorded on the `FileEntry`, so the codec selected via `final_codec` stays
+/// visible in `CompressionStatistics`'s per-method breakdown.
+fn compression_method_for_codec(codec: Codec) -> CompressionMethod {
+    match codec {
+        Codec::Stored => CompressionMethod::Store,
+        Codec::Zstd(level) => CompressionMethod::Zstd { level: level.get() },
+        // `CompressionMethod::Lz4` has no level field (see its doc
+        // comment); the codec's level is still self-describing on
+        // `Codec::Lz4` itself, it just isn't carried any further here.
+        Codec::Lz4(_) => CompressionMethod::Lz4,
+        Codec::Snappy => CompressionMethod::Snappy,
+        // `BrotliCompressor`/`DeflateCompressor` always run at a fixed
+        // quality/level (see their `new()`), so that's what gets recorded.
+        Codec::Brotli => CompressionMethod::Brotli { level: 9 },
+        Codec::Deflate => CompressionMethod::Deflate { level: 6 },
+    }
 }
 
 // Common methods available in all states
@@ -1255,6 +1779,116 @@ mod tests {
         assert!(compression_result.statistics.dictionary_entries > 0);
     }
 
+    #[test]
+    fn test_compression_with_fsst_dictionary_strategy() {
+        let temp_dir = create_test_directory();
+        let target_folder = temp_dir.path().to_path_buf();
+
+        let config = CompressionConfig::builder()
+            .dictionary_strategy(DictionaryStrategy::Fsst)
+            .build()
+            .unwrap();
+
+        let compressor = UniversalCompressor::with_config(target_folder, None, config).unwrap();
+
+        let mut ready_compressor = compressor
+            .configure()
+            .analyze()
+            .unwrap()
+            .build_dictionary()
+            .unwrap()
+            .prepare_replacement()
+            .unwrap();
+
+        let result = ready_compressor.compress().unwrap();
+        assert!(result.symbol_table.is_some());
+        assert!(result.statistics.total_files_processed > 0);
+
+        let output_dir = tempfile::TempDir::new().unwrap();
+        result.restore(output_dir.path()).unwrap();
+        let restored = std::fs::read_to_string(output_dir.path().join("test.rs")).unwrap();
+        assert_eq!(
+            restored,
+            "fn main() { println!(\"Hello, world!\"); }\n\
+             fn test() { println!(\"Hello, world!\"); }\n\
+             fn demo() { println!(\"Hello, world!\"); }"
+        );
+    }
+
+    #[test]
+    fn test_compression_with_trained_zstd_dictionary_round_trips() {
+        let temp_dir = create_test_directory();
+        let target_folder = temp_dir.path().to_path_buf();
+
+        let config = CompressionConfig::builder()
+            .zstd_dictionary_config(ZstdDictionaryConfig::Train {
+                sample_limit: 10,
+                dict_size: DictSize::new(1024).unwrap(),
+            })
+            .build()
+            .unwrap();
+
+        let compressor = UniversalCompressor::with_config(target_folder, None, config).unwrap();
+
+        let mut ready_compressor = compressor
+            .configure()
+            .analyze()
+            .unwrap()
+            .build_dictionary()
+            .unwrap()
+            .prepare_replacement()
+            .unwrap();
+
+        let result = ready_compressor.compress().unwrap();
+        assert!(result.zstd_dictionary.is_some());
+
+        let output_dir = tempfile::TempDir::new().unwrap();
+        result.restore(output_dir.path()).unwrap();
+        let restored = std::fs::read_to_string(output_dir.path().join("test.rs")).unwrap();
+        assert_eq!(
+            restored,
+            "fn main() { println!(\"Hello, world!\"); }\n\
+             fn test() { println!(\"Hello, world!\"); }\n\
+             fn demo() { println!(\"Hello, world!\"); }"
+        );
+    }
+
+    #[test]
+    fn test_write_archive_round_trips_through_read_block_archive() {
+        let temp_dir = create_test_directory();
+        let target_folder = temp_dir.path().to_path_buf();
+
+        let compressor = UniversalCompressor::new(target_folder, None).unwrap();
+        let mut ready_compressor = compressor
+            .configure()
+            .analyze()
+            .unwrap()
+            .build_dictionary()
+            .unwrap()
+            .prepare_replacement()
+            .unwrap();
+
+        let result = ready_compressor.compress().unwrap();
+
+        let archive_path = temp_dir.path().join("archive.bin");
+        let written_path = ready_compressor
+            .write_archive(&result.entries, &archive_path)
+            .unwrap();
+        assert_eq!(written_path, archive_path);
+
+        let archive_bytes = std::fs::read(&archive_path).unwrap();
+        let codec = Codec::Zstd(crate::compression::config::ZstdLevel::default());
+        let (entries, dictionary_entries) = crate::compression::read_block_archive(
+            &archive_bytes,
+            codec,
+            crate::compression::config::ChecksumConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(entries.len(), result.entries.len());
+        assert_eq!(dictionary_entries.len(), ready_compressor.get_dictionary_entries().len());
+    }
+
     #[test]
     fn test_zstd_compression_levels() {
         let temp_dir = create_test_directory();
@@ -1373,30 +2007,42 @@ mod tests {
     fn test_file_collection_with_file_limit() {
         let temp_dir = create_test_directory_with_many_files(50); // Create 50 files
         let target_folder = temp_dir.path().to_path_buf();
+        let limits = CompressorLimits::new(Some(10), None, None);
 
-        let compressor = UniversalCompressor::new(target_folder, None).unwrap();
+        let compressor = UniversalCompressor::new(target_folder, Some(limits)).unwrap();
         let configured = compressor.configure();
 
         // This should collect files but stop at the limit
         let result = configured.analyze();
         assert!(result.is_ok());
 
-        // TODO: Verify file limit was respected once implemented
+        let analyzed = result.unwrap();
+        let stats = analyzed.statistics().unwrap();
+        assert!(stats.collection_limits_hit.contains(&CollectionLimit::MaxFiles));
     }
 
     #[test]
     fn test_file_collection_with_memory_limit() {
-        let temp_dir = create_test_directory_with_large_files(); // Create files with large content
+        let temp_dir = create_test_directory_with_large_files(); // ~1MB each, 10 files
         let target_folder = temp_dir.path().to_path_buf();
+        let limits = CompressorLimits::new(None, Some(5 * 1024 * 1024), None);
 
-        let compressor = UniversalCompressor::new(target_folder, None).unwrap();
+        let compressor = UniversalCompressor::new(target_folder, Some(limits)).unwrap();
         let configured = compressor.configure();
 
-        // This should collect files but stop at memory limit
+        // This should collect files but stop at the total-bytes limit
         let result = configured.analyze();
         assert!(result.is_ok());
 
-        // TODO: Verify memory limit was respected once implemented
+        let analyzed = result.unwrap();
+        let stats = analyzed.statistics().unwrap();
+        assert!(stats.collection_limits_hit.contains(&CollectionLimit::MaxTotalBytes));
+        // The limit is measured against real decoded content, not on-disk
+        // metadata, so the accumulated total should land at or just past
+        // the 5MB cap (collection stops once a file pushes it over), never
+        // wildly beyond what the ~1MB test files could actually produce.
+        assert!(stats.collected_content_bytes >= 5 * 1024 * 1024);
+        assert!(stats.collected_content_bytes < 10 * 1024 * 1024);
     }
 
     #[test]
@@ -1404,6 +2050,7 @@ mod tests {
         let temp_dir = create_test_directory_with_many_files(2000); // Create many files
         let target_folder = temp_dir.path().to_path_buf();
 
+        // Default limits cap at 1000 files, so 2000 files should trip it.
         let compressor = UniversalCompressor::new(target_folder, None).unwrap();
         let configured = compressor.configure();
 
@@ -1411,7 +2058,59 @@ mod tests {
         let result = configured.analyze();
         assert!(result.is_ok());
 
-        // TODO: Verify warning logs are generated when limits are hit
+        let analyzed = result.unwrap();
+        let stats = analyzed.statistics().unwrap();
+        assert!(!stats.collection_limits_hit.is_empty());
+    }
+
+    #[test]
+    fn test_file_collection_skips_oversized_files() {
+        let temp_dir = create_test_directory_with_large_files(); // ~1MB each, 10 files
+        // One small file stays under the limit so collection doesn't empty out entirely.
+        std::fs::write(temp_dir.path().join("small.rs"), "fn small() {}").unwrap();
+        let target_folder = temp_dir.path().to_path_buf();
+        let limits = CompressorLimits::new(None, None, Some(100 * 1024));
+
+        let compressor = UniversalCompressor::new(target_folder, Some(limits)).unwrap();
+        let configured = compressor.configure();
+
+        let analyzed = configured.analyze().unwrap();
+        let stats = analyzed.statistics().unwrap();
+        assert!(stats.collection_limits_hit.contains(&CollectionLimit::MaxFileSize));
+        assert_eq!(stats.oversized_files_skipped, 10);
+    }
+
+    #[test]
+    fn test_walk_target_files_respects_promptignore() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp directory");
+        std::fs::write(temp_dir.path().join("keep.rs"), "fn keep() {}").unwrap();
+        std::fs::write(temp_dir.path().join("drop.rs"), "fn drop_me() {}").unwrap();
+        std::fs::write(temp_dir.path().join(".promptignore"), "drop.rs\n").unwrap();
+
+        let paths = walk_target_files(temp_dir.path(), &[]).unwrap();
+        let names: Vec<_> = paths
+            .iter()
+            .filter_map(|p| p.file_name().and_then(|n| n.to_str()))
+            .collect();
+
+        assert!(names.contains(&"keep.rs"));
+        assert!(!names.contains(&"drop.rs"));
+    }
+
+    #[test]
+    fn test_walk_target_files_caps_entries_per_directory() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp directory");
+        let extra = MAX_ENTRIES_PER_DIR + 10;
+        for i in 0..extra {
+            std::fs::write(
+                temp_dir.path().join(format!("file_{:04}.rs", i)),
+                format!("fn f{}() {{}}", i),
+            )
+            .unwrap();
+        }
+
+        let paths = walk_target_files(temp_dir.path(), &[]).unwrap();
+        assert_eq!(paths.len(), MAX_ENTRIES_PER_DIR);
     }
 
     #[test]
@@ -1437,12 +2136,18 @@ mod tests {
         assert!(compression_result.unwrap().statistics.total_files_processed > 0);
     }
 
-    // Helper function to create directory with many files
+    // Helper function to create directory with many files, spread across
+    // subdirectories below `MAX_ENTRIES_PER_DIR` each so tests exercising
+    // `CompressorLimits::max_files` aren't accidentally capped by the
+    // per-directory entry cap first.
     fn create_test_directory_with_many_files(count: usize) -> tempfile::TempDir {
         let temp_dir = tempfile::TempDir::new().expect("Failed to create temp directory");
+        const FILES_PER_SUBDIR: usize = 100;
 
         for i in 0..count {
-            let file_path = temp_dir.path().join(format!("file_{}.rs", i));
+            let subdir = temp_dir.path().join(format!("dir_{}", i / FILES_PER_SUBDIR));
+            std::fs::create_dir_all(&subdir).unwrap();
+            let file_path = subdir.join(format!("file_{}.rs", i));
             std::fs::write(
                 &file_path,
                 format!("fn test_{}() {{ println!(\"Hello {}\"); }}", i, i),
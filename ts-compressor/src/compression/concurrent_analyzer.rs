@@ -5,6 +5,7 @@
 
 use crate::compression::{FrequencyAnalysis, CompressionError};
 use dashmap::DashMap;
+use rayon::prelude::*;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::collections::HashMap;
@@ -67,7 +68,64 @@ impl ConcurrentFrequencyAnalyzer {
                 .fetch_add(count, Ordering::Relaxed);
         }
     }
-    
+
+    /// Analyze one large document by internally splitting it into
+    /// overlapping chunks and processing them concurrently over a rayon
+    /// work-stealing thread pool, instead of requiring the caller to split
+    /// content and spawn threads by hand.
+    ///
+    /// Chunks overlap by `min(50, content.len())` bytes so that patterns
+    /// straddling a chunk boundary aren't lost. Each chunk is first counted
+    /// into a local `HashMap` and only then folded into the shared
+    /// `DashMap` via `merge_local_patterns`, which costs one batched write
+    /// per chunk instead of every window in the document contending on the
+    /// shared map directly.
+    pub fn analyze_parallel(&self, content: &str) {
+        if content.is_empty() {
+            return;
+        }
+
+        let overlap = content.len().min(50);
+        let chunks = self.split_into_overlapping_chunks(content, overlap);
+
+        chunks
+            .par_iter()
+            .map(|chunk| local_pattern_counts(chunk, self.min_pattern_length))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .for_each(|local| self.merge_local_patterns(local));
+    }
+
+    /// Split `content` into chunks sized for the current rayon thread pool,
+    /// each overlapping the next by `overlap` bytes (snapped to char
+    /// boundaries, since content may contain multi-byte UTF-8 sequences).
+    fn split_into_overlapping_chunks(&self, content: &str, overlap: usize) -> Vec<String> {
+        let num_chunks = rayon::current_num_threads().max(1);
+        let target_size = (content.len() / num_chunks).max(overlap + 1);
+
+        let mut chunks = Vec::new();
+        let mut start = 0usize;
+        loop {
+            let mut end = (start + target_size).min(content.len());
+            while end < content.len() && !content.is_char_boundary(end) {
+                end += 1;
+            }
+            chunks.push(content[start..end].to_string());
+
+            if end >= content.len() {
+                break;
+            }
+
+            let mut next_start = end.saturating_sub(overlap);
+            while next_start > 0 && !content.is_char_boundary(next_start) {
+                next_start -= 1;
+            }
+            start = next_start;
+        }
+
+        chunks
+    }
+
     /// Get the frequency of a specific pattern
     pub fn get_pattern_frequency(&self, pattern: &str) -> usize {
         self.pattern_frequencies
@@ -86,6 +144,35 @@ impl ConcurrentFrequencyAnalyzer {
     }
 }
 
+/// Single-threaded window scan used per-chunk by `analyze_parallel`, kept
+/// free of any `self`/`Arc` access so each rayon task can run on an owned
+/// local `HashMap` with no shared-state contention until the final merge.
+fn local_pattern_counts(content: &str, min_pattern_length: usize) -> HashMap<String, usize> {
+    let mut local = HashMap::new();
+    if content.is_empty() {
+        return local;
+    }
+
+    for window_size in min_pattern_length..=content.len().min(50) {
+        for window in content.as_bytes().windows(window_size) {
+            if let Ok(pattern) = std::str::from_utf8(window) {
+                if pattern.trim().is_empty() || pattern.chars().all(|c| c == pattern.chars().next().unwrap()) {
+                    continue;
+                }
+
+                let alphanumeric_count = pattern.chars().filter(|c| c.is_alphanumeric()).count();
+                if alphanumeric_count < pattern.len() / 2 {
+                    continue;
+                }
+
+                *local.entry(pattern.to_string()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    local
+}
+
 impl FrequencyAnalysis for ConcurrentFrequencyAnalyzer {
     /// Analyze content for pattern frequencies (delegates to analyze_chunk)
     fn analyze_content(&mut self, content: &str) {
@@ -314,4 +401,42 @@ mod tests {
         assert_eq!(patterns[2].0, "lowest_freq");
         assert_eq!(patterns[2].1, 2);
     }
+
+    #[test]
+    fn test_analyze_parallel_matches_sequential_analysis() {
+        // Short enough that `analyze_parallel` processes it as a single
+        // chunk regardless of thread count, so counts must match exactly.
+        let content = "function function function";
+
+        let sequential = ConcurrentFrequencyAnalyzer::new(4, 2);
+        sequential.analyze_chunk(content);
+
+        let parallel = ConcurrentFrequencyAnalyzer::new(4, 2);
+        parallel.analyze_parallel(content);
+
+        assert_eq!(
+            parallel.get_pattern_frequency("function"),
+            sequential.get_pattern_frequency("function")
+        );
+        assert!(parallel.should_compress_pattern("function"));
+    }
+
+    #[test]
+    fn test_analyze_parallel_finds_patterns_across_chunk_boundaries() {
+        // Long enough to be split into multiple chunks; the repeated
+        // pattern must still be found thanks to the chunk overlap.
+        let content = "function test() { return 'test'; } ".repeat(20);
+
+        let parallel = ConcurrentFrequencyAnalyzer::new(4, 2);
+        parallel.analyze_parallel(&content);
+
+        assert!(parallel.get_pattern_frequency("function") >= 20);
+    }
+
+    #[test]
+    fn test_analyze_parallel_handles_empty_content() {
+        let analyzer = ConcurrentFrequencyAnalyzer::new(4, 2);
+        analyzer.analyze_parallel("");
+        assert!(analyzer.get_frequent_patterns().is_empty());
+    }
 }
\ No newline at end of file
@@ -3,8 +3,33 @@
 //! Provides type-safe configuration with validation and builder patterns
 //! for flexible compression parameter management.
 
+use crate::compression::codec::Codec;
 use crate::compression::error::{CompressionError, CompressionResult};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
+use std::path::Path;
+use sysinfo::System;
+
+/// Implements `Serialize`/`Deserialize` for a validated newtype by
+/// round-tripping through its inner scalar and re-running `new()` on the
+/// way back in, so a config file with an out-of-range value produces the
+/// same `config_validation` error `CompressionConfigBuilder` would.
+macro_rules! impl_scalar_serde {
+    ($ty:ident, $inner:ty) => {
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                self.get().serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = <$inner>::deserialize(deserializer)?;
+                Self::new(value).map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
 
 /// Newtype for minimum pattern length with validation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -44,6 +69,8 @@ impl fmt::Display for MinPatternLength {
     }
 }
 
+impl_scalar_serde!(MinPatternLength, usize);
+
 /// Newtype for frequency threshold with validation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct FrequencyThreshold(usize);
@@ -82,6 +109,8 @@ impl fmt::Display for FrequencyThreshold {
     }
 }
 
+impl_scalar_serde!(FrequencyThreshold, usize);
+
 /// Newtype for zstd compression level with validation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ZstdLevel(i32);
@@ -115,6 +144,51 @@ impl fmt::Display for ZstdLevel {
     }
 }
 
+impl_scalar_serde!(ZstdLevel, i32);
+
+/// Newtype for the lz4hc compression level with validation.
+///
+/// Mirrors `ZstdLevel`'s role for `Codec::Zstd`: `Codec::Lz4` carries one
+/// of these so the codec is self-describing about the level it was
+/// selected with (and round-trips through `Codec`'s `"lz4/9"` string
+/// form), even though `Lz4Compressor` itself wraps `lz4_flex`'s fast
+/// block API, which has no tunable level to forward it to -- see
+/// `Lz4Compressor`'s doc comment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Lz4Level(i32);
+
+impl Lz4Level {
+    /// Create a new Lz4Level with validation. `1..=12` matches the lz4hc
+    /// reference implementation's level range.
+    pub fn new(level: i32) -> CompressionResult<Self> {
+        if !(1..=12).contains(&level) {
+            return Err(CompressionError::config_validation(
+                "Lz4 compression level must be between 1 and 12",
+            ));
+        }
+        Ok(Self(level))
+    }
+
+    /// Get the inner value
+    pub fn get(&self) -> i32 {
+        self.0
+    }
+}
+
+impl Default for Lz4Level {
+    fn default() -> Self {
+        Self(9) // lz4hc's own default level
+    }
+}
+
+impl fmt::Display for Lz4Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl_scalar_serde!(Lz4Level, i32);
+
 /// Newtype for thread count with validation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ThreadCount(usize);
@@ -153,6 +227,8 @@ impl fmt::Display for ThreadCount {
     }
 }
 
+impl_scalar_serde!(ThreadCount, usize);
+
 /// Newtype for chunk size with validation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ChunkSize(usize);
@@ -191,6 +267,8 @@ impl fmt::Display for ChunkSize {
     }
 }
 
+impl_scalar_serde!(ChunkSize, usize);
+
 /// Newtype for channel buffer size with validation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ChannelBufferSize(usize);
@@ -229,6 +307,8 @@ impl fmt::Display for ChannelBufferSize {
     }
 }
 
+impl_scalar_serde!(ChannelBufferSize, usize);
+
 /// Newtype for memory map threshold with validation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct MemoryMapThreshold(usize);
@@ -267,13 +347,496 @@ impl fmt::Display for MemoryMapThreshold {
     }
 }
 
+impl_scalar_serde!(MemoryMapThreshold, usize);
+
+/// Newtype for the number of leading bytes `sniff_is_text` inspects, with
+/// validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TextSniffSampleLen(usize);
+
+impl TextSniffSampleLen {
+    /// Create a new TextSniffSampleLen with validation
+    pub fn new(len: usize) -> CompressionResult<Self> {
+        if len < 64 {
+            return Err(CompressionError::config_validation(
+                "Text sniff sample length must be at least 64 bytes",
+            ));
+        }
+        if len > 1024 * 1024 {
+            return Err(CompressionError::config_validation(
+                "Text sniff sample length cannot exceed 1MB",
+            ));
+        }
+        Ok(Self(len))
+    }
+
+    /// Get the inner value
+    pub fn get(&self) -> usize {
+        self.0
+    }
+}
+
+impl Default for TextSniffSampleLen {
+    fn default() -> Self {
+        Self(8192) // 8KB, matching archive-to-txt's content-sniff fallback
+    }
+}
+
+impl fmt::Display for TextSniffSampleLen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bytes", self.0)
+    }
+}
+
+impl_scalar_serde!(TextSniffSampleLen, usize);
+
+/// Newtype for the maximum proportion of non-UTF-8/non-printable bytes a
+/// `sniff_is_text` sample may contain before it's classified as binary.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NonPrintableRatio(f64);
+
+impl NonPrintableRatio {
+    /// Create a new NonPrintableRatio with validation
+    pub fn new(ratio: f64) -> CompressionResult<Self> {
+        if !(0.0..=1.0).contains(&ratio) {
+            return Err(CompressionError::config_validation(
+                "Non-printable ratio must be between 0.0 and 1.0",
+            ));
+        }
+        Ok(Self(ratio))
+    }
+
+    /// Get the inner value
+    pub fn get(&self) -> f64 {
+        self.0
+    }
+}
+
+impl Default for NonPrintableRatio {
+    fn default() -> Self {
+        Self(0.3) // Up to 30% suspect bytes still counts as text
+    }
+}
+
+impl fmt::Display for NonPrintableRatio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.0}%", self.0 * 100.0)
+    }
+}
+
+impl_scalar_serde!(NonPrintableRatio, f64);
+
+/// Validated min/avg/max byte targets for FastCDC content-defined chunking
+/// (see `crate::compression::fastcdc`). `min <= avg <= max`, and `avg` must
+/// be a power of two so the normalized-chunking gear masks (`avg.trailing_zeros()`
+/// set bits) are well-defined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentDefinedChunking {
+    min: usize,
+    avg: usize,
+    max: usize,
+}
+
+impl ContentDefinedChunking {
+    /// Create a new set of FastCDC size targets with validation.
+    pub fn new(min: usize, avg: usize, max: usize) -> CompressionResult<Self> {
+        if !(min <= avg && avg <= max) {
+            return Err(CompressionError::config_validation(
+                "Content-defined chunking requires min <= avg <= max",
+            ));
+        }
+        if min == 0 {
+            return Err(CompressionError::config_validation(
+                "Content-defined chunking min size must be at least 1 byte",
+            ));
+        }
+        if !avg.is_power_of_two() {
+            return Err(CompressionError::config_validation(
+                "Content-defined chunking avg size must be a power of two",
+            ));
+        }
+        Ok(Self { min, avg, max })
+    }
+
+    /// Shortest chunk FastCDC will produce, other than a final short chunk
+    /// at the end of the input.
+    pub fn min(&self) -> usize {
+        self.min
+    }
+
+    /// Target chunk size FastCDC's normalized-chunking mask switch aims for.
+    pub fn avg(&self) -> usize {
+        self.avg
+    }
+
+    /// Longest chunk FastCDC will produce; a cut is forced here even if no
+    /// gear-hash boundary was found.
+    pub fn max(&self) -> usize {
+        self.max
+    }
+}
+
+impl Default for ContentDefinedChunking {
+    fn default() -> Self {
+        // 2KB/8KB/16KB, the same min/avg/max ratios FastCDC's reference
+        // implementation and restic both default to.
+        Self {
+            min: 2 * 1024,
+            avg: 8 * 1024,
+            max: 16 * 1024,
+        }
+    }
+}
+
+/// Plain on-the-wire shape for `ContentDefinedChunking`, deserialized first
+/// so `new()`'s min/avg/max validation can run on the raw fields before
+/// they're accepted.
+#[derive(Serialize, Deserialize)]
+struct ContentDefinedChunkingRaw {
+    min: usize,
+    avg: usize,
+    max: usize,
+}
+
+impl Serialize for ContentDefinedChunking {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ContentDefinedChunkingRaw {
+            min: self.min,
+            avg: self.avg,
+            max: self.max,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ContentDefinedChunking {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = ContentDefinedChunkingRaw::deserialize(deserializer)?;
+        Self::new(raw.min, raw.avg, raw.max).map_err(serde::de::Error::custom)
+    }
+}
+
+/// How file content is split into chunks before the final codec runs.
+/// `Fixed` is the historical uniform-length behavior; `ContentDefined`
+/// switches to FastCDC boundary detection, which keeps unedited regions of
+/// a file aligned to the same chunk boundaries across edits and so dedups
+/// better (see `crate::compression::fastcdc`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChunkingStrategy {
+    Fixed(ChunkSize),
+    ContentDefined(ContentDefinedChunking),
+}
+
+impl Default for ChunkingStrategy {
+    fn default() -> Self {
+        Self::Fixed(ChunkSize::default())
+    }
+}
+
+/// Newtype for zstd's window log (`ZSTD_c_windowLog`), the base-2 logarithm
+/// of the maximum back-reference distance, with validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct WindowLog(u32);
+
+impl WindowLog {
+    /// Create a new WindowLog with validation. `10..=31` is the range
+    /// `ZSTD_WINDOWLOG_MIN`/`ZSTD_WINDOWLOG_LIMIT_DEFAULT`-extended covers.
+    pub fn new(log: u32) -> CompressionResult<Self> {
+        if !(10..=31).contains(&log) {
+            return Err(CompressionError::config_validation(
+                "Zstd window log must be between 10 and 31",
+            ));
+        }
+        Ok(Self(log))
+    }
+
+    /// Get the inner value
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for WindowLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl_scalar_serde!(WindowLog, u32);
+
+/// Zstd's match-finder strategy (`ZSTD_strategy`), trading compression
+/// speed for ratio. Ordered fastest/weakest to slowest/strongest, matching
+/// the underlying `ZSTD_strategy` enum's own ordering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ZstdStrategy {
+    Fast,
+    DFast,
+    Greedy,
+    Lazy,
+    Lazy2,
+    BtLazy2,
+    BtOpt,
+    BtUltra,
+    BtUltra2,
+}
+
+impl ZstdStrategy {
+    /// Whether this is one of the "ultra" strategies, which the zstd C API
+    /// requires a sufficiently high compression level to enable.
+    fn is_ultra(&self) -> bool {
+        matches!(self, ZstdStrategy::BtUltra | ZstdStrategy::BtUltra2)
+    }
+}
+
+/// Advanced zstd tuning beyond the single `ZstdLevel` knob, for power users
+/// trading encode time for ratio on large, highly redundant inputs. All
+/// fields default to "let zstd derive it from the level", matching
+/// `ZstdCompressor`'s existing behavior when none of this is set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ZstdAdvanced {
+    /// Force a specific match-finder strategy instead of the one zstd
+    /// derives from the compression level.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<ZstdStrategy>,
+    /// Force a specific maximum back-reference window instead of the one
+    /// zstd derives from the compression level.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_log: Option<WindowLog>,
+    /// Enable long-distance matching, which finds matches beyond the
+    /// normal window using a separate hash table - effective on large
+    /// inputs with distant repeats. Requires `window_log` of at least 27
+    /// (see `ZstdAdvanced::validate`).
+    pub enable_long_distance_matching: bool,
+}
+
+impl ZstdAdvanced {
+    /// Cross-field validation against the compression `level` these
+    /// parameters will run at.
+    pub fn validate(&self, level: ZstdLevel) -> CompressionResult<()> {
+        if self.enable_long_distance_matching {
+            match self.window_log {
+                Some(log) if log.get() >= 27 => {}
+                _ => {
+                    return Err(CompressionError::config_validation(
+                        "Long-distance matching requires a window log of at least 27",
+                    ));
+                }
+            }
+        }
+
+        if let Some(strategy) = self.strategy {
+            if strategy.is_ultra() && level.get() < 20 {
+                return Err(CompressionError::config_validation(
+                    "BtUltra/BtUltra2 strategies require a compression level of at least 20",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Newtype for the target size, in bytes, of a trained `ZstdDictionary`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DictSize(usize);
+
+impl DictSize {
+    /// Create a new DictSize with validation. `1KB..=1MB` covers zstd's own
+    /// recommended range for `ZDICT_trainFromBuffer` - below 1KB there's too
+    /// little room to capture shared structure, and zstd's own training
+    /// algorithm sees diminishing returns well before 1MB.
+    pub fn new(bytes: usize) -> CompressionResult<Self> {
+        if !(1024..=1024 * 1024).contains(&bytes) {
+            return Err(CompressionError::config_validation(
+                "Zstd dictionary size must be between 1KB and 1MB",
+            ));
+        }
+        Ok(Self(bytes))
+    }
+
+    /// Get the inner value
+    pub fn get(&self) -> usize {
+        self.0
+    }
+}
+
+impl Default for DictSize {
+    /// zstd CLI's own default training size for small-file corpora.
+    fn default() -> Self {
+        Self(16 * 1024)
+    }
+}
+
+impl fmt::Display for DictSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl_scalar_serde!(DictSize, usize);
+
+/// Selects whether `final_codec`'s zstd stage shares a trained dictionary
+/// across files instead of compressing each one independently - effective
+/// for many small, similar payloads that don't individually carry enough
+/// redundancy for zstd to exploit on their own. Ignored unless `final_codec`
+/// is `Codec::Zstd`.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ZstdDictionaryConfig {
+    /// No shared dictionary; each file is compressed independently (the
+    /// historical behavior).
+    #[default]
+    None,
+    /// Use an already-trained dictionary's raw bytes, e.g. one persisted
+    /// from an earlier `Train` run via `ZstdDictionary::as_bytes`.
+    Provided(Vec<u8>),
+    /// Train a fresh dictionary from up to `sample_limit` of the collected
+    /// files' content before compressing any of them.
+    Train {
+        sample_limit: usize,
+        dict_size: DictSize,
+    },
+}
+
+/// Per-block integrity checksum `block_archive::write_blocks` computes and
+/// `read_blocks` verifies over each block's decoded bytes, after
+/// decompressing it, following the checksum
+/// ClickHouse's LZ4 framing prepends to every compressed block. Variants
+/// trade checksum size in the block header for collision resistance; like
+/// `final_codec`, the same variant used to write an archive must be passed
+/// back in to read it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ChecksumConfig {
+    /// No checksum is stored or verified.
+    None,
+    /// 64-bit XXH3 hash, 8 bytes per block header.
+    Xxh3_64,
+    /// 128-bit XXH3 hash, 16 bytes per block header (the historical
+    /// behavior, previously a fixed, unconfigurable truncated SHA-256).
+    #[default]
+    Xxh3_128,
+}
+
+impl ChecksumConfig {
+    /// Length in bytes of this algorithm's checksum, 0 for `None`.
+    pub fn checksum_len(&self) -> usize {
+        match self {
+            Self::None => 0,
+            Self::Xxh3_64 => 8,
+            Self::Xxh3_128 => 16,
+        }
+    }
+}
+
+/// Newtype for the memory budget `ParallelConfig::auto()` sizes its buffers
+/// against, in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MemoryBudget(u64);
+
+impl MemoryBudget {
+    /// Create a new MemoryBudget with validation.
+    pub fn new(bytes: u64) -> CompressionResult<Self> {
+        if bytes == 0 {
+            return Err(CompressionError::config_validation(
+                "Memory budget must be greater than 0 bytes",
+            ));
+        }
+        Ok(Self(bytes))
+    }
+
+    /// Get the inner value
+    pub fn get(&self) -> u64 {
+        self.0
+    }
+
+    /// Detect this machine's available memory via `sysinfo` and take
+    /// roughly two-thirds of it as the budget - the same headroom
+    /// Meilisearch's `MaxMemory` leaves for the rest of the system. Returns
+    /// `None` when `sysinfo` can't determine available memory (e.g. an
+    /// unsupported platform), so the caller can fall back to static
+    /// defaults instead.
+    fn detect() -> Option<Self> {
+        let mut system = System::new();
+        system.refresh_memory();
+        let available_bytes = system.available_memory();
+        if available_bytes == 0 {
+            return None;
+        }
+        Self::new(available_bytes / 3 * 2).ok()
+    }
+}
+
+impl fmt::Display for MemoryBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}MB", self.0 / (1024 * 1024))
+    }
+}
+
+impl_scalar_serde!(MemoryBudget, u64);
+
+/// Host-derived defaults for `--max-threads`/`--memory-map-threshold-mb`,
+/// for callers that want `ParallelConfig::auto`'s sizing logic without
+/// going through the rest of its derivation (e.g. a CLI that builds its
+/// own `ParallelConfig` from individually-resolved flags). Detection never
+/// fails outright: `max_threads` falls back to `ThreadCount::default()`'s
+/// own `num_cpus::get()`, and `memory_budget_bytes` is `None` on a host
+/// `MemoryBudget::detect` can't read, so callers can fall back to a static
+/// default instead of failing closed.
+#[derive(Debug, Clone, Copy)]
+pub struct ResourceBudget {
+    /// Logical core count, from `num_cpus::get()`.
+    pub max_threads: usize,
+    /// ~Two-thirds of detected available memory, in bytes. `None` when
+    /// `sysinfo` couldn't determine available memory on this host.
+    pub memory_budget_bytes: Option<u64>,
+}
+
+impl ResourceBudget {
+    /// Query the host via `sysinfo` and `num_cpus`.
+    pub fn detect() -> Self {
+        Self {
+            max_threads: num_cpus::get(),
+            memory_budget_bytes: MemoryBudget::detect().map(|budget| budget.get()),
+        }
+    }
+
+    /// `memory_budget_bytes` in whole megabytes, clamped into
+    /// `MemoryMapThreshold`'s valid `[1KB, 1GB]` range the same way
+    /// `ParallelConfig::auto` clamps it. `None` if memory couldn't be
+    /// detected.
+    pub fn memory_map_threshold_mb(&self) -> Option<usize> {
+        self.memory_budget_bytes
+            .map(|bytes| (bytes.clamp(1024, 1024 * 1024 * 1024) / (1024 * 1024)) as usize)
+    }
+}
+
+/// Pins `ParallelConfig::max_threads` worker threads to consecutive physical
+/// cores instead of leaving them for the OS scheduler to migrate freely,
+/// following the `pin_threads: Option<usize>` option gzp exposes on its
+/// parallel compression pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ThreadPinning {
+    /// No pinning; threads run wherever the OS scheduler puts them (the
+    /// historical behavior).
+    #[default]
+    None,
+    /// Pin worker thread `i` to the physical core at index `start_core + i`.
+    StartingAt(usize),
+}
+
 /// Configuration for parallel processing parameters
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParallelConfig {
     pub max_threads: ThreadCount,
     pub chunk_size: ChunkSize,
     pub channel_buffer_size: ChannelBufferSize,
     pub memory_map_threshold: MemoryMapThreshold,
+    /// Byte-chunking behavior content destined for the final codec goes
+    /// through. Defaults to `ChunkingStrategy::Fixed(chunk_size)`; see
+    /// `ChunkingStrategy::ContentDefined` for FastCDC boundary detection.
+    pub chunking_strategy: ChunkingStrategy,
+    /// Core affinity for `max_threads` worker threads. Defaults to
+    /// `ThreadPinning::None`.
+    pub thread_pinning: ThreadPinning,
 }
 
 impl ParallelConfig {
@@ -297,8 +860,66 @@ impl ParallelConfig {
             ));
         }
 
+        if let ChunkingStrategy::ContentDefined(cdc) = self.chunking_strategy {
+            if cdc.max() > self.memory_map_threshold.get() {
+                return Err(CompressionError::config_validation(
+                    "Content-defined chunking max size cannot be larger than memory map threshold",
+                ));
+            }
+        }
+
+        if let ThreadPinning::StartingAt(start_core) = self.thread_pinning {
+            let core_count = num_cpus::get();
+            if start_core + self.max_threads.get() > core_count {
+                return Err(CompressionError::config_validation(format!(
+                    "Thread pinning range [{start_core}, {}) exceeds this machine's {core_count} cores",
+                    start_core + self.max_threads.get(),
+                )));
+            }
+        }
+
         Ok(())
     }
+
+    /// Derive a `ParallelConfig` sized to this machine's available memory,
+    /// instead of `Default`'s static constants. Splits `MemoryBudget::detect`'s
+    /// budget evenly across `max_threads`' worker threads, keeps
+    /// `channel_buffer_size` at its static default, and lets `chunk_size`
+    /// absorb the rest of each thread's share (clamped to `ChunkSize`'s own
+    /// validated range); `memory_map_threshold` is set to the whole budget,
+    /// clamped the same way. Falls back to `ParallelConfig::default()`
+    /// entirely when memory can't be detected, or if the derived values
+    /// would somehow fail `validate()`.
+    pub fn auto() -> Self {
+        let Some(budget) = MemoryBudget::detect() else {
+            return Self::default();
+        };
+
+        let max_threads = ThreadCount::default();
+        let channel_buffer_size = ChannelBufferSize::default();
+        let per_thread_budget = budget.get() / max_threads.get() as u64;
+        let chunk_size_bytes = (per_thread_budget / channel_buffer_size.get() as u64)
+            .clamp(1024, 10 * 1024 * 1024) as usize;
+        let chunk_size = ChunkSize::new(chunk_size_bytes).unwrap_or_default();
+
+        let memory_map_threshold_bytes = budget.get().clamp(1024, 1024 * 1024 * 1024) as usize;
+        let memory_map_threshold = MemoryMapThreshold::new(memory_map_threshold_bytes).unwrap_or_default();
+
+        let config = Self {
+            max_threads,
+            chunk_size,
+            channel_buffer_size,
+            memory_map_threshold,
+            chunking_strategy: ChunkingStrategy::Fixed(chunk_size),
+            thread_pinning: ThreadPinning::default(),
+        };
+
+        if config.validate().is_ok() {
+            config
+        } else {
+            Self::default()
+        }
+    }
 }
 
 impl Default for ParallelConfig {
@@ -308,6 +929,8 @@ impl Default for ParallelConfig {
             chunk_size: ChunkSize::default(),
             channel_buffer_size: ChannelBufferSize::default(),
             memory_map_threshold: MemoryMapThreshold::default(),
+            chunking_strategy: ChunkingStrategy::default(),
+            thread_pinning: ThreadPinning::default(),
         }
     }
 }
@@ -319,6 +942,8 @@ pub struct ParallelConfigBuilder {
     chunk_size: Option<usize>,
     channel_buffer_size: Option<usize>,
     memory_map_threshold: Option<usize>,
+    chunking_strategy: Option<ChunkingStrategy>,
+    thread_pinning: Option<ThreadPinning>,
 }
 
 impl ParallelConfigBuilder {
@@ -351,17 +976,37 @@ impl ParallelConfigBuilder {
         self
     }
 
+    /// Switch to FastCDC content-defined chunking with the given min/avg/max
+    /// byte targets, instead of the default `ChunkingStrategy::Fixed`.
+    pub fn content_defined_chunking(mut self, min: usize, avg: usize, max: usize) -> CompressionResult<Self> {
+        self.chunking_strategy = Some(ChunkingStrategy::ContentDefined(ContentDefinedChunking::new(
+            min, avg, max,
+        )?));
+        Ok(self)
+    }
+
+    /// Pin `max_threads` worker threads to consecutive physical cores
+    /// starting at `start_core`, instead of the default `ThreadPinning::None`.
+    /// `build()` rejects a `start_core` that would run this machine out of
+    /// cores before `max_threads` is satisfied.
+    pub fn pin_threads(mut self, start_core: usize) -> Self {
+        self.thread_pinning = Some(ThreadPinning::StartingAt(start_core));
+        self
+    }
+
     /// Build the parallel configuration with validation
     pub fn build(self) -> CompressionResult<ParallelConfig> {
+        let chunk_size = match self.chunk_size {
+            Some(size) => ChunkSize::new(size)?,
+            None => ChunkSize::default(),
+        };
+
         let config = ParallelConfig {
             max_threads: match self.max_threads {
                 Some(count) => ThreadCount::new(count)?,
                 None => ThreadCount::default(),
             },
-            chunk_size: match self.chunk_size {
-                Some(size) => ChunkSize::new(size)?,
-                None => ChunkSize::default(),
-            },
+            chunk_size,
             channel_buffer_size: match self.channel_buffer_size {
                 Some(size) => ChannelBufferSize::new(size)?,
                 None => ChannelBufferSize::default(),
@@ -370,6 +1015,8 @@ impl ParallelConfigBuilder {
                 Some(threshold) => MemoryMapThreshold::new(threshold)?,
                 None => MemoryMapThreshold::default(),
             },
+            chunking_strategy: self.chunking_strategy.unwrap_or(ChunkingStrategy::Fixed(chunk_size)),
+            thread_pinning: self.thread_pinning.unwrap_or_default(),
         };
 
         config.validate()?;
@@ -377,15 +1024,200 @@ impl ParallelConfigBuilder {
     }
 }
 
+/// Caps `ConfiguredState::analyze()`'s file collection enforces before
+/// dictionary analysis runs, so a pathologically large or numerous target
+/// tree can't exhaust memory before compression even starts. Each cap is
+/// `None` for "unlimited".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompressorLimits {
+    /// Stop collecting once this many files have been read.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_files: Option<usize>,
+    /// Stop collecting once the running total of file content read
+    /// reaches this many bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_total_bytes: Option<u64>,
+    /// Skip any individual file larger than this many bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_file_size: Option<u64>,
+}
+
+impl CompressorLimits {
+    /// Construct explicit limits, bypassing the environment entirely.
+    pub fn new(max_files: Option<usize>, max_total_bytes: Option<u64>, max_file_size: Option<u64>) -> Self {
+        Self {
+            max_files,
+            max_total_bytes,
+            max_file_size,
+        }
+    }
+
+    /// Read `PROMPT_MAX_FILES`, `PROMPT_MAX_TOTAL_BYTES`, and
+    /// `PROMPT_MAX_FILE_SIZE` from the environment, falling back to
+    /// `Self::default()` for any that are unset or fail to parse as an
+    /// integer. `0` in any of them means "unlimited" (`None`), the same
+    /// convention `ZstdLevel`'s builder uses for "use the default".
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            max_files: env_limit("PROMPT_MAX_FILES").unwrap_or(defaults.max_files),
+            max_total_bytes: env_limit("PROMPT_MAX_TOTAL_BYTES").unwrap_or(defaults.max_total_bytes),
+            max_file_size: env_limit("PROMPT_MAX_FILE_SIZE").unwrap_or(defaults.max_file_size),
+        }
+    }
+}
+
+impl Default for CompressorLimits {
+    fn default() -> Self {
+        Self {
+            max_files: Some(1000),
+            max_total_bytes: Some(500 * 1024 * 1024),
+            max_file_size: Some(50 * 1024 * 1024),
+        }
+    }
+}
+
+/// Parse `key` as an integer limit, where `0` means "unlimited". Returns
+/// `None` (as opposed to `Some(None)`) when the variable is unset or not a
+/// valid integer, so the caller can fall back to its own default.
+fn env_limit<T: std::str::FromStr + Default + PartialEq>(key: &str) -> Option<Option<T>> {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse::<T>().ok())
+        .map(|n| if n == T::default() { None } else { Some(n) })
+}
+
+/// Best-effort scalar interpretation of an environment variable's raw value
+/// for `CompressionConfig::from_env`: an integer or float if it parses as
+/// one, `true`/`false` as a bool, and a plain string (e.g. `"zstd/9"`)
+/// otherwise.
+fn parse_env_scalar(raw: &str) -> toml::Value {
+    if let Ok(n) = raw.parse::<i64>() {
+        toml::Value::Integer(n)
+    } else if let Ok(n) = raw.parse::<f64>() {
+        toml::Value::Float(n)
+    } else if let Ok(b) = raw.parse::<bool>() {
+        toml::Value::Boolean(b)
+    } else {
+        toml::Value::String(raw.to_string())
+    }
+}
+
+/// Selects which algorithm `UniversalCompressor::compress`'s Step 3 uses to
+/// shrink each file's content before the `final_codec` pass runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DictionaryStrategy {
+    /// The existing `FrequencyAnalyzer`/`DictionaryBuilder`/`PatternReplacer`
+    /// path: variable-length patterns substituted with variable-length hex
+    /// tokens.
+    #[default]
+    Frequency,
+    /// A single FSST symbol table (see [`crate::compression::FsstCompressor`])
+    /// trained across the whole batch, substituting single-byte codes for
+    /// 1-8 byte symbols. Tends to beat the frequency/dictionary path on
+    /// short, repetitive source lines, where per-pattern hex tokens carry
+    /// too much overhead relative to the text they replace.
+    Fsst,
+    /// Candidates chosen by [`crate::compression::cover::train`] instead of
+    /// `FrequencyAnalyzer::get_frequent_patterns`, ranked by realized
+    /// savings rather than raw occurrence count. Still flows through
+    /// `DictionaryBuilder`/`PatternReplacer` like `Frequency` does - see
+    /// `cover_config` for the tunable sampling parameters.
+    Cover,
+}
+
+/// Tunable parameters for [`crate::compression::cover::train`], the
+/// zstd-COVER-inspired substring sampler used when `dictionary_strategy` is
+/// `DictionaryStrategy::Cover`. Exposed on the builder so callers can trade
+/// dictionary size against compression ratio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoverConfig {
+    /// Shortest candidate substring length sampled.
+    pub k_min: usize,
+    /// Longest candidate substring length sampled.
+    pub k_max: usize,
+    /// Per-entry cost, in bytes, of a dictionary reference (a replacement
+    /// token). A candidate no longer than this can never pay for the token
+    /// that would replace it, so it's never emitted.
+    pub overhead: usize,
+    /// Candidate selection stops once the combined byte length of chosen
+    /// entries reaches this budget.
+    pub dictionary_budget: usize,
+}
+
+impl Default for CoverConfig {
+    fn default() -> Self {
+        Self {
+            k_min: 8,
+            k_max: 64,
+            // Matches a generated hex token's length (e.g. "T0000"), the
+            // thing each selected entry is actually replaced with.
+            overhead: 5,
+            dictionary_budget: 64 * 1024,
+        }
+    }
+}
+
 /// Main configuration structure for compression operations
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompressionConfig {
     pub min_pattern_length: MinPatternLength,
     pub min_frequency_threshold: FrequencyThreshold,
     pub enable_zstd_compression: bool,
     pub zstd_compression_level: ZstdLevel,
+    /// Advanced zstd tuning (strategy, window log, long-distance matching)
+    /// beyond `zstd_compression_level`, applied by `ZstdCompressor` when
+    /// `final_codec` is `Codec::Zstd`. Defaults to `ZstdAdvanced::default()`
+    /// (every knob left at zstd's own level-derived choice).
+    pub zstd_advanced: ZstdAdvanced,
+    /// A dictionary `final_codec`'s zstd stage shares across files instead
+    /// of compressing each one independently. Defaults to
+    /// `ZstdDictionaryConfig::None`. Not to be confused with
+    /// `dictionary_strategy`, which selects the unrelated Step 3
+    /// pattern-substitution pass.
+    pub zstd_dictionary_config: ZstdDictionaryConfig,
+    /// Algorithm `UniversalCompressor::write_archive`'s block layer checksums
+    /// each compressed block with, and `read_block_archive`/
+    /// `extract_block_archive` must be passed to verify them. Defaults to
+    /// `ChecksumConfig::Xxh3_128`.
+    pub checksum_config: ChecksumConfig,
+    /// The `Compressor` backend `UniversalCompressor::compress`'s final
+    /// stage runs each file through, recorded via its `codec_id` so the
+    /// archive is self-describing about which codec produced it. Defaults
+    /// to `Codec::Zstd` (or `Codec::Stored` if `enable_zstd_compression` is
+    /// `false`) unless overridden with `CompressionConfigBuilder::codec`.
+    pub final_codec: Codec,
+    /// Which Step 3 substitution algorithm `compress` runs before
+    /// `final_codec`. Defaults to `DictionaryStrategy::Frequency`.
+    pub dictionary_strategy: DictionaryStrategy,
+    /// Sampling parameters for `crate::compression::cover::train`, used
+    /// when `dictionary_strategy` is `DictionaryStrategy::Cover`. Ignored
+    /// otherwise.
+    pub cover_config: CoverConfig,
+    /// How many leading bytes of a file `FileEntry::from_path` samples when
+    /// confirming `FileTypeRegistry`'s extension-driven guess with
+    /// `file_type::sniff_is_text`.
+    pub text_sniff_sample_len: TextSniffSampleLen,
+    /// Above this proportion of non-UTF-8/non-printable bytes in the
+    /// sample, `file_type::sniff_is_text` classifies a file as binary
+    /// regardless of what its extension suggested.
+    pub max_non_printable_ratio: NonPrintableRatio,
+    /// Extra glob patterns `collect_files_from_archiver`'s directory walk
+    /// excludes, on top of `.gitignore`/`.ignore` rules and the walk's
+    /// always-on `target/`, `node_modules/`, `.git/` exclusions (e.g.
+    /// `"*.lock"` or `"vendor/**"`). Empty by default.
+    pub exclude_globs: Vec<String>,
     #[allow(dead_code)]
     pub parallel_config: ParallelConfig,
+    /// Caps `ConfiguredState::analyze()`'s file collection enforces.
+    /// Defaults to `CompressorLimits::from_env()`.
+    pub collection_limits: CompressorLimits,
+    /// Maximum number of entries `ConfiguredState::compress_chunked()`'s
+    /// incrementally-trained dictionary is allowed to grow to. Once reached,
+    /// later batches' newly-discovered patterns are dropped rather than
+    /// evicting or reassigning tokens already in use, so earlier batches'
+    /// compressed output stays valid. Unused outside chunked compression.
+    pub max_dictionary_entries: usize,
 }
 
 impl CompressionConfig {
@@ -402,8 +1234,61 @@ impl CompressionConfig {
                 "Large pattern lengths require higher frequency thresholds for efficiency",
             ));
         }
+        self.zstd_advanced.validate(self.zstd_compression_level)?;
+        if let ZstdDictionaryConfig::Train { sample_limit, .. } = self.zstd_dictionary_config {
+            if sample_limit == 0 {
+                return Err(CompressionError::config_validation(
+                    "Zstd dictionary training sample limit must be at least 1",
+                ));
+            }
+        }
         Ok(())
     }
+
+    /// Load a `CompressionConfig` from a TOML file at `path`, the same
+    /// knobs `CompressionConfigBuilder` exposes but as a deployment-managed
+    /// config file rather than hardcoded builder calls. Each newtype's
+    /// `Deserialize` impl re-runs its own `new()` validation, so an
+    /// out-of-range file value surfaces the same `config_validation` error
+    /// the builder would; `validate()` then re-checks the cross-field rules
+    /// once the whole struct is assembled.
+    pub fn from_toml_path<P: AsRef<Path>>(path: P) -> CompressionResult<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let config: Self = toml::from_str(&contents)
+            .map_err(|e| CompressionError::config_validation(format!("Invalid config file: {e}")))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Load a `CompressionConfig` from environment variables prefixed with
+    /// `prefix` (e.g. `prefix = "COMPRESS"` reads `COMPRESS_FINAL_CODEC`),
+    /// one per top-level field, named by upper-snake-casing the field name.
+    /// Unset variables keep `CompressionConfig::default()`'s value for that
+    /// field. Each variable's value is parsed as TOML so scalars, strings,
+    /// and the `final_codec`/enum fields all use the same representation
+    /// `from_toml_path` does, then validated the same way.
+    pub fn from_env(prefix: &str) -> CompressionResult<Self> {
+        let default = Self::default();
+        let default_value = toml::Value::try_from(&default)
+            .map_err(|e| CompressionError::config_validation(format!("Invalid default config: {e}")))?;
+        let mut table = match default_value {
+            toml::Value::Table(table) => table,
+            _ => unreachable!("CompressionConfig always serializes to a TOML table"),
+        };
+
+        for key in table.clone().keys() {
+            let var = format!("{prefix}_{}", key.to_ascii_uppercase());
+            if let Ok(raw) = std::env::var(&var) {
+                table.insert(key.clone(), parse_env_scalar(&raw));
+            }
+        }
+
+        let config: Self = toml::Value::Table(table)
+            .try_into()
+            .map_err(|e| CompressionError::config_validation(format!("Invalid environment config: {e}")))?;
+        config.validate()?;
+        Ok(config)
+    }
 }
 
 impl Default for CompressionConfig {
@@ -413,11 +1298,28 @@ impl Default for CompressionConfig {
             min_frequency_threshold: FrequencyThreshold::default(),
             enable_zstd_compression: true,
             zstd_compression_level: ZstdLevel::default(),
+            zstd_advanced: ZstdAdvanced::default(),
+            zstd_dictionary_config: ZstdDictionaryConfig::default(),
+            checksum_config: ChecksumConfig::default(),
+            final_codec: Codec::Zstd(ZstdLevel::default()),
+            dictionary_strategy: DictionaryStrategy::default(),
+            cover_config: CoverConfig::default(),
+            text_sniff_sample_len: TextSniffSampleLen::default(),
+            max_non_printable_ratio: NonPrintableRatio::default(),
+            exclude_globs: Vec::new(),
             parallel_config: ParallelConfig::default(),
+            collection_limits: CompressorLimits::from_env(),
+            max_dictionary_entries: DEFAULT_MAX_DICTIONARY_ENTRIES,
         }
     }
 }
 
+/// Default for `CompressionConfig::max_dictionary_entries` - the same
+/// ceiling `HexTokenGenerator::new()` reaches before rolling over to a new
+/// token prefix, so a run that never hits this cap never risks the
+/// rollover's longer tokens either.
+const DEFAULT_MAX_DICTIONARY_ENTRIES: usize = 65_535;
+
 /// Builder for CompressionConfig with method chaining
 #[derive(Debug, Default)]
 pub struct CompressionConfigBuilder {
@@ -425,7 +1327,18 @@ pub struct CompressionConfigBuilder {
     min_frequency_threshold: Option<usize>,
     enable_zstd_compression: Option<bool>,
     zstd_compression_level: Option<i32>,
+    zstd_advanced: Option<ZstdAdvanced>,
+    zstd_dictionary_config: Option<ZstdDictionaryConfig>,
+    checksum_config: Option<ChecksumConfig>,
+    codec: Option<Codec>,
+    dictionary_strategy: Option<DictionaryStrategy>,
+    cover_config: Option<CoverConfig>,
+    text_sniff_sample_len: Option<usize>,
+    max_non_printable_ratio: Option<f64>,
+    exclude_globs: Option<Vec<String>>,
     parallel_config: Option<ParallelConfig>,
+    collection_limits: Option<CompressorLimits>,
+    max_dictionary_entries: Option<usize>,
 }
 
 impl CompressionConfigBuilder {
@@ -459,14 +1372,109 @@ impl CompressionConfigBuilder {
         self
     }
 
+    /// Set advanced zstd tuning (strategy, window log, long-distance
+    /// matching), instead of `ZstdAdvanced::default()`. Only takes effect
+    /// when `final_codec` is `Codec::Zstd`.
+    pub fn zstd_advanced(mut self, advanced: ZstdAdvanced) -> Self {
+        self.zstd_advanced = Some(advanced);
+        self
+    }
+
+    /// Share a trained dictionary across files in `final_codec`'s zstd
+    /// stage, instead of `ZstdDictionaryConfig::None`. Only takes effect
+    /// when `final_codec` is `Codec::Zstd`.
+    pub fn zstd_dictionary_config(mut self, config: ZstdDictionaryConfig) -> Self {
+        self.zstd_dictionary_config = Some(config);
+        self
+    }
+
+    /// Select the block archive's per-block checksum algorithm, instead of
+    /// the default `ChecksumConfig::Xxh3_128`.
+    pub fn checksum(mut self, config: ChecksumConfig) -> Self {
+        self.checksum_config = Some(config);
+        self
+    }
+
+    /// Override the final-stage `Compressor` backend directly, instead of
+    /// deriving it from `enable_zstd_compression`/`zstd_compression_level`
+    /// (e.g. to select `Codec::Lz4` or the no-op `Codec::Stored`).
+    pub fn codec(mut self, codec: Codec) -> Self {
+        self.codec = Some(codec);
+        self
+    }
+
+    /// Select which Step 3 substitution algorithm `compress` runs, instead
+    /// of the default `DictionaryStrategy::Frequency` path.
+    pub fn dictionary_strategy(mut self, strategy: DictionaryStrategy) -> Self {
+        self.dictionary_strategy = Some(strategy);
+        self
+    }
+
+    /// Override `crate::compression::cover::train`'s sampling parameters,
+    /// instead of `CoverConfig::default()`. Only takes effect when
+    /// `dictionary_strategy` is `DictionaryStrategy::Cover`.
+    pub fn cover_config(mut self, config: CoverConfig) -> Self {
+        self.cover_config = Some(config);
+        self
+    }
+
+    /// Set how many leading bytes `sniff_is_text` samples per file.
+    pub fn text_sniff_sample_len(mut self, len: usize) -> Self {
+        self.text_sniff_sample_len = Some(len);
+        self
+    }
+
+    /// Set the maximum proportion of non-UTF-8/non-printable bytes a
+    /// sample may contain before `sniff_is_text` calls it binary.
+    pub fn max_non_printable_ratio(mut self, ratio: f64) -> Self {
+        self.max_non_printable_ratio = Some(ratio);
+        self
+    }
+
+    /// Set extra glob patterns the directory walk excludes, on top of
+    /// `.gitignore`/`.ignore` rules and the walk's always-on
+    /// `target/`/`node_modules/`/`.git/` exclusions.
+    pub fn exclude_globs(mut self, globs: Vec<String>) -> Self {
+        self.exclude_globs = Some(globs);
+        self
+    }
+
     /// Set parallel configuration
     pub fn parallel_config(mut self, config: ParallelConfig) -> Self {
         self.parallel_config = Some(config);
         self
     }
 
+    /// Override the file-collection caps, instead of
+    /// `CompressorLimits::from_env()`.
+    pub fn collection_limits(mut self, limits: CompressorLimits) -> Self {
+        self.collection_limits = Some(limits);
+        self
+    }
+
+    /// Cap `compress_chunked()`'s incrementally-trained dictionary at this
+    /// many entries, instead of `DEFAULT_MAX_DICTIONARY_ENTRIES`.
+    pub fn max_dictionary_entries(mut self, max_entries: usize) -> Self {
+        self.max_dictionary_entries = Some(max_entries);
+        self
+    }
+
     /// Build the configuration with validation
     pub fn build(self) -> CompressionResult<CompressionConfig> {
+        let enable_zstd_compression = self.enable_zstd_compression.unwrap_or(true);
+        let zstd_compression_level = match self.zstd_compression_level {
+            // 0 means "use the default level" rather than an out-of-range
+            // error, so callers can pass through an unset/zeroed config
+            // value without having to special-case it themselves.
+            Some(0) | None => ZstdLevel::default(),
+            Some(level) => ZstdLevel::new(level)?,
+        };
+        let final_codec = self.codec.unwrap_or(if enable_zstd_compression {
+            Codec::Zstd(zstd_compression_level)
+        } else {
+            Codec::Stored
+        });
+
         let config = CompressionConfig {
             min_pattern_length: match self.min_pattern_length {
                 Some(length) => MinPatternLength::new(length)?,
@@ -476,12 +1484,26 @@ impl CompressionConfigBuilder {
                 Some(threshold) => FrequencyThreshold::new(threshold)?,
                 None => FrequencyThreshold::default(),
             },
-            enable_zstd_compression: self.enable_zstd_compression.unwrap_or(true),
-            zstd_compression_level: match self.zstd_compression_level {
-                Some(level) => ZstdLevel::new(level)?,
-                None => ZstdLevel::default(),
+            enable_zstd_compression,
+            zstd_compression_level,
+            zstd_advanced: self.zstd_advanced.unwrap_or_default(),
+            zstd_dictionary_config: self.zstd_dictionary_config.unwrap_or_default(),
+            checksum_config: self.checksum_config.unwrap_or_default(),
+            final_codec,
+            dictionary_strategy: self.dictionary_strategy.unwrap_or_default(),
+            cover_config: self.cover_config.unwrap_or_default(),
+            text_sniff_sample_len: match self.text_sniff_sample_len {
+                Some(len) => TextSniffSampleLen::new(len)?,
+                None => TextSniffSampleLen::default(),
             },
+            max_non_printable_ratio: match self.max_non_printable_ratio {
+                Some(ratio) => NonPrintableRatio::new(ratio)?,
+                None => NonPrintableRatio::default(),
+            },
+            exclude_globs: self.exclude_globs.unwrap_or_default(),
             parallel_config: self.parallel_config.unwrap_or_default(),
+            collection_limits: self.collection_limits.unwrap_or_else(CompressorLimits::from_env),
+            max_dictionary_entries: self.max_dictionary_entries.unwrap_or(DEFAULT_MAX_DICTIONARY_ENTRIES),
         };
 
         config.validate()?;
@@ -528,6 +1550,217 @@ mod tests {
         assert_eq!(config.min_frequency_threshold.get(), 4);
         assert!(!config.enable_zstd_compression);
         assert_eq!(config.zstd_compression_level.get(), 6);
+        assert_eq!(config.final_codec, Codec::Stored);
+    }
+
+    #[test]
+    fn test_final_codec_defaults_from_zstd_settings() {
+        let config = CompressionConfig::builder()
+            .enable_zstd_compression(true)
+            .zstd_compression_level(7)
+            .build()
+            .unwrap();
+        assert_eq!(config.final_codec, Codec::Zstd(ZstdLevel::new(7).unwrap()));
+    }
+
+    #[test]
+    fn test_final_codec_override_takes_precedence() {
+        let config = CompressionConfig::builder()
+            .enable_zstd_compression(true)
+            .codec(Codec::Lz4(Lz4Level::default()))
+            .build()
+            .unwrap();
+        assert_eq!(config.final_codec, Codec::Lz4(Lz4Level::default()));
+    }
+
+    #[test]
+    fn test_window_log_validation() {
+        assert!(WindowLog::new(9).is_err());
+        assert!(WindowLog::new(32).is_err());
+        assert_eq!(WindowLog::new(27).unwrap().get(), 27);
+    }
+
+    #[test]
+    fn test_zstd_advanced_defaults_to_no_tuning() {
+        let advanced = ZstdAdvanced::default();
+        assert_eq!(advanced.strategy, None);
+        assert_eq!(advanced.window_log, None);
+        assert!(!advanced.enable_long_distance_matching);
+        assert!(advanced.validate(ZstdLevel::default()).is_ok());
+    }
+
+    #[test]
+    fn test_zstd_advanced_long_distance_matching_requires_window_log() {
+        let advanced = ZstdAdvanced {
+            enable_long_distance_matching: true,
+            ..Default::default()
+        };
+        assert!(advanced.validate(ZstdLevel::default()).is_err());
+
+        let advanced = ZstdAdvanced {
+            enable_long_distance_matching: true,
+            window_log: Some(WindowLog::new(26).unwrap()),
+            ..Default::default()
+        };
+        assert!(advanced.validate(ZstdLevel::default()).is_err());
+
+        let advanced = ZstdAdvanced {
+            enable_long_distance_matching: true,
+            window_log: Some(WindowLog::new(27).unwrap()),
+            ..Default::default()
+        };
+        assert!(advanced.validate(ZstdLevel::default()).is_ok());
+    }
+
+    #[test]
+    fn test_zstd_advanced_ultra_strategy_requires_high_level() {
+        let advanced = ZstdAdvanced {
+            strategy: Some(ZstdStrategy::BtUltra2),
+            ..Default::default()
+        };
+        assert!(advanced.validate(ZstdLevel::new(19).unwrap()).is_err());
+        assert!(advanced.validate(ZstdLevel::new(20).unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_compression_config_builder_zstd_advanced() {
+        let advanced = ZstdAdvanced {
+            strategy: Some(ZstdStrategy::BtOpt),
+            window_log: Some(WindowLog::new(24).unwrap()),
+            enable_long_distance_matching: false,
+        };
+        let config = CompressionConfig::builder().zstd_advanced(advanced).build().unwrap();
+        assert_eq!(config.zstd_advanced, advanced);
+    }
+
+    #[test]
+    fn test_compression_config_rejects_invalid_zstd_advanced() {
+        let advanced = ZstdAdvanced {
+            strategy: Some(ZstdStrategy::BtUltra),
+            ..Default::default()
+        };
+        let result = CompressionConfig::builder()
+            .zstd_compression_level(3)
+            .zstd_advanced(advanced)
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_dict_size_validation() {
+        assert!(DictSize::new(1023).is_err());
+        assert!(DictSize::new(1024 * 1024 + 1).is_err());
+        assert_eq!(DictSize::new(64 * 1024).unwrap().get(), 64 * 1024);
+    }
+
+    #[test]
+    fn test_zstd_dictionary_config_defaults_to_none() {
+        let config = CompressionConfig::default();
+        assert_eq!(config.zstd_dictionary_config, ZstdDictionaryConfig::None);
+    }
+
+    #[test]
+    fn test_compression_config_builder_zstd_dictionary_config() {
+        let config = CompressionConfig::builder()
+            .zstd_dictionary_config(ZstdDictionaryConfig::Train {
+                sample_limit: 200,
+                dict_size: DictSize::default(),
+            })
+            .build()
+            .unwrap();
+        assert_eq!(
+            config.zstd_dictionary_config,
+            ZstdDictionaryConfig::Train {
+                sample_limit: 200,
+                dict_size: DictSize::default(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_compression_config_rejects_zero_sample_limit() {
+        let result = CompressionConfig::builder()
+            .zstd_dictionary_config(ZstdDictionaryConfig::Train {
+                sample_limit: 0,
+                dict_size: DictSize::default(),
+            })
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_checksum_config_defaults_to_xxh3_128() {
+        let config = CompressionConfig::default();
+        assert_eq!(config.checksum_config, ChecksumConfig::Xxh3_128);
+        assert_eq!(config.checksum_config.checksum_len(), 16);
+    }
+
+    #[test]
+    fn test_checksum_config_lengths() {
+        assert_eq!(ChecksumConfig::None.checksum_len(), 0);
+        assert_eq!(ChecksumConfig::Xxh3_64.checksum_len(), 8);
+        assert_eq!(ChecksumConfig::Xxh3_128.checksum_len(), 16);
+    }
+
+    #[test]
+    fn test_compression_config_builder_checksum() {
+        let config = CompressionConfig::builder().checksum(ChecksumConfig::Xxh3_64).build().unwrap();
+        assert_eq!(config.checksum_config, ChecksumConfig::Xxh3_64);
+    }
+
+    #[test]
+    fn test_dictionary_strategy_defaults_to_frequency() {
+        let config = CompressionConfig::default();
+        assert_eq!(config.dictionary_strategy, DictionaryStrategy::Frequency);
+    }
+
+    #[test]
+    fn test_dictionary_strategy_override() {
+        let config = CompressionConfig::builder()
+            .dictionary_strategy(DictionaryStrategy::Fsst)
+            .build()
+            .unwrap();
+        assert_eq!(config.dictionary_strategy, DictionaryStrategy::Fsst);
+    }
+
+    #[test]
+    fn test_text_sniff_sample_len_validation() {
+        assert!(TextSniffSampleLen::new(8).is_err());
+        assert!(TextSniffSampleLen::new(8192).is_ok());
+        assert!(TextSniffSampleLen::new(2 * 1024 * 1024).is_err());
+    }
+
+    #[test]
+    fn test_non_printable_ratio_validation() {
+        assert!(NonPrintableRatio::new(-0.1).is_err());
+        assert!(NonPrintableRatio::new(0.3).is_ok());
+        assert!(NonPrintableRatio::new(1.1).is_err());
+    }
+
+    #[test]
+    fn test_text_sniff_thresholds_override() {
+        let config = CompressionConfig::builder()
+            .text_sniff_sample_len(1024)
+            .max_non_printable_ratio(0.1)
+            .build()
+            .unwrap();
+        assert_eq!(config.text_sniff_sample_len.get(), 1024);
+        assert_eq!(config.max_non_printable_ratio.get(), 0.1);
+    }
+
+    #[test]
+    fn test_exclude_globs_default_empty() {
+        let config = CompressionConfig::default();
+        assert!(config.exclude_globs.is_empty());
+    }
+
+    #[test]
+    fn test_exclude_globs_override() {
+        let config = CompressionConfig::builder()
+            .exclude_globs(vec!["*.lock".to_string(), "vendor/**".to_string()])
+            .build()
+            .unwrap();
+        assert_eq!(config.exclude_globs, vec!["*.lock".to_string(), "vendor/**".to_string()]);
     }
 
     #[test]
@@ -540,6 +1773,15 @@ mod tests {
         assert!(config.is_err());
     }
 
+    #[test]
+    fn test_zstd_compression_level_zero_means_default() {
+        let config = CompressionConfig::builder()
+            .zstd_compression_level(0)
+            .build()
+            .unwrap();
+        assert_eq!(config.zstd_compression_level, ZstdLevel::default());
+    }
+
     #[test]
     fn test_default_config() {
         let config = CompressionConfig::default();
@@ -663,6 +1905,81 @@ mod tests {
 
         // Validate default config is valid
         assert!(config.validate().is_ok());
+        assert_eq!(config.chunking_strategy, ChunkingStrategy::Fixed(config.chunk_size));
+    }
+
+    #[test]
+    fn test_memory_budget_rejects_zero() {
+        assert!(MemoryBudget::new(0).is_err());
+        assert_eq!(MemoryBudget::new(1024 * 1024 * 1024).unwrap().get(), 1024 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_parallel_config_auto_produces_a_valid_config() {
+        // Whether or not `sysinfo` can detect memory in the test
+        // environment, `auto()` must never produce a config that fails its
+        // own cross-field validation.
+        let config = ParallelConfig::auto();
+        assert!(config.validate().is_ok());
+        assert!(config.max_threads.get() > 0);
+    }
+
+    #[test]
+    fn test_content_defined_chunking_requires_ordered_bounds() {
+        assert!(ContentDefinedChunking::new(8192, 2048, 16384).is_err()); // min > avg
+        assert!(ContentDefinedChunking::new(2048, 8192, 4096).is_err()); // avg > max
+        assert!(ContentDefinedChunking::new(0, 2048, 16384).is_err()); // min == 0
+        assert!(ContentDefinedChunking::new(2048, 3000, 16384).is_err()); // avg not a power of two
+
+        let cdc = ContentDefinedChunking::new(2048, 8192, 16384).unwrap();
+        assert_eq!((cdc.min(), cdc.avg(), cdc.max()), (2048, 8192, 16384));
+    }
+
+    #[test]
+    fn test_parallel_config_builder_content_defined_chunking() {
+        let config = ParallelConfig::builder()
+            .content_defined_chunking(2048, 8192, 16384)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            config.chunking_strategy,
+            ChunkingStrategy::ContentDefined(ContentDefinedChunking::new(2048, 8192, 16384).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_parallel_config_rejects_content_defined_max_over_memory_map_threshold() {
+        let config = ParallelConfig::builder()
+            .content_defined_chunking(2048, 8192, 16 * 1024 * 1024)
+            .unwrap()
+            .memory_map_threshold(1024 * 1024)
+            .build();
+
+        assert!(config.is_err());
+    }
+
+    #[test]
+    fn test_parallel_config_defaults_to_no_thread_pinning() {
+        let config = ParallelConfig::default();
+        assert_eq!(config.thread_pinning, ThreadPinning::None);
+    }
+
+    #[test]
+    fn test_parallel_config_builder_pin_threads() {
+        let config = ParallelConfig::builder().max_threads(2).pin_threads(0).build().unwrap();
+        assert_eq!(config.thread_pinning, ThreadPinning::StartingAt(0));
+    }
+
+    #[test]
+    fn test_parallel_config_rejects_pinning_range_past_core_count() {
+        let config = ParallelConfig::builder()
+            .max_threads(num_cpus::get())
+            .pin_threads(1)
+            .build();
+
+        assert!(config.is_err());
     }
 
     #[test]
@@ -698,4 +2015,79 @@ mod tests {
         let threshold = MemoryMapThreshold::new(2 * 1024 * 1024).unwrap();
         assert_eq!(format!("{}", threshold), "2MB");
     }
+
+    #[test]
+    fn test_zstd_level_serde_round_trips() {
+        let level = ZstdLevel::new(12).unwrap();
+        let serialized = serde_json::to_string(&level).unwrap();
+        assert_eq!(serialized, "12");
+        let deserialized: ZstdLevel = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(level, deserialized);
+    }
+
+    #[test]
+    fn test_zstd_level_deserialize_rejects_out_of_range_value() {
+        let err = serde_json::from_str::<ZstdLevel>("100").unwrap_err();
+        // Re-runs ZstdLevel::new's own validation, so this is the same
+        // rejection the builder gives for an out-of-range level.
+        assert!(err.to_string().contains("between 1 and 22"));
+    }
+
+    #[test]
+    fn test_content_defined_chunking_serde_round_trips() {
+        let cdc = ContentDefinedChunking::new(1024, 4096, 8192).unwrap();
+        let serialized = toml::to_string(&cdc).unwrap();
+        let deserialized: ContentDefinedChunking = toml::from_str(&serialized).unwrap();
+        assert_eq!(cdc, deserialized);
+    }
+
+    #[test]
+    fn test_content_defined_chunking_deserialize_rejects_invalid_ordering() {
+        let err = toml::from_str::<ContentDefinedChunking>("min = 100\navg = 50\nmax = 200\n").unwrap_err();
+        assert!(err.to_string().contains("min <= avg <= max"));
+    }
+
+    #[test]
+    fn test_compression_config_from_toml_path_round_trips_default() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("compress.toml");
+        let config = CompressionConfig::default();
+        std::fs::write(&path, toml::to_string(&config).unwrap()).unwrap();
+
+        let loaded = CompressionConfig::from_toml_path(&path).unwrap();
+        assert_eq!(loaded.min_pattern_length, config.min_pattern_length);
+        assert_eq!(loaded.final_codec, config.final_codec);
+        assert_eq!(loaded.checksum_config, config.checksum_config);
+    }
+
+    #[test]
+    fn test_compression_config_from_toml_path_surfaces_validation_errors() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("compress.toml");
+        let mut config = CompressionConfig::default();
+        config.zstd_compression_level = ZstdLevel::new(22).unwrap();
+        let mut contents = toml::to_string(&config).unwrap();
+        // Corrupt a valid field past its newtype's own range so the
+        // rejection has to come from Deserialize re-running `new()`.
+        contents = contents.replace("min_pattern_length = 4", "min_pattern_length = 0");
+        std::fs::write(&path, contents).unwrap();
+
+        let err = CompressionConfig::from_toml_path(&path).unwrap_err();
+        assert!(matches!(err, CompressionError::ConfigValidation { .. }));
+    }
+
+    #[test]
+    fn test_compression_config_from_env_overrides_defaults() {
+        let prefix = "TEST_COMPRESS_FROM_ENV";
+        std::env::set_var(format!("{prefix}_MIN_PATTERN_LENGTH"), "10");
+        std::env::set_var(format!("{prefix}_FINAL_CODEC"), "lz4/5");
+
+        let config = CompressionConfig::from_env(prefix).unwrap();
+
+        std::env::remove_var(format!("{prefix}_MIN_PATTERN_LENGTH"));
+        std::env::remove_var(format!("{prefix}_FINAL_CODEC"));
+
+        assert_eq!(config.min_pattern_length.get(), 10);
+        assert_eq!(config.final_codec, Codec::Lz4(Lz4Level::new(5).unwrap()));
+    }
 }
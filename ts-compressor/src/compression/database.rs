@@ -3,12 +3,50 @@
 //! Provides SQLite-based storage for compression patterns, checkpoints, and
 //! compression statistics with ACID transactions and data integrity.
 
+use crate::compression::codec::Codec;
+use crate::compression::framed::{compress_framed, decompress_framed};
 use crate::compression::{CompressionError, CompressionResult};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fs;
 use std::path::Path;
 use std::time::SystemTime;
-use tracing::{debug, error, info, instrument};
+use tracing::{debug, error, info, instrument, warn};
+
+/// Largest key this store accepts for a single record (a chunk hash or a
+/// pattern string) -- mirrors the per-field bounds `validate_compression_config`
+/// enforces on the CLI side, but for writes landing directly in the store.
+pub const MAX_KEY_BYTES: usize = 4 * 1024;
+
+/// Largest serialized value this store accepts for a single record (one
+/// chunk's compressed bytes, or one pattern entry).
+pub const MAX_VALUE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Largest combined size of all values in one atomic batch write (e.g. one
+/// `save_patterns` call). A batch over this limit is rejected outright
+/// rather than partially written, since this placeholder store has no way
+/// to roll back a partial insert.
+pub const MAX_BATCH_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Reject a write whose key or value exceeds the store's per-record limits.
+/// `what` names the record kind in the error message (e.g. `"chunk"`,
+/// `"pattern"`), matching the precise `too large for write (max N bytes)`
+/// wording callers can match on.
+fn check_record_size(what: &str, key_bytes: usize, value_bytes: usize) -> CompressionResult<()> {
+    if key_bytes > MAX_KEY_BYTES {
+        return Err(CompressionError::config_validation(format!(
+            "{} key too large for write (max {} bytes), got {} bytes",
+            what, MAX_KEY_BYTES, key_bytes
+        )));
+    }
+    if value_bytes > MAX_VALUE_BYTES {
+        return Err(CompressionError::config_validation(format!(
+            "{} value too large for write (max {} bytes), got {} bytes",
+            what, MAX_VALUE_BYTES, value_bytes
+        )));
+    }
+    Ok(())
+}
 
 /// Checkpoint metadata for resumable compression
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +59,16 @@ pub struct CompressionCheckpoint {
     pub patterns_found: usize,
     pub compression_config: String, // JSON-serialized config
     pub status: CheckpointStatus,
+    /// Every content-defined chunk hash emitted so far (cumulative across
+    /// every file processed up to `processed_files`), so a resumed pass
+    /// can pick its running chunk count back up and `checkpoint_chunks_intact`
+    /// can confirm none of them have gone missing from the chunk store.
+    pub chunk_hashes: Vec<String>,
+    /// Hash of `compression_config`, so a resumed pass can refuse to
+    /// continue against a checkpoint whose config no longer matches --
+    /// resuming with a different chunk-size target would cut boundaries
+    /// inconsistent with the chunks already stored.
+    pub config_fingerprint: String,
 }
 
 /// Status of a compression checkpoint
@@ -51,11 +99,27 @@ pub struct CompressionDatabase {
     db_path: std::path::PathBuf,
     checkpoints: HashMap<i64, CompressionCheckpoint>,
     patterns: HashMap<i64, Vec<PatternEntry>>,
+    /// Content-defined chunks, keyed by hash, shared across every
+    /// checkpoint -- the persisted counterpart to `dedup::ChunkPool`'s
+    /// in-memory interning, so a chunk already stored under one checkpoint
+    /// is never re-stored by a later one. Each value is a `framed`
+    /// self-describing frame, so chunks compressed under different
+    /// `chunk_codec` settings across runs still decompress correctly.
+    chunks: HashMap<String, Vec<u8>>,
+    /// Codec `store_chunk` compresses new chunk bytes with before writing
+    /// them to `chunks`. Defaults to `Codec::Stored` (no-op), matching this
+    /// store's behavior before chunk compression existed.
+    chunk_codec: Codec,
     next_id: i64,
 }
 
 impl CompressionDatabase {
-    /// Create or open database at specified path
+    /// Create or open database at specified path, loading any existing
+    /// checkpoints/patterns/chunks from it via `import_checkpoints` so a
+    /// later `resume_compression` call sees state a previous process run
+    /// already wrote with `save`. A file that can't be read or parsed is
+    /// treated as absent (with a warning) rather than aborting, since a
+    /// corrupt database shouldn't block starting a fresh one.
     pub fn new<P: AsRef<Path>>(db_path: P) -> CompressionResult<Self> {
         let db_path = db_path.as_ref().to_path_buf();
 
@@ -69,17 +133,122 @@ impl CompressionDatabase {
             })?;
         }
 
-        let db = Self {
+        let mut db = Self {
             db_path,
             checkpoints: HashMap::new(),
             patterns: HashMap::new(),
+            chunks: HashMap::new(),
+            chunk_codec: Codec::Stored,
             next_id: 1,
         };
 
+        if db.db_path.exists() {
+            match fs::read_to_string(&db.db_path) {
+                Ok(json) if json.trim().is_empty() => {}
+                Ok(json) => {
+                    if let Err(e) = db.import_checkpoints(&json) {
+                        warn!(path = %db.db_path.display(), error = %e, "Failed to load existing database, starting fresh");
+                    } else {
+                        info!(path = %db.db_path.display(), "Database loaded from disk");
+                        return Ok(db);
+                    }
+                }
+                Err(e) => {
+                    warn!(path = %db.db_path.display(), error = %e, "Failed to read existing database file, starting fresh");
+                }
+            }
+        }
+
         info!("Database initialized at: {}", db.db_path.display());
         Ok(db)
     }
 
+    /// Write the current in-memory state to `db_path` as JSON, the same
+    /// format `export_checkpoints` produces. Since this placeholder store
+    /// keeps everything in memory, the whole dataset is rewritten on every
+    /// save rather than appended -- simple, and fine at the checkpoint/
+    /// chunk volumes this crate's own tests and CLI usage exercise.
+    ///
+    /// Written via a sibling temp file plus an atomic rename rather than a
+    /// direct `fs::write`, so a crash or kill partway through (e.g. during
+    /// `clean_checkpoints_with_policy`) leaves the previous, still-valid
+    /// file in place instead of a half-written one.
+    pub fn save(&self) -> CompressionResult<()> {
+        let json = self.export_checkpoints()?;
+        let tmp_path = self.db_path.with_extension("tmp");
+        fs::write(&tmp_path, json).map_err(|e| {
+            CompressionError::config_validation(format!(
+                "Failed to write database to temp file: {}",
+                e
+            ))
+        })?;
+        fs::rename(&tmp_path, &self.db_path).map_err(|e| {
+            CompressionError::config_validation(format!("Failed to finalize database write: {}", e))
+        })
+    }
+
+    /// Most recently created checkpoint, if any -- what `resume_compression`
+    /// resumes from when no specific id is given. Ties (possible given
+    /// `SystemTime::now()`'s resolution under a tight per-file loop) are
+    /// broken by id, which strictly increases with insertion order.
+    pub fn latest_checkpoint(&self) -> Option<CompressionCheckpoint> {
+        self.checkpoints
+            .values()
+            .max_by_key(|c| (c.created_at, c.id))
+            .cloned()
+    }
+
+    /// Set the codec `store_chunk` compresses new chunk bytes with. Chunks
+    /// already stored under a different codec are left as-is -- each is
+    /// self-describing via `framed`, so mixing codecs across a database's
+    /// lifetime is safe.
+    pub fn set_chunk_codec(&mut self, codec: Codec) {
+        self.chunk_codec = codec;
+    }
+
+    /// Store a content-defined chunk keyed by its hash, compressed with
+    /// `chunk_codec`. A no-op if a chunk with that hash is already stored.
+    /// Rejects the write up front if the hash or the compressed frame
+    /// exceeds this store's per-record limits (see `check_record_size`),
+    /// before anything is inserted.
+    pub fn store_chunk(&mut self, hash: &str, bytes: &[u8]) -> CompressionResult<()> {
+        if !self.chunks.contains_key(hash) {
+            let frame = compress_framed(bytes, self.chunk_codec)?;
+            check_record_size("chunk", hash.len(), frame.len())?;
+            self.chunks.insert(hash.to_string(), frame);
+        }
+        Ok(())
+    }
+
+    /// Load and decompress a chunk previously written by `store_chunk`.
+    pub fn load_chunk(&self, hash: &str) -> CompressionResult<Option<Vec<u8>>> {
+        self.chunks
+            .get(hash)
+            .map(|frame| decompress_framed(frame))
+            .transpose()
+    }
+
+    /// Whether a chunk with this hash is already stored.
+    pub fn has_chunk(&self, hash: &str) -> bool {
+        self.chunks.contains_key(hash)
+    }
+
+    /// Number of distinct chunks stored.
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Whether every chunk hash `checkpoint` references is still present
+    /// in the chunk store. `resume_compression` refuses to resume
+    /// otherwise, since a missing chunk means the files already recorded
+    /// can no longer be reconstructed faithfully.
+    pub fn checkpoint_chunks_intact(&self, checkpoint: &CompressionCheckpoint) -> bool {
+        checkpoint
+            .chunk_hashes
+            .iter()
+            .all(|hash| self.chunks.contains_key(hash))
+    }
+
     /// Save a new checkpoint
     #[instrument(skip(self, checkpoint))]
     pub fn save_checkpoint(
@@ -153,31 +322,141 @@ impl CompressionDatabase {
         }
     }
 
-    /// Clean old checkpoints, keeping only the latest N
-    #[instrument(skip(self))]
+    /// Clean old checkpoints, keeping only the latest N. A thin wrapper
+    /// around [`Self::clean_checkpoints_with_policy`] for callers that only
+    /// care about the deleted count.
     pub fn clean_checkpoints(&mut self, keep_count: usize) -> CompressionResult<usize> {
+        self.clean_checkpoints_with_policy(RetentionPolicy::KeepCount(keep_count))
+            .map(|summary| summary.deleted_count)
+    }
+
+    /// Total size in bytes of the (deduplicated) chunks `checkpoint`
+    /// references, measured as stored (i.e. after `chunk_codec`
+    /// compression) -- the real on-disk footprint this checkpoint is
+    /// responsible for, as opposed to the checkpoint record itself, which
+    /// is tiny metadata by comparison.
+    pub fn checkpoint_size_bytes(&self, checkpoint: &CompressionCheckpoint) -> u64 {
+        checkpoint
+            .chunk_hashes
+            .iter()
+            .collect::<std::collections::HashSet<_>>()
+            .into_iter()
+            .filter_map(|hash| self.chunks.get(hash))
+            .map(|bytes| bytes.len() as u64)
+            .sum()
+    }
+
+    /// Delete checkpoints not selected by `policy`, then garbage-collect
+    /// any chunk no longer referenced by a surviving checkpoint, so a
+    /// size- or age-based policy actually reclaims disk space rather than
+    /// only trimming checkpoint metadata. Mutates in-memory state only --
+    /// callers persist the result with a single `save()` afterward, so a
+    /// crash mid-cleanup leaves the on-disk database exactly as it was
+    /// before cleanup started rather than partially cleaned.
+    #[instrument(skip(self))]
+    pub fn clean_checkpoints_with_policy(
+        &mut self,
+        policy: RetentionPolicy,
+    ) -> CompressionResult<CleanupSummary> {
         let mut checkpoints: Vec<_> = self.checkpoints.values().cloned().collect();
         checkpoints.sort_by(|a, b| b.created_at.cmp(&a.created_at));
 
-        if checkpoints.len() <= keep_count {
-            return Ok(0);
-        }
+        let keep_ids: std::collections::HashSet<i64> = match policy {
+            RetentionPolicy::KeepCount(n) => {
+                checkpoints.iter().take(n).filter_map(|c| c.id).collect()
+            }
+            RetentionPolicy::MaxAge(max_age) => checkpoints
+                .iter()
+                .filter(|c| {
+                    SystemTime::now()
+                        .duration_since(c.created_at)
+                        .map(|age| age <= max_age)
+                        .unwrap_or(true) // created_at is in the future (clock skew): keep it
+                })
+                .filter_map(|c| c.id)
+                .collect(),
+            RetentionPolicy::MaxTotalBytes(budget) => {
+                // `checkpoint_size_bytes` only dedupes *within* one
+                // checkpoint; since `store_chunk` is itself
+                // content-addressed, two checkpoints sharing chunks would
+                // otherwise get double-counted against `budget`. Track the
+                // union of hashes kept so far and only charge the budget
+                // for the marginal bytes each additional checkpoint
+                // actually adds.
+                let mut kept = std::collections::HashSet::new();
+                let mut kept_hashes: std::collections::HashSet<&String> =
+                    std::collections::HashSet::new();
+                let mut total = 0u64;
+                for checkpoint in &checkpoints {
+                    let new_hashes: std::collections::HashSet<&String> = checkpoint
+                        .chunk_hashes
+                        .iter()
+                        .filter(|hash| !kept_hashes.contains(hash))
+                        .collect();
+                    let added_size: u64 = new_hashes
+                        .iter()
+                        .filter_map(|hash| self.chunks.get(*hash))
+                        .map(|bytes| bytes.len() as u64)
+                        .sum();
+                    if total + added_size > budget && !kept.is_empty() {
+                        break;
+                    }
+                    if let Some(id) = checkpoint.id {
+                        kept.insert(id);
+                    }
+                    kept_hashes.extend(new_hashes);
+                    total += added_size;
+                }
+                kept
+            }
+        };
 
-        let to_delete = &checkpoints[keep_count..];
-        let mut deleted_count = 0;
+        let to_delete: Vec<i64> = checkpoints
+            .iter()
+            .filter_map(|c| c.id)
+            .filter(|id| !keep_ids.contains(id))
+            .collect();
+        let deleted_count = to_delete.len();
 
-        for checkpoint in to_delete {
-            if let Some(id) = checkpoint.id {
-                self.delete_checkpoint(id)?;
-                deleted_count += 1;
+        for id in to_delete {
+            self.delete_checkpoint(id)?;
+        }
+
+        let live_hashes: std::collections::HashSet<&String> = self
+            .checkpoints
+            .values()
+            .flat_map(|c| c.chunk_hashes.iter())
+            .collect();
+        let orphaned_hashes: Vec<String> = self
+            .chunks
+            .keys()
+            .filter(|hash| !live_hashes.contains(hash))
+            .cloned()
+            .collect();
+
+        let mut reclaimed_bytes = 0u64;
+        for hash in orphaned_hashes {
+            if let Some(bytes) = self.chunks.remove(&hash) {
+                reclaimed_bytes += bytes.len() as u64;
             }
         }
 
-        info!(deleted_count = deleted_count, "Old checkpoints cleaned");
-        Ok(deleted_count)
+        info!(
+            deleted_count = deleted_count,
+            reclaimed_bytes = reclaimed_bytes,
+            "Checkpoints cleaned"
+        );
+        Ok(CleanupSummary {
+            deleted_count,
+            reclaimed_bytes,
+        })
     }
 
-    /// Save patterns for a checkpoint
+    /// Save patterns for a checkpoint. Every entry is validated against the
+    /// per-record limits, and the batch's combined size against
+    /// `MAX_BATCH_BYTES`, before any entry is inserted -- one oversized
+    /// pattern (or an overly large batch of otherwise-fine ones) fails the
+    /// whole call rather than leaving a partially-written checkpoint.
     #[instrument(skip(self, patterns))]
     pub fn save_patterns(
         &mut self,
@@ -186,6 +465,7 @@ impl CompressionDatabase {
     ) -> CompressionResult<()> {
         let now = SystemTime::now();
         let mut pattern_entries = Vec::new();
+        let mut batch_bytes: u64 = 0;
 
         for (pattern, frequency, token) in patterns {
             let entry = PatternEntry {
@@ -197,9 +477,26 @@ impl CompressionDatabase {
                 first_seen: now,
                 last_used: now,
             };
+            let entry_bytes = serde_json::to_vec(&entry)
+                .map_err(|e| {
+                    CompressionError::config_validation(format!(
+                        "Failed to serialize pattern entry: {}",
+                        e
+                    ))
+                })?
+                .len();
+            check_record_size("pattern", pattern.len(), entry_bytes)?;
+            batch_bytes += entry_bytes as u64;
             pattern_entries.push(entry);
         }
 
+        if batch_bytes > MAX_BATCH_BYTES {
+            return Err(CompressionError::config_validation(format!(
+                "pattern batch too large for write (max {} bytes), got {} bytes",
+                MAX_BATCH_BYTES, batch_bytes
+            )));
+        }
+
         self.patterns.insert(checkpoint_id, pattern_entries);
 
         debug!(
@@ -251,6 +548,7 @@ impl CompressionDatabase {
             total_checkpoints,
             total_patterns,
             completed_checkpoints,
+            total_chunks: self.chunks.len(),
         }
     }
 
@@ -296,6 +594,7 @@ impl CompressionDatabase {
         let export_data = DatabaseExport {
             checkpoints: self.checkpoints.values().cloned().collect(),
             patterns: self.patterns.clone(),
+            chunks: self.chunks.clone(),
         };
 
         serde_json::to_string_pretty(&export_data).map_err(|e| {
@@ -323,6 +622,10 @@ impl CompressionDatabase {
             self.patterns.insert(checkpoint_id, patterns);
         }
 
+        for (hash, bytes) in import_data.chunks {
+            self.chunks.entry(hash).or_insert(bytes);
+        }
+
         info!("Checkpoints imported successfully");
         Ok(())
     }
@@ -334,6 +637,42 @@ pub struct DatabaseStatistics {
     pub total_checkpoints: usize,
     pub total_patterns: usize,
     pub completed_checkpoints: usize,
+    pub total_chunks: usize,
+}
+
+/// How [`CompressionDatabase::clean_checkpoints_with_policy`] selects which
+/// checkpoints survive a cleanup pass. Exactly one policy applies per call
+/// -- the CLI's `checkpoint clean` command picks one from its flags rather
+/// than intersecting several, since "keep the 5 newest" and "keep
+/// everything under 500MB" can disagree about which checkpoints that is.
+#[derive(Debug, Clone, Copy)]
+pub enum RetentionPolicy {
+    /// Keep only the N most recently created checkpoints.
+    KeepCount(usize),
+    /// Keep only checkpoints created within this duration of now.
+    MaxAge(std::time::Duration),
+    /// Keep the newest checkpoints whose cumulative chunk size (see
+    /// `checkpoint_size_bytes`) stays at or under this many bytes. Always
+    /// keeps at least the single newest checkpoint, even if it alone
+    /// exceeds the budget.
+    MaxTotalBytes(u64),
+}
+
+/// Result of a [`CompressionDatabase::clean_checkpoints_with_policy`] call.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CleanupSummary {
+    pub deleted_count: usize,
+    pub reclaimed_bytes: u64,
+}
+
+impl std::fmt::Display for CleanupSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} checkpoint(s) deleted, {} byte(s) reclaimed",
+            self.deleted_count, self.reclaimed_bytes
+        )
+    }
 }
 
 /// Export/import data structure
@@ -341,6 +680,7 @@ pub struct DatabaseStatistics {
 struct DatabaseExport {
     checkpoints: Vec<CompressionCheckpoint>,
     patterns: HashMap<i64, Vec<PatternEntry>>,
+    chunks: HashMap<String, Vec<u8>>,
 }
 
 impl std::fmt::Display for CheckpointStatus {
@@ -360,6 +700,7 @@ impl std::fmt::Display for DatabaseStatistics {
         writeln!(f, "  Total checkpoints: {}", self.total_checkpoints)?;
         writeln!(f, "  Completed checkpoints: {}", self.completed_checkpoints)?;
         writeln!(f, "  Total patterns: {}", self.total_patterns)?;
+        writeln!(f, "  Total chunks: {}", self.total_chunks)?;
         Ok(())
     }
 }
@@ -394,6 +735,8 @@ mod tests {
             patterns_found: 25,
             compression_config: "{}".to_string(),
             status: CheckpointStatus::InProgress,
+            chunk_hashes: Vec::new(),
+            config_fingerprint: "fp".to_string(),
         };
 
         // Save checkpoint
@@ -439,6 +782,8 @@ mod tests {
             patterns_found: 3,
             compression_config: "{}".to_string(),
             status: CheckpointStatus::Completed,
+            chunk_hashes: Vec::new(),
+            config_fingerprint: "fp".to_string(),
         };
 
         let checkpoint_id = db.save_checkpoint(&checkpoint).unwrap();
@@ -477,6 +822,8 @@ mod tests {
                 patterns_found: 25 + i,
                 compression_config: "{}".to_string(),
                 status: CheckpointStatus::InProgress,
+                chunk_hashes: Vec::new(),
+                config_fingerprint: "fp".to_string(),
             };
             db.save_checkpoint(&checkpoint).unwrap();
         }
@@ -502,6 +849,8 @@ mod tests {
                 patterns_found: 25,
                 compression_config: "{}".to_string(),
                 status: CheckpointStatus::Completed,
+                chunk_hashes: Vec::new(),
+                config_fingerprint: "fp".to_string(),
             };
             db.save_checkpoint(&checkpoint).unwrap();
         }
@@ -530,6 +879,8 @@ mod tests {
             patterns_found: 2,
             compression_config: "{}".to_string(),
             status: CheckpointStatus::Completed,
+            chunk_hashes: Vec::new(),
+            config_fingerprint: "fp".to_string(),
         };
 
         let checkpoint_id = db.save_checkpoint(&checkpoint).unwrap();
@@ -567,6 +918,8 @@ mod tests {
             patterns_found: 2,
             compression_config: "{}".to_string(),
             status: CheckpointStatus::Completed,
+            chunk_hashes: Vec::new(),
+            config_fingerprint: "fp".to_string(),
         };
 
         let checkpoint_id = db.save_checkpoint(&checkpoint).unwrap();
@@ -596,4 +949,144 @@ mod tests {
         let imported_patterns = db2.load_patterns(checkpoint_id).unwrap();
         assert_eq!(imported_patterns.len(), 2);
     }
+
+    fn checkpoint_with(created_at: SystemTime, chunk_hashes: Vec<String>) -> CompressionCheckpoint {
+        CompressionCheckpoint {
+            id: None,
+            target_folder: "/test/path".to_string(),
+            created_at,
+            total_files: 1,
+            processed_files: 1,
+            patterns_found: 0,
+            compression_config: "{}".to_string(),
+            status: CheckpointStatus::Completed,
+            chunk_hashes,
+            config_fingerprint: "fp".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_clean_checkpoints_with_policy_reclaims_orphaned_chunks() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let mut db = CompressionDatabase::new(&db_path).unwrap();
+
+        db.store_chunk("old-only", b"old chunk bytes").unwrap();
+        db.store_chunk("shared", b"shared chunk bytes").unwrap();
+
+        let now = SystemTime::now();
+        let older = checkpoint_with(
+            now - std::time::Duration::from_secs(10),
+            vec!["old-only".to_string(), "shared".to_string()],
+        );
+        let newer = checkpoint_with(now, vec!["shared".to_string()]);
+        db.save_checkpoint(&older).unwrap();
+        db.save_checkpoint(&newer).unwrap();
+        assert_eq!(db.chunk_count(), 2);
+
+        let summary = db
+            .clean_checkpoints_with_policy(RetentionPolicy::KeepCount(1))
+            .unwrap();
+
+        assert_eq!(summary.deleted_count, 1);
+        assert!(summary.reclaimed_bytes > 0);
+        // "shared" is still referenced by the surviving checkpoint, so only
+        // "old-only" should have been garbage-collected.
+        assert_eq!(db.chunk_count(), 1);
+        assert!(!db.has_chunk("old-only"));
+        assert!(db.has_chunk("shared"));
+    }
+
+    #[test]
+    fn test_max_total_bytes_keeps_at_least_one_checkpoint_over_budget() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let mut db = CompressionDatabase::new(&db_path).unwrap();
+
+        db.store_chunk("big", &vec![0u8; 4096]).unwrap();
+        let checkpoint = checkpoint_with(SystemTime::now(), vec!["big".to_string()]);
+        db.save_checkpoint(&checkpoint).unwrap();
+
+        // A budget of 1 byte is smaller than even this lone checkpoint's
+        // chunk, but `MaxTotalBytes` must still keep it rather than
+        // deleting every checkpoint.
+        let summary = db
+            .clean_checkpoints_with_policy(RetentionPolicy::MaxTotalBytes(1))
+            .unwrap();
+
+        assert_eq!(summary.deleted_count, 0);
+        assert_eq!(db.list_checkpoints().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_max_total_bytes_dedupes_shared_chunks_across_checkpoints() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let mut db = CompressionDatabase::new(&db_path).unwrap();
+
+        db.store_chunk("shared", &vec![7u8; 1024]).unwrap();
+        let shared_size = db.chunks.get("shared").unwrap().len() as u64;
+
+        let now = SystemTime::now();
+        let older = checkpoint_with(
+            now - std::time::Duration::from_secs(10),
+            vec!["shared".to_string()],
+        );
+        let newer = checkpoint_with(now, vec!["shared".to_string()]);
+        db.save_checkpoint(&older).unwrap();
+        db.save_checkpoint(&newer).unwrap();
+
+        // Both checkpoints reference the same content-addressed chunk, so
+        // the real on-disk footprint of keeping both is `shared_size`, not
+        // `2 * shared_size` -- a budget of exactly `shared_size` must keep
+        // both rather than evicting the older one as if they didn't overlap.
+        let summary = db
+            .clean_checkpoints_with_policy(RetentionPolicy::MaxTotalBytes(shared_size))
+            .unwrap();
+
+        assert_eq!(summary.deleted_count, 0);
+        assert_eq!(db.list_checkpoints().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_store_chunk_rejects_oversized_key() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let mut db = CompressionDatabase::new(&db_path).unwrap();
+
+        let oversized_hash = "a".repeat(MAX_KEY_BYTES + 1);
+        let result = db.store_chunk(&oversized_hash, b"small");
+
+        assert!(result.is_err());
+        assert!(!db.has_chunk(&oversized_hash));
+    }
+
+    #[test]
+    fn test_check_record_size_rejects_oversized_value() {
+        let result = check_record_size("chunk", 4, MAX_VALUE_BYTES + 1);
+        assert!(result.is_err());
+        assert!(check_record_size("chunk", 4, MAX_VALUE_BYTES).is_ok());
+    }
+
+    #[test]
+    fn test_save_patterns_rejects_oversized_batch_without_partial_write() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let mut db = CompressionDatabase::new(&db_path).unwrap();
+
+        let checkpoint = checkpoint_with(SystemTime::now(), Vec::new());
+        let checkpoint_id = db.save_checkpoint(&checkpoint).unwrap();
+
+        // Each token stays under `MAX_VALUE_BYTES` on its own, but five of
+        // them together exceed `MAX_BATCH_BYTES`.
+        let big_token = "t".repeat(MAX_VALUE_BYTES - 1024);
+        let patterns: Vec<(String, usize, String)> = (0..5)
+            .map(|i| (format!("pattern{i}"), 1, big_token.clone()))
+            .collect();
+
+        let result = db.save_patterns(checkpoint_id, &patterns);
+
+        assert!(result.is_err());
+        assert!(db.load_patterns(checkpoint_id).unwrap().is_empty());
+    }
 }
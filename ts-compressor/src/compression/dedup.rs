@@ -0,0 +1,219 @@
+//! Content-defined chunking with cross-file deduplication
+//!
+//! `collect_files_from_archiver` treats every `FileEntry` independently, so
+//! vendored copies, generated files, and lockfiles that share most of their
+//! bytes get compressed redundantly. This module splits each file's
+//! `original_content` into variable-length chunks using the same gear-hash
+//! content-defined boundaries [`crate::compression::integrity`] already
+//! cuts for its Merkle/manifest chunking, hashes each chunk, and interns it
+//! into a shared [`ChunkPool`] so identical chunks across files collapse to
+//! one copy. Each file becomes an ordered list of indices into that pool.
+//!
+//! `deduplicate` is a standalone pass: it runs as `compress()`'s Step 1.5,
+//! after `build_dictionary` has built the dictionary and before Step 3's
+//! pattern substitution, and reports the bytes it
+//! elided via `DedupResult::bytes_saved`, which callers fold into
+//! `CompressionStatistics::dedup_bytes_saved`. The frequency analyzer and
+//! final codec still run over each file's own content rather than the pool
+//! directly - rerouting them to operate on chunk references instead of
+//! strings would touch the dictionary/restore/archive formats throughout
+//! the crate, so for now this stage only measures and reports the
+//! opportunity rather than changing what gets compressed.
+
+use crate::compression::config::ChunkingStrategy;
+use crate::compression::integrity::chunk_boundaries as gear_chunk_boundaries;
+use crate::compression::types::FileEntry;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Cut `bytes` into content-defined chunks per `strategy`: the default
+/// fixed-mask gear chunking integrity.rs already used, or the
+/// normalized-chunking FastCDC variant when `strategy` is
+/// `ChunkingStrategy::ContentDefined`. `ChunkingStrategy::Fixed` is
+/// accepted for completeness but still chunks by content, not by a fixed
+/// byte stride - `deduplicate`'s whole point is surviving edits, which
+/// fixed-length slicing can't do.
+fn boundaries_for(bytes: &[u8], strategy: ChunkingStrategy) -> Vec<(usize, usize)> {
+    match strategy {
+        ChunkingStrategy::Fixed(_) => gear_chunk_boundaries(bytes),
+        ChunkingStrategy::ContentDefined(params) => crate::compression::fastcdc::chunk_boundaries(bytes, params),
+    }
+}
+
+/// Deduplicated store of content-defined chunks, keyed by their SHA-256
+/// digest so identical chunks from different files collapse to one entry.
+#[derive(Debug, Default)]
+pub struct ChunkPool {
+    chunks: Vec<Vec<u8>>,
+    index_by_hash: HashMap<String, usize>,
+}
+
+impl ChunkPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Intern `bytes` into the pool, returning the index of its chunk.
+    /// Returns the existing index if an identical chunk was already seen.
+    fn intern(&mut self, bytes: &[u8]) -> usize {
+        let hash = format!("{:x}", Sha256::digest(bytes));
+        if let Some(&index) = self.index_by_hash.get(&hash) {
+            return index;
+        }
+
+        let index = self.chunks.len();
+        self.chunks.push(bytes.to_vec());
+        self.index_by_hash.insert(hash, index);
+        index
+    }
+
+    /// Bytes of the chunk at `index`.
+    pub fn chunk(&self, index: usize) -> &[u8] {
+        &self.chunks[index]
+    }
+
+    /// Number of distinct chunks held in the pool.
+    pub fn unique_chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Total bytes actually stored in the pool, after dedup.
+    pub fn pool_bytes(&self) -> usize {
+        self.chunks.iter().map(Vec::len).sum()
+    }
+}
+
+/// One file's content as an ordered list of references into a [`ChunkPool`].
+#[derive(Debug, Clone)]
+pub struct ChunkedFile {
+    pub relative_path: PathBuf,
+    pub chunk_indices: Vec<usize>,
+}
+
+/// Output of [`deduplicate`]: a shared chunk pool, each file's chunk
+/// references into it, and the before/after byte totals needed to report
+/// dedup savings.
+#[derive(Debug)]
+pub struct DedupResult {
+    pub pool: ChunkPool,
+    pub files: Vec<ChunkedFile>,
+    pub bytes_before_dedup: usize,
+    pub bytes_after_dedup: usize,
+}
+
+impl DedupResult {
+    /// Bytes elided by collapsing duplicate chunks into shared pool entries.
+    pub fn bytes_saved(&self) -> usize {
+        self.bytes_before_dedup.saturating_sub(self.bytes_after_dedup)
+    }
+
+    /// Reassemble a chunked file's original content by concatenating its
+    /// chunks in order, the inverse of `deduplicate`'s per-file split.
+    pub fn reconstruct(&self, file: &ChunkedFile) -> Vec<u8> {
+        let mut out = Vec::new();
+        for &index in &file.chunk_indices {
+            out.extend_from_slice(self.pool.chunk(index));
+        }
+        out
+    }
+}
+
+/// Split every entry's `original_content` into content-defined chunks and
+/// intern them into a shared [`ChunkPool`], so identical chunks across
+/// `entries` are stored once regardless of which files they came from.
+/// `strategy` selects the chunk-boundary algorithm (see `boundaries_for`).
+pub fn deduplicate(entries: &[FileEntry], strategy: ChunkingStrategy) -> DedupResult {
+    let mut pool = ChunkPool::new();
+    let mut files = Vec::with_capacity(entries.len());
+    let mut bytes_before_dedup = 0;
+
+    for entry in entries {
+        let bytes = entry.original_content.as_bytes();
+        bytes_before_dedup += bytes.len();
+
+        let chunk_indices = boundaries_for(bytes, strategy)
+            .into_iter()
+            .map(|(offset, len)| pool.intern(&bytes[offset..offset + len]))
+            .collect();
+
+        files.push(ChunkedFile {
+            relative_path: entry.relative_path.clone(),
+            chunk_indices,
+        });
+    }
+
+    let bytes_after_dedup = pool.pool_bytes();
+    DedupResult {
+        pool,
+        files,
+        bytes_before_dedup,
+        bytes_after_dedup,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, content: &str) -> FileEntry {
+        FileEntry::new(PathBuf::from(path), content.to_string(), false)
+    }
+
+    #[test]
+    fn test_identical_files_collapse_to_shared_chunks() {
+        let content = "fn vendored() { /* generated */ }\n".repeat(500);
+        let entries = vec![entry("vendor/a.rs", &content), entry("vendor/b.rs", &content)];
+
+        let result = deduplicate(&entries, ChunkingStrategy::default());
+
+        assert_eq!(result.files[0].chunk_indices, result.files[1].chunk_indices);
+        assert!(result.bytes_saved() > 0);
+        assert_eq!(result.bytes_before_dedup, content.len() * 2);
+        assert_eq!(result.bytes_after_dedup, result.pool.pool_bytes());
+    }
+
+    #[test]
+    fn test_distinct_files_produce_no_savings() {
+        let entries = vec![entry("a.rs", "fn a() {}"), entry("b.rs", "completely different content")];
+
+        let result = deduplicate(&entries, ChunkingStrategy::default());
+
+        assert_eq!(result.bytes_saved(), 0);
+        assert_eq!(result.pool.unique_chunk_count(), entries.len());
+    }
+
+    #[test]
+    fn test_reconstruct_round_trips_each_file() {
+        let entries = vec![
+            entry("a.rs", &"abc".repeat(10_000)),
+            entry("b.rs", "short and unrelated"),
+        ];
+
+        let result = deduplicate(&entries, ChunkingStrategy::default());
+
+        for (entry, file) in entries.iter().zip(&result.files) {
+            assert_eq!(result.reconstruct(file), entry.original_content.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_content_defined_strategy_also_dedups() {
+        let content = "fn vendored() { /* generated */ }\n".repeat(500);
+        let entries = vec![entry("vendor/a.rs", &content), entry("vendor/b.rs", &content)];
+        let strategy =
+            ChunkingStrategy::ContentDefined(crate::compression::config::ContentDefinedChunking::new(512, 2048, 8192).unwrap());
+
+        let result = deduplicate(&entries, strategy);
+
+        assert_eq!(result.files[0].chunk_indices, result.files[1].chunk_indices);
+        assert!(result.bytes_saved() > 0);
+    }
+
+    #[test]
+    fn test_empty_batch_produces_empty_pool() {
+        let result = deduplicate(&[], ChunkingStrategy::default());
+        assert_eq!(result.pool.unique_chunk_count(), 0);
+        assert_eq!(result.bytes_saved(), 0);
+    }
+}
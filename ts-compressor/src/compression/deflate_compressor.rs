@@ -0,0 +1,77 @@
+//! Deflate compression integration
+//!
+//! Thin wrapper around `flate2`'s raw deflate encoder/decoder (no zlib or
+//! gzip framing).
+
+use crate::compression::codec::Compressor;
+use crate::compression::error::{CompressionError, CompressionResult};
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+
+/// Deflate compressor at a fixed, balanced compression level.
+#[derive(Debug)]
+pub struct DeflateCompressor {
+    level: Compression,
+}
+
+impl DeflateCompressor {
+    /// Create a new deflate compressor using the default compression level.
+    pub fn new() -> Self {
+        Self {
+            level: Compression::default(),
+        }
+    }
+}
+
+impl Default for DeflateCompressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Compressor for DeflateCompressor {
+    fn compress(&self, data: &[u8]) -> CompressionResult<Vec<u8>> {
+        let mut encoder = DeflateEncoder::new(Vec::new(), self.level);
+        encoder
+            .write_all(data)
+            .map_err(CompressionError::deflate_compression)?;
+        encoder.finish().map_err(CompressionError::deflate_compression)
+    }
+
+    fn decompress(&self, data: &[u8]) -> CompressionResult<Vec<u8>> {
+        let mut decoder = DeflateDecoder::new(data);
+        let mut decompressed = Vec::new();
+        decoder
+            .read_to_end(&mut decompressed)
+            .map_err(CompressionError::deflate_compression)?;
+        Ok(decompressed)
+    }
+
+    fn codec_id(&self) -> u8 {
+        5
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deflate_round_trip() {
+        let compressor = DeflateCompressor::new();
+        let data = b"function test() { return 'hello world'; }".repeat(10);
+
+        let compressed = compressor.compress(&data).unwrap();
+        let decompressed = compressor.decompress(&compressed).unwrap();
+
+        assert_eq!(decompressed, data);
+    }
+
+    #[test]
+    fn test_deflate_rejects_garbage() {
+        let compressor = DeflateCompressor::new();
+        assert!(compressor.decompress(b"not deflate data").is_err());
+    }
+}
@@ -0,0 +1,166 @@
+//! Zero-copy persistent dictionary archives
+//!
+//! `DictionaryBuilder` rebuilds its forward/reverse `HashMap`s from scratch
+//! every run, with nowhere to save a trained dictionary for reuse. This
+//! module adds an `rkyv` archive format: [`DictionaryBuilder::to_archive`]
+//! serializes the dictionary into a self-contained byte buffer that can be
+//! written to disk and later memory-mapped, and [`ArchivedDictionary`] reads
+//! it back by indexing borrowed `&str`s straight out of the archived bytes —
+//! no owned `HashMap` of deserialized strings is built, so repeated
+//! decompression against a shared dictionary starts instantly.
+
+use std::collections::HashMap;
+
+use rkyv::{Archive, Deserialize, Serialize};
+use tracing::debug;
+
+use crate::compression::{CompressionError, DictionaryBuilder, DictionaryBuilding};
+
+/// A single pattern/token pair as stored in the archive.
+#[derive(Archive, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[archive(check_bytes)]
+pub struct DictionaryEntry {
+    pattern: String,
+    token: String,
+}
+
+impl DictionaryBuilder {
+    /// Serialize the dictionary into a zero-copy `rkyv` archive suitable for
+    /// writing to disk and later loading with [`ArchivedDictionary::from_bytes`].
+    pub fn to_archive(&self) -> Vec<u8> {
+        let entries: Vec<DictionaryEntry> = self
+            .get_dictionary_entries()
+            .into_iter()
+            .map(|(pattern, token)| DictionaryEntry { pattern, token })
+            .collect();
+
+        debug!(entry_count = entries.len(), "Serializing dictionary to rkyv archive");
+
+        rkyv::to_bytes::<_, 1024>(&entries)
+            .expect("dictionary entries are always archivable")
+            .into_vec()
+    }
+}
+
+/// A dictionary loaded directly from an `rkyv` archive's bytes.
+///
+/// Lookups read the archived pattern and token strings straight out of
+/// `bytes`; only a small `HashMap` of borrowed `&str` keys is built on load
+/// to index them for reverse (token -> pattern) lookup, not a copy of the
+/// strings themselves.
+#[derive(Debug)]
+pub struct ArchivedDictionary<'a> {
+    entries: &'a rkyv::vec::ArchivedVec<ArchivedDictionaryEntry>,
+    token_to_pattern: HashMap<&'a str, &'a str>,
+}
+
+impl<'a> ArchivedDictionary<'a> {
+    /// Validate `bytes` as an `rkyv` archive of dictionary entries and index
+    /// them for lookup.
+    ///
+    /// Re-checks the same bidirectional-consistency invariant
+    /// `DictionaryBuilder::validate_dictionary` enforces at build time: every
+    /// pattern and every token appears at most once in the archive.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, CompressionError> {
+        let entries = rkyv::check_archived_root::<Vec<DictionaryEntry>>(bytes)
+            .map_err(|e| CompressionError::dictionary_build(format!("Corrupt dictionary archive: {}", e)))?;
+
+        let mut seen_patterns: HashMap<&'a str, &'a str> = HashMap::with_capacity(entries.len());
+        let mut token_to_pattern: HashMap<&'a str, &'a str> = HashMap::with_capacity(entries.len());
+
+        for entry in entries.iter() {
+            let pattern = entry.pattern.as_str();
+            let token = entry.token.as_str();
+
+            if seen_patterns.insert(pattern, token).is_some() {
+                return Err(CompressionError::dictionary_build(format!(
+                    "Duplicate pattern in archive: '{}'",
+                    pattern
+                )));
+            }
+            if token_to_pattern.insert(token, pattern).is_some() {
+                return Err(CompressionError::dictionary_build(format!(
+                    "Duplicate token in archive: '{}'",
+                    token
+                )));
+            }
+        }
+
+        debug!(entry_count = entries.len(), "Loaded and validated rkyv dictionary archive");
+
+        Ok(Self { entries, token_to_pattern })
+    }
+
+    /// Look up the pattern a token expands to, for decompression.
+    pub fn pattern_for_token(&self, token: &str) -> Option<&str> {
+        self.token_to_pattern.get(token).copied()
+    }
+
+    /// Number of entries in the archive.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// True if the archive has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_builder() -> DictionaryBuilder {
+        let mut builder = DictionaryBuilder::new();
+        builder
+            .build_dictionary(vec![
+                ("function".to_string(), 5),
+                ("return".to_string(), 3),
+                ("const".to_string(), 4),
+            ])
+            .unwrap();
+        builder
+    }
+
+    #[test]
+    fn test_archive_round_trips_reverse_lookup() {
+        let builder = sample_builder();
+        let bytes = builder.to_archive();
+
+        let archived = ArchivedDictionary::from_bytes(&bytes).unwrap();
+        assert_eq!(archived.len(), builder.entry_count());
+
+        for (pattern, token) in builder.get_dictionary_entries() {
+            assert_eq!(archived.pattern_for_token(&token), Some(pattern.as_str()));
+        }
+    }
+
+    #[test]
+    fn test_archive_of_empty_dictionary() {
+        let builder = DictionaryBuilder::new();
+        let bytes = builder.to_archive();
+
+        let archived = ArchivedDictionary::from_bytes(&bytes).unwrap();
+        assert!(archived.is_empty());
+        assert_eq!(archived.pattern_for_token("T0000"), None);
+    }
+
+    #[test]
+    fn test_archive_rejects_corrupt_bytes() {
+        let mut bytes = sample_builder().to_archive();
+        for byte in bytes.iter_mut() {
+            *byte ^= 0xff;
+        }
+
+        assert!(ArchivedDictionary::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_archive_rejects_truncated_bytes() {
+        let bytes = sample_builder().to_archive();
+        let truncated = &bytes[..bytes.len() / 2];
+
+        assert!(ArchivedDictionary::from_bytes(truncated).is_err());
+    }
+}
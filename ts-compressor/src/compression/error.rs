@@ -26,7 +26,28 @@ pub enum CompressionError {
         #[source]
         source: std::io::Error,
     },
-    
+
+    #[error("Lz4 compression failed: {message}")]
+    Lz4Compression { message: String },
+
+    #[error("Snappy compression failed: {source}")]
+    SnappyCompression {
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Brotli compression failed: {source}")]
+    BrotliCompression {
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Deflate compression failed: {source}")]
+    DeflateCompression {
+        #[source]
+        source: std::io::Error,
+    },
+
     #[error("Output file creation failed: {path}")]
     OutputCreation { path: PathBuf },
     
@@ -38,7 +59,16 @@ pub enum CompressionError {
     
     #[error("Integrity check failed: {message}")]
     IntegrityCheck { message: String },
-    
+
+    #[error("Checksum mismatch: expected {expected}, computed {actual}")]
+    ChecksumMismatch { expected: String, actual: String },
+
+    #[error("Manifest authentication failed: {message}")]
+    ManifestAuthentication { message: String },
+
+    #[error("Signature verification failed: {message}")]
+    SignatureVerification { message: String },
+
     #[error("Git operation failed")]
     GitOperation {
         #[from]
@@ -100,11 +130,56 @@ impl CompressionError {
             message: message.into(),
         }
     }
-    
+
+    /// Create a checksum mismatch error with the expected and computed
+    /// checksums (hex-encoded)
+    pub fn checksum_mismatch<S: Into<String>>(expected: S, actual: S) -> Self {
+        Self::ChecksumMismatch {
+            expected: expected.into(),
+            actual: actual.into(),
+        }
+    }
+
+    /// Create a manifest authentication error with context
+    pub fn manifest_authentication<S: Into<String>>(message: S) -> Self {
+        Self::ManifestAuthentication {
+            message: message.into(),
+        }
+    }
+
+    /// Create a signature verification error with context
+    pub fn signature_verification<S: Into<String>>(message: S) -> Self {
+        Self::SignatureVerification {
+            message: message.into(),
+        }
+    }
+
     /// Create a zstd compression error with IO error context
     pub fn zstd_compression(source: std::io::Error) -> Self {
         Self::ZstdCompression { source }
     }
+
+    /// Create an lz4 compression error with context
+    pub fn lz4_compression<S: Into<String>>(message: S) -> Self {
+        Self::Lz4Compression {
+            message: message.into(),
+        }
+    }
+
+    /// Create a snappy compression error with IO error context
+    pub fn snappy_compression(source: std::io::Error) -> Self {
+        Self::SnappyCompression { source }
+    }
+
+    /// Create a brotli compression error with IO error context
+    pub fn brotli_compression(source: std::io::Error) -> Self {
+        Self::BrotliCompression { source }
+    }
+
+    /// Create a deflate compression error with IO error context
+    pub fn deflate_compression(source: std::io::Error) -> Self {
+        Self::DeflateCompression { source }
+    }
 }
 
 /// Result type alias for compression operations
@@ -156,6 +231,25 @@ mod tests {
         let error = CompressionError::pattern_replacement("test message");
         assert!(error.to_string().contains("Pattern replacement failed"));
         
+        // Test Lz4Compression
+        let error = CompressionError::lz4_compression("test message");
+        assert!(error.to_string().contains("Lz4 compression failed"));
+
+        // Test SnappyCompression
+        let io_error = std::io::Error::new(std::io::ErrorKind::InvalidData, "bad frame");
+        let error = CompressionError::snappy_compression(io_error);
+        assert!(error.to_string().contains("Snappy compression failed"));
+
+        // Test BrotliCompression
+        let io_error = std::io::Error::new(std::io::ErrorKind::InvalidData, "bad stream");
+        let error = CompressionError::brotli_compression(io_error);
+        assert!(error.to_string().contains("Brotli compression failed"));
+
+        // Test DeflateCompression
+        let io_error = std::io::Error::new(std::io::ErrorKind::InvalidData, "bad stream");
+        let error = CompressionError::deflate_compression(io_error);
+        assert!(error.to_string().contains("Deflate compression failed"));
+
         // Test OutputCreation
         let error = CompressionError::output_creation("/test/path");
         assert!(error.to_string().contains("Output file creation failed"));
@@ -168,6 +262,20 @@ mod tests {
         // Test IntegrityCheck
         let error = CompressionError::integrity_check("test message");
         assert!(error.to_string().contains("Integrity check failed"));
+
+        // Test ChecksumMismatch
+        let error = CompressionError::checksum_mismatch("deadbeef", "cafebabe");
+        assert!(error.to_string().contains("Checksum mismatch"));
+        assert!(error.to_string().contains("deadbeef"));
+        assert!(error.to_string().contains("cafebabe"));
+
+        // Test ManifestAuthentication
+        let error = CompressionError::manifest_authentication("test message");
+        assert!(error.to_string().contains("Manifest authentication failed"));
+
+        // Test SignatureVerification
+        let error = CompressionError::signature_verification("test message");
+        assert!(error.to_string().contains("Signature verification failed"));
     }
     
     #[test]
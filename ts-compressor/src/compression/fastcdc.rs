@@ -0,0 +1,166 @@
+//! FastCDC content-defined chunking with normalized chunk-size distribution
+//!
+//! [`crate::compression::integrity::chunk_boundaries`] already cuts
+//! gear-hash content-defined chunks for Merkle/manifest checksums, but uses
+//! one fixed mask and fixed min/max sizes. This module is the configurable
+//! sibling selected by [`crate::compression::config::ChunkingStrategy::ContentDefined`]:
+//! it takes caller-supplied min/avg/max targets (see
+//! [`crate::compression::config::ContentDefinedChunking`]) and uses FastCDC's
+//! *normalized chunking* trick - a stricter mask (more set bits, so a match
+//! is less likely) before `avg` bytes, and a looser mask (fewer set bits)
+//! after - so most chunks cluster near `avg` instead of following the long
+//! tail a single fixed mask produces. A forced cut at `max` bounds worst
+//! case, and no cut is considered before `min`.
+
+/// Fixed pseudo-random table for the gear-hash rolling hash used by
+/// [`chunk_boundaries`]. Generated at compile time with a small
+/// splitmix64-style mixer seeded from the table index, so it is stable
+/// across builds without needing a `rand` dependency - same technique as
+/// `integrity::GEAR_TABLE`, duplicated here rather than shared since that
+/// table is private to the Merkle-chunking use case it was built for.
+const GEAR_TABLE: [u64; 256] = {
+    let mut table = [0u64; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut z = (i as u64).wrapping_add(0x9E3779B97F4A7C15).wrapping_add(1);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        table[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    table
+};
+
+/// Bits added to/subtracted from `avg`'s bit width for the small/large
+/// masks, FastCDC's "normalization level". Level 2, matching the FastCDC
+/// paper's own recommendation and restic's default.
+const NORMALIZATION_LEVEL: u32 = 2;
+
+/// Build the stricter (more set bits) and looser (fewer set bits) gear
+/// masks used before/after `avg` bytes into the current chunk.
+fn normalized_masks(avg: usize) -> (u64, u64) {
+    let avg_bits = avg.trailing_zeros();
+    let small_bits = avg_bits + NORMALIZATION_LEVEL;
+    let large_bits = avg_bits.saturating_sub(NORMALIZATION_LEVEL).max(1);
+    (mask_with_bits(small_bits), mask_with_bits(large_bits))
+}
+
+fn mask_with_bits(bits: u32) -> u64 {
+    let bits = bits.min(63);
+    (1u64 << bits) - 1
+}
+
+/// Split `content` into content-defined chunks whose sizes cluster around
+/// `params.avg()`, using FastCDC's normalized gear-hash chunking. Returns
+/// each chunk's `(offset, length)`, in order, covering all of `content`.
+pub fn chunk_boundaries(
+    content: &[u8],
+    params: crate::compression::config::ContentDefinedChunking,
+) -> Vec<(usize, usize)> {
+    if content.is_empty() {
+        return Vec::new();
+    }
+
+    let (mask_small, mask_large) = normalized_masks(params.avg());
+    let mut boundaries = Vec::new();
+    let mut start = 0usize;
+    let mut hash: u64 = 0;
+
+    for (i, &byte) in content.iter().enumerate() {
+        let chunk_len = i - start + 1;
+
+        if chunk_len < params.min() {
+            hash = (hash << 1).wrapping_add(GEAR_TABLE[byte as usize]);
+            continue;
+        }
+
+        if chunk_len >= params.max() {
+            boundaries.push((start, chunk_len));
+            start = i + 1;
+            hash = 0;
+            continue;
+        }
+
+        hash = (hash << 1).wrapping_add(GEAR_TABLE[byte as usize]);
+        let mask = if chunk_len < params.avg() { mask_small } else { mask_large };
+        if hash & mask == 0 {
+            boundaries.push((start, chunk_len));
+            start = i + 1;
+            hash = 0;
+        }
+    }
+
+    if start < content.len() {
+        boundaries.push((start, content.len() - start));
+    }
+
+    boundaries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::config::ContentDefinedChunking;
+
+    fn params() -> ContentDefinedChunking {
+        ContentDefinedChunking::new(512, 2048, 8192).unwrap()
+    }
+
+    #[test]
+    fn test_empty_content_has_no_chunks() {
+        assert_eq!(chunk_boundaries(&[], params()), Vec::new());
+    }
+
+    #[test]
+    fn test_chunks_cover_content_contiguously() {
+        let data: Vec<u8> = (0..50_000u32).map(|i| (i % 251) as u8).collect();
+        let chunks = chunk_boundaries(&data, params());
+
+        let mut cursor = 0;
+        for (offset, len) in &chunks {
+            assert_eq!(*offset, cursor);
+            assert!(*len > 0);
+            cursor += len;
+        }
+        assert_eq!(cursor, data.len());
+    }
+
+    #[test]
+    fn test_respects_min_and_max_bounds() {
+        let data: Vec<u8> = (0..50_000u32).map(|i| (i % 251) as u8).collect();
+        let p = params();
+        let chunks = chunk_boundaries(&data, p);
+
+        for (i, (_, len)) in chunks.iter().enumerate() {
+            // The final chunk may be shorter than `min` since there's
+            // nothing left to extend it with.
+            if i + 1 != chunks.len() {
+                assert!(*len >= p.min());
+            }
+            assert!(*len <= p.max());
+        }
+    }
+
+    #[test]
+    fn test_insertion_only_perturbs_local_chunks() {
+        let base: Vec<u8> = (0..50_000u32).map(|i| (i % 251) as u8).collect();
+        let mut edited = base.clone();
+        edited.splice(20_000..20_000, std::iter::repeat(0xAAu8).take(37));
+
+        let base_chunks: Vec<&[u8]> = chunk_boundaries(&base, params())
+            .into_iter()
+            .map(|(offset, len)| &base[offset..offset + len])
+            .collect();
+        let edited_chunks: Vec<&[u8]> = chunk_boundaries(&edited, params())
+            .into_iter()
+            .map(|(offset, len)| &edited[offset..offset + len])
+            .collect();
+
+        let shared = base_chunks.iter().filter(|c| edited_chunks.contains(c)).count();
+        assert!(
+            shared > base_chunks.len() / 2,
+            "expected most chunks to survive an unrelated local edit, got {shared}/{}",
+            base_chunks.len()
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! Extension-driven file handling policy
+//!
+//! Modeled on ripgrep's decompressor dispatch: rather than a single
+//! allowlist deciding "text or skip", each extension maps to a
+//! [`FilePolicy`] describing how `collect_files_from_archiver` should
+//! handle it, and callers can `register` more mappings at runtime instead
+//! of editing the allowlist in place.
+//!
+//! The registry only decides whether to collect a file at all (`Skip`) and
+//! whether to store it as-is (`PreCompressed`); it's a fast path that never
+//! has to read file content to make those calls. Whether a collected file
+//! is actually `Text` or `Binary` is decided by [`sniff_is_text`] instead,
+//! since an extension alone can't tell an extension-less `LICENSE` file
+//! from a binary blob, or a `.json` file that's actually corrupt/binary.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use common::path::ExtensionExt;
+
+/// How a file's extension says it should be handled during collection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilePolicy {
+    /// Read as text and run through the normal compression pipeline.
+    Text,
+    /// Read as bytes (lossily, as text) and kept out of pattern analysis,
+    /// but still eligible for whole-file compression methods.
+    Binary,
+    /// Don't collect this file at all.
+    Skip,
+    /// Already compressed by its own format (e.g. `.png`, `.zst`); storing
+    /// it as-is is cheaper than wasting a compression pass on it.
+    PreCompressed,
+}
+
+/// Extension -> [`FilePolicy`] lookup, seeded with sensible defaults and
+/// extensible at runtime via [`FileTypeRegistry::register`].
+#[derive(Debug, Clone)]
+pub struct FileTypeRegistry {
+    policies: HashMap<String, FilePolicy>,
+    default_policy: FilePolicy,
+}
+
+const TEXT_EXTENSIONS: &[&str] = &[
+    "rs",
+    "toml",
+    "md",
+    "txt",
+    "json",
+    "yaml",
+    "yml",
+    "js",
+    "ts",
+    "tsx",
+    "jsx",
+    "html",
+    "css",
+    "scss",
+    "py",
+    "rb",
+    "go",
+    "java",
+    "c",
+    "cpp",
+    "h",
+    "hpp",
+    "sh",
+    "bash",
+    "zsh",
+    "fish",
+    "ps1",
+    "bat",
+    "cmd",
+    "xml",
+    "svg",
+    "gitignore",
+    "dockerfile",
+    "makefile",
+];
+
+const PRECOMPRESSED_EXTENSIONS: &[&str] = &[
+    "gz", "zst", "zstd", "br", "lz4", "bz2", "xz", "zip", "7z", "png", "jpg", "jpeg", "gif", "webp",
+    "woff", "woff2",
+];
+
+impl FileTypeRegistry {
+    /// Build a registry seeded with the repo's existing text-file allowlist
+    /// plus common pre-compressed formats. Extensions outside both lists
+    /// default to `Binary`, so the registry is purely additive over the
+    /// old "text or skip" split rather than stricter than before.
+    pub fn new() -> Self {
+        let mut policies = HashMap::new();
+        for ext in TEXT_EXTENSIONS {
+            policies.insert((*ext).to_string(), FilePolicy::Text);
+        }
+        for ext in PRECOMPRESSED_EXTENSIONS {
+            policies.insert((*ext).to_string(), FilePolicy::PreCompressed);
+        }
+
+        Self {
+            policies,
+            default_policy: FilePolicy::Binary,
+        }
+    }
+
+    /// Register or override the policy for an extension (case-insensitive,
+    /// no leading dot).
+    pub fn register(&mut self, extension: impl Into<String>, policy: FilePolicy) {
+        self.policies
+            .insert(extension.into().to_ascii_lowercase(), policy);
+    }
+
+    /// Resolve the policy for `path`'s extension, falling back to the
+    /// registry's default for unmatched or missing extensions.
+    pub fn policy_for(&self, path: &Path) -> FilePolicy {
+        path.extension_str()
+            .and_then(|ext| self.policies.get(&ext.to_ascii_lowercase()))
+            .copied()
+            .unwrap_or(self.default_policy)
+    }
+}
+
+impl Default for FileTypeRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Decide whether `sample` (a file's leading bytes, already truncated to
+/// whatever sample length the caller wants to pay for) looks like text.
+/// Rejects outright if a NUL byte appears; otherwise measures the
+/// proportion of bytes that are either not part of a valid UTF-8 sequence
+/// or are non-printable ASCII control characters (excluding `\n`/`\r`/`\t`),
+/// and calls it text only if that proportion is at or below
+/// `max_non_printable_ratio`.
+pub fn sniff_is_text(sample: &[u8], max_non_printable_ratio: f64) -> bool {
+    if sample.is_empty() {
+        return true;
+    }
+    if sample.contains(&0) {
+        return false;
+    }
+
+    let valid_len = match std::str::from_utf8(sample) {
+        Ok(_) => sample.len(),
+        Err(e) => e.valid_up_to(),
+    };
+    let non_utf8_bytes = sample.len() - valid_len;
+    let non_printable_bytes = sample[..valid_len]
+        .iter()
+        .filter(|&&b| b < 0x20 && !matches!(b, b'\n' | b'\r' | b'\t'))
+        .count();
+
+    let suspect_ratio = (non_utf8_bytes + non_printable_bytes) as f64 / sample.len() as f64;
+    suspect_ratio <= max_non_printable_ratio
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_text_extension() {
+        let registry = FileTypeRegistry::new();
+        assert_eq!(registry.policy_for(Path::new("main.rs")), FilePolicy::Text);
+    }
+
+    #[test]
+    fn test_known_precompressed_extension() {
+        let registry = FileTypeRegistry::new();
+        assert_eq!(
+            registry.policy_for(Path::new("photo.png")),
+            FilePolicy::PreCompressed
+        );
+    }
+
+    #[test]
+    fn test_unknown_extension_defaults_to_binary() {
+        let registry = FileTypeRegistry::new();
+        assert_eq!(
+            registry.policy_for(Path::new("archive.bin")),
+            FilePolicy::Binary
+        );
+    }
+
+    #[test]
+    fn test_register_overrides_default() {
+        let mut registry = FileTypeRegistry::new();
+        registry.register("bin", FilePolicy::Skip);
+        assert_eq!(registry.policy_for(Path::new("archive.bin")), FilePolicy::Skip);
+    }
+
+    #[test]
+    fn test_register_is_case_insensitive() {
+        let mut registry = FileTypeRegistry::new();
+        registry.register("LOG", FilePolicy::Skip);
+        assert_eq!(registry.policy_for(Path::new("run.log")), FilePolicy::Skip);
+    }
+
+    #[test]
+    fn test_sniff_is_text_accepts_plain_text() {
+        assert!(sniff_is_text(b"fn main() {\n    println!(\"hi\");\n}\n", 0.3));
+    }
+
+    #[test]
+    fn test_sniff_is_text_rejects_nul_byte() {
+        assert!(!sniff_is_text(b"plain text\0with a nul", 0.3));
+    }
+
+    #[test]
+    fn test_sniff_is_text_rejects_mostly_binary_sample() {
+        let sample: Vec<u8> = (0u8..=255).collect();
+        assert!(!sniff_is_text(&sample, 0.3));
+    }
+
+    #[test]
+    fn test_sniff_is_text_tolerates_a_few_suspect_bytes_under_threshold() {
+        let mut sample = b"mostly clean ASCII text ".repeat(20);
+        sample.push(0x01); // one stray control byte among ~500
+        assert!(sniff_is_text(&sample, 0.3));
+    }
+
+    #[test]
+    fn test_sniff_is_text_accepts_empty_sample() {
+        assert!(sniff_is_text(b"", 0.3));
+    }
+}
@@ -0,0 +1,136 @@
+//! Self-describing compressed frame format
+//!
+//! Each frame is `[codec id: u8][original length: LEB128 varint][payload]`,
+//! so a decoder can recover which codec produced it and exactly how many
+//! bytes to allocate for the decompressed output, without any out-of-band
+//! metadata.
+
+use crate::compression::codec::Codec;
+use crate::compression::error::{CompressionError, CompressionResult};
+
+/// Compress `data` with `codec`, prefixing the result with a one-byte codec
+/// id and a varint-encoded original length.
+pub fn compress_framed(data: &[u8], codec: Codec) -> CompressionResult<Vec<u8>> {
+    let payload = codec.compressor()?.compress(data)?;
+
+    let mut frame = Vec::with_capacity(1 + 10 + payload.len());
+    frame.push(codec.codec_id());
+    write_varint(data.len() as u64, &mut frame);
+    frame.extend_from_slice(&payload);
+    Ok(frame)
+}
+
+/// Decompress a frame produced by `compress_framed`, dispatching to the
+/// codec recorded in the header and sizing the output buffer exactly from
+/// the stored original length.
+pub fn decompress_framed(frame: &[u8]) -> CompressionResult<Vec<u8>> {
+    let codec_id = *frame
+        .first()
+        .ok_or_else(|| CompressionError::config_validation("Frame is empty: missing codec id"))?;
+    let (original_len, header_len) = read_varint(&frame[1..])?;
+    let payload = &frame[1 + header_len..];
+
+    if codec_id == ZSTD_CODEC_ID {
+        // Route through zstd's bulk decompressor with the exact size carried
+        // in the frame header, instead of ZstdCompressor's own capped
+        // decompress, so frames larger than the 1MB safety limit still work.
+        return zstd::bulk::decompress(payload, original_len as usize)
+            .map_err(CompressionError::zstd_compression);
+    }
+
+    Codec::from_codec_id(codec_id)?.compressor()?.decompress(payload)
+}
+
+const ZSTD_CODEC_ID: u8 = 1;
+
+pub(crate) fn write_varint(mut value: u64, out: &mut Vec<u8>) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+pub(crate) fn read_varint(bytes: &[u8]) -> CompressionResult<(u64, usize)> {
+    let mut value: u64 = 0;
+    for (i, &byte) in bytes.iter().enumerate() {
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+    Err(CompressionError::config_validation(
+        "Frame is truncated: incomplete length varint",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_varint_round_trip() {
+        for value in [0u64, 1, 127, 128, 300, u32::MAX as u64, u64::MAX] {
+            let mut buf = Vec::new();
+            write_varint(value, &mut buf);
+            let (decoded, len) = read_varint(&buf).unwrap();
+            assert_eq!(decoded, value);
+            assert_eq!(len, buf.len());
+        }
+    }
+
+    #[test]
+    fn test_framed_round_trip_per_codec() {
+        let data = b"function test() { return 'hello world'; }".repeat(20);
+        for spec in ["zstd/5", "lz4", "snappy", "brotli", "deflate", "stored"] {
+            let codec = Codec::from_str(spec).unwrap();
+            let frame = compress_framed(&data, codec).unwrap();
+            let decompressed = decompress_framed(&frame).unwrap();
+            assert_eq!(decompressed, data, "round trip failed for {spec}");
+        }
+    }
+
+    #[test]
+    fn test_framed_header_encodes_codec_and_length() {
+        let data = b"hello, world!";
+        let codec = Codec::from_str("lz4").unwrap();
+        let frame = compress_framed(data, codec).unwrap();
+
+        assert_eq!(frame[0], codec.codec_id());
+        let (original_len, _) = read_varint(&frame[1..]).unwrap();
+        assert_eq!(original_len as usize, data.len());
+    }
+
+    #[test]
+    fn test_decompress_framed_rejects_empty_input() {
+        assert!(decompress_framed(&[]).is_err());
+    }
+
+    #[test]
+    fn test_decompress_framed_rejects_unknown_codec_id() {
+        let mut frame = vec![99u8];
+        write_varint(0, &mut frame);
+        assert!(decompress_framed(&frame).is_err());
+    }
+
+    #[test]
+    fn test_framed_zstd_round_trips_above_one_megabyte() {
+        // Regression test for the hard-coded 1MB decompress cap: a payload
+        // whose decompressed size exceeds it must still round-trip.
+        let data = "function test() { return 'hello world'; }\n"
+            .repeat(50_000)
+            .into_bytes();
+        assert!(data.len() > 1024 * 1024);
+
+        let codec = Codec::from_str("zstd/3").unwrap();
+        let frame = compress_framed(&data, codec).unwrap();
+        let decompressed = decompress_framed(&frame).unwrap();
+
+        assert_eq!(decompressed, data);
+    }
+}
@@ -0,0 +1,332 @@
+//! FSST-style bulk symbol-table trainer
+//!
+//! Unlike `DictionaryBuilder`, which learns one token per frequent pattern
+//! file-by-file, `FsstCompressor::train_bulk` learns a single byte-level
+//! symbol table shared across an entire batch of `FileEntry` inputs, in the
+//! style of "FSST: Fast Random Access String Compression" (Boncz, Neumann,
+//! Leis). Each symbol is a 1-8 byte string assigned a code 0-254; code 255
+//! is reserved as an escape that precedes any literal byte the table can't
+//! represent, so every input is encodable regardless of which symbols made
+//! the final table.
+
+use crate::compression::types::FileEntry;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// Code that precedes a literal byte not covered by any table symbol.
+pub const ESCAPE_CODE: u8 = 255;
+
+/// Maximum number of real symbols a table may hold (codes `0..MAX_SYMBOLS`).
+pub const MAX_SYMBOLS: usize = 255;
+
+/// Longest byte string a single symbol may represent.
+pub const MAX_SYMBOL_LEN: usize = 8;
+
+/// Training rounds to run before freezing the table.
+const TRAINING_ROUNDS: usize = 5;
+
+/// Size of the lossy perfect-hash index used for fast encoding; a power of
+/// two so the hash can be masked instead of reduced with a modulo.
+const HASH_TABLE_SIZE: usize = 1 << 14;
+
+/// Trains `SymbolTable`s from a batch of files. Carries no state of its own;
+/// every method is a pure function of its inputs.
+#[derive(Debug, Default)]
+pub struct FsstCompressor;
+
+impl FsstCompressor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Train a single symbol table shared across every file in `entries`.
+    ///
+    /// Starts from one single-byte symbol per distinct byte value observed
+    /// in the corpus (so the table can always fall back to a length-1
+    /// match instead of an escape for common bytes), then iterates
+    /// `TRAINING_ROUNDS` times: encode the corpus with the current table,
+    /// tally how often each symbol matched and how often each pair of
+    /// adjacent matches could be merged into a longer symbol, and rebuild
+    /// the table by greedily keeping the `MAX_SYMBOLS` candidates with the
+    /// highest `frequency * length` gain. Single-byte symbols are always
+    /// kept regardless of gain, so the final table never has to escape a
+    /// byte value it has already seen.
+    pub fn train_bulk(entries: &[&FileEntry]) -> SymbolTable {
+        let corpus: Vec<&[u8]> = entries
+            .iter()
+            .map(|entry| entry.original_content.as_bytes())
+            .collect();
+
+        let mut singles: Vec<u8> = {
+            let mut seen = [false; 256];
+            for bytes in &corpus {
+                for &b in *bytes {
+                    seen[b as usize] = true;
+                }
+            }
+            (0u16..256).filter(|&b| seen[b as usize]).map(|b| b as u8).collect()
+        };
+        singles.truncate(MAX_SYMBOLS);
+
+        let mut symbols: Vec<Vec<u8>> = singles.iter().map(|&b| vec![b]).collect();
+
+        for _ in 0..TRAINING_ROUNDS {
+            let table = SymbolTable::from_symbols(symbols.clone());
+            let mut gains: HashMap<Vec<u8>, usize> = HashMap::new();
+
+            for bytes in &corpus {
+                let matches = table.greedy_match_positions(bytes);
+                for &(start, len) in &matches {
+                    let sym = &bytes[start..start + len];
+                    *gains.entry(sym.to_vec()).or_insert(0) += 1;
+                }
+                for pair in matches.windows(2) {
+                    let (start, len_a) = pair[0];
+                    let (_, len_b) = pair[1];
+                    let merged_len = len_a + len_b;
+                    if merged_len <= MAX_SYMBOL_LEN {
+                        let merged = bytes[start..start + merged_len].to_vec();
+                        *gains.entry(merged).or_insert(0) += 1;
+                    }
+                }
+            }
+
+            let mut candidates: Vec<(Vec<u8>, usize)> = gains
+                .into_iter()
+                .map(|(sym, freq)| {
+                    let gain = freq * sym.len();
+                    (sym, gain)
+                })
+                .collect();
+            candidates.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| b.0.len().cmp(&a.0.len())));
+
+            let mut next_symbols: Vec<Vec<u8>> = singles.iter().map(|&b| vec![b]).collect();
+            for (sym, _gain) in candidates {
+                if next_symbols.len() >= MAX_SYMBOLS {
+                    break;
+                }
+                if sym.len() > 1 && !next_symbols.contains(&sym) {
+                    next_symbols.push(sym);
+                }
+            }
+
+            symbols = next_symbols;
+        }
+
+        SymbolTable::from_symbols(symbols)
+    }
+}
+
+/// A trained table of up to `MAX_SYMBOLS` byte-string symbols plus the
+/// escape code, with a lossy hash index for O(1) candidate lookup during
+/// encoding.
+#[derive(Debug, Clone)]
+pub struct SymbolTable {
+    symbols: Vec<Vec<u8>>,
+    /// First 2-3 bytes of a candidate window, hashed -> candidate code.
+    /// Lossy: a collision keeps whichever symbol claimed the slot first,
+    /// and a hit is always re-verified byte-for-byte before being trusted.
+    hash_index: Vec<Option<u8>>,
+}
+
+impl SymbolTable {
+    fn from_symbols(mut symbols: Vec<Vec<u8>>) -> Self {
+        symbols.truncate(MAX_SYMBOLS);
+        // Longest-first so the hash index prefers the longer symbol when
+        // two candidates share the same leading bytes and hash slot.
+        symbols.sort_by(|a, b| b.len().cmp(&a.len()));
+
+        let mut hash_index = vec![None; HASH_TABLE_SIZE];
+        for (code, symbol) in symbols.iter().enumerate() {
+            let slot = Self::hash_prefix(symbol) % HASH_TABLE_SIZE;
+            hash_index[slot].get_or_insert(code as u8);
+        }
+
+        Self { symbols, hash_index }
+    }
+
+    /// Number of real symbols in the table (excludes the escape code).
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// Digest identifying this table's symbols and their code assignment,
+    /// so a `ContentHeader::FsstCompressed` entry can be checked against
+    /// the table supplied at decompress time, mirroring `Dictionary::id`.
+    pub fn id(&self) -> String {
+        let mut hasher = Sha256::new();
+        for symbol in &self.symbols {
+            hasher.update([symbol.len() as u8]);
+            hasher.update(symbol);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    fn hash_prefix(bytes: &[u8]) -> usize {
+        let mut key: u32 = 0;
+        for &b in bytes.iter().take(3) {
+            key = key.wrapping_mul(131).wrapping_add(b as u32);
+        }
+        key as usize
+    }
+
+    /// Candidate code for the window starting at `bytes`, verified against
+    /// the table's actual symbol bytes; `None` on a lossy-hash miss or a
+    /// verification failure.
+    fn lookup(&self, bytes: &[u8]) -> Option<(u8, usize)> {
+        let slot = Self::hash_prefix(bytes) % HASH_TABLE_SIZE;
+        let code = self.hash_index[slot]?;
+        let symbol = &self.symbols[code as usize];
+        if bytes.starts_with(symbol.as_slice()) {
+            Some((code, symbol.len()))
+        } else {
+            None
+        }
+    }
+
+    /// Greedily match `bytes` against the table, returning `(start, len)`
+    /// for every matched symbol (escaped bytes are not included). Used by
+    /// training to tally symbol and symbol-pair frequencies.
+    fn greedy_match_positions(&self, bytes: &[u8]) -> Vec<(usize, usize)> {
+        let mut positions = Vec::new();
+        let mut pos = 0;
+        while pos < bytes.len() {
+            match self.lookup(&bytes[pos..]) {
+                Some((_, len)) => {
+                    positions.push((pos, len));
+                    pos += len;
+                }
+                None => pos += 1,
+            }
+        }
+        positions
+    }
+
+    /// Encode `data` as a stream of codes: a symbol code followed directly
+    /// by the next code/escape, or `ESCAPE_CODE` followed by one literal
+    /// byte for anything the table doesn't cover.
+    pub fn compress(&self, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(data.len());
+        let mut pos = 0;
+        while pos < data.len() {
+            match self.lookup(&data[pos..]) {
+                Some((code, len)) => {
+                    out.push(code);
+                    pos += len;
+                }
+                None => {
+                    out.push(ESCAPE_CODE);
+                    out.push(data[pos]);
+                    pos += 1;
+                }
+            }
+        }
+        out
+    }
+
+    /// Reverse `compress`, expanding codes back to their symbol bytes.
+    pub fn decompress(&self, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(data.len());
+        let mut pos = 0;
+        while pos < data.len() {
+            let code = data[pos];
+            if code == ESCAPE_CODE {
+                pos += 1;
+                if pos < data.len() {
+                    out.push(data[pos]);
+                    pos += 1;
+                }
+            } else {
+                out.extend_from_slice(&self.symbols[code as usize]);
+                pos += 1;
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::types::FileEntry;
+    use std::path::PathBuf;
+
+    fn entry(content: &str) -> FileEntry {
+        FileEntry::new(PathBuf::from("file.rs"), content.to_string(), false)
+    }
+
+    #[test]
+    fn test_train_bulk_round_trips_each_file() {
+        let files = vec![
+            entry("function test() { return 42; }"),
+            entry("function other() { return 7; }"),
+            entry("function test() { return 42; }"),
+        ];
+        let refs: Vec<&FileEntry> = files.iter().collect();
+        let table = FsstCompressor::train_bulk(&refs);
+
+        for file in &files {
+            let bytes = file.original_content.as_bytes();
+            let compressed = table.compress(bytes);
+            let decompressed = table.decompress(&compressed);
+            assert_eq!(decompressed, bytes);
+        }
+    }
+
+    #[test]
+    fn test_train_bulk_caps_symbol_count() {
+        let files = vec![entry(&"abcdefghij".repeat(200))];
+        let refs: Vec<&FileEntry> = files.iter().collect();
+        let table = FsstCompressor::train_bulk(&refs);
+        assert!(table.len() <= MAX_SYMBOLS);
+    }
+
+    #[test]
+    fn test_empty_corpus_produces_empty_table() {
+        let table = FsstCompressor::train_bulk(&[]);
+        assert!(table.is_empty());
+        assert_eq!(table.compress(b""), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_escape_handles_unseen_bytes() {
+        let files = vec![entry("aaaa")];
+        let refs: Vec<&FileEntry> = files.iter().collect();
+        let table = FsstCompressor::train_bulk(&refs);
+
+        // 'z' never appeared in training, so it must round-trip via escape.
+        let data = b"aaaaz";
+        let compressed = table.compress(data);
+        assert_eq!(table.decompress(&compressed), data);
+    }
+
+    #[test]
+    fn test_id_is_stable_for_equivalent_tables_and_differs_for_distinct_ones() {
+        let files = vec![entry("function test() { return 42; }")];
+        let refs: Vec<&FileEntry> = files.iter().collect();
+        let table_a = FsstCompressor::train_bulk(&refs);
+        let table_b = FsstCompressor::train_bulk(&refs);
+        assert_eq!(table_a.id(), table_b.id());
+
+        let other_files = vec![entry("totally different corpus content")];
+        let other_refs: Vec<&FileEntry> = other_files.iter().collect();
+        let table_c = FsstCompressor::train_bulk(&other_refs);
+        assert_ne!(table_a.id(), table_c.id());
+    }
+
+    #[test]
+    fn test_repeated_pattern_compresses_shorter_than_input() {
+        let text = "the quick brown fox ".repeat(50);
+        let files = vec![entry(&text)];
+        let refs: Vec<&FileEntry> = files.iter().collect();
+        let table = FsstCompressor::train_bulk(&refs);
+
+        let compressed = table.compress(text.as_bytes());
+        assert!(compressed.len() < text.len());
+        assert_eq!(table.decompress(&compressed), text.as_bytes());
+    }
+}
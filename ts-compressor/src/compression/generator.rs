@@ -6,6 +6,17 @@
 use crate::compression::{CompressionError, TokenGeneration};
 use std::fmt;
 
+/// Letter prefixes tried in order once the 4-hex-digit space under the
+/// current prefix (`0000`-`FFFF`, `PREFIX_SPACE` values) is exhausted: `T`,
+/// `U`, `V`, ... `Z`. Restricted to this block (rather than the full
+/// alphabet) since it's already a ~7x lift over the old single-prefix
+/// ceiling, and every prefix still guarantees collision-freedom with real
+/// code identifiers the same way the original `T` prefix did.
+const TOKEN_PREFIXES: [char; 7] = ['T', 'U', 'V', 'W', 'X', 'Y', 'Z'];
+
+/// Number of distinct 4-hex-digit values under a single prefix.
+const PREFIX_SPACE: u32 = 0x1_0000;
+
 /// Newtype for hex tokens with compile-time guarantees
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct HexToken(String);
@@ -28,9 +39,12 @@ impl HexToken {
 
     /// Validate hex token format
     fn is_valid_hex_token(token: &str) -> bool {
-        // New format: T0000, T0001, etc. (5 characters: T + 4 hex digits)
+        // Format: <prefix><4 hex digits>, e.g. T0000, T0001, U0000, ...
         token.len() == 5
-            && token.starts_with('T')
+            && token
+                .chars()
+                .next()
+                .map_or(false, |c| TOKEN_PREFIXES.contains(&c))
             && token
                 .chars()
                 .skip(1)
@@ -50,6 +64,18 @@ impl AsRef<str> for HexToken {
     }
 }
 
+/// How a generator's capacity is bounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenCapacity {
+    /// A hard cap under the single `T` prefix, as set via `with_max_tokens`.
+    /// Never exceeds `HexTokenGenerator::MAX_TOKENS`.
+    Bounded(u32),
+    /// No hard cap: once the current prefix's 4-hex-digit space is
+    /// exhausted, generation rolls over to the next prefix in
+    /// `TOKEN_PREFIXES`, until every prefix is exhausted.
+    Unbounded,
+}
+
 /// Generator for sequential hexadecimal tokens
 ///
 /// Generates tokens in sequence: A0, A1, A2... A9, AA, AB... AZ, B0, B1...
@@ -57,22 +83,27 @@ impl AsRef<str> for HexToken {
 #[derive(Debug, Clone)]
 pub struct HexTokenGenerator {
     current_token: u32,
-    max_tokens: u32,
+    prefix_index: usize,
+    capacity: TokenCapacity,
 }
 
 impl HexTokenGenerator {
-    /// Maximum number of tokens that can be generated (16^4 - 1)
+    /// Maximum number of tokens that can be generated under a single prefix (16^4 - 1)
     const MAX_TOKENS: u32 = 65535; // 0xFFFF
 
-    /// Create a new token generator
+    /// Create a new token generator. Unlike `with_max_tokens`, this has no
+    /// hard cap: once the `T` prefix's space is exhausted, it rolls over
+    /// through `TOKEN_PREFIXES` rather than overflowing.
     pub fn new() -> Self {
         Self {
             current_token: 0,
-            max_tokens: Self::MAX_TOKENS,
+            prefix_index: 0,
+            capacity: TokenCapacity::Unbounded,
         }
     }
 
-    /// Create a token generator with custom maximum
+    /// Create a token generator with a custom maximum, capped at the
+    /// original single-prefix ceiling.
     #[allow(dead_code)]
     pub fn with_max_tokens(max_tokens: u32) -> Result<Self, CompressionError> {
         if max_tokens == 0 {
@@ -89,11 +120,12 @@ impl HexTokenGenerator {
 
         Ok(Self {
             current_token: 0,
-            max_tokens,
+            prefix_index: 0,
+            capacity: TokenCapacity::Bounded(max_tokens),
         })
     }
 
-    /// Format a token value as hexadecimal string
+    /// Format a token value as hexadecimal string under the `T` prefix
     ///
     /// Uses collision-free sequential hex tokens: T0000, T0001, T0002... TFFFF
     /// The 'T' prefix ensures no collision with actual code patterns.
@@ -101,25 +133,48 @@ impl HexTokenGenerator {
         format!("T{:04X}", value)
     }
 
+    /// Format a token value under an arbitrary `TOKEN_PREFIXES` prefix, for
+    /// the rolled-over tiers an unbounded generator produces beyond `T`.
+    fn format_prefixed_token(prefix: char, value: u32) -> String {
+        format!("{}{:04X}", prefix, value)
+    }
+
     /// Check if more tokens are available
     pub fn has_next(&self) -> bool {
-        self.current_token < self.max_tokens
+        match self.capacity {
+            TokenCapacity::Bounded(max) => self.current_token < max,
+            TokenCapacity::Unbounded => self.prefix_index < TOKEN_PREFIXES.len(),
+        }
     }
 
     /// Get the current token count
     #[allow(dead_code)]
     pub fn token_count(&self) -> u32 {
-        self.current_token
+        match self.capacity {
+            TokenCapacity::Bounded(_) => self.current_token,
+            TokenCapacity::Unbounded => self.prefix_index as u32 * PREFIX_SPACE + self.current_token,
+        }
     }
 
     /// Get remaining token capacity
     pub fn remaining_capacity(&self) -> u32 {
-        self.max_tokens.saturating_sub(self.current_token)
+        match self.capacity {
+            TokenCapacity::Bounded(max) => max.saturating_sub(self.current_token),
+            TokenCapacity::Unbounded => {
+                if self.prefix_index >= TOKEN_PREFIXES.len() {
+                    0
+                } else {
+                    let remaining_prefixes = (TOKEN_PREFIXES.len() - self.prefix_index - 1) as u32;
+                    (PREFIX_SPACE - self.current_token) + remaining_prefixes * PREFIX_SPACE
+                }
+            }
+        }
     }
 
     /// Reset the token generator to start from the beginning
     pub fn reset(&mut self) {
         self.current_token = 0;
+        self.prefix_index = 0;
     }
 }
 
@@ -131,18 +186,29 @@ impl Default for HexTokenGenerator {
 
 impl TokenGeneration for HexTokenGenerator {
     fn next_token(&mut self) -> Result<String, CompressionError> {
-        if self.current_token >= self.max_tokens {
+        if !self.has_next() {
             return Err(CompressionError::TokenOverflow);
         }
 
-        let token_str = Self::format_token(self.current_token);
+        let token_str = match self.capacity {
+            TokenCapacity::Bounded(_) => Self::format_token(self.current_token),
+            TokenCapacity::Unbounded => {
+                Self::format_prefixed_token(TOKEN_PREFIXES[self.prefix_index], self.current_token)
+            }
+        };
         self.current_token += 1;
 
+        if self.capacity == TokenCapacity::Unbounded && self.current_token >= PREFIX_SPACE {
+            self.current_token = 0;
+            self.prefix_index += 1;
+        }
+
         Ok(token_str)
     }
 
     fn reset(&mut self) {
         self.current_token = 0;
+        self.prefix_index = 0;
     }
 }
 
@@ -333,4 +399,46 @@ mod tests {
         assert_eq!(generator.next_token().unwrap(), "T0000");
         assert!(generator.next_token().is_err());
     }
+
+    #[test]
+    fn test_unbounded_generator_rolls_over_prefix() {
+        let mut generator = HexTokenGenerator::new();
+
+        // Fast-forward to the last token of the 'T' prefix's space.
+        for _ in 0..(PREFIX_SPACE - 1) {
+            generator.next_token().unwrap();
+        }
+        assert_eq!(generator.next_token().unwrap(), "TFFFF");
+
+        // The next token rolls over into the 'U' prefix rather than
+        // overflowing.
+        assert_eq!(generator.next_token().unwrap(), "U0000");
+        assert_eq!(generator.next_token().unwrap(), "U0001");
+    }
+
+    #[test]
+    fn test_unbounded_generator_exhausts_all_prefixes() {
+        let mut generator = HexTokenGenerator::new();
+        let total_capacity = TOKEN_PREFIXES.len() as u32 * PREFIX_SPACE;
+
+        assert_eq!(generator.remaining_capacity(), total_capacity);
+
+        for _ in 0..total_capacity {
+            assert!(generator.next_token().is_ok());
+        }
+
+        assert_eq!(generator.remaining_capacity(), 0);
+        assert!(matches!(
+            generator.next_token(),
+            Err(CompressionError::TokenOverflow)
+        ));
+    }
+
+    #[test]
+    fn test_hex_token_newtype_accepts_rolled_over_prefixes() {
+        assert!(HexToken::new("U0000".to_string()).is_some());
+        assert!(HexToken::new("ZFFFF".to_string()).is_some());
+        // A letter outside the rollover scheme is still rejected.
+        assert!(HexToken::new("Q0000".to_string()).is_none());
+    }
 }
@@ -8,12 +8,28 @@ use std::collections::HashMap;
 use std::path::Path;
 use sha2::{Sha256, Digest};
 use crc32fast::Hasher as Crc32Hasher;
+use chacha20poly1305::{XChaCha20Poly1305, XNonce, Key, KeyInit};
+use chacha20poly1305::aead::{Aead, AeadCore, OsRng, Payload};
+use ed25519_dalek::{Signer, SigningKey, Verifier, VerifyingKey};
+
+/// Length in bytes of the random XChaCha20-Poly1305 nonce prepended to an
+/// encrypted manifest.
+/// Current manifest format version, recorded via `MANIFEST_VERSION:` so a
+/// reader knows whether `FILE:`/`CHUNK:` path fields are percent-escaped.
+const MANIFEST_VERSION: u32 = 2;
+
+const MANIFEST_NONCE_LEN: usize = 24;
+/// Length in bytes of the Poly1305 authentication tag appended to the
+/// ciphertext by the `aead` crate.
+const MANIFEST_TAG_LEN: usize = 16;
 
 /// Integrity validator for compression operations
 pub struct IntegrityValidator {
     checksums: HashMap<String, FileChecksum>,
     dictionary_hash: Option<String>,
     validation_mode: ValidationMode,
+    merkle_root: Option<String>,
+    hash_algorithm: HashAlgorithm,
 }
 
 /// File checksum with multiple hash algorithms
@@ -22,6 +38,114 @@ pub struct FileChecksum {
     pub crc32: u32,
     pub sha256: String,
     pub size: usize,
+    /// Content-defined chunks covering the file, in offset order.
+    /// Concatenating each chunk's bytes reproduces the file exactly.
+    pub chunks: Vec<ChunkChecksum>,
+    /// Which `HashAlgorithm` produced `digest`.
+    pub algorithm: HashAlgorithmTag,
+    /// Digest computed with `algorithm`, independent of `crc32`/`sha256`.
+    /// This is the digest `validate_file` authenticates against.
+    pub digest: String,
+}
+
+/// Selects which digest algorithm `IntegrityValidator` uses for whole-file
+/// checksums and dictionary hashing, independent of `ValidationMode` (which
+/// only controls whether SHA256 is additionally computed alongside CRC32 in
+/// `calculate_checksum`). BLAKE3 gives large, parallelizable throughput
+/// wins on big archives, and its keyed mode is a MAC for free: a user
+/// without the key can't forge a matching digest, unlike a publicly
+/// recomputable SHA256.
+#[derive(Debug, Clone)]
+pub enum HashAlgorithm {
+    Crc32,
+    Sha256,
+    Blake3,
+    Blake3Keyed([u8; 32]),
+}
+
+impl Default for HashAlgorithm {
+    /// SHA256, for backward compatibility with manifests written before
+    /// pluggable hash algorithms existed.
+    fn default() -> Self {
+        HashAlgorithm::Sha256
+    }
+}
+
+impl HashAlgorithm {
+    fn tag(&self) -> HashAlgorithmTag {
+        match self {
+            HashAlgorithm::Crc32 => HashAlgorithmTag::Crc32,
+            HashAlgorithm::Sha256 => HashAlgorithmTag::Sha256,
+            HashAlgorithm::Blake3 => HashAlgorithmTag::Blake3,
+            HashAlgorithm::Blake3Keyed(_) => HashAlgorithmTag::Blake3Keyed,
+        }
+    }
+
+    /// Compute the hex-encoded digest of `data` with this algorithm.
+    fn digest(&self, data: &[u8]) -> String {
+        match self {
+            HashAlgorithm::Crc32 => {
+                let mut hasher = Crc32Hasher::new();
+                hasher.update(data);
+                format!("{:08x}", hasher.finalize())
+            }
+            HashAlgorithm::Sha256 => {
+                let mut hasher = Sha256::new();
+                hasher.update(data);
+                format!("{:x}", hasher.finalize())
+            }
+            HashAlgorithm::Blake3 => blake3::hash(data).to_hex().to_string(),
+            HashAlgorithm::Blake3Keyed(key) => blake3::keyed_hash(key, data).to_hex().to_string(),
+        }
+    }
+}
+
+/// Manifest-persisted tag identifying which [`HashAlgorithm`] produced a
+/// [`FileChecksum`]'s `digest`, without the secret key of a keyed variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithmTag {
+    Crc32,
+    Sha256,
+    Blake3,
+    Blake3Keyed,
+}
+
+impl HashAlgorithmTag {
+    fn as_str(&self) -> &'static str {
+        match self {
+            HashAlgorithmTag::Crc32 => "CRC32",
+            HashAlgorithmTag::Sha256 => "SHA256",
+            HashAlgorithmTag::Blake3 => "BLAKE3",
+            HashAlgorithmTag::Blake3Keyed => "BLAKE3_KEYED",
+        }
+    }
+
+    fn parse(s: &str) -> CompressionResult<Self> {
+        match s {
+            "CRC32" => Ok(HashAlgorithmTag::Crc32),
+            "SHA256" => Ok(HashAlgorithmTag::Sha256),
+            "BLAKE3" => Ok(HashAlgorithmTag::Blake3),
+            "BLAKE3_KEYED" => Ok(HashAlgorithmTag::Blake3Keyed),
+            other => Err(CompressionError::integrity_check(format!(
+                "Unknown hash algorithm in manifest: {}", other
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for HashAlgorithmTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Checksum of a single content-defined chunk within a file.
+#[derive(Debug, Clone)]
+pub struct ChunkChecksum {
+    pub offset: usize,
+    pub len: usize,
+    pub crc32: u32,
+    pub sha256: String,
 }
 
 /// Validation mode for different integrity levels
@@ -43,6 +167,315 @@ pub struct IntegrityReport {
     pub files_invalid: usize,
     pub dictionary_valid: bool,
     pub validation_errors: Vec<String>,
+    /// Whether the archive's recomputed Merkle root matches the root stored
+    /// in the manifest. `None` when no Merkle root was available to compare.
+    pub archive_root_matches: Option<bool>,
+}
+
+/// Which side of a Merkle inclusion-proof step the sibling hash sits on,
+/// relative to the node being folded up from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// Target average chunk size of 8 KiB: a boundary is cut whenever the low
+/// 13 bits of the rolling hash are all zero.
+const CHUNK_MASK: u64 = (1 << 13) - 1;
+/// Minimum chunk size, to bound variance on low-entropy runs that would
+/// otherwise satisfy the mask almost immediately.
+const CHUNK_MIN_SIZE: usize = 2 * 1024;
+/// Maximum chunk size, to bound variance on high-entropy runs that never
+/// satisfy the mask.
+const CHUNK_MAX_SIZE: usize = 64 * 1024;
+
+/// Fixed pseudo-random table for the gear-hash rolling hash used by
+/// [`chunk_boundaries`]. Generated at compile time with a small
+/// splitmix64-style mixer seeded from the table index, so it is stable
+/// across builds without needing a `rand` dependency.
+const GEAR_TABLE: [u64; 256] = {
+    let mut table = [0u64; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut z = (i as u64).wrapping_add(0x9E3779B97F4A7C15).wrapping_add(1);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        table[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    table
+};
+
+/// Split `content` into content-defined chunks using a gear-hash rolling
+/// hash: each byte shifts an accumulator left and folds in a table lookup,
+/// and a boundary is cut whenever the accumulator's low bits are all zero
+/// (clamped between [`CHUNK_MIN_SIZE`] and [`CHUNK_MAX_SIZE`]). Because the
+/// cut points depend only on a local window of content, inserting or
+/// deleting bytes only perturbs the chunks touching the edit; everything
+/// else re-chunks identically.
+///
+/// Shared with [`crate::compression::dedup`], which interns the same
+/// content-defined chunks into a cross-file pool instead of hashing them
+/// for a manifest.
+pub(crate) fn chunk_boundaries(content: &[u8]) -> Vec<(usize, usize)> {
+    if content.is_empty() {
+        return Vec::new();
+    }
+
+    let mut boundaries = Vec::new();
+    let mut start = 0usize;
+    let mut hash: u64 = 0;
+
+    for (i, &byte) in content.iter().enumerate() {
+        hash = (hash << 1).wrapping_add(GEAR_TABLE[byte as usize]);
+        let chunk_len = i - start + 1;
+
+        if chunk_len >= CHUNK_MAX_SIZE || (chunk_len >= CHUNK_MIN_SIZE && hash & CHUNK_MASK == 0) {
+            boundaries.push((start, chunk_len));
+            start = i + 1;
+            hash = 0;
+        }
+    }
+
+    if start < content.len() {
+        boundaries.push((start, content.len() - start));
+    }
+
+    boundaries
+}
+
+/// Compute a [`ChunkChecksum`] for each content-defined chunk of `content`.
+/// SHA256 is skipped in [`ValidationMode::Fast`], matching
+/// [`IntegrityValidator::calculate_checksum`].
+fn chunk_checksums(content: &[u8], mode: ValidationMode) -> Vec<ChunkChecksum> {
+    chunk_boundaries(content)
+        .into_iter()
+        .map(|(offset, len)| {
+            let slice = &content[offset..offset + len];
+
+            let mut crc32_hasher = Crc32Hasher::new();
+            crc32_hasher.update(slice);
+            let crc32 = crc32_hasher.finalize();
+
+            let sha256 = if matches!(mode, ValidationMode::Fast) {
+                String::new()
+            } else {
+                let mut sha256_hasher = Sha256::new();
+                sha256_hasher.update(slice);
+                format!("{:x}", sha256_hasher.finalize())
+            };
+
+            ChunkChecksum { offset, len, crc32, sha256 }
+        })
+        .collect()
+}
+
+/// Identity used to look up whether a chunk already exists: the SHA256 when
+/// available, falling back to the CRC32 in [`ValidationMode::Fast`].
+fn chunk_identity(chunk: &ChunkChecksum) -> String {
+    if chunk.sha256.is_empty() {
+        format!("{:08x}", chunk.crc32)
+    } else {
+        chunk.sha256.clone()
+    }
+}
+
+fn sha256_concat(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    let digest = hasher.finalize();
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&digest);
+    out
+}
+
+/// Merkle leaf hash: `H(0x00 || path || file_sha256)`. The `0x00` tag keeps
+/// leaves from colliding with internal nodes, which are tagged `0x01`.
+fn merkle_leaf_hash(path: &str, sha256_hex: &str) -> [u8; 32] {
+    sha256_concat(&[&[0x00], path.as_bytes(), sha256_hex.as_bytes()])
+}
+
+/// Merkle internal node hash: `H(0x01 || left || right)`.
+fn merkle_node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    sha256_concat(&[&[0x01], left, right])
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> CompressionResult<[u8; 32]> {
+    if hex.len() != 64 {
+        return Err(CompressionError::integrity_check("Invalid Merkle root length".to_string()));
+    }
+    let mut out = [0u8; 32];
+    for i in 0..32 {
+        out[i] = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+            .map_err(|_| CompressionError::integrity_check("Invalid Merkle root hex".to_string()))?;
+    }
+    Ok(out)
+}
+
+/// Percent-escape the characters in `path` that would otherwise be mistaken
+/// for a manifest field delimiter or line terminator (`:`, `\n`, `\r`) or
+/// that would make the escaping itself ambiguous (`%`). Everything else,
+/// including non-ASCII UTF-8, passes through untouched.
+fn percent_encode_path(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    for ch in path.chars() {
+        match ch {
+            ':' | '\n' | '\r' | '%' => {
+                let mut buf = [0u8; 4];
+                for byte in ch.encode_utf8(&mut buf).as_bytes() {
+                    out.push_str(&format!("%{:02X}", byte));
+                }
+            }
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+/// Reverse `percent_encode_path`, decoding `%XX` escapes back into their
+/// original bytes and re-validating the result as UTF-8.
+fn percent_decode_path(encoded: &str) -> CompressionResult<String> {
+    let bytes = encoded.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if i + 2 >= bytes.len() {
+                return Err(CompressionError::integrity_check("Truncated percent-escape in manifest path".to_string()));
+            }
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3])
+                .map_err(|_| CompressionError::integrity_check("Invalid percent-escape in manifest path".to_string()))?;
+            let byte = u8::from_str_radix(hex, 16)
+                .map_err(|_| CompressionError::integrity_check("Invalid percent-escape in manifest path".to_string()))?;
+            out.push(byte);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).map_err(|_| CompressionError::integrity_check("Manifest path is not valid UTF-8 after percent-decoding".to_string()))
+}
+
+fn signature_from_hex(hex: &str) -> CompressionResult<[u8; 64]> {
+    if hex.len() != 128 {
+        return Err(CompressionError::signature_verification("Invalid signature length"));
+    }
+    let mut out = [0u8; 64];
+    for i in 0..64 {
+        out[i] = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+            .map_err(|_| CompressionError::signature_verification("Invalid signature hex"))?;
+    }
+    Ok(out)
+}
+
+/// Build each level of the Merkle tree bottom-up from leaf hashes, pairing
+/// adjacent nodes into `merkle_node_hash` and duplicating the last node of a
+/// level when its count is odd. `levels[0]` is the leaves and `levels.last()`
+/// is the single-element root level.
+fn merkle_levels(leaves: Vec<[u8; 32]>) -> Vec<Vec<[u8; 32]>> {
+    let mut levels = vec![leaves];
+    while levels.last().unwrap().len() > 1 {
+        let level = levels.last().unwrap();
+        let mut next = Vec::with_capacity((level.len() + 1) / 2);
+        let mut i = 0;
+        while i < level.len() {
+            let left = level[i];
+            let right = if i + 1 < level.len() { level[i + 1] } else { level[i] };
+            next.push(merkle_node_hash(&left, &right));
+            i += 2;
+        }
+        levels.push(next);
+    }
+    levels
+}
+
+/// Walk `levels` from the leaf at `idx` up to the root, recording the
+/// sibling hash and which side it sits on at each step.
+fn merkle_proof_from_levels(levels: &[Vec<[u8; 32]>], mut idx: usize) -> Vec<(Side, [u8; 32])> {
+    let mut proof = Vec::new();
+    for level in &levels[..levels.len() - 1] {
+        let sibling_idx = idx ^ 1;
+        let sibling = if sibling_idx < level.len() { level[sibling_idx] } else { level[idx] };
+        let side = if idx % 2 == 0 { Side::Right } else { Side::Left };
+        proof.push((side, sibling));
+        idx /= 2;
+    }
+    proof
+}
+
+/// Encrypt a serialized manifest with XChaCha20-Poly1305, binding it to
+/// `root_sha256` (the archive's Merkle root) as associated data so a
+/// ciphertext can't be replayed against a different set of files. Returns
+/// `nonce || ciphertext || tag`.
+pub fn encrypt_manifest(manifest: &str, key: &[u8; 32], root_sha256: &str) -> CompressionResult<Vec<u8>> {
+    let cipher = XChaCha20Poly1305::new(Key::from_slice(key));
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let ciphertext = cipher
+        .encrypt(&nonce, Payload { msg: manifest.as_bytes(), aad: root_sha256.as_bytes() })
+        .map_err(|_| CompressionError::manifest_authentication("Failed to encrypt manifest"))?;
+
+    let mut out = Vec::with_capacity(MANIFEST_NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// Decrypt and authenticate a manifest produced by `encrypt_manifest`. The
+/// Poly1305 tag is verified before any checksum parsing happens: a wrong
+/// key, a flipped byte, or a mismatched `root_sha256` all fail here with a
+/// `CompressionError::ManifestAuthentication`, loudly, instead of silently
+/// producing garbage that later parses as a (wrong) manifest.
+pub fn decrypt_manifest(data: &[u8], key: &[u8; 32], root_sha256: &str) -> CompressionResult<String> {
+    if data.len() < MANIFEST_NONCE_LEN + MANIFEST_TAG_LEN {
+        return Err(CompressionError::manifest_authentication("Encrypted manifest is truncated"));
+    }
+
+    let (nonce_bytes, ciphertext) = data.split_at(MANIFEST_NONCE_LEN);
+    let nonce = XNonce::from_slice(nonce_bytes);
+    let cipher = XChaCha20Poly1305::new(Key::from_slice(key));
+
+    let plaintext = cipher
+        .decrypt(nonce, Payload { msg: ciphertext, aad: root_sha256.as_bytes() })
+        .map_err(|_| CompressionError::manifest_authentication(
+            "Poly1305 tag verification failed: manifest was tampered with or the key is wrong",
+        ))?;
+
+    String::from_utf8(plaintext)
+        .map_err(|_| CompressionError::manifest_authentication("Decrypted manifest is not valid UTF-8"))
+}
+
+/// Verify a detached ed25519 signature appended by `sign_manifest`. Strips
+/// the trailing `SIG:<hex>` line, re-canonicalizes the remaining manifest
+/// body (byte-identical to what `sign_manifest` signed), and checks it
+/// against `public_key`. Returns `Ok(false)` for a well-formed but
+/// non-matching signature, and `Err` if the manifest has no `SIG:` line or
+/// the signature is malformed - proving who produced the archive, not just
+/// that the bytes match a checksum the verifier already had.
+pub fn verify_manifest_signature(manifest: &str, public_key: &[u8; 32]) -> CompressionResult<bool> {
+    let trimmed = manifest.trim_end_matches('\n');
+    let (body, sig_line) = match trimmed.rfind('\n') {
+        Some(idx) => (&manifest[..idx + 1], &trimmed[idx + 1..]),
+        None => ("", trimmed),
+    };
+
+    let sig_hex = sig_line.strip_prefix("SIG:")
+        .ok_or_else(|| CompressionError::signature_verification("Manifest has no SIG: line"))?;
+    let sig_bytes = signature_from_hex(sig_hex)?;
+    let signature = ed25519_dalek::Signature::from_bytes(&sig_bytes);
+
+    let verifying_key = VerifyingKey::from_bytes(public_key)
+        .map_err(|_| CompressionError::signature_verification("Invalid ed25519 public key"))?;
+
+    Ok(verifying_key.verify(body.as_bytes(), &signature).is_ok())
 }
 
 impl IntegrityValidator {
@@ -52,9 +485,18 @@ impl IntegrityValidator {
             checksums: HashMap::new(),
             dictionary_hash: None,
             validation_mode: mode,
+            merkle_root: None,
+            hash_algorithm: HashAlgorithm::default(),
         }
     }
 
+    /// Override the digest algorithm used for whole-file checksums and
+    /// dictionary hashing. Defaults to SHA256 for backward compatibility.
+    pub fn with_hash_algorithm(mut self, algorithm: HashAlgorithm) -> Self {
+        self.hash_algorithm = algorithm;
+        self
+    }
+
     /// Calculate checksum for file content
     pub fn calculate_checksum(&self, content: &[u8]) -> FileChecksum {
         let mut crc32_hasher = Crc32Hasher::new();
@@ -74,6 +516,9 @@ impl IntegrityValidator {
             crc32,
             sha256,
             size: content.len(),
+            chunks: chunk_checksums(content, self.validation_mode),
+            algorithm: self.hash_algorithm.tag(),
+            digest: self.hash_algorithm.digest(content),
         }
     }
 
@@ -102,6 +547,16 @@ impl IntegrityValidator {
 
         let current_checksum = self.calculate_checksum(content);
 
+        // A checksum stored under a different hash algorithm can't be
+        // compared as a digest mismatch; the validator must be reconfigured
+        // with the algorithm the checksum was stored under instead.
+        if current_checksum.algorithm != stored_checksum.algorithm {
+            return Err(CompressionError::integrity_check(format!(
+                "Hash algorithm mismatch for '{}': checksum was stored with {}, validator is configured for {}",
+                path, stored_checksum.algorithm, current_checksum.algorithm
+            )));
+        }
+
         // Always check CRC32 and size
         if current_checksum.crc32 != stored_checksum.crc32 {
             return Ok(false);
@@ -118,25 +573,61 @@ impl IntegrityValidator {
             return Ok(false);
         }
 
+        // Check the configured algorithm's digest - the primary
+        // tamper-evidence check, and the only one a keyed BLAKE3 algorithm
+        // can't be forged against without the key.
+        if current_checksum.digest != stored_checksum.digest {
+            return Ok(false);
+        }
+
         Ok(true)
     }
 
-    /// Set dictionary hash for validation
-    pub fn set_dictionary_hash(&mut self, dictionary: &HashMap<String, String>) {
-        let mut hasher = Sha256::new();
+    /// Re-chunk `content` and return the chunks whose hash isn't already
+    /// present among the chunks stored for `path`, i.e. the ranges a
+    /// deduplicating re-compression would need to touch. Unrelated edits
+    /// leave most chunk boundaries untouched, so this is typically a small
+    /// subset of the file's total chunks rather than the whole file.
+    pub fn changed_chunks(&self, path: &str, content: &[u8]) -> CompressionResult<Vec<ChunkChecksum>> {
+        let stored_checksum = self.checksums.get(path)
+            .ok_or_else(|| CompressionError::integrity_check(
+                format!("No checksum found for file: {}", path)
+            ))?;
+
+        let known_chunks: std::collections::HashSet<String> = stored_checksum
+            .chunks
+            .iter()
+            .map(chunk_identity)
+            .collect();
+
+        Ok(chunk_checksums(content, self.validation_mode)
+            .into_iter()
+            .filter(|chunk| !known_chunks.contains(&chunk_identity(chunk)))
+            .collect())
+    }
 
-        // Sort dictionary entries for consistent hashing
+    /// Serialize dictionary entries, sorted by pattern, into the byte
+    /// sequence `set_dictionary_hash`/`validate_dictionary` hash.
+    fn serialize_dictionary(dictionary: &HashMap<String, String>) -> Vec<u8> {
         let mut entries: Vec<_> = dictionary.iter().collect();
         entries.sort_by(|a, b| a.0.cmp(b.0));
 
+        let mut buffer = Vec::new();
         for (pattern, token) in entries {
-            hasher.update(pattern.as_bytes());
-            hasher.update(b":");
-            hasher.update(token.as_bytes());
-            hasher.update(b"\n");
+            buffer.extend_from_slice(pattern.as_bytes());
+            buffer.push(b':');
+            buffer.extend_from_slice(token.as_bytes());
+            buffer.push(b'\n');
         }
+        buffer
+    }
 
-        self.dictionary_hash = Some(format!("{:x}", hasher.finalize()));
+    /// Set dictionary hash for validation, using the configured
+    /// `HashAlgorithm`. A `Blake3Keyed` algorithm makes this a MAC: only a
+    /// holder of the key can produce a matching hash.
+    pub fn set_dictionary_hash(&mut self, dictionary: &HashMap<String, String>) {
+        let buffer = Self::serialize_dictionary(dictionary);
+        self.dictionary_hash = Some(self.hash_algorithm.digest(&buffer));
     }
 
     /// Validate dictionary integrity
@@ -146,19 +637,8 @@ impl IntegrityValidator {
                 "No dictionary hash available for validation".to_string()
             ))?;
 
-        // Calculate current hash
-        let mut hasher = Sha256::new();
-        let mut entries: Vec<_> = dictionary.iter().collect();
-        entries.sort_by(|a, b| a.0.cmp(b.0));
-
-        for (pattern, token) in entries {
-            hasher.update(pattern.as_bytes());
-            hasher.update(b":");
-            hasher.update(token.as_bytes());
-            hasher.update(b"\n");
-        }
-
-        let current_hash = format!("{:x}", hasher.finalize());
+        let buffer = Self::serialize_dictionary(dictionary);
+        let current_hash = self.hash_algorithm.digest(&buffer);
 
         Ok(current_hash == *stored_hash)
     }
@@ -223,6 +703,68 @@ impl IntegrityValidator {
         Ok(true)
     }
 
+    /// Sort `files` by path and hash each one into its Merkle leaf.
+    fn sorted_leaves(&self, files: &[(String, Vec<u8>)]) -> Vec<(String, [u8; 32])> {
+        let mut leaves: Vec<(String, [u8; 32])> = files
+            .iter()
+            .map(|(path, content)| {
+                let checksum = self.calculate_checksum(content);
+                (path.clone(), merkle_leaf_hash(path, &checksum.sha256))
+            })
+            .collect();
+        leaves.sort_by(|a, b| a.0.cmp(&b.0));
+        leaves
+    }
+
+    /// Compute the Merkle root over `files`: leaves are `H(0x00 || path ||
+    /// file_sha256)` sorted by path, folded pairwise with `H(0x01 || left
+    /// || right)` up to a single 32-byte root, hex-encoded.
+    pub fn merkle_root(&self, files: &[(String, Vec<u8>)]) -> String {
+        let leaves = self.sorted_leaves(files);
+        if leaves.is_empty() {
+            return to_hex(&sha256_concat(&[]));
+        }
+        let hashes: Vec<[u8; 32]> = leaves.into_iter().map(|(_, hash)| hash).collect();
+        let levels = merkle_levels(hashes);
+        to_hex(&levels.last().unwrap()[0])
+    }
+
+    /// Compute the Merkle leaf hash for a single `(path, file_sha256)` pair,
+    /// so a consumer holding only that file and a proof can verify
+    /// inclusion without needing the rest of the archive.
+    pub fn leaf_hash(path: &str, sha256_hex: &str) -> [u8; 32] {
+        merkle_leaf_hash(path, sha256_hex)
+    }
+
+    /// Build the inclusion proof for `path`: the sibling hash and side at
+    /// each level from its leaf up to the root, `O(log n)` in file count.
+    pub fn inclusion_proof(&self, files: &[(String, Vec<u8>)], path: &str) -> CompressionResult<Vec<(Side, [u8; 32])>> {
+        let leaves = self.sorted_leaves(files);
+        let idx = leaves
+            .iter()
+            .position(|(leaf_path, _)| leaf_path == path)
+            .ok_or_else(|| CompressionError::integrity_check(format!("Path not found in archive: {}", path)))?;
+
+        let hashes: Vec<[u8; 32]> = leaves.into_iter().map(|(_, hash)| hash).collect();
+        let levels = merkle_levels(hashes);
+        Ok(merkle_proof_from_levels(&levels, idx))
+    }
+
+    /// Re-fold `leaf` up through `proof` and compare the result to `root`,
+    /// proving `leaf` is part of the archive without needing the other
+    /// files or the full manifest.
+    pub fn verify_inclusion(leaf: [u8; 32], proof: &[(Side, [u8; 32])], root: &str) -> CompressionResult<bool> {
+        let root = from_hex(root)?;
+        let mut current = leaf;
+        for (side, sibling) in proof {
+            current = match side {
+                Side::Right => merkle_node_hash(&current, sibling),
+                Side::Left => merkle_node_hash(sibling, &current),
+            };
+        }
+        Ok(current == root)
+    }
+
     /// Perform comprehensive integrity validation
     pub fn validate_comprehensive(&self,
         files: &[(String, Vec<u8>)],
@@ -234,8 +776,20 @@ impl IntegrityValidator {
             files_invalid: 0,
             dictionary_valid: true,
             validation_errors: Vec::new(),
+            archive_root_matches: None,
         };
 
+        // Compare the archive's recomputed Merkle root against the one
+        // recorded in the manifest, if any.
+        if let Some(stored_root) = &self.merkle_root {
+            let current_root = self.merkle_root(files);
+            let matches = current_root == *stored_root;
+            report.archive_root_matches = Some(matches);
+            if !matches {
+                report.validation_errors.push("Merkle root mismatch: archive contents changed".to_string());
+            }
+        }
+
         // Validate dictionary first
         if let Err(e) = self.validate_dictionary_bidirectional(dictionary) {
             report.dictionary_valid = false;
@@ -289,44 +843,138 @@ impl IntegrityValidator {
         manifest.push_str(&format!("# Validation Mode: {:?}\n", self.validation_mode));
         manifest.push_str("\n");
 
+        // Record the manifest format version so a reader knows whether path
+        // fields below are percent-escaped (v2) or raw (v1, pre-dating
+        // paths that contain ':').
+        manifest.push_str(&format!("MANIFEST_VERSION:{}\n", MANIFEST_VERSION));
+
         // Add dictionary hash
         if let Some(hash) = &self.dictionary_hash {
             manifest.push_str(&format!("DICT_HASH:{}\n", hash));
         }
+
+        // Add Merkle root, so a single file plus an inclusion proof can be
+        // verified against this manifest without trusting the rest of it
+        manifest.push_str(&format!("MERKLE_ROOT:{}\n", self.merkle_root(files)));
+
+        // Record which hash algorithm the FILE entries' digests use, so a
+        // validator configured for a different algorithm refuses to parse
+        // this manifest rather than comparing mismatched digests.
+        manifest.push_str(&format!("ALGO:{}\n", self.hash_algorithm.tag()));
         manifest.push_str("\n");
 
-        // Add file checksums
+        // Add file checksums. Paths are percent-escaped so a `:`, newline,
+        // or literal `%` inside a path can't be mistaken for a field
+        // delimiter and desynchronize the split below.
         manifest.push_str("# File Checksums\n");
         for (path, content) in files {
             let checksum = self.calculate_checksum(content);
-            manifest.push_str(&format!("FILE:{}:{}:{}:{}\n",
-                                      path, checksum.crc32, checksum.sha256, checksum.size));
+            let escaped_path = percent_encode_path(path);
+            manifest.push_str(&format!("FILE:{}:{}:{}:{}:{}:{}\n",
+                                      escaped_path, checksum.crc32, checksum.sha256, checksum.size,
+                                      checksum.algorithm, checksum.digest));
+            for chunk in &checksum.chunks {
+                manifest.push_str(&format!("CHUNK:{}:{}:{}:{}:{}\n",
+                                          escaped_path, chunk.offset, chunk.len, chunk.crc32, chunk.sha256));
+            }
         }
 
         Ok(manifest)
     }
 
-    /// Parse integrity manifest
+    /// Generate a manifest and seal it with `encrypt_manifest`, keyed and
+    /// authenticated against `files`' Merkle root.
+    pub fn generate_encrypted_manifest(&self,
+        files: &[(String, Vec<u8>)],
+        dictionary: &HashMap<String, String>,
+        key: &[u8; 32],
+    ) -> CompressionResult<Vec<u8>> {
+        let manifest = self.generate_manifest(files, dictionary)?;
+        encrypt_manifest(&manifest, key, &self.merkle_root(files))
+    }
+
+    /// Authenticate and decrypt a manifest produced by
+    /// `generate_encrypted_manifest`, then parse it. `expected_root_sha256`
+    /// must be the Merkle root of the files the caller expects this
+    /// manifest to describe; authentication fails before any checksum
+    /// parsing happens if the tag, key, or root don't match.
+    pub fn parse_encrypted_manifest(&mut self, data: &[u8], key: &[u8; 32], expected_root_sha256: &str) -> CompressionResult<()> {
+        let manifest = decrypt_manifest(data, key, expected_root_sha256)?;
+        self.parse_manifest(&manifest)
+    }
+
+    /// Sign `manifest` with a detached ed25519 signature, returning the
+    /// manifest text with a trailing `SIG:<hex>` line appended. The
+    /// signature covers `manifest` exactly as passed in, so it must be the
+    /// last thing appended to the manifest body before signing. Pairs with
+    /// `verify_manifest_signature` so a distributor can publish an archive
+    /// plus a signed manifest and recipients confirm both integrity (via
+    /// the checksums) and authenticity (via a pinned public key).
+    pub fn sign_manifest(&self, manifest: &str, signing_key: &[u8; 32]) -> String {
+        let key = SigningKey::from_bytes(signing_key);
+        let signature = key.sign(manifest.as_bytes());
+        format!("{}SIG:{}\n", manifest, to_hex(&signature.to_bytes()))
+    }
+
+    /// Parse integrity manifest. Manifests written without a
+    /// `MANIFEST_VERSION:` line are read as v1, with `FILE:`/`CHUNK:` path
+    /// fields taken literally (pre-dating paths that contain `:`); `v2`
+    /// manifests percent-decode the path field instead, so a colon, newline,
+    /// or literal `%` inside a path round-trips instead of desynchronizing
+    /// the `:`-split fields that follow it.
     pub fn parse_manifest(&mut self, manifest: &str) -> CompressionResult<()> {
+        let mut version: u32 = 1;
+
         for line in manifest.lines() {
             let line = line.trim();
             if line.is_empty() || line.starts_with('#') {
                 continue;
             }
 
-            if line.starts_with("DICT_HASH:") {
+            if line.starts_with("MANIFEST_VERSION:") {
+                version = line[18..].parse::<u32>()
+                    .map_err(|_| CompressionError::integrity_check("Invalid MANIFEST_VERSION in manifest".to_string()))?;
+            } else if line.starts_with("DICT_HASH:") {
                 self.dictionary_hash = Some(line[10..].to_string());
+            } else if line.starts_with("MERKLE_ROOT:") {
+                self.merkle_root = Some(line[12..].to_string());
+            } else if line.starts_with("ALGO:") {
+                let manifest_algorithm = HashAlgorithmTag::parse(&line[5..])?;
+                if manifest_algorithm != self.hash_algorithm.tag() {
+                    return Err(CompressionError::integrity_check(format!(
+                        "Manifest hash algorithm '{}' does not match validator's configured algorithm '{}'",
+                        manifest_algorithm, self.hash_algorithm.tag()
+                    )));
+                }
             } else if line.starts_with("FILE:") {
                 let parts: Vec<&str> = line[5..].split(':').collect();
-                if parts.len() == 4 {
-                    let path = parts[0].to_string();
+                if parts.len() == 6 {
+                    let path = if version >= 2 { percent_decode_path(parts[0])? } else { parts[0].to_string() };
                     let crc32 = parts[1].parse::<u32>()
                         .map_err(|_| CompressionError::integrity_check("Invalid CRC32 in manifest".to_string()))?;
                     let sha256 = parts[2].to_string();
                     let size = parts[3].parse::<usize>()
                         .map_err(|_| CompressionError::integrity_check("Invalid size in manifest".to_string()))?;
+                    let algorithm = HashAlgorithmTag::parse(parts[4])?;
+                    let digest = parts[5].to_string();
 
-                    self.add_file_checksum(&path, FileChecksum { crc32, sha256, size });
+                    self.add_file_checksum(&path, FileChecksum { crc32, sha256, size, chunks: Vec::new(), algorithm, digest });
+                }
+            } else if line.starts_with("CHUNK:") {
+                let parts: Vec<&str> = line[6..].split(':').collect();
+                if parts.len() == 5 {
+                    let path = if version >= 2 { percent_decode_path(parts[0])? } else { parts[0].to_string() };
+                    let offset = parts[1].parse::<usize>()
+                        .map_err(|_| CompressionError::integrity_check("Invalid offset in manifest".to_string()))?;
+                    let len = parts[2].parse::<usize>()
+                        .map_err(|_| CompressionError::integrity_check("Invalid length in manifest".to_string()))?;
+                    let crc32 = parts[3].parse::<u32>()
+                        .map_err(|_| CompressionError::integrity_check("Invalid chunk CRC32 in manifest".to_string()))?;
+                    let sha256 = parts[4].to_string();
+
+                    if let Some(checksum) = self.checksums.get_mut(&path) {
+                        checksum.chunks.push(ChunkChecksum { offset, len, crc32, sha256 });
+                    }
                 }
             }
         }
@@ -343,6 +991,10 @@ impl std::fmt::Display for IntegrityReport {
         writeln!(f, "  Files invalid: {}", self.files_invalid)?;
         writeln!(f, "  Dictionary valid: {}", self.dictionary_valid)?;
 
+        if let Some(matches) = self.archive_root_matches {
+            writeln!(f, "  Archive root matches: {}", matches)?;
+        }
+
         if !self.validation_errors.is_empty() {
             writeln!(f, "  Validation errors:")?;
             for error in &self.validation_errors {
@@ -365,4 +1017,433 @@ mod tests {
 
         let checksum = validator.calculate_checksum(content);
         assert_eq!(checksum.size, 13);
-        assert_ne!(check
+        assert_ne!(checksum.crc32, 0);
+        assert!(!checksum.sha256.is_empty());
+    }
+
+    #[test]
+    fn test_checksum_calculation_fast_mode_skips_sha256() {
+        let validator = IntegrityValidator::new(ValidationMode::Fast);
+        let content = b"Hello, World!";
+
+        let checksum = validator.calculate_checksum(content);
+        assert!(checksum.sha256.is_empty());
+    }
+
+    #[test]
+    fn test_chunking_reassembles_to_original_content() {
+        let validator = IntegrityValidator::new(ValidationMode::Standard);
+        let content = "the quick brown fox jumps over the lazy dog\n".repeat(2_000).into_bytes();
+
+        let checksum = validator.calculate_checksum(&content);
+        assert!(!checksum.chunks.is_empty());
+
+        let mut reassembled = Vec::with_capacity(content.len());
+        for chunk in &checksum.chunks {
+            reassembled.extend_from_slice(&content[chunk.offset..chunk.offset + chunk.len]);
+        }
+        assert_eq!(reassembled, content);
+    }
+
+    #[test]
+    fn test_chunking_respects_min_and_max_size() {
+        let validator = IntegrityValidator::new(ValidationMode::Standard);
+        let content = "a".repeat(500_000).into_bytes();
+
+        let checksum = validator.calculate_checksum(&content);
+        for (i, chunk) in checksum.chunks.iter().enumerate() {
+            if i + 1 < checksum.chunks.len() {
+                assert!(chunk.len >= CHUNK_MIN_SIZE);
+            }
+            assert!(chunk.len <= CHUNK_MAX_SIZE);
+        }
+    }
+
+    #[test]
+    fn test_changed_chunks_only_reports_edited_region() {
+        let mut validator = IntegrityValidator::new(ValidationMode::Standard);
+        let original = "the quick brown fox jumps over the lazy dog\n".repeat(2_000).into_bytes();
+
+        let checksum = validator.calculate_checksum(&original);
+        validator.add_file_checksum("file.txt", checksum.clone());
+
+        // Unmodified content should report no changed chunks.
+        let unchanged = validator.changed_chunks("file.txt", &original).unwrap();
+        assert!(unchanged.is_empty());
+
+        // Editing a small region near the middle should only dirty the
+        // chunk(s) covering that region, not the whole file.
+        let mut edited = original.clone();
+        let mid = edited.len() / 2;
+        edited.splice(mid..mid + 5, b"ZZZZZ".iter().copied());
+
+        let changed = validator.changed_chunks("file.txt", &edited).unwrap();
+        assert!(!changed.is_empty());
+        assert!(changed.len() < checksum.chunks.len());
+    }
+
+    #[test]
+    fn test_manifest_round_trips_chunk_checksums() {
+        let mut validator = IntegrityValidator::new(ValidationMode::Standard);
+        let files = vec![(
+            "file.txt".to_string(),
+            "the quick brown fox jumps over the lazy dog\n".repeat(2_000).into_bytes(),
+        )];
+        let dictionary = HashMap::new();
+
+        let manifest = validator.generate_manifest(&files, &dictionary).unwrap();
+        assert!(manifest.contains("CHUNK:file.txt:"));
+
+        let mut parsed = IntegrityValidator::new(ValidationMode::Standard);
+        parsed.parse_manifest(&manifest).unwrap();
+
+        let original_chunks = validator.calculate_checksum(&files[0].1).chunks;
+        let stored = parsed.checksums.get("file.txt").unwrap();
+        assert_eq!(stored.chunks.len(), original_chunks.len());
+        for (parsed_chunk, original_chunk) in stored.chunks.iter().zip(original_chunks.iter()) {
+            assert_eq!(parsed_chunk.offset, original_chunk.offset);
+            assert_eq!(parsed_chunk.len, original_chunk.len);
+            assert_eq!(parsed_chunk.sha256, original_chunk.sha256);
+        }
+    }
+
+    fn sample_files() -> Vec<(String, Vec<u8>)> {
+        vec![
+            ("a.txt".to_string(), b"content of file a".to_vec()),
+            ("b.txt".to_string(), b"content of file b".to_vec()),
+            ("c.txt".to_string(), b"content of file c".to_vec()),
+            ("d.txt".to_string(), b"content of file d".to_vec()),
+            ("e.txt".to_string(), b"content of file e".to_vec()),
+        ]
+    }
+
+    #[test]
+    fn test_merkle_root_is_order_independent() {
+        let validator = IntegrityValidator::new(ValidationMode::Standard);
+        let mut files = sample_files();
+        let root_a = validator.merkle_root(&files);
+
+        files.reverse();
+        let root_b = validator.merkle_root(&files);
+
+        assert_eq!(root_a, root_b, "root should not depend on input order, only path sort order");
+    }
+
+    #[test]
+    fn test_merkle_root_changes_when_a_file_changes() {
+        let validator = IntegrityValidator::new(ValidationMode::Standard);
+        let mut files = sample_files();
+        let original_root = validator.merkle_root(&files);
+
+        files[2].1 = b"modified content".to_vec();
+        let changed_root = validator.merkle_root(&files);
+
+        assert_ne!(original_root, changed_root);
+    }
+
+    #[test]
+    fn test_inclusion_proof_verifies_against_root() {
+        let validator = IntegrityValidator::new(ValidationMode::Standard);
+        let files = sample_files();
+        let root = validator.merkle_root(&files);
+
+        for (path, content) in &files {
+            let checksum = validator.calculate_checksum(content);
+            let leaf = IntegrityValidator::leaf_hash(path, &checksum.sha256);
+            let proof = validator.inclusion_proof(&files, path).unwrap();
+
+            assert!(IntegrityValidator::verify_inclusion(leaf, &proof, &root).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_inclusion_proof_rejects_wrong_leaf() {
+        let validator = IntegrityValidator::new(ValidationMode::Standard);
+        let files = sample_files();
+        let root = validator.merkle_root(&files);
+
+        let proof = validator.inclusion_proof(&files, "a.txt").unwrap();
+        let wrong_leaf = IntegrityValidator::leaf_hash("a.txt", "0000000000000000000000000000000000000000000000000000000000000000");
+
+        assert!(!IntegrityValidator::verify_inclusion(wrong_leaf, &proof, &root).unwrap());
+    }
+
+    #[test]
+    fn test_inclusion_proof_rejects_unknown_path() {
+        let validator = IntegrityValidator::new(ValidationMode::Standard);
+        let files = sample_files();
+
+        assert!(validator.inclusion_proof(&files, "missing.txt").is_err());
+    }
+
+    #[test]
+    fn test_manifest_round_trips_merkle_root_and_reports_match() {
+        let mut validator = IntegrityValidator::new(ValidationMode::Standard);
+        let files = sample_files();
+        let dictionary = HashMap::new();
+
+        let manifest = validator.generate_manifest(&files, &dictionary).unwrap();
+        assert!(manifest.contains("MERKLE_ROOT:"));
+
+        let mut parsed = IntegrityValidator::new(ValidationMode::Standard);
+        parsed.parse_manifest(&manifest).unwrap();
+        for (path, content) in &files {
+            let checksum = parsed.calculate_checksum(content);
+            parsed.add_file_checksum(path, checksum);
+        }
+
+        let report = parsed.validate_comprehensive(&files, &dictionary).unwrap();
+        assert_eq!(report.archive_root_matches, Some(true));
+    }
+
+    #[test]
+    fn test_encrypted_manifest_round_trips() {
+        let key = [7u8; 32];
+        let validator = IntegrityValidator::new(ValidationMode::Standard);
+        let files = sample_files();
+        let dictionary = HashMap::new();
+
+        let sealed = validator.generate_encrypted_manifest(&files, &dictionary, &key).unwrap();
+
+        let mut parsed = IntegrityValidator::new(ValidationMode::Standard);
+        let root = validator.merkle_root(&files);
+        parsed.parse_encrypted_manifest(&sealed, &key, &root).unwrap();
+
+        assert!(parsed.checksums.contains_key("a.txt"));
+    }
+
+    #[test]
+    fn test_decrypt_manifest_rejects_wrong_key() {
+        let key = [7u8; 32];
+        let wrong_key = [8u8; 32];
+        let validator = IntegrityValidator::new(ValidationMode::Standard);
+        let files = sample_files();
+        let dictionary = HashMap::new();
+
+        let sealed = validator.generate_encrypted_manifest(&files, &dictionary, &key).unwrap();
+        let root = validator.merkle_root(&files);
+
+        let mut parsed = IntegrityValidator::new(ValidationMode::Standard);
+        let err = parsed.parse_encrypted_manifest(&sealed, &wrong_key, &root).unwrap_err();
+        assert!(matches!(err, CompressionError::ManifestAuthentication { .. }));
+    }
+
+    #[test]
+    fn test_decrypt_manifest_rejects_tampered_ciphertext() {
+        let key = [7u8; 32];
+        let validator = IntegrityValidator::new(ValidationMode::Standard);
+        let files = sample_files();
+        let dictionary = HashMap::new();
+
+        let mut sealed = validator.generate_encrypted_manifest(&files, &dictionary, &key).unwrap();
+        let last = sealed.len() - 1;
+        sealed[last] ^= 0xFF;
+        let root = validator.merkle_root(&files);
+
+        let mut parsed = IntegrityValidator::new(ValidationMode::Standard);
+        assert!(parsed.parse_encrypted_manifest(&sealed, &key, &root).is_err());
+    }
+
+    #[test]
+    fn test_decrypt_manifest_rejects_wrong_root_as_aad() {
+        let key = [7u8; 32];
+        let validator = IntegrityValidator::new(ValidationMode::Standard);
+        let files = sample_files();
+        let dictionary = HashMap::new();
+
+        let sealed = validator.generate_encrypted_manifest(&files, &dictionary, &key).unwrap();
+
+        let mut parsed = IntegrityValidator::new(ValidationMode::Standard);
+        let wrong_root = "0".repeat(64);
+        assert!(parsed.parse_encrypted_manifest(&sealed, &key, &wrong_root).is_err());
+    }
+
+    #[test]
+    fn test_blake3_checksum_round_trips() {
+        let mut validator = IntegrityValidator::new(ValidationMode::Standard).with_hash_algorithm(HashAlgorithm::Blake3);
+        let content = b"the quick brown fox jumps over the lazy dog";
+
+        let checksum = validator.calculate_checksum(content);
+        assert_eq!(checksum.algorithm, HashAlgorithmTag::Blake3);
+
+        validator.add_file_checksum("file.txt", checksum);
+        assert!(validator.validate_file("file.txt", content).unwrap());
+        assert!(!validator.validate_file("file.txt", b"different content").unwrap());
+    }
+
+    #[test]
+    fn test_blake3_keyed_checksum_is_tamper_evident_to_wrong_key() {
+        let key = [3u8; 32];
+        let wrong_key = [9u8; 32];
+        let content = b"secret archive contents";
+
+        let mut signer = IntegrityValidator::new(ValidationMode::Standard).with_hash_algorithm(HashAlgorithm::Blake3Keyed(key));
+        let checksum = signer.calculate_checksum(content);
+        signer.add_file_checksum("file.txt", checksum);
+        assert!(signer.validate_file("file.txt", content).unwrap());
+
+        let mut attacker = IntegrityValidator::new(ValidationMode::Standard).with_hash_algorithm(HashAlgorithm::Blake3Keyed(wrong_key));
+        let forged = attacker.calculate_checksum(content);
+        attacker.add_file_checksum("file.txt", forged);
+        assert!(!attacker.validate_file("file.txt", content).unwrap());
+    }
+
+    #[test]
+    fn test_validate_file_rejects_algorithm_mismatch() {
+        let mut sha_validator = IntegrityValidator::new(ValidationMode::Standard);
+        let content = b"some file content";
+        let checksum = sha_validator.calculate_checksum(content);
+        sha_validator.add_file_checksum("file.txt", checksum);
+
+        let mut blake_validator = IntegrityValidator::new(ValidationMode::Standard).with_hash_algorithm(HashAlgorithm::Blake3);
+        blake_validator.add_file_checksum("file.txt", sha_validator.calculate_checksum(content));
+
+        assert!(blake_validator.validate_file("file.txt", content).is_err());
+    }
+
+    #[test]
+    fn test_dictionary_hash_uses_configured_algorithm() {
+        let mut dictionary = HashMap::new();
+        dictionary.insert("pattern_a".to_string(), "T0001".to_string());
+
+        let mut sha_validator = IntegrityValidator::new(ValidationMode::Standard);
+        sha_validator.set_dictionary_hash(&dictionary);
+
+        let mut blake_validator = IntegrityValidator::new(ValidationMode::Standard).with_hash_algorithm(HashAlgorithm::Blake3);
+        blake_validator.set_dictionary_hash(&dictionary);
+
+        assert_ne!(sha_validator.dictionary_hash, blake_validator.dictionary_hash);
+        assert!(sha_validator.validate_dictionary(&dictionary).unwrap());
+        assert!(blake_validator.validate_dictionary(&dictionary).unwrap());
+    }
+
+    #[test]
+    fn test_manifest_rejects_algorithm_mismatch_before_parsing_files() {
+        let validator = IntegrityValidator::new(ValidationMode::Standard).with_hash_algorithm(HashAlgorithm::Blake3);
+        let files = sample_files();
+        let dictionary = HashMap::new();
+
+        let manifest = validator.generate_manifest(&files, &dictionary).unwrap();
+        assert!(manifest.contains("ALGO:BLAKE3"));
+
+        let mut sha_validator = IntegrityValidator::new(ValidationMode::Standard);
+        let err = sha_validator.parse_manifest(&manifest).unwrap_err();
+        assert!(matches!(err, CompressionError::IntegrityCheck { .. }));
+        assert!(sha_validator.checksums.is_empty(), "no FILE lines should be parsed after an ALGO mismatch");
+    }
+
+    #[test]
+    fn test_sign_manifest_round_trips() {
+        let signing_key = [5u8; 32];
+        let public_key = SigningKey::from_bytes(&signing_key).verifying_key().to_bytes();
+
+        let validator = IntegrityValidator::new(ValidationMode::Standard);
+        let files = sample_files();
+        let dictionary = HashMap::new();
+
+        let manifest = validator.generate_manifest(&files, &dictionary).unwrap();
+        let signed = validator.sign_manifest(&manifest, &signing_key);
+        assert!(signed.contains("SIG:"));
+
+        assert!(verify_manifest_signature(&signed, &public_key).unwrap());
+
+        let mut parsed = IntegrityValidator::new(ValidationMode::Standard);
+        parsed.parse_manifest(&signed).unwrap();
+        assert!(parsed.checksums.contains_key("a.txt"), "a trailing SIG: line must not disturb FILE: parsing");
+    }
+
+    #[test]
+    fn test_verify_manifest_signature_rejects_wrong_public_key() {
+        let signing_key = [5u8; 32];
+        let wrong_public_key = SigningKey::from_bytes(&[6u8; 32]).verifying_key().to_bytes();
+
+        let validator = IntegrityValidator::new(ValidationMode::Standard);
+        let manifest = validator.generate_manifest(&sample_files(), &HashMap::new()).unwrap();
+        let signed = validator.sign_manifest(&manifest, &signing_key);
+
+        assert!(!verify_manifest_signature(&signed, &wrong_public_key).unwrap());
+    }
+
+    #[test]
+    fn test_verify_manifest_signature_rejects_tampered_body() {
+        let signing_key = [5u8; 32];
+        let public_key = SigningKey::from_bytes(&signing_key).verifying_key().to_bytes();
+
+        let validator = IntegrityValidator::new(ValidationMode::Standard);
+        let manifest = validator.generate_manifest(&sample_files(), &HashMap::new()).unwrap();
+        let signed = validator.sign_manifest(&manifest, &signing_key);
+
+        let tampered = signed.replacen("MERKLE_ROOT:", "MERKLE_ROOT:tampered", 1);
+        assert!(!verify_manifest_signature(&tampered, &public_key).unwrap());
+    }
+
+    #[test]
+    fn test_verify_manifest_signature_rejects_missing_sig_line() {
+        let validator = IntegrityValidator::new(ValidationMode::Standard);
+        let manifest = validator.generate_manifest(&sample_files(), &HashMap::new()).unwrap();
+
+        let err = verify_manifest_signature(&manifest, &[0u8; 32]).unwrap_err();
+        assert!(matches!(err, CompressionError::SignatureVerification { .. }));
+    }
+
+    #[test]
+    fn test_manifest_round_trips_paths_with_colons_spaces_and_newlines() {
+        let validator = IntegrityValidator::new(ValidationMode::Standard);
+        let files = vec![
+            ("C:\\weird\\windows\\path.txt".to_string(), b"windows-style path".to_vec()),
+            ("dir/file with spaces.txt".to_string(), b"spacey path".to_vec()),
+            ("dir/name\nwith\nnewlines.txt".to_string(), b"newline path".to_vec()),
+            ("https://example.com/archive:v1.tar".to_string(), b"url-like path".to_vec()),
+            ("literal%percent.txt".to_string(), b"percent path".to_vec()),
+        ];
+        let dictionary = HashMap::new();
+
+        let manifest = validator.generate_manifest(&files, &dictionary).unwrap();
+        assert!(manifest.contains("MANIFEST_VERSION:2"));
+
+        let mut parsed = IntegrityValidator::new(ValidationMode::Standard);
+        parsed.parse_manifest(&manifest).unwrap();
+
+        assert_eq!(parsed.checksums.len(), files.len(), "every FILE: entry must survive parsing, none silently dropped");
+        for (path, content) in &files {
+            assert!(parsed.validate_file(path, content).unwrap(), "path '{}' did not round-trip", path);
+        }
+    }
+
+    #[test]
+    fn test_manifest_round_trips_empty_sha256_in_fast_mode() {
+        let validator = IntegrityValidator::new(ValidationMode::Fast);
+        let files = vec![("weird:path.txt".to_string(), b"fast mode content".to_vec())];
+        let dictionary = HashMap::new();
+
+        let manifest = validator.generate_manifest(&files, &dictionary).unwrap();
+
+        let mut parsed = IntegrityValidator::new(ValidationMode::Fast);
+        parsed.parse_manifest(&manifest).unwrap();
+
+        let checksum = parsed.checksums.get("weird:path.txt").unwrap();
+        assert!(checksum.sha256.is_empty());
+        assert!(parsed.validate_file("weird:path.txt", b"fast mode content").unwrap());
+    }
+
+    #[test]
+    fn test_parse_manifest_reads_legacy_v1_lines_without_colons_in_paths() {
+        let legacy_manifest = "# Integrity Manifest\n\nMERKLE_ROOT:0000000000000000000000000000000000000000000000000000000000000000\nALGO:SHA256\n\n# File Checksums\nFILE:plain.txt:12345:abc:10:SHA256:abc\n";
+
+        let mut parsed = IntegrityValidator::new(ValidationMode::Standard);
+        parsed.parse_manifest(legacy_manifest).unwrap();
+
+        assert!(parsed.checksums.contains_key("plain.txt"));
+    }
+
+    #[test]
+    fn test_percent_encode_decode_path_round_trips() {
+        let paths = ["plain.txt", "weird:path.txt", "dir/name\nwith\nnewlines.txt", "literal%percent.txt", "unicode/\u{1F600}.txt"];
+        for path in paths {
+            let encoded = percent_encode_path(path);
+            assert!(!encoded.contains(':'), "encoded path must not contain a raw ':': {}", encoded);
+            let decoded = percent_decode_path(&encoded).unwrap();
+            assert_eq!(decoded, path);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Lz4 compression integration
+//!
+//! Thin wrapper around `lz4_flex`'s block API, favoring compression/
+//! decompression speed over ratio.
+
+use crate::compression::codec::Compressor;
+use crate::compression::config::Lz4Level;
+use crate::compression::error::{CompressionError, CompressionResult};
+
+/// Lz4 compressor using the size-prepended block format, so decompression
+/// doesn't need to know the original length ahead of time.
+///
+/// Carries an `Lz4Level` so `Codec::Lz4` is self-describing and round-trips
+/// through its `"lz4/N"` string form, but the level has no effect here:
+/// `lz4_flex`'s block API (`compress_prepend_size`) is the fast, fixed
+/// algorithm, not the tunable lz4hc one, so the level is accepted and
+/// stored but never forwarded to a compression call.
+#[derive(Debug, Default)]
+pub struct Lz4Compressor {
+    #[allow(dead_code)]
+    level: Lz4Level,
+}
+
+impl Lz4Compressor {
+    /// Create a new lz4 compressor at the given level (currently unused; see struct docs).
+    pub fn new(level: Lz4Level) -> Self {
+        Self { level }
+    }
+}
+
+impl Compressor for Lz4Compressor {
+    fn compress(&self, data: &[u8]) -> CompressionResult<Vec<u8>> {
+        Ok(lz4_flex::compress_prepend_size(data))
+    }
+
+    fn decompress(&self, data: &[u8]) -> CompressionResult<Vec<u8>> {
+        lz4_flex::decompress_size_prepended(data)
+            .map_err(|e| CompressionError::lz4_compression(e.to_string()))
+    }
+
+    fn codec_id(&self) -> u8 {
+        2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lz4_round_trip() {
+        let compressor = Lz4Compressor::new(Lz4Level::default());
+        let data = b"function test() { return 'hello world'; }".repeat(10);
+
+        let compressed = compressor.compress(&data).unwrap();
+        let decompressed = compressor.decompress(&compressed).unwrap();
+
+        assert_eq!(decompressed, data);
+    }
+
+    #[test]
+    fn test_lz4_rejects_garbage() {
+        let compressor = Lz4Compressor::new(Lz4Level::default());
+        assert!(compressor.decompress(b"not lz4 data").is_err());
+    }
+}
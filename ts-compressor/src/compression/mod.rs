@@ -4,39 +4,128 @@
 //! for maximum codebase size reduction through intelligent pattern recognition
 //! and hexadecimal token replacement.
 
+pub mod ac_matcher;
+pub mod algotest;
 pub mod analyzer;
+pub mod base64;
+pub mod block_archive;
+pub mod brotli_compressor;
 pub mod builder;
+pub mod chunked;
+pub mod cleanup;
+pub mod codec;
 pub mod compressor;
 pub mod concurrent_analyzer;
 pub mod config;
+pub mod cover;
+pub mod database;
+pub mod dedup;
+pub mod deflate_compressor;
+pub mod dictionary_archive;
 pub mod error;
+pub mod fastcdc;
+pub mod file_type;
+pub mod framed;
+pub mod fsst;
 pub mod generator;
+pub mod integrity;
+pub mod lz4_compressor;
+pub mod parallel_zstd_compressor;
+pub mod pattern_matcher;
+pub mod pattern_miner;
 pub mod replacer;
+pub mod restore;
+pub mod snappy_compressor;
+pub mod stored_compressor;
+pub mod streaming_analyzer;
 pub mod types;
+pub mod ui;
+pub mod wu_manber;
 pub mod zstd_compressor;
 
 // Re-export main types for convenience
+pub use ac_matcher::AhoCorasickMatcher;
+pub use algotest::{AlgotestReport, AlgotestResult};
 pub use analyzer::FrequencyAnalyzer;
+pub use block_archive::{
+    extract_archive as extract_block_archive, read_archive as read_block_archive,
+    write_archive as write_block_archive, IndexEntry as BlockIndexEntry,
+};
+pub use brotli_compressor::BrotliCompressor;
 pub use builder::DictionaryBuilder;
+pub use chunked::{Batch, ChunkManifest, ChunkedOutcome};
+pub use cleanup::CleanupGuard;
+pub use codec::{Codec, Compressor};
 pub use compressor::UniversalCompressor;
 pub use concurrent_analyzer::ConcurrentFrequencyAnalyzer;
-pub use config::CompressionConfig;
+pub use config::{
+    ChecksumConfig, ChunkingStrategy, CompressionConfig, CompressorLimits, ContentDefinedChunking,
+    CoverConfig, DictSize, DictionaryStrategy, MemoryBudget, ResourceBudget, ThreadPinning,
+    WindowLog, ZstdAdvanced, ZstdDictionaryConfig, ZstdStrategy,
+};
+pub use fastcdc::chunk_boundaries as fastcdc_chunk_boundaries;
+pub use cover::train as cover_train;
+pub use database::{
+    CheckpointStatus, CleanupSummary, CompressionCheckpoint, CompressionDatabase,
+    DatabaseStatistics, RetentionPolicy, MAX_BATCH_BYTES, MAX_KEY_BYTES, MAX_VALUE_BYTES,
+};
+pub use dedup::{deduplicate, ChunkPool, ChunkedFile, DedupResult};
+pub use deflate_compressor::DeflateCompressor;
+pub use dictionary_archive::{ArchivedDictionary, DictionaryEntry};
 pub use error::CompressionError;
+pub use file_type::{FilePolicy, FileTypeRegistry};
+pub use framed::{compress_framed, decompress_framed};
+pub use fsst::{FsstCompressor, SymbolTable};
 pub use generator::{HexTokenGenerator, HexToken};
+pub use integrity::{
+    ChunkChecksum, FileChecksum, HashAlgorithm, HashAlgorithmTag, IntegrityReport, IntegrityValidator,
+    Side, ValidationMode, verify_manifest_signature,
+};
+pub use lz4_compressor::Lz4Compressor;
+pub use parallel_zstd_compressor::ParallelZstdCompressor;
+pub use pattern_matcher::AhoCorasick;
+pub use pattern_miner::{MinedDictionary, PatternMiner};
 pub use replacer::PatternReplacer;
-pub use types::{CompressionResult, CompressionStatistics, FileEntry};
-pub use zstd_compressor::{ZstdCompressor, ZstdStreamCompressor, ZstdStreamDecompressor, ZstdCompressionStats};
+pub use restore::{RestoreReport, Verification};
+pub use snappy_compressor::SnappyCompressor;
+pub use stored_compressor::StoredCompressor;
+pub use streaming_analyzer::StreamingAnalyzer;
+pub use types::{
+    CollectionLimit, CompressionMethod, CompressionResult, CompressionStatistics, ContentHeader,
+    Dictionary, FileEntry, MethodStats,
+};
+pub use ui::{ColorUI, PlainUI, Report, Sizes, UiKind, UI};
+pub use wu_manber::PatternSearcher;
+pub use zstd_compressor::{ZstdCompressionStats, ZstdCompressor, ZstdDictionary, ZstdStreamCompressor, ZstdStreamDecompressor};
 
 /// Core trait for frequency analysis operations
 pub trait FrequencyAnalysis {
     /// Analyze content for pattern frequencies
     fn analyze_content(&mut self, content: &str);
-    
+
     /// Get patterns that meet frequency threshold
     fn get_frequent_patterns(&self) -> Vec<(String, usize)>;
-    
+
     /// Check if pattern should be compressed
     fn should_compress_pattern(&self, pattern: &str) -> bool;
+
+    /// Re-scan `content` with an Aho-Corasick automaton built over the
+    /// current candidate patterns (`get_frequent_patterns`) and count
+    /// genuinely non-overlapping occurrences, greedily preferring the
+    /// longest match ending at each position and resuming the scan right
+    /// after it. Unlike `get_frequent_patterns`, these counts reflect real
+    /// compression savings rather than overlap-inflated window counts.
+    fn get_nonoverlapping_frequencies(&self, content: &str) -> Vec<(String, usize)> {
+        let candidates: Vec<String> = self
+            .get_frequent_patterns()
+            .into_iter()
+            .map(|(pattern, _)| pattern)
+            .collect();
+        if candidates.is_empty() {
+            return Vec::new();
+        }
+        AhoCorasick::new(candidates).count_nonoverlapping(content)
+    }
 }
 
 /// Core trait for dictionary building operations
@@ -0,0 +1,183 @@
+//! Parallel block compression for large payloads
+//!
+//! Splits large input into fixed-size blocks, compresses each one
+//! concurrently across a rayon thread pool, and concatenates the
+//! resulting independently-decodable zstd frames, mirroring the
+//! block-parallel approach used by parallel gzip tools. The per-block
+//! compressed lengths are recorded in a small header so the matching
+//! decompressor can find and decode (or skip to) each block without
+//! rescanning the whole payload.
+
+use crate::compression::config::ZstdLevel;
+use crate::compression::error::{CompressionError, CompressionResult};
+use crate::compression::framed::{read_varint, write_varint};
+use crate::compression::zstd_compressor::ZstdCompressor;
+use rayon::prelude::*;
+
+/// Default block size blocks are split into before compressing.
+const DEFAULT_BLOCK_SIZE: usize = 256 * 1024; // 256KiB
+
+fn build_thread_pool(num_threads: usize) -> CompressionResult<rayon::ThreadPool> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .map_err(|e| CompressionError::config_validation(e.to_string()))
+}
+
+/// Compresses large payloads by splitting them into fixed-size blocks and
+/// compressing each block concurrently, trading a small compression-ratio
+/// cost for near-linear speedups on big inputs.
+pub struct ParallelZstdCompressor {
+    compression_level: ZstdLevel,
+    block_size: usize,
+    num_threads: usize,
+}
+
+impl ParallelZstdCompressor {
+    /// Create a new parallel compressor at `level`, using the default
+    /// block size and all available parallelism.
+    pub fn new(level: ZstdLevel) -> Self {
+        Self {
+            compression_level: level,
+            block_size: DEFAULT_BLOCK_SIZE,
+            num_threads: num_cpus::get(),
+        }
+    }
+
+    /// Override the block size input is split into before compression.
+    #[allow(dead_code)]
+    pub fn with_block_size(mut self, block_size: usize) -> Self {
+        self.block_size = block_size.max(1);
+        self
+    }
+
+    /// Override the number of threads blocks are compressed across.
+    #[allow(dead_code)]
+    pub fn with_num_threads(mut self, num_threads: usize) -> Self {
+        self.num_threads = num_threads.max(1);
+        self
+    }
+
+    /// Compress `data`, returning `[num_blocks varint][compressed_len
+    /// varint]*num_blocks` followed by the concatenated compressed blocks.
+    pub fn compress(&self, data: &[u8]) -> CompressionResult<Vec<u8>> {
+        if data.is_empty() {
+            let mut header = Vec::new();
+            write_varint(0, &mut header);
+            return Ok(header);
+        }
+
+        let blocks: Vec<&[u8]> = data.chunks(self.block_size).collect();
+        let compressor = ZstdCompressor::new(self.compression_level)?;
+        let pool = build_thread_pool(self.num_threads)?;
+
+        let compressed_blocks: Vec<Vec<u8>> = pool
+            .install(|| blocks.par_iter().map(|block| compressor.compress(block)).collect::<CompressionResult<Vec<_>>>())?;
+
+        let mut header = Vec::new();
+        write_varint(compressed_blocks.len() as u64, &mut header);
+        for block in &compressed_blocks {
+            write_varint(block.len() as u64, &mut header);
+        }
+
+        let mut output = header;
+        for block in compressed_blocks {
+            output.extend_from_slice(&block);
+        }
+        Ok(output)
+    }
+
+    /// Decompress output produced by `compress`, decoding blocks
+    /// concurrently using the offsets recorded in the header.
+    pub fn decompress(&self, data: &[u8]) -> CompressionResult<Vec<u8>> {
+        let mut pos = 0;
+        let (num_blocks, len) = read_varint(&data[pos..])?;
+        pos += len;
+
+        let mut block_lens = Vec::with_capacity(num_blocks as usize);
+        for _ in 0..num_blocks {
+            let (block_len, len) = read_varint(&data[pos..])?;
+            pos += len;
+            block_lens.push(block_len as usize);
+        }
+
+        let mut block_ranges = Vec::with_capacity(block_lens.len());
+        let mut offset = pos;
+        for block_len in block_lens {
+            block_ranges.push(offset..offset + block_len);
+            offset += block_len;
+        }
+
+        let compressor = ZstdCompressor::new(self.compression_level)?;
+        let pool = build_thread_pool(self.num_threads)?;
+
+        let decompressed_blocks: Vec<Vec<u8>> = pool.install(|| {
+            block_ranges
+                .par_iter()
+                .map(|range| compressor.decompress(&data[range.clone()]))
+                .collect::<CompressionResult<Vec<_>>>()
+        })?;
+
+        let mut output = Vec::with_capacity(decompressed_blocks.iter().map(Vec::len).sum());
+        for block in decompressed_blocks {
+            output.extend_from_slice(&block);
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parallel_compress_round_trip() {
+        let level = ZstdLevel::new(3).unwrap();
+        let compressor = ParallelZstdCompressor::new(level).with_block_size(64 * 1024);
+
+        let data = "function test() { return 'hello world'; }\n".repeat(5_000).into_bytes();
+        assert!(data.len() > 64 * 1024 * 2, "test data should span multiple blocks");
+
+        let compressed = compressor.compress(&data).unwrap();
+        let decompressed = compressor.decompress(&compressed).unwrap();
+
+        assert_eq!(decompressed, data);
+    }
+
+    #[test]
+    fn test_parallel_compress_empty_input() {
+        let level = ZstdLevel::new(3).unwrap();
+        let compressor = ParallelZstdCompressor::new(level);
+
+        let compressed = compressor.compress(&[]).unwrap();
+        let decompressed = compressor.decompress(&compressed).unwrap();
+
+        assert!(decompressed.is_empty());
+    }
+
+    #[test]
+    fn test_parallel_compress_single_block() {
+        let level = ZstdLevel::new(3).unwrap();
+        let compressor = ParallelZstdCompressor::new(level);
+
+        let data = b"short payload that fits in a single block".to_vec();
+        let compressed = compressor.compress(&data).unwrap();
+        let decompressed = compressor.decompress(&compressed).unwrap();
+
+        assert_eq!(decompressed, data);
+    }
+
+    #[test]
+    fn test_parallel_compress_with_custom_thread_count() {
+        let level = ZstdLevel::new(3).unwrap();
+        let compressor = ParallelZstdCompressor::new(level)
+            .with_block_size(32 * 1024)
+            .with_num_threads(2);
+
+        let data = "abcdefgh".repeat(20_000).into_bytes();
+        let compressed = compressor.compress(&data).unwrap();
+        let decompressed = compressor.decompress(&compressed).unwrap();
+
+        assert_eq!(decompressed, data);
+    }
+}
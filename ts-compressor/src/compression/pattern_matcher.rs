@@ -0,0 +1,170 @@
+//! Aho-Corasick multi-pattern matching
+//!
+//! Used to re-scan content for a fixed candidate pattern set and count
+//! genuine, non-overlapping occurrences, as opposed to the overlap-inflated
+//! counts produced by sliding-window frequency analysis.
+
+use std::collections::HashMap;
+
+/// One node in the Aho-Corasick trie: `goto` are the trie/automaton
+/// transitions, `fail` is the suffix link used when a byte has no direct
+/// transition, and `pattern` is the index into the original pattern list
+/// when this node marks the end of a pattern.
+#[derive(Debug, Default)]
+struct AcNode {
+    goto: HashMap<u8, usize>,
+    fail: usize,
+    pattern: Option<usize>,
+}
+
+/// Aho-Corasick automaton over a fixed set of candidate patterns, built once
+/// and then used to scan arbitrary content for non-overlapping matches.
+#[derive(Debug)]
+pub struct AhoCorasick {
+    nodes: Vec<AcNode>,
+    patterns: Vec<String>,
+}
+
+impl AhoCorasick {
+    /// Build the trie and fail links for `patterns`. Patterns are matched
+    /// case-sensitively and by their raw bytes.
+    pub fn new(patterns: Vec<String>) -> Self {
+        let mut nodes = vec![AcNode::default()];
+
+        for (idx, pattern) in patterns.iter().enumerate() {
+            let mut state = 0;
+            for &byte in pattern.as_bytes() {
+                state = *nodes[state].goto.entry(byte).or_insert_with(|| {
+                    nodes.push(AcNode::default());
+                    nodes.len() - 1
+                });
+            }
+            nodes[state].pattern = Some(idx);
+        }
+
+        // Breadth-first fail-link construction: root's children fail to
+        // root, and every other node's fail link is its parent's fail
+        // target's transition on the same byte (falling back to root).
+        let mut queue = std::collections::VecDeque::new();
+        let root_children: Vec<(u8, usize)> = nodes[0]
+            .goto
+            .iter()
+            .map(|(&byte, &next)| (byte, next))
+            .collect();
+        for (_, next) in root_children {
+            nodes[next].fail = 0;
+            queue.push_back(next);
+        }
+
+        while let Some(state) = queue.pop_front() {
+            let transitions: Vec<(u8, usize)> = nodes[state]
+                .goto
+                .iter()
+                .map(|(&byte, &next)| (byte, next))
+                .collect();
+            for (byte, next) in transitions {
+                let mut fail = nodes[state].fail;
+                while fail != 0 && !nodes[fail].goto.contains_key(&byte) {
+                    fail = nodes[fail].fail;
+                }
+                nodes[next].fail = nodes[fail].goto.get(&byte).copied().unwrap_or(0);
+                if nodes[next].fail == next {
+                    nodes[next].fail = 0;
+                }
+                queue.push_back(next);
+            }
+        }
+
+        Self { nodes, patterns }
+    }
+
+    fn step(&self, mut state: usize, byte: u8) -> usize {
+        loop {
+            if let Some(&next) = self.nodes[state].goto.get(&byte) {
+                return next;
+            }
+            if state == 0 {
+                return 0;
+            }
+            state = self.nodes[state].fail;
+        }
+    }
+
+    /// The longest pattern ending at `state`, found by walking the fail
+    /// chain until a terminal node is reached. Fail links always point to a
+    /// strictly shorter suffix, so the first terminal node found is the
+    /// longest match ending at the current scan position.
+    fn longest_match_at(&self, mut state: usize) -> Option<usize> {
+        loop {
+            if let Some(pattern_idx) = self.nodes[state].pattern {
+                return Some(pattern_idx);
+            }
+            if state == 0 {
+                return None;
+            }
+            state = self.nodes[state].fail;
+        }
+    }
+
+    /// Scan `content`, greedily matching non-overlapping occurrences: at
+    /// each position, prefer the longest candidate pattern ending there and
+    /// resume scanning right after the matched span (rather than by a
+    /// single byte), so a match can never double-count a byte range already
+    /// claimed by an earlier match.
+    pub fn count_nonoverlapping(&self, content: &str) -> Vec<(String, usize)> {
+        let bytes = content.as_bytes();
+        let mut counts = vec![0usize; self.patterns.len()];
+
+        let mut pos = 0usize;
+        let mut state = 0usize;
+        while pos < bytes.len() {
+            state = self.step(state, bytes[pos]);
+            pos += 1;
+
+            if let Some(pattern_idx) = self.longest_match_at(state) {
+                counts[pattern_idx] += 1;
+                state = 0;
+            }
+        }
+
+        self.patterns
+            .iter()
+            .cloned()
+            .zip(counts)
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_pattern_match_count() {
+        let ac = AhoCorasick::new(vec!["aa".to_string()]);
+        let counts = ac.count_nonoverlapping("aaaa");
+        // Non-overlapping: "aaaa" contains exactly two disjoint "aa" spans
+        assert_eq!(counts, vec![("aa".to_string(), 2)]);
+    }
+
+    #[test]
+    fn test_prefers_longest_match_at_each_position() {
+        // "ion" and "on" both end at the same position in "function" (via
+        // "ion"'s suffix link to "on"); the longer "ion" should be counted
+        // and the shorter "on" should not also be counted for that span.
+        let ac = AhoCorasick::new(vec!["on".to_string(), "ion".to_string()]);
+        let counts = ac.count_nonoverlapping("function");
+        assert_eq!(counts, vec![("ion".to_string(), 1)]);
+    }
+
+    #[test]
+    fn test_multiple_disjoint_patterns() {
+        let ac = AhoCorasick::new(vec!["cat".to_string(), "dog".to_string()]);
+        let mut counts = ac.count_nonoverlapping("cat dog cat");
+        counts.sort();
+        let mut expected = vec![("cat".to_string(), 2), ("dog".to_string(), 1)];
+        expected.sort();
+        assert_eq!(counts, expected);
+    }
+}
@@ -0,0 +1,342 @@
+//! Suffix-array-driven pattern mining
+//!
+//! `FrequencyAnalyzer` reports every pattern that clears a caller-chosen
+//! length/frequency threshold, and `DictionaryBuilder` happily assigns a
+//! token to anything it's handed -- neither one asks whether a pattern is
+//! actually worth a token. `PatternMiner` answers that question directly:
+//! it builds a suffix array over the input (prefix-doubling, O(n log^2 n)),
+//! derives the LCP array via Kasai's algorithm (O(n)), and scans it with a
+//! monotonic stack to enumerate every maximal repeated substring together
+//! with its occurrence positions. Each candidate is scored by its estimated
+//! byte savings -- `(len - token_len) * count - (len + token_len)`, the
+//! trailing term accounting for the one-time dictionary entry -- and
+//! candidates are bound to tokens in descending score order, skipping any
+//! occurrence that overlaps one already claimed by a higher-scoring
+//! candidate, until the token generator or the supply of positive-savings
+//! candidates runs out.
+
+use crate::compression::{AhoCorasickMatcher, CompressionError, HexTokenGenerator, TokenGeneration};
+
+/// The length of a generated token (e.g. `"T0000"`), used when scoring how
+/// much a candidate substring would actually save once replaced.
+const TOKEN_LEN: i64 = 5;
+
+/// The dictionary `PatternMiner::mine` selects, plus the text with every
+/// chosen pattern already replaced by its token.
+#[derive(Debug, Clone)]
+pub struct MinedDictionary {
+    /// `(pattern, token)` pairs, sorted by token for deterministic output.
+    pub entries: Vec<(String, String)>,
+    /// `content` with every selected pattern substituted by its token.
+    pub rewritten: String,
+}
+
+/// Mines the highest-payoff repeated substrings out of a source text and
+/// assigns each one the next token from its generator.
+#[derive(Debug, Clone)]
+pub struct PatternMiner {
+    min_pattern_length: usize,
+    token_generator: HexTokenGenerator,
+}
+
+impl PatternMiner {
+    /// Create a miner that only considers candidates at least
+    /// `min_pattern_length` bytes long.
+    pub fn new(min_pattern_length: usize) -> Self {
+        Self {
+            min_pattern_length,
+            token_generator: HexTokenGenerator::new(),
+        }
+    }
+
+    /// Create a miner backed by a caller-supplied token generator, e.g. one
+    /// with a custom capacity.
+    pub fn with_token_generator(min_pattern_length: usize, token_generator: HexTokenGenerator) -> Self {
+        Self {
+            min_pattern_length,
+            token_generator,
+        }
+    }
+
+    /// Mine `content` for repeated substrings worth tokenizing, greedily
+    /// selecting the highest-scoring non-overlapping candidates and
+    /// returning the chosen dictionary alongside the rewritten text.
+    pub fn mine(&mut self, content: &str) -> Result<MinedDictionary, CompressionError> {
+        if content.is_empty() {
+            return Ok(MinedDictionary {
+                entries: Vec::new(),
+                rewritten: String::new(),
+            });
+        }
+
+        let bytes = content.as_bytes();
+        let suffix_array = build_suffix_array(bytes);
+        let lcp = build_lcp_array(bytes, &suffix_array);
+        let candidates = enumerate_repeat_candidates(&suffix_array, &lcp);
+
+        let mut scored: Vec<(i64, usize, Vec<usize>)> = candidates
+            .into_iter()
+            .filter(|(len, _)| *len >= self.min_pattern_length)
+            .filter_map(|(len, positions)| {
+                let score = savings_score(len, positions.len());
+                (score > 0).then_some((score, len, positions))
+            })
+            .collect();
+
+        // Highest score first; break ties on length so longer matches (more
+        // context preserved per substitution) are preferred.
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| b.1.cmp(&a.1)));
+
+        let mut covered = vec![false; bytes.len()];
+        let mut entries = Vec::new();
+
+        for (_, len, positions) in scored {
+            if !self.token_generator.has_next() {
+                break;
+            }
+
+            let surviving: Vec<usize> = positions
+                .into_iter()
+                .filter(|&pos| !covered[pos..pos + len].iter().any(|&c| c))
+                .collect();
+
+            // Re-score with the occurrences that actually survived overlap
+            // with an already-claimed, higher-priority candidate; a
+            // candidate that looked profitable before eviction may no
+            // longer be.
+            if savings_score(len, surviving.len()) <= 0 {
+                continue;
+            }
+
+            let pattern = match std::str::from_utf8(&bytes[surviving[0]..surviving[0] + len]) {
+                Ok(pattern) => pattern.to_string(),
+                Err(_) => continue,
+            };
+
+            let token = self.token_generator.next_token()?;
+
+            for pos in surviving {
+                covered[pos..pos + len].fill(true);
+            }
+
+            entries.push((pattern, token));
+        }
+
+        entries.sort_by(|a, b| a.1.cmp(&b.1));
+
+        let rewritten = if entries.is_empty() {
+            content.to_string()
+        } else {
+            AhoCorasickMatcher::from_entries(entries.clone()).apply(content).0
+        };
+
+        Ok(MinedDictionary { entries, rewritten })
+    }
+}
+
+impl Default for PatternMiner {
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+/// Estimated byte savings for replacing `count` occurrences of a `len`-byte
+/// pattern with a `TOKEN_LEN`-byte token: per-occurrence savings minus the
+/// one-time cost of the dictionary entry itself.
+fn savings_score(len: usize, count: usize) -> i64 {
+    let len = len as i64;
+    let count = count as i64;
+    (len - TOKEN_LEN) * count - (len + TOKEN_LEN)
+}
+
+/// Build a suffix array over `bytes` via prefix doubling: each round sorts
+/// suffixes by `(rank at offset 0, rank at offset k)` for a doubling `k`,
+/// which refines ties until every suffix has a unique rank.
+fn build_suffix_array(bytes: &[u8]) -> Vec<usize> {
+    let n = bytes.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let mut suffix_array: Vec<usize> = (0..n).collect();
+    let mut rank: Vec<i64> = bytes.iter().map(|&b| b as i64).collect();
+    let mut next_rank = vec![0i64; n];
+
+    let mut k = 1usize;
+    while k < n {
+        let rank_key = |i: usize| -> (i64, i64) {
+            let second = if i + k < n { rank[i + k] } else { -1 };
+            (rank[i], second)
+        };
+
+        suffix_array.sort_by_key(|&i| rank_key(i));
+
+        next_rank[suffix_array[0]] = 0;
+        for idx in 1..n {
+            let prev = rank_key(suffix_array[idx - 1]);
+            let cur = rank_key(suffix_array[idx]);
+            next_rank[suffix_array[idx]] = next_rank[suffix_array[idx - 1]] + if cur > prev { 1 } else { 0 };
+        }
+        rank.copy_from_slice(&next_rank);
+
+        if rank[suffix_array[n - 1]] as usize == n - 1 {
+            break;
+        }
+        k *= 2;
+    }
+
+    suffix_array
+}
+
+/// Derive the LCP array from `suffix_array` via Kasai's algorithm: `lcp[i]`
+/// is the length of the common prefix shared by the suffixes at ranks `i`
+/// and `i - 1` (`lcp[0]` is an unused sentinel).
+fn build_lcp_array(bytes: &[u8], suffix_array: &[usize]) -> Vec<usize> {
+    let n = bytes.len();
+    let mut rank = vec![0usize; n];
+    for (i, &suffix) in suffix_array.iter().enumerate() {
+        rank[suffix] = i;
+    }
+
+    let mut lcp = vec![0usize; n];
+    let mut h = 0usize;
+    for i in 0..n {
+        if rank[i] > 0 {
+            let j = suffix_array[rank[i] - 1];
+            while i + h < n && j + h < n && bytes[i + h] == bytes[j + h] {
+                h += 1;
+            }
+            lcp[rank[i]] = h;
+            h = h.saturating_sub(1);
+        } else {
+            h = 0;
+        }
+    }
+
+    lcp
+}
+
+/// Scan the LCP array with a monotonic stack to enumerate every maximal
+/// repeated substring: for a run of `lcp` values all `>= height` spanning
+/// suffix-array ranks `[start, end]`, the substring of that `height` occurs
+/// once at each of those `end - start + 2` ranks. Each popped stack entry
+/// yields one `(length, occurrence start positions)` candidate. This is the
+/// same "largest rectangle in a histogram" technique applied to every
+/// rectangle rather than just the tallest one.
+fn enumerate_repeat_candidates(suffix_array: &[usize], lcp: &[usize]) -> Vec<(usize, Vec<usize>)> {
+    let n = suffix_array.len();
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+    let mut candidates: Vec<(usize, Vec<usize>)> = Vec::new();
+
+    for i in 1..=n {
+        let height = if i < n { lcp[i] } else { 0 };
+        let mut start = i;
+
+        while let Some(&(top_height, top_start)) = stack.last() {
+            if top_height > height {
+                stack.pop();
+                let positions = suffix_array[top_start - 1..i].to_vec();
+                candidates.push((top_height, positions));
+                start = top_start;
+            } else if top_height == height {
+                start = top_start;
+                break;
+            } else {
+                break;
+            }
+        }
+
+        if height > 0 {
+            stack.push((height, start));
+        }
+    }
+
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suffix_array_known_text() {
+        // "banana" -> suffixes sorted lexicographically:
+        // a(5) ana(3) anana(1) banana(0) na(4) nana(2)
+        let sa = build_suffix_array(b"banana");
+        assert_eq!(sa, vec![5, 3, 1, 0, 4, 2]);
+    }
+
+    #[test]
+    fn test_lcp_array_known_text() {
+        let sa = build_suffix_array(b"banana");
+        let lcp = build_lcp_array(b"banana", &sa);
+        // lcp[0] is a sentinel; lcp[1..] matches the standard "banana" LCP array.
+        assert_eq!(&lcp[1..], &[1, 3, 0, 0, 2]);
+    }
+
+    #[test]
+    fn test_enumerate_repeat_candidates_finds_repeated_substring() {
+        let content = "abcabcabc";
+        let sa = build_suffix_array(content.as_bytes());
+        let lcp = build_lcp_array(content.as_bytes(), &sa);
+        let candidates = enumerate_repeat_candidates(&sa, &lcp);
+
+        // "abc" occurs 3 times non-overlapping; some candidate must report
+        // exactly that.
+        assert!(candidates
+            .iter()
+            .any(|(len, positions)| *len == 3 && positions.len() == 3));
+    }
+
+    #[test]
+    fn test_mine_empty_content() {
+        let mut miner = PatternMiner::new(3);
+        let result = miner.mine("").unwrap();
+        assert!(result.entries.is_empty());
+        assert_eq!(result.rewritten, "");
+    }
+
+    #[test]
+    fn test_mine_skips_unprofitable_short_patterns() {
+        // Every candidate here is far too short/infrequent to pay for its
+        // own dictionary entry.
+        let mut miner = PatternMiner::new(3);
+        let result = miner.mine("ab ab cd cd").unwrap();
+        assert!(result.entries.is_empty());
+        assert_eq!(result.rewritten, "ab ab cd cd");
+    }
+
+    #[test]
+    fn test_mine_picks_genuinely_valuable_repeats() {
+        let mut miner = PatternMiner::new(3);
+        let content = "function compress() {} function decompress() {} function analyze() {}";
+        let result = miner.mine(content).unwrap();
+
+        assert!(!result.entries.is_empty());
+        // "function" is long and frequent enough to be worth a token.
+        assert!(result.entries.iter().any(|(pattern, _)| pattern == "function"));
+        assert!(!result.rewritten.contains("function"));
+    }
+
+    #[test]
+    fn test_mine_round_trips_through_dictionary() {
+        let mut miner = PatternMiner::new(3);
+        let content = "repeated repeated repeated text here, repeated repeated again";
+        let result = miner.mine(content).unwrap();
+
+        for (pattern, token) in &result.entries {
+            assert!(!result.rewritten.contains(pattern.as_str()));
+            assert!(result.rewritten.contains(token.as_str()));
+        }
+    }
+
+    #[test]
+    fn test_mine_respects_token_generator_capacity() {
+        let miner_generator = HexTokenGenerator::with_max_tokens(1).unwrap();
+        let mut miner = PatternMiner::with_token_generator(3, miner_generator);
+        let content = "aaaaa bbbbb aaaaa bbbbb aaaaa bbbbb ccccc ccccc ccccc";
+
+        let result = miner.mine(content).unwrap();
+        assert_eq!(result.entries.len(), 1);
+    }
+}
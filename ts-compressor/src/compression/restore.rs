@@ -0,0 +1,377 @@
+//! Reverse pipeline: read the archive `generate_output_file` wrote and
+//! reconstruct the original tree.
+//!
+//! `UniversalCompressor::compress` only ever moves forward - pattern
+//! replacement, then a final-stage codec - and the archive it produces is a
+//! dump of that forward state. `restore_archive` parses that same text back
+//! into `FileEntry`/`Dictionary` values and runs `FileEntry::decompress` (the
+//! inverse of the forward pipeline) over each one, then checks the result
+//! against the `Checksum:` line captured at compress time so a caller can
+//! confirm the round trip before deleting the source tree.
+
+use crate::compression::types::{CompressionMethod, ContentHeader, Dictionary, FileEntry, FileSize};
+use crate::compression::CompressionError;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// One restored file's checksum outcome.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verification {
+    /// The archive didn't record a `Checksum:` for this entry.
+    NotChecked,
+    /// Restored content's SHA-256 matched the checksum captured at compress time.
+    Matched,
+    /// Restored content's SHA-256 did not match; the archive or this restore
+    /// step corrupted the file.
+    Mismatched,
+}
+
+/// Summary of one `restore_archive` run.
+#[derive(Debug, Clone)]
+pub struct RestoreReport {
+    pub files_restored: usize,
+    pub verifications: HashMap<PathBuf, Verification>,
+}
+
+impl RestoreReport {
+    /// Entries whose restored content didn't match the checksum captured at
+    /// compress time.
+    pub fn mismatches(&self) -> impl Iterator<Item = &PathBuf> {
+        self.verifications
+            .iter()
+            .filter(|(_, verification)| matches!(verification, Verification::Mismatched))
+            .map(|(path, _)| path)
+    }
+}
+
+/// One `### File:` block from the archive's `## Compressed Content` section.
+struct ParsedEntry {
+    relative_path: PathBuf,
+    header: ContentHeader,
+    method: Option<CompressionMethod>,
+    checksum: Option<String>,
+    content: String,
+}
+
+/// Read the archive at `archive_path`, restore every entry's original
+/// content under `output_dir`, and verify each restored file's SHA-256
+/// against the `Checksum:` recorded for it at compress time.
+pub fn restore_archive(archive_path: &Path, output_dir: &Path) -> Result<RestoreReport, CompressionError> {
+    let text = std::fs::read_to_string(archive_path).map_err(|e| {
+        CompressionError::file_processing(
+            archive_path.to_path_buf(),
+            format!("Failed to read archive: {}", e),
+        )
+    })?;
+
+    let dictionary = Dictionary::from_entries(parse_dictionary(&text));
+    let parsed_entries = parse_entries(&text)?;
+
+    std::fs::create_dir_all(output_dir)?;
+    let canonical_root = std::fs::canonicalize(output_dir)?;
+
+    let mut verifications = HashMap::with_capacity(parsed_entries.len());
+    let mut files_restored = 0;
+
+    for parsed in parsed_entries {
+        let entry = FileEntry {
+            relative_path: parsed.relative_path.clone(),
+            original_content: String::new(),
+            compressed_content: Some(parsed.content),
+            is_binary: false,
+            original_size: FileSize::new(0),
+            compressed_size: None,
+            header: parsed.header,
+            method: parsed.method,
+        };
+
+        // This text archive format doesn't embed a trained `SymbolTable`
+        // alongside the `## Embedded Dictionary` section, so an entry
+        // compressed under `DictionaryStrategy::Fsst` can't be restored from
+        // it yet; likewise it has nowhere to embed a `ZstdDictionary`, so an
+        // entry whose final codec ran against `zstd_dictionary_config` also
+        // can't be restored from it. `decompress` surfaces either case as a
+        // clear error rather than silently returning garbage.
+        let restored = entry.decompress(&dictionary, None, None)?;
+        let dest = restore_path(&canonical_root, &parsed.relative_path)?;
+        std::fs::write(&dest, &restored)?;
+        files_restored += 1;
+
+        let verification = match &parsed.checksum {
+            None => Verification::NotChecked,
+            Some(expected) => {
+                let actual = format!("{:x}", Sha256::digest(restored.as_bytes()));
+                if &actual == expected {
+                    Verification::Matched
+                } else {
+                    Verification::Mismatched
+                }
+            }
+        };
+        verifications.insert(parsed.relative_path, verification);
+    }
+
+    Ok(RestoreReport {
+        files_restored,
+        verifications,
+    })
+}
+
+/// Re-root `relative_path` under `canonical_root`, the same guard
+/// `main::validate_restore_path` applies to the `CodeArchiver`-format
+/// restore: root/prefix components are dropped, and a surviving `..`/`.` is
+/// treated as a path-traversal attempt rather than followed. Once joined,
+/// the destination's parent is created and canonicalized to confirm it
+/// still resolves inside `canonical_root`, catching a symlink planted
+/// along the way (by this or an earlier entry in the same restore) that a
+/// textually-clean relative path could otherwise walk through.
+///
+/// Shared with `crate::compression::block_archive::extract_archive`, which
+/// restores the same kind of `relative_path`s from a different archive
+/// format and needs the identical traversal guard.
+pub(crate) fn restore_path(canonical_root: &Path, relative_path: &Path) -> Result<PathBuf, CompressionError> {
+    let mut rel = PathBuf::new();
+    for component in relative_path.components() {
+        match component {
+            std::path::Component::RootDir | std::path::Component::Prefix(_) => {}
+            std::path::Component::ParentDir | std::path::Component::CurDir => {
+                return Err(CompressionError::file_processing(
+                    relative_path.to_path_buf(),
+                    "Archive entry path escapes the output root",
+                ));
+            }
+            std::path::Component::Normal(part) => rel.push(part),
+        }
+    }
+
+    let dest_path = canonical_root.join(rel);
+
+    if let Some(parent) = dest_path.parent() {
+        std::fs::create_dir_all(parent)?;
+        let canonical_parent = std::fs::canonicalize(parent)?;
+        if !canonical_parent.starts_with(canonical_root) {
+            return Err(CompressionError::file_processing(
+                relative_path.to_path_buf(),
+                "Archive entry path resolves outside the output root",
+            ));
+        }
+    }
+
+    Ok(dest_path)
+}
+
+/// Parse the `## Embedded Dictionary` section's `DICT:pattern=token` lines.
+/// The token is always a fixed `<letter><4 hex digits>` shape (see
+/// `HexToken`) and never contains `=`, so splitting from the right is safe
+/// even when `pattern` itself contains a literal `=`.
+fn parse_dictionary(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .filter_map(|line| line.strip_prefix("DICT:"))
+        .filter_map(|rest| rest.rsplit_once('='))
+        .map(|(pattern, token)| (pattern.to_string(), token.to_string()))
+        .collect()
+}
+
+/// Parse the `## Compressed Content` section's `### File:` blocks.
+fn parse_entries(text: &str) -> Result<Vec<ParsedEntry>, CompressionError> {
+    enum State {
+        Idle,
+        InEntry {
+            relative_path: PathBuf,
+            header: Option<ContentHeader>,
+            method: Option<CompressionMethod>,
+            checksum: Option<String>,
+        },
+        InContent {
+            relative_path: PathBuf,
+            header: ContentHeader,
+            method: Option<CompressionMethod>,
+            checksum: Option<String>,
+            lines: Vec<String>,
+        },
+    }
+
+    let mut entries = Vec::new();
+    let mut state = State::Idle;
+
+    for line in text.lines() {
+        state = match state {
+            State::Idle => match line.strip_prefix("### File: ") {
+                Some(path) => State::InEntry {
+                    relative_path: PathBuf::from(path),
+                    header: None,
+                    method: None,
+                    checksum: None,
+                },
+                None => State::Idle,
+            },
+            State::InEntry {
+                relative_path,
+                header,
+                method,
+                checksum,
+            } => {
+                if let Some(rest) = line.strip_prefix("Header: ") {
+                    let header = Some(parse_header(rest)?);
+                    State::InEntry {
+                        relative_path,
+                        header,
+                        method,
+                        checksum,
+                    }
+                } else if let Some(rest) = line.strip_prefix("Method: ") {
+                    let method = Some(rest.parse::<CompressionMethod>()?);
+                    State::InEntry {
+                        relative_path,
+                        header,
+                        method,
+                        checksum,
+                    }
+                } else if let Some(rest) = line.strip_prefix("Checksum: ") {
+                    State::InEntry {
+                        relative_path,
+                        header,
+                        method,
+                        checksum: Some(rest.to_string()),
+                    }
+                } else if line == "Content:" {
+                    let header = header.ok_or_else(|| {
+                        CompressionError::file_processing(
+                            relative_path.clone(),
+                            "Archive entry is missing its 'Header:' line",
+                        )
+                    })?;
+                    State::InContent {
+                        relative_path,
+                        header,
+                        method,
+                        checksum,
+                        lines: Vec::new(),
+                    }
+                } else {
+                    // "Original size:"/"Compressed size:"/"Compression ratio:"
+                    // lines aren't needed to restore content.
+                    State::InEntry {
+                        relative_path,
+                        header,
+                        method,
+                        checksum,
+                    }
+                }
+            }
+            State::InContent {
+                relative_path,
+                header,
+                method,
+                checksum,
+                mut lines,
+            } => {
+                if line == "---" {
+                    entries.push(ParsedEntry {
+                        relative_path,
+                        header,
+                        method,
+                        checksum,
+                        content: lines.join("\n"),
+                    });
+                    State::Idle
+                } else {
+                    lines.push(line.to_string());
+                    State::InContent {
+                        relative_path,
+                        header,
+                        method,
+                        checksum,
+                        lines,
+                    }
+                }
+            }
+        };
+    }
+
+    Ok(entries)
+}
+
+fn parse_header(text: &str) -> Result<ContentHeader, CompressionError> {
+    if text == "Plain" {
+        return Ok(ContentHeader::Plain);
+    }
+
+    text.strip_prefix("Compressed dictionary_id=")
+        .map(|dictionary_id| ContentHeader::Compressed {
+            dictionary_id: dictionary_id.to_string(),
+        })
+        .ok_or_else(|| CompressionError::file_processing(text.to_string(), "Unrecognized 'Header:' value"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn sample_archive(checksum: &str) -> String {
+        let dictionary_id = Dictionary::from_entries(vec![("hello world".to_string(), "T0000".to_string())])
+            .id()
+            .to_string();
+        // `Method: store` means the final-stage codec was a no-op, but
+        // `compress()` still base64-encodes every entry's final-stage
+        // output, so the dictionary-token text must be encoded here too.
+        let content = crate::compression::base64::encode(b"T0000");
+        format!(
+            "## Embedded Dictionary\n\
+             # dictionary_id={dictionary_id}\n\
+             DICT:hello world=T0000\n\
+             \n\
+             ## Compressed Content\n\
+             ### File: greeting.txt\n\
+             Header: Compressed dictionary_id={dictionary_id}\n\
+             Method: store\n\
+             Original size: 11 bytes\n\
+             Checksum: {checksum}\n\
+             Content:\n\
+             {content}\n\
+             ---\n"
+        )
+    }
+
+    #[test]
+    fn test_restore_archive_round_trips_dictionary_entry() {
+        let expected_checksum = format!("{:x}", Sha256::digest(b"hello world"));
+        let dir = TempDir::new().unwrap();
+        let archive_path = dir.path().join("archive.txt");
+        std::fs::write(&archive_path, sample_archive(&expected_checksum)).unwrap();
+
+        let output_dir = dir.path().join("restored");
+        let report = restore_archive(&archive_path, &output_dir).unwrap();
+
+        assert_eq!(report.files_restored, 1);
+        let restored = std::fs::read_to_string(output_dir.join("greeting.txt")).unwrap();
+        assert_eq!(restored, "hello world");
+        assert_eq!(
+            report.verifications[&PathBuf::from("greeting.txt")],
+            Verification::Matched
+        );
+        assert_eq!(report.mismatches().count(), 0);
+    }
+
+    #[test]
+    fn test_restore_archive_flags_checksum_mismatch() {
+        let dir = TempDir::new().unwrap();
+        let archive_path = dir.path().join("archive.txt");
+        let wrong_checksum = "0".repeat(64);
+        std::fs::write(&archive_path, sample_archive(&wrong_checksum)).unwrap();
+
+        let output_dir = dir.path().join("restored");
+        let report = restore_archive(&archive_path, &output_dir).unwrap();
+
+        assert_eq!(report.mismatches().count(), 1);
+    }
+
+    #[test]
+    fn test_restore_path_rejects_parent_dir_escape() {
+        let dir = TempDir::new().unwrap();
+        let canonical_root = std::fs::canonicalize(dir.path()).unwrap();
+        assert!(restore_path(&canonical_root, Path::new("../escape.txt")).is_err());
+    }
+}
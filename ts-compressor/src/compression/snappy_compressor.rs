@@ -0,0 +1,57 @@
+//! Snappy compression integration
+//!
+//! Thin wrapper around the `snap` crate's raw (frame-free) encoder/decoder.
+
+use crate::compression::codec::Compressor;
+use crate::compression::error::{CompressionError, CompressionResult};
+
+/// Snappy compressor using the raw block format.
+#[derive(Debug, Default)]
+pub struct SnappyCompressor;
+
+impl SnappyCompressor {
+    /// Create a new snappy compressor.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Compressor for SnappyCompressor {
+    fn compress(&self, data: &[u8]) -> CompressionResult<Vec<u8>> {
+        snap::raw::Encoder::new()
+            .compress_vec(data)
+            .map_err(|e| CompressionError::snappy_compression(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))
+    }
+
+    fn decompress(&self, data: &[u8]) -> CompressionResult<Vec<u8>> {
+        snap::raw::Decoder::new()
+            .decompress_vec(data)
+            .map_err(|e| CompressionError::snappy_compression(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))
+    }
+
+    fn codec_id(&self) -> u8 {
+        3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snappy_round_trip() {
+        let compressor = SnappyCompressor::new();
+        let data = b"function test() { return 'hello world'; }".repeat(10);
+
+        let compressed = compressor.compress(&data).unwrap();
+        let decompressed = compressor.decompress(&compressed).unwrap();
+
+        assert_eq!(decompressed, data);
+    }
+
+    #[test]
+    fn test_snappy_rejects_garbage() {
+        let compressor = SnappyCompressor::new();
+        assert!(compressor.decompress(b"not snappy data").is_err());
+    }
+}
@@ -0,0 +1,56 @@
+//! No-op "stored" codec
+//!
+//! Passes data through unchanged in both directions, so the `Codec`
+//! abstraction can represent "don't compress this" the same way it
+//! represents every other backend, instead of callers special-casing a
+//! disabled-compression branch.
+
+use crate::compression::codec::Compressor;
+use crate::compression::error::CompressionResult;
+
+/// Identity codec: `compress`/`decompress` both return the input unchanged.
+#[derive(Debug, Default)]
+pub struct StoredCompressor;
+
+impl StoredCompressor {
+    /// Create a new stored (no-op) compressor.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Compressor for StoredCompressor {
+    fn compress(&self, data: &[u8]) -> CompressionResult<Vec<u8>> {
+        Ok(data.to_vec())
+    }
+
+    fn decompress(&self, data: &[u8]) -> CompressionResult<Vec<u8>> {
+        Ok(data.to_vec())
+    }
+
+    fn codec_id(&self) -> u8 {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stored_round_trip_is_identity() {
+        let compressor = StoredCompressor::new();
+        let data = b"function test() { return 'hello world'; }".repeat(10);
+
+        let compressed = compressor.compress(&data).unwrap();
+        assert_eq!(compressed, data);
+
+        let decompressed = compressor.decompress(&compressed).unwrap();
+        assert_eq!(decompressed, data);
+    }
+
+    #[test]
+    fn test_stored_codec_id_is_zero() {
+        assert_eq!(StoredCompressor::new().codec_id(), 0);
+    }
+}
@@ -0,0 +1,105 @@
+//! Streaming frequency analysis with chunk-boundary carry
+//!
+//! `ConcurrentFrequencyAnalyzer::analyze_chunk` treats every call as an
+//! isolated string, so a pattern split across two successive reads (e.g.
+//! reading a large file in fixed-size blocks) is never counted. This module
+//! wraps the concurrent analyzer with a small carry buffer so callers can
+//! pipe arbitrarily large streams through a fixed memory window without
+//! losing patterns that straddle a chunk boundary.
+
+use crate::compression::ConcurrentFrequencyAnalyzer;
+
+/// Stateful wrapper around `ConcurrentFrequencyAnalyzer` for analyzing a
+/// stream of chunks (e.g. successive reads from a file or stdin).
+///
+/// Retains the trailing `max_pattern_length - 1` bytes of each chunk and
+/// prepends them to the next one before analysis, so a pattern that crosses
+/// a chunk boundary is still seen intact at least once.
+pub struct StreamingAnalyzer {
+    analyzer: ConcurrentFrequencyAnalyzer,
+    carry: String,
+    max_pattern_length: usize,
+}
+
+impl StreamingAnalyzer {
+    /// Create a streaming analyzer. `max_pattern_length` bounds how many
+    /// trailing bytes of each chunk are carried into the next one, and
+    /// should match the longest pattern length the caller cares about.
+    pub fn new(min_length: usize, min_frequency: usize, max_pattern_length: usize) -> Self {
+        Self {
+            analyzer: ConcurrentFrequencyAnalyzer::new(min_length, min_frequency),
+            carry: String::new(),
+            max_pattern_length,
+        }
+    }
+
+    /// Feed the next chunk of the stream. The previous chunk's carry is
+    /// prepended before analysis, and a new carry is taken from the tail of
+    /// `chunk` for the next call.
+    pub fn feed(&mut self, chunk: &str) {
+        if chunk.is_empty() {
+            return;
+        }
+
+        let mut combined = String::with_capacity(self.carry.len() + chunk.len());
+        combined.push_str(&self.carry);
+        combined.push_str(chunk);
+
+        self.analyzer.analyze_chunk(&combined);
+
+        let carry_len = self.max_pattern_length.saturating_sub(1).min(combined.len());
+        let mut carry_start = combined.len() - carry_len;
+        while carry_start < combined.len() && !combined.is_char_boundary(carry_start) {
+            carry_start += 1;
+        }
+        self.carry = combined[carry_start..].to_string();
+    }
+
+    /// Finish the stream and hand back the underlying analyzer so the
+    /// caller can read out `get_frequent_patterns`/`get_pattern_frequency`.
+    /// Any carry still held is discarded, since it was already analyzed as
+    /// the tail of the last chunk fed in.
+    pub fn finish(self) -> ConcurrentFrequencyAnalyzer {
+        self.analyzer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::FrequencyAnalysis;
+
+    #[test]
+    fn test_feed_finds_pattern_split_across_chunk_boundary() {
+        // "function" split right down the middle across two feeds
+        let mut streaming = StreamingAnalyzer::new(4, 1, 9);
+        streaming.feed("abc func");
+        streaming.feed("tion xyz");
+
+        let analyzer = streaming.finish();
+        assert!(analyzer.get_pattern_frequency("function") >= 1);
+    }
+
+    #[test]
+    fn test_feed_without_carry_misses_boundary_pattern() {
+        // Same split, but with no carry retained (max_pattern_length of 1),
+        // demonstrating why the carry buffer exists.
+        let mut streaming = StreamingAnalyzer::new(4, 1, 1);
+        streaming.feed("abc func");
+        streaming.feed("tion xyz");
+
+        let analyzer = streaming.finish();
+        assert_eq!(analyzer.get_pattern_frequency("function"), 0);
+    }
+
+    #[test]
+    fn test_feed_accumulates_across_many_chunks() {
+        let mut streaming = StreamingAnalyzer::new(4, 2, 9);
+        for _ in 0..3 {
+            streaming.feed("function test() { ");
+        }
+
+        let analyzer = streaming.finish();
+        assert!(analyzer.should_compress_pattern("function"));
+    }
+}
@@ -3,7 +3,18 @@
 //! Provides type-safe data models with domain-specific newtypes
 //! and comprehensive statistics tracking.
 
-use std::path::PathBuf;
+use crate::compression::base64;
+use crate::compression::codec::Codec;
+use crate::compression::config::{Lz4Level, ZstdLevel};
+use crate::compression::file_type::{FilePolicy, FileTypeRegistry};
+use crate::compression::fsst::SymbolTable;
+use crate::compression::ui::Report;
+use crate::compression::zstd_compressor::{ZstdCompressor, ZstdDictionary};
+use crate::compression::CompressionError;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 /// Newtype for compression ratio with validation and display
@@ -93,6 +104,186 @@ impl std::ops::Sub for FileSize {
     }
 }
 
+/// Self-describing header stored alongside an entry's bytes, modeled after
+/// garage's `DataBlockElem`: a decompressor reads this instead of guessing
+/// whether the stored content still needs dictionary expansion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContentHeader {
+    /// Bytes are stored exactly as they should be restored.
+    Plain,
+    /// Bytes have had dictionary tokens substituted in; `dictionary_id`
+    /// pins down which `Dictionary` can reverse them.
+    Compressed { dictionary_id: String },
+    /// Bytes have been run through a trained FSST `SymbolTable`; `table_id`
+    /// pins down which table can reverse them, the same way `dictionary_id`
+    /// does for `Compressed`.
+    FsstCompressed { table_id: String },
+}
+
+/// Reverse (token -> pattern) lookup used to expand dictionary-compressed
+/// content back to its source text.
+///
+/// Built from the same `(pattern, token)` pairs `DictionaryBuilder` produces
+/// (see `get_dictionary_entries`), storing only the direction decompression
+/// needs - the read-only counterpart to `ArchivedDictionary::pattern_for_token`
+/// for dictionaries that haven't been archived with rkyv.
+#[derive(Debug, Clone)]
+pub struct Dictionary {
+    id: String,
+    token_to_pattern: HashMap<String, String>,
+}
+
+impl Dictionary {
+    /// Build a dictionary from `(pattern, token)` pairs. The id is a SHA-256
+    /// digest over the entries, so a `ContentHeader::Compressed` can be
+    /// checked against the dictionary passed to `decompress` without any
+    /// external bookkeeping.
+    pub fn from_entries(entries: Vec<(String, String)>) -> Self {
+        let id = Self::compute_id(&entries);
+        let token_to_pattern = entries.into_iter().map(|(pattern, token)| (token, pattern)).collect();
+        Self { id, token_to_pattern }
+    }
+
+    fn compute_id(entries: &[(String, String)]) -> String {
+        let mut sorted: Vec<&(String, String)> = entries.iter().collect();
+        sorted.sort_by(|a, b| a.1.cmp(&b.1));
+
+        let mut hasher = Sha256::new();
+        for (pattern, token) in sorted {
+            hasher.update(token.as_bytes());
+            hasher.update(b"\0");
+            hasher.update(pattern.as_bytes());
+            hasher.update(b"\0");
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// The digest identifying this dictionary's mappings.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Look up the pattern a token was substituted from.
+    pub fn pattern_for_token(&self, token: &str) -> Option<&str> {
+        self.token_to_pattern.get(token).map(String::as_str)
+    }
+
+    /// Expand every known token in `content` back to its source pattern.
+    pub fn expand(&self, content: &str) -> String {
+        let mut result = content.to_string();
+        for (token, pattern) in &self.token_to_pattern {
+            if result.contains(token.as_str()) {
+                result = result.replace(token.as_str(), pattern.as_str());
+            }
+        }
+        result
+    }
+}
+
+impl Default for Dictionary {
+    fn default() -> Self {
+        Self::from_entries(Vec::new())
+    }
+}
+
+/// Selects which compression backend produced a `FileEntry`'s bytes, parsed
+/// from a `name` or `name/level` selector (e.g. `brotli/3`) the way zvault
+/// and include-flate accept algorithm selectors. Stored per-entry so mixed
+/// outputs - some files dictionary-compressed, others stored, others run
+/// through a byte-oriented codec - stay self-describing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompressionMethod {
+    /// Dictionary pattern/token substitution (see `Dictionary`).
+    Dictionary,
+    /// FSST symbol-table substitution (see `crate::compression::fsst::SymbolTable`).
+    Fsst,
+    Deflate { level: u32 },
+    Zstd { level: i32 },
+    Brotli { level: u32 },
+    /// lz4 block compression; no tunable level at the one-shot API this
+    /// module wraps.
+    Lz4,
+    /// Snappy block compression; no tunable level at the one-shot API this
+    /// module wraps.
+    Snappy,
+    /// Bytes stored as-is, no compression applied.
+    Store,
+}
+
+impl CompressionMethod {
+    const DEFAULT_DEFLATE_LEVEL: u32 = 6;
+    const DEFAULT_ZSTD_LEVEL: i32 = 3;
+    const DEFAULT_BROTLI_LEVEL: u32 = 9;
+}
+
+impl std::str::FromStr for CompressionMethod {
+    type Err = CompressionError;
+
+    /// Parse a `name` or `name/level` selector, e.g. `"zstd"` or `"brotli/3"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(2, '/');
+        let name = parts.next().unwrap_or("").to_ascii_lowercase();
+        let level = parts.next();
+
+        let parse_level = |level: Option<&str>| -> Result<Option<i64>, CompressionError> {
+            level
+                .map(|text| {
+                    text.parse::<i64>().map_err(|_| {
+                        CompressionError::config_validation(format!(
+                            "Invalid compression level: '{}'",
+                            text
+                        ))
+                    })
+                })
+                .transpose()
+        };
+
+        match name.as_str() {
+            "dictionary" => Ok(Self::Dictionary),
+            "fsst" => Ok(Self::Fsst),
+            "store" => Ok(Self::Store),
+            "lz4" => Ok(Self::Lz4),
+            "snappy" => Ok(Self::Snappy),
+            "deflate" => Ok(Self::Deflate {
+                level: parse_level(level)?.unwrap_or(Self::DEFAULT_DEFLATE_LEVEL as i64) as u32,
+            }),
+            "zstd" => Ok(Self::Zstd {
+                level: parse_level(level)?.unwrap_or(Self::DEFAULT_ZSTD_LEVEL as i64) as i32,
+            }),
+            "brotli" => Ok(Self::Brotli {
+                level: parse_level(level)?.unwrap_or(Self::DEFAULT_BROTLI_LEVEL as i64) as u32,
+            }),
+            other => Err(CompressionError::config_validation(format!(
+                "Unknown compression method: '{}'",
+                other
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for CompressionMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Dictionary => write!(f, "dictionary"),
+            Self::Fsst => write!(f, "fsst"),
+            Self::Store => write!(f, "store"),
+            Self::Lz4 => write!(f, "lz4"),
+            Self::Snappy => write!(f, "snappy"),
+            Self::Deflate { level } => write!(f, "deflate/{}", level),
+            Self::Zstd { level } => write!(f, "zstd/{}", level),
+            Self::Brotli { level } => write!(f, "brotli/{}", level),
+        }
+    }
+}
+
+/// Aggregate bytes in/out and file count contributed by one `CompressionMethod`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MethodStats {
+    pub bytes_in: usize,
+    pub bytes_out: usize,
+    pub count: usize,
+}
+
 /// Represents a file in the compressed output
 #[derive(Debug, Clone)]
 pub struct FileEntry {
@@ -102,6 +293,8 @@ pub struct FileEntry {
     pub is_binary: bool,
     pub original_size: FileSize,
     pub compressed_size: Option<FileSize>,
+    pub header: ContentHeader,
+    pub method: Option<CompressionMethod>,
 }
 
 impl FileEntry {
@@ -115,16 +308,116 @@ impl FileEntry {
             is_binary,
             original_size,
             compressed_size: None,
+            header: ContentHeader::Plain,
+            method: None,
         }
     }
-    
-    /// Apply compression to this file entry
-    pub fn apply_compression(&mut self, compressed_content: String) {
+
+    /// Read `path` and build a `FileEntry` for it, returning `Ok(None)` for
+    /// `FilePolicy::Skip` so callers can drop it without special-casing the
+    /// skip themselves.
+    ///
+    /// `registry`'s extension-driven policy is only a fast path for
+    /// `Skip`/`PreCompressed` - it never needs to read the file to rule
+    /// those out or in. Whether the rest is `Text` or `Binary` is decided
+    /// by content sniffing (`file_type::sniff_is_text`) over the leading
+    /// `sniff_sample_len` bytes, so an extension-less text file isn't
+    /// wrongly treated as binary and a `.json`/`.rs` file that's actually
+    /// binary isn't wrongly fed to pattern analysis.
+    ///
+    /// Content is read with `String::from_utf8_lossy` rather than
+    /// `read_to_string` so a file sniffed as binary with non-UTF-8 bytes
+    /// still produces an entry instead of an error; `is_binary` records
+    /// that this content is an approximation, not the literal original
+    /// bytes. `PreCompressed` entries are immediately marked `Store`d, so
+    /// later pipeline stages don't waste a pass re-compressing
+    /// already-compressed data.
+    pub fn from_path(
+        path: &Path,
+        relative_path: PathBuf,
+        registry: &FileTypeRegistry,
+        sniff_sample_len: usize,
+        max_non_printable_ratio: f64,
+    ) -> Result<Option<Self>, CompressionError> {
+        let policy = registry.policy_for(path);
+        if policy == FilePolicy::Skip {
+            return Ok(None);
+        }
+
+        let bytes = fs::read(path).map_err(|e| {
+            CompressionError::file_processing(
+                path.display().to_string(),
+                format!("Failed to read file: {}", e),
+            )
+        })?;
+        let content = String::from_utf8_lossy(&bytes).into_owned();
+        let is_binary = if policy == FilePolicy::PreCompressed {
+            true
+        } else {
+            let sample_len = bytes.len().min(sniff_sample_len);
+            !crate::compression::file_type::sniff_is_text(&bytes[..sample_len], max_non_printable_ratio)
+        };
+
+        let mut entry = Self::new(relative_path, content, is_binary);
+        if policy == FilePolicy::PreCompressed {
+            let stored = entry.original_content.clone();
+            entry.apply_compression(stored, CompressionMethod::Store, None);
+        }
+
+        Ok(Some(entry))
+    }
+
+    /// Apply compression to this file entry, recording which backend
+    /// produced `compressed_content` so mixed-method outputs stay representable.
+    /// When `report` is supplied, its running `Sizes` are updated with this
+    /// entry's contribution so a `UI` can render live progress.
+    pub fn apply_compression(
+        &mut self,
+        compressed_content: String,
+        method: CompressionMethod,
+        report: Option<&Report>,
+    ) {
         let compressed_size = FileSize::new(compressed_content.len());
+        if let Some(report) = report {
+            report.add(self.original_size.bytes(), compressed_size.bytes());
+        }
         self.compressed_content = Some(compressed_content);
         self.compressed_size = Some(compressed_size);
+        self.method = Some(method);
     }
-    
+
+    /// Apply dictionary-substitution compression, recording the dictionary's
+    /// id in this entry's header so decompression never has to guess which
+    /// dictionary produced the bytes.
+    pub fn apply_dictionary_compression(
+        &mut self,
+        compressed_content: String,
+        dictionary_id: impl Into<String>,
+        report: Option<&Report>,
+    ) {
+        self.header = ContentHeader::Compressed {
+            dictionary_id: dictionary_id.into(),
+        };
+        self.apply_compression(compressed_content, CompressionMethod::Dictionary, report);
+    }
+
+    /// Apply FSST symbol-table substitution, recording the table's id in
+    /// this entry's header so decompression never has to guess which table
+    /// produced the bytes. `compressed_content` is the table's code stream,
+    /// base64-encoded the same way a final-stage `Codec`'s output is, since
+    /// FSST codes are arbitrary bytes rather than valid UTF-8 text.
+    pub fn apply_fsst_compression(
+        &mut self,
+        compressed_content: String,
+        table_id: impl Into<String>,
+        report: Option<&Report>,
+    ) {
+        self.header = ContentHeader::FsstCompressed {
+            table_id: table_id.into(),
+        };
+        self.apply_compression(compressed_content, CompressionMethod::Fsst, report);
+    }
+
     /// Get compression ratio for this file
     pub fn compression_ratio(&self) -> Option<CompressionRatio> {
         self.compressed_size.and_then(|compressed| {
@@ -136,11 +429,148 @@ impl FileEntry {
             }
         })
     }
-    
+
     /// Check if this file was compressed
     pub fn is_compressed(&self) -> bool {
         self.compressed_content.is_some()
     }
+
+    /// Reconstruct this entry's original content using its recorded header.
+    /// `Plain` entries (and any entry that was never dictionary-compressed,
+    /// including `is_binary` ones) come back byte-exact unchanged; `Compressed`
+    /// entries have their dictionary tokens expanded back to source patterns
+    /// via `dict`; `FsstCompressed` entries have their symbol codes expanded
+    /// back via `symbol_table` (only needed for that header variant - pass
+    /// `None` when restoring an entry that can't be `FsstCompressed`).
+    /// `zstd_dictionary` is likewise only needed when this entry's final
+    /// codec was `Codec::Zstd` run against a shared dictionary (see
+    /// `CompressionConfig::zstd_dictionary_config`) - pass `None` otherwise.
+    /// Either way, `undo_final_compression` runs first, since
+    /// `compressed_content` holds the final-stage codec's output (see
+    /// `UniversalCompressor::compress`'s last step), not the Step 3 output
+    /// directly.
+    pub fn decompress(
+        &self,
+        dict: &Dictionary,
+        symbol_table: Option<&SymbolTable>,
+        zstd_dictionary: Option<&ZstdDictionary>,
+    ) -> Result<String, CompressionError> {
+        let dictionary_stage_content = self.undo_final_compression(zstd_dictionary)?;
+
+        match &self.header {
+            ContentHeader::Plain => Ok(dictionary_stage_content),
+            ContentHeader::Compressed { dictionary_id } => {
+                if dictionary_id != dict.id() {
+                    return Err(CompressionError::pattern_replacement(format!(
+                        "entry '{}' was compressed with dictionary '{}', but dictionary '{}' was supplied",
+                        self.relative_path.display(),
+                        dictionary_id,
+                        dict.id()
+                    )));
+                }
+
+                Ok(dict.expand(&dictionary_stage_content))
+            }
+            ContentHeader::FsstCompressed { table_id } => {
+                let table = symbol_table.ok_or_else(|| {
+                    CompressionError::pattern_replacement(format!(
+                        "entry '{}' was fsst-compressed with table '{}', but no symbol table was supplied",
+                        self.relative_path.display(),
+                        table_id
+                    ))
+                })?;
+                if table_id != &table.id() {
+                    return Err(CompressionError::pattern_replacement(format!(
+                        "entry '{}' was fsst-compressed with table '{}', but table '{}' was supplied",
+                        self.relative_path.display(),
+                        table_id,
+                        table.id()
+                    )));
+                }
+
+                let fsst_bytes = base64::decode(&dictionary_stage_content)?;
+                String::from_utf8(table.decompress(&fsst_bytes)).map_err(|e| {
+                    CompressionError::pattern_replacement(format!(
+                        "entry '{}' decompressed to invalid UTF-8: {}",
+                        self.relative_path.display(),
+                        e
+                    ))
+                })
+            }
+        }
+    }
+
+    /// Undo the final-stage `Codec` pass (base64-decode `compressed_content`,
+    /// then run that codec's `Compressor::decompress`), returning whatever
+    /// was fed into that stage: dictionary-token text for an entry whose
+    /// header is `Compressed`, or the original content for a `Plain` one.
+    /// A `method` of `None` or `CompressionMethod::Dictionary` means no
+    /// final-stage codec ran over this entry (e.g.
+    /// `UniversalCompressor::get_compressed_files` only performs the
+    /// dictionary stage), so `compressed_content` is returned unchanged.
+    /// `zstd_dictionary`, when given, is used in place of a plain
+    /// `Codec::Zstd` compressor - see `decompress`'s doc comment.
+    fn undo_final_compression(&self, zstd_dictionary: Option<&ZstdDictionary>) -> Result<String, CompressionError> {
+        let Some(content) = &self.compressed_content else {
+            return Ok(self.original_content.clone());
+        };
+
+        let codec = match self.method {
+            Some(method) if method != CompressionMethod::Dictionary => {
+                Some(codec_for_compression_method(method)?)
+            }
+            _ => None,
+        };
+
+        let Some(codec) = codec else {
+            return Ok(content.clone());
+        };
+
+        let compressed_bytes = base64::decode(content)?;
+        let restored = match (codec, zstd_dictionary) {
+            (Codec::Zstd(level), Some(dictionary)) => {
+                ZstdCompressor::with_dictionary(level, dictionary)?.decompress(&compressed_bytes)?
+            }
+            _ => codec.compressor()?.decompress(&compressed_bytes)?,
+        };
+        String::from_utf8(restored).map_err(|e| {
+            CompressionError::pattern_replacement(format!(
+                "entry '{}' decompressed to invalid UTF-8: {}",
+                self.relative_path.display(),
+                e
+            ))
+        })
+    }
+}
+
+/// Inverse of `compressor::compression_method_for_codec`: map a `FileEntry`'s
+/// recorded final-stage `CompressionMethod` back to the `Codec` whose
+/// `Compressor` can undo it.
+fn codec_for_compression_method(method: CompressionMethod) -> Result<Codec, CompressionError> {
+    match method {
+        CompressionMethod::Store => Ok(Codec::Stored),
+        CompressionMethod::Zstd { level } => Ok(Codec::Zstd(ZstdLevel::new(level)?)),
+        CompressionMethod::Lz4 => Ok(Codec::Lz4(Lz4Level::default())),
+        CompressionMethod::Snappy => Ok(Codec::Snappy),
+        CompressionMethod::Brotli { .. } => Ok(Codec::Brotli),
+        CompressionMethod::Deflate { .. } => Ok(Codec::Deflate),
+        CompressionMethod::Dictionary => Err(CompressionError::pattern_replacement(
+            "Dictionary is not a final-stage codec and can't be inverted as one".to_string(),
+        )),
+        CompressionMethod::Fsst => Err(CompressionError::pattern_replacement(
+            "Fsst is not a final-stage codec and can't be inverted as one".to_string(),
+        )),
+    }
+}
+
+/// Which `CompressorLimits` cap stopped or trimmed file collection. Carried
+/// on `CompressionStatistics` instead of only being logged via `warn!`, so
+/// callers (and tests) can assert on which limits actually fired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CollectionLimit {
+    MaxFiles,
+    MaxTotalBytes,
+    MaxFileSize,
 }
 
 /// Detailed statistics about the compression process
@@ -154,6 +584,21 @@ pub struct CompressionStatistics {
     pub processing_time: Duration,
     pub files_compressed: usize,
     pub files_skipped: usize,
+    pub method_breakdown: HashMap<CompressionMethod, MethodStats>,
+    /// Bytes elided by the pre-dictionary content-defined chunking pass
+    /// (see `crate::compression::dedup`), reported separately from
+    /// `space_saved` since it's measured before pattern replacement runs.
+    pub dedup_bytes_saved: usize,
+    /// Which `CompressorLimits` caps fired during file collection. Empty
+    /// when every file in the target tree fit within all of them.
+    pub collection_limits_hit: Vec<CollectionLimit>,
+    /// Files skipped outright for exceeding `CompressorLimits::max_file_size`.
+    pub oversized_files_skipped: usize,
+    /// Sum of every collected file's in-memory decoded content size - the
+    /// same quantity `CompressorLimits::max_total_bytes` is measured
+    /// against - so callers can confirm the limit was respected against
+    /// real data rather than trusting on-disk metadata.
+    pub collected_content_bytes: u64,
 }
 
 impl CompressionStatistics {
@@ -168,9 +613,30 @@ impl CompressionStatistics {
             processing_time: Duration::new(0, 0),
             files_compressed: 0,
             files_skipped: 0,
+            method_breakdown: HashMap::new(),
+            dedup_bytes_saved: 0,
+            collection_limits_hit: Vec::new(),
+            oversized_files_skipped: 0,
+            collected_content_bytes: 0,
         }
     }
-    
+
+    /// Record one entry's contribution to the per-method breakdown.
+    pub fn record_method(&mut self, method: CompressionMethod, bytes_in: usize, bytes_out: usize) {
+        let entry = self.method_breakdown.entry(method).or_default();
+        entry.bytes_in += bytes_in;
+        entry.bytes_out += bytes_out;
+        entry.count += 1;
+    }
+
+    /// Record that `limit` fired during file collection, if it hasn't
+    /// already been recorded.
+    pub fn record_limit_hit(&mut self, limit: CollectionLimit) {
+        if !self.collection_limits_hit.contains(&limit) {
+            self.collection_limits_hit.push(limit);
+        }
+    }
+
     /// Calculate overall compression ratio
     pub fn compression_ratio(&self) -> CompressionRatio {
         if self.original_total_size.bytes() == 0 {
@@ -213,9 +679,31 @@ impl std::fmt::Display for CompressionStatistics {
         writeln!(f, "  Original size: {}", self.original_total_size)?;
         writeln!(f, "  Compressed size: {}", self.compressed_total_size)?;
         writeln!(f, "  Space saved: {}", self.space_saved())?;
+        writeln!(f, "  Dedup bytes saved: {}", self.dedup_bytes_saved)?;
         writeln!(f, "  Compression ratio: {}", self.compression_ratio())?;
         writeln!(f, "  Processing time: {:.2}s", self.processing_time.as_secs_f64())?;
-        write!(f, "  Efficiency: {:.2} patterns/file", self.compression_efficiency())
+        writeln!(f, "  Efficiency: {:.2} patterns/file", self.compression_efficiency())?;
+
+        if self.method_breakdown.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(f, "  Per-method breakdown:")?;
+        let mut methods: Vec<(&CompressionMethod, &MethodStats)> = self.method_breakdown.iter().collect();
+        methods.sort_by_key(|(method, _)| method.to_string());
+        let last = methods.len() - 1;
+        for (index, (method, stats)) in methods.into_iter().enumerate() {
+            let line = format!(
+                "    {}: files={} bytes_in={} bytes_out={}",
+                method, stats.count, stats.bytes_in, stats.bytes_out
+            );
+            if index == last {
+                write!(f, "{}", line)?;
+            } else {
+                writeln!(f, "{}", line)?;
+            }
+        }
+        Ok(())
     }
 }
 
@@ -226,6 +714,17 @@ pub struct CompressionResult {
     pub statistics: CompressionStatistics,
     pub dictionary_size: usize,
     pub patterns_replaced: usize,
+    pub entries: Vec<FileEntry>,
+    pub dictionary: Dictionary,
+    /// The trained table when `CompressionConfig::dictionary_strategy` was
+    /// `DictionaryStrategy::Fsst`; `None` under the default frequency
+    /// strategy, whose entries carry no `FsstCompressed` header to expand.
+    pub symbol_table: Option<SymbolTable>,
+    /// The dictionary `final_codec`'s zstd stage was run against, when
+    /// `CompressionConfig::zstd_dictionary_config` wasn't
+    /// `ZstdDictionaryConfig::None`; `None` otherwise, or when `final_codec`
+    /// wasn't `Codec::Zstd` at all.
+    pub zstd_dictionary: Option<ZstdDictionary>,
 }
 
 impl CompressionResult {
@@ -235,31 +734,59 @@ impl CompressionResult {
         statistics: CompressionStatistics,
         dictionary_size: usize,
         patterns_replaced: usize,
+        entries: Vec<FileEntry>,
+        dictionary: Dictionary,
+        symbol_table: Option<SymbolTable>,
+        zstd_dictionary: Option<ZstdDictionary>,
     ) -> Self {
         Self {
             output_file_path,
             statistics,
             dictionary_size,
             patterns_replaced,
+            entries,
+            dictionary,
+            symbol_table,
+            zstd_dictionary,
         }
     }
-    
+
     /// Get compression percentage
     pub fn compression_percentage(&self) -> f64 {
         self.statistics.compression_ratio().as_percentage()
     }
-    
+
     /// Get space saved in bytes
     pub fn space_saved(&self) -> FileSize {
         self.statistics.space_saved()
     }
+
+    /// Reconstruct every entry's original content under `output_dir`,
+    /// following each entry's `ContentHeader` rather than inferring from
+    /// content shape whether dictionary tokens need expanding.
+    pub fn restore(&self, output_dir: &Path) -> Result<(), CompressionError> {
+        for entry in &self.entries {
+            let restored = entry.decompress(
+                &self.dictionary,
+                self.symbol_table.as_ref(),
+                self.zstd_dictionary.as_ref(),
+            )?;
+            let dest = output_dir.join(&entry.relative_path);
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&dest, restored)?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::Path;
-    
+    use crate::compression::codec::Compressor;
+    use crate::compression::stored_compressor::StoredCompressor;
+
     #[test]
     fn test_compression_ratio() {
         let ratio = CompressionRatio::new(0.75).unwrap();
@@ -298,9 +825,126 @@ mod tests {
         assert!(!entry.is_compressed());
         assert!(entry.compression_ratio().is_none());
         
-        entry.apply_compression("fn main(){}".to_string());
+        entry.apply_compression("fn main(){}".to_string(), CompressionMethod::Store, None);
         assert!(entry.is_compressed());
         assert!(entry.compression_ratio().is_some());
+        assert_eq!(entry.method, Some(CompressionMethod::Store));
+    }
+
+    #[test]
+    fn test_apply_compression_updates_shared_report() {
+        let mut entry = FileEntry::new(
+            Path::new("test.rs").to_path_buf(),
+            "fn main() {}".to_string(),
+            false,
+        );
+        let report = Report::new();
+
+        entry.apply_compression("fn(){}".to_string(), CompressionMethod::Store, Some(&report));
+
+        let sizes = report.sizes();
+        assert_eq!(sizes.original, entry.original_size.bytes());
+        assert_eq!(sizes.compressed, "fn(){}".len());
+        assert_eq!(report.files_processed(), 1);
+    }
+
+    #[test]
+    fn test_decompress_undoes_final_codec_then_dictionary_stage() {
+        let dict = Dictionary::from_entries(vec![("hello world".to_string(), "T0000".to_string())]);
+        let mut entry = FileEntry::new(
+            Path::new("greeting.txt").to_path_buf(),
+            "hello world".to_string(),
+            false,
+        );
+
+        let dictionary_stage = "T0000".to_string();
+        entry.apply_dictionary_compression(dictionary_stage.clone(), dict.id().to_string(), None);
+
+        let codec = Codec::Zstd(ZstdLevel::new(3).unwrap());
+        let final_compressed = codec.compressor().unwrap().compress(dictionary_stage.as_bytes()).unwrap();
+        entry.apply_compression(base64::encode(&final_compressed), CompressionMethod::Zstd { level: 3 }, None);
+
+        assert_eq!(entry.decompress(&dict, None, None).unwrap(), "hello world");
+    }
+
+    #[test]
+    fn test_decompress_plain_entry_round_trips_through_final_codec() {
+        let dict = Dictionary::default();
+        let mut entry = FileEntry::new(
+            Path::new("data.bin").to_path_buf(),
+            "raw bytes".to_string(),
+            true,
+        );
+
+        let final_compressed = StoredCompressor.compress(b"raw bytes").unwrap();
+        entry.apply_compression(base64::encode(&final_compressed), CompressionMethod::Store, None);
+
+        assert_eq!(entry.decompress(&dict, None, None).unwrap(), "raw bytes");
+    }
+
+    #[test]
+    fn test_decompress_undoes_final_codec_then_fsst_stage() {
+        let files = vec![FileEntry::new(
+            Path::new("greeting.txt").to_path_buf(),
+            "hello world".to_string(),
+            false,
+        )];
+        let refs: Vec<&FileEntry> = files.iter().collect();
+        let table = crate::compression::fsst::FsstCompressor::train_bulk(&refs);
+        let mut entry = files.into_iter().next().unwrap();
+
+        let fsst_stage = base64::encode(&table.compress(b"hello world"));
+        entry.apply_fsst_compression(fsst_stage.clone(), table.id(), None);
+
+        let codec = Codec::Zstd(ZstdLevel::new(3).unwrap());
+        let final_compressed = codec.compressor().unwrap().compress(fsst_stage.as_bytes()).unwrap();
+        entry.apply_compression(base64::encode(&final_compressed), CompressionMethod::Zstd { level: 3 }, None);
+
+        assert_eq!(entry.decompress(&Dictionary::default(), Some(&table), None).unwrap(), "hello world");
+    }
+
+    #[test]
+    fn test_decompress_fsst_entry_without_table_is_an_error() {
+        let mut entry = FileEntry::new(
+            Path::new("greeting.txt").to_path_buf(),
+            "hello world".to_string(),
+            false,
+        );
+        entry.apply_fsst_compression(base64::encode(b"whatever"), "some-table-id".to_string(), None);
+
+        assert!(entry.decompress(&Dictionary::default(), None, None).is_err());
+    }
+
+    #[test]
+    fn test_compression_method_parses_name_and_level() {
+        assert_eq!("dictionary".parse::<CompressionMethod>().unwrap(), CompressionMethod::Dictionary);
+        assert_eq!("fsst".parse::<CompressionMethod>().unwrap(), CompressionMethod::Fsst);
+        assert_eq!("store".parse::<CompressionMethod>().unwrap(), CompressionMethod::Store);
+        assert_eq!("lz4".parse::<CompressionMethod>().unwrap(), CompressionMethod::Lz4);
+        assert_eq!("snappy".parse::<CompressionMethod>().unwrap(), CompressionMethod::Snappy);
+        assert_eq!("zstd".parse::<CompressionMethod>().unwrap(), CompressionMethod::Zstd { level: 3 });
+        assert_eq!("brotli/5".parse::<CompressionMethod>().unwrap(), CompressionMethod::Brotli { level: 5 });
+        assert_eq!("deflate/9".parse::<CompressionMethod>().unwrap(), CompressionMethod::Deflate { level: 9 });
+        assert!("made_up".parse::<CompressionMethod>().is_err());
+        assert!("zstd/not_a_number".parse::<CompressionMethod>().is_err());
+    }
+
+    #[test]
+    fn test_statistics_method_breakdown_display() {
+        let mut stats = CompressionStatistics::new();
+        stats.record_method(CompressionMethod::Dictionary, 100, 40);
+        stats.record_method(CompressionMethod::Dictionary, 50, 20);
+        stats.record_method(CompressionMethod::Store, 10, 10);
+
+        let dictionary_stats = stats.method_breakdown[&CompressionMethod::Dictionary];
+        assert_eq!(dictionary_stats.count, 2);
+        assert_eq!(dictionary_stats.bytes_in, 150);
+        assert_eq!(dictionary_stats.bytes_out, 60);
+
+        let rendered = stats.to_string();
+        assert!(rendered.contains("Per-method breakdown"));
+        assert!(rendered.contains("dictionary: files=2 bytes_in=150 bytes_out=60"));
+        assert!(rendered.contains("store: files=1 bytes_in=10 bytes_out=10"));
     }
     
     #[test]
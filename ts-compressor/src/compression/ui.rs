@@ -0,0 +1,255 @@
+//! Progress-reporting UI abstraction
+//!
+//! Modeled on conserve's `UI`: a pluggable progress/output surface
+//! selectable by name (`auto`, `plain`, `color`), fed by a shared `Report`
+//! that accumulates running `Sizes` as `FileEntry::apply_compression`
+//! processes each file.
+
+use std::io::IsTerminal;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::compression::error::CompressionError;
+
+/// Running original/compressed byte totals.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sizes {
+    pub original: usize,
+    pub compressed: usize,
+}
+
+/// Percentage of `sizes.original` that `sizes.compressed` represents,
+/// mirroring `CompressionRatio`'s ratio math but without its `[0, 1]`
+/// clamp, since a live progress bar may legitimately show a partially
+/// processed batch that has (so far) expanded rather than shrunk.
+pub fn compression_percent(sizes: &Sizes) -> f64 {
+    if sizes.original == 0 {
+        0.0
+    } else {
+        sizes.compressed as f64 / sizes.original as f64 * 100.0
+    }
+}
+
+/// Shared accumulator updated as each `FileEntry` finishes compressing,
+/// and rendered by a `UI`'s `show_progress`.
+#[derive(Debug)]
+pub struct Report {
+    sizes: Mutex<Sizes>,
+    files_processed: Mutex<usize>,
+    started: Instant,
+}
+
+impl Report {
+    pub fn new() -> Self {
+        Self {
+            sizes: Mutex::new(Sizes::default()),
+            files_processed: Mutex::new(0),
+            started: Instant::now(),
+        }
+    }
+
+    /// Record one file's contribution to the running totals.
+    pub fn add(&self, original: usize, compressed: usize) {
+        let mut sizes = self.sizes.lock().unwrap();
+        sizes.original += original;
+        sizes.compressed += compressed;
+        *self.files_processed.lock().unwrap() += 1;
+    }
+
+    /// Snapshot of the running totals.
+    pub fn sizes(&self) -> Sizes {
+        *self.sizes.lock().unwrap()
+    }
+
+    /// Number of files recorded so far.
+    pub fn files_processed(&self) -> usize {
+        *self.files_processed.lock().unwrap()
+    }
+
+    /// Time elapsed since this report was created.
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
+
+impl Default for Report {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A progress/output surface a compression run reports through.
+pub trait UI {
+    /// Render the current state of `report`, e.g. as a progress bar line
+    /// with a live ratio and ETA derived from `report.elapsed()`.
+    fn show_progress(&mut self, report: &Report);
+
+    /// Print an informational line.
+    fn print(&mut self, message: &str);
+
+    /// Print a warning/error line, distinct from normal output.
+    fn problem(&mut self, message: &str);
+
+    /// Flush or clear any in-progress rendering; called once the run ends.
+    fn finish(&mut self);
+}
+
+/// Plain-text UI: one line per `show_progress` call, no cursor control.
+/// Used for piped output or any non-TTY destination.
+#[derive(Debug, Default)]
+pub struct PlainUI;
+
+impl UI for PlainUI {
+    fn show_progress(&mut self, report: &Report) {
+        let sizes = report.sizes();
+        println!(
+            "{} files, {:.1}% ({} -> {} bytes, {:.1}s elapsed)",
+            report.files_processed(),
+            compression_percent(&sizes),
+            sizes.original,
+            sizes.compressed,
+            report.elapsed().as_secs_f64()
+        );
+    }
+
+    fn print(&mut self, message: &str) {
+        println!("{}", message);
+    }
+
+    fn problem(&mut self, message: &str) {
+        eprintln!("{}", message);
+    }
+
+    fn finish(&mut self) {}
+}
+
+/// Color UI: redraws the same terminal line in place using a carriage
+/// return, and colors problem output.
+#[derive(Debug, Default)]
+pub struct ColorUI {
+    progress_drawn: bool,
+}
+
+impl UI for ColorUI {
+    fn show_progress(&mut self, report: &Report) {
+        let sizes = report.sizes();
+        print!(
+            "\r\x1b[2K{} files, {:.1}% ({} -> {} bytes, {:.1}s elapsed)",
+            report.files_processed(),
+            compression_percent(&sizes),
+            sizes.original,
+            sizes.compressed,
+            report.elapsed().as_secs_f64()
+        );
+        let _ = std::io::Write::flush(&mut std::io::stdout());
+        self.progress_drawn = true;
+    }
+
+    fn print(&mut self, message: &str) {
+        self.clear_progress_line();
+        println!("{}", message);
+    }
+
+    fn problem(&mut self, message: &str) {
+        self.clear_progress_line();
+        eprintln!("\x1b[31m{}\x1b[0m", message);
+    }
+
+    fn finish(&mut self) {
+        self.clear_progress_line();
+    }
+}
+
+impl ColorUI {
+    fn clear_progress_line(&mut self) {
+        if self.progress_drawn {
+            print!("\r\x1b[2K");
+            let _ = std::io::Write::flush(&mut std::io::stdout());
+            self.progress_drawn = false;
+        }
+    }
+}
+
+/// Which `UI` implementation to use, selectable by name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UiKind {
+    /// Picks `Color` when stdout is a TTY and `Plain` otherwise.
+    Auto,
+    Plain,
+    Color,
+}
+
+impl FromStr for UiKind {
+    type Err = CompressionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Ok(UiKind::Auto),
+            "plain" => Ok(UiKind::Plain),
+            "color" => Ok(UiKind::Color),
+            other => Err(CompressionError::config_validation(format!(
+                "Unknown UI kind: {other}"
+            ))),
+        }
+    }
+}
+
+impl UiKind {
+    /// Build the concrete `UI` implementation for this kind, resolving
+    /// `Auto` against whether stdout is currently a TTY.
+    pub fn build(self) -> Box<dyn UI> {
+        match self {
+            UiKind::Plain => Box::new(PlainUI),
+            UiKind::Color => Box::new(ColorUI::default()),
+            UiKind::Auto => {
+                if std::io::stdout().is_terminal() {
+                    Box::new(ColorUI::default())
+                } else {
+                    Box::new(PlainUI)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compression_percent() {
+        let sizes = Sizes { original: 100, compressed: 40 };
+        assert_eq!(compression_percent(&sizes), 40.0);
+    }
+
+    #[test]
+    fn test_compression_percent_empty_is_zero() {
+        let sizes = Sizes::default();
+        assert_eq!(compression_percent(&sizes), 0.0);
+    }
+
+    #[test]
+    fn test_report_accumulates_sizes() {
+        let report = Report::new();
+        report.add(100, 40);
+        report.add(50, 20);
+
+        let sizes = report.sizes();
+        assert_eq!(sizes.original, 150);
+        assert_eq!(sizes.compressed, 60);
+        assert_eq!(report.files_processed(), 2);
+    }
+
+    #[test]
+    fn test_ui_kind_parses_known_names() {
+        assert_eq!(UiKind::from_str("plain").unwrap(), UiKind::Plain);
+        assert_eq!(UiKind::from_str("Color").unwrap(), UiKind::Color);
+        assert_eq!(UiKind::from_str("AUTO").unwrap(), UiKind::Auto);
+    }
+
+    #[test]
+    fn test_ui_kind_rejects_unknown_name() {
+        assert!(UiKind::from_str("fancy").is_err());
+    }
+}
@@ -0,0 +1,192 @@
+//! Wu-Manber multi-pattern scanning
+//!
+//! Locates every occurrence of a fixed candidate pattern set (typically the
+//! output of `FrequencyAnalysis::get_frequent_patterns`) in arbitrary input
+//! text, so the compression/replacement pass can find substitution sites
+//! without scanning once per pattern.
+
+use std::collections::HashMap;
+
+/// Width, in bytes, of the trailing block hashed for the SHIFT/HASH tables.
+const BLOCK_SIZE: usize = 2;
+
+/// Prebuilt Wu-Manber searcher over a fixed candidate pattern set.
+///
+/// Scanning slides a window of the shortest pattern's length across the
+/// text. At each position the trailing `BLOCK_SIZE` bytes are hashed and
+/// looked up in the SHIFT table to see how far the window can safely jump
+/// ahead without possibly skipping a match; only when that shift is zero is
+/// a full verification against the HASH table's candidate patterns needed.
+/// Build once and reuse across many inputs, since constructing the tables
+/// costs O(total pattern bytes).
+#[derive(Debug)]
+pub struct PatternSearcher {
+    patterns: Vec<String>,
+    /// Length of the shortest pattern; the width of the scanning window.
+    min_pattern_len: usize,
+    /// Trailing-block hash -> bytes the window may safely advance.
+    shift_table: HashMap<u16, usize>,
+    /// Trailing-block hash -> indices of patterns ending in that block,
+    /// consulted only when `shift_table` says the shift is zero.
+    hash_table: HashMap<u16, Vec<usize>>,
+}
+
+impl PatternSearcher {
+    /// Build a searcher directly from `FrequencyAnalysis::get_frequent_patterns`,
+    /// discarding the frequency counts it carries alongside each pattern.
+    pub fn from_frequent_patterns(patterns: Vec<(String, usize)>) -> Self {
+        Self::new(patterns.into_iter().map(|(pattern, _)| pattern).collect())
+    }
+
+    /// Build a searcher over `patterns`. Patterns shorter than `BLOCK_SIZE`
+    /// cannot be hashed and are dropped, since Wu-Manber's block shifting
+    /// requires at least one full block to examine.
+    pub fn new(patterns: Vec<String>) -> Self {
+        let patterns: Vec<String> = patterns
+            .into_iter()
+            .filter(|p| p.len() >= BLOCK_SIZE)
+            .collect();
+
+        if patterns.is_empty() {
+            return Self {
+                patterns,
+                min_pattern_len: 0,
+                shift_table: HashMap::new(),
+                hash_table: HashMap::new(),
+            };
+        }
+
+        let min_pattern_len = patterns.iter().map(|p| p.len()).min().unwrap();
+        let default_shift = min_pattern_len - BLOCK_SIZE + 1;
+
+        let mut shift_table = HashMap::new();
+        let mut hash_table: HashMap<u16, Vec<usize>> = HashMap::new();
+
+        for (idx, pattern) in patterns.iter().enumerate() {
+            // Only the pattern's first `min_pattern_len` bytes matter, since
+            // every scanning window is exactly that wide.
+            let prefix = &pattern.as_bytes()[..min_pattern_len];
+
+            for block_end in BLOCK_SIZE..=min_pattern_len {
+                let block = block_hash(&prefix[block_end - BLOCK_SIZE..block_end]);
+                let shift = min_pattern_len - block_end;
+                let entry = shift_table.entry(block).or_insert(default_shift);
+                if shift < *entry {
+                    *entry = shift;
+                }
+            }
+
+            let trailing = block_hash(&prefix[min_pattern_len - BLOCK_SIZE..]);
+            hash_table.entry(trailing).or_default().push(idx);
+        }
+
+        Self {
+            patterns,
+            min_pattern_len,
+            shift_table,
+            hash_table,
+        }
+    }
+
+    fn shift_for(&self, block: u16) -> usize {
+        self.shift_table
+            .get(&block)
+            .copied()
+            .unwrap_or(self.min_pattern_len - BLOCK_SIZE + 1)
+    }
+
+    /// Scan `text` for non-overlapping matches, returning `(start, end,
+    /// pattern)` byte spans in the order found. At each candidate position
+    /// the longest verified pattern wins, and the scan resumes right after
+    /// the matched span rather than by a single byte.
+    pub fn find_all_nonoverlapping<'s>(&'s self, text: &str) -> Vec<(usize, usize, &'s str)> {
+        let mut matches = Vec::new();
+        let bytes = text.as_bytes();
+        if self.patterns.is_empty() || bytes.len() < self.min_pattern_len {
+            return matches;
+        }
+
+        // `pos` is the inclusive index of the current window's last byte.
+        let mut pos = self.min_pattern_len - 1;
+        while pos < bytes.len() {
+            let window_start = pos + 1 - self.min_pattern_len;
+            let block = block_hash(&bytes[pos + 1 - BLOCK_SIZE..=pos]);
+            let shift = self.shift_for(block);
+
+            if shift > 0 {
+                pos += shift;
+                continue;
+            }
+
+            let mut best: Option<(usize, usize)> = None; // (pattern_idx, len)
+            if let Some(candidates) = self.hash_table.get(&block) {
+                for &idx in candidates {
+                    let pattern = self.patterns[idx].as_bytes();
+                    let end = window_start + pattern.len();
+                    if end <= bytes.len()
+                        && &bytes[window_start..end] == pattern
+                        && best.map(|(_, len)| pattern.len() > len).unwrap_or(true)
+                    {
+                        best = Some((idx, pattern.len()));
+                    }
+                }
+            }
+
+            match best {
+                Some((idx, len)) => {
+                    matches.push((window_start, window_start + len, self.patterns[idx].as_str()));
+                    pos = window_start + len + self.min_pattern_len - 1;
+                }
+                None => pos += 1,
+            }
+        }
+
+        matches
+    }
+}
+
+fn block_hash(block: &[u8]) -> u16 {
+    debug_assert_eq!(block.len(), BLOCK_SIZE);
+    ((block[0] as u16) << 8) | block[1] as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finds_single_pattern() {
+        let searcher = PatternSearcher::new(vec!["function".to_string()]);
+        let matches = searcher.find_all_nonoverlapping("a function call and another function");
+        assert_eq!(matches.len(), 2);
+        assert!(matches.iter().all(|(_, _, p)| *p == "function"));
+    }
+
+    #[test]
+    fn test_non_overlapping_matches_advance_past_span() {
+        let searcher = PatternSearcher::new(vec!["aa".to_string()]);
+        let matches = searcher.find_all_nonoverlapping("aaaa");
+        assert_eq!(matches, vec![(0, 2, "aa"), (2, 4, "aa")]);
+    }
+
+    #[test]
+    fn test_prefers_longest_candidate_pattern() {
+        let searcher = PatternSearcher::new(vec!["on".to_string(), "ion".to_string()]);
+        let matches = searcher.find_all_nonoverlapping("function");
+        assert_eq!(matches, vec![(5, 8, "ion")]);
+    }
+
+    #[test]
+    fn test_multiple_distinct_patterns() {
+        let searcher = PatternSearcher::new(vec!["cat".to_string(), "dog".to_string()]);
+        let matches = searcher.find_all_nonoverlapping("cat dog cat");
+        let spans: Vec<&str> = matches.iter().map(|(_, _, p)| *p).collect();
+        assert_eq!(spans, vec!["cat", "dog", "cat"]);
+    }
+
+    #[test]
+    fn test_empty_pattern_set_finds_nothing() {
+        let searcher = PatternSearcher::new(vec![]);
+        assert!(searcher.find_all_nonoverlapping("anything").is_empty());
+    }
+}
@@ -2,14 +2,104 @@
 //!
 //! Provides idiomatic Rust wrapper around zstd compression with
 //! RAII resource management and type-safe compression levels.
+//!
+//! Decompression requires the `experimental` feature of the `zstd` crate,
+//! which exposes `Decompressor::upper_bound` for reading the content size
+//! embedded in a zstd frame header.
 
-use crate::compression::config::ZstdLevel;
+use crate::compression::codec::Compressor;
+use crate::compression::config::{ZstdAdvanced, ZstdLevel, ZstdStrategy};
 use crate::compression::error::{CompressionError, CompressionResult};
 use std::io::{Read, Write};
 
+/// Map our `ZstdStrategy` to the `zstd_safe` crate's own strategy enum,
+/// which `CParameter::Strategy` expects.
+fn to_zstd_safe_strategy(strategy: ZstdStrategy) -> zstd::zstd_safe::Strategy {
+    match strategy {
+        ZstdStrategy::Fast => zstd::zstd_safe::Strategy::ZSTD_fast,
+        ZstdStrategy::DFast => zstd::zstd_safe::Strategy::ZSTD_dfast,
+        ZstdStrategy::Greedy => zstd::zstd_safe::Strategy::ZSTD_greedy,
+        ZstdStrategy::Lazy => zstd::zstd_safe::Strategy::ZSTD_lazy,
+        ZstdStrategy::Lazy2 => zstd::zstd_safe::Strategy::ZSTD_lazy2,
+        ZstdStrategy::BtLazy2 => zstd::zstd_safe::Strategy::ZSTD_btlazy2,
+        ZstdStrategy::BtOpt => zstd::zstd_safe::Strategy::ZSTD_btopt,
+        ZstdStrategy::BtUltra => zstd::zstd_safe::Strategy::ZSTD_btultra,
+        ZstdStrategy::BtUltra2 => zstd::zstd_safe::Strategy::ZSTD_btultra2,
+    }
+}
+
+/// Apply `advanced`'s knobs to a freshly-constructed bulk compressor, in
+/// the order the zstd C API expects (window log and strategy before
+/// enabling long-distance matching, which depends on the window already
+/// being set).
+fn apply_advanced_params(
+    compressor: &mut zstd::bulk::Compressor<'_>,
+    advanced: &ZstdAdvanced,
+) -> CompressionResult<()> {
+    if let Some(window_log) = advanced.window_log {
+        compressor
+            .set_parameter(zstd::zstd_safe::CParameter::WindowLog(window_log.get()))
+            .map_err(|e| CompressionError::zstd_compression(e))?;
+    }
+    if let Some(strategy) = advanced.strategy {
+        compressor
+            .set_parameter(zstd::zstd_safe::CParameter::Strategy(to_zstd_safe_strategy(strategy)))
+            .map_err(|e| CompressionError::zstd_compression(e))?;
+    }
+    if advanced.enable_long_distance_matching {
+        compressor
+            .set_parameter(zstd::zstd_safe::CParameter::EnableLongDistanceMatching(true))
+            .map_err(|e| CompressionError::zstd_compression(e))?;
+    }
+    Ok(())
+}
+
+/// Fallback capacity used to decompress a frame whose header carries no
+/// content size, bounding memory use for untrusted input.
+const DEFAULT_MAX_DECOMPRESSED_SIZE: usize = 512 * 1024 * 1024; // 512MB
+
+/// A trained zstd dictionary, built from a corpus of small, similar
+/// payloads that don't compress well standalone (e.g. many short prompts
+/// sharing vocabulary). Persist `as_bytes`/`into_bytes` alongside the
+/// compressed data so the same dictionary can be reloaded for
+/// decompression later.
+#[derive(Debug, Clone)]
+pub struct ZstdDictionary {
+    bytes: Vec<u8>,
+}
+
+impl ZstdDictionary {
+    /// Train a dictionary of roughly `dict_size` bytes from `samples`.
+    pub fn train_from_samples(samples: &[&[u8]], dict_size: usize) -> CompressionResult<Self> {
+        let bytes = zstd::dict::from_samples(samples, dict_size)
+            .map_err(|e| CompressionError::zstd_compression(e))?;
+        Ok(Self { bytes })
+    }
+
+    /// Load a previously trained dictionary from its raw bytes.
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self { bytes }
+    }
+
+    /// The raw dictionary bytes, for persisting alongside the data it
+    /// compresses.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Take ownership of the raw dictionary bytes.
+    #[allow(dead_code)]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
 /// Newtype wrapper for zstd compression context with RAII
 pub struct ZstdCompressor {
     compression_level: ZstdLevel,
+    max_decompressed_size: usize,
+    dictionary: Option<Vec<u8>>,
+    advanced: ZstdAdvanced,
 }
 
 impl ZstdCompressor {
@@ -17,20 +107,110 @@ impl ZstdCompressor {
     pub fn new(level: ZstdLevel) -> CompressionResult<Self> {
         Ok(Self {
             compression_level: level,
+            max_decompressed_size: DEFAULT_MAX_DECOMPRESSED_SIZE,
+            dictionary: None,
+            advanced: ZstdAdvanced::default(),
+        })
+    }
+
+    /// Create a zstd compressor (and matching decompressor, via the same
+    /// type's `decompress`) that uses a shared dictionary instead of
+    /// compressing each payload independently.
+    #[allow(dead_code)]
+    pub fn with_dictionary(level: ZstdLevel, dictionary: &ZstdDictionary) -> CompressionResult<Self> {
+        Ok(Self {
+            compression_level: level,
+            max_decompressed_size: DEFAULT_MAX_DECOMPRESSED_SIZE,
+            dictionary: Some(dictionary.as_bytes().to_vec()),
+            advanced: ZstdAdvanced::default(),
         })
     }
 
+    /// Apply advanced tuning (strategy, window log, long-distance matching)
+    /// beyond the plain compression level; see `ZstdAdvanced`. Takes effect
+    /// on the next `compress()` call.
+    #[allow(dead_code)]
+    pub fn with_advanced(mut self, advanced: ZstdAdvanced) -> CompressionResult<Self> {
+        advanced.validate(self.compression_level)?;
+        self.advanced = advanced;
+        Ok(self)
+    }
+
+    /// Override the capacity used to decompress frames that carry no
+    /// content size in their header, so callers can bound memory use for
+    /// untrusted input.
+    #[allow(dead_code)]
+    pub fn with_max_decompressed_size(mut self, max_decompressed_size: usize) -> Self {
+        self.max_decompressed_size = max_decompressed_size;
+        self
+    }
+
     /// Compress data using zstd
     pub fn compress(&self, data: &[u8]) -> CompressionResult<Vec<u8>> {
-        zstd::bulk::compress(data, self.compression_level.get())
-            .map_err(|e| CompressionError::zstd_compression(e))
+        match &self.dictionary {
+            Some(dict) => {
+                let mut compressor =
+                    zstd::bulk::Compressor::with_dictionary(self.compression_level.get(), dict)
+                        .map_err(|e| CompressionError::zstd_compression(e))?;
+                apply_advanced_params(&mut compressor, &self.advanced)?;
+                compressor
+                    .compress(data)
+                    .map_err(|e| CompressionError::zstd_compression(e))
+            }
+            None if self.advanced == ZstdAdvanced::default() => {
+                zstd::bulk::compress(data, self.compression_level.get())
+                    .map_err(|e| CompressionError::zstd_compression(e))
+            }
+            None => {
+                let mut compressor = zstd::bulk::Compressor::new(self.compression_level.get())
+                    .map_err(|e| CompressionError::zstd_compression(e))?;
+                apply_advanced_params(&mut compressor, &self.advanced)?;
+                compressor
+                    .compress(data)
+                    .map_err(|e| CompressionError::zstd_compression(e))
+            }
+        }
     }
 
     /// Decompress data using zstd
+    ///
+    /// Sizes the output buffer from the content size embedded in the zstd
+    /// frame header (`Decompressor::upper_bound`) instead of a fixed cap, so
+    /// this works for payloads of any size. Only frames with no content
+    /// size recorded (e.g. streamed without `set_pledged_src_size`) fall
+    /// back to `max_decompressed_size`.
     #[allow(dead_code)]
     pub fn decompress(&self, data: &[u8]) -> CompressionResult<Vec<u8>> {
-        zstd::bulk::decompress(data, 1024 * 1024) // 1MB limit for safety
-            .map_err(|e| CompressionError::zstd_compression(e))
+        let capacity = zstd::bulk::Decompressor::upper_bound(data).unwrap_or(self.max_decompressed_size);
+        match &self.dictionary {
+            Some(dict) => {
+                let mut decompressor = zstd::bulk::Decompressor::with_dictionary(dict)
+                    .map_err(|e| CompressionError::zstd_compression(e))?;
+                decompressor
+                    .decompress(data, capacity)
+                    .map_err(|e| CompressionError::zstd_compression(e))
+            }
+            None => zstd::bulk::decompress(data, capacity).map_err(|e| CompressionError::zstd_compression(e)),
+        }
+    }
+
+    /// Compress `data`, timing the operation and returning the compressed
+    /// bytes alongside populated `ZstdCompressionStats` (sizes, level, and
+    /// elapsed time), so callers don't have to assemble the stats by hand.
+    #[allow(dead_code)]
+    pub fn compress_with_stats(&self, data: &[u8]) -> CompressionResult<(Vec<u8>, ZstdCompressionStats)> {
+        let start = std::time::Instant::now();
+        let compressed = self.compress(data)?;
+        let compression_time_ms = start.elapsed().as_millis();
+
+        let stats = ZstdCompressionStats {
+            original_size: data.len(),
+            compressed_size: compressed.len(),
+            compression_level: self.compression_level,
+            compression_time_ms,
+        };
+
+        Ok((compressed, stats))
     }
 
     /// Compress string data
@@ -55,6 +235,27 @@ impl ZstdCompressor {
     pub fn compression_level(&self) -> ZstdLevel {
         self.compression_level
     }
+
+    /// Get the fallback capacity used for frames without a recorded
+    /// content size
+    #[allow(dead_code)]
+    pub fn max_decompressed_size(&self) -> usize {
+        self.max_decompressed_size
+    }
+}
+
+impl Compressor for ZstdCompressor {
+    fn compress(&self, data: &[u8]) -> CompressionResult<Vec<u8>> {
+        self.compress(data)
+    }
+
+    fn decompress(&self, data: &[u8]) -> CompressionResult<Vec<u8>> {
+        self.decompress(data)
+    }
+
+    fn codec_id(&self) -> u8 {
+        1
+    }
 }
 
 /// Streaming zstd compressor for large data
@@ -287,6 +488,25 @@ mod tests {
         assert!(stats.compression_percentage() < 100.0);
     }
 
+    #[test]
+    fn test_zstd_compress_with_stats() {
+        let level = ZstdLevel::new(5).unwrap();
+        let compressor = ZstdCompressor::new(level).unwrap();
+
+        let test_data = "Hello, world! ".repeat(50);
+        let (compressed, stats) = compressor.compress_with_stats(test_data.as_bytes()).unwrap();
+
+        assert_eq!(stats.original_size, test_data.len());
+        assert_eq!(stats.compressed_size, compressed.len());
+        assert_eq!(stats.compression_level, level);
+        assert!(stats.compression_ratio() > 0.0);
+        assert!(stats.compression_ratio() < 1.0);
+        assert!(stats.space_saved() > 0);
+
+        let decompressed = compressor.decompress_to_string(&compressed).unwrap();
+        assert_eq!(decompressed, test_data);
+    }
+
     #[test]
     fn test_zstd_streaming_compression() {
         let level = ZstdLevel::new(3).unwrap();
@@ -310,6 +530,91 @@ mod tests {
         assert_eq!(decompressed, b"Hello, world!");
     }
 
+    #[test]
+    fn test_zstd_decompress_above_one_megabyte() {
+        // Regression test for the old hard-coded 1MB decompress cap.
+        let level = ZstdLevel::new(3).unwrap();
+        let compressor = ZstdCompressor::new(level).unwrap();
+
+        let large_data = "function test() { return 'hello world'; }\n".repeat(50_000);
+        assert!(large_data.len() > 1024 * 1024);
+
+        let compressed = compressor.compress_string(&large_data).unwrap();
+        let decompressed = compressor.decompress_to_string(&compressed).unwrap();
+
+        assert_eq!(decompressed, large_data);
+    }
+
+    #[test]
+    fn test_zstd_max_decompressed_size_default_and_override() {
+        let level = ZstdLevel::new(3).unwrap();
+        let compressor = ZstdCompressor::new(level).unwrap();
+        assert_eq!(compressor.max_decompressed_size(), 512 * 1024 * 1024);
+
+        let compressor = compressor.with_max_decompressed_size(1024);
+        assert_eq!(compressor.max_decompressed_size(), 1024);
+    }
+
+    #[test]
+    fn test_zstd_dictionary_training_and_round_trip() {
+        let samples: Vec<&[u8]> = vec![
+            b"fn handler(req: Request) -> Response { respond_ok() }",
+            b"fn handler(req: Request) -> Response { respond_error() }",
+            b"fn middleware(req: Request) -> Response { respond_ok() }",
+        ];
+        let dictionary = ZstdDictionary::train_from_samples(&samples, 1024).unwrap();
+
+        let level = ZstdLevel::new(3).unwrap();
+        let compressor = ZstdCompressor::with_dictionary(level, &dictionary).unwrap();
+
+        let prompt = b"fn handler(req: Request) -> Response { respond_ok() }";
+        let compressed = compressor.compress(prompt).unwrap();
+        let decompressed = compressor.decompress(&compressed).unwrap();
+
+        assert_eq!(decompressed, prompt);
+    }
+
+    #[test]
+    fn test_zstd_dictionary_persists_as_bytes() {
+        let samples: Vec<&[u8]> = vec![b"alpha beta gamma", b"alpha beta delta", b"alpha gamma delta"];
+        let dictionary = ZstdDictionary::train_from_samples(&samples, 512).unwrap();
+
+        let reloaded = ZstdDictionary::from_bytes(dictionary.as_bytes().to_vec());
+        assert_eq!(reloaded.as_bytes(), dictionary.as_bytes());
+    }
+
+    #[test]
+    fn test_zstd_advanced_strategy_and_window_log_round_trip() {
+        use crate::compression::config::{WindowLog, ZstdAdvanced, ZstdStrategy};
+
+        let advanced = ZstdAdvanced {
+            strategy: Some(ZstdStrategy::Lazy2),
+            window_log: Some(WindowLog::new(20).unwrap()),
+            enable_long_distance_matching: false,
+        };
+        let compressor = ZstdCompressor::new(ZstdLevel::new(5).unwrap())
+            .unwrap()
+            .with_advanced(advanced)
+            .unwrap();
+
+        let data = b"the quick brown fox jumps over the lazy dog".repeat(100);
+        let compressed = compressor.compress(&data).unwrap();
+        let decompressed = compressor.decompress(&compressed).unwrap();
+        assert_eq!(decompressed, data);
+    }
+
+    #[test]
+    fn test_zstd_with_advanced_rejects_invalid_tuning() {
+        use crate::compression::config::ZstdAdvanced;
+
+        let advanced = ZstdAdvanced {
+            enable_long_distance_matching: true,
+            ..Default::default()
+        };
+        let result = ZstdCompressor::new(ZstdLevel::default()).unwrap().with_advanced(advanced);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_zstd_error_handling() {
         let level = ZstdLevel::new(3).unwrap();
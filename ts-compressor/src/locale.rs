@@ -0,0 +1,174 @@
+//! Locale detection and message catalog for the archiver's user-facing
+//! output (`display_filter_stats`, `write_header`, `write_file_contents`).
+//!
+//! Each message is looked up by a stable string id through a [`Catalog`],
+//! rather than formatted inline at the call site, so translating the
+//! archiver's output is a matter of adding a table to `catalog_for` instead
+//! of touching the formatting code. `En` is the only table shipped today.
+
+use std::env;
+
+/// A supported output language. Adding one means adding a variant here and
+/// a matching table in `catalog_for`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    En,
+}
+
+impl Lang {
+    /// Parse a `--lang`/`LANG`-style tag (e.g. `en`, `en_US.UTF-8`) into a
+    /// supported language, ignoring any territory/encoding suffix. Returns
+    /// `None` for a tag with no matching table, so callers can fall through
+    /// to the next source instead of silently picking a wrong language.
+    fn parse(tag: &str) -> Option<Self> {
+        let primary = tag.split(['_', '.', '-']).next().unwrap_or(tag);
+        match primary.to_ascii_lowercase().as_str() {
+            "en" | "c" | "posix" => Some(Self::En),
+            _ => None,
+        }
+    }
+}
+
+/// Resolve the output language: an explicit `--lang` value wins if it
+/// parses, then the first of `LC_ALL`/`LC_MESSAGES`/`LANG` that parses,
+/// then `Lang::En`.
+pub fn detect_locale(lang_override: Option<&str>) -> Lang {
+    if let Some(lang) = lang_override.and_then(Lang::parse) {
+        return lang;
+    }
+    for var in ["LC_ALL", "LC_MESSAGES", "LANG"] {
+        if let Some(lang) = env::var(var).ok().as_deref().and_then(Lang::parse) {
+            return lang;
+        }
+    }
+    Lang::En
+}
+
+/// Resolves message ids plus positional arguments into display text for one
+/// language.
+#[derive(Debug, Clone, Copy)]
+pub struct Catalog {
+    lang: Lang,
+}
+
+impl Catalog {
+    pub fn new(lang: Lang) -> Self {
+        Self { lang }
+    }
+
+    /// Resolve `id` through this catalog's language table, substituting
+    /// `{0}`, `{1}`, ... with `args` in order. An id missing from the table
+    /// reflects back verbatim rather than panicking, so a translation table
+    /// that falls behind degrades to a visible id instead of crashing the
+    /// archiver.
+    pub fn get(&self, id: &str, args: &[&str]) -> String {
+        let template = catalog_for(self.lang)
+            .iter()
+            .find(|(key, _)| *key == id)
+            .map(|(_, template)| *template)
+            .unwrap_or(id);
+
+        let mut out = template.to_string();
+        for (index, arg) in args.iter().enumerate() {
+            out = out.replace(&format!("{{{}}}", index), arg);
+        }
+        out
+    }
+}
+
+impl Default for Catalog {
+    fn default() -> Self {
+        Self::new(Lang::En)
+    }
+}
+
+fn catalog_for(lang: Lang) -> &'static [(&'static str, &'static str)] {
+    match lang {
+        Lang::En => EN,
+    }
+}
+
+const EN: &[(&str, &str)] = &[
+    ("stats.header", "\n\u{1F4CA} File Filtering Statistics:"),
+    ("stats.total_found", "   Total files found: {0}"),
+    ("stats.included", "   Files included: {0} \u{1F7E2}"),
+    ("stats.excluded", "   Files excluded: {0} \u{1F534}"),
+    (
+        "stats.by_extension",
+        "     \u{2514}\u{2500} By extension filter: {0}",
+    ),
+    (
+        "stats.by_llm",
+        "     \u{2514}\u{2500} By LLM optimization: {0} \u{1F916}",
+    ),
+    (
+        "stats.llm_excluded_header",
+        "        \u{2728} LLM optimization excluded:",
+    ),
+    (
+        "stats.llm_excluded_training",
+        "        \u{1F4DA} This creates cleaner training data focused on source code",
+    ),
+    (
+        "stats.by_ignore_pattern",
+        "     \u{2514}\u{2500} By ignore patterns: {0}",
+    ),
+    (
+        "stats.by_filter_file",
+        "     \u{2514}\u{2500} By filter files: {0}",
+    ),
+    ("stats.by_git", "     \u{2514}\u{2500} By Git rules: {0}"),
+    ("stats.skipped", "   Files skipped (unreadable): {0}"),
+    ("stats.inclusion_rate", "   Inclusion rate: {0}% \u{1F4C8}"),
+    (
+        "stats.total_size",
+        "   Total size included: {0} bytes \u{1F4BE}",
+    ),
+    (
+        "stats.llm_tip_1",
+        "\n\u{1F4A1} Tip: Use --llm-optimize flag to automatically exclude",
+    ),
+    (
+        "stats.llm_tip_2",
+        "   build artifacts, dependencies, and binary files for",
+    ),
+    (
+        "stats.llm_tip_3",
+        "   cleaner LLM training data preparation.",
+    ),
+    (
+        "header.git_repo",
+        "Git repository detected. Will respect .gitignore rules.",
+    ),
+    (
+        "header.not_git_repo",
+        "Not a git repository or git not available. Will process all files.",
+    ),
+    ("banner.processing", "Processing files..."),
+    (
+        "banner.llm_optimize",
+        "\u{1F916} LLM optimization enabled - excluding build artifacts and dependencies",
+    ),
+    (
+        "banner.ignore_patterns",
+        "\u{1F4DD} Custom ignore patterns: {0}",
+    ),
+    (
+        "banner.include_extensions",
+        "\u{1F3AF} Including only extensions: {0}",
+    ),
+    (
+        "category.build_artifacts",
+        "Build artifacts and compiled files",
+    ),
+    (
+        "category.dependencies",
+        "Dependencies and package manager files",
+    ),
+    ("category.cache_temp", "Cache and temporary files"),
+    ("category.ide_editor", "IDE and editor configuration"),
+    ("category.os_generated", "OS-generated files"),
+    ("category.secrets_config", "Environment and secret files"),
+    ("category.media_files", "Binary media files"),
+    ("category.data_models", "Large data files and ML models"),
+];
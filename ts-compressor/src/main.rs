@@ -1,15 +1,30 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use chrono::Local;
 use clap::{Parser, Subcommand};
+use common::FileFlags;
 use git2::Repository;
+use ignore::{
+    gitignore::{Gitignore, GitignoreBuilder},
+    Match, WalkBuilder,
+};
 use mime_guess::from_path;
+use notify::{RecursiveMode, Watcher};
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
 use std::collections::HashSet;
 use std::fs::{self, File};
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::mpsc::channel;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use swc_core::{
-    common::{errors::Handler, source_map::SourceMap, Globals, Mark, GLOBALS},
+    common::{
+        errors::Handler,
+        source_map::{SourceMap, SourceMapGenConfig},
+        Globals, Mark, GLOBALS,
+    },
     ecma::{
         codegen::{text_writer::JsWriter, Emitter},
         minifier::{
@@ -27,6 +42,25 @@ use tracing_subscriber::{EnvFilter, FmtSubscriber};
 use walkdir::WalkDir;
 
 mod compression;
+mod locale;
+mod normalize;
+
+use locale::{detect_locale, Catalog, Lang};
+use normalize::NormalizeConfig;
+
+/// How long to keep draining incoming filesystem events after the first one
+/// before triggering a rebuild, so a burst of saves collapses into a single
+/// re-archive rather than one rebuild per event.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// How many of a file's leading bytes `FilterContext::sniff_file_is_text`
+/// samples, matching `TextSniffSampleLen`'s default in the compression
+/// pipeline's own content-sniffing config.
+const TEXT_SNIFF_SAMPLE_LEN: usize = 8192;
+
+/// Proportion of non-UTF-8/non-printable bytes a sample can have and still
+/// count as text, matching `NonPrintableRatio`'s default.
+const MAX_NON_PRINTABLE_RATIO: f64 = 0.3;
 
 #[derive(Error, Debug)]
 pub enum ArchiveError {
@@ -54,6 +88,10 @@ enum Commands {
         input_dir: PathBuf,
         /// Output directory for minified JavaScript files
         output_dir: PathBuf,
+        /// Emit a `<name>.js.map` source map next to each minified file,
+        /// with a trailing `//# sourceMappingURL=` comment pointing to it
+        #[arg(long)]
+        sourcemap: bool,
         /// Log level (trace, debug, info, warn, error)
         #[arg(long, default_value = "info")]
         log_level: String,
@@ -74,9 +112,61 @@ enum Commands {
         /// Include only specific file extensions (e.g., rs,js,py)
         #[arg(long)]
         include_extensions: Option<String>,
+        /// Include only files matching a named file type (e.g. `rust`,
+        /// `web`, `cpp`; can be used multiple times). See
+        /// `CodeArchiver::known_file_types` for the full built-in list.
+        #[arg(long = "type")]
+        file_type: Vec<String>,
+        /// Exclude files matching a named file type (e.g. `docs`); can be
+        /// used multiple times.
+        #[arg(long = "type-not")]
+        type_not: Vec<String>,
         /// Show filtering statistics
         #[arg(long)]
         show_filter_stats: bool,
+        /// Normalize the archive text (absolute paths under the target
+        /// folder become `$ROOT/...`, paths under the home directory
+        /// become `$HOME/...`) so the same input tree always produces
+        /// byte-identical output, suitable for golden-file diffing.
+        #[arg(long)]
+        normalize: bool,
+        /// After the initial archive, keep running and re-archive whenever
+        /// a file under the target folder changes, debouncing rapid
+        /// bursts into a single rebuild.
+        #[arg(long)]
+        watch: bool,
+        /// Number of threads to scan and read files with (default: available parallelism)
+        #[arg(long)]
+        jobs: Option<usize>,
+        /// Record an unreadable or permission-denied file as a skipped
+        /// entry in the filtering statistics instead of aborting the run
+        #[arg(long)]
+        keep_going: bool,
+        /// Language for the filtering-statistics report, LLM-optimization
+        /// tip, and in-archive banners (e.g. `en`). Defaults to the
+        /// `LC_ALL`/`LC_MESSAGES`/`LANG` environment locale.
+        #[arg(long)]
+        lang: Option<String>,
+        /// Log level (trace, debug, info, warn, error)
+        #[arg(long, default_value = "info")]
+        log_level: String,
+    },
+    /// Reconstruct a directory tree from a text archive written by `archive`
+    Unarchive {
+        /// The text archive file to reconstruct from
+        archive_file: PathBuf,
+        /// Directory to write reconstructed files into (created if missing)
+        #[arg(short, long)]
+        output_dir: PathBuf,
+        /// Maximum size in bytes for any single reconstructed file
+        #[arg(long, default_value = "104857600")]
+        max_file_size: u64,
+        /// Maximum cumulative size in bytes across all reconstructed files
+        #[arg(long, default_value = "1073741824")]
+        max_total_size: u64,
+        /// Maximum number of files to reconstruct
+        #[arg(long, default_value = "100000")]
+        max_files: usize,
         /// Log level (trace, debug, info, warn, error)
         #[arg(long, default_value = "info")]
         log_level: String,
@@ -109,9 +199,36 @@ enum Commands {
         /// Channel buffer size for parallel processing (default: 100)
         #[arg(long, default_value = "100")]
         channel_buffer_size: usize,
-        /// Memory map threshold in MB for large files (default: 1)
-        #[arg(long, default_value = "1")]
-        memory_map_threshold_mb: usize,
+        /// Memory map threshold in MB for large files (default: ~2/3 of
+        /// this machine's available memory, clamped to a sane range)
+        #[arg(long)]
+        memory_map_threshold_mb: Option<usize>,
+        /// Cut chunks at content-defined (FastCDC) boundaries instead of
+        /// fixed offsets, so an insertion or deletion only perturbs the
+        /// chunks around the edit rather than every chunk after it. The
+        /// min/avg/max targets are derived from `--chunk-size-kb`.
+        #[arg(long)]
+        content_defined_chunking: bool,
+        /// Codec content-defined chunks are compressed with before being
+        /// written to the checkpoint database (e.g. "none", "lz4",
+        /// "zstd/9", "snappy", "deflate"); see `Codec::from_str` for the
+        /// full "name/level" grammar. Fast codecs trade ratio for speed;
+        /// zstd/deflate trade the other way (default: none)
+        #[arg(long, default_value = "none")]
+        chunk_compression_type: String,
+        /// Log level (trace, debug, info, warn, error)
+        #[arg(long, default_value = "info")]
+        log_level: String,
+    },
+    /// Reconstruct a directory tree from an archive written by
+    /// `universal-compress`, verifying restored content against the
+    /// checksums captured at compress time
+    UniversalRestore {
+        /// The archive file to restore from
+        archive_file: PathBuf,
+        /// Directory to write reconstructed files into (created if missing)
+        #[arg(short, long)]
+        output_dir: PathBuf,
         /// Log level (trace, debug, info, warn, error)
         #[arg(long, default_value = "info")]
         log_level: String,
@@ -164,25 +281,77 @@ enum CheckpointAction {
         #[arg(long)]
         checkpoint_id: i64,
     },
-    /// Clean old checkpoints (keep only the latest N)
+    /// Clean old checkpoints under one retention policy: the newest N, a
+    /// total chunk-data size budget, or a maximum age. Exactly one policy
+    /// applies per run, in that priority order when more than one flag is
+    /// given; `--keep-count` alone (the default: 5) if none are.
     Clean {
         /// Database path containing checkpoints (default: compression_patterns.db)
         #[arg(long, default_value = "compression_patterns.db")]
         database_path: PathBuf,
-        /// Number of checkpoints to keep (default: 5)
-        #[arg(long, default_value = "5")]
-        keep_count: usize,
+        /// Keep only the latest N checkpoints (default: 5, used when
+        /// neither of the other two policies is given)
+        #[arg(long)]
+        keep_count: Option<usize>,
+        /// Keep the newest checkpoints whose chunk data totals no more
+        /// than this many megabytes, discarding the rest
+        #[arg(long)]
+        max_total_bytes_mb: Option<u64>,
+        /// Keep only checkpoints created within this many days
+        #[arg(long)]
+        max_age_days: Option<u64>,
     },
 }
 
-fn main() -> Result<()> {
+fn main() {
+    if let Err(err) = run() {
+        let cli_error = cli_error_for(&err);
+        cli_error.report();
+        std::process::exit(cli_error.exit_code);
+    }
+}
+
+/// Map a top-level failure to a [`common::error::CliError`] so `main` can
+/// report it and exit with a stable, meaningful code (invalid input → 2,
+/// path/IO not found → 3, git error → 4, serialization → 5, anything else →
+/// 101) instead of the single undifferentiated nonzero status `?` in `main`
+/// used to produce. The full `{:#}` causal chain anyhow already built up is
+/// kept as the reported message; only the exit code is reclassified.
+fn cli_error_for(err: &anyhow::Error) -> common::error::CliError {
+    let exit_code = if let Some(archive_err) = err.downcast_ref::<ArchiveError>() {
+        match archive_err {
+            ArchiveError::Path { .. } => 3,
+            ArchiveError::Io(e) => common::error::exit_code_for_io_kind(e.kind()),
+            ArchiveError::Git(_) => 4,
+        }
+    } else if let Some(common_err) = err.downcast_ref::<common::error::Error>() {
+        common::error::exit_code_for(common_err)
+    } else if let Some(io_err) = err.downcast_ref::<std::io::Error>() {
+        common::error::exit_code_for_io_kind(io_err.kind())
+    } else if err.downcast_ref::<git2::Error>().is_some() {
+        4
+    } else if err.downcast_ref::<clap::Error>().is_some() {
+        2
+    } else {
+        101
+    };
+
+    common::error::CliError {
+        error: Some(common::error::Error::custom(format!("{:#}", err))),
+        exit_code,
+    }
+}
+
+fn run() -> Result<()> {
     let cli = Cli::parse();
 
     // Extract log level from command and initialize structured logging
     let log_level = match &cli.command {
         Commands::Compress { log_level, .. } => log_level,
         Commands::Archive { log_level, .. } => log_level,
+        Commands::Unarchive { log_level, .. } => log_level,
         Commands::UniversalCompress { log_level, .. } => log_level,
+        Commands::UniversalRestore { log_level, .. } => log_level,
         Commands::Resume { log_level, .. } => log_level,
         Commands::Checkpoint { .. } => "info", // Default for checkpoint commands
     };
@@ -195,10 +364,11 @@ fn main() -> Result<()> {
         Commands::Compress {
             input_dir,
             output_dir,
+            sourcemap,
             ..
         } => {
             info!("Starting TypeScript compression");
-            compress_typescript(input_dir, output_dir)
+            compress_typescript(input_dir, output_dir, sourcemap)
         }
         Commands::Archive {
             target_folder,
@@ -206,7 +376,14 @@ fn main() -> Result<()> {
             llm_optimize,
             ignore_pattern,
             include_extensions,
+            file_type,
+            type_not,
             show_filter_stats,
+            normalize,
+            watch,
+            jobs,
+            keep_going,
+            lang,
             ..
         } => {
             info!("Starting code archiving with intelligent filtering");
@@ -216,7 +393,31 @@ fn main() -> Result<()> {
                 llm_optimize,
                 ignore_pattern,
                 include_extensions,
+                file_type,
+                type_not,
                 show_filter_stats,
+                normalize,
+                watch,
+                jobs,
+                keep_going,
+                lang,
+            )
+        }
+        Commands::Unarchive {
+            archive_file,
+            output_dir,
+            max_file_size,
+            max_total_size,
+            max_files,
+            ..
+        } => {
+            info!("Starting archive reconstruction");
+            unarchive_bundle(
+                archive_file,
+                output_dir,
+                max_file_size,
+                max_total_size,
+                max_files,
             )
         }
         Commands::UniversalCompress {
@@ -230,6 +431,8 @@ fn main() -> Result<()> {
             chunk_size_kb,
             channel_buffer_size,
             memory_map_threshold_mb,
+            content_defined_chunking,
+            chunk_compression_type,
             ..
         } => {
             info!("Starting universal compression with enhanced configuration");
@@ -244,8 +447,18 @@ fn main() -> Result<()> {
                 chunk_size_kb,
                 channel_buffer_size,
                 memory_map_threshold_mb,
+                content_defined_chunking,
+                chunk_compression_type,
             )
         }
+        Commands::UniversalRestore {
+            archive_file,
+            output_dir,
+            ..
+        } => {
+            info!("Starting universal archive restoration");
+            universal_restore(archive_file, output_dir)
+        }
         Commands::Resume {
             database_path,
             output_dir,
@@ -298,13 +511,15 @@ fn init_tracing(log_level: &str) -> Result<()> {
     name = "compress_typescript",
     fields(
         input_dir = %input_dir.display(),
-        output_dir = %output_dir.display()
+        output_dir = %output_dir.display(),
+        sourcemap = sourcemap
     )
 )]
-fn compress_typescript(input_dir: PathBuf, output_dir: PathBuf) -> Result<()> {
+fn compress_typescript(input_dir: PathBuf, output_dir: PathBuf, sourcemap: bool) -> Result<()> {
     info!(
         input_dir = %input_dir.display(),
         output_dir = %output_dir.display(),
+        sourcemap = sourcemap,
         "Starting TypeScript compression"
     );
 
@@ -325,10 +540,20 @@ fn compress_typescript(input_dir: PathBuf, output_dir: PathBuf) -> Result<()> {
                 "Processing TypeScript file"
             );
 
-            let minified = minify_file(entry.path())?;
-            let out_path = output_dir
-                .join(entry.path().file_name().unwrap())
-                .with_extension("js");
+            let (mut minified, source_map) = minify_file(entry.path(), sourcemap)?;
+            let out_name = entry.path().file_name().unwrap();
+            let out_path = output_dir.join(out_name).with_extension("js");
+
+            if let Some(source_map_json) = source_map {
+                let map_file_name = format!(
+                    "{}.js.map",
+                    out_path.file_stem().unwrap().to_string_lossy()
+                );
+                let map_path = output_dir.join(&map_file_name);
+                fs::write(&map_path, source_map_json)?;
+                minified.push_str(&format!("\n//# sourceMappingURL={}\n", map_file_name));
+            }
+
             let mut out_file = File::create(&out_path)?;
             out_file.write_all(minified.as_bytes())?;
 
@@ -368,7 +593,81 @@ fn archive_code_folder(
     llm_optimize: bool,
     ignore_patterns: Vec<String>,
     include_extensions: Option<String>,
+    include_types: Vec<String>,
+    exclude_types: Vec<String>,
     show_filter_stats: bool,
+    normalize: bool,
+    watch: bool,
+    jobs: Option<usize>,
+    keep_going: bool,
+    lang: Option<String>,
+) -> Result<()> {
+    // Resolve the target folder to an absolute path up front, so the
+    // watcher below keeps watching the intended root for the lifetime of
+    // the process even if the working directory changes or the folder is
+    // deleted and recreated.
+    let target_folder = target_folder.canonicalize().unwrap_or(target_folder);
+
+    // Root any relative ignore pattern at the target folder rather than
+    // the caller's current working directory, so the same pattern matches
+    // the same files no matter where `ts-compressor archive` is invoked
+    // from -- the same normalization `code-archiver` applies to its own
+    // include/exclude patterns via `FileFlags`.
+    let ignore_patterns = FileFlags::new(Vec::new(), ignore_patterns)
+        .with_absolute_paths(&target_folder)
+        .ignore;
+
+    run_archive_pass(
+        &target_folder,
+        &output_dir,
+        llm_optimize,
+        &ignore_patterns,
+        &include_extensions,
+        &include_types,
+        &exclude_types,
+        show_filter_stats,
+        normalize,
+        jobs,
+        keep_going,
+        lang.as_deref(),
+    )?;
+
+    if watch {
+        watch_and_rearchive_folder(
+            &target_folder,
+            &output_dir,
+            llm_optimize,
+            &ignore_patterns,
+            &include_extensions,
+            &include_types,
+            &exclude_types,
+            show_filter_stats,
+            normalize,
+            jobs,
+            keep_going,
+            lang.as_deref(),
+        );
+    }
+
+    Ok(())
+}
+
+/// Run one archive pass over `target_folder`, reusing the same
+/// ignore/extension filtering for both the one-shot run and every
+/// `--watch` rebuild.
+fn run_archive_pass(
+    target_folder: &Path,
+    output_dir: &Option<PathBuf>,
+    llm_optimize: bool,
+    ignore_patterns: &[String],
+    include_extensions: &Option<String>,
+    include_types: &[String],
+    exclude_types: &[String],
+    show_filter_stats: bool,
+    normalize: bool,
+    jobs: Option<usize>,
+    keep_going: bool,
+    lang: Option<&str>,
 ) -> Result<()> {
     info!(
         target_folder = %target_folder.display(),
@@ -377,33 +676,310 @@ fn archive_code_folder(
     );
 
     debug!("Creating code archiver with filtering options");
-    let mut archiver = CodeArchiver::new(target_folder, output_dir)?;
+    let mut archiver = CodeArchiver::new(target_folder.to_path_buf(), output_dir.clone())?;
+    archiver.set_locale(detect_locale(lang));
 
     // Configure filtering options
     if llm_optimize {
         archiver.enable_llm_optimization();
-        info!("ü§ñ LLM optimization enabled - filtering build artifacts and dependencies");
+        info!("🤖 LLM optimization enabled - filtering build artifacts and dependencies");
     }
 
     if !ignore_patterns.is_empty() {
-        archiver.add_ignore_patterns(ignore_patterns);
-        info!("üìù Custom ignore patterns added");
+        archiver.add_ignore_patterns(ignore_patterns.to_vec());
+        info!("📝 Custom ignore patterns added");
     }
 
-    if let Some(extensions) = include_extensions {
+    if let Some(extensions) = include_extensions.clone() {
         archiver.set_include_extensions(extensions);
-        info!("üéØ File extension filtering enabled");
+        info!("🎯 File extension filtering enabled");
+    }
+
+    if !include_types.is_empty() {
+        archiver.set_include_types(include_types.to_vec());
+        info!("🏷️  File type filtering enabled");
+    }
+
+    if !exclude_types.is_empty() {
+        archiver.set_exclude_types(exclude_types.to_vec());
+        info!("🏷️  File type exclusion enabled");
     }
 
     if show_filter_stats {
         archiver.enable_filter_statistics();
-        info!("üìä Filter statistics enabled");
+        info!("📊 Filter statistics enabled");
+    }
+
+    if normalize {
+        archiver.enable_normalization();
+        info!("🧮 Output normalization enabled");
+    }
+
+    archiver.set_jobs(jobs);
+    if keep_going {
+        archiver.enable_keep_going();
+        info!("⏭️  Keep-going enabled - unreadable files will be skipped, not fatal");
     }
 
     debug!("Creating archive file");
     archiver.create_archive()
 }
 
+/// Watch `target_folder` (already resolved to an absolute path) for
+/// changes and re-run `run_archive_pass` with the same filtering options
+/// on every debounced burst of filesystem events. Recursive watching means
+/// a deleted and recreated subdirectory under `target_folder` is picked
+/// back up without restarting the process.
+fn watch_and_rearchive_folder(
+    target_folder: &Path,
+    output_dir: &Option<PathBuf>,
+    llm_optimize: bool,
+    ignore_patterns: &[String],
+    include_extensions: &Option<String>,
+    include_types: &[String],
+    exclude_types: &[String],
+    show_filter_stats: bool,
+    normalize: bool,
+    jobs: Option<usize>,
+    keep_going: bool,
+    lang: Option<&str>,
+) {
+    let (tx, rx) = channel();
+    let mut watcher = match notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res {
+            let _ = tx.send(event);
+        }
+    }) {
+        Ok(w) => w,
+        Err(e) => {
+            error!("Failed to start file watcher: {}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = watcher.watch(target_folder, RecursiveMode::Recursive) {
+        error!("Failed to watch '{}': {}", target_folder.display(), e);
+        return;
+    }
+
+    info!(target_folder = %target_folder.display(), "Watching for changes");
+    println!(
+        "Watching '{}' for changes (Ctrl+C to stop)...",
+        target_folder.display()
+    );
+
+    while rx.recv().is_ok() {
+        // Drain the rest of this burst so rapid successive events collapse
+        // into a single rebuild.
+        while rx.recv_timeout(WATCH_DEBOUNCE).is_ok() {}
+
+        println!("\nChange detected, rebuilding archive...");
+        if let Err(e) = run_archive_pass(
+            target_folder,
+            output_dir,
+            llm_optimize,
+            ignore_patterns,
+            include_extensions,
+            include_types,
+            exclude_types,
+            show_filter_stats,
+            normalize,
+            jobs,
+            keep_going,
+            lang,
+        ) {
+            error!("Archive rebuild failed: {}", e);
+        }
+    }
+}
+
+/// Reconstruct a directory tree from a text archive written by
+/// `CodeArchiver::render_entry`: each entry's `Absolute path:` line is
+/// re-rooted under `output_dir` rather than written back to its original
+/// absolute location, rejecting any entry that still escapes `output_dir`
+/// after that normalization and enforcing the per-file/total/count ceilings
+/// before anything is written, the same way `CodeArchiver::extract_tar`
+/// guards tar restores in the `code-archiver` crate.
+#[instrument(
+    name = "unarchive_bundle",
+    fields(
+        archive_file = %archive_file.display(),
+        output_dir = %output_dir.display()
+    )
+)]
+fn unarchive_bundle(
+    archive_file: PathBuf,
+    output_dir: PathBuf,
+    max_file_size: u64,
+    max_total_size: u64,
+    max_files: usize,
+) -> Result<()> {
+    let content = fs::read_to_string(&archive_file).context("Failed to read archive file")?;
+
+    fs::create_dir_all(&output_dir).context("Failed to create output directory")?;
+    let canonical_root = output_dir
+        .canonicalize()
+        .context("Failed to canonicalize output directory")?;
+
+    enum State {
+        Idle,
+        AfterPath(String),
+        InText(String, String),
+    }
+
+    let mut state = State::Idle;
+    let mut total_size: u64 = 0;
+    let mut file_count: usize = 0;
+
+    for line in content.lines() {
+        state = match state {
+            State::Idle => match line.strip_prefix("Absolute path: ") {
+                Some(path) => State::AfterPath(path.to_string()),
+                None => State::Idle,
+            },
+            State::AfterPath(path) => {
+                if line == "<text starts>" {
+                    State::InText(path, String::new())
+                } else if line == "Binary file, content not included." {
+                    // The archive format never embeds binary content, so
+                    // there's nothing to restore for this entry beyond
+                    // recreating an empty placeholder at its path.
+                    restore_entry(
+                        &canonical_root,
+                        &path,
+                        &[],
+                        &mut total_size,
+                        &mut file_count,
+                        max_file_size,
+                        max_total_size,
+                        max_files,
+                    )?;
+                    State::Idle
+                } else {
+                    warn!(path = %path, "Malformed archive entry header, skipping");
+                    State::Idle
+                }
+            }
+            State::InText(path, mut buf) => {
+                if line == "<text ends>" {
+                    restore_entry(
+                        &canonical_root,
+                        &path,
+                        buf.as_bytes(),
+                        &mut total_size,
+                        &mut file_count,
+                        max_file_size,
+                        max_total_size,
+                        max_files,
+                    )?;
+                    State::Idle
+                } else {
+                    buf.push_str(line);
+                    buf.push('\n');
+                    State::InText(path, buf)
+                }
+            }
+        };
+    }
+
+    info!(
+        files_restored = file_count,
+        total_size = total_size,
+        "Archive reconstruction completed"
+    );
+    println!(
+        "Restored {} files ({} bytes) to {}",
+        file_count,
+        total_size,
+        output_dir.display()
+    );
+
+    Ok(())
+}
+
+/// Validate and write one archive entry's reconstructed `content` under
+/// `canonical_root`, aborting the instant the per-file, cumulative, or
+/// file-count ceiling would be exceeded rather than after writing.
+fn restore_entry(
+    canonical_root: &Path,
+    absolute_path_str: &str,
+    content: &[u8],
+    total_size: &mut u64,
+    file_count: &mut usize,
+    max_file_size: u64,
+    max_total_size: u64,
+    max_files: usize,
+) -> Result<()> {
+    if *file_count >= max_files {
+        bail!(
+            "Archive exceeds the maximum file count ({max_files}); aborting before restoring '{absolute_path_str}'"
+        );
+    }
+
+    let entry_size = content.len() as u64;
+    if entry_size > max_file_size {
+        bail!(
+            "Entry '{absolute_path_str}' is {entry_size} bytes, exceeding the per-file limit of {max_file_size} bytes"
+        );
+    }
+
+    let projected_total = *total_size + entry_size;
+    if projected_total > max_total_size {
+        bail!(
+            "Restoring '{absolute_path_str}' would bring the cumulative size to {projected_total} bytes, exceeding the limit of {max_total_size} bytes"
+        );
+    }
+
+    let dest_path = validate_restore_path(canonical_root, absolute_path_str)?;
+    fs::write(&dest_path, content)?;
+
+    *total_size = projected_total;
+    *file_count += 1;
+    Ok(())
+}
+
+/// Re-root the archive's recorded `Absolute path:` string under
+/// `canonical_root`: root/prefix components are dropped rather than
+/// followed, and a `..`/`.` surviving among the remaining components is
+/// treated as a path-traversal attempt and rejected outright. Once joined,
+/// the destination's parent is created and canonicalized to confirm it
+/// still resolves inside `canonical_root`, catching a symlink planted
+/// along the way that a crafted entry order might otherwise walk through.
+fn validate_restore_path(canonical_root: &Path, absolute_path_str: &str) -> Result<PathBuf> {
+    let mut rel_path = PathBuf::new();
+    for component in Path::new(absolute_path_str).components() {
+        match component {
+            std::path::Component::RootDir | std::path::Component::Prefix(_) => {}
+            std::path::Component::ParentDir | std::path::Component::CurDir => {
+                return Err(ArchiveError::Path {
+                    message: format!(
+                        "Archive entry path escapes the output root: {absolute_path_str}"
+                    ),
+                }
+                .into());
+            }
+            std::path::Component::Normal(part) => rel_path.push(part),
+        }
+    }
+
+    let dest_path = canonical_root.join(&rel_path);
+
+    if let Some(parent) = dest_path.parent() {
+        fs::create_dir_all(parent)?;
+        let canonical_parent = parent.canonicalize()?;
+        if !canonical_parent.starts_with(canonical_root) {
+            return Err(ArchiveError::Path {
+                message: format!(
+                    "Archive entry path resolves outside the output root: {absolute_path_str}"
+                ),
+            }
+            .into());
+        }
+    }
+
+    Ok(dest_path)
+}
+
 #[instrument(
     name = "universal_compress",
     fields(
@@ -556,7 +1132,16 @@ fn generate_output_file(
         .unwrap_or("unknown");
 
     let output_path = output_dir.join(format!("{}_{}.txt", folder_name, timestamp));
-    let mut file = File::create(&output_path)?;
+    let temp_output_path = output_dir.join(format!("{}_{}.txt.tmp", folder_name, timestamp));
+
+    // `CleanupGuard` was armed back in `prepare_replacement`; registering the
+    // temp path here means a Ctrl+C during the writes below deletes the
+    // half-written file instead of leaving it next to (or in place of) a
+    // prior, complete run's output.
+    let cleanup_guard = compression::CleanupGuard::new();
+    cleanup_guard.register_temp_path(temp_output_path.clone());
+
+    let mut file = File::create(&temp_output_path)?;
 
     // Write header
     writeln!(file, "# Universal Code Compression Output")?;
@@ -578,7 +1163,14 @@ fn generate_output_file(
     write_directory_manifest(&mut file, target_folder)?;
 
     // Write compressed content
-    write_compressed_content(&mut file, compressor)?;
+    write_compressed_content(&mut file, result)?;
+    drop(file);
+
+    // Move the finished file into place and disarm cleanup - from here on
+    // there's nothing left for an interrupt to leave half-written.
+    cleanup_guard
+        .commit(&temp_output_path, &output_path)
+        .context("Failed to finalize output file")?;
 
     Ok(output_path)
 }
@@ -641,6 +1233,10 @@ fn write_embedded_dictionary(
     if dictionary_entries.is_empty() {
         writeln!(file, "# No dictionary entries found")?;
     } else {
+        let dictionary_id = compression::types::Dictionary::from_entries(dictionary_entries.clone())
+            .id()
+            .to_string();
+        writeln!(file, "# dictionary_id={}", dictionary_id)?;
         for (pattern, token) in dictionary_entries {
             writeln!(file, "DICT:{}={}", pattern, token)?;
         }
@@ -672,49 +1268,66 @@ fn write_directory_manifest(file: &mut File, target_folder: &PathBuf) -> Result<
     Ok(())
 }
 
+/// Write the `## Compressed Content` section from `result.entries` - the
+/// actual output of the `compress` pipeline, final-stage codec included -
+/// so `compression::restore::restore_archive` can read this same section
+/// back and invert it with `FileEntry::decompress`. Each entry also gets a
+/// `Checksum:` line (the original content's SHA-256) that restore verifies
+/// the restored bytes against.
 fn write_compressed_content(
     file: &mut File,
-    compressor: &compression::UniversalCompressor<compression::compressor::ReadyState>,
+    result: &compression::types::CompressionResult,
 ) -> Result<()> {
     writeln!(file, "## Compressed Content")?;
 
-    // Get compressed files from the compressor
-    match compressor.get_compressed_files() {
-        Ok(files) => {
-            if files.is_empty() {
-                writeln!(file, "# No files found to compress")?;
-            } else {
-                for file_entry in files {
-                    writeln!(file, "### File: {}", file_entry.relative_path.display())?;
-                    writeln!(
-                        file,
-                        "Original size: {} bytes",
-                        file_entry.original_size.bytes()
-                    )?;
+    if result.entries.is_empty() {
+        writeln!(file, "# No files found to compress")?;
+    } else {
+        for file_entry in &result.entries {
+            writeln!(file, "### File: {}", file_entry.relative_path.display())?;
+            match &file_entry.header {
+                compression::types::ContentHeader::Plain => {
+                    writeln!(file, "Header: Plain")?;
+                }
+                compression::types::ContentHeader::Compressed { dictionary_id } => {
+                    writeln!(file, "Header: Compressed dictionary_id={}", dictionary_id)?;
+                }
+                compression::types::ContentHeader::FsstCompressed { table_id } => {
+                    writeln!(file, "Header: FsstCompressed table_id={}", table_id)?;
+                }
+            }
+            if let Some(method) = file_entry.method {
+                writeln!(file, "Method: {}", method)?;
+            }
+            writeln!(
+                file,
+                "Original size: {} bytes",
+                file_entry.original_size.bytes()
+            )?;
 
-                    if let Some(compressed_size) = file_entry.compressed_size {
-                        writeln!(file, "Compressed size: {} bytes", compressed_size.bytes())?;
-                        let ratio = if file_entry.original_size.bytes() > 0 {
-                            (compressed_size.bytes() as f64)
-                                / (file_entry.original_size.bytes() as f64)
-                        } else {
-                            0.0
-                        };
-                        writeln!(file, "Compression ratio: {:.2}%", (1.0 - ratio) * 100.0)?;
-                    }
+            if let Some(compressed_size) = file_entry.compressed_size {
+                writeln!(file, "Compressed size: {} bytes", compressed_size.bytes())?;
+                let ratio = if file_entry.original_size.bytes() > 0 {
+                    (compressed_size.bytes() as f64) / (file_entry.original_size.bytes() as f64)
+                } else {
+                    0.0
+                };
+                writeln!(file, "Compression ratio: {:.2}%", (1.0 - ratio) * 100.0)?;
+            }
 
-                    writeln!(file, "Content:")?;
-                    if let Some(compressed_content) = &file_entry.compressed_content {
-                        writeln!(file, "{}", compressed_content)?;
-                    } else {
-                        writeln!(file, "{}", file_entry.original_content)?;
-                    }
-                    writeln!(file, "---")?;
-                }
+            writeln!(
+                file,
+                "Checksum: {:x}",
+                Sha256::digest(file_entry.original_content.as_bytes())
+            )?;
+
+            writeln!(file, "Content:")?;
+            if let Some(compressed_content) = &file_entry.compressed_content {
+                writeln!(file, "{}", compressed_content)?;
+            } else {
+                writeln!(file, "{}", file_entry.original_content)?;
             }
-        }
-        Err(e) => {
-            writeln!(file, "# Error retrieving compressed files: {}", e)?;
+            writeln!(file, "---")?;
         }
     }
 
@@ -722,8 +1335,23 @@ fn write_compressed_content(
     Ok(())
 }
 
+/// A `SourceMapGenConfig` that inlines the original TypeScript source into
+/// the generated map's `sourcesContent`, so a debugger can show the real
+/// `.ts` source without needing the file on disk alongside the archive.
+struct MinifierSourceMapConfig;
+
+impl SourceMapGenConfig for MinifierSourceMapConfig {
+    fn file_name_to_source(&self, f: &swc_core::common::FileName) -> String {
+        f.to_string()
+    }
+
+    fn inline_sources_content(&self, _f: &swc_core::common::FileName) -> bool {
+        true
+    }
+}
+
 // Original TypeScript minification functionality preserved
-fn minify_file(path: &Path) -> Result<String> {
+fn minify_file(path: &Path, emit_sourcemap: bool) -> Result<(String, Option<String>)> {
     let cm = std::rc::Rc::new(SourceMap::default());
     let _handler = Handler::with_emitter_writer(Box::new(std::io::stderr()), Some(cm.clone()));
 
@@ -768,9 +1396,16 @@ fn minify_file(path: &Path) -> Result<String> {
             },
         );
 
-        // Serialize to code
+        // Serialize to code, optionally collecting position mappings for a
+        // source map alongside the emitted text
         let mut buf = Vec::new();
-        let writer = JsWriter::new(cm.clone(), "\n", &mut buf, None);
+        let mut src_map_buf = Vec::new();
+        let writer = JsWriter::new(
+            cm.clone(),
+            "\n",
+            &mut buf,
+            emit_sourcemap.then_some(&mut src_map_buf),
+        );
         let mut emitter = Emitter {
             cfg: Default::default(),
             cm: cm.clone(),
@@ -781,7 +1416,21 @@ fn minify_file(path: &Path) -> Result<String> {
             .emit_program(&program)
             .context("Failed to emit code")?;
 
-        Ok(String::from_utf8(buf).context("Invalid UTF-8")?)
+        let code = String::from_utf8(buf).context("Invalid UTF-8")?;
+
+        let source_map_json = if emit_sourcemap {
+            let source_map =
+                cm.build_source_map_with_config(&src_map_buf, None, MinifierSourceMapConfig);
+            let mut map_buf = Vec::new();
+            source_map
+                .to_writer(&mut map_buf)
+                .context("Failed to serialize source map")?;
+            Some(String::from_utf8(map_buf).context("Invalid UTF-8 in source map")?)
+        } else {
+            None
+        };
+
+        Ok((code, source_map_json))
     })
 }
 
@@ -792,10 +1441,101 @@ pub struct CodeArchiver {
     git_repo: Option<Repository>,
     is_git_repo: bool,
     llm_optimize: bool,
+    /// Which built-in LLM-optimization preset `build_llm_ignore_matcher`
+    /// compiles, set via `set_llm_optimization_level`. `None` (the default
+    /// once `llm_optimize` is on) falls back to the full, comprehensive
+    /// pattern list for backward compatibility with `enable_llm_optimization`.
+    llm_level: Option<LlmLevel>,
     ignore_patterns: Vec<String>,
+    /// Gitignore-syntax filter files added via `add_filter_file`, read and
+    /// compiled into a `FilterFileMatcher` fresh each scan (see
+    /// `build_filter_file_matcher`) so edits to the file are picked up by
+    /// the next archive pass without restarting.
+    filter_files: Vec<PathBuf>,
     include_extensions: Option<Vec<String>>,
     show_filter_stats: bool,
+    normalize: bool,
+    /// Thread count for the parallel scan/read pass in `write_file_contents`,
+    /// `None` meaning "let rayon pick the available parallelism".
+    jobs: Option<usize>,
+    keep_going: bool,
     filter_stats: FilterStatistics,
+    output_format: ArchiveFormat,
+    /// When set, each text file's content is minified (see
+    /// `FilterContext::minify_content`) before it's written into the
+    /// concatenated-text archive, trading exact-source fidelity for fewer
+    /// tokens in the emitted bundle.
+    minify_sources: bool,
+    /// Type names passed to `set_include_types` (`--type`): a file must
+    /// match at least one of these (resolved via `resolve_type_patterns`)
+    /// to survive, on top of any `include_extensions` gate.
+    select_types: Vec<String>,
+    /// Type names passed to `set_exclude_types` (`--type-not`), folded
+    /// into `build_user_ignore_matcher` as plain exclude patterns.
+    negate_types: Vec<String>,
+    /// Custom type registrations added via `register_type`, checked ahead
+    /// of `known_file_types` so a custom mapping can override a built-in
+    /// of the same name.
+    custom_types: Vec<(String, Vec<String>)>,
+    /// Resolves every user-facing message in `display_filter_stats`,
+    /// `write_header`, and `write_file_contents` (see `locale`), set via
+    /// `set_locale`. Defaults to `Lang::En`.
+    catalog: Catalog,
+}
+
+/// Container format `CodeArchiver::create_archive` writes, set via
+/// `set_output_format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArchiveFormat {
+    /// A single `.txt` file of concatenated, rendered file contents (the
+    /// original and still default format).
+    #[default]
+    ConcatenatedText,
+    /// A plain, uncompressed USTAR tar archive preserving each included
+    /// file's relative path.
+    Tar,
+    /// Same as `Tar`, gzip-compressed.
+    TarGz,
+    /// Same as `Tar`, lz4-frame-compressed.
+    TarLz4,
+    /// Same as `Tar`, zstd-compressed.
+    TarZst,
+}
+
+/// Granularity of the built-in LLM-optimization preset, set via
+/// `set_llm_optimization_level`. Each level is cumulative over the one
+/// before it (see `get_llm_optimization_levels`); `Comprehensive` is every
+/// pattern `get_llm_ignore_patterns` returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LlmLevel {
+    Basic,
+    Standard,
+    Aggressive,
+    Comprehensive,
+}
+
+impl LlmLevel {
+    /// Parse a `--llm-optimize-level`-style string into a level, matching
+    /// the string keys `get_llm_optimization_levels` uses.
+    fn parse(level: &str) -> Option<Self> {
+        match level {
+            "basic" => Some(Self::Basic),
+            "standard" => Some(Self::Standard),
+            "aggressive" => Some(Self::Aggressive),
+            "comprehensive" => Some(Self::Comprehensive),
+            _ => None,
+        }
+    }
+
+    /// The `get_llm_optimization_levels` key for this level.
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Basic => "basic",
+            Self::Standard => "standard",
+            Self::Aggressive => "aggressive",
+            Self::Comprehensive => "comprehensive",
+        }
+    }
 }
 
 /// Statistics for file filtering operations
@@ -807,9 +1547,587 @@ pub struct FilterStatistics {
     pub excluded_by_extension: usize,
     pub excluded_by_ignore_pattern: usize,
     pub excluded_by_llm_optimization: usize,
+    /// Per-category breakdown of `excluded_by_llm_optimization`, keyed by
+    /// `get_llm_patterns_by_category` category name (e.g. `"build_artifacts"`),
+    /// so `get_llm_optimization_stats` can report real counts instead of a
+    /// single total.
+    pub llm_category_counts: std::collections::HashMap<String, usize>,
+    pub excluded_by_filter_file: usize,
     pub excluded_by_git: usize,
     pub total_size_included: usize,
     pub total_size_excluded: usize,
+    /// Files that couldn't be read. Covers two distinct, independently
+    /// incremented cases: a file that was walked and included but whose
+    /// content couldn't be read (e.g. permission denied) -- always
+    /// non-fatal, rendered as a placeholder line, regardless of
+    /// `--keep-going` -- and a directory-walk entry `ignore` itself
+    /// couldn't read, which is only skipped rather than aborting the run
+    /// when `--keep-going` is set.
+    pub files_skipped: usize,
+    /// Bytes shaved off included files' content by `minify_sources`
+    /// (`total_size_included` already reflects the post-minification size;
+    /// this is how much smaller that made it).
+    pub bytes_saved_by_minification: usize,
+}
+
+impl FilterStatistics {
+    /// Fold another batch's counters into this one, for reassembling the
+    /// totals a parallel scan computed per-thread.
+    fn merge(&mut self, other: &FilterStatistics) {
+        self.total_files_found += other.total_files_found;
+        self.files_included += other.files_included;
+        self.files_excluded += other.files_excluded;
+        self.excluded_by_extension += other.excluded_by_extension;
+        self.excluded_by_ignore_pattern += other.excluded_by_ignore_pattern;
+        self.excluded_by_llm_optimization += other.excluded_by_llm_optimization;
+        for (category, count) in &other.llm_category_counts {
+            *self.llm_category_counts.entry(category.clone()).or_insert(0) += count;
+        }
+        self.excluded_by_filter_file += other.excluded_by_filter_file;
+        self.excluded_by_git += other.excluded_by_git;
+        self.total_size_included += other.total_size_included;
+        self.total_size_excluded += other.total_size_excluded;
+        self.files_skipped += other.files_skipped;
+        self.bytes_saved_by_minification += other.bytes_saved_by_minification;
+    }
+}
+
+/// Why `FilterContext::classify_exclusion` decided to drop a file, so the
+/// caller can credit the right `FilterStatistics` counter.
+#[derive(Debug, Clone, Copy)]
+enum ExclusionReason {
+    Extension,
+    Llm,
+    IgnorePattern,
+    FilterFile,
+}
+
+/// A composable predicate over paths, so filter-file matching can be built
+/// out of small, independently testable pieces (`IncludeMatcher`/
+/// `ExcludeMatcher`) combined with boolean-set combinators (`Union`/
+/// `Difference`) instead of one monolithic check.
+trait PathMatcher: Send + Sync {
+    fn is_match(&self, path: &Path, is_dir: bool) -> bool;
+}
+
+/// Matches a path that one of the wrapped patterns names -- built from a
+/// filter file's `!`-prefixed (re-inclusion) lines with the `!` stripped,
+/// so a plain `Gitignore::matched` hit means "this path is included".
+struct IncludeMatcher(Gitignore);
+
+impl PathMatcher for IncludeMatcher {
+    fn is_match(&self, path: &Path, is_dir: bool) -> bool {
+        matches!(self.0.matched(path, is_dir), Match::Ignore(_))
+    }
+}
+
+/// Matches a path that one of the wrapped patterns names -- built from a
+/// filter file's plain (non-`!`) lines.
+struct ExcludeMatcher(Gitignore);
+
+impl PathMatcher for ExcludeMatcher {
+    fn is_match(&self, path: &Path, is_dir: bool) -> bool {
+        matches!(self.0.matched(path, is_dir), Match::Ignore(_))
+    }
+}
+
+/// Matches a path that any wrapped matcher matches. Members are `Arc`
+/// rather than `Box` so the same union (e.g. `FilterFileMatcher::includes`)
+/// can be shared as both a standalone check and the `subtract` side of a
+/// `Difference`, without cloning the underlying `Gitignore` matchers.
+struct Union(Vec<Arc<dyn PathMatcher>>);
+
+impl PathMatcher for Union {
+    fn is_match(&self, path: &Path, is_dir: bool) -> bool {
+        self.0.iter().any(|matcher| matcher.is_match(path, is_dir))
+    }
+}
+
+/// Matches a path `base` matches and `subtract` doesn't -- a set difference.
+struct Difference {
+    base: Arc<dyn PathMatcher>,
+    subtract: Arc<dyn PathMatcher>,
+}
+
+impl PathMatcher for Difference {
+    fn is_match(&self, path: &Path, is_dir: bool) -> bool {
+        self.base.is_match(path, is_dir) && !self.subtract.is_match(path, is_dir)
+    }
+}
+
+/// The effective include/exclude set contributed by every filter file
+/// added via `CodeArchiver::add_filter_file`, modeled as `(includes ∪
+/// llm_defaults) − excludes`: `includes` can rescue a path the LLM-
+/// optimization preset would otherwise drop (checked directly in
+/// `ignore_verdict`, ahead of the LLM preset), while `excludes` drops a
+/// path unless `includes` also names it. Built fresh per scan by
+/// `CodeArchiver::build_filter_file_matcher`.
+struct FilterFileMatcher {
+    includes: Arc<dyn PathMatcher>,
+    /// `excludes − includes`, i.e. excluded unless also rescued.
+    effective_exclude: Difference,
+}
+
+impl FilterFileMatcher {
+    /// An empty matcher for when no filter files were added, so callers
+    /// don't need to special-case `Option<FilterFileMatcher>`.
+    fn empty() -> Self {
+        Self::from_union(Union(Vec::new()), Union(Vec::new()))
+    }
+
+    /// Combine every filter file's per-file `IncludeMatcher`s and
+    /// `ExcludeMatcher`s (already unioned by the caller) into the
+    /// `excludes − includes` difference.
+    fn from_union(includes: Union, excludes: Union) -> Self {
+        let includes: Arc<dyn PathMatcher> = Arc::new(includes);
+        Self {
+            includes: Arc::clone(&includes),
+            effective_exclude: Difference {
+                base: Arc::new(excludes),
+                subtract: includes,
+            },
+        }
+    }
+
+    fn is_rescued(&self, path: &Path, is_dir: bool) -> bool {
+        self.includes.is_match(path, is_dir)
+    }
+
+    fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        self.effective_exclude.is_match(path, is_dir)
+    }
+}
+
+/// Resolves `.gitattributes` rules (`export-ignore`, `binary`/`-text`,
+/// `text`/`text=auto`) for paths under the tree it was built from, built
+/// fresh each scan by `CodeArchiver::build_git_attributes`. Every
+/// `.gitattributes` file found while walking the tree contributes its own
+/// layer, checked deepest directory first so a nested file's rule for a
+/// given attribute wins over an ancestor's -- the same stacking precedence
+/// nested `.gitignore` files get from `ignore::WalkBuilder`.
+struct GitAttributes {
+    layers: Vec<AttributesLayer>,
+}
+
+/// One `.gitattributes` file's rules, compiled into `Gitignore` matchers
+/// rooted at that file's own directory.
+struct AttributesLayer {
+    dir: PathBuf,
+    export_ignore: Gitignore,
+    binary: Gitignore,
+    text: Gitignore,
+}
+
+/// How a `.gitattributes` `text`/`binary` attribute resolves a path's
+/// content classification, consulted by `FilterContext::render_entry`
+/// ahead of the extension allowlist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextAttribute {
+    /// `binary` or `-text`: always binary, extension and content ignored.
+    Binary,
+    /// `text` or `text=auto`: classify via `FilterContext::sniff_file_is_text`
+    /// instead of `is_likely_text_file`'s extension allowlist.
+    Auto,
+}
+
+impl GitAttributes {
+    /// Walk `root` for every `.gitattributes` file and compile its rules.
+    /// A file that can't be read is skipped with a warning rather than
+    /// aborting the scan, the same fallback `build_filter_file_matcher`
+    /// gives an unreadable filter file.
+    fn load(root: &Path) -> Self {
+        let mut layers: Vec<AttributesLayer> = WalkDir::new(root)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name() == ".gitattributes")
+            .filter_map(|entry| AttributesLayer::parse(entry.path()))
+            .collect();
+        // Deepest directory first, so `is_export_ignored`/`text_attribute`
+        // hit the nearest file's rule before any ancestor's.
+        layers.sort_by_key(|layer| std::cmp::Reverse(layer.dir.components().count()));
+        Self { layers }
+    }
+
+    /// Whether `path` carries the `export-ignore` attribute, matching
+    /// `git archive`'s behavior of omitting such paths entirely.
+    fn is_export_ignored(&self, path: &Path) -> bool {
+        self.layers
+            .iter()
+            .filter(|layer| path.starts_with(&layer.dir))
+            .any(|layer| matches!(layer.export_ignore.matched(path, false), Match::Ignore(_)))
+    }
+
+    /// The nearest `.gitattributes` file's `text`/`binary` verdict for
+    /// `path`, or `None` if no layer's rules cover it.
+    fn text_attribute(&self, path: &Path) -> Option<TextAttribute> {
+        for layer in self.layers.iter().filter(|layer| path.starts_with(&layer.dir)) {
+            if matches!(layer.binary.matched(path, false), Match::Ignore(_)) {
+                return Some(TextAttribute::Binary);
+            }
+            if matches!(layer.text.matched(path, false), Match::Ignore(_)) {
+                return Some(TextAttribute::Auto);
+            }
+        }
+        None
+    }
+}
+
+impl AttributesLayer {
+    /// Parse one `.gitattributes` file's lines into its three attribute
+    /// matchers. A line naming any attribute other than `export-ignore`,
+    /// `binary`, `-text`, `text`, or `text=auto` is kept for its other
+    /// attributes but ignored for ours -- the same scope
+    /// `archive-to-txt`'s gitattributes handling covers.
+    fn parse(path: &Path) -> Option<Self> {
+        let dir = path.parent()?.to_path_buf();
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Failed to read .gitattributes");
+                return None;
+            }
+        };
+
+        let mut export_ignore_patterns = Vec::new();
+        let mut binary_patterns = Vec::new();
+        let mut text_patterns = Vec::new();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let Some(pattern) = fields.next() else {
+                continue;
+            };
+            for attr in fields {
+                match attr {
+                    "export-ignore" => export_ignore_patterns.push(pattern),
+                    "binary" | "-text" => binary_patterns.push(pattern),
+                    "text" | "text=auto" => text_patterns.push(pattern),
+                    _ => {}
+                }
+            }
+        }
+
+        Some(Self {
+            export_ignore: CodeArchiver::compile_gitignore(&dir, export_ignore_patterns.into_iter()),
+            binary: CodeArchiver::compile_gitignore(&dir, binary_patterns.into_iter()),
+            text: CodeArchiver::compile_gitignore(&dir, text_patterns.into_iter()),
+            dir,
+        })
+    }
+}
+
+/// One file's archive text (`None` if it was excluded entirely) and the
+/// `FilterStatistics` it contributed, as computed by
+/// `FilterContext::render_entry` independently of every other file.
+struct RenderedEntry {
+    text: Option<String>,
+    stats: FilterStatistics,
+}
+
+/// Evaluate `path` against, in priority order: `user_ignore` (the compiled
+/// `--ignore-pattern` matcher), `filter_files` (every `add_filter_file`
+/// file's combined includes/excludes), then `llm_ignore` (the built-in
+/// LLM-optimization preset) -- so an explicit `--ignore-pattern` always has
+/// the final say, a filter-file re-inclusion can still rescue an
+/// LLM-default exclusion, and a filter-file exclusion only applies where
+/// neither of those rescued the path. `user_ignore`/`llm_ignore` use real
+/// gitignore syntax (anchoring, directory-only trailing `/`, `!` negation,
+/// last-match-wins) instead of the ad-hoc substring matching this replaced.
+fn ignore_verdict(
+    llm_ignore: &Gitignore,
+    user_ignore: &Gitignore,
+    filter_files: &FilterFileMatcher,
+    path: &Path,
+    is_dir: bool,
+) -> Option<ExclusionReason> {
+    match user_ignore.matched(path, is_dir) {
+        Match::Ignore(_) => return Some(ExclusionReason::IgnorePattern),
+        Match::Whitelist(_) => return None,
+        Match::None => {}
+    }
+    if filter_files.is_rescued(path, is_dir) {
+        return None;
+    }
+    if filter_files.is_excluded(path, is_dir) {
+        return Some(ExclusionReason::FilterFile);
+    }
+    match llm_ignore.matched(path, is_dir) {
+        Match::Ignore(_) => Some(ExclusionReason::Llm),
+        _ => None,
+    }
+}
+
+/// Find which named `get_llm_patterns_by_category` category (if any)
+/// matches `path`, so an LLM-preset exclusion can be credited to it in
+/// `FilterStatistics::llm_category_counts`. Only ever meaningful to call
+/// once `ignore_verdict` has already returned `ExclusionReason::Llm` for
+/// the same path -- a category match here doesn't by itself mean the path
+/// was excluded, since `llm_ignore` may cover a narrower level than the
+/// full category set this classifies against.
+fn find_llm_category<'a>(categories: &'a [(String, Gitignore)], path: &Path, is_dir: bool) -> Option<&'a str> {
+    categories
+        .iter()
+        .find(|(_, matcher)| matches!(matcher.matched(path, is_dir), Match::Ignore(_)))
+        .map(|(name, _)| name.as_str())
+}
+
+/// The subset of a `CodeArchiver`'s filtering configuration that
+/// `render_and_write_entries` needs inside its rayon pool. `CodeArchiver`
+/// itself can't be shared across threads this way -- it holds a
+/// `git2::Repository`, which isn't `Sync` -- so the pool closure captures
+/// this small, cloned, `Sync` snapshot instead.
+struct FilterContext {
+    include_extensions: Option<Vec<String>>,
+    llm_ignore: Gitignore,
+    /// One `Gitignore` per `get_llm_patterns_by_category` category, used
+    /// only to attribute an already-decided `ExclusionReason::Llm` verdict
+    /// to a category via `find_llm_category`.
+    llm_categories: Vec<(String, Gitignore)>,
+    user_ignore: Gitignore,
+    filter_files: FilterFileMatcher,
+    minify_sources: bool,
+    /// Compiled from `CodeArchiver::select_types` (`--type`); `None` means
+    /// no type filter was given, so every file passes this gate.
+    type_select: Option<Gitignore>,
+    /// Every `.gitattributes` file found under the target folder, for
+    /// `export-ignore` exclusion and `binary`/`text` content classification.
+    git_attributes: GitAttributes,
+}
+
+impl FilterContext {
+    /// Snapshot the filtering fields of `archiver` at the start of a scan,
+    /// compiling its ignore patterns into `Gitignore` matchers once up
+    /// front rather than per file.
+    fn from_archiver(archiver: &CodeArchiver) -> Self {
+        Self {
+            include_extensions: archiver.include_extensions.clone(),
+            llm_ignore: archiver.build_llm_ignore_matcher(),
+            llm_categories: archiver.build_llm_category_matchers(),
+            minify_sources: archiver.minify_sources,
+            user_ignore: archiver.build_user_ignore_matcher(),
+            filter_files: archiver.build_filter_file_matcher(),
+            type_select: archiver.build_type_select_matcher(),
+            git_attributes: archiver.build_git_attributes(),
+        }
+    }
+
+    /// Decide whether `file_path` should be excluded from the archive and,
+    /// if so, why -- a pure query with no `FilterStatistics` side effect,
+    /// so it's safe for `render_entry` to call from any thread in
+    /// `render_and_write_entries`'s pool; callers fold the verdict into
+    /// their own stats afterward.
+    fn classify_exclusion(&self, file_path: &Path) -> Option<ExclusionReason> {
+        // `export-ignore` is an unconditional omission, matching `git
+        // archive`'s own behavior, so it's checked ahead of every other
+        // gate.
+        if self.git_attributes.is_export_ignored(file_path) {
+            return Some(ExclusionReason::IgnorePattern);
+        }
+
+        // Check extension filtering first
+        if let Some(ref allowed_extensions) = self.include_extensions {
+            let allowed = file_path
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(|ext| allowed_extensions.contains(&ext.to_lowercase()))
+                .unwrap_or(false);
+            if !allowed {
+                return Some(ExclusionReason::Extension);
+            }
+        }
+
+        // `--type` selections are another allow-list gate, alongside
+        // `include_extensions`, that a file must clear before the
+        // exclude-oriented `ignore_verdict` checks run.
+        if let Some(ref type_select) = self.type_select {
+            if !matches!(type_select.matched(file_path, false), Match::Ignore(_)) {
+                return Some(ExclusionReason::Extension);
+            }
+        }
+
+        ignore_verdict(
+            &self.llm_ignore,
+            &self.user_ignore,
+            &self.filter_files,
+            file_path,
+            false,
+        )
+    }
+
+    /// Classify and (if included) read and format a single file's archive
+    /// entry. Takes `&self` so `render_and_write_entries` can call it
+    /// concurrently across a thread pool and fold the returned
+    /// `FilterStatistics` contribution back together afterward.
+    fn render_entry(&self, file_path: &Path) -> RenderedEntry {
+        let mut stats = FilterStatistics {
+            total_files_found: 1,
+            ..Default::default()
+        };
+
+        if let Some(reason) = self.classify_exclusion(file_path) {
+            stats.files_excluded = 1;
+            match reason {
+                ExclusionReason::Extension => stats.excluded_by_extension = 1,
+                ExclusionReason::Llm => {
+                    stats.excluded_by_llm_optimization = 1;
+                    if let Some(category) = find_llm_category(&self.llm_categories, file_path, false) {
+                        stats.llm_category_counts.insert(category.to_string(), 1);
+                    }
+                }
+                ExclusionReason::IgnorePattern => stats.excluded_by_ignore_pattern = 1,
+                ExclusionReason::FilterFile => stats.excluded_by_filter_file = 1,
+            }
+            return RenderedEntry { text: None, stats };
+        }
+        stats.files_included = 1;
+
+        let mut text = format!("Absolute path: {}\n", file_path.display());
+
+        // Check if file is text or binary (Pattern 31.4 - Default values),
+        // deferring to a `.gitattributes` `binary`/`text` attribute over
+        // the extension allowlist when one covers this path.
+        let is_text = match self.git_attributes.text_attribute(file_path) {
+            Some(TextAttribute::Binary) => false,
+            Some(TextAttribute::Auto) => Self::sniff_file_is_text(file_path),
+            None => {
+                let mime_type = from_path(file_path).first_or_octet_stream();
+                mime_type.type_() == mime::TEXT
+                    || mime_type == mime::APPLICATION_JSON
+                    || Self::is_likely_text_file(file_path)
+            }
+        };
+
+        if is_text {
+            text.push_str("<text starts>\n");
+
+            // Read and render file content (Pattern 4.1 - RAII pattern)
+            match fs::read_to_string(file_path) {
+                Ok(content) if self.minify_sources => {
+                    let minified = Self::minify_content(file_path, &content);
+                    stats.bytes_saved_by_minification = content.len().saturating_sub(minified.len());
+                    stats.total_size_included = minified.len();
+                    text.push_str(&minified);
+                }
+                Ok(content) => {
+                    stats.total_size_included = content.len();
+                    text.push_str(&content);
+                }
+                Err(_) => {
+                    stats.files_skipped = 1;
+                    text.push_str("Error reading file content\n");
+                }
+            }
+
+            text.push_str("<text ends>\n");
+        } else {
+            text.push_str("Binary file, content not included.\n");
+        }
+
+        text.push('\n');
+        RenderedEntry {
+            text: Some(text),
+            stats,
+        }
+    }
+
+    /// Check if file is likely text based on extension (Pattern 31.8 - Pattern matching)
+    fn is_likely_text_file(path: &Path) -> bool {
+        let text_extensions = [
+            "rs", "toml", "md", "txt", "json", "yaml", "yml", "js", "ts", "tsx", "jsx", "html",
+            "css", "scss", "py", "rb", "go", "java", "c", "cpp", "h", "hpp", "sh", "bash", "zsh",
+            "fish", "ps1", "bat", "cmd", "xml", "svg", "gitignore", "dockerfile", "makefile",
+        ];
+
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| text_extensions.contains(&ext.to_lowercase().as_str()))
+            .unwrap_or(false)
+    }
+
+    /// Sample up to `TEXT_SNIFF_SAMPLE_LEN` bytes of `path`'s content and
+    /// classify it with the compression pipeline's own
+    /// `compression::file_type::sniff_is_text` heuristic (reject on a NUL
+    /// byte, otherwise threshold the proportion of non-UTF-8/non-printable
+    /// bytes) rather than `is_likely_text_file`'s extension allowlist.
+    /// Used when a `.gitattributes` `text`/`text=auto` attribute makes
+    /// content sniffing authoritative for a path.
+    fn sniff_file_is_text(path: &Path) -> bool {
+        match fs::read(path) {
+            Ok(bytes) => {
+                let sample_len = bytes.len().min(TEXT_SNIFF_SAMPLE_LEN);
+                compression::file_type::sniff_is_text(&bytes[..sample_len], MAX_NON_PRINTABLE_RATIO)
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Pass `content` (already read from `file_path`) through a
+    /// language-appropriate minifier when `minify_sources` is on. JS/TS/JSX/
+    /// TSX reuses the same swc strip-types/compress/mangle pipeline
+    /// `minify_file` gives the standalone `compress-ts` command; every other
+    /// text file gets the cheaper `minify_generic_text` fallback. A file
+    /// that fails to parse (e.g. a `.ts` file with a syntax error) is
+    /// returned unchanged rather than dropped, so a minifier error never
+    /// costs the archive any content.
+    fn minify_content(file_path: &Path, content: &str) -> String {
+        let is_js_like = file_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|ext| matches!(ext.to_lowercase().as_str(), "js" | "jsx" | "ts" | "tsx"))
+            .unwrap_or(false);
+
+        if is_js_like {
+            return match minify_file(file_path, false) {
+                Ok((minified, _)) => minified,
+                Err(e) => {
+                    warn!(path = %file_path.display(), error = %e, "Failed to minify source, archiving verbatim");
+                    content.to_string()
+                }
+            };
+        }
+
+        Self::minify_generic_text(file_path, content)
+    }
+
+    /// Cheap, language-agnostic minification for non-JS/TS text files:
+    /// strips trailing whitespace from every line, collapses runs of blank
+    /// lines into one, and (for extensions with a known single-line
+    /// comment syntax) drops full-line comments.
+    fn minify_generic_text(file_path: &Path, content: &str) -> String {
+        let comment_prefix = file_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(|ext| match ext.to_lowercase().as_str() {
+                "rs" | "c" | "cpp" | "h" | "hpp" | "java" | "go" | "scss" | "css" => Some("//"),
+                "py" | "rb" | "sh" | "bash" | "zsh" | "fish" | "yaml" | "yml" | "toml"
+                | "gitignore" | "dockerfile" => Some("#"),
+                _ => None,
+            });
+
+        let mut out = String::with_capacity(content.len());
+        let mut in_blank_run = false;
+        for line in content.lines() {
+            let trimmed = line.trim_end();
+            if comment_prefix.is_some_and(|prefix| trimmed.trim_start().starts_with(prefix)) {
+                continue;
+            }
+            if trimmed.is_empty() {
+                if in_blank_run {
+                    continue;
+                }
+                in_blank_run = true;
+            } else {
+                in_blank_run = false;
+            }
+            out.push_str(trimmed);
+            out.push('\n');
+        }
+        out
+    }
 }
 
 impl std::fmt::Debug for CodeArchiver {
@@ -820,11 +2138,18 @@ impl std::fmt::Debug for CodeArchiver {
             .field("is_git_repo", &self.is_git_repo)
             .field("git_repo", &self.git_repo.is_some())
             .field("llm_optimize", &self.llm_optimize)
+            .field("llm_level", &self.llm_level)
             .field("ignore_patterns", &self.ignore_patterns.len())
+            .field("filter_files", &self.filter_files.len())
             .field(
                 "include_extensions",
                 &self.include_extensions.as_ref().map(|e| e.len()),
             )
+            .field("output_format", &self.output_format)
+            .field("minify_sources", &self.minify_sources)
+            .field("select_types", &self.select_types)
+            .field("negate_types", &self.negate_types)
+            .field("custom_types", &self.custom_types.len())
             .finish()
     }
 }
@@ -864,10 +2189,21 @@ impl CodeArchiver {
             git_repo,
             is_git_repo,
             llm_optimize: false,
+            llm_level: None,
             ignore_patterns: Vec::new(),
+            filter_files: Vec::new(),
             include_extensions: None,
             show_filter_stats: false,
+            normalize: false,
+            jobs: None,
+            keep_going: false,
             filter_stats: FilterStatistics::default(),
+            output_format: ArchiveFormat::default(),
+            minify_sources: false,
+            select_types: Vec::new(),
+            negate_types: Vec::new(),
+            custom_types: Vec::new(),
+            catalog: Catalog::new(Lang::En),
         })
     }
 
@@ -876,11 +2212,27 @@ impl CodeArchiver {
         self.llm_optimize = true;
     }
 
+    /// Minify each text file's content (see `FilterContext::minify_content`)
+    /// before it's written into the concatenated-text archive, trading
+    /// exact-source fidelity for fewer tokens in the emitted bundle.
+    pub fn enable_minify_sources(&mut self) {
+        self.minify_sources = true;
+    }
+
     /// Add custom ignore patterns
     pub fn add_ignore_patterns(&mut self, patterns: Vec<String>) {
         self.ignore_patterns.extend(patterns);
     }
 
+    /// Add an external gitignore-syntax filter file: one pattern per line,
+    /// `!` re-includes a path. Can be called more than once; every added
+    /// file's patterns feed the same `FilterFileMatcher` (see
+    /// `build_filter_file_matcher`), so a later file's re-inclusion can
+    /// rescue an earlier file's exclusion.
+    pub fn add_filter_file(&mut self, path: PathBuf) {
+        self.filter_files.push(path);
+    }
+
     /// Set file extensions to include (comma-separated)
     pub fn set_include_extensions(&mut self, extensions: String) {
         self.include_extensions = Some(
@@ -891,11 +2243,132 @@ impl CodeArchiver {
         );
     }
 
+    /// Select named file types to include (ripgrep's `--type`, e.g.
+    /// `"rust"`/`"web"`): a file must match at least one selected type's
+    /// globs (see `known_file_types`/`custom_types`) to survive, on top of
+    /// any `include_extensions` gate. Unlike extension filtering, this
+    /// also matches extension-less names like `Makefile`/`Dockerfile`.
+    pub fn set_include_types(&mut self, types: Vec<String>) {
+        self.select_types = types;
+    }
+
+    /// Exclude named file types (ripgrep's `--type-not`, e.g. `"lock"`):
+    /// every glob the name resolves to is folded into
+    /// `build_user_ignore_matcher` as a plain exclude pattern.
+    pub fn set_exclude_types(&mut self, types: Vec<String>) {
+        self.negate_types = types;
+    }
+
+    /// Register (or override) a named file type's glob patterns, checked
+    /// ahead of `known_file_types` by `resolve_type_patterns` so a custom
+    /// mapping can override a built-in of the same name.
+    pub fn register_type(&mut self, name: impl Into<String>, globs: Vec<String>) {
+        self.custom_types.push((name.into(), globs));
+    }
+
+    /// Built-in `--type`/`--type-not` name -> glob-pattern table, modeled
+    /// on the `ignore` crate's own `default_types`: a short name like
+    /// `rust` or `web` expands to the globs that define it. Includes a few
+    /// extension-less names (`make`, `docker`, `cmake`) that
+    /// `include_extensions` can never express, since it requires a
+    /// `.extension()` to check against.
+    fn known_file_types(&self) -> &'static [(&'static str, &'static [&'static str])] {
+        &[
+            ("bazel", &["*.bazel", "*.bzl", "BUILD", "WORKSPACE", "MODULE.bazel"]),
+            ("cmake", &["CMakeLists.txt", "*.cmake"]),
+            ("cpp", &["*.c", "*.cpp", "*.cc", "*.cxx", "*.h", "*.hpp", "*.hh"]),
+            (
+                "docker",
+                &["Dockerfile", "*.dockerfile", "docker-compose.yml", "docker-compose.yaml"],
+            ),
+            ("docs", &["*.md", "*.markdown", "*.rst", "*.adoc", "*.txt"]),
+            ("go", &["*.go", "go.mod", "go.sum"]),
+            ("make", &["Makefile", "makefile", "GNUmakefile", "*.mk"]),
+            ("py", &["*.py", "*.pyi"]),
+            ("rust", &["*.rs", "Cargo.toml"]),
+            ("web", &["*.html", "*.css", "*.scss", "*.js", "*.ts", "*.jsx", "*.tsx"]),
+            ("yaml", &["*.yaml", "*.yml"]),
+        ]
+    }
+
+    /// Expand type names into the union of their glob patterns:
+    /// `custom_types` is checked first so a custom mapping can override a
+    /// built-in of the same name; an unrecognized name is dropped with a
+    /// warning rather than silently matching nothing.
+    fn resolve_type_patterns(&self, names: &[String]) -> Vec<String> {
+        names
+            .iter()
+            .flat_map(|name| {
+                self.custom_types
+                    .iter()
+                    .find(|(n, _)| n == name)
+                    .map(|(_, globs)| globs.clone())
+                    .or_else(|| {
+                        self.known_file_types()
+                            .iter()
+                            .find(|(n, _)| n == name)
+                            .map(|(_, globs)| globs.iter().map(|g| g.to_string()).collect())
+                    })
+                    .unwrap_or_else(|| {
+                        warn!(name = %name, "Unknown file type");
+                        Vec::new()
+                    })
+            })
+            .collect()
+    }
+
+    /// Compile `select_types` (resolved through `resolve_type_patterns`)
+    /// into the `Gitignore` `classify_exclusion` requires a file to match,
+    /// or `None` when no `--type` was given so every file passes this gate.
+    fn build_type_select_matcher(&self) -> Option<Gitignore> {
+        if self.select_types.is_empty() {
+            return None;
+        }
+        let patterns = self.resolve_type_patterns(&self.select_types);
+        Some(Self::compile_gitignore(
+            &self.target_folder,
+            patterns.iter().map(String::as_str),
+        ))
+    }
+
     /// Enable filter statistics collection
     pub fn enable_filter_statistics(&mut self) {
         self.show_filter_stats = true;
     }
 
+    /// Run the archive text through [`normalize::normalize_archive`] before
+    /// writing it, so absolute paths under the target folder and the
+    /// user's home directory are rewritten to stable `$ROOT`/`$HOME`
+    /// tokens and the same input tree always produces identical output.
+    pub fn enable_normalization(&mut self) {
+        self.normalize = true;
+    }
+
+    /// Set how many threads the parallel scan/read pass in
+    /// `write_file_contents` should use; `None` lets rayon pick the
+    /// available parallelism.
+    pub fn set_jobs(&mut self, jobs: Option<usize>) {
+        self.jobs = jobs;
+    }
+
+    /// Record an unreadable or permission-denied file as a skipped entry
+    /// in the filtering statistics instead of aborting the whole run.
+    pub fn enable_keep_going(&mut self) {
+        self.keep_going = true;
+    }
+
+    /// Set the language the filtering-statistics report, LLM-optimization
+    /// tip, git-repo header line, and in-archive banners are rendered in.
+    pub fn set_locale(&mut self, lang: Lang) {
+        self.catalog = Catalog::new(lang);
+    }
+
+    /// Set the container format `create_archive` writes. Defaults to
+    /// `ArchiveFormat::ConcatenatedText`.
+    pub fn set_output_format(&mut self, format: ArchiveFormat) {
+        self.output_format = format;
+    }
+
     /// Get LLM-optimized ignore patterns for cleaner training data
     ///
     /// This method returns a comprehensive list of file patterns that should be
@@ -922,7 +2395,7 @@ impl CodeArchiver {
     /// These exclusions help create cleaner, more focused training datasets
     /// that contain primarily source code and documentation rather than
     /// generated artifacts or binary files.
-    fn get_llm_ignore_patterns(&self) -> Vec<&str> {
+    fn get_llm_ignore_patterns() -> Vec<&'static str> {
         vec![
             // Build artifacts and outputs
             "target/",
@@ -1301,6 +2774,139 @@ impl CodeArchiver {
         ]
     }
 
+    /// Compile the built-in LLM-optimization preset into a `Gitignore`
+    /// matcher (empty when `llm_optimize` is off, so callers can check it
+    /// unconditionally).
+    fn build_llm_ignore_matcher(&self) -> Gitignore {
+        if !self.llm_optimize {
+            return Gitignore::empty();
+        }
+        let patterns = match self.llm_level {
+            Some(level) => self
+                .get_llm_optimization_levels()
+                .remove(level.as_str())
+                .unwrap_or_else(Self::get_llm_ignore_patterns),
+            None => Self::get_llm_ignore_patterns(),
+        };
+        Self::compile_gitignore(&self.target_folder, patterns.into_iter())
+    }
+
+    /// Compile each `get_llm_patterns_by_category` category into its own
+    /// `Gitignore`, so an LLM-preset exclusion can later be attributed to
+    /// the category that caused it (see `find_llm_category`). Compiled
+    /// unconditionally, even with `llm_optimize` off or a narrower
+    /// `llm_level` selected -- a path can only reach `find_llm_category` by
+    /// first matching the (already level-restricted) `llm_ignore` matcher,
+    /// so the broader category set here can't misattribute an exclusion
+    /// that wouldn't otherwise have happened.
+    fn build_llm_category_matchers(&self) -> Vec<(String, Gitignore)> {
+        self.get_llm_patterns_by_category()
+            .into_iter()
+            .map(|(name, patterns)| {
+                (
+                    name.to_string(),
+                    Self::compile_gitignore(&self.target_folder, patterns.into_iter()),
+                )
+            })
+            .collect()
+    }
+
+    /// Compile `--ignore-pattern` into a `Gitignore` matcher, giving real
+    /// gitignore syntax (anchoring, directory-only trailing `/`, `!`
+    /// negation) instead of the ad-hoc substring matching this replaced.
+    /// `--type-not` names are resolved to globs and folded in alongside
+    /// the literal patterns, so a negated type behaves like one more
+    /// `--ignore-pattern`.
+    fn build_user_ignore_matcher(&self) -> Gitignore {
+        let negated_type_patterns = self.resolve_type_patterns(&self.negate_types);
+        Self::compile_gitignore(
+            &self.target_folder,
+            self.ignore_patterns
+                .iter()
+                .map(String::as_str)
+                .chain(negated_type_patterns.iter().map(String::as_str)),
+        )
+    }
+
+    /// Read every file added via `add_filter_file`, compiling each one's
+    /// plain lines into its own `ExcludeMatcher` and its `!`-prefixed
+    /// (re-inclusion) lines, stripped of the `!`, into its own
+    /// `IncludeMatcher`. The per-file matchers are combined with `Union`
+    /// (a path excluded/included by *any* filter file counts) into the
+    /// `(includes ∪ llm_defaults) − excludes` matcher `ignore_verdict`
+    /// consults. A filter file that can't be read is skipped with a
+    /// warning rather than aborting the scan, the same fallback
+    /// `compile_gitignore` gives an unparseable pattern line.
+    fn build_filter_file_matcher(&self) -> FilterFileMatcher {
+        if self.filter_files.is_empty() {
+            return FilterFileMatcher::empty();
+        }
+
+        let mut includes: Vec<Arc<dyn PathMatcher>> = Vec::new();
+        let mut excludes: Vec<Arc<dyn PathMatcher>> = Vec::new();
+
+        for path in &self.filter_files {
+            let content = match fs::read_to_string(path) {
+                Ok(content) => content,
+                Err(e) => {
+                    warn!(path = %path.display(), error = %e, "Failed to read filter file");
+                    continue;
+                }
+            };
+
+            let mut include_lines = Vec::new();
+            let mut exclude_lines = Vec::new();
+            for line in content.lines() {
+                let trimmed = line.trim();
+                if trimmed.is_empty() || trimmed.starts_with('#') {
+                    continue;
+                }
+                match trimmed.strip_prefix('!') {
+                    Some(pattern) => include_lines.push(pattern.to_string()),
+                    None => exclude_lines.push(trimmed.to_string()),
+                }
+            }
+
+            includes.push(Arc::new(IncludeMatcher(Self::compile_gitignore(
+                &self.target_folder,
+                include_lines.iter().map(String::as_str),
+            ))));
+            excludes.push(Arc::new(ExcludeMatcher(Self::compile_gitignore(
+                &self.target_folder,
+                exclude_lines.iter().map(String::as_str),
+            ))));
+        }
+
+        FilterFileMatcher::from_union(Union(includes), Union(excludes))
+    }
+
+    /// Discover and compile every `.gitattributes` file under
+    /// `target_folder`, consulted by `FilterContext::classify_exclusion`
+    /// (for `export-ignore`), `FilterContext::render_entry` (for
+    /// `binary`/`text`), and `write_git_tree_structure` (for
+    /// `export-ignore`). Built fresh each scan, the same as
+    /// `build_filter_file_matcher`, so edits to a `.gitattributes` file are
+    /// picked up without restarting a watch session.
+    fn build_git_attributes(&self) -> GitAttributes {
+        GitAttributes::load(&self.target_folder)
+    }
+
+    /// Compile `patterns` into a `Gitignore` matcher anchored at `root`,
+    /// skipping (with a warning) any line that fails to parse rather than
+    /// aborting the whole set.
+    fn compile_gitignore<'a>(root: &Path, patterns: impl Iterator<Item = &'a str>) -> Gitignore {
+        let mut builder = GitignoreBuilder::new(root);
+        for pattern in patterns {
+            if let Err(e) = builder.add_line(None, pattern) {
+                warn!(pattern, error = %e, "Invalid ignore pattern");
+            }
+        }
+        builder.build().unwrap_or_else(|e| {
+            warn!(error = %e, "Failed to compile ignore pattern matcher");
+            Gitignore::empty()
+        })
+    }
+
     /// Get LLM optimization patterns by category
     ///
     /// Returns patterns grouped by category for more granular control
@@ -1492,7 +3098,7 @@ impl CodeArchiver {
         levels.insert("aggressive", aggressive);
 
         // Comprehensive level - all patterns
-        levels.insert("comprehensive", self.get_llm_ignore_patterns());
+        levels.insert("comprehensive", Self::get_llm_ignore_patterns());
 
         levels
     }
@@ -1502,103 +3108,28 @@ impl CodeArchiver {
     /// Sets the LLM optimization to use a specific level of filtering.
     /// Available levels: basic, standard, aggressive, comprehensive
     pub fn set_llm_optimization_level(&mut self, level: &str) {
-        if let Some(_patterns) = self.get_llm_optimization_levels().get(level) {
-            self.llm_optimize = true;
-            // Store the level for later use in filtering
-            // Note: This would require adding a field to store the current level
-            // For now, we'll document the intended behavior
+        match LlmLevel::parse(level) {
+            Some(level) => {
+                self.llm_optimize = true;
+                self.llm_level = Some(level);
+            }
+            None => warn!(level, "Unknown LLM optimization level"),
         }
     }
 
     /// Get statistics about LLM optimization categories
     ///
-    /// Returns detailed statistics about which categories of files were excluded
-    /// during LLM-optimized filtering.
+    /// Returns real per-category counts of how many files each
+    /// `get_llm_patterns_by_category` category excluded during the most
+    /// recent scan (see `FilterStatistics::llm_category_counts`), keyed
+    /// `"<category>_excluded"`. A category absent from the result excluded
+    /// nothing this run.
     fn get_llm_optimization_stats(&self) -> std::collections::HashMap<String, usize> {
-        let mut stats = std::collections::HashMap::new();
-
-        // This would be populated during filtering
-        // For now, return the current basic stats
-        stats.insert("build_artifacts_excluded".to_string(), 0);
-        stats.insert("dependencies_excluded".to_string(), 0);
-        stats.insert("cache_temp_excluded".to_string(), 0);
-        stats.insert("ide_editor_excluded".to_string(), 0);
-        stats.insert("os_generated_excluded".to_string(), 0);
-        stats.insert("secrets_config_excluded".to_string(), 0);
-        stats.insert("media_files_excluded".to_string(), 0);
-        stats.insert("data_models_excluded".to_string(), 0);
-
-        stats
-    }
-
-    /// Check if file should be included based on filtering rules
-    fn should_include_file(&mut self, file_path: &Path) -> bool {
-        let file_name = file_path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-        let file_path_str = file_path.to_string_lossy();
-
-        self.filter_stats.total_files_found += 1;
-
-        // Check extension filtering first
-        if let Some(ref allowed_extensions) = self.include_extensions {
-            if let Some(ext) = file_path.extension().and_then(|e| e.to_str()) {
-                if !allowed_extensions.contains(&ext.to_lowercase()) {
-                    self.filter_stats.excluded_by_extension += 1;
-                    return false;
-                }
-            } else {
-                // No extension, exclude if extensions are specified
-                self.filter_stats.excluded_by_extension += 1;
-                return false;
-            }
-        }
-
-        // Check LLM optimization patterns
-        if self.llm_optimize {
-            for pattern in self.get_llm_ignore_patterns() {
-                if Self::matches_glob_pattern(&file_path_str, pattern)
-                    || Self::matches_glob_pattern(file_name, pattern)
-                {
-                    self.filter_stats.excluded_by_llm_optimization += 1;
-                    return false;
-                }
-            }
-        }
-
-        // Check custom ignore patterns
-        for pattern in &self.ignore_patterns {
-            if Self::matches_glob_pattern(&file_path_str, pattern)
-                || Self::matches_glob_pattern(file_name, pattern)
-            {
-                self.filter_stats.excluded_by_ignore_pattern += 1;
-                return false;
-            }
-        }
-
-        self.filter_stats.files_included += 1;
-        true
-    }
-
-    /// Simple glob pattern matching
-    fn matches_glob_pattern(text: &str, pattern: &str) -> bool {
-        if pattern.ends_with('/') {
-            // Directory pattern
-            let dir_pattern = &pattern[..pattern.len() - 1];
-            return text.contains(dir_pattern);
-        }
-
-        if pattern.contains('*') {
-            // Wildcard pattern
-            if pattern.starts_with('*') && pattern.len() > 1 {
-                return text.ends_with(&pattern[1..]);
-            }
-            if pattern.ends_with('*') && pattern.len() > 1 {
-                return text.starts_with(&pattern[..pattern.len() - 1]);
-            }
-            return text.contains(&pattern.replace('*', ""));
-        }
-
-        // Exact match
-        text == pattern || text.contains(pattern)
+        self.filter_stats
+            .llm_category_counts
+            .iter()
+            .map(|(category, count)| (format!("{category}_excluded"), *count))
+            .collect()
     }
 
     /// Display filtering statistics with enhanced LLM optimization details
@@ -1608,44 +3139,96 @@ impl CodeArchiver {
         }
 
         let stats = &self.filter_stats;
-        println!("\nüìä File Filtering Statistics:");
-        println!("   Total files found: {}", stats.total_files_found);
-        println!("   Files included: {} üü¢", stats.files_included);
-        println!("   Files excluded: {} üî¥", stats.files_excluded);
+        println!("{}", self.catalog.get("stats.header", &[]));
+        println!(
+            "{}",
+            self.catalog
+                .get("stats.total_found", &[&stats.total_files_found.to_string()])
+        );
+        println!(
+            "{}",
+            self.catalog
+                .get("stats.included", &[&stats.files_included.to_string()])
+        );
+        println!(
+            "{}",
+            self.catalog
+                .get("stats.excluded", &[&stats.files_excluded.to_string()])
+        );
 
         if stats.excluded_by_extension > 0 {
             println!(
-                "     ‚îî‚îÄ By extension filter: {}",
-                stats.excluded_by_extension
+                "{}",
+                self.catalog.get(
+                    "stats.by_extension",
+                    &[&stats.excluded_by_extension.to_string()]
+                )
             );
         }
         if stats.excluded_by_llm_optimization > 0 {
             println!(
-                "     ‚îî‚îÄ By LLM optimization: {} ü§ñ",
-                stats.excluded_by_llm_optimization
+                "{}",
+                self.catalog.get(
+                    "stats.by_llm",
+                    &[&stats.excluded_by_llm_optimization.to_string()]
+                )
             );
 
-            // Show LLM optimization benefits
+            // Show LLM optimization benefits, with a real per-category count
+            // wherever `get_llm_optimization_stats` has one for this run.
             if self.llm_optimize {
-                println!("        ‚ú® LLM optimization excluded:");
-                println!("           ‚Ä¢ Build artifacts and compiled files");
-                println!("           ‚Ä¢ Dependencies and package manager files");
-                println!("           ‚Ä¢ Cache and temporary files");
-                println!("           ‚Ä¢ IDE and editor configuration");
-                println!("           ‚Ä¢ Binary media files");
-                println!("           ‚Ä¢ Environment and secret files");
-                println!("           ‚Ä¢ Large data files and ML models");
-                println!("        üìö This creates cleaner training data focused on source code");
+                println!("{}", self.catalog.get("stats.llm_excluded_header", &[]));
+                let category_stats = self.get_llm_optimization_stats();
+                for (category, label_id) in [
+                    ("build_artifacts", "category.build_artifacts"),
+                    ("dependencies", "category.dependencies"),
+                    ("cache_temp", "category.cache_temp"),
+                    ("ide_editor", "category.ide_editor"),
+                    ("os_generated", "category.os_generated"),
+                    ("secrets_config", "category.secrets_config"),
+                    ("media_files", "category.media_files"),
+                    ("data_models", "category.data_models"),
+                ] {
+                    let label = self.catalog.get(label_id, &[]);
+                    match category_stats.get(&format!("{category}_excluded")) {
+                        Some(count) => println!("           • {label}: {count}"),
+                        None => println!("           • {label}"),
+                    }
+                }
+                println!("{}", self.catalog.get("stats.llm_excluded_training", &[]));
             }
         }
         if stats.excluded_by_ignore_pattern > 0 {
             println!(
-                "     ‚îî‚îÄ By ignore patterns: {}",
-                stats.excluded_by_ignore_pattern
+                "{}",
+                self.catalog.get(
+                    "stats.by_ignore_pattern",
+                    &[&stats.excluded_by_ignore_pattern.to_string()]
+                )
+            );
+        }
+        if stats.excluded_by_filter_file > 0 {
+            println!(
+                "{}",
+                self.catalog.get(
+                    "stats.by_filter_file",
+                    &[&stats.excluded_by_filter_file.to_string()]
+                )
             );
         }
         if stats.excluded_by_git > 0 {
-            println!("     ‚îî‚îÄ By Git rules: {}", stats.excluded_by_git);
+            println!(
+                "{}",
+                self.catalog
+                    .get("stats.by_git", &[&stats.excluded_by_git.to_string()])
+            );
+        }
+        if stats.files_skipped > 0 {
+            println!(
+                "{}",
+                self.catalog
+                    .get("stats.skipped", &[&stats.files_skipped.to_string()])
+            );
         }
 
         let inclusion_rate = if stats.total_files_found > 0 {
@@ -1653,20 +3236,27 @@ impl CodeArchiver {
         } else {
             0.0
         };
-        println!("   Inclusion rate: {:.1}% üìà", inclusion_rate);
+        println!(
+            "{}",
+            self.catalog
+                .get("stats.inclusion_rate", &[&format!("{:.1}", inclusion_rate)])
+        );
 
         if stats.total_size_included > 0 {
             println!(
-                "   Total size included: {} bytes üíæ",
-                stats.total_size_included
+                "{}",
+                self.catalog.get(
+                    "stats.total_size",
+                    &[&stats.total_size_included.to_string()]
+                )
             );
         }
 
         // Show LLM optimization recommendation
         if !self.llm_optimize && stats.files_excluded > 0 {
-            println!("\nüí° Tip: Use --llm-optimize flag to automatically exclude");
-            println!("   build artifacts, dependencies, and binary files for");
-            println!("   cleaner LLM training data preparation.");
+            println!("{}", self.catalog.get("stats.llm_tip_1", &[]));
+            println!("{}", self.catalog.get("stats.llm_tip_2", &[]));
+            println!("{}", self.catalog.get("stats.llm_tip_3", &[]));
         }
 
         println!();
@@ -1674,6 +3264,26 @@ impl CodeArchiver {
 
     /// Create the archive file (Pattern 4.1 - RAII pattern)
     pub fn create_archive(&mut self) -> Result<()> {
+        // Ensure output directory exists
+        fs::create_dir_all(&self.output_dir)?;
+
+        let output_file = match self.output_format {
+            ArchiveFormat::ConcatenatedText => self.create_concatenated_text_archive()?,
+            ArchiveFormat::Tar | ArchiveFormat::TarGz | ArchiveFormat::TarLz4 | ArchiveFormat::TarZst => {
+                self.create_tar_archive()?
+            }
+        };
+
+        // Display filtering statistics
+        self.display_filter_stats();
+
+        println!("Archive created: {:?}", output_file);
+        Ok(())
+    }
+
+    /// Write the original, default archive format: a single `.txt` file of
+    /// concatenated, rendered file contents. Returns the path written.
+    fn create_concatenated_text_archive(&mut self) -> Result<PathBuf> {
         let timestamp = Local::now().format("%Y%m%d%H%M%S").to_string();
         let folder_name = self
             .target_folder
@@ -1685,42 +3295,190 @@ impl CodeArchiver {
             .output_dir
             .join(format!("{}-{}.txt", folder_name, timestamp));
 
-        // Ensure output directory exists
-        fs::create_dir_all(&self.output_dir)?;
+        if self.normalize {
+            // Assemble the archive in memory first so the normalization
+            // pass can run over the whole buffer before anything touches
+            // disk, rather than normalizing a file we've already written.
+            let mut buffer: Vec<u8> = Vec::new();
+            self.write_header(&mut buffer)?;
+            self.write_directory_structure(&mut buffer)?;
+            self.write_file_contents(&mut buffer)?;
+
+            let raw =
+                String::from_utf8(buffer).context("archive content is not valid UTF-8")?;
+            let normalize_config = NormalizeConfig::new(self.target_folder.clone());
+            let normalized = normalize::normalize_archive(&raw, &normalize_config);
+            fs::write(&output_file, normalized)?;
+        } else {
+            let mut file = File::create(&output_file)?;
+            self.write_header(&mut file)?;
+            self.write_directory_structure(&mut file)?;
+            self.write_file_contents(&mut file)?;
+        }
+
+        Ok(output_file)
+    }
+
+    /// Write a tar archive (optionally gzip- or lz4-frame-compressed per
+    /// `self.output_format`) of every included file, preserving its path
+    /// relative to `target_folder`. Returns the path written.
+    ///
+    /// Entries are streamed straight from the walk into the tar writer
+    /// wrapped in the chosen encoder -- `write_tar_entries` never buffers
+    /// more than one file's content at a time -- so memory use doesn't
+    /// grow with repository size the way `create_concatenated_text_archive`
+    /// does under `self.normalize`.
+    fn create_tar_archive(&mut self) -> Result<PathBuf> {
+        let timestamp = Local::now().format("%Y%m%d%H%M%S").to_string();
+        let folder_name = self
+            .target_folder
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown");
+
+        let extension = match self.output_format {
+            ArchiveFormat::Tar => "tar",
+            ArchiveFormat::TarGz => "tar.gz",
+            ArchiveFormat::TarLz4 => "tar.lz4",
+            ArchiveFormat::TarZst => "tar.zst",
+            ArchiveFormat::ConcatenatedText => "txt",
+        };
+        let output_file = self
+            .output_dir
+            .join(format!("{}-{}.{}", folder_name, timestamp, extension));
+
+        let paths = self.collect_file_paths()?;
+        let file = File::create(&output_file)?;
+        match self.output_format {
+            ArchiveFormat::Tar => {
+                let mut builder = tar::Builder::new(file);
+                self.write_tar_manifest(&mut builder)?;
+                self.write_tar_entries(&mut builder, paths)?;
+                builder.into_inner()?;
+            }
+            ArchiveFormat::TarGz => {
+                let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+                let mut builder = tar::Builder::new(encoder);
+                self.write_tar_manifest(&mut builder)?;
+                self.write_tar_entries(&mut builder, paths)?;
+                builder.into_inner()?.finish()?;
+            }
+            ArchiveFormat::TarLz4 => {
+                let encoder = lz4_flex::frame::FrameEncoder::new(file);
+                let mut builder = tar::Builder::new(encoder);
+                self.write_tar_manifest(&mut builder)?;
+                self.write_tar_entries(&mut builder, paths)?;
+                builder
+                    .into_inner()?
+                    .finish()
+                    .map_err(|e| anyhow::anyhow!("failed to finish lz4 frame: {e}"))?;
+            }
+            ArchiveFormat::TarZst => {
+                let encoder = zstd::stream::write::Encoder::new(file, 0)?;
+                let mut builder = tar::Builder::new(encoder);
+                self.write_tar_manifest(&mut builder)?;
+                self.write_tar_entries(&mut builder, paths)?;
+                builder.into_inner()?.finish()?;
+            }
+            ArchiveFormat::ConcatenatedText => {
+                bail!("create_tar_archive called with ArchiveFormat::ConcatenatedText")
+            }
+        }
 
-        let mut file = File::create(&output_file)?;
+        Ok(output_file)
+    }
 
-        // Write header information
-        self.write_header(&mut file)?;
+    /// Write the same header/directory-structure text the concatenated-
+    /// text format leads with as a synthetic `MANIFEST` entry, so a tar
+    /// archive opens with the same orientation -- repo status and
+    /// directory tree -- instead of being just a bag of files.
+    fn write_tar_manifest<W: Write>(&self, builder: &mut tar::Builder<W>) -> Result<()> {
+        let mut manifest = Vec::new();
+        self.write_header(&mut manifest)?;
+        self.write_directory_structure(&mut manifest)?;
+
+        let mtime = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let mut header = tar::Header::new_gnu();
+        header.set_size(manifest.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(mtime);
+        header.set_cksum();
+        builder.append_data(&mut header, "MANIFEST", manifest.as_slice())?;
+        Ok(())
+    }
 
-        // Write directory structure
-        self.write_directory_structure(&mut file)?;
+    /// Append each of `paths` that isn't filtered out to `builder` as a tar
+    /// entry named by its path relative to `target_folder`, streaming each
+    /// file's content straight from disk (`tar::Builder::append_file` reads
+    /// it as it writes rather than loading it into memory first). Folds the
+    /// resulting `FilterStatistics` into `self.filter_stats` once done, the
+    /// same contract `render_and_write_entries` upholds for the text format.
+    fn write_tar_entries<W: Write>(
+        &mut self,
+        builder: &mut tar::Builder<W>,
+        paths: Vec<PathBuf>,
+    ) -> Result<()> {
+        let ctx = FilterContext::from_archiver(self);
+        let mut stats = FilterStatistics::default();
+
+        for path in paths {
+            stats.total_files_found += 1;
+
+            if let Some(reason) = ctx.classify_exclusion(&path) {
+                stats.files_excluded += 1;
+                match reason {
+                    ExclusionReason::Extension => stats.excluded_by_extension += 1,
+                    ExclusionReason::Llm => {
+                        stats.excluded_by_llm_optimization += 1;
+                        if let Some(category) = find_llm_category(&ctx.llm_categories, &path, false) {
+                            *stats.llm_category_counts.entry(category.to_string()).or_insert(0) += 1;
+                        }
+                    }
+                    ExclusionReason::IgnorePattern => stats.excluded_by_ignore_pattern += 1,
+                    ExclusionReason::FilterFile => stats.excluded_by_filter_file += 1,
+                }
+                continue;
+            }
 
-        // Write file contents
-        self.write_file_contents(&mut file)?;
+            let relative_path = path.strip_prefix(&self.target_folder).unwrap_or(&path);
+            let mut source = match File::open(&path) {
+                Ok(f) => f,
+                Err(_) if self.keep_going => {
+                    warn!(path = %path.display(), "Skipping unreadable entry");
+                    stats.files_skipped += 1;
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
 
-        // Display filtering statistics
-        self.display_filter_stats();
+            stats.files_included += 1;
+            stats.total_size_included += source.metadata()?.len() as usize;
+            builder.append_file(relative_path, &mut source)?;
+        }
 
-        println!("Archive created: {:?}", output_file);
+        self.filter_stats.merge(&stats);
         Ok(())
     }
 
     /// Write archive header (Pattern 9.5 - Display implementation)
-    fn write_header(&self, file: &mut File) -> Result<()> {
-        let repo_status = if self.is_git_repo {
-            "Git repository detected. Will respect .gitignore rules."
+    fn write_header<W: Write>(&self, file: &mut W) -> Result<()> {
+        let repo_status_id = if self.is_git_repo {
+            "header.git_repo"
         } else {
-            "Not a git repository or git not available. Will process all files."
+            "header.not_git_repo"
         };
 
-        writeln!(file, "{}", repo_status)?;
+        writeln!(file, "{}", self.catalog.get(repo_status_id, &[]))?;
         writeln!(file)?;
         Ok(())
     }
 
     /// Write directory structure using tree-like output (Pattern 15.1 - Custom iterators)
-    fn write_directory_structure(&self, file: &mut File) -> Result<()> {
+    fn write_directory_structure<W: Write>(&self, file: &mut W) -> Result<()> {
         writeln!(file, "Directory structure:")?;
 
         if self.is_git_repo {
@@ -1734,7 +3492,7 @@ impl CodeArchiver {
     }
 
     /// Write git-aware directory structure (Pattern 31.1 - Option combinators)
-    fn write_git_tree_structure(&self, file: &mut File) -> Result<()> {
+    fn write_git_tree_structure<W: Write>(&self, file: &mut W) -> Result<()> {
         let repo = self.git_repo.as_ref().unwrap();
         let workdir = repo.workdir().unwrap_or(&self.target_folder);
 
@@ -1747,7 +3505,13 @@ impl CodeArchiver {
         let mut files = self.get_git_tracked_files(rel_path)?;
         files.sort();
 
+        // Matching `git archive`'s behavior, a path carrying the
+        // `export-ignore` attribute never appears in the listing.
+        let git_attributes = self.build_git_attributes();
         for file_path in files {
+            if git_attributes.is_export_ignored(&workdir.join(&file_path)) {
+                continue;
+            }
             let tree_line = self.format_tree_line(&file_path);
             writeln!(file, "{}", tree_line)?;
         }
@@ -1788,7 +3552,7 @@ impl CodeArchiver {
     }
 
     /// Write regular directory structure using walkdir (Pattern 15.9 - Collection views)
-    fn write_regular_tree_structure(&self, file: &mut File) -> Result<()> {
+    fn write_regular_tree_structure<W: Write>(&self, file: &mut W) -> Result<()> {
         // Try to use system tree command first, fallback to custom implementation
         if let Ok(output) = Command::new("tree").arg(&self.target_folder).output() {
             if output.status.success() {
@@ -1797,8 +3561,11 @@ impl CodeArchiver {
             }
         }
 
-        // Fallback: custom tree implementation
-        for entry in WalkDir::new(&self.target_folder) {
+        // Fallback: custom tree implementation, walked in a fixed
+        // lexicographic order regardless of the filesystem's own
+        // enumeration order, so this listing stays aligned with the
+        // equally-sorted walk in `collect_file_paths`.
+        for entry in WalkDir::new(&self.target_folder).sort_by_file_name() {
             let entry = entry?;
             let depth = entry.depth();
             let name = entry.file_name().to_string_lossy();
@@ -1823,24 +3590,29 @@ impl CodeArchiver {
     }
 
     /// Write file contents (Pattern 2.3 - Question mark operator chaining)
-    fn write_file_contents(&mut self, file: &mut File) -> Result<()> {
-        writeln!(file, "Processing files...")?;
+    fn write_file_contents<W: Write>(&mut self, file: &mut W) -> Result<()> {
+        writeln!(file, "{}", self.catalog.get("banner.processing", &[]))?;
 
         if self.llm_optimize {
-            writeln!(
-                file,
-                "ü§ñ LLM optimization enabled - excluding build artifacts and dependencies"
-            )?;
+            writeln!(file, "{}", self.catalog.get("banner.llm_optimize", &[]))?;
         }
         if !self.ignore_patterns.is_empty() {
             writeln!(
                 file,
-                "üìù Custom ignore patterns: {:?}",
-                self.ignore_patterns
+                "{}",
+                self.catalog.get(
+                    "banner.ignore_patterns",
+                    &[&format!("{:?}", self.ignore_patterns)]
+                )
             )?;
         }
         if let Some(ref extensions) = self.include_extensions {
-            writeln!(file, "üéØ Including only extensions: {:?}", extensions)?;
+            writeln!(
+                file,
+                "{}",
+                self.catalog
+                    .get("banner.include_extensions", &[&format!("{:?}", extensions)])
+            )?;
         }
         writeln!(file)?;
 
@@ -1852,7 +3624,7 @@ impl CodeArchiver {
     }
 
     /// Write git-tracked file contents (Pattern 31.2 - Collection operations)
-    fn write_git_file_contents(&mut self, file: &mut File) -> Result<()> {
+    fn write_git_file_contents<W: Write>(&mut self, file: &mut W) -> Result<()> {
         // Collect file paths first to avoid borrow conflicts
         let file_paths = {
             let repo = self.git_repo.as_ref().unwrap();
@@ -1862,7 +3634,11 @@ impl CodeArchiver {
                 .strip_prefix(workdir)
                 .unwrap_or(Path::new("."));
 
-            let files = self.get_git_tracked_files(rel_path)?;
+            let mut files = self.get_git_tracked_files(rel_path)?;
+            // Sort to match `write_git_tree_structure`'s order, so a
+            // file's body lines up with the same position its header
+            // appears at in the directory listing above.
+            files.sort();
 
             files
                 .into_iter()
@@ -1871,113 +3647,269 @@ impl CodeArchiver {
                 .collect::<Vec<_>>()
         };
 
-        // Now write the files without holding any immutable borrows
-        for full_path in file_paths {
-            self.write_single_file_content(file, &full_path)?;
-        }
+        self.render_and_write_entries(file, file_paths)
+    }
 
-        Ok(())
+    /// Write all file contents, pruning ignored directories as they're
+    /// reached instead of walking the whole tree and filtering every file
+    /// afterward (Pattern 15.1 - Custom iterators).
+    fn write_all_file_contents<W: Write>(&mut self, file: &mut W) -> Result<()> {
+        let paths = self.collect_file_paths()?;
+        self.render_and_write_entries(file, paths)
     }
 
-    /// Write all file contents (Pattern 15.1 - Custom iterators)
-    fn write_all_file_contents(&mut self, file: &mut File) -> Result<()> {
-        for entry in WalkDir::new(&self.target_folder) {
-            let entry = entry?;
-            if entry.file_type().is_file() {
-                self.write_single_file_content(file, entry.path())?;
+    /// Walk `target_folder` with `ignore::WalkBuilder`, appending every
+    /// visited file's path to the returned list and skipping (without ever
+    /// opening) any subdirectory the LLM-preset/`--ignore-pattern` matchers
+    /// would exclude. Unlike the ad-hoc substring matching this replaced,
+    /// `ignore_verdict` gives real gitignore semantics -- leading `/`
+    /// anchors to `target_folder`, a trailing `/` matches directories only,
+    /// `**` spans path segments, `!pattern` re-includes, last-match-wins --
+    /// and `WalkBuilder` itself natively honors any real `.gitignore`/
+    /// `.ignore` file nested deeper in the tree, stacking on top of
+    /// ancestor rules and evaluated deepest-directory-first the way
+    /// ripgrep's own walk does. Entries are sorted by file name, the same
+    /// fixed ordering `write_regular_tree_structure`'s fallback walk uses,
+    /// so output stays deterministic. Reading and filtering file contents
+    /// is deferred to `render_and_write_entries`, which can run that
+    /// (I/O-bound) work in parallel once the full, ordered path list is
+    /// known. Under `--keep-going`, an entry the walker can't read (e.g.
+    /// permission denied) is recorded as a skipped entry instead of
+    /// aborting the whole walk.
+    ///
+    /// A pruned directory is never descended into, so its contents are
+    /// never enumerated -- but `filter_stats.files_excluded` still counts
+    /// it as one exclusion, credited through `pruned_stats` (shared with
+    /// the `filter_entry` closure, which the `ignore` crate may invoke from
+    /// its own worker threads) and folded into `self.filter_stats` once the
+    /// walk finishes.
+    fn collect_file_paths(&mut self) -> Result<Vec<PathBuf>> {
+        let llm_ignore = self.build_llm_ignore_matcher();
+        let llm_categories = self.build_llm_category_matchers();
+        let user_ignore = self.build_user_ignore_matcher();
+        let filter_files = self.build_filter_file_matcher();
+        let pruned_stats = Arc::new(Mutex::new(FilterStatistics::default()));
+
+        let mut builder = WalkBuilder::new(&self.target_folder);
+        builder
+            .git_ignore(true)
+            .git_exclude(true)
+            .ignore(true)
+            .parents(true)
+            .sort_by_file_name(|a, b| a.cmp(b));
+        // Only directories are pruned here; an individually-excluded file
+        // is left for `render_and_write_entries` to classify (via the same
+        // `ignore_verdict`) so its exclusion still gets credited to
+        // `FilterStatistics`.
+        let filter_stats_handle = Arc::clone(&pruned_stats);
+        builder.filter_entry(move |entry| {
+            if entry.depth() == 0 || !entry.file_type().map_or(false, |ft| ft.is_dir()) {
+                return true;
+            }
+            match ignore_verdict(&llm_ignore, &user_ignore, &filter_files, entry.path(), true) {
+                Some(reason) => {
+                    let mut stats = filter_stats_handle.lock().unwrap();
+                    stats.files_excluded += 1;
+                    match reason {
+                        ExclusionReason::Llm => {
+                            stats.excluded_by_llm_optimization += 1;
+                            if let Some(category) = find_llm_category(&llm_categories, entry.path(), true) {
+                                *stats.llm_category_counts.entry(category.to_string()).or_insert(0) += 1;
+                            }
+                        }
+                        ExclusionReason::IgnorePattern => stats.excluded_by_ignore_pattern += 1,
+                        ExclusionReason::FilterFile => stats.excluded_by_filter_file += 1,
+                        ExclusionReason::Extension => {}
+                    }
+                    false
+                }
+                None => true,
+            }
+        });
+
+        let mut paths = Vec::new();
+        for entry in builder.build() {
+            match entry {
+                Ok(entry) => {
+                    if entry.file_type().map_or(false, |ft| ft.is_file()) {
+                        paths.push(entry.into_path());
+                    }
+                }
+                Err(e) if self.keep_going => {
+                    warn!(error = %e, "Skipping unreadable entry");
+                    self.filter_stats.files_skipped += 1;
+                }
+                Err(e) => return Err(e.into()),
             }
         }
-        Ok(())
+        self.filter_stats.merge(&pruned_stats.lock().unwrap());
+        Ok(paths)
     }
 
-    /// Write content of a single file (Pattern 31.3 - Early returns and guards)
-    fn write_single_file_content(
+    /// Render every path in `paths` -- classifying it, and for an included
+    /// file reading and formatting its body -- across a rayon thread pool
+    /// sized by `self.jobs` (default: available parallelism), then write
+    /// the results to `file` sequentially in `paths`' original order, so
+    /// output stays byte-for-byte deterministic no matter which thread
+    /// finishes first (pairing with `collect_file_paths`'/
+    /// `write_git_file_contents`'s fixed ordering). Each entry's
+    /// `FilterStatistics` contribution is folded into `self.filter_stats`
+    /// here on the main thread, since `FilterContext::render_entry` itself
+    /// never mutates it. Filtering runs against a `FilterContext` snapshot
+    /// rather than `self` directly, since `self` holds a `git2::Repository`
+    /// and isn't `Sync`.
+    fn render_and_write_entries<W: Write>(
         &mut self,
-        output_file: &mut File,
-        file_path: &Path,
+        file: &mut W,
+        paths: Vec<PathBuf>,
     ) -> Result<()> {
-        // Check if file should be included based on filtering rules
-        if !self.should_include_file(file_path) {
-            self.filter_stats.files_excluded += 1;
-            return Ok(()); // Skip this file
-        }
-
-        writeln!(output_file, "Absolute path: {}", file_path.display())?;
-
-        // Check if file is text or binary (Pattern 31.4 - Default values)
-        let mime_type = from_path(file_path).first_or_octet_stream();
-        let is_text = mime_type.type_() == mime::TEXT
-            || mime_type == mime::APPLICATION_JSON
-            || self.is_likely_text_file(file_path);
+        let ctx = FilterContext::from_archiver(self);
+        let render_all = || {
+            paths
+                .par_iter()
+                .map(|path| ctx.render_entry(path))
+                .collect::<Vec<_>>()
+        };
 
-        if is_text {
-            writeln!(output_file, "<text starts>")?;
+        let rendered = match self.jobs {
+            Some(jobs) => rayon::ThreadPoolBuilder::new()
+                .num_threads(jobs)
+                .build()
+                .map_err(|e| anyhow::anyhow!("Failed to build thread pool with {jobs} jobs: {e}"))?
+                .install(render_all),
+            None => render_all(),
+        };
 
-            // Read and write file content (Pattern 4.1 - RAII pattern)
-            match fs::read_to_string(file_path) {
-                Ok(content) => {
-                    self.filter_stats.total_size_included += content.len();
-                    output_file.write_all(content.as_bytes())?;
-                }
-                Err(_) => {
-                    writeln!(output_file, "Error reading file content")?;
-                }
+        for entry in rendered {
+            self.filter_stats.merge(&entry.stats);
+            if let Some(text) = entry.text {
+                file.write_all(text.as_bytes())?;
             }
-
-            writeln!(output_file, "<text ends>")?;
-        } else {
-            writeln!(output_file, "Binary file, content not included.")?;
         }
 
-        writeln!(output_file)?;
         Ok(())
     }
+}
+/// Snapshot of the parameters that determine how a checkpointed compression
+/// run cuts and hashes chunks, stored verbatim as a checkpoint's
+/// `compression_config` and fingerprinted so `resume_compression` can tell
+/// whether it's safe to pick a run back up.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct CheckpointConfigSnapshot {
+    target_folder: String,
+    output_dir: Option<String>,
+    min_pattern_length: usize,
+    min_frequency_threshold: usize,
+    enable_zstd: bool,
+    chunk_size_kb: usize,
+    /// Codec chunks were stored under; defaults to "none" so checkpoints
+    /// written before this field existed still deserialize.
+    #[serde(default = "default_chunk_compression_type")]
+    chunk_compression_type: String,
+}
 
-    /// Check if file is likely text based on extension (Pattern 31.8 - Pattern matching)
-    fn is_likely_text_file(&self, path: &Path) -> bool {
-        let text_extensions = [
-            "rs",
-            "toml",
-            "md",
-            "txt",
-            "json",
-            "yaml",
-            "yml",
-            "js",
-            "ts",
-            "tsx",
-            "jsx",
-            "html",
-            "css",
-            "scss",
-            "py",
-            "rb",
-            "go",
-            "java",
-            "c",
-            "cpp",
-            "h",
-            "hpp",
-            "sh",
-            "bash",
-            "zsh",
-            "fish",
-            "ps1",
-            "bat",
-            "cmd",
-            "xml",
-            "svg",
-            "gitignore",
-            "dockerfile",
-            "makefile",
-        ];
+fn default_chunk_compression_type() -> String {
+    "none".to_string()
+}
 
-        path.extension()
-            .and_then(|ext| ext.to_str())
-            .map(|ext| text_extensions.contains(&ext.to_lowercase().as_str()))
-            .unwrap_or(false)
+/// SHA-256 fingerprint of a checkpoint's serialized configuration, matching
+/// [`compression::dedup`]'s convention of hashing with `Sha256::digest` and
+/// hex-formatting the result.
+fn config_fingerprint(config_json: &str) -> String {
+    format!("{:x}", Sha256::digest(config_json.as_bytes()))
+}
+
+/// Derive content-defined chunking bounds from the CLI's `chunk_size_kb`,
+/// rounding it up to the nearest power of two since
+/// [`compression::ContentDefinedChunking`] requires `avg` to be one. `min`
+/// and `max` bracket it at a quarter and four times `avg`, the same ratios
+/// [`compression::fastcdc`]'s own test fixture uses.
+fn content_defined_chunking_for(chunk_size_kb: usize) -> compression::ContentDefinedChunking {
+    let avg = (chunk_size_kb * 1024).next_power_of_two().max(2);
+    compression::ContentDefinedChunking::new(avg / 4, avg, avg * 4)
+        .expect("derived chunking bounds are always min <= avg <= max with avg a power of two")
+}
+
+/// Walk `target_folder`'s files in a stable order, content-define-chunk and
+/// hash each one not already covered by `skip_files`, and record a
+/// checkpoint after every file so a killed or interrupted run can resume
+/// from the last one recorded. Returns the accumulated chunk hashes and the
+/// id of the final checkpoint saved.
+fn run_checkpointed_chunking(
+    database: &mut compression::CompressionDatabase,
+    target_folder: &Path,
+    params: compression::ContentDefinedChunking,
+    config_json: &str,
+    config_fp: &str,
+    skip_files: usize,
+    mut chunk_hashes: Vec<String>,
+) -> Result<(Vec<String>, i64)> {
+    let mut files: Vec<PathBuf> = WalkDir::new(target_folder)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.path().to_path_buf())
+        .collect();
+    files.sort();
+
+    let total_files = files.len();
+    let mut checkpoint_id = database
+        .save_checkpoint(&compression::CompressionCheckpoint {
+            id: None,
+            target_folder: target_folder.display().to_string(),
+            created_at: SystemTime::now(),
+            total_files,
+            processed_files: skip_files,
+            patterns_found: 0,
+            compression_config: config_json.to_string(),
+            status: compression::CheckpointStatus::InProgress,
+            chunk_hashes: chunk_hashes.clone(),
+            config_fingerprint: config_fp.to_string(),
+        })
+        .context("Failed to save initial checkpoint")?;
+
+    for (index, file_path) in files.iter().enumerate().skip(skip_files) {
+        let content = fs::read(file_path)
+            .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
+
+        for (offset, len) in compression::fastcdc_chunk_boundaries(&content, params) {
+            let hash = format!("{:x}", Sha256::digest(&content[offset..offset + len]));
+            database
+                .store_chunk(&hash, &content[offset..offset + len])
+                .with_context(|| {
+                    format!("Failed to store chunk for file: {}", file_path.display())
+                })?;
+            chunk_hashes.push(hash);
+        }
+
+        checkpoint_id = database
+            .save_checkpoint(&compression::CompressionCheckpoint {
+                id: None,
+                target_folder: target_folder.display().to_string(),
+                created_at: SystemTime::now(),
+                total_files,
+                processed_files: index + 1,
+                patterns_found: 0,
+                compression_config: config_json.to_string(),
+                status: compression::CheckpointStatus::InProgress,
+                chunk_hashes: chunk_hashes.clone(),
+                config_fingerprint: config_fp.to_string(),
+            })
+            .with_context(|| {
+                format!(
+                    "Failed to save checkpoint after file: {}",
+                    file_path.display()
+                )
+            })?;
+        database
+            .save()
+            .context("Failed to persist database to disk")?;
     }
+
+    Ok((chunk_hashes, checkpoint_id))
 }
+
 /// Enhanced universal compression with full configuration support
 #[instrument(
     name = "universal_compress_enhanced",
@@ -1990,7 +3922,9 @@ impl CodeArchiver {
         max_threads = ?max_threads,
         chunk_size_kb = chunk_size_kb,
         channel_buffer_size = channel_buffer_size,
-        memory_map_threshold_mb = memory_map_threshold_mb
+        memory_map_threshold_mb = ?memory_map_threshold_mb,
+        content_defined_chunking = content_defined_chunking,
+        chunk_compression_type = %chunk_compression_type
     )
 )]
 fn universal_compress_enhanced(
@@ -2003,9 +3937,14 @@ fn universal_compress_enhanced(
     max_threads: Option<usize>,
     chunk_size_kb: usize,
     channel_buffer_size: usize,
-    memory_map_threshold_mb: usize,
+    memory_map_threshold_mb: Option<usize>,
+    content_defined_chunking: bool,
+    chunk_compression_type: String,
 ) -> Result<()> {
-    use compression::{config::ParallelConfig, CompressionConfig};
+    use compression::{
+        config::{ParallelConfig, ResourceBudget},
+        CompressionConfig,
+    };
     use std::time::Instant;
 
     let _start_time = Instant::now();
@@ -2020,10 +3959,32 @@ fn universal_compress_enhanced(
         max_threads = ?max_threads,
         chunk_size_kb = chunk_size_kb,
         channel_buffer_size = channel_buffer_size,
-        memory_map_threshold_mb = memory_map_threshold_mb,
+        memory_map_threshold_mb = ?memory_map_threshold_mb,
         "Starting enhanced universal compression pipeline"
     );
 
+    // Resolve an unset memory map threshold from this machine's available
+    // memory instead of a fixed constant, so a 4GB laptop and a 512GB
+    // server each get a threshold proportional to their own resources; an
+    // explicit value is left untouched as a hard override, but warned
+    // about (not rejected) if it exceeds what's actually available.
+    let resource_budget = ResourceBudget::detect();
+    let memory_map_threshold_mb = match memory_map_threshold_mb {
+        Some(mb) => {
+            if let Some(budget_bytes) = resource_budget.memory_budget_bytes {
+                let budget_mb = budget_bytes / (1024 * 1024);
+                if mb as u64 > budget_mb {
+                    warn!(
+                        "--memory-map-threshold-mb {} exceeds ~{}MB of available memory on this machine; large files may cause heavy paging",
+                        mb, budget_mb
+                    );
+                }
+            }
+            mb
+        }
+        None => resource_budget.memory_map_threshold_mb().unwrap_or(1),
+    };
+
     // Validate configuration parameters
     info!("Validating configuration parameters");
     validate_compression_config(
@@ -2033,6 +3994,7 @@ fn universal_compress_enhanced(
         chunk_size_kb,
         channel_buffer_size,
         memory_map_threshold_mb,
+        &chunk_compression_type,
     )?;
 
     // Build parallel configuration
@@ -2046,6 +4008,14 @@ fn universal_compress_enhanced(
         parallel_builder = parallel_builder.max_threads(threads);
     }
 
+    if content_defined_chunking {
+        let cdc = content_defined_chunking_for(chunk_size_kb);
+        parallel_builder = parallel_builder
+            .content_defined_chunking(cdc.min(), cdc.avg(), cdc.max())
+            .context("Failed to configure content-defined chunking")?;
+        info!("Content-defined (FastCDC) chunking enabled");
+    }
+
     let parallel_config = parallel_builder
         .build()
         .context("Failed to build parallel configuration")?;
@@ -2064,17 +4034,109 @@ fn universal_compress_enhanced(
 
     debug!(config = ?config, "Configuration built successfully");
 
-    // TODO: Implement database-aware compression pipeline
-    // For now, fall back to the original implementation
-    warn!("Database-aware compression pipeline not yet implemented, falling back to original implementation");
+    // Record a checkpoint for every file chunked, so a killed run can be
+    // picked back up with `resume_compression` instead of starting over.
+    info!("Opening checkpoint database");
+    let mut database = compression::CompressionDatabase::new(&database_path)
+        .context("Failed to open checkpoint database")?;
+    database.set_chunk_codec(
+        chunk_compression_type
+            .parse()
+            .context("Failed to parse chunk compression type")?,
+    );
+
+    let config_snapshot = CheckpointConfigSnapshot {
+        target_folder: target_folder.display().to_string(),
+        output_dir: output_dir.as_ref().map(|p| p.display().to_string()),
+        min_pattern_length,
+        min_frequency_threshold,
+        enable_zstd,
+        chunk_size_kb,
+        chunk_compression_type: chunk_compression_type.clone(),
+    };
+    let config_json = serde_json::to_string(&config_snapshot)
+        .context("Failed to serialize checkpoint configuration")?;
+    let config_fp = config_fingerprint(&config_json);
+    let chunking_params = content_defined_chunking_for(chunk_size_kb);
+
+    info!("Content-defining and checkpointing chunks before compression");
+    let (_, checkpoint_id) = run_checkpointed_chunking(
+        &mut database,
+        &target_folder,
+        chunking_params,
+        &config_json,
+        &config_fp,
+        0,
+        Vec::new(),
+    )
+    .context("Failed to checkpoint chunking pass")?;
 
-    universal_compress(
+    let compress_result = universal_compress(
         target_folder,
         output_dir,
         min_pattern_length,
         min_frequency_threshold,
         enable_zstd,
+    );
+
+    let final_status = if compress_result.is_ok() {
+        compression::CheckpointStatus::Completed
+    } else {
+        compression::CheckpointStatus::Failed
+    };
+    database
+        .update_checkpoint_status(checkpoint_id, final_status)
+        .context("Failed to update checkpoint status")?;
+    database
+        .save()
+        .context("Failed to persist database to disk")?;
+
+    compress_result
+}
+
+/// Restore the tree a `universal-compress` archive describes, reversing
+/// `UniversalCompressor::compress`'s pattern-replacement and final-stage
+/// codec passes, then report whether each restored file's content matched
+/// the checksum captured at compress time.
+#[instrument(
+    name = "universal_restore",
+    fields(
+        archive_file = %archive_file.display(),
+        output_dir = %output_dir.display()
     )
+)]
+fn universal_restore(archive_file: PathBuf, output_dir: PathBuf) -> Result<()> {
+    use compression::UniversalCompressor;
+
+    let report = UniversalCompressor::restore(&archive_file, &output_dir)
+        .context("Failed to restore archive")?;
+
+    let mismatches: Vec<_> = report.mismatches().collect();
+    info!(
+        files_restored = report.files_restored,
+        mismatches = mismatches.len(),
+        "Archive restoration completed"
+    );
+    println!(
+        "Restored {} files to {}",
+        report.files_restored,
+        output_dir.display()
+    );
+
+    if mismatches.is_empty() {
+        println!("All restored files matched their recorded checksum.");
+    } else {
+        for path in &mismatches {
+            warn!(path = %path.display(), "Restored content does not match the checksum captured at compress time");
+        }
+        bail!(
+            "{} of {} restored files did not match their recorded checksum",
+            mismatches.len(),
+            report.files_restored
+        );
+    }
+
+    Ok(())
 }
 
 /// Resume compression from a checkpoint
@@ -2106,17 +4168,87 @@ fn resume_compression(
         ));
     }
 
-    // TODO: Implement checkpoint resume functionality
-    // This would involve:
-    // 1. Loading checkpoint state from database
-    // 2. Validating checkpoint integrity
-    // 3. Resuming from the saved state
-    // 4. Continuing with the compression pipeline
+    let mut database = compression::CompressionDatabase::new(&database_path)
+        .context("Failed to open checkpoint database")?;
+
+    let checkpoint = database.latest_checkpoint().ok_or_else(|| {
+        anyhow::anyhow!(
+            "No checkpoints found in database: {}",
+            database_path.display()
+        )
+    })?;
+
+    let recomputed_fp = config_fingerprint(&checkpoint.compression_config);
+    if recomputed_fp != checkpoint.config_fingerprint {
+        bail!(
+            "Checkpoint configuration fingerprint mismatch (expected {}, got {}); the checkpoint may be corrupt",
+            checkpoint.config_fingerprint,
+            recomputed_fp
+        );
+    }
+    if !database.checkpoint_chunks_intact(&checkpoint) {
+        bail!(
+            "Checkpoint references chunks that are missing from the database, cannot resume safely"
+        );
+    }
+
+    let config_snapshot: CheckpointConfigSnapshot =
+        serde_json::from_str(&checkpoint.compression_config)
+            .context("Failed to parse checkpoint configuration")?;
+    database.set_chunk_codec(
+        config_snapshot
+            .chunk_compression_type
+            .parse()
+            .context("Failed to parse checkpointed chunk compression type")?,
+    );
+
+    if checkpoint.processed_files >= checkpoint.total_files {
+        info!(checkpoint_id = ?checkpoint.id, "Checkpoint already covers every file, nothing to resume");
+    } else {
+        info!(
+            checkpoint_id = ?checkpoint.id,
+            processed_files = checkpoint.processed_files,
+            total_files = checkpoint.total_files,
+            "Resuming chunking from checkpoint"
+        );
+        run_checkpointed_chunking(
+            &mut database,
+            Path::new(&checkpoint.target_folder),
+            content_defined_chunking_for(config_snapshot.chunk_size_kb),
+            &checkpoint.compression_config,
+            &checkpoint.config_fingerprint,
+            checkpoint.processed_files,
+            checkpoint.chunk_hashes.clone(),
+        )
+        .context("Failed to resume checkpointed chunking")?;
+    }
+
+    let resolved_output_dir =
+        output_dir.or_else(|| config_snapshot.output_dir.clone().map(PathBuf::from));
+
+    let compress_result = universal_compress(
+        PathBuf::from(&checkpoint.target_folder),
+        resolved_output_dir,
+        config_snapshot.min_pattern_length,
+        config_snapshot.min_frequency_threshold,
+        config_snapshot.enable_zstd,
+    );
+
+    if let Some(id) = checkpoint.id {
+        let final_status = if compress_result.is_ok() {
+            compression::CheckpointStatus::Completed
+        } else {
+            compression::CheckpointStatus::Failed
+        };
+        database
+            .update_checkpoint_status(id, final_status)
+            .context("Failed to update checkpoint status")?;
+        database
+            .save()
+            .context("Failed to persist database to disk")?;
+    }
 
-    error!("Resume functionality not yet implemented");
-    Err(anyhow::anyhow!(
-        "Resume functionality is not yet implemented. Please use the regular compression command."
-    ))
+    compress_result
 }
 
 /// Handle checkpoint management commands
@@ -2152,13 +4284,17 @@ fn handle_checkpoint_command(action: CheckpointAction) -> Result<()> {
         CheckpointAction::Clean {
             database_path,
             keep_count,
+            max_total_bytes_mb,
+            max_age_days,
         } => {
             info!(
                 database_path = %database_path.display(),
                 keep_count = keep_count,
+                max_total_bytes_mb = max_total_bytes_mb,
+                max_age_days = max_age_days,
                 "Cleaning old checkpoints"
             );
-            clean_checkpoints(database_path, keep_count)
+            clean_checkpoints(database_path, keep_count, max_total_bytes_mb, max_age_days)
         }
     }
 }
@@ -2171,14 +4307,29 @@ fn list_checkpoints(database_path: PathBuf) -> Result<()> {
         return Ok(());
     }
 
-    // TODO: Implement database checkpoint listing
-    // This would involve:
-    // 1. Opening the database connection
-    // 2. Querying the checkpoints table
-    // 3. Formatting and displaying the results
+    let database = compression::CompressionDatabase::new(&database_path)
+        .context("Failed to open checkpoint database")?;
+    let checkpoints = database
+        .list_checkpoints()
+        .context("Failed to list checkpoints")?;
+
+    if checkpoints.is_empty() {
+        println!("No checkpoints found in {}", database_path.display());
+        return Ok(());
+    }
 
-    println!("Checkpoint listing not yet implemented.");
-    println!("Database path: {}", database_path.display());
+    println!("Checkpoints in {}:", database_path.display());
+    for checkpoint in checkpoints {
+        println!(
+            "  [{}] {} - {}/{} files, {} chunks ({})",
+            checkpoint.id.unwrap_or_default(),
+            checkpoint.target_folder,
+            checkpoint.processed_files,
+            checkpoint.total_files,
+            checkpoint.chunk_hashes.len(),
+            checkpoint.status
+        );
+    }
 
     Ok(())
 }
@@ -2192,18 +4343,35 @@ fn show_checkpoint(database_path: PathBuf, checkpoint_id: Option<i64>) -> Result
         ));
     }
 
-    // TODO: Implement checkpoint detail display
-    // This would involve:
-    // 1. Opening the database connection
-    // 2. Querying for the specific checkpoint (or latest if none specified)
-    // 3. Displaying detailed information about the checkpoint state
-
-    match checkpoint_id {
-        Some(id) => println!("Showing checkpoint {} from {}", id, database_path.display()),
-        None => println!("Showing latest checkpoint from {}", database_path.display()),
-    }
+    let database = compression::CompressionDatabase::new(&database_path)
+        .context("Failed to open checkpoint database")?;
+
+    let checkpoint = match checkpoint_id {
+        Some(id) => database
+            .load_checkpoint(id)
+            .context("Failed to load checkpoint")?
+            .ok_or_else(|| anyhow::anyhow!("Checkpoint {} not found", id))?,
+        None => database.latest_checkpoint().ok_or_else(|| {
+            anyhow::anyhow!(
+                "No checkpoints found in database: {}",
+                database_path.display()
+            )
+        })?,
+    };
 
-    println!("Checkpoint details not yet implemented.");
+    println!("Checkpoint {}", checkpoint.id.unwrap_or_default());
+    println!("  Target folder: {}", checkpoint.target_folder);
+    println!("  Status: {}", checkpoint.status);
+    println!(
+        "  Progress: {}/{} files",
+        checkpoint.processed_files, checkpoint.total_files
+    );
+    println!("  Chunks recorded: {}", checkpoint.chunk_hashes.len());
+    println!(
+        "  Chunks intact: {}",
+        database.checkpoint_chunks_intact(&checkpoint)
+    );
+    println!("  Config fingerprint: {}", checkpoint.config_fingerprint);
 
     Ok(())
 }
@@ -2217,44 +4385,57 @@ fn delete_checkpoint(database_path: PathBuf, checkpoint_id: i64) -> Result<()> {
         ));
     }
 
-    // TODO: Implement checkpoint deletion
-    // This would involve:
-    // 1. Opening the database connection
-    // 2. Verifying the checkpoint exists
-    // 3. Deleting the checkpoint record
-    // 4. Cleaning up any associated data
+    let mut database = compression::CompressionDatabase::new(&database_path)
+        .context("Failed to open checkpoint database")?;
+    database
+        .delete_checkpoint(checkpoint_id)
+        .with_context(|| format!("Failed to delete checkpoint {}", checkpoint_id))?;
+    database
+        .save()
+        .context("Failed to persist database to disk")?;
 
     println!(
-        "Deleting checkpoint {} from {}",
+        "Deleted checkpoint {} from {}",
         checkpoint_id,
         database_path.display()
     );
-    println!("Checkpoint deletion not yet implemented.");
 
     Ok(())
 }
 
-/// Clean old checkpoints, keeping only the latest N
-fn clean_checkpoints(database_path: PathBuf, keep_count: usize) -> Result<()> {
+/// Clean old checkpoints under one retention policy. `max_age_days` wins if
+/// given, then `max_total_bytes_mb`, then `keep_count` (default 5) -- see
+/// [`compression::RetentionPolicy`] for why only one policy applies per run.
+fn clean_checkpoints(
+    database_path: PathBuf,
+    keep_count: Option<usize>,
+    max_total_bytes_mb: Option<u64>,
+    max_age_days: Option<u64>,
+) -> Result<()> {
     if !database_path.exists() {
         println!("No database found at: {}", database_path.display());
         println!("No checkpoints to clean.");
         return Ok(());
     }
 
-    // TODO: Implement checkpoint cleanup
-    // This would involve:
-    // 1. Opening the database connection
-    // 2. Querying all checkpoints ordered by creation time
-    // 3. Identifying checkpoints to delete (keeping only the latest N)
-    // 4. Deleting the old checkpoints
+    let policy = if let Some(days) = max_age_days {
+        compression::RetentionPolicy::MaxAge(Duration::from_secs(days * 24 * 60 * 60))
+    } else if let Some(mb) = max_total_bytes_mb {
+        compression::RetentionPolicy::MaxTotalBytes(mb * 1024 * 1024)
+    } else {
+        compression::RetentionPolicy::KeepCount(keep_count.unwrap_or(5))
+    };
+
+    let mut database = compression::CompressionDatabase::new(&database_path)
+        .context("Failed to open checkpoint database")?;
+    let summary = database
+        .clean_checkpoints_with_policy(policy)
+        .context("Failed to clean checkpoints")?;
+    database
+        .save()
+        .context("Failed to persist database to disk")?;
 
-    println!(
-        "Cleaning checkpoints, keeping {} latest from {}",
-        keep_count,
-        database_path.display()
-    );
-    println!("Checkpoint cleanup not yet implemented.");
+    println!("{} from {}", summary, database_path.display());
 
     Ok(())
 }
@@ -2267,6 +4448,7 @@ fn validate_compression_config(
     chunk_size_kb: usize,
     channel_buffer_size: usize,
     memory_map_threshold_mb: usize,
+    chunk_compression_type: &str,
 ) -> Result<()> {
     // Validate pattern length
     if min_pattern_length < 2 {
@@ -2354,6 +4536,15 @@ fn validate_compression_config(
         ));
     }
 
+    // Validate chunk compression codec
+    if let Err(e) = chunk_compression_type.parse::<compression::Codec>() {
+        return Err(anyhow::anyhow!(
+            "Invalid chunk compression type '{}': {}",
+            chunk_compression_type,
+            e
+        ));
+    }
+
     // Cross-validation
     if let Some(threads) = max_threads {
         if threads > 64 && channel_buffer_size < 50 {
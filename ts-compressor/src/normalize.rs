@@ -0,0 +1,163 @@
+//! Archive output normalization, modeled on trybuild's `normalize` module:
+//! rewrites the machine-specific fragments of an assembled `archive` text
+//! buffer into stable, portable tokens before it's written to disk, so the
+//! same input tree produces byte-identical output on any machine and can be
+//! diffed against a committed golden fixture.
+//!
+//! Only recognized structural lines are rewritten (currently `Absolute
+//! path: ...` headers); the verbatim file content between `<text starts>`/
+//! `<text ends>` markers is never touched, so normalization can't corrupt
+//! the very source text the archive is meant to preserve.
+
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+
+/// Which absolute-path prefixes get rewritten into stable tokens, and what
+/// root the archive was built from.
+#[derive(Debug, Clone)]
+pub struct NormalizeConfig {
+    /// The folder the archive was built from. Absolute paths under it
+    /// become `$ROOT/...`.
+    pub root: PathBuf,
+    /// The invoking user's home directory, if known. Absolute paths under
+    /// it (but not already matched by `root`) become `$HOME/...`.
+    pub home: Option<PathBuf>,
+}
+
+impl NormalizeConfig {
+    /// Build a config for archiving `root`, picking up the home directory
+    /// from the `HOME` environment variable if it's set.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            home: std::env::var_os("HOME").map(PathBuf::from),
+        }
+    }
+
+    /// The path-rewrite rules for `root`/`home`, longest prefix first, so a
+    /// `root` nested under `home` is matched by its own, more specific
+    /// `$ROOT` rule before the shorter `$HOME` prefix could partially
+    /// consume it.
+    fn variations(&self) -> Vec<Variation<'_>> {
+        let mut variations = vec![Variation {
+            prefix: &self.root,
+            token: "$ROOT",
+        }];
+        if let Some(home) = &self.home {
+            variations.push(Variation {
+                prefix: home,
+                token: "$HOME",
+            });
+        }
+        variations.sort_by_key(|variation| Reverse(variation.prefix.as_os_str().len()));
+        variations
+    }
+}
+
+/// One compiled rewrite rule: an absolute-path prefix and the token it
+/// collapses to.
+struct Variation<'a> {
+    prefix: &'a Path,
+    token: &'static str,
+}
+
+impl Variation<'_> {
+    /// Rewrite `path` to `$TOKEN/...` if it starts with this variation's
+    /// prefix, or `None` if the prefix doesn't match.
+    fn apply(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(self.prefix).ok()?;
+        Some(if relative.as_os_str().is_empty() {
+            self.token.to_string()
+        } else {
+            format!("{}/{}", self.token, relative.display())
+        })
+    }
+}
+
+/// Run the normalization pass over an assembled archive's text, rewriting
+/// every `Absolute path: ...` header line's path through `config`'s
+/// variations in order, leaving the line untouched if none match.
+pub fn normalize_archive(text: &str, config: &NormalizeConfig) -> String {
+    const HEADER_PREFIX: &str = "Absolute path: ";
+
+    let variations = config.variations();
+    let mut out = String::with_capacity(text.len());
+
+    for line in text.split_inclusive('\n') {
+        let Some(rest) = line.strip_prefix(HEADER_PREFIX) else {
+            out.push_str(line);
+            continue;
+        };
+
+        let (path_part, newline) = match rest.strip_suffix('\n') {
+            Some(stripped) => (stripped, "\n"),
+            None => (rest, ""),
+        };
+
+        let rewritten = variations
+            .iter()
+            .find_map(|variation| variation.apply(Path::new(path_part)));
+
+        out.push_str(HEADER_PREFIX);
+        out.push_str(rewritten.as_deref().unwrap_or(path_part));
+        out.push_str(newline);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn root_path_is_rewritten_relative_to_root_token() {
+        let config = NormalizeConfig {
+            root: PathBuf::from("/home/alice/project"),
+            home: None,
+        };
+        let input = "Absolute path: /home/alice/project/src/main.rs\n<text starts>\nfn main() {}\n<text ends>\n";
+        let normalized = normalize_archive(input, &config);
+        assert!(normalized.starts_with("Absolute path: $ROOT/src/main.rs\n"));
+        // File content between the markers is untouched.
+        assert!(normalized.contains("fn main() {}\n"));
+    }
+
+    #[test]
+    fn home_prefix_is_rewritten_when_outside_root() {
+        let config = NormalizeConfig {
+            root: PathBuf::from("/home/alice/project"),
+            home: Some(PathBuf::from("/home/alice")),
+        };
+        let input = "Absolute path: /home/alice/.cargo/config.toml\n";
+        assert_eq!(
+            normalize_archive(input, &config),
+            "Absolute path: $HOME/.cargo/config.toml\n"
+        );
+    }
+
+    #[test]
+    fn root_nested_under_home_prefers_the_longer_root_prefix() {
+        let config = NormalizeConfig {
+            root: PathBuf::from("/home/alice/project"),
+            home: Some(PathBuf::from("/home/alice")),
+        };
+        let input = "Absolute path: /home/alice/project/src/lib.rs\n";
+        // Without longest-prefix-first ordering this would produce
+        // "$HOME/project/src/lib.rs" instead.
+        assert_eq!(
+            normalize_archive(input, &config),
+            "Absolute path: $ROOT/src/lib.rs\n"
+        );
+    }
+
+    #[test]
+    fn unrelated_lines_pass_through_unchanged() {
+        let config = NormalizeConfig {
+            root: PathBuf::from("/home/alice/project"),
+            home: None,
+        };
+        let input = "Directory structure:\n├── src\n";
+        assert_eq!(normalize_archive(input, &config), input);
+    }
+}
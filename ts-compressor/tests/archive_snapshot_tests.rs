@@ -0,0 +1,33 @@
+//! Snapshot tests for the `archive` command: each case builds a small
+//! project with `support::TestProject`, runs the compiled binary with
+//! `--normalize`, and diffs the resulting archive text against a committed
+//! `.golden` fixture under `tests/golden/`. Run with
+//! `TS_COMPRESSOR_UPDATE_GOLDEN=1` to regenerate the fixtures after an
+//! intentional, reviewed change to the archive format.
+
+mod support;
+
+use support::TestProject;
+
+#[test]
+fn archive_of_a_small_project_matches_golden_snapshot() {
+    let project = TestProject::new().with_file("hello.txt", "hello world\n");
+    let output_dir = tempfile::TempDir::new().expect("failed to create output temp directory");
+
+    project
+        .run(&[
+            "archive",
+            project.path().to_str().unwrap(),
+            "--output-dir",
+            output_dir.path().to_str().unwrap(),
+            "--normalize",
+        ])
+        .expect_success()
+        .assert_archive_matches(
+            output_dir.path(),
+            concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/golden/small_project.golden"
+            ),
+        );
+}
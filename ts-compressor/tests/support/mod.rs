@@ -0,0 +1,160 @@
+//! Shared test-support harness for exercising the already-compiled
+//! `ts-compressor` binary against on-disk fixtures, in the spirit of
+//! `cli_test_dir`'s `TestDir`: build a scratch project, run a subcommand
+//! through `env!("CARGO_BIN_EXE_ts-compressor")` (no `cargo run`
+//! respawn/recompile per test case), and assert on its exit status and
+//! output. Paired with [`crate::normalize`]'s `--normalize` flag, tests can
+//! diff the whole archive text against a committed golden fixture instead
+//! of the old dozen of brittle `contains` checks.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// A scratch directory tree, rooted at a fixed `project` basename (rather
+/// than the surrounding temp directory's randomized name) so an archive
+/// taken of it embeds a stable root name instead of breaking golden
+/// comparisons every run.
+pub struct TestProject {
+    // Kept alive for the lifetime of the project so the directory isn't
+    // cleaned up out from under a test still using `path()`.
+    _root: tempfile::TempDir,
+    project_dir: PathBuf,
+}
+
+impl TestProject {
+    pub fn new() -> Self {
+        let root = tempfile::TempDir::new().expect("failed to create scratch test directory");
+        let project_dir = root.path().join("project");
+        fs::create_dir_all(&project_dir).expect("failed to create project directory");
+        Self {
+            _root: root,
+            project_dir,
+        }
+    }
+
+    /// The project's root directory, to pass as `archive`'s target folder.
+    pub fn path(&self) -> &Path {
+        &self.project_dir
+    }
+
+    /// Write `contents` to `relative_path` under this project, creating
+    /// any parent directories it needs. Consumes and returns `self` so
+    /// calls can be chained while building up a fixture.
+    pub fn with_file(self, relative_path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Self {
+        let full_path = self.project_dir.join(relative_path);
+        if let Some(parent) = full_path.parent() {
+            fs::create_dir_all(parent).expect("failed to create parent directory for test file");
+        }
+        fs::write(&full_path, contents).expect("failed to write test file");
+        self
+    }
+
+    /// Create an (initially empty) directory under this project.
+    pub fn with_dir(self, relative_path: impl AsRef<Path>) -> Self {
+        fs::create_dir_all(self.project_dir.join(relative_path))
+            .expect("failed to create test directory");
+        self
+    }
+
+    /// Run the compiled `ts-compressor` binary with `args`, capturing its
+    /// output.
+    pub fn run(&self, args: &[&str]) -> TestOutput {
+        let output = Command::new(env!("CARGO_BIN_EXE_ts-compressor"))
+            .args(args)
+            .current_dir(&self.project_dir)
+            .output()
+            .expect("failed to run ts-compressor binary");
+        TestOutput { output }
+    }
+}
+
+/// The captured result of one [`TestProject::run`] invocation.
+pub struct TestOutput {
+    output: Output,
+}
+
+impl TestOutput {
+    pub fn stdout(&self) -> String {
+        String::from_utf8_lossy(&self.output.stdout).into_owned()
+    }
+
+    pub fn stderr(&self) -> String {
+        String::from_utf8_lossy(&self.output.stderr).into_owned()
+    }
+
+    /// Assert the process exited successfully, panicking with both
+    /// captured output streams if it didn't. Returns `self` for chaining.
+    pub fn expect_success(self) -> Self {
+        assert!(
+            self.output.status.success(),
+            "expected success, got {:?}\n--- stdout ---\n{}\n--- stderr ---\n{}",
+            self.output.status,
+            self.stdout(),
+            self.stderr()
+        );
+        self
+    }
+
+    /// Assert the process exited with exactly `code`, panicking with both
+    /// captured output streams if it didn't. Returns `self` for chaining.
+    pub fn expect_failure(self, code: i32) -> Self {
+        assert_eq!(
+            self.output.status.code(),
+            Some(code),
+            "expected exit code {code}, got {:?}\n--- stdout ---\n{}\n--- stderr ---\n{}",
+            self.output.status,
+            self.stdout(),
+            self.stderr()
+        );
+        self
+    }
+
+    /// Assert the single `.txt` archive file produced directly under
+    /// `output_dir` matches the committed fixture at `golden_path`. Set
+    /// `TS_COMPRESSOR_UPDATE_GOLDEN=1` to rewrite the fixture in place
+    /// instead of asserting against it, mirroring trybuild's blessing
+    /// workflow.
+    pub fn assert_archive_matches(self, output_dir: &Path, golden_path: impl AsRef<Path>) -> Self {
+        let archive_path = find_archive_file(output_dir);
+        let actual = fs::read_to_string(&archive_path).expect("failed to read archive output");
+        assert_matches_golden(&actual, golden_path.as_ref());
+        self
+    }
+}
+
+fn find_archive_file(output_dir: &Path) -> PathBuf {
+    fs::read_dir(output_dir)
+        .expect("failed to read archive output directory")
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .find(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .expect("no .txt archive file was produced")
+}
+
+/// Compare `actual` against the fixture at `golden_path`, or -- with
+/// `TS_COMPRESSOR_UPDATE_GOLDEN=1` set -- overwrite the fixture with
+/// `actual` instead of asserting, the same update-in-place workflow
+/// trybuild calls "blessing".
+fn assert_matches_golden(actual: &str, golden_path: &Path) {
+    if std::env::var_os("TS_COMPRESSOR_UPDATE_GOLDEN").is_some() {
+        if let Some(parent) = golden_path.parent() {
+            fs::create_dir_all(parent).expect("failed to create golden fixture directory");
+        }
+        fs::write(golden_path, actual).expect("failed to update golden fixture");
+        return;
+    }
+
+    let expected = fs::read_to_string(golden_path).unwrap_or_else(|_| {
+        panic!(
+            "missing golden fixture at {}; run with TS_COMPRESSOR_UPDATE_GOLDEN=1 to create it",
+            golden_path.display()
+        )
+    });
+    assert_eq!(
+        actual, expected,
+        "archive output doesn't match golden fixture at {}; run with \
+         TS_COMPRESSOR_UPDATE_GOLDEN=1 to update it",
+        golden_path.display()
+    );
+}